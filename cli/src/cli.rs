@@ -61,6 +61,33 @@ pub enum Subcommand {
 	/// capabilities of running a validator.
 	HostPerfCheck,
 
+	/// Check the integrity of the availability store, e.g. after an unclean shutdown.
+	///
+	/// Verifies the stored data and chunks of every candidate against its erasure root and
+	/// reports any corruption. Candidates stored by a node version which didn't record erasure
+	/// roots are skipped. The node must not be running and its database must exist.
+	AvailabilityCheck(AvailabilityCheckCmd),
+
+	/// Export the approval voting data to a file, to move a validator to another host.
+	///
+	/// The node must not be running.
+	ExportApprovalCheckpoint(ApprovalCheckpointCmd),
+
+	/// Import the approval voting data exported by `export-approval-checkpoint`.
+	///
+	/// The node must not be running and must not have any approval voting data yet.
+	ImportApprovalCheckpoint(ApprovalCheckpointCmd),
+
+	/// Export the chain spec with the light sync state of the last finalized block embedded.
+	///
+	/// Light clients started from the exported chain spec sync from that block rather than from
+	/// genesis. The node must not be running.
+	ExportSyncState(ExportSyncStateCmd),
+
+	/// Database maintenance.
+	#[clap(subcommand)]
+	Db(DbCmd),
+
 	/// Try some command against runtime state.
 	#[cfg(feature = "try-runtime")]
 	TryRuntime(try_runtime_cli::TryRuntimeCmd),
@@ -81,6 +108,118 @@ pub struct ValidationWorkerCommand {
 	pub socket_path: String,
 }
 
+#[allow(missing_docs)]
+#[derive(Debug, Parser)]
+pub struct AvailabilityCheckCmd {
+	/// Delete all data of the candidates found to be corrupted, so it can be fetched again.
+	#[clap(long)]
+	pub prune: bool,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: sc_cli::SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub database_params: sc_cli::DatabaseParams,
+}
+
+impl sc_cli::CliConfiguration for AvailabilityCheckCmd {
+	fn shared_params(&self) -> &sc_cli::SharedParams {
+		&self.shared_params
+	}
+
+	fn database_params(&self) -> Option<&sc_cli::DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
+
+#[allow(missing_docs)]
+#[derive(Debug, Parser)]
+pub struct ExportSyncStateCmd {
+	/// The chain spec file to write.
+	#[clap(parse(from_os_str))]
+	pub path: std::path::PathBuf,
+
+	/// Export the genesis state as raw storage.
+	#[clap(long)]
+	pub raw: bool,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: sc_cli::SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub database_params: sc_cli::DatabaseParams,
+}
+
+impl sc_cli::CliConfiguration for ExportSyncStateCmd {
+	fn shared_params(&self) -> &sc_cli::SharedParams {
+		&self.shared_params
+	}
+
+	fn database_params(&self) -> Option<&sc_cli::DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
+
+#[allow(missing_docs)]
+#[derive(Debug, Parser)]
+pub struct ApprovalCheckpointCmd {
+	/// The checkpoint file.
+	#[clap(parse(from_os_str))]
+	pub path: std::path::PathBuf,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: sc_cli::SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub database_params: sc_cli::DatabaseParams,
+}
+
+impl sc_cli::CliConfiguration for ApprovalCheckpointCmd {
+	fn shared_params(&self) -> &sc_cli::SharedParams {
+		&self.shared_params
+	}
+
+	fn database_params(&self) -> Option<&sc_cli::DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
+
+/// Database maintenance subcommands.
+#[derive(Debug, Parser)]
+pub enum DbCmd {
+	/// Copy the parachains database from RocksDB to ParityDB.
+	///
+	/// Keeps the availability, approval voting and dispute data of a validator switching to
+	/// `--database paritydb`. The client database isn't copied and is synced again. The node must
+	/// not be running.
+	MigrateRocksdbParitydb(MigrateDbCmd),
+}
+
+#[allow(missing_docs)]
+#[derive(Debug, Parser)]
+pub struct MigrateDbCmd {
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: sc_cli::SharedParams,
+}
+
+impl sc_cli::CliConfiguration for MigrateDbCmd {
+	fn shared_params(&self) -> &sc_cli::SharedParams {
+		&self.shared_params
+	}
+
+	// The paths of both databases are needed.
+	fn database(&self) -> sc_cli::Result<Option<sc_cli::Database>> {
+		Ok(Some(sc_cli::Database::Auto))
+	}
+}
+
 #[allow(missing_docs)]
 #[derive(Debug, Parser)]
 pub struct RunCmd {
@@ -101,7 +240,10 @@ pub struct RunCmd {
 	#[clap(long = "grandpa-pause", number_of_values(2))]
 	pub grandpa_pause: Vec<u32>,
 
-	/// Enable the BEEFY gadget (now is not avalible).
+	/// Enable the BEEFY gadget.
+	///
+	/// The gadget signs MMR roots of finalized blocks for bridges, once the runtime provides a BEEFY
+	/// validator set.
 	#[clap(long)]
 	pub beefy: bool,
 
@@ -118,6 +260,303 @@ pub struct RunCmd {
 	/// commonly `127.0.0.1:4040`.
 	#[clap(long)]
 	pub pyroscope_server: Option<String>,
+
+	/// Spawn the PVF preparation and execution workers from the given binary instead of the
+	/// node binary.
+	///
+	/// The binary has to support the `prepare-worker` and `execute-worker` subcommands and report
+	/// exactly the same version as the node. It is looked up and its version is checked again
+	/// every time a worker is spawned.
+	#[clap(long)]
+	pub pvf_worker_path: Option<std::path::PathBuf>,
+
+	/// Limit the data segment of each PVF worker to the given number of MiB.
+	#[clap(long)]
+	pub pvf_worker_memory_limit: Option<u64>,
+
+	/// Spawn the PVF workers with an empty environment.
+	#[clap(long)]
+	pub pvf_worker_clear_env: bool,
+
+	/// Prune the availability data of candidates finalized more than this many blocks ago.
+	///
+	/// By default availability data is only pruned based on time. Must be at least 15000 blocks,
+	/// the 25 hours the data is kept for otherwise, as the candidates can still be disputed.
+	#[clap(long)]
+	pub availability_pruning: Option<u32>,
+
+	/// Specify the state pruning mode, overriding `--pruning`.
+	///
+	/// `archive` keeps the state of all blocks, `archive-canonical` the state of all finalized
+	/// blocks and a number the state of that many recent blocks. Validators and collators require
+	/// `archive-canonical` at minimum. Also applies to the subcommands opening the database, such as
+	/// `check-block` and `export-blocks`.
+	#[clap(long, value_name = "MODE", global = true)]
+	pub state_pruning: Option<StatePruning>,
+
+	/// Specify the blocks pruning mode, overriding `--keep-blocks`.
+	///
+	/// `archive` keeps the bodies of all finalized blocks and a number the bodies of that many
+	/// recent finalized blocks.
+	#[clap(long, value_name = "MODE", global = true)]
+	pub blocks_pruning: Option<BlocksPruning>,
+
+	/// Run as a storage chain node, keeping the bodies of all finalized blocks.
+	///
+	/// The data stored through the transaction storage pallet is indexed with the block bodies, and
+	/// block authors have to prove it is still available for the storage period of the pallet.
+	/// Can't be combined with `--blocks-pruning`.
+	#[clap(long, global = true)]
+	pub storage_chain: bool,
+
+	/// Append the verdict of every candidate validation to the given file, one JSON object per
+	/// line.
+	///
+	/// Each entry holds the para, the PoV and validation code hashes, the verdict and the
+	/// duration of the validation, as well as the candidate hash of valid candidates.
+	#[clap(long)]
+	pub validation_audit_log: Option<std::path::PathBuf>,
+
+	/// Rotate the validation audit log once it exceeds this many MiB.
+	#[clap(long, default_value = "64")]
+	pub validation_audit_log_max_size: u64,
+
+	/// The number of rotated validation audit log files to keep.
+	#[clap(long, default_value = "4")]
+	pub validation_audit_log_max_files: usize,
+
+	/// The number of blocks approval checking may lag behind the chain head before the finality
+	/// lag alarm is raised.
+	#[clap(long, value_name = "BLOCKS", default_value = "50")]
+	pub finality_lag_threshold: u32,
+
+	/// Hand our assignments and approvals of the candidates holding up finality to approval
+	/// distribution again whenever the finality lag alarm is raised.
+	#[clap(long)]
+	pub finality_lag_redistribute: bool,
+
+	/// How the available data of candidates is recovered, e.g. for approval checking.
+	///
+	/// `backers-first-if-smaller` requests the full data from the backing group first if it is
+	/// estimated to be at most `--availability-recovery-fast-path-limit`, and otherwise
+	/// reconstructs it from the chunks of all validators. `backers-first` does so regardless of
+	/// the size, `chunks-only` never asks the backing group.
+	#[clap(long, arg_enum, value_name = "STRATEGY", default_value = "backers-first-if-smaller")]
+	pub availability_recovery: AvailabilityRecovery,
+
+	/// The size limit of the data recovered from the backing group first with
+	/// `--availability-recovery backers-first-if-smaller`, in KiB.
+	#[clap(long, value_name = "KIB", default_value = "128")]
+	pub availability_recovery_fast_path_limit: usize,
+
+	/// Export the inputs of every candidate found invalid into the given directory.
+	///
+	/// The exported bundles can be validated again offline to reproduce the verdict, e.g. of a
+	/// dispute. The oldest bundles are removed once the limits below are exceeded.
+	#[clap(long, value_name = "DIR")]
+	pub invalid_candidate_export: Option<std::path::PathBuf>,
+
+	/// The number of exported invalid candidates to keep.
+	#[clap(long, default_value = "64")]
+	pub invalid_candidate_export_max_files: usize,
+
+	/// The total size of the exported invalid candidates to keep, in MiB.
+	#[clap(long, default_value = "1024")]
+	pub invalid_candidate_export_max_size: u64,
+
+	/// Report the per-para candidate validation metrics only for the given comma separated para
+	/// ids.
+	///
+	/// The metrics of all other paras are aggregated under the `other` label, which bounds the
+	/// number of metric series. All paras are aggregated if not given.
+	#[clap(long, value_name = "PARA_IDS", use_value_delimiter = true)]
+	pub validation_metrics_paras: Vec<u32>,
+
+	/// The maximum number of seconds the node keeps working on the current leaves after SIGINT or
+	/// SIGTERM.
+	///
+	/// Approvals already being checked and the availability bitfield of the current leaf are still
+	/// signed and broadcast, but no new leaves are picked up. The node exits as soon as that work
+	/// is done, and networking is only stopped after that. A second signal exits right away.
+	#[clap(long, value_name = "SECS", default_value = "6")]
+	pub shutdown_grace_period: u64,
+
+	/// Apply a set of defaults tuned for the role of the node.
+	///
+	/// Sets the database cache, state and blocks pruning, offchain worker and RPC limits in one go.
+	/// Flags passed explicitly always take precedence over the profile.
+	#[clap(long, arg_enum)]
+	pub profile: Option<NodeProfile>,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub rpc_servers: RpcServerParams,
+}
+
+/// Settings of the HTTP and WS RPC servers, overriding the ones shared by both servers.
+#[derive(Debug, Parser)]
+pub struct RpcServerParams {
+	/// Don't start the HTTP RPC server.
+	#[clap(long)]
+	pub no_rpc_http: bool,
+
+	/// Don't start the WS RPC server.
+	#[clap(long)]
+	pub no_ws: bool,
+
+	/// Specify the browser origins allowed to access the HTTP RPC server, overriding
+	/// `--rpc-cors`.
+	///
+	/// A comma-separated list of origins (protocol://domain or special `null` value), or `all`
+	/// to disable the validation.
+	#[clap(long, value_name = "ORIGINS", parse(from_str = parse_cors))]
+	pub rpc_http_cors: Option<Cors>,
+
+	/// Specify the browser origins allowed to access the WS RPC server, overriding `--rpc-cors`.
+	///
+	/// A comma-separated list of origins (protocol://domain or special `null` value), or `all`
+	/// to disable the validation.
+	#[clap(long, value_name = "ORIGINS", parse(from_str = parse_cors))]
+	pub ws_cors: Option<Cors>,
+
+	/// Maximum HTTP RPC payload size in MiB, overriding `--rpc-max-payload`.
+	#[clap(long)]
+	pub rpc_http_max_payload: Option<usize>,
+
+	/// Maximum number of HTTP RPC requests served at the same time.
+	///
+	/// Every connection has at most one request in flight, so this bounds the number of
+	/// connections being served. Requests over the limit are answered with an error.
+	#[clap(long)]
+	pub rpc_http_max_connections: Option<usize>,
+
+	/// Maximum WS RPC payload size in MiB, overriding `--rpc-max-payload`.
+	#[clap(long)]
+	pub ws_max_payload: Option<usize>,
+
+	/// Maximum number of subscriptions open at the same time on a WS RPC connection.
+	///
+	/// Subscriptions ending on their own, like the ones watching an extrinsic, aren't counted.
+	#[clap(long)]
+	pub ws_max_subscriptions_per_connection: Option<usize>,
+}
+
+/// The browser origins allowed to access an RPC server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Cors {
+	/// All origins are allowed.
+	All,
+	/// Only the given origins are allowed.
+	List(Vec<String>),
+}
+
+impl From<Cors> for Option<Vec<String>> {
+	fn from(cors: Cors) -> Self {
+		match cors {
+			Cors::All => None,
+			Cors::List(origins) => Some(origins),
+		}
+	}
+}
+
+fn parse_cors(s: &str) -> Cors {
+	match s.trim() {
+		"all" | "*" => Cors::All,
+		origins => Cors::List(origins.split(',').map(|o| o.trim().to_owned()).collect()),
+	}
+}
+
+/// The state pruning mode, as passed to `--state-pruning`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatePruning {
+	/// Keep the state of all blocks.
+	Archive,
+	/// Keep the state of all finalized blocks.
+	ArchiveCanonical,
+	/// Keep the state of the given number of recent blocks.
+	Recent(u32),
+}
+
+impl std::str::FromStr for StatePruning {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"archive" => Ok(StatePruning::Archive),
+			"archive-canonical" => Ok(StatePruning::ArchiveCanonical),
+			n => n.parse().map(StatePruning::Recent).map_err(|_| {
+				format!(
+					"Invalid state pruning mode `{}`, expected `archive`, `archive-canonical` or \
+					a number of blocks",
+					n
+				)
+			}),
+		}
+	}
+}
+
+impl From<StatePruning> for sc_service::PruningMode {
+	fn from(pruning: StatePruning) -> Self {
+		match pruning {
+			StatePruning::Archive => sc_service::PruningMode::ArchiveAll,
+			StatePruning::ArchiveCanonical => sc_service::PruningMode::ArchiveCanonical,
+			StatePruning::Recent(n) => sc_service::PruningMode::keep_blocks(n),
+		}
+	}
+}
+
+/// The blocks pruning mode, as passed to `--blocks-pruning`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlocksPruning {
+	/// Keep all finalized blocks.
+	Archive,
+	/// Keep the given number of recent finalized blocks.
+	Recent(u32),
+}
+
+impl std::str::FromStr for BlocksPruning {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"archive" => Ok(BlocksPruning::Archive),
+			n =>
+				n.parse().map(BlocksPruning::Recent).map_err(|_| {
+					format!("Invalid blocks pruning mode `{}`, expected `archive` or a number of blocks", n)
+				}),
+		}
+	}
+}
+
+impl From<BlocksPruning> for sc_service::KeepBlocks {
+	fn from(pruning: BlocksPruning) -> Self {
+		match pruning {
+			BlocksPruning::Archive => sc_service::KeepBlocks::All,
+			BlocksPruning::Recent(n) => sc_service::KeepBlocks::Some(n),
+		}
+	}
+}
+
+/// The ways the available data of candidates can be recovered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ArgEnum)]
+pub enum AvailabilityRecovery {
+	/// Reconstruct the data from the chunks of all validators.
+	ChunksOnly,
+	/// Request the full data from the backing group first.
+	BackersFirst,
+	/// Request the full data from the backing group first if it is small enough.
+	BackersFirstIfSmaller,
+}
+
+/// Sets of defaults tuned for the role of a node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ArgEnum)]
+pub enum NodeProfile {
+	/// A validator: keeps enough state for approval voting and disputes.
+	Validator,
+	/// A node serving RPC requests for recent state.
+	Rpc,
+	/// A node keeping the full history of states and blocks.
+	Archive,
 }
 
 #[allow(missing_docs)]
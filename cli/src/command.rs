@@ -14,13 +14,13 @@
 // You should have received a copy of the GNU General Public License
 // along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
 
-use crate::cli::{Cli, Subcommand};
+use crate::cli::{AvailabilityRecovery, Cli, DbCmd, RpcServerParams, RunCmd, Subcommand};
 use futures::future::TryFutureExt;
 use log::info;
 use sc_cli::{Role, RuntimeVersion, SubstrateCli};
 use service::{self, IdentifyVariant};
 use sp_core::crypto::Ss58AddressFormat;
-use std::net::ToSocketAddrs;
+use std::{net::ToSocketAddrs, time::Duration};
 
 pub use crate::error::Error;
 pub use selendra_performance_test::PerfCheckError;
@@ -98,6 +98,14 @@ impl SubstrateCli for Cli {
 			"selendra-local" => Box::new(service::chain_spec::selendra_local_testnet_config()?),
 			#[cfg(feature = "selendra-native")]
 			"selendra-staging" => Box::new(service::chain_spec::selendra_staging_testnet_config()?),
+			path if service::chain_spec::ChainSpecBuilder::is_description(path) => {
+				let builder = service::chain_spec::ChainSpecBuilder::from_file(path.as_ref())?;
+				if self.run.force_cardamom || builder.is_cardamom() {
+					Box::new(builder.build_cardamom()?)
+				} else {
+					Box::new(builder.build_selendra()?)
+				}
+			},
 			path => {
 				let path = std::path::PathBuf::from(path);
 
@@ -163,6 +171,108 @@ fn host_perf_check() -> Result<()> {
 	}
 }
 
+/// Checks the availability store and reports the problems found.
+///
+/// Fails if problems were found and not pruned.
+fn availability_check(config: &sc_service::Configuration, prune: bool) -> Result<()> {
+	let report = service::check_availability_store(config, prune)?;
+
+	for corruption in &report.corruptions {
+		log::warn!("Availability store: {}", corruption);
+	}
+
+	info!(
+		"Checked {} candidates in the availability store, found {} problems, pruned {} candidates",
+		report.candidates_checked,
+		report.corruptions.len(),
+		report.candidates_pruned,
+	);
+
+	if report.candidates_skipped > 0 {
+		info!(
+			"Skipped {} candidates stored without an erasure root, they are pruned as usual",
+			report.candidates_skipped,
+		);
+	}
+
+	if !prune && !report.corruptions.is_empty() {
+		return Err(Error::Other(
+			"The availability store is corrupted. Run with `--prune` to delete the affected \
+				candidates."
+				.into(),
+		))
+	}
+
+	Ok(())
+}
+
+/// Exports the approval voting data to, or imports it from, the checkpoint file at `path`.
+fn approval_checkpoint(
+	config: &sc_service::Configuration,
+	path: &std::path::Path,
+	import: bool,
+) -> Result<()> {
+	let (action, summary) = if import {
+		("Imported", service::import_approval_checkpoint(config, path)?)
+	} else {
+		("Exported", service::export_approval_checkpoint(config, path)?)
+	};
+
+	match summary.block_range {
+		Some((start, end)) => info!(
+			"{} approval voting data of {} blocks and {} candidates, covering blocks #{}..#{}",
+			action, summary.blocks, summary.candidates, start, end,
+		),
+		None => info!("{} empty approval voting data", action),
+	}
+
+	Ok(())
+}
+
+/// Copies the parachains database from RocksDB to ParityDB, logging the progress.
+fn migrate_parachains_db(config: &sc_service::Configuration) -> Result<()> {
+	let copied = service::migrate_parachains_db_to_paritydb(config, |col, keys| {
+		info!("Copied {} keys of column {}", keys, col)
+	})?;
+
+	info!(
+		"Copied {} keys of the parachains database to ParityDB. Start the node with \
+			`--database paritydb` to use it.",
+		copied.iter().sum::<usize>(),
+	);
+	Ok(())
+}
+
+/// Writes the chain spec with the light sync state of the last finalized block to `path`.
+fn export_sync_state(
+	config: &mut sc_service::Configuration,
+	path: &std::path::Path,
+	raw: bool,
+) -> Result<()> {
+	service::export_sync_state(config, path, raw)?;
+	info!("Exported the chain spec with the light sync state to {}", path.display());
+	Ok(())
+}
+
+/// Apply `--state-pruning`, `--blocks-pruning` and `--storage-chain` to `config`.
+fn apply_pruning(run: &RunCmd, config: &mut sc_service::Configuration) -> Result<()> {
+	if let Some(state_pruning) = run.state_pruning {
+		config.state_pruning = state_pruning.into();
+	}
+	match (run.storage_chain, run.blocks_pruning) {
+		(true, Some(_)) =>
+			return Err(Error::Other(
+				"`--storage-chain` keeps all blocks and can't be combined with `--blocks-pruning`"
+					.into(),
+			)),
+		(true, None) => config.keep_blocks = sc_service::KeepBlocks::All,
+		(false, Some(blocks_pruning)) => config.keep_blocks = blocks_pruning.into(),
+		(false, None) => {},
+	}
+
+	Ok(())
+}
+
 /// Launch a node, accepting arguments just like a regular node,
 /// accepts an alternative overseer generator, to adjust behavior
 /// for integration tests as needed.
@@ -172,13 +282,17 @@ pub fn run_node(run: Cli, overseer_gen: impl service::OverseerGen) -> Result<()>
 }
 
 fn run_node_inner<F>(
-	cli: Cli,
+	mut cli: Cli,
 	overseer_gen: impl service::OverseerGen,
 	logger_hook: F,
 ) -> Result<()>
 where
 	F: FnOnce(&mut sc_cli::LoggerBuilder, &sc_service::Configuration),
 {
+	if let Some(profile) = cli.run.profile {
+		profile.apply(&mut cli.run);
+	}
+
 	let runner = cli
 		.create_runner_with_logger_hook::<sc_cli::RunCmd, F>(&cli.run.base, logger_hook)
 		.map_err(Error::from)?;
@@ -210,27 +324,148 @@ where
 		None
 	};
 
-	runner.run_node_until_exit(move |config| async move {
-		let role = config.role.clone();
-
-		match role {
-			Role::Light => Err(Error::Other("Light client not enabled".into())),
-			_ => service::build_full(
-				config,
-				service::IsCollator::No,
-				grandpa_pause,
-				cli.run.beefy,
-				jaeger_agent,
-				None,
-				false,
-				overseer_gen,
-			)
-			.map(|full| full.task_manager)
-			.map_err(Into::into),
-		}
+	let sandbox = service::SandboxConfig {
+		memory_limit: cli.run.pvf_worker_memory_limit.map(|mib| mib * 1024 * 1024),
+		clear_env: cli.run.pvf_worker_clear_env,
+		expected_version: None,
+	};
+	let isolation_strategy = match cli.run.pvf_worker_path {
+		None => service::IsolationStrategy::ExternalProcessSelfHost(sandbox),
+		Some(ref binary) => service::IsolationStrategy::ExternalProcessCustomHost {
+			binary: binary.clone(),
+			sandbox: service::SandboxConfig {
+				expected_version: Some(Cli::impl_version()),
+				..sandbox
+			},
+		},
+	};
+
+	let validation_audit_log =
+		cli.run
+			.validation_audit_log
+			.clone()
+			.map(|path| service::ValidationAuditLogConfig {
+				path,
+				max_file_size: cli.run.validation_audit_log_max_size * 1024 * 1024,
+				max_rotated_files: cli.run.validation_audit_log_max_files,
+			});
+
+	let invalid_candidate_export =
+		cli.run
+			.invalid_candidate_export
+			.clone()
+			.map(|dir| service::ValidationBundleExportConfig {
+				dir,
+				max_bundles: cli.run.invalid_candidate_export_max_files,
+				max_total_size: cli.run.invalid_candidate_export_max_size * 1024 * 1024,
+			});
+
+	let finality_lag_watchdog = service::FinalityLagWatchdogConfig {
+		lag_threshold: cli.run.finality_lag_threshold,
+		redistribute: cli.run.finality_lag_redistribute,
+	};
+
+	let availability_recovery_strategy = match cli.run.availability_recovery {
+		AvailabilityRecovery::ChunksOnly => service::AvailabilityRecoveryStrategy::ChunksOnly,
+		AvailabilityRecovery::BackersFirst => service::AvailabilityRecoveryStrategy::BackersFirst,
+		AvailabilityRecovery::BackersFirstIfSmaller =>
+			service::AvailabilityRecoveryStrategy::BackersFirstIfSmaller(
+				cli.run.availability_recovery_fast_path_limit * 1024,
+			),
+	};
+
+	let validation_metrics_paras = cli
+		.run
+		.validation_metrics_paras
+		.iter()
+		.copied()
+		.map(service::ParaId::from)
+		.collect();
+
+	let shutdown_grace_period = Duration::from_secs(cli.run.shutdown_grace_period);
+
+	// The exit signals are handled by `service::run_until_exit` instead of the runner, which would
+	// shut the service down right away.
+	sc_cli::print_node_infos::<Cli>(runner.config());
+	runner.sync_run(move |mut config| {
+		let tokio_handle = config.tokio_handle.clone();
+		tokio_handle.block_on(async move {
+			apply_pruning(&cli.run, &mut config)?;
+
+			let role = config.role.clone();
+			let mut rpc_servers = service::take_rpc_servers(&mut config);
+			configure_rpc_servers(&cli.run.rpc_servers, &mut rpc_servers);
+
+			match role {
+				Role::Light => Err(Error::Other("Light client not enabled".into())),
+				_ => {
+					let mut full = service::build_full(
+						config,
+						service::IsCollator::No,
+						grandpa_pause,
+						cli.run.beefy,
+						jaeger_agent,
+						None,
+						false,
+						service::NewFullParams {
+							isolation_strategy,
+							availability_pruning: cli.run.availability_pruning,
+							validation_audit_log,
+							validation_metrics_paras,
+							invalid_candidate_export,
+							finality_lag_watchdog,
+							availability_recovery_strategy,
+						},
+						overseer_gen,
+					)?;
+
+					let rpc_servers = rpc_servers.start(&full.rpc_handler_builder)?;
+					full.task_manager.keep_alive(rpc_servers);
+
+					service::run_until_exit(
+						full.task_manager,
+						full.overseer_handle,
+						full.pending_work,
+						shutdown_grace_period,
+					)
+					.await?;
+					Ok(())
+				},
+			}
+		})
 	})
 }
 
+/// Apply the settings of the HTTP and WS RPC servers given on the command line.
+fn configure_rpc_servers(params: &RpcServerParams, servers: &mut service::RpcServersConfig) {
+	if params.no_rpc_http {
+		servers.http = None;
+	}
+	if params.no_ws {
+		servers.ws = None;
+	}
+
+	if let Some(http) = &mut servers.http {
+		if let Some(cors) = &params.rpc_http_cors {
+			http.cors = cors.clone().into();
+		}
+		if params.rpc_http_max_payload.is_some() {
+			http.max_payload = params.rpc_http_max_payload;
+		}
+		http.max_connections = params.rpc_http_max_connections;
+	}
+
+	if let Some(ws) = &mut servers.ws {
+		if let Some(cors) = &params.ws_cors {
+			ws.cors = cors.clone().into();
+		}
+		if params.ws_max_payload.is_some() {
+			ws.max_payload = params.ws_max_payload;
+		}
+		ws.max_subscriptions_per_connection = params.ws_max_subscriptions_per_connection;
+	}
+}
+
 /// Parses selendra specific CLI arguments and run the service.
 pub fn run() -> Result<()> {
 	let cli: Cli = Cli::from_args();
@@ -273,6 +508,7 @@ pub fn run() -> Result<()> {
 			set_default_ss58_version(chain_spec);
 
 			runner.async_run(|mut config| {
+				apply_pruning(&cli.run, &mut config)?;
 				let (client, _, import_queue, task_manager) =
 					service::new_chain_ops(&mut config, None)?;
 				Ok((cmd.run(client, import_queue).map_err(Error::SubstrateCli), task_manager))
@@ -285,6 +521,7 @@ pub fn run() -> Result<()> {
 			set_default_ss58_version(chain_spec);
 
 			Ok(runner.async_run(|mut config| {
+				apply_pruning(&cli.run, &mut config)?;
 				let (client, _, _, task_manager) =
 					service::new_chain_ops(&mut config, None).map_err(Error::SelendraService)?;
 				Ok((cmd.run(client, config.database).map_err(Error::SubstrateCli), task_manager))
@@ -297,6 +534,7 @@ pub fn run() -> Result<()> {
 			set_default_ss58_version(chain_spec);
 
 			Ok(runner.async_run(|mut config| {
+				apply_pruning(&cli.run, &mut config)?;
 				let (client, _, _, task_manager) = service::new_chain_ops(&mut config, None)?;
 				Ok((cmd.run(client, config.chain_spec).map_err(Error::SubstrateCli), task_manager))
 			})?)
@@ -308,6 +546,7 @@ pub fn run() -> Result<()> {
 			set_default_ss58_version(chain_spec);
 
 			Ok(runner.async_run(|mut config| {
+				apply_pruning(&cli.run, &mut config)?;
 				let (client, _, import_queue, task_manager) =
 					service::new_chain_ops(&mut config, None)?;
 				Ok((cmd.run(client, import_queue).map_err(Error::SubstrateCli), task_manager))
@@ -315,7 +554,10 @@ pub fn run() -> Result<()> {
 		},
 		Some(Subcommand::PurgeChain(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
-			Ok(runner.sync_run(|config| cmd.run(config.database))?)
+			Ok(runner.sync_run(|mut config| {
+				apply_pruning(&cli.run, &mut config)?;
+				cmd.run(config.database).map_err(Error::SubstrateCli)
+			})?)
 		},
 		Some(Subcommand::Revert(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
@@ -324,6 +566,7 @@ pub fn run() -> Result<()> {
 			set_default_ss58_version(chain_spec);
 
 			Ok(runner.async_run(|mut config| {
+				apply_pruning(&cli.run, &mut config)?;
 				let (client, backend, _, task_manager) = service::new_chain_ops(&mut config, None)?;
 				Ok((cmd.run(client, backend).map_err(Error::SubstrateCli), task_manager))
 			})?)
@@ -402,6 +645,7 @@ pub fn run() -> Result<()> {
 			set_default_ss58_version(chain_spec);
 
 			Ok(runner.async_run(|mut config| {
+				apply_pruning(&cli.run, &mut config)?;
 				let (client, backend, _, task_manager) = service::new_chain_ops(&mut config, None)?;
 				let db = backend.expose_db();
 				let storage = backend.expose_storage();
@@ -419,6 +663,26 @@ pub fn run() -> Result<()> {
 
 			host_perf_check()
 		},
+		Some(Subcommand::AvailabilityCheck(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			Ok(runner.sync_run(|config| availability_check(&config, cmd.prune))?)
+		},
+		Some(Subcommand::ExportApprovalCheckpoint(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			Ok(runner.sync_run(|config| approval_checkpoint(&config, &cmd.path, false))?)
+		},
+		Some(Subcommand::ImportApprovalCheckpoint(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			Ok(runner.sync_run(|config| approval_checkpoint(&config, &cmd.path, true))?)
+		},
+		Some(Subcommand::ExportSyncState(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			Ok(runner.sync_run(|mut config| export_sync_state(&mut config, &cmd.path, cmd.raw))?)
+		},
+		Some(Subcommand::Db(DbCmd::MigrateRocksdbParitydb(cmd))) => {
+			let runner = cli.create_runner(cmd)?;
+			Ok(runner.sync_run(|config| migrate_parachains_db(&config))?)
+		},
 		Some(Subcommand::Key(cmd)) => Ok(cmd.run(&cli)?),
 		#[cfg(feature = "try-runtime")]
 		Some(Subcommand::TryRuntime(cmd)) => {
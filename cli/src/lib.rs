@@ -26,6 +26,8 @@ mod command;
 mod error;
 #[cfg(all(feature = "cli", build_type = "release"))]
 mod host_perf_check;
+#[cfg(feature = "cli")]
+mod profile;
 
 #[cfg(feature = "full-node")]
 pub use service::RuntimeApiCollection;
@@ -0,0 +1,101 @@
+// Copyright 2020-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Role based defaults for the node configuration.
+
+use crate::cli::{BlocksPruning, NodeProfile, RunCmd, StatePruning};
+use sc_cli::OffchainWorkerEnabled;
+
+/// The defaults applied by a [`NodeProfile`].
+struct Preset {
+	/// Database cache size in MiB.
+	database_cache_size: usize,
+	/// State pruning mode, as passed to `--state-pruning`.
+	state_pruning: StatePruning,
+	/// Blocks pruning mode, as passed to `--blocks-pruning`.
+	blocks_pruning: BlocksPruning,
+	/// Whether the offchain worker is run.
+	offchain_worker: OffchainWorkerEnabled,
+	/// Maximum RPC payload size in MiB.
+	rpc_max_payload: Option<usize>,
+	/// Maximum number of WS RPC server connections.
+	ws_max_connections: Option<usize>,
+}
+
+impl NodeProfile {
+	fn preset(self) -> Preset {
+		match self {
+			// Approval voting and disputes query the runtime at finalized blocks which may lag far
+			// behind the best block, so validators keep the state of all of them. Block authors
+			// prove the data of the transaction storage pallet, so they keep all blocks as well.
+			NodeProfile::Validator => Preset {
+				database_cache_size: 1024,
+				state_pruning: StatePruning::ArchiveCanonical,
+				blocks_pruning: BlocksPruning::Archive,
+				offchain_worker: OffchainWorkerEnabled::WhenValidating,
+				rpc_max_payload: None,
+				ws_max_connections: None,
+			},
+			NodeProfile::Rpc => Preset {
+				database_cache_size: 2048,
+				state_pruning: StatePruning::Recent(1000),
+				blocks_pruning: BlocksPruning::Recent(1000),
+				offchain_worker: OffchainWorkerEnabled::Never,
+				rpc_max_payload: Some(32),
+				ws_max_connections: Some(1000),
+			},
+			NodeProfile::Archive => Preset {
+				database_cache_size: 4096,
+				state_pruning: StatePruning::Archive,
+				blocks_pruning: BlocksPruning::Archive,
+				offchain_worker: OffchainWorkerEnabled::Never,
+				rpc_max_payload: Some(32),
+				ws_max_connections: Some(1000),
+			},
+		}
+	}
+
+	/// Fill in the settings of `run` which were not given explicitly with the defaults of this
+	/// profile.
+	pub(crate) fn apply(self, run: &mut RunCmd) {
+		let preset = self.preset();
+
+		if run.base.import_params.pruning_params.pruning.is_none() {
+			run.state_pruning.get_or_insert(preset.state_pruning);
+		}
+		// A storage chain node already keeps all blocks.
+		if run.base.import_params.pruning_params.keep_blocks.is_none() && !run.storage_chain {
+			run.blocks_pruning.get_or_insert(preset.blocks_pruning);
+		}
+
+		let cmd = &mut run.base;
+
+		let database_params = &mut cmd.import_params.database_params;
+		database_params.database_cache_size.get_or_insert(preset.database_cache_size);
+
+		// The flag has no "unset" state, so only the default value is overridden.
+		if cmd.offchain_worker_params.enabled == OffchainWorkerEnabled::WhenValidating {
+			cmd.offchain_worker_params.enabled = preset.offchain_worker;
+		}
+
+		if cmd.rpc_max_payload.is_none() {
+			cmd.rpc_max_payload = preset.rpc_max_payload;
+		}
+		if cmd.ws_max_connections.is_none() {
+			cmd.ws_max_connections = preset.ws_max_connections;
+		}
+	}
+}
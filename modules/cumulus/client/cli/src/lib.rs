@@ -27,6 +27,7 @@ use std::{
 	fs,
 	io::{self, Write},
 	net::SocketAddr,
+	time::Duration,
 };
 use url::Url;
 
@@ -160,13 +161,44 @@ pub struct RunCmd {
 		conflicts_with = "ferdie"
 	)]
 	pub relay_chain_rpc_url: Option<Url>,
+
+	/// The maximum number of blocks the best relay chain block may be ahead of the finalized one
+	/// for the collator to start collating.
+	#[clap(long, value_name = "BLOCKS", default_value = "8")]
+	pub relay_chain_max_finality_lag: u32,
+
+	/// The number of seconds to wait for the relay chain to be synced and finalized before
+	/// starting the collator anyway.
+	#[clap(long, value_name = "SECONDS", default_value = "600")]
+	pub relay_chain_freshness_timeout: u64,
 }
 
+/// The default of [`CollatorOptions::relay_chain_max_finality_lag`].
+pub const DEFAULT_RELAY_CHAIN_MAX_FINALITY_LAG: u32 = 8;
+
+/// The default of [`CollatorOptions::relay_chain_freshness_timeout`].
+pub const DEFAULT_RELAY_CHAIN_FRESHNESS_TIMEOUT: Duration = Duration::from_secs(600);
+
 /// Options only relevant for collator nodes
 #[derive(Clone, Debug)]
 pub struct CollatorOptions {
 	/// Location of relay chain full node
 	pub relay_chain_rpc_url: Option<Url>,
+	/// The maximum number of blocks the best relay chain block may be ahead of the finalized one
+	/// for the collator to start collating.
+	pub relay_chain_max_finality_lag: u32,
+	/// How long to wait for the relay chain to be fresh before starting the collator anyway.
+	pub relay_chain_freshness_timeout: Duration,
+}
+
+impl Default for CollatorOptions {
+	fn default() -> Self {
+		CollatorOptions {
+			relay_chain_rpc_url: None,
+			relay_chain_max_finality_lag: DEFAULT_RELAY_CHAIN_MAX_FINALITY_LAG,
+			relay_chain_freshness_timeout: DEFAULT_RELAY_CHAIN_FRESHNESS_TIMEOUT,
+		}
+	}
 }
 
 /// A non-redundant version of the `RunCmd` that sets the `validator` field when the
@@ -189,7 +221,11 @@ impl RunCmd {
 
 	/// Create [`CollatorOptions`] representing options only relevant to parachain collator nodes
 	pub fn collator_options(&self) -> CollatorOptions {
-		CollatorOptions { relay_chain_rpc_url: self.relay_chain_rpc_url.clone() }
+		CollatorOptions {
+			relay_chain_rpc_url: self.relay_chain_rpc_url.clone(),
+			relay_chain_max_finality_lag: self.relay_chain_max_finality_lag,
+			relay_chain_freshness_timeout: Duration::from_secs(self.relay_chain_freshness_timeout),
+		}
 	}
 }
 
@@ -0,0 +1,70 @@
+// Copyright 2019-2021 SmallWorld Selendra (Kh).
+// This file is part of Cumulus.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Cumulus.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Caching the statuses of the blocks the collator was asked to build on.
+//!
+//! The same parachain head is usually given to the collator for several relay chain blocks in a
+//! row, so its status doesn't need to be fetched from the backend every time.
+
+use sc_client_api::BlockBackend;
+use sp_consensus::BlockStatus;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+use parking_lot::Mutex;
+use std::{collections::VecDeque, sync::Arc};
+
+/// The number of block statuses cached.
+const CACHE_SIZE: usize = 16;
+
+/// A cache of the statuses of recently checked blocks.
+///
+/// The status of a block is invalidated when it is imported, see
+/// [`BlockStatusCache::on_block_imported`].
+pub(crate) struct BlockStatusCache<Block: BlockT, BS> {
+	block_status: Arc<BS>,
+	statuses: Mutex<VecDeque<(Block::Hash, BlockStatus)>>,
+}
+
+impl<Block: BlockT, BS: BlockBackend<Block>> BlockStatusCache<Block, BS> {
+	/// Create a new instance.
+	pub(crate) fn new(block_status: Arc<BS>) -> Self {
+		Self { block_status, statuses: Mutex::new(VecDeque::with_capacity(CACHE_SIZE)) }
+	}
+
+	/// Get the status of the given block, from the cache if possible.
+	pub(crate) fn block_status(&self, hash: Block::Hash) -> sp_blockchain::Result<BlockStatus> {
+		// The lock is held while querying the backend, so the status of a block imported in the
+		// meantime can't be cached after the import was noted.
+		let mut statuses = self.statuses.lock();
+		if let Some((_, status)) = statuses.iter().find(|(h, _)| *h == hash) {
+			return Ok(*status)
+		}
+
+		let status = self.block_status.block_status(&BlockId::Hash(hash))?;
+
+		if statuses.len() == CACHE_SIZE {
+			statuses.pop_front();
+		}
+		statuses.push_back((hash, status));
+
+		Ok(status)
+	}
+
+	/// Invalidate the cached status of an imported block.
+	pub(crate) fn on_block_imported(&self, hash: Block::Hash) {
+		self.statuses.lock().retain(|(h, _)| *h != hash);
+	}
+}
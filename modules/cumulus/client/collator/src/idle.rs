@@ -0,0 +1,149 @@
+// Copyright 2019-2021 SmallWorld Selendra (Kh).
+// This file is part of Cumulus.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Cumulus.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Skipping collations while the parachain is idle.
+//!
+//! By default a candidate is produced for every relay parent, even if the parachain has nothing
+//! to do, which only churns out empty blocks on quiet parachains. With an [`IdleCollation`] the
+//! collator skips the relay parents at which an [`IdleCheck`] reports the parachain as idle.
+
+use cumulus_primitives_core::{relay_chain::Hash as PHash, ParaId};
+use cumulus_relay_chain_interface::RelayChainInterface;
+
+use sc_transaction_pool_api::TransactionPool;
+use sp_runtime::traits::Block as BlockT;
+
+use std::sync::{
+	atomic::{AtomicU32, Ordering},
+	Arc,
+};
+
+use crate::LOG_TARGET;
+
+/// Decides whether the parachain has anything to do at a relay parent.
+#[async_trait::async_trait]
+pub trait IdleCheck<Block: BlockT>: Send + Sync {
+	/// Whether a block built on `parent` at `relay_parent` would have nothing to do.
+	async fn is_idle(&self, parent: &Block::Header, relay_parent: PHash) -> bool;
+}
+
+/// An [`IdleCheck`] considering the parachain idle while there are no ready transactions in its
+/// pool and no downward or inbound HRMP messages for it on the relay chain.
+pub struct PoolAndMessagesIdleCheck<Pool, RCInterface> {
+	para_id: ParaId,
+	transaction_pool: Arc<Pool>,
+	relay_chain_interface: RCInterface,
+}
+
+impl<Pool, RCInterface> PoolAndMessagesIdleCheck<Pool, RCInterface> {
+	/// Create a new instance.
+	pub fn new(
+		para_id: ParaId,
+		transaction_pool: Arc<Pool>,
+		relay_chain_interface: RCInterface,
+	) -> Self {
+		Self { para_id, transaction_pool, relay_chain_interface }
+	}
+}
+
+#[async_trait::async_trait]
+impl<Block, Pool, RCInterface> IdleCheck<Block> for PoolAndMessagesIdleCheck<Pool, RCInterface>
+where
+	Block: BlockT,
+	Pool: TransactionPool<Block = Block>,
+	RCInterface: RelayChainInterface,
+{
+	async fn is_idle(&self, _: &Block::Header, relay_parent: PHash) -> bool {
+		if self.transaction_pool.status().ready > 0 {
+			return false
+		}
+
+		// Not being able to tell whether there are messages counts as having some.
+		match self
+			.relay_chain_interface
+			.retrieve_dmq_contents(self.para_id, relay_parent)
+			.await
+		{
+			Ok(messages) if messages.is_empty() => {},
+			Ok(_) => return false,
+			Err(e) => {
+				tracing::debug!(
+					target: LOG_TARGET,
+					error = ?e,
+					"Failed to retrieve the downward messages.",
+				);
+				return false
+			},
+		}
+
+		match self
+			.relay_chain_interface
+			.retrieve_all_inbound_hrmp_channel_contents(self.para_id, relay_parent)
+			.await
+		{
+			Ok(channels) => channels.values().all(|messages| messages.is_empty()),
+			Err(e) => {
+				tracing::debug!(
+					target: LOG_TARGET,
+					error = ?e,
+					"Failed to retrieve the inbound HRMP messages.",
+				);
+				false
+			},
+		}
+	}
+}
+
+/// Skipping collations while the parachain is idle.
+pub struct IdleCollation<Block: BlockT> {
+	/// Decides whether the parachain is idle.
+	pub check: Box<dyn IdleCheck<Block>>,
+	/// The maximum number of relay parents in a row to skip.
+	///
+	/// A block is still produced now and then, so the parachain picks up signals from the relay
+	/// chain, like the go-ahead for a code upgrade.
+	pub max_skipped: u32,
+}
+
+/// The relay parents skipped by a collator.
+pub(crate) struct IdleCollationState<Block: BlockT> {
+	config: IdleCollation<Block>,
+	/// The number of relay parents skipped in a row.
+	skipped: AtomicU32,
+}
+
+impl<Block: BlockT> IdleCollationState<Block> {
+	/// Create a new instance.
+	pub(crate) fn new(config: IdleCollation<Block>) -> Self {
+		Self { config, skipped: AtomicU32::new(0) }
+	}
+
+	/// Whether the collation on `parent` at `relay_parent` should be skipped.
+	pub(crate) async fn should_skip(&self, parent: &Block::Header, relay_parent: PHash) -> bool {
+		if self.skipped.load(Ordering::Relaxed) >= self.config.max_skipped {
+			self.skipped.store(0, Ordering::Relaxed);
+			return false
+		}
+
+		if self.config.check.is_idle(parent, relay_parent).await {
+			self.skipped.fetch_add(1, Ordering::Relaxed);
+			true
+		} else {
+			self.skipped.store(0, Ordering::Relaxed);
+			false
+		}
+	}
+}
@@ -22,7 +22,7 @@ use cumulus_primitives_core::{
 	PersistedValidationData,
 };
 
-use sc_client_api::BlockBackend;
+use sc_client_api::{backend::AuxStore, BlockBackend, BlockchainEvents};
 use sp_api::{ApiExt, ProvideRuntimeApi};
 use sp_consensus::BlockStatus;
 use sp_core::traits::SpawnNamed;
@@ -40,20 +40,45 @@ use selendra_overseer::Handle as OverseerHandle;
 use selendra_primitives::v1::{CollatorPair, Id as ParaId};
 
 use codec::{Decode, Encode};
-use futures::{channel::oneshot, FutureExt};
+use futures::{
+	channel::{mpsc, oneshot},
+	FutureExt, StreamExt,
+};
 use parking_lot::Mutex;
-use std::sync::Arc;
+use std::{fmt, sync::Arc, time::Instant};
+use substrate_prometheus_endpoint::Registry;
 use tracing::Instrument;
 
+mod block_status_cache;
+mod idle;
+mod metrics;
+mod pending_collations;
+mod proof_accounting;
+pub mod rpc;
+use block_status_cache::BlockStatusCache;
+use idle::IdleCollationState;
+pub use idle::{IdleCheck, IdleCollation, PoolAndMessagesIdleCheck};
+use metrics::{CollationFailure, Metrics};
+use pending_collations::PendingCollations;
+pub use pending_collations::{
+	restore_pending_announcements, take_pending_collations, PendingCollation,
+};
+pub use proof_accounting::{
+	storage_proof_breakdown, PalletPrefix, StorageProofBreakdown, PALLET_PREFIX_LEN,
+};
+
 /// The logging target.
 const LOG_TARGET: &str = "cumulus-collator";
 
 /// The implementation of the Cumulus `Collator`.
 pub struct Collator<Block: BlockT, BS, RA> {
-	block_status: Arc<BS>,
+	block_status: Arc<BlockStatusCache<Block, BS>>,
 	parachain_consensus: Box<dyn ParachainConsensus<Block>>,
 	wait_to_announce: Arc<Mutex<WaitToAnnounce<Block>>>,
+	pending_collations: Arc<PendingCollations<Block, BS>>,
 	runtime_api: Arc<RA>,
+	idle_collation: Option<Arc<IdleCollationState<Block>>>,
+	metrics: Metrics,
 }
 
 impl<Block: BlockT, BS, RA> Clone for Collator<Block, BS, RA> {
@@ -61,8 +86,11 @@ impl<Block: BlockT, BS, RA> Clone for Collator<Block, BS, RA> {
 		Self {
 			block_status: self.block_status.clone(),
 			wait_to_announce: self.wait_to_announce.clone(),
+			pending_collations: self.pending_collations.clone(),
 			parachain_consensus: self.parachain_consensus.clone(),
 			runtime_api: self.runtime_api.clone(),
+			idle_collation: self.idle_collation.clone(),
+			metrics: self.metrics.clone(),
 		}
 	}
 }
@@ -70,7 +98,7 @@ impl<Block: BlockT, BS, RA> Clone for Collator<Block, BS, RA> {
 impl<Block, BS, RA> Collator<Block, BS, RA>
 where
 	Block: BlockT,
-	BS: BlockBackend<Block>,
+	BS: BlockBackend<Block> + BlockchainEvents<Block> + AuxStore + Send + Sync + 'static,
 	RA: ProvideRuntimeApi<Block>,
 	RA::Api: CollectCollationInfo<Block>,
 {
@@ -81,17 +109,58 @@ where
 		announce_block: Arc<dyn Fn(Block::Hash, Option<Vec<u8>>) + Send + Sync>,
 		runtime_api: Arc<RA>,
 		parachain_consensus: Box<dyn ParachainConsensus<Block>>,
+		idle_collation: Option<IdleCollation<Block>>,
+		prometheus_registry: Option<&Registry>,
 	) -> Self {
-		let wait_to_announce = Arc::new(Mutex::new(WaitToAnnounce::new(spawner, announce_block)));
+		let wait_to_announce =
+			Arc::new(Mutex::new(WaitToAnnounce::new(spawner.clone(), announce_block)));
+		let pending_collations = Arc::new(PendingCollations::new(block_status.clone()));
+		let block_status_cache = Arc::new(BlockStatusCache::new(block_status.clone()));
+		let metrics = Metrics::register(prometheus_registry).unwrap_or_else(|e| {
+			tracing::warn!(
+				target: LOG_TARGET,
+				error = ?e,
+				"Failed to register the collator metrics.",
+			);
+			Metrics::default()
+		});
 
-		Self { block_status, wait_to_announce, runtime_api, parachain_consensus }
+		let mut imported_blocks = block_status.import_notification_stream();
+		let cache = block_status_cache.clone();
+		let announcements = wait_to_announce.clone();
+		spawner.spawn(
+			"cumulus-collator-import-watcher",
+			None,
+			async move {
+				while let Some(notification) = imported_blocks.next().await {
+					cache.on_block_imported(notification.hash);
+
+					if notification.is_new_best {
+						announcements
+							.lock()
+							.note_new_best(notification.hash, *notification.header.number());
+					}
+				}
+			}
+			.boxed(),
+		);
+
+		Self {
+			block_status: block_status_cache,
+			wait_to_announce,
+			pending_collations,
+			runtime_api,
+			parachain_consensus,
+			idle_collation: idle_collation.map(|config| Arc::new(IdleCollationState::new(config))),
+			metrics,
+		}
 	}
 
 	/// Checks the status of the given block hash in the Parachain.
 	///
 	/// Returns `true` if the block could be found and is good to be build on.
 	fn check_block_status(&self, hash: Block::Hash, header: &Block::Header) -> bool {
-		match self.block_status.block_status(&BlockId::Hash(hash)) {
+		match self.block_status.block_status(hash) {
 			Ok(BlockStatus::Queued) => {
 				tracing::debug!(
 					target: LOG_TARGET,
@@ -214,6 +283,25 @@ where
 		relay_parent: PHash,
 		validation_data: PersistedValidationData,
 	) -> Option<CollationResult> {
+		let _timer = self.metrics.time_produce_candidate();
+
+		match self.try_produce_candidate(relay_parent, validation_data).await {
+			Ok(result) => {
+				self.metrics.on_collation_succeeded();
+				Some(result)
+			},
+			Err(failure) => {
+				self.metrics.on_collation_failed(failure);
+				None
+			},
+		}
+	}
+
+	async fn try_produce_candidate(
+		&mut self,
+		relay_parent: PHash,
+		validation_data: PersistedValidationData,
+	) -> Result<CollationResult, CollationFailure> {
 		tracing::trace!(
 			target: LOG_TARGET,
 			relay_parent = ?relay_parent,
@@ -228,13 +316,25 @@ where
 					error = ?e,
 					"Could not decode the head data."
 				);
-				return None
+				return Err(CollationFailure::InvalidParentHead)
 			},
 		};
 
 		let last_head_hash = last_head.hash();
 		if !self.check_block_status(last_head_hash, &last_head) {
-			return None
+			return Err(CollationFailure::ParentNotReady)
+		}
+
+		if let Some(idle_collation) = &self.idle_collation {
+			if idle_collation.should_skip(&last_head, relay_parent).await {
+				tracing::debug!(
+					target: LOG_TARGET,
+					relay_parent = ?relay_parent,
+					at = ?last_head_hash,
+					"Parachain is idle, skipping collation.",
+				);
+				return Err(CollationFailure::Idle)
+			}
 		}
 
 		tracing::info!(
@@ -247,10 +347,19 @@ where
 		let candidate = self
 			.parachain_consensus
 			.produce_candidate(&last_head, relay_parent, &validation_data)
-			.await?;
+			.await
+			.ok_or(CollationFailure::NoCandidate)?;
 
 		let (header, extrinsics) = candidate.block.deconstruct();
 
+		if tracing::enabled!(target: LOG_TARGET, tracing::Level::DEBUG) {
+			let breakdown = storage_proof_breakdown::<HashFor<Block>>(
+				&candidate.proof,
+				*last_head.state_root(),
+			);
+			tracing::debug!(target: LOG_TARGET, %breakdown, "Storage proof size by pallet.");
+		}
+
 		let compact_proof = match candidate
 			.proof
 			.into_compact_proof::<HashFor<Block>>(last_head.state_root().clone())
@@ -258,19 +367,24 @@ where
 			Ok(proof) => proof,
 			Err(e) => {
 				tracing::error!(target: "cumulus-collator", "Failed to compact proof: {:?}", e);
-				return None
+				return Err(CollationFailure::CompactProof)
 			},
 		};
 
 		// Create the parachain block data for the validators.
 		let b = ParachainBlockData::<Block>::new(header, extrinsics, compact_proof);
 
+		let header_size = b.header().encoded_size();
+		let extrinsics_size = b.extrinsics().encoded_size();
+		let storage_proof_size = b.storage_proof().encoded_size();
+		self.metrics.on_pov_size(header_size, extrinsics_size, storage_proof_size);
+
 		tracing::info!(
 			target: LOG_TARGET,
 			"PoV size {{ header: {}kb, extrinsics: {}kb, storage_proof: {}kb }}",
-			b.header().encode().len() as f64 / 1024f64,
-			b.extrinsics().encode().len() as f64 / 1024f64,
-			b.storage_proof().encode().len() as f64 / 1024f64,
+			header_size as f64 / 1024f64,
+			extrinsics_size as f64 / 1024f64,
+			storage_proof_size as f64 / 1024f64,
 		);
 
 		let pov =
@@ -282,19 +396,92 @@ where
 			pov.block_data.0.len() as f64 / 1024f64,
 		);
 
+		// The relay chain checks the size of the encoded, compressed PoV.
+		let (pov_hash, pov_size) = pov.hash_and_encoded_size();
+		if pov_size > validation_data.max_pov_size as usize {
+			tracing::error!(
+				target: LOG_TARGET,
+				pov_size,
+				max_pov_size = validation_data.max_pov_size,
+				"PoV exceeds the maximum PoV size of the relay chain, aborting collation.",
+			);
+			return Err(CollationFailure::OversizedPov)
+		}
+
 		let block_hash = b.header().hash();
-		let collation = self.build_collation(b, block_hash, pov)?;
+		let block_number = *b.header().number();
+		let collation = self
+			.build_collation(b, block_hash, pov)
+			.ok_or(CollationFailure::CollationInfo)?;
+
+		self.pending_collations.insert(PendingCollation {
+			block_hash,
+			head_data_hash: collation.head_data.hash(),
+			relay_parent,
+			pov_hash,
+		});
 
 		let (result_sender, signed_stmt_recv) = oneshot::channel();
 
-		self.wait_to_announce.lock().wait_to_announce(block_hash, signed_stmt_recv);
+		// Only forget the collation once the statement arrived, as the channel is also dropped
+		// when the collator is shutting down.
+		let pending_collations = self.pending_collations.clone();
+		let metrics = self.metrics.clone();
+		let produced_at = Instant::now();
+		let signed_stmt_recv = signed_stmt_recv.inspect(move |res| {
+			if res.is_ok() {
+				pending_collations.remove(block_hash);
+				metrics.on_block_announced(produced_at.elapsed());
+			}
+		});
+
+		self.wait_to_announce
+			.lock()
+			.wait_to_announce(block_hash, block_number, signed_stmt_recv);
 
 		tracing::info!(target: LOG_TARGET, ?block_hash, "Produced proof-of-validity candidate.",);
 
-		Some(CollationResult { collation, result_sender: Some(result_sender) })
+		Ok(CollationResult { collation, result_sender: Some(result_sender) })
+	}
+}
+
+/// A handle to rotate the key of a running collator, e.g. from an RPC.
+///
+/// Created by [`collator_key_rotation`].
+#[derive(Clone)]
+pub struct CollatorKeyRotation(mpsc::UnboundedSender<CollatorPair>);
+
+/// The new keys sent through a [`CollatorKeyRotation`], to be passed to [`start_collator`].
+pub type CollatorKeyRotations = mpsc::UnboundedReceiver<CollatorPair>;
+
+/// The collator whose key should be rotated isn't running anymore.
+#[derive(Debug)]
+pub struct CollatorStopped;
+
+impl fmt::Display for CollatorStopped {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "The collator isn't running anymore")
+	}
+}
+
+impl std::error::Error for CollatorStopped {}
+
+impl CollatorKeyRotation {
+	/// Rotate the collator key.
+	///
+	/// The new key is used from the next relay chain session on, so it needs to be known to the
+	/// parachain by then.
+	pub fn rotate(&self, key: CollatorPair) -> Result<(), CollatorStopped> {
+		self.0.unbounded_send(key).map_err(|_| CollatorStopped)
 	}
 }
 
+/// Create a handle to rotate the key of a collator, and the keys to pass to [`start_collator`].
+pub fn collator_key_rotation() -> (CollatorKeyRotation, CollatorKeyRotations) {
+	let (sender, receiver) = mpsc::unbounded();
+	(CollatorKeyRotation(sender), receiver)
+}
+
 /// Parameters for [`start_collator`].
 pub struct StartCollatorParams<Block: BlockT, RA, BS, Spawner> {
 	pub para_id: ParaId,
@@ -304,7 +491,11 @@ pub struct StartCollatorParams<Block: BlockT, RA, BS, Spawner> {
 	pub overseer_handle: OverseerHandle,
 	pub spawner: Spawner,
 	pub key: CollatorPair,
+	pub key_rotations: Option<CollatorKeyRotations>,
 	pub parachain_consensus: Box<dyn ParachainConsensus<Block>>,
+	/// Skip collations while the parachain is idle, `None` to collate on every relay parent.
+	pub idle_collation: Option<IdleCollation<Block>>,
+	pub prometheus_registry: Option<Registry>,
 }
 
 /// Start the collator.
@@ -316,41 +507,71 @@ pub async fn start_collator<Block, RA, BS, Spawner>(
 		mut overseer_handle,
 		spawner,
 		key,
+		key_rotations,
 		parachain_consensus,
+		idle_collation,
 		runtime_api,
+		prometheus_registry,
 	}: StartCollatorParams<Block, RA, BS, Spawner>,
 ) where
 	Block: BlockT,
-	BS: BlockBackend<Block> + Send + Sync + 'static,
+	BS: BlockBackend<Block> + BlockchainEvents<Block> + AuxStore + Send + Sync + 'static,
 	Spawner: SpawnNamed + Clone + Send + Sync + 'static,
 	RA: ProvideRuntimeApi<Block> + Send + Sync + 'static,
 	RA::Api: CollectCollationInfo<Block>,
 {
 	let collator = Collator::new(
 		block_status,
-		Arc::new(spawner),
+		Arc::new(spawner.clone()),
 		announce_block,
 		runtime_api,
 		parachain_consensus,
+		idle_collation,
+		prometheus_registry.as_ref(),
 	);
 
 	let span = tracing::Span::current();
-	let config = CollationGenerationConfig {
-		key,
-		para_id,
-		collator: Box::new(move |relay_parent, validation_data| {
-			let collator = collator.clone();
-			collator
-				.produce_candidate(relay_parent, validation_data.clone())
-				.instrument(span.clone())
-				.boxed()
-		}),
+	let config = move |key| {
+		let collator = collator.clone();
+		let span = span.clone();
+		CollationGenerationConfig {
+			key,
+			para_id,
+			collator: Box::new(move |relay_parent, validation_data| {
+				let collator = collator.clone();
+				collator
+					.produce_candidate(relay_parent, validation_data.clone())
+					.instrument(span.clone())
+					.boxed()
+			}),
+		}
 	};
 
 	overseer_handle
-		.send_msg(CollationGenerationMessage::Initialize(config), "StartCollator")
+		.send_msg(CollationGenerationMessage::Initialize(config(key)), "StartCollator")
 		.await;
 
+	if let Some(mut key_rotations) = key_rotations {
+		let mut overseer_handle = overseer_handle.clone();
+		spawner.spawn(
+			"cumulus-collator-key-rotation",
+			None,
+			async move {
+				// Initializing the collation generation again rotates the key.
+				while let Some(key) = key_rotations.next().await {
+					tracing::info!(
+						target: LOG_TARGET,
+						"Rotating the collator key at the next relay chain session.",
+					);
+					overseer_handle
+						.send_msg(CollationGenerationMessage::Initialize(config(key)), "RotateKey")
+						.await;
+				}
+			}
+			.boxed(),
+		);
+	}
+
 	overseer_handle
 		.send_msg(CollatorProtocolMessage::CollateOn(para_id), "StartCollator")
 		.await;
@@ -373,6 +594,9 @@ mod tests {
 	use sp_runtime::traits::BlakeTwo256;
 	use sp_state_machine::Backend;
 
+	/// The maximum PoV size used by the tests, the default of the relay chain.
+	const MAX_POV_SIZE: u32 = 5 * 1024 * 1024;
+
 	struct AlwaysSupportsParachains;
 	impl HeadSupportsParachains for AlwaysSupportsParachains {
 		fn head_supports_parachains(&self, _head: &PHash) -> bool {
@@ -411,15 +635,16 @@ mod tests {
 		}
 	}
 
-	#[test]
-	fn collates_produces_a_block_and_storage_proof_does_not_contains_code() {
-		sp_tracing::try_init_simple();
-
+	/// Start a collator on top of `client` and return the config it initialized collation
+	/// generation with.
+	fn start_test_collator(
+		client: Arc<Client>,
+		prometheus_registry: Option<Registry>,
+		idle_collation: Option<IdleCollation<Block>>,
+	) -> CollationGenerationConfig {
 		let spawner = TaskExecutor::new();
 		let para_id = ParaId::from(100);
 		let announce_block = |_, _| ();
-		let client = Arc::new(TestClientBuilder::new().build());
-		let header = client.header(&BlockId::Number(0)).unwrap().unwrap();
 
 		let (sub_tx, sub_rx) = mpsc::channel(64);
 
@@ -440,7 +665,10 @@ mod tests {
 			spawner,
 			para_id,
 			key: CollatorPair::generate().0,
+			key_rotations: None,
 			parachain_consensus: Box::new(DummyParachainConsensus { client: client.clone() }),
+			idle_collation,
+			prometheus_registry,
 		});
 		block_on(collator_start);
 
@@ -448,12 +676,35 @@ mod tests {
 			.0
 			.expect("message should be send by `start_collator` above.");
 
-		let config = match msg {
+		match msg {
 			CollationGenerationMessage::Initialize(config) => config,
-		};
+		}
+	}
+
+	/// Get the metric family with the given name from the registry.
+	fn metric_family(
+		registry: &Registry,
+		name: &str,
+	) -> substrate_prometheus_endpoint::prometheus::proto::MetricFamily {
+		registry
+			.gather()
+			.into_iter()
+			.find(|family| family.get_name() == name)
+			.expect("Metric is registered")
+	}
+
+	#[test]
+	fn collates_produces_a_block_and_storage_proof_does_not_contains_code() {
+		sp_tracing::try_init_simple();
+
+		let client = Arc::new(TestClientBuilder::new().build());
+		let header = client.header(&BlockId::Number(0)).unwrap().unwrap();
+
+		let config = start_test_collator(client.clone(), None, None);
 
 		let mut validation_data = PersistedValidationData::default();
 		validation_data.parent_head = header.encode().into();
+		validation_data.max_pov_size = MAX_POV_SIZE;
 		let relay_parent = Default::default();
 
 		let collation = block_on((config.collator)(relay_parent, &validation_data))
@@ -487,4 +738,168 @@ mod tests {
 			.unwrap_err()
 			.contains("Trie lookup error: Database missing expected key"));
 	}
+
+	#[test]
+	fn produced_collation_is_pending_until_announced() {
+		sp_tracing::try_init_simple();
+
+		let client = Arc::new(TestClientBuilder::new().build());
+		let header = client.header(&BlockId::Number(0)).unwrap().unwrap();
+
+		let config = start_test_collator(client.clone(), None, None);
+
+		let mut validation_data = PersistedValidationData::default();
+		validation_data.parent_head = header.encode().into();
+		validation_data.max_pov_size = MAX_POV_SIZE;
+		let relay_parent = PHash::repeat_byte(1);
+
+		let collation = block_on((config.collator)(relay_parent, &validation_data))
+			.expect("Collation is build")
+			.collation;
+
+		let head_data_hash = collation.head_data.hash();
+		let pov = collation.proof_of_validity.into_compressed();
+		let block_hash = client.header(&BlockId::Number(1)).unwrap().unwrap().hash();
+
+		let pending = take_pending_collations::<PHash, _>(&*client);
+		assert_eq!(
+			pending,
+			vec![PendingCollation {
+				block_hash,
+				head_data_hash,
+				relay_parent,
+				pov_hash: pov.hash()
+			}],
+		);
+
+		// Taking the pending collations removes them from the store.
+		assert!(take_pending_collations::<PHash, _>(&*client).is_empty());
+	}
+
+	#[test]
+	fn collation_exceeding_max_pov_size_is_aborted() {
+		sp_tracing::try_init_simple();
+
+		let client = Arc::new(TestClientBuilder::new().build());
+		let header = client.header(&BlockId::Number(0)).unwrap().unwrap();
+
+		let registry = Registry::new();
+		let config = start_test_collator(client.clone(), Some(registry.clone()), None);
+
+		let mut validation_data = PersistedValidationData::default();
+		validation_data.parent_head = header.encode().into();
+		validation_data.max_pov_size = 1;
+
+		assert!(block_on((config.collator)(Default::default(), &validation_data)).is_none());
+		assert!(take_pending_collations::<PHash, _>(&*client).is_empty());
+
+		let oversized_povs = metric_family(&registry, "cumulus_collator_oversized_povs_total");
+		assert_eq!(1.0, oversized_povs.get_metric()[0].get_counter().get_value());
+
+		let collations = metric_family(&registry, "cumulus_collator_collations_total");
+		assert_eq!("oversized_pov", collations.get_metric()[0].get_label()[0].get_value());
+		assert_eq!(1.0, collations.get_metric()[0].get_counter().get_value());
+	}
+
+	#[test]
+	fn produced_collation_is_recorded_in_metrics() {
+		sp_tracing::try_init_simple();
+
+		let client = Arc::new(TestClientBuilder::new().build());
+		let header = client.header(&BlockId::Number(0)).unwrap().unwrap();
+
+		let registry = Registry::new();
+		let config = start_test_collator(client.clone(), Some(registry.clone()), None);
+
+		let mut validation_data = PersistedValidationData::default();
+		validation_data.parent_head = header.encode().into();
+		validation_data.max_pov_size = MAX_POV_SIZE;
+
+		block_on((config.collator)(Default::default(), &validation_data))
+			.expect("Collation is build");
+
+		let collations = metric_family(&registry, "cumulus_collator_collations_total");
+		assert_eq!("success", collations.get_metric()[0].get_label()[0].get_value());
+		assert_eq!(1.0, collations.get_metric()[0].get_counter().get_value());
+
+		let produce_candidate =
+			metric_family(&registry, "cumulus_collator_produce_candidate_seconds");
+		assert_eq!(1, produce_candidate.get_metric()[0].get_histogram().get_sample_count());
+
+		let mut components = metric_family(&registry, "cumulus_collator_pov_size_bytes")
+			.get_metric()
+			.iter()
+			.map(|metric| {
+				assert_eq!(1, metric.get_histogram().get_sample_count());
+				metric.get_label()[0].get_value().to_string()
+			})
+			.collect::<Vec<_>>();
+		components.sort();
+		assert_eq!(components, vec!["extrinsics", "header", "storage_proof"]);
+	}
+
+	#[test]
+	fn idle_parachain_skips_collations() {
+		struct AlwaysIdle;
+
+		#[async_trait::async_trait]
+		impl IdleCheck<Block> for AlwaysIdle {
+			async fn is_idle(&self, _: &Header, _: PHash) -> bool {
+				true
+			}
+		}
+
+		sp_tracing::try_init_simple();
+
+		let client = Arc::new(TestClientBuilder::new().build());
+		let header = client.header(&BlockId::Number(0)).unwrap().unwrap();
+
+		let registry = Registry::new();
+		let idle_collation = IdleCollation { check: Box::new(AlwaysIdle), max_skipped: 2 };
+		let config =
+			start_test_collator(client.clone(), Some(registry.clone()), Some(idle_collation));
+
+		let mut validation_data = PersistedValidationData::default();
+		validation_data.parent_head = header.encode().into();
+		validation_data.max_pov_size = MAX_POV_SIZE;
+
+		// Skipping is limited, so a block is still built after `max_skipped` relay parents.
+		for _ in 0..2 {
+			assert!(block_on((config.collator)(Default::default(), &validation_data)).is_none());
+		}
+		block_on((config.collator)(Default::default(), &validation_data))
+			.expect("Collation is build");
+
+		let collations = metric_family(&registry, "cumulus_collator_collations_total");
+		let mut results = collations
+			.get_metric()
+			.iter()
+			.map(|metric| {
+				(metric.get_label()[0].get_value().to_string(), metric.get_counter().get_value())
+			})
+			.collect::<Vec<_>>();
+		results.sort_by(|a, b| a.0.cmp(&b.0));
+		assert_eq!(results, vec![("idle".to_string(), 2.0), ("success".to_string(), 1.0)]);
+	}
+
+	#[test]
+	fn block_status_cache_is_invalidated_on_import() {
+		let client = Arc::new(TestClientBuilder::new().build());
+		let cache = BlockStatusCache::<Block, _>::new(client.clone());
+
+		let (block, _, _) = client
+			.init_block_builder(None, Default::default())
+			.build()
+			.expect("Builds block")
+			.into_inner();
+		let hash = block.header().hash();
+		assert_eq!(BlockStatus::Unknown, cache.block_status(hash).unwrap());
+
+		block_on(client.import(BlockOrigin::Own, block)).expect("Imports the block");
+
+		// The cached status is returned until the import is noted.
+		assert_eq!(BlockStatus::Unknown, cache.block_status(hash).unwrap());
+		cache.on_block_imported(hash);
+		assert_eq!(BlockStatus::InChainWithState, cache.block_status(hash).unwrap());
+	}
 }
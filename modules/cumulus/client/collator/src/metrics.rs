@@ -0,0 +1,184 @@
+// Copyright 2019-2021 SmallWorld Selendra (Kh).
+// This file is part of Cumulus.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Cumulus.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Prometheus metrics of the collator.
+
+use std::time::Duration;
+use substrate_prometheus_endpoint::{
+	exponential_buckets, prometheus::HistogramTimer, register, Counter, CounterVec, Histogram,
+	HistogramOpts, HistogramVec, Opts, PrometheusError, Registry, U64,
+};
+
+/// Metrics of the collator.
+#[derive(Clone, Default)]
+pub(crate) struct Metrics(Option<MetricsInner>);
+
+#[derive(Clone)]
+struct MetricsInner {
+	/// Collations aborted because their PoV exceeded the maximum PoV size.
+	oversized_povs: Counter<U64>,
+	/// Finished collations.
+	///
+	/// Split by result, which is either `success` or the reason the collation failed.
+	collations: CounterVec<U64>,
+	/// Time spent producing a candidate.
+	produce_candidate: Histogram,
+	/// Size of the PoV, split by the `header`, `extrinsics` and `storage_proof` components.
+	pov_size: HistogramVec,
+	/// Time between producing a collation and announcing its block.
+	announcement_latency: Histogram,
+}
+
+/// The reason producing a collation failed.
+#[derive(Clone, Copy)]
+pub(crate) enum CollationFailure {
+	/// The head data of the parent couldn't be decoded.
+	InvalidParentHead,
+	/// The parent isn't imported or can't be built on.
+	ParentNotReady,
+	/// The parachain was idle, so the collation was skipped.
+	Idle,
+	/// The consensus didn't produce a candidate.
+	NoCandidate,
+	/// The storage proof couldn't be compacted.
+	CompactProof,
+	/// The PoV exceeded the maximum PoV size.
+	OversizedPov,
+	/// The collation info couldn't be fetched from the runtime.
+	CollationInfo,
+}
+
+impl CollationFailure {
+	fn label(self) -> &'static str {
+		match self {
+			Self::InvalidParentHead => "invalid_parent_head",
+			Self::ParentNotReady => "parent_not_ready",
+			Self::Idle => "idle",
+			Self::NoCandidate => "no_candidate",
+			Self::CompactProof => "compact_proof",
+			Self::OversizedPov => "oversized_pov",
+			Self::CollationInfo => "collation_info",
+		}
+	}
+}
+
+impl Metrics {
+	/// Register the metrics in the given registry, if any.
+	pub(crate) fn register(registry: Option<&Registry>) -> Result<Self, PrometheusError> {
+		match registry {
+			None => Ok(Self(None)),
+			Some(registry) => MetricsInner::register(registry).map(|inner| Self(Some(inner))),
+		}
+	}
+
+	/// Get a timer to time producing a candidate.
+	pub(crate) fn time_produce_candidate(&self) -> Option<HistogramTimer> {
+		self.0.as_ref().map(|metrics| metrics.produce_candidate.start_timer())
+	}
+
+	/// A collation was produced successfully.
+	pub(crate) fn on_collation_succeeded(&self) {
+		if let Some(metrics) = &self.0 {
+			metrics.collations.with_label_values(&["success"]).inc();
+		}
+	}
+
+	/// Producing a collation failed.
+	pub(crate) fn on_collation_failed(&self, failure: CollationFailure) {
+		if let Some(metrics) = &self.0 {
+			metrics.collations.with_label_values(&[failure.label()]).inc();
+
+			if let CollationFailure::OversizedPov = failure {
+				metrics.oversized_povs.inc();
+			}
+		}
+	}
+
+	/// Record the size of the components of a PoV.
+	pub(crate) fn on_pov_size(&self, header: usize, extrinsics: usize, storage_proof: usize) {
+		if let Some(metrics) = &self.0 {
+			metrics.pov_size.with_label_values(&["header"]).observe(header as f64);
+			metrics.pov_size.with_label_values(&["extrinsics"]).observe(extrinsics as f64);
+			metrics
+				.pov_size
+				.with_label_values(&["storage_proof"])
+				.observe(storage_proof as f64);
+		}
+	}
+
+	/// The block of a collation was announced, the given time after it was produced.
+	pub(crate) fn on_block_announced(&self, latency: Duration) {
+		if let Some(metrics) = &self.0 {
+			metrics.announcement_latency.observe(latency.as_secs_f64());
+		}
+	}
+}
+
+impl MetricsInner {
+	fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Self {
+			oversized_povs: register(
+				Counter::new(
+					"cumulus_collator_oversized_povs_total",
+					"Number of collations aborted because their PoV exceeded the maximum PoV size",
+				)?,
+				registry,
+			)?,
+			collations: register(
+				CounterVec::new(
+					Opts::new(
+						"cumulus_collator_collations_total",
+						"Number of finished collations, by result",
+					),
+					&["result"],
+				)?,
+				registry,
+			)?,
+			produce_candidate: register(
+				Histogram::with_opts(
+					HistogramOpts::new(
+						"cumulus_collator_produce_candidate_seconds",
+						"Time spent producing a candidate",
+					)
+					.buckets(vec![0.05, 0.1, 0.25, 0.5, 1.0, 1.5, 2.0, 3.0, 5.0, 10.0]),
+				)?,
+				registry,
+			)?,
+			pov_size: register(
+				HistogramVec::new(
+					HistogramOpts::new(
+						"cumulus_collator_pov_size_bytes",
+						"Size of the uncompressed PoV, by component",
+					)
+					// 1KiB to 8MiB.
+					.buckets(exponential_buckets(1024.0, 2.0, 14)?),
+					&["component"],
+				)?,
+				registry,
+			)?,
+			announcement_latency: register(
+				Histogram::with_opts(
+					HistogramOpts::new(
+						"cumulus_collator_announcement_latency_seconds",
+						"Time between producing a collation and announcing its block",
+					)
+					.buckets(vec![0.5, 1.0, 2.0, 3.0, 6.0, 12.0, 18.0, 24.0, 36.0, 60.0]),
+				)?,
+				registry,
+			)?,
+		})
+	}
+}
@@ -0,0 +1,236 @@
+// Copyright 2019-2021 SmallWorld Selendra (Kh).
+// This file is part of Cumulus.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Cumulus.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Persistence of collations whose blocks weren't announced yet.
+//!
+//! A produced block is only announced after a validator seconded its collation. If the collator
+//! restarts in between, the channel the statement would have been sent over is gone. To not
+//! orphan the block, the pending collations are stored in the aux store. On startup they are
+//! taken out of it with [`take_pending_collations`], before the collator is started, and
+//! [`restore_pending_announcements`] announces the blocks of those that got backed by the relay
+//! chain in the meantime.
+
+use cumulus_primitives_core::{relay_chain::Hash as PHash, ParaId};
+use cumulus_relay_chain_interface::RelayChainInterface;
+
+use sc_client_api::{backend::AuxStore, BlockBackend};
+use sp_consensus::BlockStatus;
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, Header as HeaderT},
+};
+
+use codec::{Codec, Decode, Encode};
+use futures::StreamExt;
+use parking_lot::Mutex;
+use std::sync::Arc;
+
+use crate::LOG_TARGET;
+
+/// The aux store key the pending collations are stored under.
+const PENDING_COLLATIONS_KEY: &[u8] = b"cumulus_collator_pending_collations";
+
+/// The maximum number of pending collations stored.
+///
+/// Collations whose statement channel was dropped are never removed explicitly, so the oldest
+/// collations are dropped once this number is exceeded.
+const MAX_PENDING_COLLATIONS: usize = 16;
+
+/// The number of new best relay chain blocks to wait for a restored collation to be backed.
+const RESTORED_COLLATION_TTL: usize = 10;
+
+/// A produced collation whose block wasn't announced yet.
+#[derive(Clone, Debug, PartialEq, Encode, Decode)]
+pub struct PendingCollation<Hash> {
+	/// The hash of the parachain block.
+	pub block_hash: Hash,
+	/// The hash of the head data of the parachain block.
+	pub head_data_hash: PHash,
+	/// The relay parent the collation was produced on.
+	pub relay_parent: PHash,
+	/// The hash of the PoV of the collation.
+	pub pov_hash: PHash,
+}
+
+fn load<Hash: Decode, AS: AuxStore>(aux_store: &AS) -> Vec<PendingCollation<Hash>> {
+	match aux_store.get_aux(PENDING_COLLATIONS_KEY) {
+		Ok(Some(encoded)) => Decode::decode(&mut &encoded[..]).unwrap_or_else(|e| {
+			tracing::warn!(
+				target: LOG_TARGET,
+				error = ?e,
+				"Failed to decode the pending collations, ignoring them.",
+			);
+			Vec::new()
+		}),
+		Ok(None) => Vec::new(),
+		Err(e) => {
+			tracing::error!(
+				target: LOG_TARGET,
+				error = ?e,
+				"Failed to load the pending collations.",
+			);
+			Vec::new()
+		},
+	}
+}
+
+fn write<Hash: Encode, AS: AuxStore>(aux_store: &AS, pending: &[PendingCollation<Hash>]) {
+	if let Err(e) = aux_store.insert_aux(&[(PENDING_COLLATIONS_KEY, &pending.encode()[..])], &[]) {
+		tracing::error!(
+			target: LOG_TARGET,
+			error = ?e,
+			"Failed to write the pending collations.",
+		);
+	}
+}
+
+/// The pending collations of the collator, mirrored to the aux store.
+pub(crate) struct PendingCollations<Block: BlockT, AS> {
+	aux_store: Arc<AS>,
+	pending: Mutex<Vec<PendingCollation<Block::Hash>>>,
+}
+
+impl<Block: BlockT, AS: AuxStore> PendingCollations<Block, AS> {
+	/// Create a new instance.
+	///
+	/// Collations stored before are overwritten by the first collation inserted, so they need to
+	/// be taken out with [`take_pending_collations`] first.
+	pub(crate) fn new(aux_store: Arc<AS>) -> Self {
+		Self { aux_store, pending: Mutex::new(Vec::new()) }
+	}
+
+	/// Add a collation whose block is waiting to be announced.
+	pub(crate) fn insert(&self, collation: PendingCollation<Block::Hash>) {
+		let mut pending = self.pending.lock();
+		pending.push(collation);
+
+		let excess = pending.len().saturating_sub(MAX_PENDING_COLLATIONS);
+		pending.drain(..excess);
+
+		write(&*self.aux_store, &pending);
+	}
+
+	/// Remove the collation of the given block, after its block was announced.
+	pub(crate) fn remove(&self, block_hash: Block::Hash) {
+		let mut pending = self.pending.lock();
+		let len = pending.len();
+		pending.retain(|c| c.block_hash != block_hash);
+
+		if pending.len() != len {
+			write(&*self.aux_store, &pending);
+		}
+	}
+}
+
+/// Take the collations that were pending when the collator was stopped out of the aux store.
+pub fn take_pending_collations<Hash: Codec, AS: AuxStore>(
+	aux_store: &AS,
+) -> Vec<PendingCollation<Hash>> {
+	let pending = load(aux_store);
+	if !pending.is_empty() {
+		write::<Hash, _>(aux_store, &[]);
+	}
+
+	pending
+}
+
+/// Announce the blocks of the collations that were pending when the collator was stopped.
+///
+/// As the statement of the validator seconding a collation is lost on restart, the block is
+/// announced without one as soon as the relay chain has a candidate with its head pending
+/// availability, which is accepted by the block announce validation. Collations that don't get
+/// backed within a few relay chain blocks are forgotten.
+pub async fn restore_pending_announcements<Block, BS, RCInterface>(
+	mut pending: Vec<PendingCollation<Block::Hash>>,
+	para_id: ParaId,
+	block_status: Arc<BS>,
+	relay_chain_interface: RCInterface,
+	announce_block: Arc<dyn Fn(Block::Hash, Option<Vec<u8>>) + Send + Sync>,
+) where
+	Block: BlockT,
+	BS: BlockBackend<Block>,
+	RCInterface: RelayChainInterface,
+{
+	pending.retain(|c| {
+		matches!(
+			block_status.block_status(&BlockId::Hash(c.block_hash)),
+			Ok(BlockStatus::InChainWithState)
+		)
+	});
+
+	tracing::debug!(target: LOG_TARGET, restored = pending.len(), "Restored pending collations.",);
+
+	if pending.is_empty() {
+		return
+	}
+
+	let mut new_best_heads = match relay_chain_interface.new_best_notification_stream().await {
+		Ok(stream) => stream.take(RESTORED_COLLATION_TTL),
+		Err(e) => {
+			tracing::error!(
+				target: LOG_TARGET,
+				error = ?e,
+				"Unable to retrieve the new best relay chain blocks stream.",
+			);
+			return
+		},
+	};
+
+	while let Some(header) = new_best_heads.next().await {
+		if pending.is_empty() {
+			break
+		}
+
+		let backed_head = match relay_chain_interface
+			.candidate_pending_availability(header.hash(), para_id)
+			.await
+		{
+			Ok(Some(receipt)) => receipt.descriptor.para_head,
+			Ok(None) => continue,
+			Err(e) => {
+				tracing::debug!(
+					target: LOG_TARGET,
+					error = ?e,
+					"Failed to get the candidate pending availability.",
+				);
+				continue
+			},
+		};
+
+		pending.retain(|c| {
+			if c.head_data_hash != backed_head {
+				return true
+			}
+
+			tracing::debug!(
+				target: LOG_TARGET,
+				block_hash = ?c.block_hash,
+				pov_hash = ?c.pov_hash,
+				"Announcing block of restored collation.",
+			);
+			announce_block(c.block_hash, None);
+			false
+		});
+	}
+
+	if !pending.is_empty() {
+		tracing::debug!(
+			target: LOG_TARGET,
+			not_backed = pending.len(),
+			"Restored pending collations were not backed.",
+		);
+	}
+}
@@ -0,0 +1,172 @@
+// Copyright 2019-2021 SmallWorld Selendra (Kh).
+// This file is part of Cumulus.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Cumulus.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Accounting of the size of storage proofs.
+//!
+//! The storage proof is usually the largest part of the PoV. To find out which storage accesses
+//! bloat it, [`storage_proof_breakdown`] attributes the size of every trie node in the proof to
+//! the storage prefix of the pallet the node belongs to.
+
+use sp_core::{hexdisplay::HexDisplay, Hasher};
+use sp_trie::{LayoutV1, StorageProof};
+use std::{collections::BTreeMap, fmt};
+use trie_db::{node::Node, TrieDB, TrieDBNodeIterator};
+
+/// The length of the storage prefix of a pallet, in bytes.
+pub const PALLET_PREFIX_LEN: usize = 16;
+
+/// The storage prefix of a pallet, `twox128(pallet_name)`.
+pub type PalletPrefix = [u8; PALLET_PREFIX_LEN];
+
+/// The size of a storage proof, broken down by the pallets its trie nodes belong to.
+///
+/// All sizes are in bytes of encoded trie nodes.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StorageProofBreakdown {
+	/// The size of the nodes below each pallet prefix.
+	pub pallets: BTreeMap<PalletPrefix, usize>,
+	/// The size of the nodes above the pallet prefixes, which are shared by all pallets.
+	pub shared: usize,
+	/// The size of the nodes that aren't reachable through the main trie, like the nodes of
+	/// child tries and values stored outside of their leaf.
+	pub other: usize,
+}
+
+impl StorageProofBreakdown {
+	/// The total size of the proof.
+	pub fn total(&self) -> usize {
+		self.pallets.values().sum::<usize>() + self.shared + self.other
+	}
+
+	/// The pallets ordered by the size of their nodes, the largest first.
+	pub fn largest_pallets(&self) -> Vec<(PalletPrefix, usize)> {
+		let mut pallets = self.pallets.iter().map(|(p, s)| (*p, *s)).collect::<Vec<_>>();
+		pallets.sort_by(|a, b| b.1.cmp(&a.1));
+		pallets
+	}
+}
+
+impl fmt::Display for StorageProofBreakdown {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "shared: {}b, other: {}b", self.shared, self.other)?;
+		for (prefix, size) in self.largest_pallets() {
+			write!(f, ", 0x{}: {}b", HexDisplay::from(&prefix), size)?;
+		}
+
+		Ok(())
+	}
+}
+
+/// Break down the size of a storage proof recorded against the state with the given root.
+///
+/// Trie nodes missing from the proof weren't accessed and are skipped. If the root itself is
+/// missing, the whole proof is accounted as [`StorageProofBreakdown::other`].
+pub fn storage_proof_breakdown<H: Hasher>(
+	proof: &StorageProof,
+	state_root: H::Out,
+) -> StorageProofBreakdown {
+	let total = proof.iter_nodes().map(|n| n.len()).sum::<usize>();
+	let mut breakdown = StorageProofBreakdown::default();
+
+	let db = proof.clone().into_memory_db::<H>();
+	let nodes = TrieDB::<LayoutV1<H>>::new(&db, &state_root)
+		.and_then(|trie| TrieDBNodeIterator::new(&trie).map(|nodes| nodes.collect::<Vec<_>>()));
+	let nodes = match nodes {
+		Ok(nodes) => nodes,
+		Err(_) => {
+			breakdown.other = total;
+			return breakdown
+		},
+	};
+
+	for (mut key, hash, node) in nodes.into_iter().filter_map(Result::ok) {
+		// Inline nodes are part of the encoding of their parent.
+		if hash.is_none() {
+			continue
+		}
+
+		match node.node() {
+			Node::Leaf(partial, _) |
+			Node::Extension(partial, _) |
+			Node::NibbledBranch(partial, _, _) => key.append_partial(partial.right()),
+			Node::Empty | Node::Branch(_, _) => {},
+		}
+
+		let size = node.data().len();
+		if key.len() >= PALLET_PREFIX_LEN * 2 {
+			let mut prefix = PalletPrefix::default();
+			prefix.copy_from_slice(&key.inner()[..PALLET_PREFIX_LEN]);
+			*breakdown.pallets.entry(prefix).or_default() += size;
+		} else {
+			breakdown.shared += size;
+		}
+	}
+
+	let attributed = breakdown.pallets.values().sum::<usize>() + breakdown.shared;
+	breakdown.other = total.saturating_sub(attributed);
+	breakdown
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_runtime::traits::BlakeTwo256;
+	use sp_trie::{trie_types::TrieDBMutV1, MemoryDB, TrieMut};
+
+	fn key(prefix: u8, item: u8) -> Vec<u8> {
+		let mut key = vec![prefix; PALLET_PREFIX_LEN];
+		key.push(item);
+		key
+	}
+
+	#[test]
+	fn proof_size_is_attributed_to_pallets() {
+		let mut db = MemoryDB::<BlakeTwo256>::default();
+		let mut root = Default::default();
+		{
+			let mut trie = TrieDBMutV1::<BlakeTwo256>::new(&mut db, &mut root);
+			for item in 0..32 {
+				trie.insert(&key(1, item), &[item; 4]).unwrap();
+			}
+			trie.insert(&key(2, 0), &[0; 4]).unwrap();
+			trie.insert(&key(2, 1), &[1; 4]).unwrap();
+		}
+
+		let proof = StorageProof::new(db.drain().into_iter().map(|(_, (node, _))| node));
+		let total = proof.iter_nodes().map(|n| n.len()).sum::<usize>();
+		let breakdown = storage_proof_breakdown::<BlakeTwo256>(&proof, root);
+
+		assert_eq!(breakdown.total(), total);
+		assert_eq!(breakdown.other, 0);
+		assert!(breakdown.shared > 0);
+
+		let largest = breakdown.largest_pallets();
+		assert_eq!(largest.len(), 2);
+		assert_eq!(largest[0].0, [1; PALLET_PREFIX_LEN]);
+		assert_eq!(largest[1].0, [2; PALLET_PREFIX_LEN]);
+		assert!(largest[0].1 > largest[1].1);
+	}
+
+	#[test]
+	fn proof_without_root_is_not_attributed() {
+		let proof = StorageProof::new(vec![vec![1, 2, 3]]);
+		let breakdown = storage_proof_breakdown::<BlakeTwo256>(&proof, Default::default());
+
+		assert!(breakdown.pallets.is_empty());
+		assert_eq!(breakdown.shared, 0);
+		assert_eq!(breakdown.other, 3);
+	}
+}
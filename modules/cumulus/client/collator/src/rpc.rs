@@ -0,0 +1,64 @@
+// Copyright 2019-2021 SmallWorld Selendra (Kh).
+// This file is part of Cumulus.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Cumulus.  If not, see <http://www.gnu.org/licenses/>.
+
+//! RPC to rotate the key of a running collator.
+
+use crate::CollatorKeyRotation;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result as RpcResult};
+use jsonrpc_derive::rpc;
+use sc_rpc_api::DenyUnsafe;
+use selendra_primitives::v1::{CollatorId, CollatorPair};
+use sp_core::Pair;
+
+/// Collator key RPC methods.
+#[rpc]
+pub trait CollatorKeyApi {
+	/// Generate a new collator key and switch to it at the next relay chain session.
+	///
+	/// Returns the public key of the new collator key. Like the collator key generated at
+	/// startup, the new key isn't persisted. This is an unsafe method.
+	#[rpc(name = "collator_rotateKey")]
+	fn rotate_key(&self) -> RpcResult<CollatorId>;
+}
+
+/// Implements the [`CollatorKeyApi`] by sending the new keys to the collator.
+pub struct CollatorKey {
+	rotation: CollatorKeyRotation,
+	deny_unsafe: DenyUnsafe,
+}
+
+impl CollatorKey {
+	/// Create a new collator key RPC handler rotating the key through the given handle.
+	pub fn new(rotation: CollatorKeyRotation, deny_unsafe: DenyUnsafe) -> Self {
+		CollatorKey { rotation, deny_unsafe }
+	}
+}
+
+impl CollatorKeyApi for CollatorKey {
+	fn rotate_key(&self) -> RpcResult<CollatorId> {
+		self.deny_unsafe.check_if_safe()?;
+
+		let key = CollatorPair::generate().0;
+		let public = key.public();
+		self.rotation.rotate(key).map_err(|e| RpcError {
+			code: ErrorCode::InternalError,
+			message: e.to_string(),
+			data: None,
+		})?;
+
+		Ok(public)
+	}
+}
@@ -24,7 +24,7 @@ use sp_consensus::block_validation::{
 	BlockAnnounceValidator as BlockAnnounceValidatorT, Validation,
 };
 use sp_core::traits::SpawnNamed;
-use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT, NumberFor};
 
 use cumulus_relay_chain_interface::RelayChainInterface;
 use selendra_node_primitives::{CollationSecondedSignal, Statement};
@@ -35,9 +35,14 @@ use selendra_primitives::v1::{
 };
 
 use codec::{Decode, DecodeAll, Encode};
-use futures::{channel::oneshot, future::FutureExt, Future};
+use futures::{
+	channel::oneshot,
+	future::{self, Either, FutureExt},
+	Future,
+};
+use parking_lot::Mutex;
 
-use std::{convert::TryFrom, fmt, marker::PhantomData, pin::Pin, sync::Arc};
+use std::{collections::HashMap, convert::TryFrom, fmt, marker::PhantomData, pin::Pin, sync::Arc};
 
 #[cfg(test)]
 mod tests;
@@ -382,9 +387,14 @@ where
 ///
 /// This object will spawn a new task every time the method `wait_to_announce` is called and cancel
 /// the previous task running.
+///
+/// Announcements of blocks that are reorged out, see [`WaitToAnnounce::note_new_best`], are
+/// cancelled.
 pub struct WaitToAnnounce<Block: BlockT> {
 	spawner: Arc<dyn SpawnNamed + Send + Sync>,
 	announce_block: Arc<dyn Fn(Block::Hash, Option<Vec<u8>>) + Send + Sync>,
+	/// The blocks waiting to be announced, with the sender to cancel their announcement.
+	pending: Arc<Mutex<HashMap<Block::Hash, (NumberFor<Block>, oneshot::Sender<()>)>>>,
 }
 
 impl<Block: BlockT> WaitToAnnounce<Block> {
@@ -393,7 +403,7 @@ impl<Block: BlockT> WaitToAnnounce<Block> {
 		spawner: Arc<dyn SpawnNamed + Send + Sync>,
 		announce_block: Arc<dyn Fn(Block::Hash, Option<Vec<u8>>) + Send + Sync>,
 	) -> WaitToAnnounce<Block> {
-		WaitToAnnounce { spawner, announce_block }
+		WaitToAnnounce { spawner, announce_block, pending: Default::default() }
 	}
 
 	/// Wait for a candidate message for the block, then announce the block. The candidate
@@ -401,9 +411,16 @@ impl<Block: BlockT> WaitToAnnounce<Block> {
 	pub fn wait_to_announce(
 		&mut self,
 		block_hash: <Block as BlockT>::Hash,
-		signed_stmt_recv: oneshot::Receiver<CollationSecondedSignal>,
+		block_number: NumberFor<Block>,
+		signed_stmt_recv: impl Future<Output = Result<CollationSecondedSignal, oneshot::Canceled>>
+			+ Send
+			+ 'static,
 	) {
 		let announce_block = self.announce_block.clone();
+		let pending = self.pending.clone();
+
+		let (cancel_sender, cancel_recv) = oneshot::channel();
+		pending.lock().insert(block_hash, (block_number, cancel_sender));
 
 		self.spawner.spawn(
 			"cumulus-wait-to-announce",
@@ -414,7 +431,16 @@ impl<Block: BlockT> WaitToAnnounce<Block> {
 					"waiting for announce block in a background task...",
 				);
 
-				wait_to_announce::<Block>(block_hash, announce_block, signed_stmt_recv).await;
+				match future::select(signed_stmt_recv.boxed(), cancel_recv).await {
+					Either::Left((signed_stmt, _)) =>
+						wait_to_announce::<Block>(block_hash, announce_block, signed_stmt),
+					Either::Right(_) => tracing::debug!(
+						target: "cumulus-network",
+						block = ?block_hash,
+						"Wait to announce stopped, because the block was reorged out.",
+					),
+				}
+				pending.lock().remove(&block_hash);
 
 				tracing::debug!(
 					target: "cumulus-network",
@@ -424,14 +450,25 @@ impl<Block: BlockT> WaitToAnnounce<Block> {
 			.boxed(),
 		);
 	}
+
+	/// Note a new best block, cancelling the announcements of the blocks it reorged out.
+	///
+	/// A block still waiting for its candidate message is reorged out by any other best block of
+	/// the same or a greater number, as the block wasn't backed by the relay chain yet.
+	pub fn note_new_best(&self, hash: Block::Hash, number: NumberFor<Block>) {
+		// Dropping the sender cancels the announcement.
+		self.pending.lock().retain(|pending_hash, (pending_number, _)| {
+			*pending_hash == hash || *pending_number > number
+		});
+	}
 }
 
-async fn wait_to_announce<Block: BlockT>(
+fn wait_to_announce<Block: BlockT>(
 	block_hash: <Block as BlockT>::Hash,
 	announce_block: Arc<dyn Fn(Block::Hash, Option<Vec<u8>>) + Send + Sync>,
-	signed_stmt_recv: oneshot::Receiver<CollationSecondedSignal>,
+	signed_stmt: Result<CollationSecondedSignal, oneshot::Canceled>,
 ) {
-	let signal = match signed_stmt_recv.await {
+	let signal = match signed_stmt {
 		Ok(s) => s,
 		Err(_) => {
 			tracing::debug!(
@@ -21,6 +21,7 @@ use cumulus_relay_chain_interface::{RelayChainError, RelayChainResult};
 use cumulus_test_service::runtime::{Block, Hash, Header};
 use futures::{executor::block_on, poll, task::Poll, FutureExt, Stream, StreamExt};
 use parking_lot::Mutex;
+use sc_client_api::{Backend, BlockchainEvents};
 use selendra_node_primitives::{SignedFullStatement, Statement};
 use selendra_primitives::v1::{
 	CandidateCommitments, CandidateDescriptor, CollatorPair, CommittedCandidateReceipt,
@@ -33,7 +34,6 @@ use selendra_test_client::{
 	Client as PClient, ClientBlockImportExt, DefaultTestClientBuilderExt, FullBackend as PBackend,
 	InitSelendraBlockBuilder, TestClientBuilder, TestClientBuilderExt,
 };
-use sc_client_api::{Backend, BlockchainEvents};
 use sp_blockchain::HeaderBackend;
 use sp_consensus::BlockOrigin;
 use sp_core::{Pair, H256};
@@ -557,6 +557,31 @@ fn block_announced_without_statement_and_block_only_backed() {
 	});
 }
 
+#[test]
+fn announcement_of_reorged_out_block_is_cancelled() {
+	let announced = Arc::new(Mutex::new(Vec::new()));
+	let announced2 = announced.clone();
+	let mut wait_to_announce = WaitToAnnounce::<Block>::new(
+		Arc::new(sp_core::testing::TaskExecutor::new()),
+		Arc::new(move |hash, _| announced2.lock().push(hash)),
+	);
+
+	let reorged_out = Hash::repeat_byte(1);
+	let (mut reorged_out_sender, reorged_out_recv) = oneshot::channel();
+	wait_to_announce.wait_to_announce(reorged_out, 1, reorged_out_recv);
+
+	let child = Hash::repeat_byte(2);
+	let (child_sender, child_recv) = oneshot::channel();
+	wait_to_announce.wait_to_announce(child, 2, child_recv);
+
+	// Another block at the height of the first one became the best block.
+	wait_to_announce.note_new_best(Hash::repeat_byte(3), 1);
+
+	block_on(reorged_out_sender.cancellation());
+	assert!(!child_sender.is_canceled());
+	assert!(announced.lock().is_empty());
+}
+
 #[derive(Default)]
 struct ApiData {
 	validators: Vec<ValidatorId>,
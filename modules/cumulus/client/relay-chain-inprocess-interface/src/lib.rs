@@ -347,6 +347,7 @@ fn build_selendra_full_node(
 			None,
 			telemetry_worker_handle,
 			true,
+			Default::default(),
 			selendra_service::RealOverseerGen,
 		)?;
 
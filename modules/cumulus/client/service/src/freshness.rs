@@ -0,0 +1,151 @@
+// Copyright 2020-2021 SmallWorld Selendra (Kh).
+// This file is part of Cumulus.
+
+// Cumulus is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Cumulus is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Cumulus.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Delaying the collator startup until the relay chain is fresh.
+//!
+//! A collator started while the relay chain node is still syncing produces candidates on stale
+//! relay parents, which are rejected by the validators. How fresh the relay chain has to be, and
+//! how long to wait for it at most, is configured in the [`CollatorOptions`].
+
+use cumulus_client_cli::CollatorOptions;
+use cumulus_relay_chain_interface::RelayChainInterface;
+use futures::{FutureExt, StreamExt};
+use futures_timer::Delay;
+use selendra_primitives::v1::BlockNumber;
+use substrate_prometheus_endpoint::{register, Gauge, PrometheusError, Registry, U64};
+
+const LOG_TARGET: &str = "cumulus-collator";
+
+/// Whether the relay chain was fresh enough to start the collator.
+pub(crate) struct ReadinessMetric(Option<Gauge<U64>>);
+
+impl ReadinessMetric {
+	/// Register the metric in the given registry, if any.
+	pub(crate) fn register(registry: Option<&Registry>) -> Result<Self, PrometheusError> {
+		registry
+			.map(|registry| {
+				register(
+					Gauge::new(
+						"cumulus_collator_relay_chain_ready",
+						"Whether the relay chain was synced and finalized enough to start the collator",
+					)?,
+					registry,
+				)
+			})
+			.transpose()
+			.map(Self)
+	}
+
+	fn set_ready(&self) {
+		if let Some(gauge) = &self.0 {
+			gauge.set(1);
+		}
+	}
+}
+
+/// Wait until the relay chain node finished its major sync and the best relay chain block is at
+/// most [`CollatorOptions::relay_chain_max_finality_lag`] blocks ahead of the finalized one.
+///
+/// Gives up after [`CollatorOptions::relay_chain_freshness_timeout`], so a relay chain whose
+/// finality is stalled doesn't keep the collator from ever starting. Doesn't wait if the relay
+/// chain notification streams can't be retrieved.
+pub(crate) async fn wait_for_fresh_relay_chain<RCInterface: RelayChainInterface>(
+	relay_chain_interface: &RCInterface,
+	collator_options: &CollatorOptions,
+	readiness: ReadinessMetric,
+) {
+	let timeout = collator_options.relay_chain_freshness_timeout;
+	let fresh = wait_for_freshness(
+		relay_chain_interface,
+		collator_options.relay_chain_max_finality_lag,
+		&readiness,
+	);
+
+	futures::select! {
+		() = fresh.fuse() => {},
+		() = Delay::new(timeout).fuse() => tracing::warn!(
+			target: LOG_TARGET,
+			?timeout,
+			"Relay chain is still not fresh, starting the collator anyway.",
+		),
+	}
+}
+
+async fn wait_for_freshness<RCInterface: RelayChainInterface>(
+	relay_chain_interface: &RCInterface,
+	max_finality_lag: BlockNumber,
+	readiness: &ReadinessMetric,
+) {
+	let streams = futures::try_join!(
+		relay_chain_interface.new_best_notification_stream(),
+		relay_chain_interface.finality_notification_stream(),
+	);
+	let (mut new_best_heads, mut finalized_heads) = match streams {
+		Ok((new_best_heads, finalized_heads)) => (new_best_heads.fuse(), finalized_heads.fuse()),
+		Err(e) => {
+			tracing::error!(
+				target: LOG_TARGET,
+				error = ?e,
+				"Unable to retrieve the relay chain notification streams, not waiting for the relay chain to be fresh.",
+			);
+			readiness.set_ready();
+			return
+		},
+	};
+
+	let mut best_number = None;
+	let mut finalized_number = None;
+
+	loop {
+		if let (Some(best), Some(finalized)) = (best_number, finalized_number) {
+			let lag: BlockNumber = best.saturating_sub(finalized);
+			let syncing = relay_chain_interface.is_major_syncing().await.unwrap_or_else(|e| {
+				tracing::debug!(
+					target: LOG_TARGET,
+					error = ?e,
+					"Failed to check whether the relay chain is syncing.",
+				);
+				true
+			});
+
+			if !syncing && lag <= max_finality_lag {
+				break
+			}
+
+			tracing::debug!(
+				target: LOG_TARGET,
+				best,
+				finalized,
+				syncing,
+				"Waiting for the relay chain to be fresh before starting the collator.",
+			);
+		}
+
+		futures::select! {
+			header = new_best_heads.next() => match header {
+				Some(header) => best_number = Some(header.number),
+				None => return,
+			},
+			header = finalized_heads.next() => match header {
+				Some(header) => finalized_number = Some(header.number),
+				None => return,
+			},
+		}
+	}
+
+	tracing::info!(target: LOG_TARGET, "Relay chain is fresh, starting the collator.");
+	readiness.set_ready();
+}
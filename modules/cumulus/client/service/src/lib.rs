@@ -22,15 +22,15 @@ use cumulus_client_cli::CollatorOptions;
 use cumulus_client_consensus_common::ParachainConsensus;
 use cumulus_primitives_core::{CollectCollationInfo, ParaId};
 use cumulus_relay_chain_interface::RelayChainInterface;
-use selendra_primitives::v1::CollatorPair;
 use sc_client_api::{
-	Backend as BackendT, BlockBackend, BlockchainEvents, Finalizer, UsageProvider,
+	AuxStore, Backend as BackendT, BlockBackend, BlockchainEvents, Finalizer, UsageProvider,
 };
 use sc_consensus::{
 	import_queue::{ImportQueue, IncomingBlock, Link, Origin},
 	BlockImport,
 };
 use sc_service::{Configuration, TaskManager};
+use selendra_primitives::v1::CollatorPair;
 use sp_api::ProvideRuntimeApi;
 use sp_blockchain::HeaderBackend;
 use sp_consensus::BlockOrigin;
@@ -40,7 +40,9 @@ use sp_runtime::{
 	Justifications,
 };
 use std::{sync::Arc, time::Duration};
+use substrate_prometheus_endpoint::Registry;
 
+mod freshness;
 pub mod genesis;
 
 /// Parameters given to [`start_collator`].
@@ -55,7 +57,15 @@ pub struct StartCollatorParams<'a, Block: BlockT, BS, Client, RCInterface, Spawn
 	pub parachain_consensus: Box<dyn ParachainConsensus<Block>>,
 	pub import_queue: IQ,
 	pub collator_key: CollatorPair,
+	/// New collator keys to switch to, see [`cumulus_client_collator::collator_key_rotation`].
+	pub collator_key_rotations: Option<cumulus_client_collator::CollatorKeyRotations>,
+	/// Skip collations while the parachain is idle, see
+	/// [`cumulus_client_collator::PoolAndMessagesIdleCheck`].
+	pub idle_collation: Option<cumulus_client_collator::IdleCollation<Block>>,
 	pub relay_chain_slot_duration: Duration,
+	pub prometheus_registry: Option<&'a Registry>,
+	/// When the relay chain is fresh enough to start collating.
+	pub collator_options: CollatorOptions,
 }
 
 /// Start a collator node for a parachain.
@@ -75,12 +85,16 @@ pub async fn start_collator<'a, Block, BS, Client, Backend, RCInterface, Spawner
 		parachain_consensus,
 		import_queue,
 		collator_key,
+		collator_key_rotations,
+		idle_collation,
 		relay_chain_slot_duration,
+		prometheus_registry,
+		collator_options,
 	}: StartCollatorParams<'a, Block, BS, Client, RCInterface, Spawner, IQ>,
 ) -> sc_service::error::Result<()>
 where
 	Block: BlockT,
-	BS: BlockBackend<Block> + Send + Sync + 'static,
+	BS: BlockBackend<Block> + BlockchainEvents<Block> + AuxStore + Send + Sync + 'static,
 	Client: Finalizer<Block, Backend>
 		+ UsageProvider<Block>
 		+ HeaderBackend<Block>
@@ -128,7 +142,21 @@ where
 		.spawn_essential_handle()
 		.spawn("cumulus-pov-recovery", None, pov_recovery.run());
 
-	cumulus_client_collator::start_collator(cumulus_client_collator::StartCollatorParams {
+	// Taken before the collator starts, which overwrites the stored pending collations.
+	let pending_collations = cumulus_client_collator::take_pending_collations(&*block_status);
+	task_manager.spawn_handle().spawn(
+		"cumulus-restore-announcements",
+		None,
+		cumulus_client_collator::restore_pending_announcements(
+			pending_collations,
+			para_id,
+			block_status.clone(),
+			relay_chain_interface.clone(),
+			announce_block.clone(),
+		),
+	);
+
+	let collator_params = cumulus_client_collator::StartCollatorParams {
 		runtime_api: client.clone(),
 		block_status,
 		announce_block,
@@ -136,9 +164,20 @@ where
 		spawner,
 		para_id,
 		key: collator_key,
+		key_rotations: collator_key_rotations,
 		parachain_consensus,
-	})
-	.await;
+		idle_collation,
+		prometheus_registry: prometheus_registry.cloned(),
+	};
+	let readiness = freshness::ReadinessMetric::register(prometheus_registry)?;
+
+	// Collations built on stale relay parents are rejected, so only start collating once the
+	// relay chain is fresh.
+	task_manager.spawn_handle().spawn("cumulus-collator-startup", None, async move {
+		freshness::wait_for_fresh_relay_chain(&relay_chain_interface, &collator_options, readiness)
+			.await;
+		cumulus_client_collator::start_collator(collator_params).await;
+	});
 
 	Ok(())
 }
@@ -29,6 +29,7 @@ use std::{
 use url::Url;
 
 use cumulus_client_cli::CollatorOptions;
+use cumulus_client_collator::rpc::{CollatorKey, CollatorKeyApi};
 use cumulus_client_consensus_common::{ParachainCandidate, ParachainConsensus};
 use cumulus_client_network::BlockAnnounceValidator;
 use cumulus_client_service::{
@@ -272,10 +273,27 @@ where
 			warp_sync: None,
 		})?;
 
+	// Collators can rotate their key through the `collator_rotateKey` RPC.
+	let (collator_key_rotation, collator_key_rotations) = if collator_key.is_some() {
+		let (rotation, rotations) = cumulus_client_collator::collator_key_rotation();
+		(Some(rotation), Some(rotations))
+	} else {
+		(None, None)
+	};
+
 	let rpc_extensions_builder = {
 		let client = client.clone();
 
-		Box::new(move |_, _| rpc_ext_builder(client.clone()))
+		Box::new(move |deny_unsafe, _| {
+			let mut io = rpc_ext_builder(client.clone())?;
+			if let Some(rotation) = &collator_key_rotation {
+				io.extend_with(CollatorKeyApi::to_delegate(CollatorKey::new(
+					rotation.clone(),
+					deny_unsafe,
+				)));
+			}
+			Ok(io)
+		})
 	};
 
 	let rpc_handlers = sc_service::spawn_tasks(sc_service::SpawnTasksParams {
@@ -353,8 +371,12 @@ where
 			parachain_consensus,
 			relay_chain_interface,
 			collator_key,
+			collator_key_rotations,
+			idle_collation: None,
 			import_queue,
 			relay_chain_slot_duration: Duration::from_secs(6),
+			prometheus_registry: prometheus_registry.as_ref(),
+			collator_options,
 		};
 
 		start_collator(params).await?;
@@ -568,8 +590,10 @@ impl TestNodeBuilder {
 			false,
 		);
 
-		let collator_options =
-			CollatorOptions { relay_chain_rpc_url: self.relay_chain_full_node_url };
+		let collator_options = CollatorOptions {
+			relay_chain_rpc_url: self.relay_chain_full_node_url,
+			..Default::default()
+		};
 
 		relay_chain_config.network.node_name =
 			format!("{} (relay chain)", relay_chain_config.network.node_name);
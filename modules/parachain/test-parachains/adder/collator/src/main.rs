@@ -69,6 +69,7 @@ fn main() -> Result<()> {
 							None,
 							None,
 							false,
+							Default::default(),
 							selendra_service::RealOverseerGen,
 						)
 						.map_err(|e| e.to_string())?;
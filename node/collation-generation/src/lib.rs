@@ -18,26 +18,38 @@
 
 #![deny(missing_docs)]
 
-use futures::{channel::mpsc, future::FutureExt, join, select, sink::SinkExt, stream::StreamExt};
+use futures::{
+	channel::{mpsc, oneshot},
+	future::FutureExt,
+	join, select,
+	sink::SinkExt,
+	stream::StreamExt,
+};
 use parity_scale_codec::Encode;
 use selendra_node_primitives::{AvailableData, CollationGenerationConfig, PoV};
 use selendra_node_subsystem::{
-	messages::{AllMessages, CollationGenerationMessage, CollatorProtocolMessage},
+	messages::{
+		AllMessages, CollationGenerationMessage, CollatorProtocolMessage, NewCollatorKey,
+		RuntimeApiMessage, RuntimeApiRequest,
+	},
 	overseer, ActiveLeavesUpdate, FromOverseer, OverseerSignal, SpawnedSubsystem, SubsystemContext,
 	SubsystemError, SubsystemResult, SubsystemSender,
 };
 use selendra_node_subsystem_util::{
 	metrics::{self, prometheus},
-	request_availability_cores, request_persisted_validation_data, request_validation_code,
-	request_validation_code_hash, request_validators,
+	request_availability_cores, request_persisted_validation_data, request_session_index_for_child,
+	request_validation_code, request_validation_code_hash, request_validators,
 };
-use selendra_primitives::v1::{
-	collator_signature_payload, CandidateCommitments, CandidateDescriptor, CandidateReceipt,
-	CoreState, Hash, Id as ParaId, OccupiedCoreAssumption, PersistedValidationData,
-	ValidationCodeHash,
+use selendra_primitives::{
+	v1::{
+		collator_signature_payload, BlockNumber, CandidateCommitments, CandidateDescriptor,
+		CandidateReceipt, CoreState, Hash, Id as ParaId, OccupiedCoreAssumption,
+		PersistedValidationData, SessionIndex, UpgradeRestriction, ValidationCodeHash,
+	},
+	v2::Constraints,
 };
 use sp_core::crypto::Pair;
-use std::sync::Arc;
+use std::{fmt, sync::Arc};
 
 mod error;
 
@@ -49,13 +61,22 @@ const LOG_TARGET: &'static str = "parachain::collation-generation";
 /// Collation Generation Subsystem
 pub struct CollationGenerationSubsystem {
 	config: Option<Arc<CollationGenerationConfig>>,
+	/// A config with a rotated collator key, waiting for the next session.
+	pending_config: Option<PendingConfig>,
 	metrics: Metrics,
 }
 
+/// A config replacing the current one at the next session boundary.
+struct PendingConfig {
+	config: Arc<CollationGenerationConfig>,
+	/// The session of the first relay chain block seen after the config was received.
+	session_index: Option<SessionIndex>,
+}
+
 impl CollationGenerationSubsystem {
 	/// Create a new instance of the `CollationGenerationSubsystem`.
 	pub fn new(metrics: Metrics) -> Self {
-		Self { config: None, metrics }
+		Self { config: None, pending_config: None, metrics }
 	}
 
 	/// Run this subsystem
@@ -117,6 +138,10 @@ impl CollationGenerationSubsystem {
 				activated,
 				..
 			}))) => {
+				if let Some(leaf) = activated.iter().next() {
+					self.apply_pending_config(leaf.hash, ctx).await;
+				}
+
 				// follow the procedure from the guide
 				if let Some(config) = &self.config {
 					let metrics = self.metrics.clone();
@@ -139,10 +164,24 @@ impl CollationGenerationSubsystem {
 			Ok(FromOverseer::Communication {
 				msg: CollationGenerationMessage::Initialize(config),
 			}) => {
-				if self.config.is_some() {
-					tracing::error!(target: LOG_TARGET, "double initialization");
-				} else {
-					self.config = Some(Arc::new(config));
+				match &self.config {
+					None => self.config = Some(Arc::new(config)),
+					Some(current) if current.para_id != config.para_id => {
+						tracing::error!(
+							target: LOG_TARGET,
+							para_id = %config.para_id,
+							collating_on = %current.para_id,
+							"double initialization for a different para",
+						);
+					},
+					Some(_) => {
+						tracing::info!(
+							target: LOG_TARGET,
+							"Rotating the collator key at the next session boundary",
+						);
+						self.pending_config =
+							Some(PendingConfig { config: Arc::new(config), session_index: None });
+					},
 				}
 				false
 			},
@@ -158,6 +197,48 @@ impl CollationGenerationSubsystem {
 			},
 		}
 	}
+
+	// replace the config by the pending one, if the given leaf is the first one of a new session.
+	async fn apply_pending_config<Context>(&mut self, leaf: Hash, ctx: &mut Context)
+	where
+		Context: SubsystemContext<Message = CollationGenerationMessage>,
+		Context: overseer::SubsystemContext<Message = CollationGenerationMessage>,
+	{
+		let pending = match &mut self.pending_config {
+			Some(pending) => pending,
+			None => return,
+		};
+
+		let session_index = match request_session_index_for_child(leaf, ctx.sender()).await.await {
+			Ok(Ok(session_index)) => session_index,
+			result => {
+				tracing::warn!(target: LOG_TARGET, ?result, "failed to fetch the session index");
+				return
+			},
+		};
+
+		match pending.session_index {
+			Some(pending_since) if session_index > pending_since => {},
+			Some(_) => return,
+			None => {
+				pending.session_index = Some(session_index);
+				return
+			},
+		}
+
+		let config = match self.pending_config.take() {
+			Some(pending) => pending.config,
+			None => return,
+		};
+
+		// the collator protocol has to declare the new key before collations signed with it are
+		// distributed.
+		ctx.send_message(CollatorProtocolMessage::RotateKey(NewCollatorKey(config.key.clone())))
+			.await;
+		self.config = Some(config);
+
+		tracing::info!(target: LOG_TARGET, session_index, "Rotated the collator key");
+	}
 }
 
 impl<Context> overseer::Subsystem<Context, SubsystemError> for CollationGenerationSubsystem
@@ -306,6 +387,28 @@ async fn handle_new_activations<Context: SubsystemContext>(
 							},
 						};
 
+					let commitments = CandidateCommitments {
+						upward_messages: collation.upward_messages,
+						horizontal_messages: collation.horizontal_messages,
+						new_validation_code: collation.new_validation_code,
+						head_data: collation.head_data,
+						processed_downward_messages: collation.processed_downward_messages,
+						hrmp_watermark: collation.hrmp_watermark,
+					};
+
+					// Validators would reject a candidate whose outputs exceed the limits of the
+					// relay chain, so don't bother distributing it.
+					if !check_outputs(
+						relay_parent,
+						scheduled_core.para_id,
+						&commitments,
+						&mut task_sender,
+					)
+					.await
+					{
+						return
+					}
+
 					// Apply compression to the block data.
 					let pov = {
 						let pov = collation.proof_of_validity.into_compressed();
@@ -355,15 +458,6 @@ async fn handle_new_activations<Context: SubsystemContext>(
 							},
 						};
 
-					let commitments = CandidateCommitments {
-						upward_messages: collation.upward_messages,
-						horizontal_messages: collation.horizontal_messages,
-						new_validation_code: collation.new_validation_code,
-						head_data: collation.head_data,
-						processed_downward_messages: collation.processed_downward_messages,
-						hrmp_watermark: collation.hrmp_watermark,
-					};
-
 					let ccr = CandidateReceipt {
 						commitments_hash: commitments.hash(),
 						descriptor: CandidateDescriptor {
@@ -410,6 +504,324 @@ async fn handle_new_activations<Context: SubsystemContext>(
 	Ok(())
 }
 
+/// Check the outputs of a collation against the acceptance criteria of the relay chain at
+/// `relay_parent`, i.e. the limits of the active host configuration on the size of the head data
+/// and the new code, as well as on the number and size of upward and horizontal messages and the
+/// capacity of the queues and channels they are sent to.
+///
+/// Returns `false` only if the runtime rejected the outputs. If the check itself fails, the
+/// collation is given the benefit of the doubt.
+async fn check_outputs(
+	relay_parent: Hash,
+	para_id: ParaId,
+	commitments: &CandidateCommitments,
+	sender: &mut mpsc::Sender<AllMessages>,
+) -> bool {
+	let (tx, rx) = oneshot::channel();
+	let request = AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+		relay_parent,
+		RuntimeApiRequest::CheckValidationOutputs(para_id, commitments.clone(), tx),
+	));
+	if let Err(err) = sender.send(request).await {
+		tracing::debug!(
+			target: LOG_TARGET,
+			%para_id,
+			err = ?err,
+			"failed to request the check of collation outputs",
+		);
+		return true
+	}
+
+	match rx.await {
+		Ok(Ok(true)) => true,
+		Ok(Ok(false)) => {
+			match fetch_constraints(relay_parent, para_id, sender)
+				.await
+				.and_then(|constraints| find_violation(commitments, &constraints))
+			{
+				Some(violation) => tracing::warn!(
+					target: LOG_TARGET,
+					%para_id,
+					?relay_parent,
+					%violation,
+					"collation outputs exceed the limits of the relay chain, dropping the collation",
+				),
+				None => log_rejected_outputs(relay_parent, para_id, commitments),
+			}
+			false
+		},
+		Ok(Err(err)) => {
+			tracing::debug!(
+				target: LOG_TARGET,
+				%para_id,
+				err = ?err,
+				"failed to check collation outputs",
+			);
+			true
+		},
+		Err(err) => {
+			tracing::debug!(
+				target: LOG_TARGET,
+				%para_id,
+				err = ?err,
+				"failed to check collation outputs",
+			);
+			true
+		},
+	}
+}
+
+/// Fetch the constraints the relay chain at `relay_parent` puts on the next candidate of `para_id`.
+async fn fetch_constraints(
+	relay_parent: Hash,
+	para_id: ParaId,
+	sender: &mut mpsc::Sender<AllMessages>,
+) -> Option<Constraints> {
+	let (tx, rx) = oneshot::channel();
+	let request = AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+		relay_parent,
+		RuntimeApiRequest::StagingParaBackingState(para_id, tx),
+	));
+	sender.send(request).await.ok()?;
+
+	match rx.await {
+		Ok(Ok(state)) => state.map(|state| state.constraints),
+		Ok(Err(err)) => {
+			tracing::debug!(
+				target: LOG_TARGET,
+				%para_id,
+				err = ?err,
+				"failed to fetch the constraints of the para",
+			);
+			None
+		},
+		Err(_) => None,
+	}
+}
+
+/// Log the outputs rejected by the relay chain when the violated limit couldn't be determined,
+/// e.g. because the runtime doesn't support the backing state API yet.
+fn log_rejected_outputs(relay_parent: Hash, para_id: ParaId, commitments: &CandidateCommitments) {
+	let mut recipients = commitments
+		.horizontal_messages
+		.iter()
+		.map(|msg| (msg.recipient, msg.data.len()))
+		.collect::<Vec<_>>();
+	recipients.sort_by_key(|(recipient, _)| *recipient);
+
+	tracing::warn!(
+		target: LOG_TARGET,
+		%para_id,
+		?relay_parent,
+		head_data_size = commitments.head_data.0.len(),
+		new_code_size = ?commitments.new_validation_code.as_ref().map(|code| code.0.len()),
+		upward_messages = commitments.upward_messages.len(),
+		upward_messages_size =
+			commitments.upward_messages.iter().map(|msg| msg.len()).sum::<usize>(),
+		max_upward_message_size =
+			commitments.upward_messages.iter().map(|msg| msg.len()).max().unwrap_or(0),
+		horizontal_messages = commitments.horizontal_messages.len(),
+		horizontal_messages_sorted = commitments
+			.horizontal_messages
+			.windows(2)
+			.all(|pair| pair[0].recipient < pair[1].recipient),
+		horizontal_message_sizes = ?recipients,
+		processed_downward_messages = commitments.processed_downward_messages,
+		hrmp_watermark = commitments.hrmp_watermark,
+		"collation outputs exceed the limits of the relay chain, dropping the collation",
+	);
+}
+
+/// A limit of the relay chain exceeded by the outputs of a collation.
+#[derive(Debug, Clone, PartialEq)]
+enum OutputsViolation {
+	HeadDataSize { size: usize, max: u32 },
+	CodeUpgradeRestricted,
+	CodeSize { size: usize, max: u32 },
+	NoDownwardMessagesProcessed { queued: usize },
+	TooManyDownwardMessagesProcessed { processed: u32, queued: usize },
+	UpwardMessageCount { count: usize, max: u32 },
+	UpwardMessageSize { idx: usize, size: usize, max: u32 },
+	UpwardQueueCount { count: usize, remaining: u32 },
+	UpwardQueueSize { size: usize, remaining: u32 },
+	HrmpWatermarkAhead { watermark: BlockNumber, relay_parent_number: BlockNumber },
+	HrmpWatermarkInvalid { watermark: BlockNumber },
+	HorizontalMessageCount { count: usize, max: u32 },
+	HorizontalMessagesNotSorted { idx: usize },
+	NoChannel { recipient: ParaId },
+	ChannelMessageSize { recipient: ParaId, size: usize, max: u32 },
+	ChannelSize { recipient: ParaId, size: usize, remaining: u32 },
+	ChannelCapacity { recipient: ParaId },
+}
+
+impl fmt::Display for OutputsViolation {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		use OutputsViolation::*;
+		match self {
+			HeadDataSize { size, max } =>
+				write!(f, "head data size {} exceeds the maximum of {}", size, max),
+			CodeUpgradeRestricted => write!(f, "new validation code while upgrades are restricted"),
+			CodeSize { size, max } =>
+				write!(f, "new validation code size {} exceeds the maximum of {}", size, max),
+			NoDownwardMessagesProcessed { queued } =>
+				write!(f, "no downward messages processed while {} are queued", queued),
+			TooManyDownwardMessagesProcessed { processed, queued } => write!(
+				f,
+				"{} downward messages processed while only {} are queued",
+				processed, queued
+			),
+			UpwardMessageCount { count, max } =>
+				write!(f, "{} upward messages exceed the maximum of {}", count, max),
+			UpwardMessageSize { idx, size, max } =>
+				write!(f, "upward message {} of size {} exceeds the maximum of {}", idx, size, max),
+			UpwardQueueCount { count, remaining } => write!(
+				f,
+				"{} upward messages exceed the {} remaining in the queue",
+				count, remaining,
+			),
+			UpwardQueueSize { size, remaining } => write!(
+				f,
+				"upward messages of total size {} exceed the {} bytes remaining in the queue",
+				size, remaining,
+			),
+			HrmpWatermarkAhead { watermark, relay_parent_number } => write!(
+				f,
+				"HRMP watermark {} is ahead of the relay parent {}",
+				watermark, relay_parent_number,
+			),
+			HrmpWatermarkInvalid { watermark } =>
+				write!(f, "HRMP watermark {} doesn't land on a block with messages", watermark),
+			HorizontalMessageCount { count, max } =>
+				write!(f, "{} horizontal messages exceed the maximum of {}", count, max),
+			HorizontalMessagesNotSorted { idx } =>
+				write!(f, "horizontal message {} isn't sorted by ascending recipient", idx),
+			NoChannel { recipient } => write!(f, "no open channel to {}", recipient),
+			ChannelMessageSize { recipient, size, max } => write!(
+				f,
+				"message to {} of size {} exceeds the channel maximum of {}",
+				recipient, size, max,
+			),
+			ChannelSize { recipient, size, remaining } => write!(
+				f,
+				"message to {} of size {} exceeds the {} bytes remaining in the channel",
+				recipient, size, remaining,
+			),
+			ChannelCapacity { recipient } =>
+				write!(f, "the channel to {} has no message capacity left", recipient),
+		}
+	}
+}
+
+/// Find the first limit of `constraints` exceeded by `commitments`, checked in the order of the
+/// acceptance checks of the runtime.
+///
+/// Returns `None` for violations of rules not covered by the constraints, like the advancement of
+/// the HRMP watermark.
+fn find_violation(
+	commitments: &CandidateCommitments,
+	constraints: &Constraints,
+) -> Option<OutputsViolation> {
+	use OutputsViolation::*;
+
+	let head_data_size = commitments.head_data.0.len();
+	if head_data_size > constraints.max_head_data_size as usize {
+		return Some(HeadDataSize { size: head_data_size, max: constraints.max_head_data_size })
+	}
+
+	if let Some(code) = &commitments.new_validation_code {
+		if let Some(UpgradeRestriction::Present) = constraints.upgrade_restriction {
+			return Some(CodeUpgradeRestricted)
+		}
+		if code.0.len() > constraints.max_code_size as usize {
+			return Some(CodeSize { size: code.0.len(), max: constraints.max_code_size })
+		}
+	}
+
+	let queued = constraints.dmp_remaining_messages.len();
+	let processed = commitments.processed_downward_messages;
+	if queued > 0 && processed == 0 {
+		return Some(NoDownwardMessagesProcessed { queued })
+	}
+	if processed as usize > queued {
+		return Some(TooManyDownwardMessagesProcessed { processed, queued })
+	}
+
+	let upward = &commitments.upward_messages;
+	if upward.len() > constraints.max_ump_num_per_candidate as usize {
+		return Some(UpwardMessageCount {
+			count: upward.len(),
+			max: constraints.max_ump_num_per_candidate,
+		})
+	}
+	if let Some((idx, msg)) = upward
+		.iter()
+		.enumerate()
+		.find(|(_, msg)| msg.len() > constraints.max_upward_message_size as usize)
+	{
+		return Some(UpwardMessageSize {
+			idx,
+			size: msg.len(),
+			max: constraints.max_upward_message_size,
+		})
+	}
+	if upward.len() > constraints.ump_remaining as usize {
+		return Some(UpwardQueueCount { count: upward.len(), remaining: constraints.ump_remaining })
+	}
+	let upward_size = upward.iter().map(|msg| msg.len()).sum::<usize>();
+	if upward_size > constraints.ump_remaining_bytes as usize {
+		return Some(UpwardQueueSize {
+			size: upward_size,
+			remaining: constraints.ump_remaining_bytes,
+		})
+	}
+
+	let watermark = commitments.hrmp_watermark;
+	let relay_parent_number = constraints.min_relay_parent_number;
+	if watermark > relay_parent_number {
+		return Some(HrmpWatermarkAhead { watermark, relay_parent_number })
+	}
+	if watermark != relay_parent_number &&
+		!constraints.hrmp_inbound.valid_watermarks.contains(&watermark)
+	{
+		return Some(HrmpWatermarkInvalid { watermark })
+	}
+
+	let horizontal = &commitments.horizontal_messages;
+	if horizontal.len() > constraints.max_hrmp_num_per_candidate as usize {
+		return Some(HorizontalMessageCount {
+			count: horizontal.len(),
+			max: constraints.max_hrmp_num_per_candidate,
+		})
+	}
+	for (idx, msg) in horizontal.iter().enumerate() {
+		if idx > 0 && msg.recipient <= horizontal[idx - 1].recipient {
+			return Some(HorizontalMessagesNotSorted { idx })
+		}
+
+		let recipient = msg.recipient;
+		let channel = match constraints
+			.hrmp_channels_out
+			.binary_search_by_key(&recipient, |(recipient, _)| *recipient)
+		{
+			Ok(i) => &constraints.hrmp_channels_out[i].1,
+			Err(_) => return Some(NoChannel { recipient }),
+		};
+
+		let size = msg.data.len();
+		if size > channel.max_message_size as usize {
+			return Some(ChannelMessageSize { recipient, size, max: channel.max_message_size })
+		}
+		if size > channel.bytes_remaining as usize {
+			return Some(ChannelSize { recipient, size, remaining: channel.bytes_remaining })
+		}
+		if channel.messages_remaining == 0 {
+			return Some(ChannelCapacity { recipient })
+		}
+	}
+
+	None
+}
+
 async fn obtain_current_validation_code_hash(
 	relay_parent: Hash,
 	para_id: ParaId,
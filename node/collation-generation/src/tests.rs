@@ -62,6 +62,35 @@ mod handle_new_activations {
 		persisted_validation_data
 	}
 
+	/// Collect the messages sent by the collation tasks, answering the checks of their outputs
+	/// with `outputs_valid`.
+	async fn collect_sent_messages(
+		rx: mpsc::Receiver<AllMessages>,
+		outputs_valid: bool,
+	) -> Vec<AllMessages> {
+		rx.filter_map(|msg| async move {
+			match msg {
+				AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+					_hash,
+					RuntimeApiRequest::CheckValidationOutputs(_para_id, _commitments, tx),
+				)) => {
+					tx.send(Ok(outputs_valid)).unwrap();
+					None
+				},
+				AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+					_hash,
+					RuntimeApiRequest::StagingParaBackingState(_para_id, tx),
+				)) => {
+					tx.send(Ok(None)).unwrap();
+					None
+				},
+				msg => Some(msg),
+			}
+		})
+		.collect()
+		.await
+	}
+
 	// Box<dyn Future<Output = Collation> + Unpin + Send
 	struct TestCollator;
 
@@ -293,7 +322,7 @@ mod handle_new_activations {
 			std::mem::drop(tx);
 
 			// collect all sent messages
-			*subsystem_sent_messages.lock().await = rx.collect().await;
+			*subsystem_sent_messages.lock().await = collect_sent_messages(rx, true).await;
 		});
 
 		let sent_messages = Arc::try_unwrap(sent_messages)
@@ -459,7 +488,7 @@ mod handle_new_activations {
 
 			std::mem::drop(tx);
 
-			*subsystem_sent_messages.lock().await = rx.collect().await;
+			*subsystem_sent_messages.lock().await = collect_sent_messages(rx, true).await;
 		});
 
 		let sent_messages = Arc::try_unwrap(sent_messages)
@@ -480,4 +509,250 @@ mod handle_new_activations {
 			_ => panic!("received wrong message type"),
 		}
 	}
+
+	#[test]
+	fn drops_collation_with_invalid_outputs() {
+		let activated_hashes: Vec<Hash> = vec![Hash::repeat_byte(4)];
+
+		let overseer = |mut handle: TestSubsystemContextHandle<CollationGenerationMessage>| async move {
+			loop {
+				match handle.try_recv().await {
+					None => break,
+					Some(AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+						_hash,
+						RuntimeApiRequest::AvailabilityCores(tx),
+					))) => {
+						tx.send(Ok(vec![CoreState::Scheduled(scheduled_core_for(16))])).unwrap();
+					},
+					Some(AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+						_hash,
+						RuntimeApiRequest::PersistedValidationData(
+							_para_id,
+							_occupied_core_assumption,
+							tx,
+						),
+					))) => {
+						tx.send(Ok(Some(test_validation_data()))).unwrap();
+					},
+					Some(AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+						_hash,
+						RuntimeApiRequest::Validators(tx),
+					))) => {
+						tx.send(Ok(vec![dummy_validator(); 3])).unwrap();
+					},
+					Some(AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+						_hash,
+						RuntimeApiRequest::ValidationCodeHash(
+							_para_id,
+							OccupiedCoreAssumption::Free,
+							tx,
+						),
+					))) => {
+						tx.send(Ok(Some(ValidationCode(vec![1, 2, 3]).hash()))).unwrap();
+					},
+					Some(msg) => {
+						panic!("didn't expect any other overseer requests; got {:?}", msg)
+					},
+				}
+			}
+		};
+
+		let (tx, rx) = mpsc::channel(0);
+
+		let sent_messages = Arc::new(Mutex::new(Vec::new()));
+		let subsystem_sent_messages = sent_messages.clone();
+		subsystem_test_harness(overseer, |mut ctx| async move {
+			handle_new_activations(test_config(16), activated_hashes, &mut ctx, Metrics(None), &tx)
+				.await
+				.unwrap();
+
+			std::mem::drop(tx);
+
+			*subsystem_sent_messages.lock().await = collect_sent_messages(rx, false).await;
+		});
+
+		let sent_messages = Arc::try_unwrap(sent_messages)
+			.expect("subsystem should have shut down by now")
+			.into_inner();
+
+		// the runtime rejected the outputs, so nothing is distributed.
+		assert!(sent_messages.is_empty());
+	}
+}
+
+mod find_violation {
+	use super::super::*;
+	use ::test_helpers::dummy_head_data;
+	use selendra_primitives::{
+		v1::{HeadData, OutboundHrmpMessage, ValidationCode},
+		v2::{InboundHrmpLimitations, OutboundHrmpChannelLimitations},
+	};
+
+	fn constraints() -> Constraints {
+		Constraints {
+			min_relay_parent_number: 10,
+			max_pov_size: 1024,
+			max_head_data_size: 32,
+			max_code_size: 1024,
+			ump_remaining: 10,
+			ump_remaining_bytes: 1024,
+			max_ump_num_per_candidate: 5,
+			max_upward_message_size: 64,
+			dmp_remaining_messages: vec![],
+			hrmp_inbound: InboundHrmpLimitations { valid_watermarks: vec![8] },
+			hrmp_channels_out: vec![(
+				ParaId::from(200),
+				OutboundHrmpChannelLimitations {
+					bytes_remaining: 100,
+					messages_remaining: 1,
+					max_message_size: 50,
+				},
+			)],
+			max_hrmp_num_per_candidate: 5,
+			required_parent: dummy_head_data(),
+			validation_code_hash: ValidationCode(vec![1, 2, 3]).hash(),
+			upgrade_restriction: None,
+			future_validation_code: None,
+		}
+	}
+
+	fn commitments() -> CandidateCommitments {
+		CandidateCommitments {
+			head_data: dummy_head_data(),
+			hrmp_watermark: 10,
+			..Default::default()
+		}
+	}
+
+	#[test]
+	fn accepts_outputs_within_limits() {
+		let mut commitments = commitments();
+		commitments.hrmp_watermark = 8;
+		commitments.upward_messages = vec![vec![0; 64]];
+		commitments.horizontal_messages =
+			vec![OutboundHrmpMessage { recipient: ParaId::from(200), data: vec![0; 50] }];
+
+		assert_eq!(find_violation(&commitments, &constraints()), None);
+	}
+
+	#[test]
+	fn reports_violated_limit_with_value_and_bound() {
+		let mut commitments = commitments();
+		commitments.head_data = HeadData(vec![0; 33]);
+		assert_eq!(
+			find_violation(&commitments, &constraints()),
+			Some(OutputsViolation::HeadDataSize { size: 33, max: 32 }),
+		);
+
+		let mut commitments = self::commitments();
+		commitments.upward_messages = vec![vec![0; 10], vec![0; 65]];
+		assert_eq!(
+			find_violation(&commitments, &constraints()),
+			Some(OutputsViolation::UpwardMessageSize { idx: 1, size: 65, max: 64 }),
+		);
+
+		let mut commitments = self::commitments();
+		commitments.hrmp_watermark = 9;
+		assert_eq!(
+			find_violation(&commitments, &constraints()),
+			Some(OutputsViolation::HrmpWatermarkInvalid { watermark: 9 }),
+		);
+	}
+
+	#[test]
+	fn reports_violated_channel_limits() {
+		let message = |recipient: u32, size| OutboundHrmpMessage {
+			recipient: ParaId::from(recipient),
+			data: vec![0; size],
+		};
+
+		let mut commitments = commitments();
+		commitments.horizontal_messages = vec![message(200, 51)];
+		assert_eq!(
+			find_violation(&commitments, &constraints()),
+			Some(OutputsViolation::ChannelMessageSize {
+				recipient: ParaId::from(200),
+				size: 51,
+				max: 50,
+			}),
+		);
+
+		let mut constraints = constraints();
+		constraints.hrmp_channels_out[0].1.bytes_remaining = 20;
+		commitments.horizontal_messages = vec![message(200, 21)];
+		assert_eq!(
+			find_violation(&commitments, &constraints),
+			Some(OutputsViolation::ChannelSize {
+				recipient: ParaId::from(200),
+				size: 21,
+				remaining: 20,
+			}),
+		);
+
+		commitments.horizontal_messages = vec![message(300, 1)];
+		assert_eq!(
+			find_violation(&commitments, &constraints),
+			Some(OutputsViolation::NoChannel { recipient: ParaId::from(300) }),
+		);
+	}
+}
+
+mod rotate_key {
+	use super::super::*;
+	use selendra_node_subsystem::messages::{RuntimeApiMessage, RuntimeApiRequest};
+	use selendra_node_subsystem_test_helpers::{subsystem_test_harness, TestSubsystemContextHandle};
+	use selendra_primitives::v1::CollatorPair;
+
+	fn test_config(key: CollatorPair) -> Arc<CollationGenerationConfig> {
+		Arc::new(CollationGenerationConfig {
+			key,
+			collator: Box::new(|_: Hash, _vd: &PersistedValidationData| {
+				futures::future::ready(None).boxed()
+			}),
+			para_id: 100.into(),
+		})
+	}
+
+	#[test]
+	fn pending_config_is_applied_at_next_session() {
+		let new_key = CollatorPair::generate().0;
+		let new_public = new_key.public();
+
+		let overseer_new_public = new_public.clone();
+		let overseer = |mut handle: TestSubsystemContextHandle<CollationGenerationMessage>| async move {
+			for session_index in [1, 1, 2] {
+				match handle.recv().await {
+					AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+						_hash,
+						RuntimeApiRequest::SessionIndexForChild(tx),
+					)) => tx.send(Ok(session_index)).unwrap(),
+					msg => panic!("didn't expect {:?}", msg),
+				}
+			}
+
+			match handle.recv().await {
+				AllMessages::CollatorProtocol(CollatorProtocolMessage::RotateKey(
+					NewCollatorKey(key),
+				)) => assert_eq!(key.public(), overseer_new_public),
+				msg => panic!("didn't expect {:?}", msg),
+			}
+		};
+
+		subsystem_test_harness(overseer, |mut ctx| async move {
+			let mut subsystem = CollationGenerationSubsystem::new(Metrics(None));
+			subsystem.config = Some(test_config(CollatorPair::generate().0));
+			subsystem.pending_config =
+				Some(PendingConfig { config: test_config(new_key), session_index: None });
+
+			// the first leaf notes the current session, the config is only applied in the next one.
+			for leaf in [Hash::repeat_byte(1), Hash::repeat_byte(2)] {
+				subsystem.apply_pending_config(leaf, &mut ctx).await;
+				assert!(subsystem.pending_config.is_some());
+			}
+
+			subsystem.apply_pending_config(Hash::repeat_byte(3), &mut ctx).await;
+			assert!(subsystem.pending_config.is_none());
+			assert_eq!(subsystem.config.expect("config is set").key.public(), new_public);
+		});
+	}
 }
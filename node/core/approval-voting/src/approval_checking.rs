@@ -364,6 +364,24 @@ fn count_no_shows(
 	(no_shows, next_no_show)
 }
 
+/// Count the assigned validators which haven't approved within the no-show duration as of
+/// `tick_now`, across all tranches of the approval entry.
+pub fn no_shows(
+	approval_entry: &ApprovalEntry,
+	approvals: &BitSlice<u8, BitOrderLsb0>,
+	block_tick: Tick,
+	no_show_duration: Tick,
+	tick_now: Tick,
+) -> usize {
+	approval_entry
+		.tranches()
+		.iter()
+		.map(|t| {
+			count_no_shows(t.assignments(), approvals, 0, block_tick, no_show_duration, tick_now).0
+		})
+		.sum()
+}
+
 /// Determine the amount of tranches of assignments needed to determine approval of a candidate.
 pub fn tranches_to_approve(
 	approval_entry: &ApprovalEntry,
@@ -450,6 +450,57 @@ fn canonicalize_works() {
 	]);
 }
 
+#[test]
+fn canonicalize_far_beyond_stored_range() {
+	let (mut db, store) = make_db();
+
+	let n_validators = 10;
+
+	let genesis = Hash::repeat_byte(0);
+	let block_hash_a = Hash::repeat_byte(1);
+	let block_hash_b = Hash::repeat_byte(2);
+
+	let candidate_receipt = make_candidate(1.into(), genesis);
+	let cand_hash = candidate_receipt.hash();
+
+	let block_entry_a = make_block_entry(block_hash_a, genesis, 1, Vec::new());
+	let block_entry_b =
+		make_block_entry(block_hash_b, block_hash_a, 2, vec![(CoreIndex(0), cand_hash)]);
+
+	let mut overlay_db = OverlayedBackend::new(&db);
+	for block_entry in vec![block_entry_a, block_entry_b] {
+		add_block_entry(&mut overlay_db, block_entry.into(), n_validators, |_| {
+			Some(NewCandidateInfo::new(candidate_receipt.clone(), GroupIndex(1), None))
+		})
+		.unwrap();
+	}
+	let write_ops = overlay_db.into_write_ops();
+	db.write(write_ops).unwrap();
+
+	assert_eq!(
+		load_stored_blocks(store.as_ref(), &TEST_CONFIG).unwrap().unwrap(),
+		StoredBlockRange(1, 3)
+	);
+
+	// Finality moved far ahead of everything we know about, e.g. while the node was down.
+	let canon_number = 1_000_000_000;
+	let mut overlay_db = OverlayedBackend::new(&db);
+	canonicalize(&mut overlay_db, canon_number, Hash::repeat_byte(42)).unwrap();
+	let write_ops = overlay_db.into_write_ops();
+	db.write(write_ops).unwrap();
+
+	assert_eq!(
+		load_stored_blocks(store.as_ref(), &TEST_CONFIG).unwrap().unwrap(),
+		StoredBlockRange(canon_number + 1, canon_number + 2)
+	);
+	assert!(load_block_entry(store.as_ref(), &TEST_CONFIG, &block_hash_a).unwrap().is_none());
+	assert!(load_block_entry(store.as_ref(), &TEST_CONFIG, &block_hash_b).unwrap().is_none());
+	assert!(load_candidate_entry(store.as_ref(), &TEST_CONFIG, &cand_hash)
+		.unwrap()
+		.is_none());
+	assert!(load_all_blocks(store.as_ref(), &TEST_CONFIG).unwrap().is_empty());
+}
+
 #[test]
 fn force_approve_works() {
 	let (mut db, store) = make_db();
@@ -566,3 +617,70 @@ fn load_all_blocks_works() {
 		vec![block_hash_a, block_hash_b, block_hash_c],
 	)
 }
+
+#[test]
+fn checkpoint_round_trip() {
+	let (mut db, store) = make_db();
+
+	let parent_hash = Hash::repeat_byte(1);
+	let block_hash_a = Hash::repeat_byte(2);
+	let block_hash_b = Hash::repeat_byte(3);
+
+	let candidate_receipt = make_candidate(1.into(), parent_hash);
+	let candidate_hash = candidate_receipt.hash();
+
+	let block_entry_a =
+		make_block_entry(block_hash_a, parent_hash, 1, vec![(CoreIndex(0), candidate_hash)]);
+	let block_entry_b =
+		make_block_entry(block_hash_b, block_hash_a, 2, vec![(CoreIndex(0), candidate_hash)]);
+
+	let n_validators = 10;
+	let mut new_candidate_info = HashMap::new();
+	new_candidate_info
+		.insert(candidate_hash, NewCandidateInfo::new(candidate_receipt, GroupIndex(0), None));
+
+	let mut overlay_db = OverlayedBackend::new(&db);
+	add_block_entry(&mut overlay_db, block_entry_a.clone().into(), n_validators, |h| {
+		new_candidate_info.get(h).map(|x| x.clone())
+	})
+	.unwrap();
+	add_block_entry(&mut overlay_db, block_entry_b.clone().into(), n_validators, |h| {
+		new_candidate_info.get(h).map(|x| x.clone())
+	})
+	.unwrap();
+	db.write(overlay_db.into_write_ops()).unwrap();
+
+	let mut checkpoint = Vec::new();
+	let exported = crate::export_checkpoint(&store, DATA_COL, &mut checkpoint).unwrap();
+	assert_eq!(
+		exported,
+		crate::CheckpointSummary { block_range: Some((1, 3)), blocks: 2, candidates: 1 },
+	);
+
+	// A checkpoint can't be imported over existing data.
+	assert!(matches!(
+		crate::import_checkpoint(&store, DATA_COL, &mut &checkpoint[..]),
+		Err(crate::CheckpointError::DatabaseNotEmpty),
+	));
+
+	let (_, new_store) = make_db();
+	assert!(matches!(
+		crate::import_checkpoint(&new_store, DATA_COL, &mut &b"garbage"[..]),
+		Err(crate::CheckpointError::UnknownFormat),
+	));
+	let imported = crate::import_checkpoint(&new_store, DATA_COL, &mut &checkpoint[..]).unwrap();
+	assert_eq!(imported, exported);
+
+	assert_eq!(
+		load_all_blocks(new_store.as_ref(), &TEST_CONFIG).unwrap(),
+		vec![block_hash_a, block_hash_b],
+	);
+	assert_eq!(
+		load_block_entry(new_store.as_ref(), &TEST_CONFIG, &block_hash_b).unwrap(),
+		load_block_entry(store.as_ref(), &TEST_CONFIG, &block_hash_b).unwrap(),
+	);
+	assert_eq!(
+		load_candidate_entry(new_store.as_ref(), &TEST_CONFIG, &candidate_hash).unwrap(),
+		load_candidate_entry(store.as_ref(), &TEST_CONFIG, &candidate_hash).unwrap(),
+	);
+}
@@ -0,0 +1,200 @@
+// Copyright 2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Checkpoints of the approval voting database.
+//!
+//! A checkpoint holds all block and candidate entries of the database, including our own
+//! assignments and approvals, so that a validator can be moved to another host without
+//! re-importing the whole unfinalized ancestry and without forgetting the assignments it
+//! already announced.
+//!
+//! Neither exporting nor importing a checkpoint may be done while the node owning the database
+//! is running.
+
+use parity_scale_codec::{Decode, Encode};
+use selendra_node_subsystem::SubsystemError;
+use selendra_node_subsystem_util::database::{DBTransaction, Database};
+use selendra_primitives::v1::{BlockNumber, Hash};
+
+use std::{
+	collections::HashSet,
+	io::{Read, Write},
+	sync::Arc,
+};
+
+use crate::approval_db::v1::{
+	self, block_entry_key, blocks_at_height_key, candidate_entry_key, BlockEntry, CandidateEntry,
+	StoredBlockRange, STORED_BLOCKS_KEY,
+};
+
+/// Prefixes every checkpoint, to recognize them and their version.
+const CHECKPOINT_MAGIC: &[u8; 8] = b"apprchk1";
+
+/// Errors while exporting or importing a checkpoint.
+#[derive(Debug, derive_more::From, derive_more::Display)]
+pub enum Error {
+	/// Reading or writing the checkpoint failed.
+	Io(std::io::Error),
+	/// Accessing the database failed.
+	Subsystem(SubsystemError),
+	/// The checkpoint couldn't be decoded.
+	InvalidDecoding(parity_scale_codec::Error),
+	/// The data isn't a checkpoint or one of an unsupported version.
+	#[from(ignore)]
+	#[display(fmt = "Not an approval voting checkpoint")]
+	UnknownFormat,
+	/// The database to import into already contains approval voting data.
+	#[from(ignore)]
+	#[display(fmt = "The approval voting database is not empty")]
+	DatabaseNotEmpty,
+}
+
+impl std::error::Error for Error {}
+
+/// A summary of an exported or imported checkpoint.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CheckpointSummary {
+	/// The range of block numbers covered, if any.
+	pub block_range: Option<(BlockNumber, BlockNumber)>,
+	/// The number of block entries.
+	pub blocks: usize,
+	/// The number of candidate entries.
+	pub candidates: usize,
+}
+
+#[derive(Encode, Decode)]
+struct Checkpoint {
+	stored_blocks: Option<StoredBlockRange>,
+	blocks_at_height: Vec<(BlockNumber, Vec<Hash>)>,
+	block_entries: Vec<BlockEntry>,
+	candidate_entries: Vec<CandidateEntry>,
+}
+
+impl Checkpoint {
+	fn summary(&self) -> CheckpointSummary {
+		CheckpointSummary {
+			block_range: self.stored_blocks.as_ref().map(|r| (r.0, r.1)),
+			blocks: self.block_entries.len(),
+			candidates: self.candidate_entries.len(),
+		}
+	}
+}
+
+/// Write a checkpoint of the approval voting data stored in column `col_data` of the database
+/// to `writer`.
+pub fn export_checkpoint(
+	db: &Arc<dyn Database>,
+	col_data: u32,
+	writer: &mut impl Write,
+) -> Result<CheckpointSummary, Error> {
+	let config = v1::Config { col_data };
+	let store = &**db;
+
+	let stored_blocks = v1::load_stored_blocks(store, &config)?;
+	let mut checkpoint = Checkpoint {
+		stored_blocks: stored_blocks.clone(),
+		blocks_at_height: Vec::new(),
+		block_entries: Vec::new(),
+		candidate_entries: Vec::new(),
+	};
+
+	// Candidates included in several blocks are only exported once.
+	let mut exported_candidates = HashSet::new();
+
+	if let Some(StoredBlockRange(start, end)) = stored_blocks {
+		for height in start..end {
+			let blocks = v1::load_blocks_at_height(store, &config, &height)?;
+			for block_hash in &blocks {
+				let block_entry = match v1::load_block_entry(store, &config, block_hash)? {
+					Some(block_entry) => block_entry,
+					None => continue,
+				};
+
+				for (_, candidate_hash) in &block_entry.candidates {
+					if !exported_candidates.insert(*candidate_hash) {
+						continue
+					}
+
+					if let Some(candidate_entry) =
+						v1::load_candidate_entry(store, &config, candidate_hash)?
+					{
+						checkpoint.candidate_entries.push(candidate_entry);
+					}
+				}
+
+				checkpoint.block_entries.push(block_entry);
+			}
+
+			checkpoint.blocks_at_height.push((height, blocks));
+		}
+	}
+
+	writer.write_all(CHECKPOINT_MAGIC)?;
+	writer.write_all(&checkpoint.encode())?;
+	writer.flush()?;
+
+	Ok(checkpoint.summary())
+}
+
+/// Restore a checkpoint read from `reader` into column `col_data` of the database.
+///
+/// The column must not contain any approval voting data yet.
+pub fn import_checkpoint(
+	db: &Arc<dyn Database>,
+	col_data: u32,
+	reader: &mut impl Read,
+) -> Result<CheckpointSummary, Error> {
+	let config = v1::Config { col_data };
+
+	if v1::load_stored_blocks(&**db, &config)?.is_some() {
+		return Err(Error::DatabaseNotEmpty)
+	}
+
+	let mut magic = [0u8; 8];
+	reader.read_exact(&mut magic)?;
+	if &magic != CHECKPOINT_MAGIC {
+		return Err(Error::UnknownFormat)
+	}
+
+	let mut encoded = Vec::new();
+	reader.read_to_end(&mut encoded)?;
+	let checkpoint = Checkpoint::decode(&mut &encoded[..])?;
+
+	let mut tx = DBTransaction::new();
+	if let Some(stored_blocks) = &checkpoint.stored_blocks {
+		tx.put_vec(config.col_data, STORED_BLOCKS_KEY, stored_blocks.encode());
+	}
+	for (height, blocks) in &checkpoint.blocks_at_height {
+		tx.put_vec(config.col_data, &blocks_at_height_key(*height), blocks.encode());
+	}
+	for block_entry in &checkpoint.block_entries {
+		tx.put_vec(
+			config.col_data,
+			&block_entry_key(&block_entry.block_hash),
+			block_entry.encode(),
+		);
+	}
+	for candidate_entry in &checkpoint.candidate_entries {
+		tx.put_vec(
+			config.col_data,
+			&candidate_entry_key(&candidate_entry.candidate.hash()),
+			candidate_entry.encode(),
+		);
+	}
+	db.write(tx)?;
+
+	Ok(checkpoint.summary())
+}
@@ -38,7 +38,7 @@ use selendra_node_subsystem::{
 		ApprovalDistributionMessage, ChainApiMessage, ChainSelectionMessage, RuntimeApiMessage,
 		RuntimeApiRequest,
 	},
-	overseer, SubsystemContext, SubsystemError, SubsystemResult,
+	overseer, SubsystemContext, SubsystemError, SubsystemResult, SubsystemSender,
 };
 use selendra_node_subsystem_util::{
 	determine_new_blocks,
@@ -75,6 +75,7 @@ struct ImportedBlockInfo {
 	force_approve: Option<BlockNumber>,
 }
 
+#[derive(Clone, Copy)]
 struct ImportedBlockInfoEnv<'a> {
 	session_window: &'a Option<RollingSessionWindow>,
 	assignment_criteria: &'a (dyn AssignmentCriteria + Send + Sync),
@@ -84,7 +85,7 @@ struct ImportedBlockInfoEnv<'a> {
 // Computes information about the imported block. Returns `None` if the info couldn't be extracted -
 // failure to communicate with overseer,
 async fn imported_block_info(
-	ctx: &mut (impl SubsystemContext + overseer::SubsystemContext),
+	sender: &mut impl SubsystemSender,
 	env: ImportedBlockInfoEnv<'_>,
 	block_hash: Hash,
 	block_header: &Header,
@@ -95,11 +96,12 @@ async fn imported_block_info(
 	// fetch candidates
 	let included_candidates: Vec<_> = {
 		let (c_tx, c_rx) = oneshot::channel();
-		ctx.send_message(RuntimeApiMessage::Request(
-			block_hash,
-			RuntimeApiRequest::CandidateEvents(c_tx),
-		))
-		.await;
+		sender
+			.send_message(
+				RuntimeApiMessage::Request(block_hash, RuntimeApiRequest::CandidateEvents(c_tx))
+					.into(),
+			)
+			.await;
 
 		let events: Vec<CandidateEvent> = match c_rx.await {
 			Ok(Ok(events)) => events,
@@ -121,11 +123,15 @@ async fn imported_block_info(
 	// short, that shouldn't happen.
 	let session_index = {
 		let (s_tx, s_rx) = oneshot::channel();
-		ctx.send_message(RuntimeApiMessage::Request(
-			block_header.parent_hash,
-			RuntimeApiRequest::SessionIndexForChild(s_tx),
-		))
-		.await;
+		sender
+			.send_message(
+				RuntimeApiMessage::Request(
+					block_header.parent_hash,
+					RuntimeApiRequest::SessionIndexForChild(s_tx),
+				)
+				.into(),
+			)
+			.await;
 
 		let session_index = match s_rx.await {
 			Ok(Ok(s)) => s,
@@ -171,11 +177,12 @@ async fn imported_block_info(
 		// This gives us the opposite invariant for sessions - the parent block's post-state gives
 		// us the canonical information about the session index for any of its children, regardless
 		// of which slot number they might be produced at.
-		ctx.send_message(RuntimeApiMessage::Request(
-			block_hash,
-			RuntimeApiRequest::CurrentBabeEpoch(s_tx),
-		))
-		.await;
+		sender
+			.send_message(
+				RuntimeApiMessage::Request(block_hash, RuntimeApiRequest::CurrentBabeEpoch(s_tx))
+					.into(),
+			)
+			.await;
 
 		match s_rx.await {
 			Ok(Ok(s)) => s,
@@ -287,6 +294,9 @@ pub struct BlockImportedCandidates {
 	pub imported_candidates: Vec<(CandidateHash, CandidateEntry)>,
 }
 
+/// The maximum number of new blocks whose info is requested from the runtime concurrently.
+const MAX_PARALLEL_IMPORTED_BLOCK_INFO_REQUESTS: usize = 16;
+
 /// Handle a new notification of a header. This will
 ///   * determine all blocks to import,
 ///   * extract candidate information from them
@@ -295,6 +305,9 @@ pub struct BlockImportedCandidates {
 ///   * import the block and candidates to the approval DB
 ///   * and return information about all candidates imported under each block.
 ///
+/// All block and candidate entries are written through `db`, so they end up in the same
+/// database transaction once the overlay is flushed.
+///
 /// It is the responsibility of the caller to schedule wakeups for each block.
 pub(crate) async fn handle_new_head(
 	ctx: &mut (impl SubsystemContext + overseer::SubsystemContext),
@@ -376,16 +389,31 @@ pub(crate) async fn handle_new_head(
 	let mut imported_candidates = Vec::with_capacity(new_blocks.len());
 
 	// `determine_new_blocks` gives us a vec in backwards order. we want to move forwards.
+	//
+	// The info of independent blocks is gathered concurrently, which matters when catching up
+	// after a major sync, but the results are still processed in order.
 	let imported_blocks_and_info = {
+		let env = ImportedBlockInfoEnv {
+			session_window: &state.session_window,
+			assignment_criteria: &*state.assignment_criteria,
+			keystore: &state.keystore,
+		};
+		let sender = ctx.sender().clone();
+
 		let mut imported_blocks_and_info = Vec::with_capacity(new_blocks.len());
-		for (block_hash, block_header) in new_blocks.into_iter().rev() {
-			let env = ImportedBlockInfoEnv {
-				session_window: &state.session_window,
-				assignment_criteria: &*state.assignment_criteria,
-				keystore: &state.keystore,
-			};
+		let mut block_infos = futures::stream::iter(new_blocks.into_iter().rev())
+			.map(move |(block_hash, block_header)| {
+				let mut sender = sender.clone();
+				async move {
+					let info =
+						imported_block_info(&mut sender, env, block_hash, &block_header).await;
+					(block_hash, block_header, info)
+				}
+			})
+			.buffered(MAX_PARALLEL_IMPORTED_BLOCK_INFO_REQUESTS);
 
-			match imported_block_info(ctx, env, block_hash, &block_header).await? {
+		while let Some((block_hash, block_header, info)) = block_infos.next().await {
+			match info? {
 				Some(i) => imported_blocks_and_info.push((block_hash, block_header, i)),
 				None => {
 					// It's possible that we've lost a race with finality.
@@ -617,6 +645,7 @@ pub(crate) mod tests {
 	fn blank_state() -> State {
 		State {
 			session_window: None,
+			session_info_db: None,
 			keystore: Arc::new(LocalKeystore::in_memory()),
 			slot_duration_millis: 6_000,
 			clock: Box::new(MockClock::default()),
@@ -757,7 +786,7 @@ pub(crate) mod tests {
 				};
 
 				let info =
-					imported_block_info(&mut ctx, env, hash, &header).await.unwrap().unwrap();
+					imported_block_info(ctx.sender(), env, hash, &header).await.unwrap().unwrap();
 
 				assert_eq!(info.included_candidates, included_candidates);
 				assert_eq!(info.session_index, session);
@@ -865,7 +894,7 @@ pub(crate) mod tests {
 					keystore: &LocalKeystore::in_memory(),
 				};
 
-				let info = imported_block_info(&mut ctx, env, hash, &header).await.unwrap();
+				let info = imported_block_info(ctx.sender(), env, hash, &header).await.unwrap();
 
 				assert!(info.is_none());
 			})
@@ -963,7 +992,7 @@ pub(crate) mod tests {
 					keystore: &LocalKeystore::in_memory(),
 				};
 
-				let info = imported_block_info(&mut ctx, env, hash, &header).await.unwrap();
+				let info = imported_block_info(ctx.sender(), env, hash, &header).await.unwrap();
 
 				assert!(info.is_none());
 			})
@@ -1063,7 +1092,7 @@ pub(crate) mod tests {
 				};
 
 				let info =
-					imported_block_info(&mut ctx, env, hash, &header).await.unwrap().unwrap();
+					imported_block_info(ctx.sender(), env, hash, &header).await.unwrap().unwrap();
 
 				assert_eq!(info.included_candidates, included_candidates);
 				assert_eq!(info.session_index, session);
@@ -34,9 +34,9 @@ use selendra_node_subsystem::{
 	messages::{
 		ApprovalCheckError, ApprovalCheckResult, ApprovalDistributionMessage,
 		ApprovalVotingMessage, AssignmentCheckError, AssignmentCheckResult,
-		AvailabilityRecoveryMessage, BlockDescription, CandidateValidationMessage, ChainApiMessage,
-		ChainSelectionMessage, DisputeCoordinatorMessage, HighestApprovedAncestorBlock,
-		ImportStatementsResult, RuntimeApiMessage, RuntimeApiRequest,
+		AvailabilityRecoveryMessage, BlockDescription, CandidateApprovalStatus,
+		CandidateValidationMessage, ChainApiMessage, ChainSelectionMessage,
+		DisputeCoordinatorMessage, HighestApprovedAncestorBlock, ImportStatementsResult,
 	},
 	overseer::{self, SubsystemSender as _},
 	FromOverseer, OverseerSignal, SpawnedSubsystem, SubsystemContext, SubsystemError,
@@ -45,9 +45,10 @@ use selendra_node_subsystem::{
 use selendra_node_subsystem_util::{
 	database::Database,
 	metrics::{self, prometheus},
+	pending_work::PendingWork,
 	rolling_session_window::{
-		new_session_window_size, RollingSessionWindow, SessionWindowSize, SessionWindowUpdate,
-		SessionsUnavailable,
+		new_session_window_size, DatabaseParams, RollingSessionWindow, SessionWindowSize,
+		SessionWindowUpdate, SessionsUnavailable,
 	},
 	TimeoutExt,
 };
@@ -86,17 +87,25 @@ use time::{slot_number_to_tick, Clock, ClockExt, SystemClock, Tick};
 mod approval_checking;
 mod approval_db;
 mod backend;
+mod checkpoint;
 mod criteria;
 mod import;
 mod ops;
 mod persisted_entries;
 mod time;
+mod watchdog;
 
 use crate::{
 	approval_db::v1::{Config as DatabaseConfig, DbBackend},
 	backend::{Backend, OverlayedBackend},
+	watchdog::FinalityLagWatchdog,
 };
 
+pub use checkpoint::{
+	export_checkpoint, import_checkpoint, CheckpointSummary, Error as CheckpointError,
+};
+pub use watchdog::{FinalityLagWatchdogConfig, DEFAULT_FINALITY_LAG_THRESHOLD};
+
 #[cfg(test)]
 mod tests;
 
@@ -116,6 +125,11 @@ pub struct Config {
 	/// The slot duration of the consensus algorithm, in milliseconds. Should be evenly
 	/// divisible by 500.
 	pub slot_duration_millis: u64,
+	/// The configuration of the watchdog tracking how far approval checking lags behind the
+	/// chain head.
+	pub finality_lag_watchdog: FinalityLagWatchdogConfig,
+	/// The work a graceful shutdown waits for, which the ongoing approval checks are part of.
+	pub pending_work: PendingWork,
 }
 
 // The mode of the approval voting subsystem. It should start in a `Syncing` mode when it first
@@ -143,6 +157,8 @@ pub struct ApprovalVotingSubsystem {
 	db: Arc<dyn Database>,
 	mode: Mode,
 	metrics: Metrics,
+	finality_lag_watchdog: FinalityLagWatchdog,
+	pending_work: PendingWork,
 }
 
 #[derive(Clone)]
@@ -156,6 +172,8 @@ struct MetricsInner {
 	block_approval_time_ticks: prometheus::Histogram,
 	time_db_transaction: prometheus::Histogram,
 	time_recover_and_approve: prometheus::Histogram,
+	finality_lag: prometheus::Gauge<prometheus::U64>,
+	finality_lag_alarm: prometheus::Gauge<prometheus::U64>,
 }
 
 /// Approval Voting metrics.
@@ -229,6 +247,13 @@ impl Metrics {
 		}
 	}
 
+	fn on_finality_lag(&self, lag: BlockNumber, alarm: bool) {
+		if let Some(metrics) = &self.0 {
+			metrics.finality_lag.set(lag as u64);
+			metrics.finality_lag_alarm.set(alarm as u64);
+		}
+	}
+
 	fn time_db_transaction(&self) -> Option<metrics::prometheus::prometheus::HistogramTimer> {
 		self.0.as_ref().map(|metrics| metrics.time_db_transaction.start_timer())
 	}
@@ -319,6 +344,20 @@ impl metrics::Metrics for Metrics {
 				)?,
 				registry,
 			)?,
+			finality_lag: prometheus::register(
+				prometheus::Gauge::new(
+					"selendra_parachain_approval_finality_lag",
+					"Number of blocks the highest fully approved block lags behind the chain head",
+				)?,
+				registry,
+			)?,
+			finality_lag_alarm: prometheus::register(
+				prometheus::Gauge::new(
+					"selendra_parachain_approval_finality_lag_alarm",
+					"Set to 1 while the approval finality lag exceeds the configured threshold",
+				)?,
+				registry,
+			)?,
 		};
 
 		Ok(Metrics(Some(metrics)))
@@ -341,6 +380,8 @@ impl ApprovalVotingSubsystem {
 			db_config: DatabaseConfig { col_data: config.col_data },
 			mode: Mode::Syncing(sync_oracle),
 			metrics,
+			finality_lag_watchdog: FinalityLagWatchdog::new(config.finality_lag_watchdog),
+			pending_work: config.pending_work,
 		}
 	}
 }
@@ -514,11 +555,16 @@ impl ApprovalState {
 struct CurrentlyCheckingSet {
 	candidate_hash_map: HashMap<CandidateHash, HashSet<Hash>>,
 	currently_checking: FuturesUnordered<BoxFuture<'static, ApprovalState>>,
+	pending_work: PendingWork,
 }
 
 impl Default for CurrentlyCheckingSet {
 	fn default() -> Self {
-		Self { candidate_hash_map: HashMap::new(), currently_checking: FuturesUnordered::new() }
+		Self {
+			candidate_hash_map: HashMap::new(),
+			currently_checking: FuturesUnordered::new(),
+			pending_work: PendingWork::default(),
+		}
 	}
 }
 
@@ -541,7 +587,9 @@ impl CurrentlyCheckingSet {
 				// validation not ongoing. launch work and time out the remote handle.
 				entry.insert(HashSet::new()).insert(relay_block);
 				let work = launch_work.await?;
+				let pending = self.pending_work.start();
 				self.currently_checking.push(Box::pin(async move {
+					let _pending = pending;
 					match work.timeout(APPROVAL_CHECKING_TIMEOUT).await {
 						None => ApprovalState {
 							candidate_hash,
@@ -581,6 +629,8 @@ impl CurrentlyCheckingSet {
 
 struct State {
 	session_window: Option<RollingSessionWindow>,
+	/// Where the session window persists its session info, if anywhere.
+	session_info_db: Option<DatabaseParams>,
 	keystore: Arc<LocalKeystore>,
 	slot_duration_millis: u64,
 	clock: Box<dyn Clock + Send + Sync>,
@@ -601,8 +651,13 @@ impl State {
 		let session_window = self.session_window.take();
 		match session_window {
 			None => {
-				self.session_window =
-					Some(RollingSessionWindow::new(ctx, APPROVAL_SESSIONS, head).await?);
+				let session_window = match self.session_info_db.clone() {
+					Some(db_params) =>
+						RollingSessionWindow::new_with_db(ctx, APPROVAL_SESSIONS, head, db_params)
+							.await?,
+					None => RollingSessionWindow::new(ctx, APPROVAL_SESSIONS, head).await?,
+				};
+				self.session_window = Some(session_window);
 				Ok(None)
 			},
 			Some(mut session_window) => {
@@ -705,6 +760,10 @@ where
 {
 	let mut state = State {
 		session_window: None,
+		session_info_db: Some(DatabaseParams {
+			db: subsystem.db.clone(),
+			db_column: subsystem.db_config.col_data,
+		}),
 		keystore: subsystem.keystore,
 		slot_duration_millis: subsystem.slot_duration_millis,
 		clock,
@@ -712,7 +771,8 @@ where
 	};
 
 	let mut wakeups = Wakeups::default();
-	let mut currently_checking_set = CurrentlyCheckingSet::default();
+	let mut currently_checking_set =
+		CurrentlyCheckingSet { pending_work: subsystem.pending_work.clone(), ..Default::default() };
 	let mut approvals_cache = lru::LruCache::new(APPROVAL_CACHE_SIZE);
 
 	let mut last_finalized_height: Option<BlockNumber> = {
@@ -727,6 +787,10 @@ where
 		}
 	};
 
+	if let Some(finalized_number) = last_finalized_height {
+		prune_finalized_on_startup(&mut ctx, &mut backend, finalized_number).await?;
+	}
+
 	loop {
 		let mut overlayed_db = OverlayedBackend::new(&backend);
 		let actions = futures::select! {
@@ -742,6 +806,12 @@ where
 				)?
 			}
 			next_msg = ctx.recv().fuse() => {
+				// Approval checking is expected to lag behind while syncing.
+				let finality_lag_watchdog = match subsystem.mode {
+					Mode::Active => Some(&mut subsystem.finality_lag_watchdog),
+					Mode::Syncing(_) => None,
+				};
+
 				let mut actions = handle_from_overseer(
 					&mut ctx,
 					&mut state,
@@ -750,6 +820,7 @@ where
 					next_msg?,
 					&mut last_finalized_height,
 					&mut wakeups,
+					finality_lag_watchdog,
 				).await?;
 
 				if let Mode::Syncing(ref mut oracle) = subsystem.mode {
@@ -819,6 +890,52 @@ where
 	Ok(())
 }
 
+/// Prune the entries of the blocks finalized while the node was down, before any new leaf is
+/// imported.
+///
+/// Approval voting then starts right from the finalized block instead of dragging the stale
+/// entries along until the first finality notification.
+async fn prune_finalized_on_startup(
+	ctx: &mut (impl SubsystemContext + overseer::SubsystemContext),
+	backend: &mut impl Backend,
+	finalized_number: BlockNumber,
+) -> SubsystemResult<()> {
+	let stored_range = match backend.load_stored_blocks()? {
+		Some(range) if range.0 < finalized_number => range,
+		_ => return Ok(()),
+	};
+
+	let (tx, rx) = oneshot::channel();
+	ctx.send_message(ChainApiMessage::FinalizedBlockHash(finalized_number, tx))
+		.await;
+	let finalized_hash = match rx.await? {
+		Ok(Some(hash)) => hash,
+		Ok(None) => {
+			tracing::warn!(target: LOG_TARGET, finalized_number, "Missing finalized block hash");
+			return Ok(())
+		},
+		Err(err) => {
+			tracing::warn!(target: LOG_TARGET, ?err, "Failed fetching finalized block hash");
+			return Ok(())
+		},
+	};
+
+	let mut overlayed_db = OverlayedBackend::new(&*backend);
+	ops::canonicalize(&mut overlayed_db, finalized_number, finalized_hash)
+		.map_err(|e| SubsystemError::with_origin("db", e))?;
+	let ops = overlayed_db.into_write_ops();
+	backend.write(ops)?;
+
+	tracing::info!(
+		target: LOG_TARGET,
+		?stored_range,
+		finalized_number,
+		"Pruned the approval voting data of blocks finalized while the node was down",
+	);
+
+	Ok(())
+}
+
 // Handle actions is a function that accepts a set of instructions
 // and subsequently updates the underlying approvals_db in accordance
 // with the linear set of instructions passed in. Therefore, actions
@@ -1033,40 +1150,8 @@ fn distribution_messages_for_activation(
 			};
 
 			match candidate_entry.approval_entry(&block_hash) {
-				Some(approval_entry) => {
-					match approval_entry.local_statements() {
-						(None, None) | (None, Some(_)) => {}, // second is impossible case.
-						(Some(assignment), None) => {
-							messages.push(ApprovalDistributionMessage::DistributeAssignment(
-								IndirectAssignmentCert {
-									block_hash,
-									validator: assignment.validator_index(),
-									cert: assignment.cert().clone(),
-								},
-								i as _,
-							));
-						},
-						(Some(assignment), Some(approval_sig)) => {
-							messages.push(ApprovalDistributionMessage::DistributeAssignment(
-								IndirectAssignmentCert {
-									block_hash,
-									validator: assignment.validator_index(),
-									cert: assignment.cert().clone(),
-								},
-								i as _,
-							));
-
-							messages.push(ApprovalDistributionMessage::DistributeApproval(
-								IndirectSignedApprovalVote {
-									block_hash,
-									candidate_index: i as _,
-									validator: assignment.validator_index(),
-									signature: approval_sig,
-								},
-							))
-						},
-					}
-				},
+				Some(approval_entry) =>
+					messages.extend(local_distribution_messages(block_hash, i as _, approval_entry)),
 				None => {
 					tracing::warn!(
 						target: LOG_TARGET,
@@ -1083,6 +1168,50 @@ fn distribution_messages_for_activation(
 	Ok(messages)
 }
 
+// The distribution messages for our own assignment and approval of a candidate, if any.
+pub(crate) fn local_distribution_messages(
+	block_hash: Hash,
+	candidate_index: CandidateIndex,
+	approval_entry: &ApprovalEntry,
+) -> Vec<ApprovalDistributionMessage> {
+	let mut messages = Vec::new();
+
+	match approval_entry.local_statements() {
+		(None, None) | (None, Some(_)) => {}, // second is impossible case.
+		(Some(assignment), None) => {
+			messages.push(ApprovalDistributionMessage::DistributeAssignment(
+				IndirectAssignmentCert {
+					block_hash,
+					validator: assignment.validator_index(),
+					cert: assignment.cert().clone(),
+				},
+				candidate_index,
+			));
+		},
+		(Some(assignment), Some(approval_sig)) => {
+			messages.push(ApprovalDistributionMessage::DistributeAssignment(
+				IndirectAssignmentCert {
+					block_hash,
+					validator: assignment.validator_index(),
+					cert: assignment.cert().clone(),
+				},
+				candidate_index,
+			));
+
+			messages.push(ApprovalDistributionMessage::DistributeApproval(
+				IndirectSignedApprovalVote {
+					block_hash,
+					candidate_index,
+					validator: assignment.validator_index(),
+					signature: approval_sig,
+				},
+			))
+		},
+	}
+
+	messages
+}
+
 // Handle an incoming signal from the overseer. Returns true if execution should conclude.
 async fn handle_from_overseer(
 	ctx: &mut (impl SubsystemContext<Message = ApprovalVotingMessage>
@@ -1093,10 +1222,12 @@ async fn handle_from_overseer(
 	x: FromOverseer<ApprovalVotingMessage>,
 	last_finalized_height: &mut Option<BlockNumber>,
 	wakeups: &mut Wakeups,
+	finality_lag_watchdog: Option<&mut FinalityLagWatchdog>,
 ) -> SubsystemResult<Vec<Action>> {
 	let actions = match x {
 		FromOverseer::Signal(OverseerSignal::ActiveLeaves(update)) => {
 			let mut actions = Vec::new();
+			let highest_activated = update.activated.iter().map(|a| a.number).max();
 
 			for activated in update.activated {
 				let head = activated.hash;
@@ -1147,6 +1278,14 @@ async fn handle_from_overseer(
 				}
 			}
 
+			if let (Some(watchdog), Some(head_number)) = (finality_lag_watchdog, highest_activated)
+			{
+				let messages = watchdog.check(db, head_number, metrics)?;
+				if !messages.is_empty() {
+					ctx.send_messages(messages.into_iter()).await;
+				}
+			}
+
 			actions
 		},
 		FromOverseer::Signal(OverseerSignal::BlockFinalized(block_hash, block_number)) => {
@@ -1189,12 +1328,76 @@ async fn handle_from_overseer(
 
 				Vec::new()
 			},
+			ApprovalVotingMessage::GetApprovalStatus(block_hash, res) => {
+				let _ = res.send(approval_status_of_block(state, db, block_hash)?);
+
+				Vec::new()
+			},
+			ApprovalVotingMessage::ForceTriggerAssignment(candidate_hash) =>
+				force_trigger_assignment(state, db, candidate_hash)?,
 		},
 	};
 
 	Ok(actions)
 }
 
+fn approval_status_of_block(
+	state: &State,
+	db: &OverlayedBackend<'_, impl Backend>,
+	block_hash: Hash,
+) -> SubsystemResult<Option<Vec<CandidateApprovalStatus>>> {
+	let block_entry = match db.load_block_entry(&block_hash)? {
+		Some(b) => b,
+		None => return Ok(None),
+	};
+
+	let block_tick = slot_number_to_tick(state.slot_duration_millis, block_entry.slot());
+	let no_show_duration = state.session_info(block_entry.session()).map(|session_info| {
+		slot_number_to_tick(
+			state.slot_duration_millis,
+			Slot::from(u64::from(session_info.no_show_slots)),
+		)
+	});
+	let tick_now = state.clock.tick_now();
+
+	let mut statuses = Vec::with_capacity(block_entry.candidates().len());
+	for (_, candidate_hash) in block_entry.candidates() {
+		let candidate_entry = match db.load_candidate_entry(candidate_hash)? {
+			Some(c) => c,
+			None => continue,
+		};
+		let approval_entry = match candidate_entry.approval_entry(&block_hash) {
+			Some(a) => a,
+			None => continue,
+		};
+
+		let approvals = candidate_entry.approvals();
+		let no_shows = no_show_duration.map_or(0, |no_show_duration| {
+			approval_checking::no_shows(
+				approval_entry,
+				approvals,
+				block_tick,
+				no_show_duration,
+				tick_now,
+			)
+		});
+
+		statuses.push(CandidateApprovalStatus {
+			candidate_hash: *candidate_hash,
+			approved: block_entry.is_candidate_approved(candidate_hash),
+			approvals: approvals.iter_ones().map(|i| ValidatorIndex(i as _)).collect(),
+			tranches: approval_entry
+				.tranches()
+				.iter()
+				.map(|t| (t.tranche(), t.assignments().iter().map(|(v, _)| *v).collect()))
+				.collect(),
+			no_shows,
+		});
+	}
+
+	Ok(Some(statuses))
+}
+
 async fn handle_approved_ancestor(
 	ctx: &mut (impl SubsystemContext + overseer::SubsystemContext),
 	db: &OverlayedBackend<'_, impl Backend>,
@@ -1993,6 +2196,92 @@ fn should_trigger_assignment(
 	}
 }
 
+/// Trigger our assignments for a candidate in all blocks it is included in, bypassing the
+/// tranche delay.
+///
+/// Assignments whose tranche is [`TICK_TOO_FAR_IN_FUTURE`] or more ahead of the current one are
+/// left to be triggered at their tick, peers would reject them as too far in the future.
+fn force_trigger_assignment(
+	state: &State,
+	db: &mut OverlayedBackend<'_, impl Backend>,
+	candidate_hash: CandidateHash,
+) -> SubsystemResult<Vec<Action>> {
+	let mut candidate_entry = match db.load_candidate_entry(&candidate_hash)? {
+		Some(c) => c,
+		None => return Ok(Vec::new()),
+	};
+
+	let mut actions = Vec::new();
+	let relay_blocks: Vec<Hash> = candidate_entry.block_assignments.keys().cloned().collect();
+	for relay_block in relay_blocks {
+		let block_entry = match db.load_block_entry(&relay_block)? {
+			Some(b) => b,
+			None => continue,
+		};
+
+		let index_in_candidate =
+			match block_entry.candidates().iter().position(|(_, h)| &candidate_hash == h) {
+				Some(i) => i,
+				None => continue,
+			};
+
+		let approval_entry = candidate_entry
+			.approval_entry_mut(&relay_block)
+			.expect("relay block taken from the candidate's approval entries; qed");
+		let backing_group = approval_entry.backing_group();
+
+		let tranche_now = state.clock.tranche_now(state.slot_duration_millis, block_entry.slot());
+		let too_far_in_future = tranche_now + TICK_TOO_FAR_IN_FUTURE as DelayTranche;
+		match approval_entry.our_assignment() {
+			Some(assignment) if assignment.tranche() >= too_far_in_future => {
+				tracing::debug!(
+					target: LOG_TARGET,
+					?candidate_hash,
+					block_hash = ?relay_block,
+					tranche = assignment.tranche(),
+					tranche_now,
+					"Not force triggering our assignment, its tranche is too far in the future.",
+				);
+				continue
+			},
+			_ => {},
+		}
+
+		if let Some((cert, val_index, tranche)) =
+			approval_entry.trigger_our_assignment(state.clock.tick_now())
+		{
+			tracing::debug!(
+				target: LOG_TARGET,
+				?candidate_hash,
+				block_hash = ?relay_block,
+				tranche,
+				"Force triggering our assignment.",
+			);
+
+			actions.push(Action::LaunchApproval {
+				candidate_hash,
+				indirect_cert: IndirectAssignmentCert {
+					block_hash: relay_block,
+					validator: val_index,
+					cert,
+				},
+				assignment_tranche: tranche,
+				relay_block_hash: relay_block,
+				candidate_index: index_in_candidate as _,
+				session: block_entry.session(),
+				candidate: candidate_entry.candidate_receipt().clone(),
+				backing_group,
+			});
+		}
+	}
+
+	if !actions.is_empty() {
+		db.write_candidate_entry(candidate_entry);
+	}
+
+	Ok(actions)
+}
+
 fn process_wakeup(
 	state: &State,
 	db: &mut OverlayedBackend<'_, impl Backend>,
@@ -2155,7 +2444,6 @@ async fn launch_approval(
 	backing_group: GroupIndex,
 ) -> SubsystemResult<RemoteHandle<ApprovalState>> {
 	let (a_tx, a_rx) = oneshot::channel();
-	let (code_tx, code_rx) = oneshot::channel();
 
 	// The background future returned by this function may
 	// be dropped before completing. This guard is used to ensure that the approval
@@ -2196,12 +2484,6 @@ async fn launch_approval(
 	))
 	.await;
 
-	ctx.send_message(RuntimeApiMessage::Request(
-		block_hash,
-		RuntimeApiRequest::ValidationCodeByHash(candidate.descriptor.validation_code_hash, code_tx),
-	))
-	.await;
-
 	let candidate = candidate.clone();
 	let metrics_guard = StaleGuard(Some(metrics));
 	let mut sender = ctx.sender().clone();
@@ -2256,32 +2538,16 @@ async fn launch_approval(
 			},
 		};
 
-		let validation_code = match code_rx.await {
-			Err(_) => return ApprovalState::failed(validator_index, candidate_hash),
-			Ok(Err(_)) => return ApprovalState::failed(validator_index, candidate_hash),
-			Ok(Ok(Some(code))) => code,
-			Ok(Ok(None)) => {
-				tracing::warn!(
-					target: LOG_TARGET,
-					"Validation code unavailable for block {:?} in the state of block {:?} (a recent descendant)",
-					candidate.descriptor.relay_parent,
-					block_hash,
-				);
-
-				// No dispute necessary, as this indicates that the chain is not behaving
-				// according to expectations.
-				metrics_guard.take().on_approval_unavailable();
-				return ApprovalState::failed(validator_index, candidate_hash)
-			},
-		};
-
 		let (val_tx, val_rx) = oneshot::channel();
 
+		// Candidate validation resolves and caches the code, so it isn't fetched and sent along
+		// for every approval. Code which can't be found is reported as an internal error and
+		// doesn't lead to a dispute.
 		sender
 			.send_message(
-				CandidateValidationMessage::ValidateFromExhaustive(
+				CandidateValidationMessage::ValidateFromCodeHash(
 					available_data.validation_data,
-					validation_code,
+					candidate.descriptor.validation_code_hash,
 					candidate.descriptor.clone(),
 					available_data.pov,
 					APPROVAL_EXECUTION_TIMEOUT,
@@ -102,8 +102,9 @@ pub fn canonicalize(
 	// All the block heights we visited but didn't necessarily delete everything from.
 	let mut visited_heights = HashMap::new();
 
-	// First visit everything before the height.
-	for i in range.0..canon_number {
+	// First visit everything before the height. Nothing is stored at or above the end of the
+	// range, which matters when finality moved far ahead while the node was down.
+	for i in range.0..std::cmp::min(canon_number, range.1) {
 		let at_height = overlay_db.load_blocks_at_height(&i)?;
 		overlay_db.delete_blocks_at_height(i);
 
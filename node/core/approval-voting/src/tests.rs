@@ -25,6 +25,7 @@ use selendra_node_primitives::{
 use selendra_node_subsystem::{
 	messages::{
 		AllMessages, ApprovalVotingMessage, AssignmentCheckResult, AvailabilityRecoveryMessage,
+		RuntimeApiMessage, RuntimeApiRequest,
 	},
 	ActivatedLeaf, ActiveLeavesUpdate, LeafStatus,
 };
@@ -33,7 +34,7 @@ use selendra_node_subsystem_util::TimeoutExt;
 use selendra_overseer::HeadSupportsParachains;
 use selendra_primitives::v1::{
 	CandidateCommitments, CandidateEvent, CoreIndex, GroupIndex, Header, Id as ParaId,
-	ValidationCode, ValidatorSignature,
+	ValidatorSignature,
 };
 use std::time::Duration;
 
@@ -484,6 +485,8 @@ fn test_harness<T: Future<Output = VirtualOverseer>>(
 			Config {
 				col_data: test_constants::TEST_CONFIG.col_data,
 				slot_duration_millis: SLOT_DURATION_MILLIS,
+				finality_lag_watchdog: Default::default(),
+				pending_work: Default::default(),
 			},
 			Arc::new(db),
 			Arc::new(keystore),
@@ -1489,6 +1492,74 @@ fn subsystem_accepts_and_imports_approval_after_assignment() {
 	});
 }
 
+#[test]
+fn subsystem_reports_approval_status_of_block() {
+	test_harness(HarnessConfig::default(), |test_harness| async move {
+		let TestHarness { mut virtual_overseer, sync_oracle_handle: _sync_oracle_handle, .. } =
+			test_harness;
+		assert_matches!(
+			overseer_recv(&mut virtual_overseer).await,
+			AllMessages::ChainApi(ChainApiMessage::FinalizedBlockNumber(rx)) => {
+				rx.send(Ok(0)).unwrap();
+			}
+		);
+
+		let block_hash = Hash::repeat_byte(0x01);
+		let candidate_index = 0;
+		let validator = ValidatorIndex(0);
+
+		ChainBuilder::new()
+			.add_block(
+				block_hash,
+				ChainBuilder::GENESIS_HASH,
+				1,
+				BlockConfig { slot: Slot::from(1), candidates: None, session_info: None },
+			)
+			.build(&mut virtual_overseer)
+			.await;
+
+		let rx = check_and_import_assignment(
+			&mut virtual_overseer,
+			block_hash,
+			candidate_index,
+			validator,
+		)
+		.await;
+		assert_eq!(rx.await, Ok(AssignmentCheckResult::Accepted));
+
+		let (tx, rx) = oneshot::channel();
+		overseer_send(
+			&mut virtual_overseer,
+			FromOverseer::Communication {
+				msg: ApprovalVotingMessage::GetApprovalStatus(block_hash, tx),
+			},
+		)
+		.await;
+
+		let statuses = rx.await.unwrap().unwrap();
+		assert_eq!(statuses.len(), 1);
+		assert!(!statuses[0].approved);
+		assert!(statuses[0].approvals.is_empty());
+		assert!(statuses[0]
+			.tranches
+			.iter()
+			.any(|(_, validators)| validators == &vec![validator]));
+		assert_eq!(statuses[0].no_shows, 0);
+
+		let (tx, rx) = oneshot::channel();
+		overseer_send(
+			&mut virtual_overseer,
+			FromOverseer::Communication {
+				msg: ApprovalVotingMessage::GetApprovalStatus(Hash::repeat_byte(0x02), tx),
+			},
+		)
+		.await;
+		assert!(rx.await.unwrap().is_none());
+
+		virtual_overseer
+	});
+}
+
 #[test]
 fn subsystem_second_approval_import_only_schedules_wakeups() {
 	test_harness(HarnessConfig::default(), |test_harness| async move {
@@ -2389,7 +2460,6 @@ pub async fn handle_double_assignment_import(
 	);
 
 	recover_available_data(virtual_overseer).await;
-	fetch_validation_code(virtual_overseer).await;
 
 	let first_message = virtual_overseer.recv().await;
 	let second_message = virtual_overseer.recv().await;
@@ -2401,9 +2471,14 @@ pub async fn handle_double_assignment_import(
 			) => {
 				assert_eq!(candidate_index, c_index);
 			},
-			AllMessages::CandidateValidation(
-				CandidateValidationMessage::ValidateFromExhaustive(_, _, _, _, timeout, tx),
-			) if timeout == APPROVAL_EXECUTION_TIMEOUT => {
+			AllMessages::CandidateValidation(CandidateValidationMessage::ValidateFromCodeHash(
+				_,
+				_,
+				_,
+				_,
+				timeout,
+				tx,
+			)) if timeout == APPROVAL_EXECUTION_TIMEOUT => {
 				tx.send(Ok(ValidationResult::Valid(Default::default(), Default::default())))
 					.unwrap();
 			},
@@ -2415,26 +2490,6 @@ pub async fn handle_double_assignment_import(
 	assert!(overseer_recv(virtual_overseer).timeout(TIMEOUT / 2).await.is_none());
 }
 
-/// Handles validation code fetch, returns the received relay parent hash.
-async fn fetch_validation_code(virtual_overseer: &mut VirtualOverseer) -> Hash {
-	let validation_code = ValidationCode(Vec::new());
-
-	assert_matches!(
-		virtual_overseer.recv().await,
-		AllMessages::RuntimeApi(RuntimeApiMessage::Request(
-			hash,
-			RuntimeApiRequest::ValidationCodeByHash(
-				_,
-				tx,
-			)
-		)) => {
-			tx.send(Ok(Some(validation_code))).unwrap();
-			hash
-		},
-		"overseer did not receive runtime API request for validation code",
-	)
-}
-
 async fn recover_available_data(virtual_overseer: &mut VirtualOverseer) {
 	let pov_block = PoV { block_data: BlockData(Vec::new()) };
 
@@ -2797,6 +2852,124 @@ fn subsystem_assignment_not_triggered_if_at_maximum_but_clock_is_before_with_dri
 	});
 }
 
+#[test]
+fn subsystem_force_trigger_assignment_bypasses_tranche() {
+	force_trigger_assignment_test(10, true);
+}
+
+#[test]
+fn subsystem_force_trigger_assignment_skips_tranche_too_far_in_future() {
+	force_trigger_assignment_test(TICK_TOO_FAR_IN_FUTURE as DelayTranche, false);
+}
+
+fn force_trigger_assignment_test(tranche: DelayTranche, should_be_triggered: bool) {
+	let assignment_criteria = Box::new(MockAssignmentCriteria(
+		move || {
+			let mut assignments = HashMap::new();
+			let _ = assignments.insert(
+				CoreIndex(0),
+				approval_db::v1::OurAssignment {
+					cert: garbage_assignment_cert(AssignmentCertKind::RelayVRFModulo { sample: 0 }),
+					tranche,
+					validator_index: ValidatorIndex(0),
+					triggered: false,
+				}
+				.into(),
+			);
+			assignments
+		},
+		|_| Ok(0),
+	));
+	let config = HarnessConfigBuilder::default().assignment_criteria(assignment_criteria).build();
+	let store = config.backend();
+
+	test_harness(config, |test_harness| async move {
+		let TestHarness { mut virtual_overseer, sync_oracle_handle: _sync_oracle_handle, .. } =
+			test_harness;
+
+		assert_matches!(
+			overseer_recv(&mut virtual_overseer).await,
+			AllMessages::ChainApi(ChainApiMessage::FinalizedBlockNumber(rx)) => {
+				rx.send(Ok(0)).unwrap();
+			}
+		);
+
+		let block_hash = Hash::repeat_byte(0x01);
+		let candidate_receipt = dummy_candidate_receipt(block_hash);
+		let candidate_hash = candidate_receipt.hash();
+
+		let validators = vec![
+			Sr25519Keyring::Alice,
+			Sr25519Keyring::Bob,
+			Sr25519Keyring::Charlie,
+			Sr25519Keyring::Dave,
+			Sr25519Keyring::Eve,
+			Sr25519Keyring::Ferdie,
+		];
+		let session_info = SessionInfo {
+			validator_groups: vec![
+				vec![ValidatorIndex(0), ValidatorIndex(1)],
+				vec![ValidatorIndex(2), ValidatorIndex(3)],
+				vec![ValidatorIndex(4), ValidatorIndex(5)],
+			],
+			..session_info(&validators)
+		};
+
+		ChainBuilder::new()
+			.add_block(
+				block_hash,
+				ChainBuilder::GENESIS_HASH,
+				1,
+				BlockConfig {
+					slot: Slot::from(1),
+					candidates: Some(vec![(candidate_receipt, CoreIndex(0), GroupIndex(2))]),
+					session_info: Some(session_info),
+				},
+			)
+			.build(&mut virtual_overseer)
+			.await;
+
+		let our_assignment_triggered = || {
+			let candidate_entry = store.load_candidate_entry(&candidate_hash).unwrap().unwrap();
+			candidate_entry
+				.approval_entry(&block_hash)
+				.unwrap()
+				.our_assignment()
+				.unwrap()
+				.triggered()
+		};
+		assert!(!our_assignment_triggered());
+
+		overseer_send(
+			&mut virtual_overseer,
+			FromOverseer::Communication {
+				msg: ApprovalVotingMessage::ForceTriggerAssignment(candidate_hash),
+			},
+		)
+		.await;
+
+		if should_be_triggered {
+			assert_matches!(
+				overseer_recv(&mut virtual_overseer).await,
+				AllMessages::ApprovalDistribution(ApprovalDistributionMessage::DistributeAssignment(
+					cert,
+					0,
+				)) => {
+					assert_eq!(cert.block_hash, block_hash);
+					assert_eq!(cert.validator, ValidatorIndex(0));
+				}
+			);
+		} else {
+			assert!(overseer_recv_with_timeout(&mut virtual_overseer, Duration::from_millis(200))
+				.await
+				.is_none());
+		}
+		assert_eq!(our_assignment_triggered(), should_be_triggered);
+
+		virtual_overseer
+	});
+}
+
 #[test]
 fn pre_covers_dont_stall_approval() {
 	// A, B are tranche 0.
@@ -3149,3 +3322,77 @@ fn waits_until_approving_assignments_are_old_enough() {
 		virtual_overseer
 	});
 }
+
+#[test]
+fn finality_lag_watchdog_reports_stuck_blocks_once() {
+	let block_hash = Hash::repeat_byte(0x01);
+	let candidate_receipt = dummy_candidate_receipt(block_hash);
+	let candidate_hash = candidate_receipt.hash();
+
+	let store = TestStore::default();
+	let mut db = OverlayedBackend::new(&store);
+	db.write_stored_block_range(StoredBlockRange(1, 2));
+	db.write_blocks_at_height(1, vec![block_hash]);
+	db.write_block_entry(
+		approval_db::v1::BlockEntry {
+			block_hash,
+			parent_hash: Default::default(),
+			block_number: 1,
+			session: 1,
+			slot: Slot::from(1),
+			relay_vrf_story: [0u8; 32],
+			candidates: vec![(CoreIndex(0), candidate_hash)],
+			approved_bitfield: bitvec::bitvec![u8, bitvec::order::Lsb0; 0; 1],
+			children: Vec::new(),
+		}
+		.into(),
+	);
+	db.write_candidate_entry(
+		approval_db::v1::CandidateEntry {
+			candidate: candidate_receipt,
+			session: 1,
+			block_assignments: vec![(
+				block_hash,
+				approval_db::v1::ApprovalEntry {
+					tranches: Vec::new(),
+					backing_group: GroupIndex(0),
+					our_assignment: Some(approval_db::v1::OurAssignment {
+						cert: garbage_assignment_cert(AssignmentCertKind::RelayVRFModulo {
+							sample: 0,
+						}),
+						tranche: 0,
+						validator_index: ValidatorIndex(0),
+						triggered: true,
+					}),
+					our_approval_sig: None,
+					assignments: bitvec::bitvec![u8, bitvec::order::Lsb0; 0; 1],
+					approved: false,
+				},
+			)]
+			.into_iter()
+			.collect(),
+			approvals: bitvec::bitvec![u8, bitvec::order::Lsb0; 0; 1],
+		}
+		.into(),
+	);
+
+	let mut watchdog = FinalityLagWatchdog::new(FinalityLagWatchdogConfig {
+		lag_threshold: 10,
+		redistribute: true,
+	});
+	let metrics = Metrics::default();
+
+	// Block 1 is not approved, so the lag is the head number.
+	assert!(watchdog.check(&db, 10, &metrics).unwrap().is_empty());
+
+	assert_matches!(
+		&watchdog.check(&db, 11, &metrics).unwrap()[..],
+		[ApprovalDistributionMessage::DistributeAssignment(cert, 0)] => {
+			assert_eq!(cert.block_hash, block_hash);
+			assert_eq!(cert.validator, ValidatorIndex(0));
+		}
+	);
+
+	// The same stuck block is not reported again.
+	assert!(watchdog.check(&db, 12, &metrics).unwrap().is_empty());
+}
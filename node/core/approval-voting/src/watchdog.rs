@@ -0,0 +1,190 @@
+// Copyright 2020-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A watchdog raising an alarm when approval checking lags behind the chain head.
+//!
+//! Finality can't progress past the lowest block which isn't fully approved. The watchdog
+//! tracks the gap between the highest block below that one and the chain head, and once the gap
+//! exceeds a threshold it reports the candidates holding up finality.
+
+use selendra_node_subsystem::{messages::ApprovalDistributionMessage, SubsystemResult};
+use selendra_primitives::v1::{BlockNumber, CandidateHash, Hash, Id as ParaId};
+
+use crate::{
+	backend::{Backend, OverlayedBackend},
+	local_distribution_messages, Metrics, LOG_TARGET,
+};
+
+/// The default number of blocks approval checking may lag behind the chain head.
+pub const DEFAULT_FINALITY_LAG_THRESHOLD: BlockNumber = 50;
+
+/// Configuration of the finality lag watchdog.
+#[derive(Debug, Clone)]
+pub struct FinalityLagWatchdogConfig {
+	/// The number of blocks the highest approved block may lag behind the chain head before the
+	/// alarm is raised.
+	pub lag_threshold: BlockNumber,
+	/// Whether to hand our assignments and approvals of the candidates holding up finality to
+	/// approval distribution again when the alarm is raised.
+	pub redistribute: bool,
+}
+
+impl Default for FinalityLagWatchdogConfig {
+	fn default() -> Self {
+		FinalityLagWatchdogConfig {
+			lag_threshold: DEFAULT_FINALITY_LAG_THRESHOLD,
+			redistribute: false,
+		}
+	}
+}
+
+/// A block which is not fully approved, together with its unapproved candidates.
+struct StuckBlock {
+	block_hash: Hash,
+	block_number: BlockNumber,
+	unapproved: Vec<(CandidateHash, ParaId)>,
+}
+
+pub(crate) struct FinalityLagWatchdog {
+	config: FinalityLagWatchdogConfig,
+	// The stuck blocks the alarm was last raised for, to avoid reporting them on every head.
+	reported: Option<Vec<Hash>>,
+}
+
+impl FinalityLagWatchdog {
+	pub(crate) fn new(config: FinalityLagWatchdogConfig) -> Self {
+		FinalityLagWatchdog { config, reported: None }
+	}
+
+	/// Check the approval lag as of a new head with the given number.
+	///
+	/// Returns the distribution messages to send again, which are only produced when the alarm is
+	/// raised for a new set of stuck blocks and redistribution is enabled.
+	pub(crate) fn check(
+		&mut self,
+		db: &OverlayedBackend<'_, impl Backend>,
+		head_number: BlockNumber,
+		metrics: &Metrics,
+	) -> SubsystemResult<Vec<ApprovalDistributionMessage>> {
+		let stuck_blocks = lowest_unapproved_blocks(db)?;
+
+		let highest_approved = match stuck_blocks.first() {
+			Some(block) => block.block_number.saturating_sub(1),
+			None => head_number,
+		};
+		let lag = head_number.saturating_sub(highest_approved);
+		let alarm = lag > self.config.lag_threshold;
+
+		metrics.on_finality_lag(lag, alarm);
+
+		if !alarm {
+			if self.reported.take().is_some() {
+				tracing::info!(target: LOG_TARGET, lag, "Approval checking caught up");
+			}
+
+			return Ok(Vec::new())
+		}
+
+		let stuck_hashes: Vec<Hash> = stuck_blocks.iter().map(|b| b.block_hash).collect();
+		if self.reported.as_ref() == Some(&stuck_hashes) {
+			return Ok(Vec::new())
+		}
+		self.reported = Some(stuck_hashes);
+
+		let mut messages = Vec::new();
+		for block in stuck_blocks {
+			tracing::warn!(
+				target: LOG_TARGET,
+				lag,
+				block_hash = ?block.block_hash,
+				block_number = block.block_number,
+				unapproved = ?block.unapproved,
+				"Approval checking lags behind the chain head",
+			);
+
+			if self.config.redistribute {
+				messages.extend(redistribution_messages(db, &block)?);
+			}
+		}
+
+		Ok(messages)
+	}
+}
+
+// The unfinalized blocks at the lowest height where not all blocks are fully approved.
+fn lowest_unapproved_blocks(
+	db: &OverlayedBackend<'_, impl Backend>,
+) -> SubsystemResult<Vec<StuckBlock>> {
+	let range = match db.load_stored_blocks()? {
+		Some(range) => range,
+		None => return Ok(Vec::new()),
+	};
+
+	for height in range.0..range.1 {
+		let mut stuck_blocks = Vec::new();
+		for block_hash in db.load_blocks_at_height(&height)? {
+			let block_entry = match db.load_block_entry(&block_hash)? {
+				Some(b) if !b.is_fully_approved() => b,
+				_ => continue,
+			};
+
+			let mut unapproved = Vec::new();
+			for candidate_hash in block_entry.unapproved_candidates() {
+				if let Some(candidate_entry) = db.load_candidate_entry(&candidate_hash)? {
+					unapproved.push((
+						candidate_hash,
+						candidate_entry.candidate_receipt().descriptor.para_id,
+					));
+				}
+			}
+
+			stuck_blocks.push(StuckBlock { block_hash, block_number: height, unapproved });
+		}
+
+		if !stuck_blocks.is_empty() {
+			return Ok(stuck_blocks)
+		}
+	}
+
+	Ok(Vec::new())
+}
+
+fn redistribution_messages(
+	db: &OverlayedBackend<'_, impl Backend>,
+	block: &StuckBlock,
+) -> SubsystemResult<Vec<ApprovalDistributionMessage>> {
+	let block_entry = match db.load_block_entry(&block.block_hash)? {
+		Some(b) => b,
+		None => return Ok(Vec::new()),
+	};
+
+	let mut messages = Vec::new();
+	for (i, (_, candidate_hash)) in block_entry.candidates().iter().enumerate() {
+		if block_entry.is_candidate_approved(candidate_hash) {
+			continue
+		}
+
+		let candidate_entry = match db.load_candidate_entry(candidate_hash)? {
+			Some(c) => c,
+			None => continue,
+		};
+		if let Some(approval_entry) = candidate_entry.approval_entry(&block.block_hash) {
+			messages.extend(local_distribution_messages(block.block_hash, i as _, approval_entry));
+		}
+	}
+
+	Ok(messages)
+}
@@ -0,0 +1,207 @@
+// Copyright 2020-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! An offline integrity check of the availability store.
+//!
+//! The stored data and chunks of a candidate are checked against the erasure root recorded from
+//! its receipt. Candidates stored before erasure roots were recorded can't be checked and are
+//! skipped.
+
+use std::fmt;
+
+use selendra_primitives::v1::{BlakeTwo256, HashT};
+
+use super::*;
+
+/// A problem found in the availability store.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Corruption {
+	/// The meta information under the given key couldn't be decoded.
+	Meta(Vec<u8>),
+	/// The erasure root recorded for the candidate couldn't be decoded.
+	ErasureRoot(CandidateHash),
+	/// The meta information claims the available data is stored, but it is missing.
+	MissingData(CandidateHash),
+	/// The available data couldn't be decoded, erasure coded or doesn't match the erasure root.
+	Data(CandidateHash),
+	/// The meta information claims the chunk is stored, but it is missing.
+	MissingChunk(CandidateHash, ValidatorIndex),
+	/// The chunk couldn't be decoded or doesn't match the erasure root.
+	Chunk(CandidateHash, ValidatorIndex),
+}
+
+impl fmt::Display for Corruption {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Corruption::Meta(key) => write!(f, "undecodable meta information at key {:?}", key),
+			Corruption::ErasureRoot(hash) => write!(f, "undecodable erasure root of {:?}", hash),
+			Corruption::MissingData(hash) => write!(f, "missing available data of {:?}", hash),
+			Corruption::Data(hash) => write!(f, "corrupted available data of {:?}", hash),
+			Corruption::MissingChunk(hash, index) =>
+				write!(f, "missing chunk {} of {:?}", index.0, hash),
+			Corruption::Chunk(hash, index) =>
+				write!(f, "corrupted chunk {} of {:?}", index.0, hash),
+		}
+	}
+}
+
+/// The outcome of [`check_integrity`].
+#[derive(Debug, Default)]
+pub struct IntegrityReport {
+	/// The number of candidates checked.
+	pub candidates_checked: usize,
+	/// The number of candidates skipped because no erasure root is recorded for them.
+	pub candidates_skipped: usize,
+	/// The problems found.
+	pub corruptions: Vec<Corruption>,
+	/// The number of candidates whose data was pruned.
+	pub candidates_pruned: usize,
+}
+
+/// Check the stored data and chunks of every candidate in the availability store.
+///
+/// When `prune` is set, all data of a candidate with any problem is deleted, so that it can be
+/// fetched again. This must not be run against a database in use by a running node.
+pub fn check_integrity(
+	db: &Arc<dyn Database>,
+	config: &Config,
+	prune: bool,
+) -> Result<IntegrityReport, Error> {
+	let mut report = IntegrityReport::default();
+	let mut tx = DBTransaction::new();
+
+	for (key, value) in db.iter_with_prefix(config.col_meta, META_PREFIX) {
+		let candidate_hash = CandidateHash::decode(&mut &key[META_PREFIX.len()..]);
+		let meta = CandidateMeta::decode(&mut &value[..]);
+		let (candidate_hash, meta) = match (candidate_hash, meta) {
+			(Ok(candidate_hash), Ok(meta)) => (candidate_hash, meta),
+			_ => {
+				report.candidates_checked += 1;
+				report.corruptions.push(Corruption::Meta(key.to_vec()));
+				if prune {
+					// Without the meta information we don't know which chunks are stored, they
+					// are left in place.
+					tx.delete(config.col_meta, &key);
+					report.candidates_pruned += 1;
+				}
+				continue
+			},
+		};
+
+		let corruptions = match load_erasure_root(db, config, &candidate_hash) {
+			Ok(Some(erasure_root)) =>
+				check_candidate(db, config, candidate_hash, &meta, erasure_root)?,
+			Ok(None) => {
+				report.candidates_skipped += 1;
+				continue
+			},
+			Err(Error::Codec(_)) => vec![Corruption::ErasureRoot(candidate_hash)],
+			Err(e) => return Err(e),
+		};
+
+		report.candidates_checked += 1;
+		if corruptions.is_empty() {
+			continue
+		}
+
+		report.corruptions.extend(corruptions);
+
+		if prune {
+			delete_candidate(&mut tx, config, &candidate_hash, &meta);
+			report.candidates_pruned += 1;
+		}
+	}
+
+	db.write(tx)?;
+	Ok(report)
+}
+
+fn check_candidate(
+	db: &Arc<dyn Database>,
+	config: &Config,
+	candidate_hash: CandidateHash,
+	meta: &CandidateMeta,
+	erasure_root: Hash,
+) -> Result<Vec<Corruption>, Error> {
+	let mut corruptions = Vec::new();
+	let n_validators = meta.chunks_stored.len();
+
+	if meta.data_available {
+		match load_available_data(db, config, &candidate_hash) {
+			Ok(Some(available_data)) =>
+				match erasure::obtain_chunks_v1(n_validators, &available_data) {
+					Ok(chunks) if erasure::branches(&chunks).root() == erasure_root => {},
+					_ => corruptions.push(Corruption::Data(candidate_hash)),
+				},
+			Ok(None) => corruptions.push(Corruption::MissingData(candidate_hash)),
+			Err(Error::Codec(_)) => corruptions.push(Corruption::Data(candidate_hash)),
+			Err(e) => return Err(e),
+		}
+	}
+
+	for (i, b) in meta.chunks_stored.iter().enumerate() {
+		if !*b {
+			continue
+		}
+
+		let index = ValidatorIndex(i as _);
+		let chunk = match load_chunk(db, config, &candidate_hash, index) {
+			Ok(Some(chunk)) => chunk,
+			Ok(None) => {
+				corruptions.push(Corruption::MissingChunk(candidate_hash, index));
+				continue
+			},
+			Err(Error::Codec(_)) => {
+				corruptions.push(Corruption::Chunk(candidate_hash, index));
+				continue
+			},
+			Err(e) => return Err(e),
+		};
+
+		match erasure::branch_hash(&erasure_root, &chunk.proof, index.0 as usize) {
+			Ok(hash) if chunk.index == index && hash == BlakeTwo256::hash(&chunk.chunk) => {},
+			_ => corruptions.push(Corruption::Chunk(candidate_hash, index)),
+		}
+	}
+
+	Ok(corruptions)
+}
+
+fn delete_candidate(
+	tx: &mut DBTransaction,
+	config: &Config,
+	candidate_hash: &CandidateHash,
+	meta: &CandidateMeta,
+) {
+	delete_meta(tx, config, candidate_hash);
+
+	if meta.data_available {
+		delete_available_data(tx, config, candidate_hash);
+	}
+
+	for (i, b) in meta.chunks_stored.iter().enumerate() {
+		if *b {
+			delete_chunk(tx, config, candidate_hash, ValidatorIndex(i as _));
+		}
+	}
+
+	// The pruning key is left in place, pruning copes with the meta information being gone.
+	if let State::Unfinalized(_, blocks) = &meta.state {
+		for (block_number, block_hash) in blocks {
+			delete_unfinalized_inclusion(tx, config, block_number.0, block_hash, candidate_hash);
+		}
+	}
+}
@@ -44,8 +44,12 @@ use selendra_subsystem::{
 	SubsystemError,
 };
 
+mod integrity;
 mod metrics;
-pub use self::metrics::*;
+pub use self::{
+	integrity::{check_integrity, Corruption, IntegrityReport},
+	metrics::*,
+};
 
 #[cfg(test)]
 mod tests;
@@ -57,8 +61,10 @@ const LOG_TARGET: &str = "parachain::availability";
 const AVAILABLE_PREFIX: &[u8; 9] = b"available";
 const CHUNK_PREFIX: &[u8; 5] = b"chunk";
 const META_PREFIX: &[u8; 4] = b"meta";
+const ERASURE_ROOT_PREFIX: &[u8; 12] = b"erasure_root";
 const UNFINALIZED_PREFIX: &[u8; 11] = b"unfinalized";
 const PRUNE_BY_TIME_PREFIX: &[u8; 13] = b"prune_by_time";
+const PRUNE_BY_FINALIZED_PREFIX: &[u8; 18] = b"prune_by_finalized";
 
 // We have some keys we want to map to empty values because existence of the key is enough. We use this because
 // rocksdb doesn't support empty values.
@@ -70,6 +76,12 @@ const KEEP_UNAVAILABLE_FOR: Duration = Duration::from_secs(60 * 60);
 /// Finalized data is kept for 25 hours.
 const KEEP_FINALIZED_FOR: Duration = Duration::from_secs(25 * 60 * 60);
 
+/// The smallest [`Config::keep_finalized_blocks`]: [`KEEP_FINALIZED_FOR`] at the relay chain block
+/// time of 6 seconds. Pruning sooner risks losing the data of candidates which can still be
+/// disputed.
+pub const MIN_KEEP_FINALIZED_BLOCKS: BlockNumber =
+	(KEEP_FINALIZED_FOR.as_secs() / 6) as BlockNumber;
+
 /// The pruning interval.
 const PRUNING_INTERVAL: Duration = Duration::from_secs(60 * 5);
 
@@ -144,11 +156,28 @@ enum State {
 }
 
 // Meta information about a candidate.
-#[derive(Debug, Encode, Decode)]
+#[derive(Debug, Encode)]
 struct CandidateMeta {
 	state: State,
 	data_available: bool,
 	chunks_stored: BitVec<u8, BitOrderLsb0>,
+	// The encoded size of the available data and chunks stored for the candidate.
+	stored_bytes: u64,
+}
+
+impl Decode for CandidateMeta {
+	fn decode<I: Input>(input: &mut I) -> Result<Self, CodecError> {
+		let state = State::decode(input)?;
+		let data_available = bool::decode(input)?;
+		let chunks_stored = BitVec::decode(input)?;
+		// Metas written before the stored size was recorded end here.
+		let stored_bytes = match input.remaining_len()? {
+			Some(0) => 0,
+			_ => u64::decode(input)?,
+		};
+
+		Ok(Self { state, data_available, chunks_stored, stored_bytes })
+	}
 }
 
 fn query_inner<D: Decode>(
@@ -248,9 +277,34 @@ fn write_meta(tx: &mut DBTransaction, config: &Config, hash: &CandidateHash, met
 
 fn delete_meta(tx: &mut DBTransaction, config: &Config, hash: &CandidateHash) {
 	let key = (META_PREFIX, hash).encode();
+	tx.delete(config.col_meta, &key[..]);
+
+	// The erasure root lives and dies with the meta information.
+	let key = (ERASURE_ROOT_PREFIX, hash).encode();
 	tx.delete(config.col_meta, &key[..])
 }
 
+fn load_erasure_root(
+	db: &Arc<dyn Database>,
+	config: &Config,
+	hash: &CandidateHash,
+) -> Result<Option<Hash>, Error> {
+	let key = (ERASURE_ROOT_PREFIX, hash).encode();
+
+	query_inner(db, config.col_meta, &key)
+}
+
+fn write_erasure_root(
+	tx: &mut DBTransaction,
+	config: &Config,
+	hash: &CandidateHash,
+	erasure_root: &Hash,
+) {
+	let key = (ERASURE_ROOT_PREFIX, hash).encode();
+
+	tx.put_vec(config.col_meta, &key, erasure_root.encode());
+}
+
 fn delete_unfinalized_height(tx: &mut DBTransaction, config: &Config, block_number: BlockNumber) {
 	let prefix = (UNFINALIZED_PREFIX, BEBlockNumber(block_number)).encode();
 	tx.delete_prefix(config.col_meta, &prefix);
@@ -290,6 +344,16 @@ fn write_pruning_key(
 	tx.put(config.col_meta, &key, TOMBSTONE_VALUE);
 }
 
+fn write_finalized_pruning_key(
+	tx: &mut DBTransaction,
+	config: &Config,
+	n: BlockNumber,
+	h: &CandidateHash,
+) {
+	let key = (PRUNE_BY_FINALIZED_PREFIX, BEBlockNumber(n), h).encode();
+	tx.put(config.col_meta, &key, TOMBSTONE_VALUE);
+}
+
 fn finalized_block_range(finalized: BlockNumber) -> (Vec<u8>, Vec<u8>) {
 	// We use big-endian encoding to iterate in ascending order.
 	let start = UNFINALIZED_PREFIX.encode();
@@ -316,6 +380,13 @@ fn pruning_range(now: impl Into<BETimestamp>) -> (Vec<u8>, Vec<u8>) {
 	(start, end)
 }
 
+fn finalized_pruning_range(finalized: BlockNumber) -> (Vec<u8>, Vec<u8>) {
+	let start = PRUNE_BY_FINALIZED_PREFIX.encode();
+	let end = (PRUNE_BY_FINALIZED_PREFIX, BEBlockNumber(finalized.saturating_add(1))).encode();
+
+	(start, end)
+}
+
 fn decode_unfinalized_key(s: &[u8]) -> Result<(BlockNumber, Hash, CandidateHash), CodecError> {
 	if !s.starts_with(UNFINALIZED_PREFIX) {
 		return Err("missing magic string".into())
@@ -334,6 +405,15 @@ fn decode_pruning_key(s: &[u8]) -> Result<(Duration, CandidateHash), CodecError>
 		.map(|(t, ch)| (t.into(), ch))
 }
 
+fn decode_finalized_pruning_key(s: &[u8]) -> Result<(BlockNumber, CandidateHash), CodecError> {
+	if !s.starts_with(PRUNE_BY_FINALIZED_PREFIX) {
+		return Err("missing magic string".into())
+	}
+
+	<(BEBlockNumber, CandidateHash)>::decode(&mut &s[PRUNE_BY_FINALIZED_PREFIX.len()..])
+		.map(|(n, ch)| (n.0, ch))
+}
+
 #[derive(Debug, thiserror::Error)]
 #[allow(missing_docs)]
 pub enum Error {
@@ -424,6 +504,10 @@ pub struct Config {
 	pub col_data: u32,
 	/// The column family for availability store meta information.
 	pub col_meta: u32,
+	/// Additionally prune the data of candidates once they've been finalized for this many
+	/// blocks, which bounds the data kept while finalizing faster than the block time, e.g. when
+	/// catching up. Must be at least [`MIN_KEEP_FINALIZED_BLOCKS`].
+	pub keep_finalized_blocks: Option<BlockNumber>,
 }
 
 trait Clock: Send + Sync {
@@ -584,6 +668,10 @@ where
 						hash,
 						number,
 					).await?;
+
+					if subsystem.config.keep_finalized_blocks.is_some() {
+						prune_finalized(&subsystem.db, &subsystem.config, number, &subsystem.metrics)?;
+					}
 				}
 				FromOverseer::Communication { msg } => {
 					let _timer = subsystem.metrics.time_process_message();
@@ -597,7 +685,7 @@ where
 			*next_pruning = Delay::new(subsystem.pruning_config.pruning_interval).fuse();
 
 			let _timer = subsystem.metrics.time_pruning();
-			prune_all(&subsystem.db, &subsystem.config, &*subsystem.clock)?;
+			prune_all(&subsystem.db, &subsystem.config, &*subsystem.clock, &subsystem.metrics)?;
 		}
 	}
 
@@ -728,6 +816,7 @@ fn note_block_backed(
 			state: State::Unavailable(now.into()),
 			data_available: false,
 			chunks_stored: bitvec::bitvec![u8, BitOrderLsb0; 0; n_validators],
+			stored_bytes: 0,
 		};
 
 		let prune_at = now + pruning_config.keep_unavailable_for;
@@ -736,6 +825,10 @@ fn note_block_backed(
 		write_meta(db_transaction, config, &candidate_hash, &meta);
 	}
 
+	// The root from the receipt is authoritative, even if one was computed from data stored
+	// before the candidate was backed.
+	write_erasure_root(db_transaction, config, &candidate_hash, &candidate.descriptor.erasure_root);
+
 	Ok(())
 }
 
@@ -991,6 +1084,14 @@ fn update_blocks_at_finalized_height(
 				now + subsystem.pruning_config.keep_finalized_for,
 				&candidate_hash,
 			);
+			if let Some(keep_finalized_blocks) = subsystem.config.keep_finalized_blocks {
+				write_finalized_pruning_key(
+					db_transaction,
+					&subsystem.config,
+					block_number.saturating_add(keep_finalized_blocks),
+					&candidate_hash,
+				);
+			}
 		} else {
 			meta.state = match meta.state {
 				State::Finalized(_) => continue,   // sanity.
@@ -1144,6 +1245,7 @@ fn store_chunk(
 		Some(true) => return Ok(true), // already stored.
 		Some(false) => {
 			meta.chunks_stored.set(chunk.index.0 as usize, true);
+			meta.stored_bytes += chunk.encoded_size() as u64;
 
 			write_chunk(&mut tx, config, &candidate_hash, chunk.index, &chunk);
 			write_meta(&mut tx, config, &candidate_hash, &meta);
@@ -1171,13 +1273,13 @@ fn store_available_data(
 ) -> Result<(), Error> {
 	let mut tx = DBTransaction::new();
 
-	let mut meta = match load_meta(&subsystem.db, &subsystem.config, &candidate_hash)? {
+	let (mut meta, backed) = match load_meta(&subsystem.db, &subsystem.config, &candidate_hash)? {
 		Some(m) => {
 			if m.data_available {
 				return Ok(()) // already stored.
 			}
 
-			m
+			(m, true)
 		},
 		None => {
 			let now = subsystem.clock.now()?;
@@ -1186,17 +1288,26 @@ fn store_available_data(
 			let prune_at = now + subsystem.pruning_config.keep_unavailable_for;
 			write_pruning_key(&mut tx, &subsystem.config, prune_at, &candidate_hash);
 
-			CandidateMeta {
+			let meta = CandidateMeta {
 				state: State::Unavailable(now.into()),
 				data_available: false,
 				chunks_stored: BitVec::new(),
-			}
+				stored_bytes: 0,
+			};
+
+			(meta, false)
 		},
 	};
 
 	let chunks = erasure::obtain_chunks_v1(n_validators, &available_data)?;
 	let branches = erasure::branches(chunks.as_ref());
 
+	if !backed {
+		// There's no receipt to take the erasure root from yet. It is overwritten once the
+		// candidate is backed.
+		write_erasure_root(&mut tx, &subsystem.config, &candidate_hash, &branches.root());
+	}
+
 	let erasure_chunks = chunks.iter().zip(branches.map(|(proof, _)| proof)).enumerate().map(
 		|(index, (chunk, proof))| ErasureChunk {
 			chunk: chunk.clone(),
@@ -1206,9 +1317,15 @@ fn store_available_data(
 	);
 
 	for chunk in erasure_chunks {
+		// Chunks stored before are overwritten with the same content, their size is already
+		// accounted for.
+		if !meta.chunks_stored.get(chunk.index.0 as usize).map_or(false, |b| *b) {
+			meta.stored_bytes += chunk.encoded_size() as u64;
+		}
 		write_chunk(&mut tx, &subsystem.config, &candidate_hash, chunk.index, &chunk);
 	}
 
+	meta.stored_bytes += available_data.encoded_size() as u64;
 	meta.data_available = true;
 	meta.chunks_stored = bitvec::bitvec![u8, BitOrderLsb0; 1; n_validators];
 
@@ -1222,11 +1339,18 @@ fn store_available_data(
 	Ok(())
 }
 
-fn prune_all(db: &Arc<dyn Database>, config: &Config, clock: &dyn Clock) -> Result<(), Error> {
+fn prune_all(
+	db: &Arc<dyn Database>,
+	config: &Config,
+	clock: &dyn Clock,
+	metrics: &Metrics,
+) -> Result<(), Error> {
 	let now = clock.now()?;
 	let (range_start, range_end) = pruning_range(now);
 
 	let mut tx = DBTransaction::new();
+	let mut pruned = 0;
+	let mut pruned_bytes = 0;
 	let iter = db
 		.iter_with_prefix(config.col_meta, &range_start[..])
 		.take_while(|(k, _)| &k[..] < &range_end[..]);
@@ -1239,38 +1363,101 @@ fn prune_all(db: &Arc<dyn Database>, config: &Config, clock: &dyn Clock) -> Resu
 			Err(_) => continue, // sanity
 		};
 
-		delete_meta(&mut tx, config, &candidate_hash);
+		if let Some(bytes) = prune_candidate(db, &mut tx, config, &candidate_hash)? {
+			pruned += 1;
+			pruned_bytes += bytes;
+		}
+	}
 
-		// Clean up all attached data of the candidate.
-		if let Some(meta) = load_meta(db, config, &candidate_hash)? {
-			// delete available data.
-			if meta.data_available {
-				delete_available_data(&mut tx, config, &candidate_hash)
-			}
+	db.write(tx)?;
+	metrics.on_pruned(pruned, pruned_bytes);
+	Ok(())
+}
 
-			// delete chunks.
-			for (i, b) in meta.chunks_stored.iter().enumerate() {
-				if *b {
-					delete_chunk(&mut tx, config, &candidate_hash, ValidatorIndex(i as _));
-				}
-			}
+/// Prune the data of candidates finalized at least `Config::keep_finalized_blocks` blocks
+/// before the given finalized block.
+fn prune_finalized(
+	db: &Arc<dyn Database>,
+	config: &Config,
+	finalized_number: BlockNumber,
+	metrics: &Metrics,
+) -> Result<(), Error> {
+	let (range_start, range_end) = finalized_pruning_range(finalized_number);
 
-			// delete unfinalized block references. Pruning references don't need to be
-			// manually taken care of as we are deleting them as we go in the outer loop.
-			if let State::Unfinalized(_, blocks) = meta.state {
-				for (block_number, block_hash) in blocks {
-					delete_unfinalized_inclusion(
-						&mut tx,
-						config,
-						block_number.0,
-						&block_hash,
-						&candidate_hash,
-					);
-				}
-			}
+	let mut tx = DBTransaction::new();
+	let mut pruned = 0;
+	let mut pruned_bytes = 0;
+	let iter = db
+		.iter_with_prefix(config.col_meta, &range_start[..])
+		.take_while(|(k, _)| &k[..] < &range_end[..]);
+
+	for (k, _v) in iter {
+		tx.delete(config.col_meta, &k[..]);
+
+		let (_, candidate_hash) = match decode_finalized_pruning_key(&k[..]) {
+			Ok(m) => m,
+			Err(_) => continue, // sanity
+		};
+
+		// The time based pruning key is left in place, it copes with the meta being gone.
+		if let Some(bytes) = prune_candidate(db, &mut tx, config, &candidate_hash)? {
+			pruned += 1;
+			pruned_bytes += bytes;
 		}
 	}
 
 	db.write(tx)?;
+
+	if pruned > 0 {
+		tracing::debug!(
+			target: LOG_TARGET,
+			finalized_number,
+			candidates = pruned,
+			bytes = pruned_bytes,
+			"Pruned data of candidates finalized long enough ago",
+		);
+	}
+	metrics.on_pruned(pruned, pruned_bytes);
 	Ok(())
 }
+
+/// Delete all data of a candidate, apart from its pruning keys.
+///
+/// Returns the number of bytes of available data and chunks pruned, or `None` if there was no
+/// data of the candidate left.
+fn prune_candidate(
+	db: &Arc<dyn Database>,
+	tx: &mut DBTransaction,
+	config: &Config,
+	candidate_hash: &CandidateHash,
+) -> Result<Option<u64>, Error> {
+	delete_meta(tx, config, candidate_hash);
+
+	// Clean up all attached data of the candidate.
+	let meta = match load_meta(db, config, candidate_hash)? {
+		Some(meta) => meta,
+		None => return Ok(None),
+	};
+
+	// delete available data.
+	if meta.data_available {
+		delete_available_data(tx, config, candidate_hash)
+	}
+
+	// delete chunks.
+	for (i, b) in meta.chunks_stored.iter().enumerate() {
+		if *b {
+			delete_chunk(tx, config, candidate_hash, ValidatorIndex(i as _));
+		}
+	}
+
+	// delete unfinalized block references. Pruning references don't need to be
+	// manually taken care of as the callers delete them as they go.
+	if let State::Unfinalized(_, blocks) = meta.state {
+		for (block_number, block_hash) in blocks {
+			delete_unfinalized_inclusion(tx, config, block_number.0, &block_hash, candidate_hash);
+		}
+	}
+
+	Ok(Some(meta.stored_bytes))
+}
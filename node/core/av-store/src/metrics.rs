@@ -19,6 +19,8 @@ use selendra_node_subsystem_util::metrics::{self, prometheus};
 #[derive(Clone)]
 pub(crate) struct MetricsInner {
 	received_availability_chunks_total: prometheus::Counter<prometheus::U64>,
+	pruned_candidates_total: prometheus::Counter<prometheus::U64>,
+	pruned_bytes_total: prometheus::Counter<prometheus::U64>,
 	pruning: prometheus::Histogram,
 	process_block_finalized: prometheus::Histogram,
 	block_activated: prometheus::Histogram,
@@ -42,6 +44,13 @@ impl Metrics {
 		}
 	}
 
+	pub(crate) fn on_pruned(&self, candidates: usize, bytes: u64) {
+		if let Some(metrics) = &self.0 {
+			metrics.pruned_candidates_total.inc_by(candidates as u64);
+			metrics.pruned_bytes_total.inc_by(bytes);
+		}
+	}
+
 	/// Provide a timer for `prune_povs` which observes on drop.
 	pub(crate) fn time_pruning(&self) -> Option<metrics::prometheus::prometheus::HistogramTimer> {
 		self.0.as_ref().map(|metrics| metrics.pruning.start_timer())
@@ -98,6 +107,20 @@ impl metrics::Metrics for Metrics {
 				)?,
 				registry,
 			)?,
+			pruned_candidates_total: prometheus::register(
+				prometheus::Counter::new(
+					"selendra_parachain_av_store_pruned_candidates_total",
+					"Number of candidates whose data was pruned.",
+				)?,
+				registry,
+			)?,
+			pruned_bytes_total: prometheus::register(
+				prometheus::Counter::new(
+					"selendra_parachain_av_store_pruned_bytes_total",
+					"Number of bytes of chunks and available data pruned.",
+				)?,
+				registry,
+			)?,
 			pruning: prometheus::register(
 				prometheus::Histogram::with_opts(prometheus::HistogramOpts::new(
 					"selendra_parachain_av_store_pruning",
@@ -44,7 +44,8 @@ mod columns {
 	pub const NUM_COLUMNS: u32 = 2;
 }
 
-const TEST_CONFIG: Config = Config { col_data: columns::DATA, col_meta: columns::META };
+const TEST_CONFIG: Config =
+	Config { col_data: columns::DATA, col_meta: columns::META, keep_finalized_blocks: None };
 
 type VirtualOverseer = test_helpers::TestSubsystemContextHandle<AvailabilityStoreMessage>;
 
@@ -72,6 +73,7 @@ impl Clock for TestClock {
 #[derive(Clone)]
 struct TestState {
 	persisted_validation_data: PersistedValidationData,
+	config: Config,
 	pruning_config: PruningConfig,
 	clock: TestClock,
 }
@@ -101,7 +103,7 @@ impl Default for TestState {
 
 		let clock = TestClock { inner: Arc::new(Mutex::new(Duration::from_secs(0))) };
 
-		Self { persisted_validation_data, pruning_config, clock }
+		Self { persisted_validation_data, config: TEST_CONFIG, pruning_config, clock }
 	}
 }
 
@@ -121,7 +123,7 @@ fn test_harness<T: Future<Output = VirtualOverseer>>(
 
 	let subsystem = AvailabilityStoreSubsystem::with_pruning_config_and_clock(
 		store,
-		TEST_CONFIG,
+		state.config,
 		state.pruning_config.clone(),
 		Box::new(state.clock),
 		Metrics::default(),
@@ -302,6 +304,7 @@ fn store_chunk_works() {
 					data_available: false,
 					chunks_stored: bitvec::bitvec![u8, BitOrderLsb0; 0; n_validators],
 					state: State::Unavailable(BETimestamp(0)),
+					stored_bytes: 0,
 				},
 			);
 		});
@@ -380,6 +383,7 @@ fn query_chunk_checks_meta() {
 						v
 					},
 					state: State::Unavailable(BETimestamp(0)),
+					stored_bytes: 0,
 				},
 			);
 		});
@@ -496,6 +500,153 @@ fn store_pov_and_query_chunk_works() {
 	});
 }
 
+#[test]
+fn stored_bytes_are_recorded() {
+	let store = test_store();
+	let test_state = TestState::default();
+
+	test_harness(test_state.clone(), store.clone(), |mut virtual_overseer| async move {
+		let candidate_hash = CandidateHash(Hash::repeat_byte(1));
+		let n_validators = 10;
+
+		let pov = PoV { block_data: BlockData(vec![4, 5, 6]) };
+
+		let available_data = AvailableData {
+			pov: Arc::new(pov),
+			validation_data: test_state.persisted_validation_data.clone(),
+		};
+
+		let chunks = erasure::obtain_chunks_v1(n_validators, &available_data).unwrap();
+		let branches = erasure::branches(chunks.as_ref());
+		let expected_bytes = available_data.encoded_size() +
+			chunks
+				.iter()
+				.zip(branches.map(|(proof, _)| proof))
+				.enumerate()
+				.map(|(index, (chunk, proof))| {
+					ErasureChunk { chunk: chunk.clone(), proof, index: ValidatorIndex(index as _) }
+						.encoded_size()
+				})
+				.sum::<usize>();
+
+		let (tx, rx) = oneshot::channel();
+		let block_msg = AvailabilityStoreMessage::StoreAvailableData {
+			candidate_hash,
+			n_validators,
+			available_data,
+			tx,
+		};
+
+		virtual_overseer.send(FromOverseer::Communication { msg: block_msg }).await;
+		assert_eq!(rx.await.unwrap(), Ok(()));
+
+		let meta = super::load_meta(&store, &TEST_CONFIG, &candidate_hash).unwrap().unwrap();
+		assert_eq!(meta.stored_bytes, expected_bytes as u64);
+		virtual_overseer
+	});
+}
+
+#[test]
+fn meta_without_stored_bytes_decodes() {
+	let chunks_stored = bitvec::bitvec![u8, BitOrderLsb0; 1; 3];
+	let state = State::Unavailable(BETimestamp(7));
+	let legacy = (&state, true, &chunks_stored).encode();
+
+	let meta = CandidateMeta::decode(&mut &legacy[..]).unwrap();
+	assert!(meta.data_available);
+	assert_eq!(meta.chunks_stored, chunks_stored);
+	assert_eq!(meta.stored_bytes, 0);
+
+	let meta = CandidateMeta { stored_bytes: 42, ..meta };
+	let decoded = CandidateMeta::decode(&mut &meta.encode()[..]).unwrap();
+	assert_eq!(decoded.stored_bytes, 42);
+}
+
+#[test]
+fn integrity_check_finds_and_prunes_corruption() {
+	let store = test_store();
+	let n_validators = 10;
+
+	let available_data = AvailableData {
+		pov: Arc::new(PoV { block_data: BlockData(vec![4, 5, 6]) }),
+		validation_data: TestState::default().persisted_validation_data,
+	};
+	let chunks = erasure::obtain_chunks_v1(n_validators, &available_data).unwrap();
+	let erasure_chunks: Vec<_> = erasure::branches(chunks.as_ref())
+		.enumerate()
+		.map(|(index, (proof, chunk))| ErasureChunk {
+			chunk: chunk.to_vec(),
+			index: ValidatorIndex(index as _),
+			proof,
+		})
+		.collect();
+
+	let erasure_root = erasure::branches(chunks.as_ref()).root();
+
+	let intact = CandidateHash(Hash::repeat_byte(1));
+	let bad_chunk = CandidateHash(Hash::repeat_byte(2));
+	let missing_data = CandidateHash(Hash::repeat_byte(3));
+	let wrong_root = CandidateHash(Hash::repeat_byte(4));
+	let no_root = CandidateHash(Hash::repeat_byte(5));
+
+	with_tx(&store, |tx| {
+		for candidate_hash in &[intact, bad_chunk, missing_data, wrong_root, no_root] {
+			let mut chunks_stored = bitvec::bitvec![u8, BitOrderLsb0; 0; n_validators];
+			for chunk in &erasure_chunks[..3] {
+				chunks_stored.set(chunk.index.0 as usize, true);
+
+				let mut chunk = chunk.clone();
+				if *candidate_hash == bad_chunk && chunk.index == ValidatorIndex(2) {
+					chunk.chunk[0] ^= 1;
+				}
+				write_chunk(tx, &TEST_CONFIG, candidate_hash, chunk.index, &chunk);
+			}
+
+			let meta = CandidateMeta {
+				data_available: *candidate_hash == missing_data,
+				chunks_stored,
+				state: State::Unavailable(BETimestamp(0)),
+				stored_bytes: 0,
+			};
+			write_meta(tx, &TEST_CONFIG, candidate_hash, &meta);
+
+			if *candidate_hash == wrong_root {
+				write_erasure_root(tx, &TEST_CONFIG, candidate_hash, &Hash::repeat_byte(9));
+			} else if *candidate_hash != no_root {
+				write_erasure_root(tx, &TEST_CONFIG, candidate_hash, &erasure_root);
+			}
+		}
+	});
+
+	let report = check_integrity(&store, &TEST_CONFIG, false).unwrap();
+	assert_eq!(report.candidates_checked, 4);
+	assert_eq!(report.candidates_skipped, 1);
+	assert_eq!(report.candidates_pruned, 0);
+	assert_eq!(report.corruptions.len(), 5);
+	assert!(report.corruptions.contains(&Corruption::Chunk(bad_chunk, ValidatorIndex(2))));
+	assert!(report.corruptions.contains(&Corruption::MissingData(missing_data)));
+	for i in 0..3 {
+		assert!(report.corruptions.contains(&Corruption::Chunk(wrong_root, ValidatorIndex(i))));
+	}
+
+	let report = check_integrity(&store, &TEST_CONFIG, true).unwrap();
+	assert_eq!(report.candidates_pruned, 3);
+
+	assert!(load_meta(&store, &TEST_CONFIG, &intact).unwrap().is_some());
+	assert!(load_meta(&store, &TEST_CONFIG, &bad_chunk).unwrap().is_none());
+	assert!(load_chunk(&store, &TEST_CONFIG, &bad_chunk, ValidatorIndex(0))
+		.unwrap()
+		.is_none());
+	assert!(load_meta(&store, &TEST_CONFIG, &missing_data).unwrap().is_none());
+	assert!(load_erasure_root(&store, &TEST_CONFIG, &wrong_root).unwrap().is_none());
+	assert!(load_meta(&store, &TEST_CONFIG, &no_root).unwrap().is_some());
+
+	let report = check_integrity(&store, &TEST_CONFIG, false).unwrap();
+	assert_eq!(report.candidates_checked, 1);
+	assert_eq!(report.candidates_skipped, 1);
+	assert!(report.corruptions.is_empty());
+}
+
 #[test]
 fn query_all_chunks_works() {
 	let store = test_store();
@@ -541,6 +692,7 @@ fn query_all_chunks_works() {
 						data_available: false,
 						chunks_stored: bitvec::bitvec![u8, BitOrderLsb0; 0; n_validators as _],
 						state: State::Unavailable(BETimestamp(0)),
+						stored_bytes: 0,
 					},
 				);
 			});
@@ -728,6 +880,88 @@ fn stored_data_kept_until_finalized() {
 	});
 }
 
+#[test]
+fn finalized_data_is_pruned_after_configured_blocks() {
+	let store = test_store();
+	let test_state = TestState {
+		config: Config { keep_finalized_blocks: Some(2), ..TEST_CONFIG },
+		..Default::default()
+	};
+
+	test_harness(test_state.clone(), store.clone(), |mut virtual_overseer| async move {
+		let n_validators = 10;
+
+		let pov = PoV { block_data: BlockData(vec![4, 5, 6]) };
+
+		let pov_hash = pov.hash();
+
+		let candidate = TestCandidateBuilder { pov_hash, ..Default::default() }.build();
+
+		let candidate_hash = candidate.hash();
+
+		let available_data = AvailableData {
+			pov: Arc::new(pov),
+			validation_data: test_state.persisted_validation_data.clone(),
+		};
+
+		let parent = Hash::repeat_byte(2);
+		let block_number = 10;
+
+		let (tx, rx) = oneshot::channel();
+		let block_msg = AvailabilityStoreMessage::StoreAvailableData {
+			candidate_hash,
+			n_validators,
+			available_data: available_data.clone(),
+			tx,
+		};
+
+		virtual_overseer.send(FromOverseer::Communication { msg: block_msg }).await;
+
+		rx.await.unwrap().unwrap();
+
+		let new_leaf = import_leaf(
+			&mut virtual_overseer,
+			parent,
+			block_number,
+			vec![candidate_included(candidate)],
+			(0..n_validators).map(|_| Sr25519Keyring::Alice.public().into()).collect(),
+		)
+		.await;
+
+		overseer_signal(
+			&mut virtual_overseer,
+			OverseerSignal::BlockFinalized(new_leaf, block_number),
+		)
+		.await;
+
+		overseer_signal(
+			&mut virtual_overseer,
+			OverseerSignal::BlockFinalized(Hash::repeat_byte(3), block_number + 1),
+		)
+		.await;
+
+		// Finalized for a single block only, the data should _still_ be in the store.
+		assert_eq!(
+			query_available_data(&mut virtual_overseer, candidate_hash).await.unwrap(),
+			available_data,
+		);
+
+		assert!(has_all_chunks(&mut virtual_overseer, candidate_hash, n_validators, true).await);
+
+		overseer_signal(
+			&mut virtual_overseer,
+			OverseerSignal::BlockFinalized(Hash::repeat_byte(4), block_number + 2),
+		)
+		.await;
+
+		// At this point data should be gone from the store, well before the time based pruning.
+		assert!(query_available_data(&mut virtual_overseer, candidate_hash).await.is_none());
+
+		assert!(has_all_chunks(&mut virtual_overseer, candidate_hash, n_validators, false).await);
+		virtual_overseer
+	});
+}
+
 #[test]
 fn we_dont_miss_anything_if_import_notifications_are_missed() {
 	let store = test_store();
@@ -39,6 +39,7 @@ use selendra_node_subsystem::{
 use selendra_node_subsystem_util::{
 	self as util,
 	metrics::{self, prometheus},
+	pending_work::PendingWork,
 	JobSender, JobSubsystem, JobTrait, Validator,
 };
 use selendra_primitives::v1::{AvailabilityBitfield, CoreState, Hash, ValidatorIndex};
@@ -56,6 +57,16 @@ const LOG_TARGET: &str = "parachain::bitfield-signing";
 /// Each `BitfieldSigningJob` prepares a signed bitfield for a single relay parent.
 pub struct BitfieldSigningJob;
 
+/// Bitfield signing run arguments.
+#[derive(Clone)]
+pub struct BitfieldSigningConfig {
+	/// The keystore holding the validator keys.
+	pub keystore: SyncCryptoStorePtr,
+	/// The work a graceful shutdown waits for, which the bitfields of the current leaves are part
+	/// of.
+	pub pending_work: PendingWork,
+}
+
 /// Errors we may encounter in the course of executing the `BitfieldSigningSubsystem`.
 #[derive(Debug, thiserror::Error)]
 #[allow(missing_docs)]
@@ -230,7 +241,7 @@ impl metrics::Metrics for Metrics {
 impl JobTrait for BitfieldSigningJob {
 	type ToJob = BitfieldSigningMessage;
 	type Error = Error;
-	type RunArgs = SyncCryptoStorePtr;
+	type RunArgs = BitfieldSigningConfig;
 	type Metrics = Metrics;
 
 	const NAME: &'static str = "bitfield-signing-job";
@@ -238,13 +249,16 @@ impl JobTrait for BitfieldSigningJob {
 	/// Run a job for the parent block indicated
 	fn run<S: SubsystemSender>(
 		leaf: ActivatedLeaf,
-		keystore: Self::RunArgs,
+		config: Self::RunArgs,
 		metrics: Self::Metrics,
 		_receiver: mpsc::Receiver<BitfieldSigningMessage>,
 		mut sender: JobSender<S>,
 	) -> Pin<Box<dyn Future<Output = Result<(), Self::Error>> + Send>> {
 		let metrics = metrics.clone();
+		let BitfieldSigningConfig { keystore, pending_work } = config;
+		let pending = pending_work.start();
 		async move {
+			let _pending = pending;
 			if let LeafStatus::Stale = leaf.status {
 				tracing::debug!(
 					target: LOG_TARGET,
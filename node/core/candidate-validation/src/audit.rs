@@ -0,0 +1,258 @@
+// Copyright 2020-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! An append-only audit log of validation verdicts.
+//!
+//! When configured, the subsystem appends one JSON object per line to the log for every candidate
+//! it validates. Once the log grows beyond the configured size it is rotated: `<path>` is renamed
+//! to `<path>.1`, `<path>.1` to `<path>.2` and so on, dropping the oldest file.
+
+use std::{
+	fs::{self, File, OpenOptions},
+	io::{self, Write},
+	path::{Path, PathBuf},
+	sync::{Arc, Mutex},
+	time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+use selendra_node_primitives::ValidationResult;
+use selendra_node_subsystem::messages::ValidationFailed;
+use selendra_primitives::v1::{CandidateDescriptor, CandidateReceipt};
+
+use crate::LOG_TARGET;
+
+/// The default size at which the audit log is rotated, in bytes.
+pub const DEFAULT_MAX_FILE_SIZE: u64 = 64 * 1024 * 1024;
+
+/// The default number of rotated audit log files which are kept.
+pub const DEFAULT_MAX_ROTATED_FILES: usize = 4;
+
+/// Configuration of the audit log.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditLogConfig {
+	/// The file the verdicts are appended to.
+	pub path: PathBuf,
+	/// The size in bytes beyond which the file is rotated.
+	pub max_file_size: u64,
+	/// The number of rotated files kept next to the current one.
+	pub max_rotated_files: usize,
+}
+
+impl AuditLogConfig {
+	/// Log into the given file, with the default rotation settings.
+	pub fn new(path: PathBuf) -> Self {
+		AuditLogConfig {
+			path,
+			max_file_size: DEFAULT_MAX_FILE_SIZE,
+			max_rotated_files: DEFAULT_MAX_ROTATED_FILES,
+		}
+	}
+}
+
+/// Appends validation verdicts to a rotated file.
+#[derive(Clone)]
+pub(crate) struct AuditLog {
+	inner: Arc<Mutex<Writer>>,
+}
+
+impl AuditLog {
+	pub(crate) fn new(config: AuditLogConfig) -> Self {
+		AuditLog { inner: Arc::new(Mutex::new(Writer { config, file: None })) }
+	}
+
+	/// Start timing the validation of the candidate with the given descriptor.
+	pub(crate) fn start(&self, descriptor: &CandidateDescriptor) -> PendingEntry {
+		PendingEntry { log: self.clone(), descriptor: descriptor.clone(), started: Instant::now() }
+	}
+
+	fn append(&self, line: String) {
+		let mut writer = match self.inner.lock() {
+			Ok(writer) => writer,
+			Err(poisoned) => poisoned.into_inner(),
+		};
+
+		if let Err(err) = writer.append(line.as_bytes()) {
+			// Drop the handle, the file is opened again for the next entry.
+			writer.file = None;
+			tracing::warn!(
+				target: LOG_TARGET,
+				path = ?writer.config.path,
+				?err,
+				"Failed to append to the validation audit log",
+			);
+		}
+	}
+}
+
+/// The validation of a candidate whose verdict is still outstanding.
+pub(crate) struct PendingEntry {
+	log: AuditLog,
+	descriptor: CandidateDescriptor,
+	started: Instant,
+}
+
+impl PendingEntry {
+	/// Record the verdict of the validation.
+	pub(crate) fn finish(self, result: &Result<ValidationResult, ValidationFailed>) {
+		let duration = self.started.elapsed();
+		let line = entry(&self.descriptor, result, duration.as_millis(), unix_millis());
+		self.log.append(line);
+	}
+}
+
+/// Render a verdict as a single JSON line.
+///
+/// The hash of the candidate is only known if it's valid, as it commits to the outputs which an
+/// invalid candidate doesn't produce. The PoV hash identifies the candidate either way.
+fn entry(
+	descriptor: &CandidateDescriptor,
+	result: &Result<ValidationResult, ValidationFailed>,
+	duration_millis: u128,
+	timestamp_millis: u128,
+) -> String {
+	let (verdict, candidate_hash, reason) = match result {
+		Ok(ValidationResult::Valid(commitments, _)) => {
+			let receipt = CandidateReceipt {
+				descriptor: descriptor.clone(),
+				commitments_hash: commitments.hash(),
+			};
+			("valid", Some(format!("{:?}", receipt.hash().0)), None)
+		},
+		Ok(ValidationResult::Invalid(reason)) => ("invalid", None, Some(format!("{:?}", reason))),
+		Err(ValidationFailed(reason)) => ("failed", None, Some(reason.clone())),
+	};
+
+	let mut line = serde_json::json!({
+		"timestamp": timestamp_millis as u64,
+		"candidate_hash": candidate_hash,
+		"para_id": u32::from(descriptor.para_id),
+		"relay_parent": format!("{:?}", descriptor.relay_parent),
+		"pov_hash": format!("{:?}", descriptor.pov_hash),
+		"validation_code_hash": format!("{:?}", descriptor.validation_code_hash),
+		"verdict": verdict,
+		"reason": reason,
+		"duration_ms": duration_millis as u64,
+	})
+	.to_string();
+	line.push('\n');
+	line
+}
+
+fn unix_millis() -> u128 {
+	SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_millis())
+}
+
+struct Writer {
+	config: AuditLogConfig,
+	file: Option<(File, u64)>,
+}
+
+impl Writer {
+	fn append(&mut self, line: &[u8]) -> io::Result<()> {
+		let (file, size) = match self.file.take() {
+			Some(open) => open,
+			None => open(&self.config.path)?,
+		};
+
+		let (mut file, mut size) =
+			if size > 0 && size + line.len() as u64 > self.config.max_file_size {
+				drop(file);
+				rotate(&self.config.path, self.config.max_rotated_files)?;
+				open(&self.config.path)?
+			} else {
+				(file, size)
+			};
+
+		file.write_all(line)?;
+		size += line.len() as u64;
+		self.file = Some((file, size));
+		Ok(())
+	}
+}
+
+fn open(path: &Path) -> io::Result<(File, u64)> {
+	if let Some(dir) = path.parent() {
+		fs::create_dir_all(dir)?;
+	}
+	let file = OpenOptions::new().create(true).append(true).open(path)?;
+	let size = file.metadata()?.len();
+	Ok((file, size))
+}
+
+fn rotated_path(path: &Path, index: usize) -> PathBuf {
+	let mut name = path.as_os_str().to_owned();
+	name.push(format!(".{}", index));
+	PathBuf::from(name)
+}
+
+/// Shift the rotated files by one, dropping the oldest, and move the current file in front.
+fn rotate(path: &Path, max_rotated_files: usize) -> io::Result<()> {
+	if max_rotated_files == 0 {
+		return fs::remove_file(path)
+	}
+
+	for index in (1..max_rotated_files).rev() {
+		let from = rotated_path(path, index);
+		if from.exists() {
+			fs::rename(&from, rotated_path(path, index + 1))?;
+		}
+	}
+	fs::rename(path, rotated_path(path, 1))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use selendra_node_primitives::InvalidCandidate;
+	use test_helpers::dummy_candidate_descriptor;
+
+	#[test]
+	fn entries_are_json_lines() {
+		let descriptor = dummy_candidate_descriptor(Default::default());
+		let result = Ok(ValidationResult::Invalid(InvalidCandidate::Timeout));
+
+		let line = entry(&descriptor, &result, 12, 34);
+		assert!(line.ends_with('\n'));
+		assert_eq!(line.matches('\n').count(), 1);
+
+		let value: serde_json::Value = serde_json::from_str(&line).unwrap();
+		assert_eq!(value["verdict"], "invalid");
+		assert_eq!(value["reason"], "Timeout");
+		assert_eq!(value["duration_ms"], 12);
+		assert_eq!(value["timestamp"], 34);
+		assert_eq!(value["para_id"], u32::from(descriptor.para_id));
+		assert!(value["candidate_hash"].is_null());
+	}
+
+	#[test]
+	fn rotates_beyond_max_size() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("audit.log");
+		let mut writer = Writer {
+			config: AuditLogConfig { path: path.clone(), max_file_size: 10, max_rotated_files: 2 },
+			file: None,
+		};
+
+		for line in ["first\n", "second\n", "third\n", "fourth\n"] {
+			writer.append(line.as_bytes()).unwrap();
+		}
+
+		assert_eq!(fs::read_to_string(&path).unwrap(), "fourth\n");
+		assert_eq!(fs::read_to_string(rotated_path(&path, 1)).unwrap(), "third\n");
+		assert_eq!(fs::read_to_string(rotated_path(&path, 2)).unwrap(), "second\n");
+		assert!(!rotated_path(&path, 3).exists());
+	}
+}
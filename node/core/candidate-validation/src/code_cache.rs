@@ -0,0 +1,81 @@
+// Copyright 2020-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A cache of recently used validation code, keyed by its hash.
+
+use std::sync::{Arc, Mutex};
+
+use lru::LruCache;
+use selendra_node_subsystem::{messages::ValidationFailed, SubsystemSender};
+use selendra_primitives::v1::{Hash, ValidationCode, ValidationCodeHash};
+
+use crate::{request_validation_code_by_hash, LOG_TARGET};
+
+/// The number of validation code blobs kept in the cache.
+///
+/// Validation code can be several megabytes large, but only few paras are usually validated at
+/// any given time.
+const VALIDATION_CODE_CACHE_SIZE: usize = 16;
+
+#[derive(Clone)]
+pub(crate) struct ValidationCodeCache(Arc<Mutex<LruCache<ValidationCodeHash, ValidationCode>>>);
+
+impl Default for ValidationCodeCache {
+	fn default() -> Self {
+		ValidationCodeCache(Arc::new(Mutex::new(LruCache::new(VALIDATION_CODE_CACHE_SIZE))))
+	}
+}
+
+impl ValidationCodeCache {
+	/// Get the validation code with the given hash, querying the runtime API at `relay_parent` if
+	/// it is not cached.
+	pub(crate) async fn get_or_fetch<Sender>(
+		&self,
+		sender: &mut Sender,
+		relay_parent: Hash,
+		validation_code_hash: ValidationCodeHash,
+	) -> Result<ValidationCode, ValidationFailed>
+	where
+		Sender: SubsystemSender,
+	{
+		let cached = self.lock().get(&validation_code_hash).cloned();
+		if let Some(code) = cached {
+			return Ok(code)
+		}
+
+		match request_validation_code_by_hash(sender, relay_parent, validation_code_hash).await {
+			Ok(Some(code)) => {
+				let _ = self.lock().put(validation_code_hash, code.clone());
+				Ok(code)
+			},
+			Ok(None) => {
+				tracing::debug!(
+					target: LOG_TARGET,
+					?relay_parent,
+					?validation_code_hash,
+					"Validation code not found on-chain",
+				);
+				Err(ValidationFailed("Validation code not found by hash".into()))
+			},
+			Err(_) =>
+				Err(ValidationFailed("Runtime API request for validation code failed".into())),
+		}
+	}
+
+	fn lock(&self) -> std::sync::MutexGuard<'_, LruCache<ValidationCodeHash, ValidationCode>> {
+		self.0.lock().expect("nothing panics while holding the lock; qed")
+	}
+}
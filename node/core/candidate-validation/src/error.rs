@@ -0,0 +1,46 @@
+// Copyright 2020-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Error types of the candidate validation subsystem.
+
+use std::path::PathBuf;
+
+/// Errors which can occur while setting up the candidate validation subsystem.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+	/// The path of the PVF worker binary could not be determined.
+	#[error("Cannot determine the path of the PVF worker binary")]
+	WorkerPath(#[source] std::io::Error),
+
+	/// The configured PVF worker binary does not exist.
+	#[error("PVF worker binary {0:?} does not exist")]
+	WorkerNotFound(PathBuf),
+
+	/// The PVF worker binary could not be asked for its version.
+	#[error("Cannot query the version of the PVF worker binary {0:?}")]
+	WorkerVersionQuery(PathBuf, #[source] std::io::Error),
+
+	/// The PVF worker binary reports a version incompatible with the node.
+	#[error("PVF worker binary {binary:?} has version {found:?}, expected {expected:?}")]
+	WorkerVersionMismatch {
+		/// The path of the worker binary.
+		binary: PathBuf,
+		/// The version the node expects.
+		expected: String,
+		/// The version reported by the worker binary.
+		found: String,
+	},
+}
@@ -0,0 +1,108 @@
+// Copyright 2020-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! The ways PVF preparation and execution can be isolated from the node.
+
+use std::path::PathBuf;
+
+use selendra_node_core_pvf::{reported_version, SandboxConfig};
+
+use crate::{Error, LOG_TARGET};
+
+/// The strategy used to spawn the PVF preparation and execution workers.
+///
+/// The worker binary is looked up every time a worker is spawned, so replacing the binary on disk
+/// takes effect for all workers spawned afterwards without restarting the node. If the
+/// [`SandboxConfig`] has an expected version, the version of the binary is checked again before
+/// every spawn as well.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IsolationStrategy {
+	/// Spawn the workers by re-executing the node binary with the worker subcommands.
+	ExternalProcessSelfHost(SandboxConfig),
+	/// Spawn the workers from a dedicated binary which handles the `prepare-worker` and
+	/// `execute-worker` subcommands.
+	ExternalProcessCustomHost {
+		/// The path to the worker binary.
+		binary: PathBuf,
+		/// The hardening options applied to the workers.
+		sandbox: SandboxConfig,
+	},
+}
+
+impl Default for IsolationStrategy {
+	fn default() -> Self {
+		Self::ExternalProcessSelfHost(SandboxConfig::default())
+	}
+}
+
+impl IsolationStrategy {
+	/// The path to the binary used for spawning the workers.
+	pub fn program_path(&self) -> Result<PathBuf, Error> {
+		match self {
+			Self::ExternalProcessSelfHost(_) => std::env::current_exe().map_err(Error::WorkerPath),
+			Self::ExternalProcessCustomHost { binary, .. } => Ok(binary.clone()),
+		}
+	}
+
+	/// The hardening options applied to the workers.
+	pub fn sandbox(&self) -> &SandboxConfig {
+		match self {
+			Self::ExternalProcessSelfHost(sandbox) |
+			Self::ExternalProcessCustomHost { sandbox, .. } => sandbox,
+		}
+	}
+
+	/// Check that the worker binary exists and is compatible with the node.
+	///
+	/// This is only done once before the subsystem is started, to refuse starting with a
+	/// misconfigured binary. The version is checked again before every spawn.
+	pub fn check_worker_binary(&self) -> Result<(), Error> {
+		let (binary, expected_version) = match self {
+			// We are the worker binary, so there is nothing to check.
+			Self::ExternalProcessSelfHost(_) => return Ok(()),
+			Self::ExternalProcessCustomHost { binary, sandbox } =>
+				(binary, &sandbox.expected_version),
+		};
+
+		if !binary.is_file() {
+			return Err(Error::WorkerNotFound(binary.clone()))
+		}
+
+		let expected = match expected_version {
+			Some(expected) => expected,
+			None => return Ok(()),
+		};
+
+		let output = std::process::Command::new(binary)
+			.arg("--version")
+			.output()
+			.map_err(|e| Error::WorkerVersionQuery(binary.clone(), e))?;
+		let output = String::from_utf8_lossy(&output.stdout);
+		let found = reported_version(&output).unwrap_or_default();
+
+		if found != expected {
+			return Err(Error::WorkerVersionMismatch {
+				binary: binary.clone(),
+				expected: expected.clone(),
+				found: found.to_owned(),
+			})
+		}
+
+		tracing::debug!(target: LOG_TARGET, ?binary, version = %found, "PVF worker binary checked");
+
+		Ok(())
+	}
+}
@@ -38,10 +38,9 @@ use selendra_node_subsystem::{
 	overseer, FromOverseer, OverseerSignal, SpawnedSubsystem, SubsystemContext, SubsystemError,
 	SubsystemResult, SubsystemSender,
 };
-use selendra_node_subsystem_util::metrics::{self, prometheus};
 use selendra_parachain::primitives::{ValidationParams, ValidationResult as WasmValidationResult};
 use selendra_primitives::v1::{
-	CandidateCommitments, CandidateDescriptor, Hash, OccupiedCoreAssumption,
+	CandidateCommitments, CandidateDescriptor, Hash, Id as ParaId, OccupiedCoreAssumption,
 	PersistedValidationData, ValidationCode, ValidationCodeHash,
 };
 
@@ -49,10 +48,30 @@ use parity_scale_codec::Encode;
 
 use futures::{channel::oneshot, prelude::*};
 
-use std::{path::PathBuf, sync::Arc, time::Duration};
+use std::{collections::HashSet, path::PathBuf, sync::Arc, time::Duration};
 
 use async_trait::async_trait;
 
+mod audit;
+mod code_cache;
+mod error;
+mod isolation;
+mod metrics;
+mod replay;
+
+pub use audit::AuditLogConfig;
+pub use error::Error;
+pub use isolation::IsolationStrategy;
+pub use metrics::Metrics;
+pub use replay::{
+	replay_validation, BundleExportConfig, BundleExporter, BundleWriter, ValidationBundle,
+};
+pub use selendra_node_core_pvf::SandboxConfig;
+
+use audit::AuditLog;
+use code_cache::ValidationCodeCache;
+use metrics::DecompressionPhase;
+
 #[cfg(test)]
 mod tests;
 
@@ -63,9 +82,16 @@ const LOG_TARGET: &'static str = "parachain::candidate-validation";
 pub struct Config {
 	/// The path where candidate validation can store compiled artifacts for PVFs.
 	pub artifacts_cache_path: PathBuf,
-	/// The path to the executable which can be used for spawning PVF compilation & validation
-	/// workers.
-	pub program_path: PathBuf,
+	/// How the PVF compilation & validation workers are spawned.
+	pub isolation_strategy: IsolationStrategy,
+	/// The paras which are reported with their own `para_id` label in the per-para validation
+	/// metrics. All other paras are aggregated under a single label.
+	pub metrics_para_allow_list: HashSet<ParaId>,
+	/// The export of the inputs of every candidate found invalid as [`ValidationBundle`]s.
+	/// Nothing is exported if `None`.
+	pub invalid_candidate_export: Option<BundleExportConfig>,
+	/// The audit log every validation verdict is appended to. Nothing is logged if `None`.
+	pub audit_log: Option<AuditLogConfig>,
 }
 
 /// The candidate validation subsystem.
@@ -87,8 +113,49 @@ impl CandidateValidationSubsystem {
 		metrics: Metrics,
 		pvf_metrics: selendra_node_core_pvf::Metrics,
 	) -> Self {
+		let metrics = metrics.with_para_allow_list(config.metrics_para_allow_list.clone());
 		CandidateValidationSubsystem { config, metrics, pvf_metrics }
 	}
+
+	/// Start building a `CandidateValidationSubsystem` with the given configuration.
+	///
+	/// Unlike [`CandidateValidationSubsystem::with_config`], the builder checks that the worker
+	/// binary of the configured [`IsolationStrategy`] is usable before the subsystem is started.
+	pub fn builder(config: Config) -> CandidateValidationSubsystemBuilder {
+		CandidateValidationSubsystemBuilder {
+			config,
+			metrics: Default::default(),
+			pvf_metrics: Default::default(),
+		}
+	}
+}
+
+/// A builder for the [`CandidateValidationSubsystem`].
+pub struct CandidateValidationSubsystemBuilder {
+	config: Config,
+	metrics: Metrics,
+	pvf_metrics: selendra_node_core_pvf::Metrics,
+}
+
+impl CandidateValidationSubsystemBuilder {
+	/// Use the given candidate validation metrics.
+	pub fn metrics(mut self, metrics: Metrics) -> Self {
+		self.metrics = metrics;
+		self
+	}
+
+	/// Use the given validation host metrics.
+	pub fn pvf_metrics(mut self, pvf_metrics: selendra_node_core_pvf::Metrics) -> Self {
+		self.pvf_metrics = pvf_metrics;
+		self
+	}
+
+	/// Check the worker binary and build the subsystem.
+	pub fn build(self) -> Result<CandidateValidationSubsystem, Error> {
+		self.config.isolation_strategy.check_worker_binary()?;
+
+		Ok(CandidateValidationSubsystem::with_config(self.config, self.metrics, self.pvf_metrics))
+	}
 }
 
 impl<Context> overseer::Subsystem<Context, SubsystemError> for CandidateValidationSubsystem
@@ -102,7 +169,9 @@ where
 			self.metrics,
 			self.pvf_metrics,
 			self.config.artifacts_cache_path,
-			self.config.program_path,
+			self.config.isolation_strategy,
+			self.config.invalid_candidate_export,
+			self.config.audit_log.map(AuditLog::new),
 		)
 		.map_err(|e| SubsystemError::with_origin("candidate-validation", e))
 		.boxed();
@@ -115,18 +184,34 @@ async fn run<Context>(
 	metrics: Metrics,
 	pvf_metrics: selendra_node_core_pvf::Metrics,
 	cache_path: PathBuf,
-	program_path: PathBuf,
+	isolation_strategy: IsolationStrategy,
+	bundle_export: Option<BundleExportConfig>,
+	audit_log: Option<AuditLog>,
 ) -> SubsystemResult<()>
 where
 	Context: SubsystemContext<Message = CandidateValidationMessage>,
 	Context: overseer::SubsystemContext<Message = CandidateValidationMessage>,
 {
-	let (validation_host, task) = selendra_node_core_pvf::start(
-		selendra_node_core_pvf::Config::new(cache_path, program_path),
-		pvf_metrics,
-	);
+	let program_path = isolation_strategy
+		.program_path()
+		.map_err(|e| SubsystemError::with_origin("candidate-validation", e))?;
+	let mut pvf_config = selendra_node_core_pvf::Config::new(cache_path, program_path);
+	pvf_config.worker_sandbox = isolation_strategy.sandbox().clone();
+
+	let (validation_host, task) = selendra_node_core_pvf::start(pvf_config, pvf_metrics);
 	ctx.spawn_blocking("pvf-validation-host", task.boxed())?;
 
+	let bundle_exporter = match bundle_export {
+		Some(config) => {
+			let (exporter, writer) = BundleExporter::new(config);
+			ctx.spawn_blocking("candidate-validation-bundle-writer", writer.run().boxed())?;
+			Some(exporter)
+		},
+		None => None,
+	};
+
+	let code_cache = ValidationCodeCache::default();
+
 	loop {
 		match ctx.recv().await? {
 			FromOverseer::Signal(OverseerSignal::ActiveLeaves(_)) => {},
@@ -143,9 +228,12 @@ where
 						let mut sender = ctx.sender().clone();
 						let metrics = metrics.clone();
 						let validation_host = validation_host.clone();
+						let bundle_exporter = bundle_exporter.clone();
+						let audit = audit_log.as_ref().map(|log| log.start(&descriptor));
 
 						async move {
 							let _timer = metrics.time_validate_from_chain_state();
+							let para_id = descriptor.para_id;
 							let res = validate_from_chain_state(
 								&mut sender,
 								validation_host,
@@ -153,10 +241,15 @@ where
 								pov,
 								timeout,
 								&metrics,
+								bundle_exporter.as_ref(),
 							)
 							.await;
 
 							metrics.on_validation_event(&res);
+							metrics.on_para_validation_event(para_id, &res);
+							if let Some(audit) = audit {
+								audit.finish(&res);
+							}
 							let _ = response_sender.send(res);
 						}
 					};
@@ -174,10 +267,13 @@ where
 					let bg = {
 						let metrics = metrics.clone();
 						let validation_host = validation_host.clone();
+						let bundle_exporter = bundle_exporter.clone();
+						let audit = audit_log.as_ref().map(|log| log.start(&descriptor));
 
 						async move {
 							let _timer = metrics.time_validate_from_exhaustive();
-							let res = validate_candidate_exhaustive(
+							let para_id = descriptor.para_id;
+							let res = validate_and_export(
 								validation_host,
 								persisted_validation_data,
 								validation_code,
@@ -185,16 +281,74 @@ where
 								pov,
 								timeout,
 								&metrics,
+								bundle_exporter.as_ref(),
 							)
 							.await;
 
 							metrics.on_validation_event(&res);
+							metrics.on_para_validation_event(para_id, &res);
+							if let Some(audit) = audit {
+								audit.finish(&res);
+							}
 							let _ = response_sender.send(res);
 						}
 					};
 
 					ctx.spawn("validate-from-exhaustive", bg.boxed())?;
 				},
+				CandidateValidationMessage::ValidateFromCodeHash(
+					persisted_validation_data,
+					validation_code_hash,
+					descriptor,
+					pov,
+					timeout,
+					response_sender,
+				) => {
+					let bg = {
+						let mut sender = ctx.sender().clone();
+						let metrics = metrics.clone();
+						let validation_host = validation_host.clone();
+						let bundle_exporter = bundle_exporter.clone();
+						let audit = audit_log.as_ref().map(|log| log.start(&descriptor));
+						let code_cache = code_cache.clone();
+
+						async move {
+							let _timer = metrics.time_validate_from_exhaustive();
+							let para_id = descriptor.para_id;
+							let res = match code_cache
+								.get_or_fetch(
+									&mut sender,
+									descriptor.relay_parent,
+									validation_code_hash,
+								)
+								.await
+							{
+								Ok(validation_code) =>
+									validate_and_export(
+										validation_host,
+										persisted_validation_data,
+										validation_code,
+										descriptor,
+										pov,
+										timeout,
+										&metrics,
+										bundle_exporter.as_ref(),
+									)
+									.await,
+								Err(e) => Err(e),
+							};
+
+							metrics.on_validation_event(&res);
+							metrics.on_para_validation_event(para_id, &res);
+							if let Some(audit) = audit {
+								audit.finish(&res);
+							}
+							let _ = response_sender.send(res);
+						}
+					};
+
+					ctx.spawn("validate-from-code-hash", bg.boxed())?;
+				},
 				CandidateValidationMessage::PreCheck(
 					relay_parent,
 					validation_code_hash,
@@ -224,6 +378,41 @@ where
 	}
 }
 
+// Validate a candidate with exhaustive parameters and export its bundle if it is invalid.
+async fn validate_and_export(
+	validation_host: ValidationHost,
+	persisted_validation_data: PersistedValidationData,
+	validation_code: ValidationCode,
+	descriptor: CandidateDescriptor,
+	pov: Arc<PoV>,
+	timeout: Duration,
+	metrics: &Metrics,
+	bundle_exporter: Option<&BundleExporter>,
+) -> Result<ValidationResult, ValidationFailed> {
+	let bundle = bundle_exporter.map(|_| ValidationBundle {
+		persisted_validation_data: persisted_validation_data.clone(),
+		validation_code: validation_code.clone(),
+		descriptor: descriptor.clone(),
+		pov: (*pov).clone(),
+	});
+	let res = validate_candidate_exhaustive(
+		validation_host,
+		persisted_validation_data,
+		validation_code,
+		descriptor,
+		pov,
+		timeout,
+		metrics,
+	)
+	.await;
+
+	if let (Some(exporter), Some(bundle)) = (bundle_exporter, bundle) {
+		exporter.export_if_invalid(bundle, &res);
+	}
+
+	res
+}
+
 struct RuntimeRequestFailed;
 
 async fn runtime_api_request<T, Sender>(
@@ -330,7 +519,9 @@ where
 #[derive(Debug)]
 enum AssumptionCheckOutcome {
 	Matches(PersistedValidationData, ValidationCode),
-	DoesNotMatch,
+	/// The hashes of the persisted validation data computed under each of the checked
+	/// assumptions, none of which matches the descriptor.
+	DoesNotMatch(Vec<(OccupiedCoreAssumption, Hash)>),
 	BadRequest,
 }
 
@@ -375,7 +566,7 @@ where
 			Ok(Some(v)) => AssumptionCheckOutcome::Matches(validation_data, v),
 		}
 	} else {
-		AssumptionCheckOutcome::DoesNotMatch
+		AssumptionCheckOutcome::DoesNotMatch(vec![(assumption, persisted_validation_data_hash)])
 	}
 }
 
@@ -399,6 +590,8 @@ where
 		// matched as well.
 	];
 
+	let mut computed = Vec::with_capacity(ASSUMPTIONS.len());
+
 	// Consider running these checks in parallel to reduce validation latency.
 	for assumption in ASSUMPTIONS {
 		let outcome = check_assumption_validation_data(sender, descriptor, *assumption).await;
@@ -406,11 +599,11 @@ where
 		match outcome {
 			AssumptionCheckOutcome::Matches(_, _) => return outcome,
 			AssumptionCheckOutcome::BadRequest => return outcome,
-			AssumptionCheckOutcome::DoesNotMatch => continue,
+			AssumptionCheckOutcome::DoesNotMatch(hashes) => computed.extend(hashes),
 		}
 	}
 
-	AssumptionCheckOutcome::DoesNotMatch
+	AssumptionCheckOutcome::DoesNotMatch(computed)
 }
 
 async fn validate_from_chain_state<Sender>(
@@ -420,6 +613,7 @@ async fn validate_from_chain_state<Sender>(
 	pov: Arc<PoV>,
 	timeout: Duration,
 	metrics: &Metrics,
+	bundle_exporter: Option<&BundleExporter>,
 ) -> Result<ValidationResult, ValidationFailed>
 where
 	Sender: SubsystemSender,
@@ -428,16 +622,34 @@ where
 		match find_assumed_validation_data(sender, &descriptor).await {
 			AssumptionCheckOutcome::Matches(validation_data, validation_code) =>
 				(validation_data, validation_code),
-			AssumptionCheckOutcome::DoesNotMatch => {
+			AssumptionCheckOutcome::DoesNotMatch(computed) => {
 				// If neither the assumption of the occupied core having the para included or the assumption
 				// of the occupied core timing out are valid, then the persisted_validation_data_hash in the descriptor
 				// is not based on the relay parent and is thus invalid.
+				tracing::debug!(
+					target: LOG_TARGET,
+					para_id = %descriptor.para_id,
+					relay_parent = ?descriptor.relay_parent,
+					expected = ?descriptor.persisted_validation_data_hash,
+					?computed,
+					"Persisted validation data of the candidate matches no assumption, \
+					the collator likely used stale validation data",
+				);
+				metrics.on_validation_data_mismatch(descriptor.para_id);
+
 				return Ok(ValidationResult::Invalid(InvalidCandidate::BadParent))
 			},
 			AssumptionCheckOutcome::BadRequest =>
 				return Err(ValidationFailed("Assumption Check: Bad request".into())),
 		};
 
+	let bundle = bundle_exporter.map(|_| ValidationBundle {
+		persisted_validation_data: validation_data.clone(),
+		validation_code: validation_code.clone(),
+		descriptor: descriptor.clone(),
+		pov: (*pov).clone(),
+	});
+
 	let validation_result = validate_candidate_exhaustive(
 		validation_host,
 		validation_data,
@@ -449,6 +661,10 @@ where
 	)
 	.await;
 
+	if let (Some(exporter), Some(bundle)) = (bundle_exporter, bundle) {
+		exporter.export_if_invalid(bundle, &validation_result);
+	}
+
 	if let Ok(ValidationResult::Valid(ref outputs, _)) = validation_result {
 		let (tx, rx) = oneshot::channel();
 		match runtime_api_request(
@@ -479,6 +695,9 @@ async fn validate_candidate_exhaustive(
 	metrics: &Metrics,
 ) -> Result<ValidationResult, ValidationFailed> {
 	let _timer = metrics.time_validate_candidate_exhaustive();
+	let _para_timer = metrics.time_para_validation(descriptor.para_id);
+
+	metrics.observe_pov_size(descriptor.para_id, pov.block_data.0.len());
 
 	let validation_code_hash = validation_code.hash();
 	tracing::debug!(
@@ -497,6 +716,8 @@ async fn validate_candidate_exhaustive(
 		return Ok(ValidationResult::Invalid(e))
 	}
 
+	let code_decompression_timer =
+		metrics.time_para_decompression(descriptor.para_id, DecompressionPhase::Code);
 	let raw_validation_code = match sp_maybe_compressed_blob::decompress(
 		&validation_code.0,
 		VALIDATION_CODE_BOMB_LIMIT,
@@ -510,6 +731,10 @@ async fn validate_candidate_exhaustive(
 		},
 	};
 
+	drop(code_decompression_timer);
+
+	let pov_decompression_timer =
+		metrics.time_para_decompression(descriptor.para_id, DecompressionPhase::PoV);
 	let raw_block_data =
 		match sp_maybe_compressed_blob::decompress(&pov.block_data.0, POV_BOMB_LIMIT) {
 			Ok(block_data) => BlockData(block_data.to_vec()),
@@ -520,6 +745,7 @@ async fn validate_candidate_exhaustive(
 				return Ok(ValidationResult::Invalid(InvalidCandidate::PoVDecompressionFailure))
 			},
 		};
+	drop(pov_decompression_timer);
 
 	let params = ValidationParams {
 		parent_head: persisted_validation_data.parent_head.clone(),
@@ -635,9 +861,7 @@ fn perform_basic_checks(
 	pov: &PoV,
 	validation_code_hash: &ValidationCodeHash,
 ) -> Result<(), InvalidCandidate> {
-	let pov_hash = pov.hash();
-
-	let encoded_pov_size = pov.encoded_size();
+	let (pov_hash, encoded_pov_size) = pov.hash_and_encoded_size();
 	if encoded_pov_size > max_pov_size as usize {
 		return Err(InvalidCandidate::ParamsTooLarge(encoded_pov_size as u64))
 	}
@@ -656,95 +880,3 @@ fn perform_basic_checks(
 
 	Ok(())
 }
-
-#[derive(Clone)]
-struct MetricsInner {
-	validation_requests: prometheus::CounterVec<prometheus::U64>,
-	validate_from_chain_state: prometheus::Histogram,
-	validate_from_exhaustive: prometheus::Histogram,
-	validate_candidate_exhaustive: prometheus::Histogram,
-}
-
-/// Candidate validation metrics.
-#[derive(Default, Clone)]
-pub struct Metrics(Option<MetricsInner>);
-
-impl Metrics {
-	fn on_validation_event(&self, event: &Result<ValidationResult, ValidationFailed>) {
-		if let Some(metrics) = &self.0 {
-			match event {
-				Ok(ValidationResult::Valid(_, _)) => {
-					metrics.validation_requests.with_label_values(&["valid"]).inc();
-				},
-				Ok(ValidationResult::Invalid(_)) => {
-					metrics.validation_requests.with_label_values(&["invalid"]).inc();
-				},
-				Err(_) => {
-					metrics.validation_requests.with_label_values(&["validation failure"]).inc();
-				},
-			}
-		}
-	}
-
-	/// Provide a timer for `validate_from_chain_state` which observes on drop.
-	fn time_validate_from_chain_state(
-		&self,
-	) -> Option<metrics::prometheus::prometheus::HistogramTimer> {
-		self.0.as_ref().map(|metrics| metrics.validate_from_chain_state.start_timer())
-	}
-
-	/// Provide a timer for `validate_from_exhaustive` which observes on drop.
-	fn time_validate_from_exhaustive(
-		&self,
-	) -> Option<metrics::prometheus::prometheus::HistogramTimer> {
-		self.0.as_ref().map(|metrics| metrics.validate_from_exhaustive.start_timer())
-	}
-
-	/// Provide a timer for `validate_candidate_exhaustive` which observes on drop.
-	fn time_validate_candidate_exhaustive(
-		&self,
-	) -> Option<metrics::prometheus::prometheus::HistogramTimer> {
-		self.0
-			.as_ref()
-			.map(|metrics| metrics.validate_candidate_exhaustive.start_timer())
-	}
-}
-
-impl metrics::Metrics for Metrics {
-	fn try_register(registry: &prometheus::Registry) -> Result<Self, prometheus::PrometheusError> {
-		let metrics = MetricsInner {
-			validation_requests: prometheus::register(
-				prometheus::CounterVec::new(
-					prometheus::Opts::new(
-						"selendra_parachain_validation_requests_total",
-						"Number of validation requests served.",
-					),
-					&["validity"],
-				)?,
-				registry,
-			)?,
-			validate_from_chain_state: prometheus::register(
-				prometheus::Histogram::with_opts(prometheus::HistogramOpts::new(
-					"selendra_parachain_candidate_validation_validate_from_chain_state",
-					"Time spent within `candidate_validation::validate_from_chain_state`",
-				))?,
-				registry,
-			)?,
-			validate_from_exhaustive: prometheus::register(
-				prometheus::Histogram::with_opts(prometheus::HistogramOpts::new(
-					"selendra_parachain_candidate_validation_validate_from_exhaustive",
-					"Time spent within `candidate_validation::validate_from_exhaustive`",
-				))?,
-				registry,
-			)?,
-			validate_candidate_exhaustive: prometheus::register(
-				prometheus::Histogram::with_opts(prometheus::HistogramOpts::new(
-					"selendra_parachain_candidate_validation_validate_candidate_exhaustive",
-					"Time spent within `candidate_validation::validate_candidate_exhaustive`",
-				))?,
-				registry,
-			)?,
-		};
-		Ok(Metrics(Some(metrics)))
-	}
-}
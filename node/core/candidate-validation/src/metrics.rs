@@ -0,0 +1,291 @@
+// Copyright 2020-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Prometheus metrics of the candidate validation subsystem.
+
+use std::{collections::HashSet, sync::Arc};
+
+use selendra_node_primitives::ValidationResult;
+use selendra_node_subsystem::messages::ValidationFailed;
+use selendra_node_subsystem_util::metrics::{self, prometheus};
+use selendra_primitives::v1::Id as ParaId;
+
+/// Label used for all paras which are not part of the para allow-list.
+const OTHER_PARAS_LABEL: &str = "other";
+
+#[derive(Clone)]
+struct MetricsInner {
+	validation_requests: prometheus::CounterVec<prometheus::U64>,
+	validate_from_chain_state: prometheus::Histogram,
+	validate_from_exhaustive: prometheus::Histogram,
+	validate_candidate_exhaustive: prometheus::Histogram,
+	para_validation_requests: prometheus::CounterVec<prometheus::U64>,
+	para_validation_duration: prometheus::HistogramVec,
+	para_pov_size: prometheus::HistogramVec,
+	para_decompression_duration: prometheus::HistogramVec,
+	para_validation_data_mismatches: prometheus::CounterVec<prometheus::U64>,
+	/// Paras which get a dedicated `para_id` label. All others are reported as
+	/// [`OTHER_PARAS_LABEL`].
+	para_allow_list: Arc<HashSet<ParaId>>,
+}
+
+/// Candidate validation metrics.
+#[derive(Default, Clone)]
+pub struct Metrics(Option<MetricsInner>);
+
+impl Metrics {
+	/// Restrict the paras which are reported with their own `para_id` label.
+	///
+	/// This bounds the cardinality of the per-para metrics. Without an allow list all paras are
+	/// reported as [`OTHER_PARAS_LABEL`].
+	pub fn with_para_allow_list(mut self, allow_list: HashSet<ParaId>) -> Self {
+		if let Some(metrics) = self.0.as_mut() {
+			metrics.para_allow_list = Arc::new(allow_list);
+		}
+		self
+	}
+
+	pub(crate) fn on_validation_event(&self, event: &Result<ValidationResult, ValidationFailed>) {
+		if let Some(metrics) = &self.0 {
+			metrics.validation_requests.with_label_values(&[validity_label(event)]).inc();
+		}
+	}
+
+	/// Record the outcome of a validation request for the given para.
+	pub(crate) fn on_para_validation_event(
+		&self,
+		para_id: ParaId,
+		event: &Result<ValidationResult, ValidationFailed>,
+	) {
+		if let Some(metrics) = &self.0 {
+			metrics
+				.para_validation_requests
+				.with_label_values(&[&metrics.para_label(para_id), validity_label(event)])
+				.inc();
+		}
+	}
+
+	/// Record the size of the PoV submitted for the given para.
+	pub(crate) fn observe_pov_size(&self, para_id: ParaId, pov_size: usize) {
+		if let Some(metrics) = &self.0 {
+			metrics
+				.para_pov_size
+				.with_label_values(&[&metrics.para_label(para_id)])
+				.observe(pov_size as f64);
+		}
+	}
+
+	/// Note that the persisted validation data of a candidate of the given para matched none of
+	/// the occupied core assumptions.
+	pub(crate) fn on_validation_data_mismatch(&self, para_id: ParaId) {
+		if let Some(metrics) = &self.0 {
+			metrics
+				.para_validation_data_mismatches
+				.with_label_values(&[&metrics.para_label(para_id)])
+				.inc();
+		}
+	}
+
+	/// Provide a timer for decompressing the validation code or the PoV of a candidate of the
+	/// given para, before it is executed, which observes on drop.
+	pub(crate) fn time_para_decompression(
+		&self,
+		para_id: ParaId,
+		phase: DecompressionPhase,
+	) -> Option<metrics::prometheus::prometheus::HistogramTimer> {
+		self.0.as_ref().map(|metrics| {
+			metrics
+				.para_decompression_duration
+				.with_label_values(&[&metrics.para_label(para_id), phase.label()])
+				.start_timer()
+		})
+	}
+
+	/// Provide a timer for `validate_from_chain_state` which observes on drop.
+	pub(crate) fn time_validate_from_chain_state(
+		&self,
+	) -> Option<metrics::prometheus::prometheus::HistogramTimer> {
+		self.0.as_ref().map(|metrics| metrics.validate_from_chain_state.start_timer())
+	}
+
+	/// Provide a timer for `validate_from_exhaustive` which observes on drop.
+	pub(crate) fn time_validate_from_exhaustive(
+		&self,
+	) -> Option<metrics::prometheus::prometheus::HistogramTimer> {
+		self.0.as_ref().map(|metrics| metrics.validate_from_exhaustive.start_timer())
+	}
+
+	/// Provide a timer for `validate_candidate_exhaustive` which observes on drop.
+	pub(crate) fn time_validate_candidate_exhaustive(
+		&self,
+	) -> Option<metrics::prometheus::prometheus::HistogramTimer> {
+		self.0
+			.as_ref()
+			.map(|metrics| metrics.validate_candidate_exhaustive.start_timer())
+	}
+
+	/// Provide a timer for the validation of a candidate of the given para which observes on
+	/// drop.
+	pub(crate) fn time_para_validation(
+		&self,
+		para_id: ParaId,
+	) -> Option<metrics::prometheus::prometheus::HistogramTimer> {
+		self.0.as_ref().map(|metrics| {
+			metrics
+				.para_validation_duration
+				.with_label_values(&[&metrics.para_label(para_id)])
+				.start_timer()
+		})
+	}
+}
+
+impl MetricsInner {
+	fn para_label(&self, para_id: ParaId) -> String {
+		if self.para_allow_list.contains(&para_id) {
+			u32::from(para_id).to_string()
+		} else {
+			OTHER_PARAS_LABEL.into()
+		}
+	}
+}
+
+/// The preparatory phases of validating a candidate which are timed separately from its
+/// execution.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum DecompressionPhase {
+	/// Decompressing the validation code.
+	Code,
+	/// Decompressing the PoV.
+	PoV,
+}
+
+impl DecompressionPhase {
+	fn label(self) -> &'static str {
+		match self {
+			DecompressionPhase::Code => "code",
+			DecompressionPhase::PoV => "pov",
+		}
+	}
+}
+
+fn validity_label(event: &Result<ValidationResult, ValidationFailed>) -> &'static str {
+	match event {
+		Ok(ValidationResult::Valid(_, _)) => "valid",
+		Ok(ValidationResult::Invalid(_)) => "invalid",
+		Err(_) => "validation failure",
+	}
+}
+
+impl metrics::Metrics for Metrics {
+	fn try_register(registry: &prometheus::Registry) -> Result<Self, prometheus::PrometheusError> {
+		let metrics = MetricsInner {
+			validation_requests: prometheus::register(
+				prometheus::CounterVec::new(
+					prometheus::Opts::new(
+						"selendra_parachain_validation_requests_total",
+						"Number of validation requests served.",
+					),
+					&["validity"],
+				)?,
+				registry,
+			)?,
+			validate_from_chain_state: prometheus::register(
+				prometheus::Histogram::with_opts(prometheus::HistogramOpts::new(
+					"selendra_parachain_candidate_validation_validate_from_chain_state",
+					"Time spent within `candidate_validation::validate_from_chain_state`",
+				))?,
+				registry,
+			)?,
+			validate_from_exhaustive: prometheus::register(
+				prometheus::Histogram::with_opts(prometheus::HistogramOpts::new(
+					"selendra_parachain_candidate_validation_validate_from_exhaustive",
+					"Time spent within `candidate_validation::validate_from_exhaustive`",
+				))?,
+				registry,
+			)?,
+			validate_candidate_exhaustive: prometheus::register(
+				prometheus::Histogram::with_opts(prometheus::HistogramOpts::new(
+					"selendra_parachain_candidate_validation_validate_candidate_exhaustive",
+					"Time spent within `candidate_validation::validate_candidate_exhaustive`",
+				))?,
+				registry,
+			)?,
+			para_validation_requests: prometheus::register(
+				prometheus::CounterVec::new(
+					prometheus::Opts::new(
+						"selendra_parachain_candidate_validation_para_requests_total",
+						"Number of validation requests served per para.",
+					),
+					&["para_id", "validity"],
+				)?,
+				registry,
+			)?,
+			para_validation_duration: prometheus::register(
+				prometheus::HistogramVec::new(
+					prometheus::HistogramOpts::new(
+						"selendra_parachain_candidate_validation_para_duration",
+						"Time spent validating a candidate per para",
+					)
+					.buckets(vec![
+						// This is synchronized with `APPROVAL_EXECUTION_TIMEOUT`  and
+						// `BACKING_EXECUTION_TIMEOUT` constants in `node/primitives/src/lib.rs`
+						0.1, 0.25, 0.5, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 12.0,
+					]),
+					&["para_id"],
+				)?,
+				registry,
+			)?,
+			para_pov_size: prometheus::register(
+				prometheus::HistogramVec::new(
+					prometheus::HistogramOpts::new(
+						"selendra_parachain_candidate_validation_para_pov_size",
+						"Size of the compressed PoV submitted for validation per para, in bytes",
+					)
+					.buckets(prometheus::exponential_buckets(16_384.0, 2.0, 10)?),
+					&["para_id"],
+				)?,
+				registry,
+			)?,
+			para_decompression_duration: prometheus::register(
+				prometheus::HistogramVec::new(
+					prometheus::HistogramOpts::new(
+						"selendra_parachain_candidate_validation_para_decompression_duration",
+						"Time spent decompressing the validation code or the PoV of a candidate \
+						per para, before executing it",
+					)
+					.buckets(vec![
+						0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0,
+					]),
+					&["para_id", "phase"],
+				)?,
+				registry,
+			)?,
+			para_validation_data_mismatches: prometheus::register(
+				prometheus::CounterVec::new(
+					prometheus::Opts::new(
+						"selendra_parachain_candidate_validation_data_mismatches_total",
+						"Number of candidates per para whose persisted validation data matched \
+						none of the occupied core assumptions",
+					),
+					&["para_id"],
+				)?,
+				registry,
+			)?,
+			para_allow_list: Default::default(),
+		};
+		Ok(Metrics(Some(metrics)))
+	}
+}
@@ -0,0 +1,262 @@
+// Copyright 2020-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Capturing and replaying candidate validations.
+//!
+//! When configured, the subsystem writes a [`ValidationBundle`] to disk for every candidate it
+//! finds invalid. Such a bundle, e.g. taken from a validator which took part in a dispute, can be
+//! validated again with [`replay_validation`] to reproduce the outcome offline.
+//!
+//! The bundles are written by a [`BundleWriter`] running as a blocking task, so the validations
+//! never wait for the disk. The oldest bundles are removed once the export directory holds more
+//! than the configured number or size of bundles.
+
+use std::{
+	fs, io,
+	path::{Path, PathBuf},
+	sync::{Arc, Mutex},
+	time::{Duration, SystemTime},
+};
+
+use futures::{channel::mpsc, StreamExt};
+use parity_scale_codec::{Decode, Encode};
+use selendra_node_core_pvf::ValidationHost;
+use selendra_node_primitives::{PoV, ValidationResult};
+use selendra_node_subsystem::messages::ValidationFailed;
+use selendra_primitives::v1::{CandidateDescriptor, PersistedValidationData, ValidationCode};
+
+use crate::{validate_candidate_exhaustive, Metrics, ValidationBackend, LOG_TARGET};
+
+/// The file extension of exported validation bundles.
+const BUNDLE_EXTENSION: &str = "bundle";
+
+/// The number of bundles waiting to be written. Further bundles are dropped until the writer
+/// catches up, which bounds the memory held by the export.
+const EXPORT_QUEUE_SIZE: usize = 8;
+
+/// The default number of bundles kept in the export directory.
+pub const DEFAULT_MAX_BUNDLES: usize = 64;
+
+/// The default total size of the bundles kept in the export directory, in bytes.
+pub const DEFAULT_MAX_TOTAL_SIZE: u64 = 1024 * 1024 * 1024;
+
+/// All inputs of the validation of a candidate.
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub struct ValidationBundle {
+	/// The persisted validation data the candidate was validated against.
+	pub persisted_validation_data: PersistedValidationData,
+	/// The validation code of the para.
+	pub validation_code: ValidationCode,
+	/// The descriptor of the candidate.
+	pub descriptor: CandidateDescriptor,
+	/// The proof of validity of the candidate.
+	pub pov: PoV,
+}
+
+impl ValidationBundle {
+	/// Read a SCALE encoded bundle from the given file.
+	pub fn read_from(path: &Path) -> io::Result<Self> {
+		let encoded = fs::read(path)?;
+		Self::decode(&mut &encoded[..]).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+	}
+
+	/// Write the bundle SCALE encoded to the given file.
+	pub fn write_to(&self, path: &Path) -> io::Result<()> {
+		fs::write(path, self.encode())
+	}
+}
+
+/// Configuration of the export of invalid candidates.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BundleExportConfig {
+	/// The directory the bundles are written into.
+	pub dir: PathBuf,
+	/// The maximum number of bundles kept in the directory.
+	pub max_bundles: usize,
+	/// The maximum total size of the bundles kept in the directory, in bytes. A single bundle
+	/// larger than this is not exported at all.
+	pub max_total_size: u64,
+}
+
+impl BundleExportConfig {
+	/// Export into the given directory, with the default limits.
+	pub fn new(dir: PathBuf) -> Self {
+		BundleExportConfig {
+			dir,
+			max_bundles: DEFAULT_MAX_BUNDLES,
+			max_total_size: DEFAULT_MAX_TOTAL_SIZE,
+		}
+	}
+}
+
+/// Hands the bundles of candidates found invalid to the [`BundleWriter`].
+#[derive(Debug, Clone)]
+pub struct BundleExporter {
+	to_writer: Arc<Mutex<mpsc::Sender<ValidationBundle>>>,
+}
+
+impl BundleExporter {
+	/// Create an exporter and the writer which has to be run for the bundles to be written.
+	pub fn new(config: BundleExportConfig) -> (Self, BundleWriter) {
+		let (to_writer, from_exporter) = mpsc::channel(EXPORT_QUEUE_SIZE);
+		(
+			BundleExporter { to_writer: Arc::new(Mutex::new(to_writer)) },
+			BundleWriter { config, from_exporter },
+		)
+	}
+
+	/// Export the bundle if the validation found the candidate invalid.
+	///
+	/// This never blocks. The bundle is dropped if the writer is lagging behind.
+	pub(crate) fn export_if_invalid(
+		&self,
+		bundle: ValidationBundle,
+		result: &Result<ValidationResult, ValidationFailed>,
+	) {
+		if !matches!(result, Ok(ValidationResult::Invalid(_))) {
+			return
+		}
+
+		let para_id = bundle.descriptor.para_id;
+		let mut to_writer = match self.to_writer.lock() {
+			Ok(to_writer) => to_writer,
+			Err(poisoned) => poisoned.into_inner(),
+		};
+		if let Err(err) = to_writer.try_send(bundle) {
+			tracing::warn!(
+				target: LOG_TARGET,
+				?para_id,
+				full = err.is_full(),
+				"Dropped the validation bundle of an invalid candidate",
+			);
+		}
+	}
+}
+
+/// Writes the bundles handed over by the [`BundleExporter`] into the export directory.
+pub struct BundleWriter {
+	config: BundleExportConfig,
+	from_exporter: mpsc::Receiver<ValidationBundle>,
+}
+
+impl BundleWriter {
+	/// Write the bundles until all exporters are dropped.
+	///
+	/// This does blocking file system operations, so it has to be spawned as a blocking task.
+	pub async fn run(mut self) {
+		while let Some(bundle) = self.from_exporter.next().await {
+			let path = self.config.dir.join(format!(
+				"{}-{:?}.{}",
+				u32::from(bundle.descriptor.para_id),
+				bundle.descriptor.pov_hash,
+				BUNDLE_EXTENSION,
+			));
+
+			match self.write(&bundle, &path) {
+				Ok(()) => tracing::info!(
+					target: LOG_TARGET,
+					?path,
+					para_id = ?bundle.descriptor.para_id,
+					"Exported the validation bundle of an invalid candidate",
+				),
+				Err(err) => tracing::warn!(
+					target: LOG_TARGET,
+					?path,
+					?err,
+					"Failed to export the validation bundle of an invalid candidate",
+				),
+			}
+		}
+	}
+
+	fn write(&self, bundle: &ValidationBundle, path: &Path) -> io::Result<()> {
+		let encoded = bundle.encode();
+		let size = encoded.len() as u64;
+		if size > self.config.max_total_size {
+			return Err(io::Error::new(
+				io::ErrorKind::Other,
+				format!(
+					"the bundle has {} bytes, more than the {} bytes allowed in total",
+					size, self.config.max_total_size,
+				),
+			))
+		}
+
+		fs::create_dir_all(&self.config.dir)?;
+		self.make_room(size)?;
+		fs::write(path, encoded)
+	}
+
+	/// Remove the oldest bundles until one more of the given size fits within the limits.
+	fn make_room(&self, size: u64) -> io::Result<()> {
+		let mut bundles = Vec::new();
+		for entry in fs::read_dir(&self.config.dir)? {
+			let path = entry?.path();
+			if path.extension().map_or(true, |ext| ext != BUNDLE_EXTENSION) {
+				continue
+			}
+
+			let metadata = fs::metadata(&path)?;
+			let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+			bundles.push((modified, metadata.len(), path));
+		}
+		bundles.sort();
+
+		let mut count = bundles.len();
+		let mut total_size = bundles.iter().map(|(_, len, _)| len).sum::<u64>();
+		for (_, len, path) in bundles {
+			if count < self.config.max_bundles &&
+				total_size.saturating_add(size) <= self.config.max_total_size
+			{
+				break
+			}
+
+			fs::remove_file(&path)?;
+			count -= 1;
+			total_size -= len;
+		}
+
+		Ok(())
+	}
+}
+
+/// Validate the candidate of the given bundle again.
+///
+/// This performs the exact same checks as the subsystem does on `ValidateFromExhaustive` requests.
+pub async fn replay_validation(
+	validation_host: ValidationHost,
+	bundle: ValidationBundle,
+	timeout: Duration,
+) -> Result<ValidationResult, ValidationFailed> {
+	replay_with_backend(validation_host, bundle, timeout).await
+}
+
+pub(crate) async fn replay_with_backend(
+	validation_backend: impl ValidationBackend,
+	bundle: ValidationBundle,
+	timeout: Duration,
+) -> Result<ValidationResult, ValidationFailed> {
+	validate_candidate_exhaustive(
+		validation_backend,
+		bundle.persisted_validation_data,
+		bundle.validation_code,
+		bundle.descriptor,
+		Arc::new(bundle.pov),
+		timeout,
+		&Metrics::default(),
+	)
+	.await
+}
@@ -337,7 +337,15 @@ fn check_does_not_match() {
 			}
 		);
 
-		assert_matches!(check_result.await, AssumptionCheckOutcome::DoesNotMatch);
+		assert_matches!(
+			check_result.await,
+			AssumptionCheckOutcome::DoesNotMatch(computed) => {
+				assert_eq!(
+					computed,
+					vec![(OccupiedCoreAssumption::Included, validation_data.hash())],
+				);
+			}
+		);
 	};
 
 	let test_fut = future::join(test_fut, check_fut);
@@ -849,3 +857,244 @@ fn precheck_properly_classifies_outcomes() {
 	inner(Err(PrepareError::TimedOut), PreCheckOutcome::Failed);
 	inner(Err(PrepareError::DidNotMakeIt), PreCheckOutcome::Failed);
 }
+
+#[test]
+fn builder_rejects_missing_worker_binary() {
+	let binary = PathBuf::from("/this/worker/does/not/exist");
+	let config = Config {
+		artifacts_cache_path: PathBuf::new(),
+		isolation_strategy: IsolationStrategy::ExternalProcessCustomHost {
+			binary: binary.clone(),
+			sandbox: Default::default(),
+		},
+		metrics_para_allow_list: Default::default(),
+		invalid_candidate_export: None,
+		audit_log: None,
+	};
+
+	assert_matches!(
+		CandidateValidationSubsystem::builder(config).build(),
+		Err(Error::WorkerNotFound(b)) if b == binary
+	);
+}
+
+#[test]
+fn invalid_candidate_bundle_is_exported_and_replays() {
+	let validation_data = PersistedValidationData { max_pov_size: 1024, ..Default::default() };
+
+	let pov = PoV { block_data: BlockData(vec![1; 32]) };
+	let validation_code = ValidationCode(vec![2; 16]);
+
+	let descriptor = make_valid_candidate_descriptor(
+		1.into(),
+		dummy_hash(),
+		validation_data.hash(),
+		pov.hash(),
+		validation_code.hash(),
+		dummy_hash(),
+		dummy_hash(),
+		Sr25519Keyring::Alice,
+	);
+
+	let bundle = ValidationBundle {
+		persisted_validation_data: validation_data,
+		validation_code,
+		descriptor,
+		pov,
+	};
+	let backend = || {
+		MockValidateCandidateBackend::with_hardcoded_result(Err(ValidationError::InvalidCandidate(
+			WasmInvalidCandidate::HardTimeout,
+		)))
+	};
+
+	let v = executor::block_on(replay::replay_with_backend(
+		backend(),
+		bundle.clone(),
+		Duration::from_secs(0),
+	));
+	assert_matches!(v, Ok(ValidationResult::Invalid(InvalidCandidate::Timeout)));
+
+	let export_dir = tempfile::tempdir().unwrap();
+	let (exporter, writer) =
+		BundleExporter::new(BundleExportConfig::new(export_dir.path().to_owned()));
+	exporter.export_if_invalid(bundle.clone(), &v);
+	drop(exporter);
+	executor::block_on(writer.run());
+
+	let exported = std::fs::read_dir(export_dir.path())
+		.unwrap()
+		.map(|entry| entry.unwrap().path())
+		.collect::<Vec<_>>();
+	assert_eq!(exported.len(), 1);
+
+	let replayed_bundle = ValidationBundle::read_from(&exported[0]).unwrap();
+	assert_eq!(replayed_bundle, bundle);
+
+	let replayed = executor::block_on(replay::replay_with_backend(
+		backend(),
+		replayed_bundle,
+		Duration::from_secs(0),
+	));
+	assert_matches!(replayed, Ok(ValidationResult::Invalid(InvalidCandidate::Timeout)));
+}
+
+#[test]
+fn bundle_export_removes_the_oldest_bundles() {
+	let export_dir = tempfile::tempdir().unwrap();
+	let (exporter, writer) = BundleExporter::new(BundleExportConfig {
+		max_bundles: 2,
+		..BundleExportConfig::new(export_dir.path().to_owned())
+	});
+	let invalid = Ok(ValidationResult::Invalid(InvalidCandidate::Timeout));
+
+	let bundles = (0..3u8)
+		.map(|i| {
+			let pov = PoV { block_data: BlockData(vec![i; 32]) };
+			let validation_code = ValidationCode(vec![2; 16]);
+			let descriptor = make_valid_candidate_descriptor(
+				1.into(),
+				dummy_hash(),
+				dummy_hash(),
+				pov.hash(),
+				validation_code.hash(),
+				dummy_hash(),
+				dummy_hash(),
+				Sr25519Keyring::Alice,
+			);
+			ValidationBundle {
+				persisted_validation_data: Default::default(),
+				validation_code,
+				descriptor,
+				pov,
+			}
+		})
+		.collect::<Vec<_>>();
+	for bundle in &bundles {
+		exporter.export_if_invalid(bundle.clone(), &invalid);
+	}
+	drop(exporter);
+	executor::block_on(writer.run());
+
+	let exported = std::fs::read_dir(export_dir.path())
+		.unwrap()
+		.map(|entry| ValidationBundle::read_from(&entry.unwrap().path()).unwrap())
+		.collect::<Vec<_>>();
+	assert_eq!(exported.len(), 2);
+	assert!(exported.contains(&bundles[2]));
+}
+
+#[test]
+fn bundle_export_skips_bundles_above_the_size_limit() {
+	let export_dir = tempfile::tempdir().unwrap();
+	let (exporter, writer) = BundleExporter::new(BundleExportConfig {
+		max_total_size: 16,
+		..BundleExportConfig::new(export_dir.path().to_owned())
+	});
+
+	let pov = PoV { block_data: BlockData(vec![1; 32]) };
+	let validation_code = ValidationCode(vec![2; 16]);
+	let descriptor = make_valid_candidate_descriptor(
+		1.into(),
+		dummy_hash(),
+		dummy_hash(),
+		pov.hash(),
+		validation_code.hash(),
+		dummy_hash(),
+		dummy_hash(),
+		Sr25519Keyring::Alice,
+	);
+	let bundle = ValidationBundle {
+		persisted_validation_data: Default::default(),
+		validation_code,
+		descriptor,
+		pov,
+	};
+	exporter.export_if_invalid(bundle, &Ok(ValidationResult::Invalid(InvalidCandidate::Timeout)));
+	drop(exporter);
+	executor::block_on(writer.run());
+
+	assert_eq!(std::fs::read_dir(export_dir.path()).unwrap().count(), 0);
+}
+
+#[test]
+fn validation_code_is_fetched_by_hash_once() {
+	let relay_parent = [3; 32].into();
+	let validation_code = ValidationCode(vec![3; 16]);
+	let validation_code_hash = validation_code.hash();
+
+	let pool = TaskExecutor::new();
+	let (mut ctx, mut ctx_handle) =
+		test_helpers::make_subsystem_context::<AllMessages, _>(pool.clone());
+
+	let code_cache = ValidationCodeCache::default();
+
+	let fetch_fut = {
+		let code_cache = code_cache.clone();
+		let mut sender = ctx.sender().clone();
+		async move { code_cache.get_or_fetch(&mut sender, relay_parent, validation_code_hash).await }
+	};
+
+	let test_fut = {
+		let validation_code = validation_code.clone();
+		async move {
+			assert_matches!(
+				ctx_handle.recv().await,
+				AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+					rp,
+					RuntimeApiRequest::ValidationCodeByHash(vch, tx),
+				)) => {
+					assert_eq!(vch, validation_code_hash);
+					assert_eq!(rp, relay_parent);
+
+					let _ = tx.send(Ok(Some(validation_code)));
+				}
+			);
+			ctx_handle
+		}
+	};
+
+	let (fetched, mut ctx_handle) = executor::block_on(future::join(fetch_fut, test_fut));
+	assert_matches!(fetched, Ok(code) if code == validation_code);
+
+	// The second request is served from the cache.
+	let cached = executor::block_on(code_cache.get_or_fetch(
+		ctx.sender(),
+		relay_parent,
+		validation_code_hash,
+	));
+	assert_matches!(cached, Ok(code) if code == validation_code);
+
+	drop(ctx);
+	assert!(executor::block_on(ctx_handle.try_recv()).is_none());
+}
+
+#[test]
+fn builder_rejects_worker_binary_with_partially_matching_version() {
+	use std::os::unix::fs::PermissionsExt as _;
+
+	// The worker reports a version which starts with the expected one.
+	let dir = tempfile::tempdir().unwrap();
+	let binary = dir.path().join("worker");
+	std::fs::write(&binary, "#!/bin/sh\necho \"selendra-worker 0.9.18-dev\"\n").unwrap();
+	std::fs::set_permissions(&binary, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+	let config = Config {
+		artifacts_cache_path: PathBuf::new(),
+		isolation_strategy: IsolationStrategy::ExternalProcessCustomHost {
+			binary,
+			sandbox: SandboxConfig {
+				expected_version: Some("0.9.18".into()),
+				..Default::default()
+			},
+		},
+		metrics_para_allow_list: Default::default(),
+		invalid_candidate_export: None,
+		audit_log: None,
+	};
+
+	assert_matches!(
+		CandidateValidationSubsystem::builder(config).build(),
+		Err(Error::WorkerVersionMismatch { found, .. }) if found == "0.9.18-dev"
+	);
+}
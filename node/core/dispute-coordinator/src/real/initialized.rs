@@ -35,8 +35,8 @@ use selendra_node_primitives::{
 };
 use selendra_node_subsystem::{
 	messages::{
-		BlockDescription, DisputeCoordinatorMessage, DisputeDistributionMessage,
-		ImportStatementsResult, RuntimeApiMessage, RuntimeApiRequest,
+		ApprovalVotingMessage, BlockDescription, DisputeCoordinatorMessage,
+		DisputeDistributionMessage, ImportStatementsResult, RuntimeApiMessage, RuntimeApiRequest,
 	},
 	overseer, ActivatedLeaf, ActiveLeavesUpdate, FromOverseer, OverseerSignal, SubsystemContext,
 };
@@ -944,6 +944,10 @@ impl Initialized {
 			} else {
 				self.metrics.on_queued_best_effort_participation();
 			}
+			// Get our approval checking for the candidate going as well, without waiting for
+			// the tranche of our assignment:
+			ctx.send_message(ApprovalVotingMessage::ForceTriggerAssignment(candidate_hash))
+				.await;
 			// Participate whenever the imported vote was local & we did not had no cast
 			// previously:
 			let r = self
@@ -30,8 +30,8 @@ use selendra_node_primitives::{AvailableData, BlockData, InvalidCandidate, PoV};
 use selendra_node_subsystem::{
 	jaeger,
 	messages::{
-		AllMessages, DisputeCoordinatorMessage, RuntimeApiMessage, RuntimeApiRequest,
-		ValidationFailed,
+		AllMessages, ApprovalVotingMessage, DisputeCoordinatorMessage, RuntimeApiMessage,
+		RuntimeApiRequest, ValidationFailed,
 	},
 	ActivatedLeaf, ActiveLeavesUpdate, LeafStatus,
 };
@@ -109,6 +109,7 @@ async fn activate_leaf(
 
 /// Full participation happy path as seen via the overseer.
 pub async fn participation_full_happy_path(ctx_handle: &mut VirtualOverseer) {
+	force_trigger_assignment(ctx_handle).await;
 	recover_available_data(ctx_handle).await;
 	fetch_validation_code(ctx_handle).await;
 	store_available_data(ctx_handle, true).await;
@@ -126,6 +127,7 @@ pub async fn participation_full_happy_path(ctx_handle: &mut VirtualOverseer) {
 
 /// Full participation with failing availability recovery.
 pub async fn participation_missing_availability(ctx_handle: &mut VirtualOverseer) {
+	force_trigger_assignment(ctx_handle).await;
 	assert_matches!(
 		ctx_handle.recv().await,
 		AllMessages::AvailabilityRecovery(
@@ -137,6 +139,15 @@ pub async fn participation_missing_availability(ctx_handle: &mut VirtualOverseer
 	);
 }
 
+/// The dispute coordinator asks approval voting for our assignment when queuing participation.
+async fn force_trigger_assignment(virtual_overseer: &mut VirtualOverseer) {
+	assert_matches!(
+		virtual_overseer.recv().await,
+		AllMessages::ApprovalVoting(ApprovalVotingMessage::ForceTriggerAssignment(_)),
+		"overseer did not receive force trigger assignment message",
+	);
+}
+
 async fn recover_available_data(virtual_overseer: &mut VirtualOverseer) {
 	let pov_block = PoV { block_data: BlockData(Vec::new()) };
 
@@ -21,7 +21,7 @@ use crate::{
 	artifacts::{ArtifactId, ArtifactPathId},
 	host::ResultSender,
 	metrics::Metrics,
-	worker_common::{IdleWorker, WorkerHandle},
+	worker_common::{IdleWorker, SandboxConfig, WorkerHandle},
 	InvalidCandidate, ValidationError, LOG_TARGET,
 };
 use async_std::path::PathBuf;
@@ -109,6 +109,7 @@ struct Queue {
 
 	program_path: PathBuf,
 	spawn_timeout: Duration,
+	sandbox: SandboxConfig,
 
 	/// The queue of jobs that are waiting for a worker to pick up.
 	queue: VecDeque<ExecuteJob>,
@@ -122,12 +123,14 @@ impl Queue {
 		program_path: PathBuf,
 		worker_capacity: usize,
 		spawn_timeout: Duration,
+		sandbox: SandboxConfig,
 		to_queue_rx: mpsc::Receiver<ToQueue>,
 	) -> Self {
 		Self {
 			metrics,
 			program_path,
 			spawn_timeout,
+			sandbox,
 			to_queue_rx,
 			queue: VecDeque::new(),
 			mux: Mux::new(),
@@ -290,17 +293,22 @@ fn spawn_extra_worker(queue: &mut Queue) {
 	queue.metrics.execute_worker().on_begin_spawn();
 	tracing::debug!(target: LOG_TARGET, "spawning an extra worker");
 
-	queue
-		.mux
-		.push(spawn_worker_task(queue.program_path.clone(), queue.spawn_timeout).boxed());
+	queue.mux.push(
+		spawn_worker_task(queue.program_path.clone(), queue.spawn_timeout, queue.sandbox.clone())
+			.boxed(),
+	);
 	queue.workers.spawn_inflight += 1;
 }
 
-async fn spawn_worker_task(program_path: PathBuf, spawn_timeout: Duration) -> QueueEvent {
+async fn spawn_worker_task(
+	program_path: PathBuf,
+	spawn_timeout: Duration,
+	sandbox: SandboxConfig,
+) -> QueueEvent {
 	use futures_timer::Delay;
 
 	loop {
-		match super::worker::spawn(&program_path, spawn_timeout).await {
+		match super::worker::spawn(&program_path, spawn_timeout, &sandbox).await {
 			Ok((idle, handle)) => break QueueEvent::Spawn(idle, handle),
 			Err(err) => {
 				tracing::warn!(target: LOG_TARGET, "failed to spawn an execute worker: {:?}", err);
@@ -350,8 +358,11 @@ pub fn start(
 	program_path: PathBuf,
 	worker_capacity: usize,
 	spawn_timeout: Duration,
+	sandbox: SandboxConfig,
 ) -> (mpsc::Sender<ToQueue>, impl Future<Output = ()>) {
 	let (to_queue_tx, to_queue_rx) = mpsc::channel(20);
-	let run = Queue::new(metrics, program_path, worker_capacity, spawn_timeout, to_queue_rx).run();
+	let run =
+		Queue::new(metrics, program_path, worker_capacity, spawn_timeout, sandbox, to_queue_rx)
+			.run();
 	(to_queue_tx, run)
 }
@@ -19,7 +19,7 @@ use crate::{
 	executor_intf::TaskExecutor,
 	worker_common::{
 		bytes_to_path, framed_recv, framed_send, path_to_bytes, spawn_with_program_path,
-		worker_event_loop, IdleWorker, SpawnErr, WorkerHandle,
+		worker_event_loop, IdleWorker, SandboxConfig, SpawnErr, WorkerHandle,
 	},
 	LOG_TARGET,
 };
@@ -32,6 +32,7 @@ use futures::FutureExt;
 use futures_timer::Delay;
 use parity_scale_codec::{Decode, Encode};
 use selendra_parachain::primitives::ValidationResult;
+use selendra_primitives::v1::{decode_bounded_validation_result, CommitmentsLimits};
 use std::time::{Duration, Instant};
 
 /// Spawns a new worker with the given program path that acts as the worker and the spawn timeout.
@@ -40,8 +41,10 @@ use std::time::{Duration, Instant};
 pub async fn spawn(
 	program_path: &Path,
 	spawn_timeout: Duration,
+	sandbox: &SandboxConfig,
 ) -> Result<(IdleWorker, WorkerHandle), SpawnErr> {
-	spawn_with_program_path("execute", program_path, &["execute-worker"], spawn_timeout).await
+	spawn_with_program_path("execute", program_path, &["execute-worker"], spawn_timeout, sandbox)
+		.await
 }
 
 /// Outcome of PVF execution.
@@ -236,7 +239,11 @@ async fn validate_using_artifact(
 
 	let duration_ms = validation_started_at.elapsed().as_millis() as u64;
 
-	let result_descriptor = match ValidationResult::decode(&mut &descriptor_bytes[..]) {
+	// The result is produced by untrusted code, so keep it from blowing up the allocations.
+	let result_descriptor = match decode_bounded_validation_result(
+		&mut &descriptor_bytes[..],
+		&CommitmentsLimits::default(),
+	) {
 		Err(err) =>
 			return Response::InvalidCandidate(format!("validation result decoding failed: {}", err)),
 		Ok(r) => r,
@@ -98,6 +98,7 @@ pub use pvf::Pvf;
 
 pub use host::{start, Config, ValidationHost};
 pub use metrics::Metrics;
+pub use worker_common::{reported_version, SandboxConfig};
 
 pub use execute::worker_entrypoint as execute_worker_entrypoint;
 pub use prepare::worker_entrypoint as prepare_worker_entrypoint;
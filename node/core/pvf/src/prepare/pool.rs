@@ -18,7 +18,7 @@ use super::worker::{self, Outcome};
 use crate::{
 	error::{PrepareError, PrepareResult},
 	metrics::Metrics,
-	worker_common::{IdleWorker, WorkerHandle},
+	worker_common::{IdleWorker, SandboxConfig, WorkerHandle},
 	LOG_TARGET,
 };
 use always_assert::never;
@@ -107,6 +107,7 @@ struct Pool {
 	program_path: PathBuf,
 	cache_path: PathBuf,
 	spawn_timeout: Duration,
+	sandbox: SandboxConfig,
 	to_pool: mpsc::Receiver<ToPool>,
 	from_pool: mpsc::UnboundedSender<FromPool>,
 	spawned: HopSlotMap<Worker, WorkerData>,
@@ -122,6 +123,7 @@ async fn run(
 		program_path,
 		cache_path,
 		spawn_timeout,
+		sandbox,
 		to_pool,
 		mut from_pool,
 		mut spawned,
@@ -149,6 +151,7 @@ async fn run(
 					&program_path,
 					&cache_path,
 					spawn_timeout,
+					&sandbox,
 					&mut spawned,
 					&mut mux,
 					to_pool,
@@ -195,6 +198,7 @@ fn handle_to_pool(
 	program_path: &Path,
 	cache_path: &Path,
 	spawn_timeout: Duration,
+	sandbox: &SandboxConfig,
 	spawned: &mut HopSlotMap<Worker, WorkerData>,
 	mux: &mut Mux,
 	to_pool: ToPool,
@@ -203,7 +207,9 @@ fn handle_to_pool(
 		ToPool::Spawn => {
 			tracing::debug!(target: LOG_TARGET, "spawning a new prepare worker");
 			metrics.prepare_worker().on_begin_spawn();
-			mux.push(spawn_worker_task(program_path.to_owned(), spawn_timeout).boxed());
+			mux.push(
+				spawn_worker_task(program_path.to_owned(), spawn_timeout, sandbox.clone()).boxed(),
+			);
 		},
 		ToPool::StartWork { worker, code, artifact_path } => {
 			if let Some(data) = spawned.get_mut(worker) {
@@ -241,11 +247,15 @@ fn handle_to_pool(
 	}
 }
 
-async fn spawn_worker_task(program_path: PathBuf, spawn_timeout: Duration) -> PoolEvent {
+async fn spawn_worker_task(
+	program_path: PathBuf,
+	spawn_timeout: Duration,
+	sandbox: SandboxConfig,
+) -> PoolEvent {
 	use futures_timer::Delay;
 
 	loop {
-		match worker::spawn(&program_path, spawn_timeout).await {
+		match worker::spawn(&program_path, spawn_timeout, &sandbox).await {
 			Ok((idle, handle)) => break PoolEvent::Spawn(idle, handle),
 			Err(err) => {
 				tracing::warn!(target: LOG_TARGET, "failed to spawn a prepare worker: {:?}", err);
@@ -375,6 +385,7 @@ pub fn start(
 	program_path: PathBuf,
 	cache_path: PathBuf,
 	spawn_timeout: Duration,
+	sandbox: SandboxConfig,
 ) -> (mpsc::Sender<ToPool>, mpsc::UnboundedReceiver<FromPool>, impl Future<Output = ()>) {
 	let (to_pool_tx, to_pool_rx) = mpsc::channel(10);
 	let (from_pool_tx, from_pool_rx) = mpsc::unbounded();
@@ -384,6 +395,7 @@ pub fn start(
 		program_path,
 		cache_path,
 		spawn_timeout,
+		sandbox,
 		to_pool: to_pool_rx,
 		from_pool: from_pool_tx,
 		spawned: HopSlotMap::with_capacity_and_key(20),
@@ -19,7 +19,7 @@ use crate::{
 	error::{PrepareError, PrepareResult},
 	worker_common::{
 		bytes_to_path, framed_recv, framed_send, path_to_bytes, spawn_with_program_path,
-		tmpfile_in, worker_event_loop, IdleWorker, SpawnErr, WorkerHandle,
+		tmpfile_in, worker_event_loop, IdleWorker, SandboxConfig, SpawnErr, WorkerHandle,
 	},
 	LOG_TARGET,
 };
@@ -42,8 +42,10 @@ const COMPILATION_TIMEOUT: Duration = Duration::from_secs(60);
 pub async fn spawn(
 	program_path: &Path,
 	spawn_timeout: Duration,
+	sandbox: &SandboxConfig,
 ) -> Result<(IdleWorker, WorkerHandle), SpawnErr> {
-	spawn_with_program_path("prepare", program_path, &["prepare-worker"], spawn_timeout).await
+	spawn_with_program_path("prepare", program_path, &["prepare-worker"], spawn_timeout, sandbox)
+		.await
 }
 
 pub enum Outcome {
@@ -35,6 +35,69 @@ use std::{
 	time::Duration,
 };
 
+/// Hardening options applied to the spawned worker processes.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SandboxConfig {
+	/// The maximum size of the data segment of a worker process, in bytes.
+	///
+	/// This is enforced with `RLIMIT_DATA`, which only accounts for the private writable memory of
+	/// the worker. Unlike `RLIMIT_AS` it doesn't count the large address space reservations
+	/// wasmtime makes for its linear memories and guard pages. A worker exceeding this limit fails
+	/// to allocate and dies, which is reported as an ambiguous worker death. `None` means the limit
+	/// is inherited from the host.
+	pub memory_limit: Option<u64>,
+	/// Whether the environment of the host should be cleared before spawning a worker.
+	pub clear_env: bool,
+	/// The version the worker binary has to report on `--version`. It is checked before every
+	/// spawn, so a binary replaced on disk by an incompatible one is never used. Not checked if
+	/// `None`.
+	pub expected_version: Option<String>,
+}
+
+/// Extract the version from the output of `<binary> --version`.
+///
+/// The output has the form `<name> <version>`, so this is the last word of the first line.
+pub fn reported_version(output: &str) -> Option<&str> {
+	output.lines().next()?.split_whitespace().last()
+}
+
+/// Check that the worker binary reports exactly the expected version.
+async fn check_worker_version(
+	debug_id: &'static str,
+	program_path: &Path,
+	expected: &str,
+) -> Result<(), SpawnErr> {
+	let output = async_process::Command::new(program_path.as_os_str())
+		.arg("--version")
+		.output()
+		.await
+		.map_err(|err| {
+			tracing::warn!(
+				target: LOG_TARGET,
+				%debug_id,
+				"cannot query the version of the worker binary: {:?}",
+				err,
+			);
+			SpawnErr::VersionMismatch
+		})?;
+	let output = String::from_utf8_lossy(&output.stdout);
+
+	match reported_version(&output) {
+		Some(found) if found == expected => Ok(()),
+		found => {
+			tracing::warn!(
+				target: LOG_TARGET,
+				%debug_id,
+				?program_path,
+				?found,
+				%expected,
+				"the worker binary has an unexpected version",
+			);
+			Err(SpawnErr::VersionMismatch)
+		},
+	}
+}
+
 /// This is publicly exposed only for integration tests.
 #[doc(hidden)]
 pub async fn spawn_with_program_path(
@@ -42,8 +105,13 @@ pub async fn spawn_with_program_path(
 	program_path: impl Into<PathBuf>,
 	extra_args: &'static [&'static str],
 	spawn_timeout: Duration,
+	sandbox: &SandboxConfig,
 ) -> Result<(IdleWorker, WorkerHandle), SpawnErr> {
 	let program_path = program_path.into();
+	if let Some(expected) = &sandbox.expected_version {
+		check_worker_version(debug_id, &program_path, expected).await?;
+	}
+	let sandbox = sandbox.clone();
 	with_transient_socket_path(debug_id, |socket_path| {
 		let socket_path = socket_path.to_owned();
 		async move {
@@ -57,8 +125,8 @@ pub async fn spawn_with_program_path(
 				SpawnErr::Bind
 			})?;
 
-			let handle =
-				WorkerHandle::spawn(program_path, extra_args, socket_path).map_err(|err| {
+			let handle = WorkerHandle::spawn(program_path, extra_args, socket_path, &sandbox)
+				.map_err(|err| {
 					tracing::warn!(
 						target: LOG_TARGET,
 						%debug_id,
@@ -195,6 +263,8 @@ pub enum SpawnErr {
 	ProcessSpawn,
 	/// The deadline allotted for the worker spawning and connecting to the socket has elapsed.
 	AcceptTimeout,
+	/// The worker binary doesn't report the expected version.
+	VersionMismatch,
 }
 
 /// This is a representation of a potentially running worker. Drop it and the process will be killed.
@@ -217,13 +287,41 @@ impl WorkerHandle {
 		program: impl AsRef<Path>,
 		extra_args: &[&str],
 		socket_path: impl AsRef<Path>,
+		sandbox: &SandboxConfig,
 	) -> io::Result<Self> {
-		let mut child = async_process::Command::new(program.as_ref())
+		let mut command = async_process::Command::new(program.as_ref());
+		command
 			.args(extra_args)
 			.arg(socket_path.as_ref().as_os_str())
 			.stdout(async_process::Stdio::piped())
-			.kill_on_drop(true)
-			.spawn()?;
+			.kill_on_drop(true);
+
+		if sandbox.clear_env {
+			command.env_clear();
+		}
+
+		if let Some(memory_limit) = sandbox.memory_limit {
+			use async_process::unix::CommandExt as _;
+
+			// `RLIMIT_AS` would also count the address space wasmtime reserves without ever
+			// touching it, so only limit the data segment.
+			//
+			// SAFETY: `setrlimit` is async-signal-safe and the closure doesn't allocate.
+			unsafe {
+				command.pre_exec(move || {
+					let limit = libc::rlimit {
+						rlim_cur: memory_limit as libc::rlim_t,
+						rlim_max: memory_limit as libc::rlim_t,
+					};
+					if libc::setrlimit(libc::RLIMIT_DATA, &limit) != 0 {
+						return Err(std::io::Error::last_os_error())
+					}
+					Ok(())
+				});
+			}
+		}
+
+		let mut child = command.spawn()?;
 
 		let stdout = child
 			.stdout
@@ -20,9 +20,14 @@ use std::time::Duration;
 
 #[async_std::test]
 async fn spawn_timeout() {
-	let result =
-		spawn_with_program_path("integration-test", PUPPET_EXE, &["sleep"], Duration::from_secs(2))
-			.await;
+	let result = spawn_with_program_path(
+		"integration-test",
+		PUPPET_EXE,
+		&["sleep"],
+		Duration::from_secs(2),
+		&Default::default(),
+	)
+	.await;
 	assert!(matches!(result, Err(SpawnErr::AcceptTimeout)));
 }
 
@@ -33,6 +38,7 @@ async fn should_connect() {
 		PUPPET_EXE,
 		&["prepare-worker"],
 		Duration::from_secs(2),
+		&Default::default(),
 	)
 	.await
 	.unwrap();
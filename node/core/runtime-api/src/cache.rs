@@ -28,7 +28,7 @@ use selendra_primitives::{
 		PersistedValidationData, ScrapedOnChainVotes, SessionIndex, ValidationCode,
 		ValidationCodeHash, ValidatorId, ValidatorIndex, ValidatorSignature,
 	},
-	v2::{PvfCheckStatement, SessionInfo},
+	v2::{BackingState, PvfCheckStatement, SessionInfo},
 };
 
 const AUTHORITIES_CACHE_SIZE: usize = 128 * 1024;
@@ -49,6 +49,9 @@ const CURRENT_BABE_EPOCH_CACHE_SIZE: usize = 64 * 1024;
 const ON_CHAIN_VOTES_CACHE_SIZE: usize = 3 * 1024;
 const PVFS_REQUIRE_PRECHECK_SIZE: usize = 1024;
 const VALIDATION_CODE_HASH_CACHE_SIZE: usize = 64 * 1024;
+const DMQ_LENGTH_CACHE_SIZE: usize = 64 * 1024;
+const HRMP_CHANNEL_DIGESTS_CACHE_SIZE: usize = 64 * 1024;
+const PARA_BACKING_STATE_CACHE_SIZE: usize = 64 * 1024;
 
 struct ResidentSizeOf<T>(T);
 
@@ -116,6 +119,10 @@ pub(crate) struct RequestResultCache {
 		(Hash, ParaId, OccupiedCoreAssumption),
 		ResidentSizeOf<Option<ValidationCodeHash>>,
 	>,
+	dmq_length: MemoryLruCache<(Hash, ParaId), ResidentSizeOf<u32>>,
+	hrmp_channel_digests:
+		MemoryLruCache<(Hash, ParaId), ResidentSizeOf<Vec<(BlockNumber, Vec<ParaId>)>>>,
+	para_backing_state: MemoryLruCache<(Hash, ParaId), ResidentSizeOf<Option<BackingState>>>,
 }
 
 impl Default for RequestResultCache {
@@ -142,6 +149,9 @@ impl Default for RequestResultCache {
 			on_chain_votes: MemoryLruCache::new(ON_CHAIN_VOTES_CACHE_SIZE),
 			pvfs_require_precheck: MemoryLruCache::new(PVFS_REQUIRE_PRECHECK_SIZE),
 			validation_code_hash: MemoryLruCache::new(VALIDATION_CODE_HASH_CACHE_SIZE),
+			dmq_length: MemoryLruCache::new(DMQ_LENGTH_CACHE_SIZE),
+			hrmp_channel_digests: MemoryLruCache::new(HRMP_CHANNEL_DIGESTS_CACHE_SIZE),
+			para_backing_state: MemoryLruCache::new(PARA_BACKING_STATE_CACHE_SIZE),
 		}
 	}
 }
@@ -399,6 +409,44 @@ impl RequestResultCache {
 	) {
 		self.validation_code_hash.insert(key, ResidentSizeOf(value));
 	}
+
+	pub(crate) fn dmq_length(&mut self, key: (Hash, ParaId)) -> Option<&u32> {
+		self.dmq_length.get(&key).map(|v| &v.0)
+	}
+
+	pub(crate) fn cache_dmq_length(&mut self, key: (Hash, ParaId), value: u32) {
+		self.dmq_length.insert(key, ResidentSizeOf(value));
+	}
+
+	pub(crate) fn hrmp_channel_digests(
+		&mut self,
+		key: (Hash, ParaId),
+	) -> Option<&Vec<(BlockNumber, Vec<ParaId>)>> {
+		self.hrmp_channel_digests.get(&key).map(|v| &v.0)
+	}
+
+	pub(crate) fn cache_hrmp_channel_digests(
+		&mut self,
+		key: (Hash, ParaId),
+		value: Vec<(BlockNumber, Vec<ParaId>)>,
+	) {
+		self.hrmp_channel_digests.insert(key, ResidentSizeOf(value));
+	}
+
+	pub(crate) fn para_backing_state(
+		&mut self,
+		key: (Hash, ParaId),
+	) -> Option<&Option<BackingState>> {
+		self.para_backing_state.get(&key).map(|v| &v.0)
+	}
+
+	pub(crate) fn cache_para_backing_state(
+		&mut self,
+		key: (Hash, ParaId),
+		value: Option<BackingState>,
+	) {
+		self.para_backing_state.insert(key, ResidentSizeOf(value));
+	}
 }
 
 pub(crate) enum RequestResult {
@@ -433,4 +481,7 @@ pub(crate) enum RequestResult {
 	// This is a request with side-effects and no result, hence ().
 	SubmitPvfCheckStatement(Hash, PvfCheckStatement, ValidatorSignature, ()),
 	ValidationCodeHash(Hash, ParaId, OccupiedCoreAssumption, Option<ValidationCodeHash>),
+	DmqLength(Hash, ParaId, u32),
+	HrmpChannelDigests(Hash, ParaId, Vec<(BlockNumber, Vec<ParaId>)>),
+	StagingParaBackingState(Hash, ParaId, Option<BackingState>),
 }
@@ -165,6 +165,12 @@ where
 			ValidationCodeHash(relay_parent, para_id, assumption, hash) => self
 				.requests_cache
 				.cache_validation_code_hash((relay_parent, para_id, assumption), hash),
+			DmqLength(relay_parent, para_id, length) =>
+				self.requests_cache.cache_dmq_length((relay_parent, para_id), length),
+			HrmpChannelDigests(relay_parent, para_id, digests) =>
+				self.requests_cache.cache_hrmp_channel_digests((relay_parent, para_id), digests),
+			StagingParaBackingState(relay_parent, para_id, state) =>
+				self.requests_cache.cache_para_backing_state((relay_parent, para_id), state),
 		}
 	}
 
@@ -264,6 +270,12 @@ where
 			Request::ValidationCodeHash(para, assumption, sender) =>
 				query!(validation_code_hash(para, assumption), sender)
 					.map(|sender| Request::ValidationCodeHash(para, assumption, sender)),
+			Request::DmqLength(id, sender) =>
+				query!(dmq_length(id), sender).map(|sender| Request::DmqLength(id, sender)),
+			Request::HrmpChannelDigests(id, sender) => query!(hrmp_channel_digests(id), sender)
+				.map(|sender| Request::HrmpChannelDigests(id, sender)),
+			Request::StagingParaBackingState(id, sender) => query!(para_backing_state(id), sender)
+				.map(|sender| Request::StagingParaBackingState(id, sender)),
 		}
 	}
 
@@ -503,6 +515,11 @@ where
 		},
 		Request::ValidationCodeHash(para, assumption, sender) =>
 			query!(ValidationCodeHash, validation_code_hash(para, assumption), ver = 2, sender),
+		Request::DmqLength(id, sender) => query!(DmqLength, dmq_length(id), ver = 3, sender),
+		Request::HrmpChannelDigests(id, sender) =>
+			query!(HrmpChannelDigests, hrmp_channel_digests(id), ver = 3, sender),
+		Request::StagingParaBackingState(id, sender) =>
+			query!(StagingParaBackingState, staging_para_backing_state(id), ver = 4, sender),
 	}
 }
 
@@ -22,12 +22,12 @@ use selendra_node_primitives::{BabeAllowedSlots, BabeEpoch, BabeEpochConfigurati
 use selendra_node_subsystem_test_helpers::make_subsystem_context;
 use selendra_primitives::{
 	v1::{
-		AuthorityDiscoveryId, CandidateEvent, CommittedCandidateReceipt, CoreState,
+		AuthorityDiscoveryId, BlockNumber, CandidateEvent, CommittedCandidateReceipt, CoreState,
 		GroupRotationInfo, Id as ParaId, InboundDownwardMessage, InboundHrmpMessage,
 		OccupiedCoreAssumption, PersistedValidationData, ScrapedOnChainVotes, SessionIndex,
 		ValidationCode, ValidationCodeHash, ValidatorId, ValidatorIndex, ValidatorSignature,
 	},
-	v2::{PvfCheckStatement, SessionInfo},
+	v2::{BackingState, PvfCheckStatement, SessionInfo},
 };
 use sp_core::testing::TaskExecutor;
 use std::{
@@ -57,6 +57,8 @@ struct MockRuntimeApi {
 	submitted_pvf_check_statement: Arc<Mutex<Vec<(PvfCheckStatement, ValidatorSignature)>>>,
 	pvfs_require_precheck: Vec<ValidationCodeHash>,
 	validation_code_hash: HashMap<ParaId, ValidationCodeHash>,
+	hrmp_channel_digests: HashMap<ParaId, Vec<(BlockNumber, Vec<ParaId>)>>,
+	para_backing_state: HashMap<ParaId, BackingState>,
 }
 
 impl ProvideRuntimeApi<Block> for MockRuntimeApi {
@@ -192,6 +194,18 @@ sp_api::mock_impl_runtime_apis! {
 		) -> Option<ValidationCodeHash> {
 			self.validation_code_hash.get(&para).map(|c| c.clone())
 		}
+
+		fn dmq_length(&self, recipient: ParaId) -> u32 {
+			self.dmq_contents.get(&recipient).map_or(0, |q| q.len() as u32)
+		}
+
+		fn hrmp_channel_digests(&self, recipient: ParaId) -> Vec<(BlockNumber, Vec<ParaId>)> {
+			self.hrmp_channel_digests.get(&recipient).map(|d| d.clone()).unwrap_or_default()
+		}
+
+		fn staging_para_backing_state(&self, para_id: ParaId) -> Option<BackingState> {
+			self.para_backing_state.get(&para_id).cloned()
+		}
 	}
 
 	impl BabeApi<Block> for MockRuntimeApi {
@@ -730,6 +744,179 @@ fn requests_dmq_contents() {
 	futures::executor::block_on(future::join(subsystem_task, test_task));
 }
 
+#[test]
+fn requests_dmq_length() {
+	let (ctx, mut ctx_handle) = make_subsystem_context(TaskExecutor::new());
+
+	let relay_parent = [1; 32].into();
+	let para_a = 5.into();
+	let para_b = 6.into();
+	let spawner = sp_core::testing::TaskExecutor::new();
+
+	let runtime_api = Arc::new({
+		let mut runtime_api = MockRuntimeApi::default();
+
+		runtime_api.dmq_contents.insert(
+			para_b,
+			vec![
+				InboundDownwardMessage { sent_at: 1, msg: vec![1] },
+				InboundDownwardMessage { sent_at: 2, msg: vec![2] },
+			],
+		);
+
+		runtime_api
+	});
+
+	let subsystem = RuntimeApiSubsystem::new(runtime_api.clone(), Metrics(None), spawner);
+	let subsystem_task = run(ctx, subsystem).map(|x| x.unwrap());
+	let test_task = async move {
+		let (tx, rx) = oneshot::channel();
+		ctx_handle
+			.send(FromOverseer::Communication {
+				msg: RuntimeApiMessage::Request(relay_parent, Request::DmqLength(para_a, tx)),
+			})
+			.await;
+		assert_eq!(rx.await.unwrap().unwrap(), 0);
+
+		let (tx, rx) = oneshot::channel();
+		ctx_handle
+			.send(FromOverseer::Communication {
+				msg: RuntimeApiMessage::Request(relay_parent, Request::DmqLength(para_b, tx)),
+			})
+			.await;
+		assert_eq!(rx.await.unwrap().unwrap(), 2);
+
+		ctx_handle.send(FromOverseer::Signal(OverseerSignal::Conclude)).await;
+	};
+	futures::executor::block_on(future::join(subsystem_task, test_task));
+}
+
+#[test]
+fn requests_hrmp_channel_digests() {
+	let (ctx, mut ctx_handle) = make_subsystem_context(TaskExecutor::new());
+
+	let relay_parent = [1; 32].into();
+	let para_a = 99.into();
+	let para_b = 66.into();
+	let para_c = 33.into();
+	let spawner = sp_core::testing::TaskExecutor::new();
+
+	let para_b_digests = vec![(1, vec![para_a]), (3, vec![para_a, para_c])];
+
+	let runtime_api = Arc::new({
+		let mut runtime_api = MockRuntimeApi::default();
+
+		runtime_api.hrmp_channel_digests.insert(para_b, para_b_digests.clone());
+
+		runtime_api
+	});
+
+	let subsystem = RuntimeApiSubsystem::new(runtime_api.clone(), Metrics(None), spawner);
+	let subsystem_task = run(ctx, subsystem).map(|x| x.unwrap());
+	let test_task = async move {
+		let (tx, rx) = oneshot::channel();
+		ctx_handle
+			.send(FromOverseer::Communication {
+				msg: RuntimeApiMessage::Request(
+					relay_parent,
+					Request::HrmpChannelDigests(para_a, tx),
+				),
+			})
+			.await;
+		assert_eq!(rx.await.unwrap().unwrap(), vec![]);
+
+		let (tx, rx) = oneshot::channel();
+		ctx_handle
+			.send(FromOverseer::Communication {
+				msg: RuntimeApiMessage::Request(
+					relay_parent,
+					Request::HrmpChannelDigests(para_b, tx),
+				),
+			})
+			.await;
+		assert_eq!(rx.await.unwrap().unwrap(), para_b_digests);
+
+		ctx_handle.send(FromOverseer::Signal(OverseerSignal::Conclude)).await;
+	};
+	futures::executor::block_on(future::join(subsystem_task, test_task));
+}
+
+#[test]
+fn requests_staging_para_backing_state() {
+	let (ctx, mut ctx_handle) = make_subsystem_context(TaskExecutor::new());
+
+	let relay_parent = [1; 32].into();
+	let para_a = 99.into();
+	let para_b = 66.into();
+	let spawner = sp_core::testing::TaskExecutor::new();
+
+	let para_b_state = BackingState {
+		constraints: primitives::v2::Constraints {
+			min_relay_parent_number: 5,
+			max_pov_size: 1024,
+			max_head_data_size: 1024,
+			max_code_size: 1024,
+			ump_remaining: 10,
+			ump_remaining_bytes: 1024,
+			max_ump_num_per_candidate: 5,
+			max_upward_message_size: 1024,
+			dmp_remaining_messages: vec![3, 4],
+			hrmp_inbound: primitives::v2::InboundHrmpLimitations { valid_watermarks: vec![4] },
+			hrmp_channels_out: vec![(
+				para_a,
+				primitives::v2::OutboundHrmpChannelLimitations {
+					bytes_remaining: 100,
+					messages_remaining: 2,
+					max_message_size: 100,
+				},
+			)],
+			max_hrmp_num_per_candidate: 5,
+			required_parent: vec![1, 2, 3].into(),
+			validation_code_hash: ValidationCode(vec![1, 2, 3]).hash(),
+			upgrade_restriction: None,
+			future_validation_code: None,
+		},
+		pending_availability: Vec::new(),
+	};
+
+	let runtime_api = Arc::new({
+		let mut runtime_api = MockRuntimeApi::default();
+
+		runtime_api.para_backing_state.insert(para_b, para_b_state.clone());
+
+		runtime_api
+	});
+
+	let subsystem = RuntimeApiSubsystem::new(runtime_api.clone(), Metrics(None), spawner);
+	let subsystem_task = run(ctx, subsystem).map(|x| x.unwrap());
+	let test_task = async move {
+		let (tx, rx) = oneshot::channel();
+		ctx_handle
+			.send(FromOverseer::Communication {
+				msg: RuntimeApiMessage::Request(
+					relay_parent,
+					Request::StagingParaBackingState(para_a, tx),
+				),
+			})
+			.await;
+		assert_eq!(rx.await.unwrap().unwrap(), None);
+
+		let (tx, rx) = oneshot::channel();
+		ctx_handle
+			.send(FromOverseer::Communication {
+				msg: RuntimeApiMessage::Request(
+					relay_parent,
+					Request::StagingParaBackingState(para_b, tx),
+				),
+			})
+			.await;
+		assert_eq!(rx.await.unwrap().unwrap(), Some(para_b_state));
+
+		ctx_handle.send(FromOverseer::Signal(OverseerSignal::Conclude)).await;
+	};
+	futures::executor::block_on(future::join(subsystem_task, test_task));
+}
+
 #[test]
 fn requests_inbound_hrmp_channels_contents() {
 	let (ctx, mut ctx_handle) = make_subsystem_context(TaskExecutor::new());
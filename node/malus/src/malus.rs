@@ -25,6 +25,9 @@ pub(crate) mod shared;
 
 mod variants;
 
+#[cfg(feature = "replay")]
+mod replay;
+
 use variants::*;
 
 /// Define the different variants of behavior.
@@ -38,6 +41,12 @@ enum NemesisVariant {
 	BackGarbageCandidate(RunCmd),
 	/// Delayed disputing of ancestors that are perfectly fine.
 	DisputeAncestor(RunCmd),
+	/// Record the messages of the candidate validation subsystem.
+	#[cfg(feature = "replay")]
+	RecordCandidateValidation(replay::candidate_validation::RecordCmd),
+	/// Replay recorded messages against a fresh candidate validation subsystem.
+	#[cfg(feature = "replay")]
+	ReplayCandidateValidation(replay::candidate_validation::ReplayCmd),
 
 	#[allow(missing_docs)]
 	#[clap(name = "prepare-worker", hide = true)]
@@ -69,6 +78,13 @@ impl MalusCli {
 				selendra_cli::run_node(run_cmd(cmd), SuggestGarbageCandidate)?,
 			NemesisVariant::DisputeAncestor(cmd) =>
 				selendra_cli::run_node(run_cmd(cmd), DisputeValidCandidates)?,
+			#[cfg(feature = "replay")]
+			NemesisVariant::RecordCandidateValidation(cmd) => selendra_cli::run_node(
+				run_cmd(cmd.run),
+				replay::candidate_validation::RecordCandidateValidation::new(&cmd.record_to)?,
+			)?,
+			#[cfg(feature = "replay")]
+			NemesisVariant::ReplayCandidateValidation(cmd) => replay::candidate_validation::replay(cmd)?,
 			NemesisVariant::PvfPrepareWorker(cmd) => {
 				#[cfg(target_os = "android")]
 				{
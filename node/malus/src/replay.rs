@@ -0,0 +1,179 @@
+// Copyright 2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Recording the messages of a subsystem and replaying them against a fresh instance.
+//!
+//! A node launched with one of the `record-*` variants wraps the chosen subsystem with an
+//! interceptor, which appends everything the subsystem receives from the overseer to a log, along
+//! with the answers it got from its counterparts and the answers it gave. The `replay-*` variants
+//! feed such a log to a new instance of the subsystem, answering its requests to the counterparts
+//! from the log, and report every answer which differs from the recorded one.
+//!
+//! The log is a sequence of SCALE encoded records, each of them prefixed with its length.
+
+use std::{
+	fs::{self, File, OpenOptions},
+	io::{self, BufWriter, Write},
+	path::Path,
+	sync::{Arc, Mutex},
+};
+
+use parity_scale_codec::{Decode, Encode};
+use selendra_node_subsystem::{
+	jaeger, ActivatedLeaf, ActiveLeavesUpdate, LeafStatus, OverseerSignal,
+};
+use selendra_primitives::v1::{BlockNumber, Hash};
+
+use crate::shared::MALUS;
+
+pub(crate) mod candidate_validation;
+
+/// An overseer signal, as far as it can be recorded.
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub(crate) enum RecordedSignal {
+	/// The set of active leaves changed.
+	ActiveLeaves { activated: Option<(Hash, BlockNumber)>, deactivated: Vec<Hash> },
+	/// A block got finalized.
+	BlockFinalized(Hash, BlockNumber),
+}
+
+impl RecordedSignal {
+	/// Record the given signal. `Conclude` isn't recorded, as the replay concludes on its own.
+	pub(crate) fn new(signal: &OverseerSignal) -> Option<Self> {
+		match signal {
+			OverseerSignal::ActiveLeaves(update) => Some(Self::ActiveLeaves {
+				activated: update.activated.as_ref().map(|leaf| (leaf.hash, leaf.number)),
+				deactivated: update.deactivated.to_vec(),
+			}),
+			OverseerSignal::BlockFinalized(hash, number) =>
+				Some(Self::BlockFinalized(*hash, *number)),
+			OverseerSignal::Conclude => None,
+		}
+	}
+
+	/// Turn the record back into a signal.
+	pub(crate) fn into_signal(self) -> OverseerSignal {
+		match self {
+			Self::ActiveLeaves { activated, deactivated } =>
+				OverseerSignal::ActiveLeaves(ActiveLeavesUpdate {
+					activated: activated.map(|(hash, number)| ActivatedLeaf {
+						hash,
+						number,
+						status: LeafStatus::Fresh,
+						span: Arc::new(jaeger::Span::Disabled),
+					}),
+					deactivated: deactivated.into(),
+				}),
+			Self::BlockFinalized(hash, number) => OverseerSignal::BlockFinalized(hash, number),
+		}
+	}
+}
+
+/// Appends records to a log file.
+#[derive(Clone)]
+pub(crate) struct RecordWriter {
+	file: Arc<Mutex<BufWriter<File>>>,
+}
+
+impl RecordWriter {
+	/// Create the log at the given path, truncating an existing one.
+	pub(crate) fn create(path: &Path) -> io::Result<Self> {
+		if let Some(dir) = path.parent() {
+			fs::create_dir_all(dir)?;
+		}
+		let file = OpenOptions::new().create(true).write(true).truncate(true).open(path)?;
+		Ok(RecordWriter { file: Arc::new(Mutex::new(BufWriter::new(file))) })
+	}
+
+	/// Append a record. The log is flushed right away, so that the records made before a crash
+	/// are kept. Failures are only logged.
+	pub(crate) fn append(&self, record: &impl Encode) {
+		let mut file = match self.file.lock() {
+			Ok(file) => file,
+			Err(poisoned) => poisoned.into_inner(),
+		};
+
+		let res = file.write_all(&record.encode().encode()).and_then(|()| file.flush());
+		if let Err(err) = res {
+			tracing::warn!(target: MALUS, ?err, "Failed to append to the record log");
+		}
+	}
+}
+
+/// Read all records from a log.
+///
+/// A truncated last record, as left behind by a node which was killed while writing it, is
+/// skipped.
+pub(crate) fn read_records<R: Decode>(path: &Path) -> io::Result<Vec<R>> {
+	let data = fs::read(path)?;
+	let mut input = &data[..];
+	let mut records = Vec::new();
+	while !input.is_empty() {
+		let encoded = match Vec::<u8>::decode(&mut input) {
+			Ok(encoded) => encoded,
+			Err(_) => {
+				tracing::warn!(target: MALUS, ?path, "Skipping a truncated record at the end");
+				break
+			},
+		};
+		let record = R::decode(&mut &encoded[..])
+			.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+		records.push(record);
+	}
+	Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn records_are_read_back() {
+		let dir = std::env::temp_dir().join(format!("malus-replay-{}", std::process::id()));
+		let path = dir.join("records.log");
+		let records = vec![
+			RecordedSignal::ActiveLeaves {
+				activated: Some((Hash::repeat_byte(1), 1)),
+				deactivated: vec![Hash::repeat_byte(2)],
+			},
+			RecordedSignal::BlockFinalized(Hash::repeat_byte(1), 1),
+		];
+
+		let writer = RecordWriter::create(&path).unwrap();
+		for record in &records {
+			writer.append(record);
+		}
+		drop(writer);
+
+		// A record cut short is dropped.
+		let mut data = fs::read(&path).unwrap();
+		data.extend(&records[0].encode().encode()[..3]);
+		fs::write(&path, data).unwrap();
+
+		assert_eq!(read_records::<RecordedSignal>(&path).unwrap(), records);
+		fs::remove_dir_all(dir).unwrap();
+	}
+
+	#[test]
+	fn signals_survive_recording() {
+		let signal = RecordedSignal::ActiveLeaves {
+			activated: Some((Hash::repeat_byte(1), 7)),
+			deactivated: vec![Hash::repeat_byte(2)],
+		};
+		assert_eq!(RecordedSignal::new(&signal.clone().into_signal()), Some(signal));
+		assert_eq!(RecordedSignal::new(&OverseerSignal::Conclude), None);
+	}
+}
@@ -0,0 +1,708 @@
+// Copyright 2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Recording and replaying the candidate validation subsystem.
+
+use std::{
+	collections::HashMap,
+	path::PathBuf,
+	sync::{
+		atomic::{AtomicU64, Ordering},
+		Arc,
+	},
+	time::Duration,
+};
+
+use clap::Parser;
+use color_eyre::eyre;
+use futures::{channel::oneshot, future, prelude::*, stream::FuturesUnordered};
+use parity_scale_codec::{Decode, Encode};
+
+use selendra_cli::{
+	prepared_overseer_builder,
+	service::{
+		AuthorityDiscoveryApi, AuxStore, BabeApi, Block, Error, HeaderBackend, Overseer,
+		OverseerConnector, OverseerGen, OverseerGenArgs, OverseerHandle, ParachainHost,
+		ProvideRuntimeApi, SpawnNamed,
+	},
+	RunCmd,
+};
+use selendra_node_core_candidate_validation::{
+	CandidateValidationSubsystem, Config as CandidateValidationConfig, IsolationStrategy,
+};
+use selendra_node_primitives::{PoV, ValidationResult};
+use selendra_node_subsystem::{
+	errors::RuntimeApiError,
+	messages::{
+		CandidateValidationMessage, PreCheckOutcome, RuntimeApiMessage, RuntimeApiRequest,
+		ValidationFailed,
+	},
+	overseer::Subsystem as _,
+	OverseerSignal,
+};
+use selendra_node_subsystem_test_helpers::{make_subsystem_context, TestSubsystemContextHandle};
+use selendra_primitives::v1::{
+	CandidateCommitments, CandidateDescriptor, Hash, Id as ParaId, OccupiedCoreAssumption,
+	PersistedValidationData, ValidationCode, ValidationCodeHash,
+};
+
+use super::{read_records, RecordWriter, RecordedSignal};
+use crate::{interceptor::*, shared::*};
+
+/// Run a node recording the messages of the candidate validation subsystem.
+#[derive(Debug, Parser)]
+#[clap(rename_all = "kebab-case")]
+pub(crate) struct RecordCmd {
+	/// The file the messages are recorded to. An existing file is overwritten.
+	#[clap(long)]
+	pub record_to: PathBuf,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub run: RunCmd,
+}
+
+/// Replay recorded messages against a fresh candidate validation subsystem.
+#[derive(Debug, Parser)]
+#[clap(rename_all = "kebab-case")]
+pub(crate) struct ReplayCmd {
+	/// The file the messages were recorded to.
+	#[clap(long)]
+	pub log: PathBuf,
+
+	/// The directory the PVFs compiled during the replay are cached in.
+	#[clap(long, default_value = "replay-artifacts")]
+	pub artifacts_cache_path: PathBuf,
+}
+
+/// A request sent to the subsystem.
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+enum RecordedRequest {
+	ValidateFromChainState {
+		descriptor: CandidateDescriptor,
+		pov: PoV,
+		timeout_millis: u64,
+	},
+	ValidateFromExhaustive {
+		persisted_validation_data: PersistedValidationData,
+		validation_code: ValidationCode,
+		descriptor: CandidateDescriptor,
+		pov: PoV,
+		timeout_millis: u64,
+	},
+	ValidateFromCodeHash {
+		persisted_validation_data: PersistedValidationData,
+		validation_code_hash: ValidationCodeHash,
+		descriptor: CandidateDescriptor,
+		pov: PoV,
+		timeout_millis: u64,
+	},
+	PreCheck {
+		relay_parent: Hash,
+		validation_code_hash: ValidationCodeHash,
+	},
+}
+
+/// The answer of the subsystem to a request.
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+enum Verdict {
+	/// The candidate is valid, with commitments of the given hash.
+	Valid { commitments_hash: Hash },
+	/// The candidate is invalid for the given reason.
+	Invalid(String),
+	/// The validation failed for the given reason.
+	Failed(String),
+	/// The outcome of a pre-check.
+	PreCheck(String),
+}
+
+impl From<&Result<ValidationResult, ValidationFailed>> for Verdict {
+	fn from(result: &Result<ValidationResult, ValidationFailed>) -> Self {
+		match result {
+			Ok(ValidationResult::Valid(commitments, _)) =>
+				Verdict::Valid { commitments_hash: commitments.hash() },
+			Ok(ValidationResult::Invalid(reason)) => Verdict::Invalid(format!("{:?}", reason)),
+			Err(ValidationFailed(reason)) => Verdict::Failed(reason.clone()),
+		}
+	}
+}
+
+impl From<&PreCheckOutcome> for Verdict {
+	fn from(outcome: &PreCheckOutcome) -> Self {
+		Verdict::PreCheck(format!("{:?}", outcome))
+	}
+}
+
+/// A runtime API request the subsystem sends.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Encode, Decode)]
+enum RecordedRuntimeApiRequest {
+	ValidationCodeByHash(ValidationCodeHash),
+	PersistedValidationData(ParaId, OccupiedCoreAssumption),
+	ValidationCode(ParaId, OccupiedCoreAssumption),
+	CheckValidationOutputs(ParaId, CandidateCommitments),
+}
+
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+enum Record {
+	/// A signal received by the subsystem.
+	Signal(RecordedSignal),
+	/// A request received by the subsystem, identified by `id`.
+	Request { id: u64, request: RecordedRequest },
+	/// The answer of the subsystem to the request `id`.
+	Verdict { id: u64, verdict: Verdict },
+	/// The answer of the runtime API to a request of the subsystem. The response is the encoded
+	/// value, or `None` if the runtime API returned an error.
+	RuntimeApi { relay_parent: Hash, request: RecordedRuntimeApiRequest, response: Option<Vec<u8>> },
+}
+
+fn millis(timeout: Duration) -> u64 {
+	timeout.as_millis() as u64
+}
+
+/// Records the messages of the candidate validation subsystem.
+#[derive(Clone)]
+struct Recorder<Spawner> {
+	writer: RecordWriter,
+	spawner: Spawner,
+	next_id: Arc<AtomicU64>,
+}
+
+impl<Spawner> Recorder<Spawner>
+where
+	Spawner: SpawnNamed + Clone + Send + 'static,
+{
+	/// Wrap the response sender of a request, so that the answer is recorded before it's passed
+	/// on.
+	fn record_answer<T>(&self, id: u64, tx: oneshot::Sender<T>) -> oneshot::Sender<T>
+	where
+		T: Send + 'static,
+		Verdict: for<'a> From<&'a T>,
+	{
+		let (forward_tx, forward_rx) = oneshot::channel();
+		let writer = self.writer.clone();
+		self.spawner.spawn(
+			"malus-record-verdict",
+			Some("malus"),
+			Box::pin(async move {
+				if let Ok(answer) = forward_rx.await {
+					writer.append(&Record::Verdict { id, verdict: Verdict::from(&answer) });
+					let _ = tx.send(answer);
+				}
+			}),
+		);
+		forward_tx
+	}
+
+	/// Wrap the response sender of a runtime API request, so that the response is recorded before
+	/// it's passed on.
+	fn record_runtime_api<T>(
+		&self,
+		relay_parent: Hash,
+		request: RecordedRuntimeApiRequest,
+		tx: oneshot::Sender<Result<T, RuntimeApiError>>,
+	) -> oneshot::Sender<Result<T, RuntimeApiError>>
+	where
+		T: Encode + Send + 'static,
+	{
+		let (forward_tx, forward_rx) = oneshot::channel();
+		let writer = self.writer.clone();
+		self.spawner.spawn(
+			"malus-record-runtime-api",
+			Some("malus"),
+			Box::pin(async move {
+				if let Ok(response) = forward_rx.await {
+					writer.append(&Record::RuntimeApi {
+						relay_parent,
+						request,
+						response: response.as_ref().ok().map(Encode::encode),
+					});
+					let _ = tx.send(response);
+				}
+			}),
+		);
+		forward_tx
+	}
+
+	fn record_request(&self, request: RecordedRequest) -> u64 {
+		let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+		self.writer.append(&Record::Request { id, request });
+		id
+	}
+}
+
+impl<Sender, Spawner> MessageInterceptor<Sender> for Recorder<Spawner>
+where
+	Sender: overseer::SubsystemSender<CandidateValidationMessage>
+		+ overseer::SubsystemSender<AllMessages>
+		+ Clone
+		+ Send
+		+ 'static,
+	Spawner: SpawnNamed + Clone + Send + Sync + 'static,
+{
+	type Message = CandidateValidationMessage;
+
+	fn intercept_incoming(
+		&self,
+		_sender: &mut Sender,
+		msg: FromOverseer<Self::Message>,
+	) -> Option<FromOverseer<Self::Message>> {
+		let msg = match msg {
+			FromOverseer::Signal(signal) => {
+				if let Some(signal) = RecordedSignal::new(&signal) {
+					self.writer.append(&Record::Signal(signal));
+				}
+				return Some(FromOverseer::Signal(signal))
+			},
+			FromOverseer::Communication { msg } => msg,
+		};
+
+		let msg = match msg {
+			CandidateValidationMessage::ValidateFromChainState(descriptor, pov, timeout, tx) => {
+				let id = self.record_request(RecordedRequest::ValidateFromChainState {
+					descriptor: descriptor.clone(),
+					pov: (*pov).clone(),
+					timeout_millis: millis(timeout),
+				});
+				let tx = self.record_answer(id, tx);
+				CandidateValidationMessage::ValidateFromChainState(descriptor, pov, timeout, tx)
+			},
+			CandidateValidationMessage::ValidateFromExhaustive(
+				persisted_validation_data,
+				validation_code,
+				descriptor,
+				pov,
+				timeout,
+				tx,
+			) => {
+				let id = self.record_request(RecordedRequest::ValidateFromExhaustive {
+					persisted_validation_data: persisted_validation_data.clone(),
+					validation_code: validation_code.clone(),
+					descriptor: descriptor.clone(),
+					pov: (*pov).clone(),
+					timeout_millis: millis(timeout),
+				});
+				let tx = self.record_answer(id, tx);
+				CandidateValidationMessage::ValidateFromExhaustive(
+					persisted_validation_data,
+					validation_code,
+					descriptor,
+					pov,
+					timeout,
+					tx,
+				)
+			},
+			CandidateValidationMessage::ValidateFromCodeHash(
+				persisted_validation_data,
+				validation_code_hash,
+				descriptor,
+				pov,
+				timeout,
+				tx,
+			) => {
+				let id = self.record_request(RecordedRequest::ValidateFromCodeHash {
+					persisted_validation_data: persisted_validation_data.clone(),
+					validation_code_hash,
+					descriptor: descriptor.clone(),
+					pov: (*pov).clone(),
+					timeout_millis: millis(timeout),
+				});
+				let tx = self.record_answer(id, tx);
+				CandidateValidationMessage::ValidateFromCodeHash(
+					persisted_validation_data,
+					validation_code_hash,
+					descriptor,
+					pov,
+					timeout,
+					tx,
+				)
+			},
+			CandidateValidationMessage::PreCheck(relay_parent, validation_code_hash, tx) => {
+				let id = self.record_request(RecordedRequest::PreCheck {
+					relay_parent,
+					validation_code_hash,
+				});
+				let tx = self.record_answer(id, tx);
+				CandidateValidationMessage::PreCheck(relay_parent, validation_code_hash, tx)
+			},
+		};
+
+		Some(FromOverseer::Communication { msg })
+	}
+
+	fn intercept_outgoing(&self, msg: AllMessages) -> Option<AllMessages> {
+		let (relay_parent, request) = match msg {
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(relay_parent, request)) =>
+				(relay_parent, request),
+			msg => return Some(msg),
+		};
+
+		let request = match request {
+			RuntimeApiRequest::ValidationCodeByHash(hash, tx) => {
+				let recorded = RecordedRuntimeApiRequest::ValidationCodeByHash(hash);
+				RuntimeApiRequest::ValidationCodeByHash(
+					hash,
+					self.record_runtime_api(relay_parent, recorded, tx),
+				)
+			},
+			RuntimeApiRequest::PersistedValidationData(para_id, assumption, tx) => {
+				let recorded =
+					RecordedRuntimeApiRequest::PersistedValidationData(para_id, assumption);
+				RuntimeApiRequest::PersistedValidationData(
+					para_id,
+					assumption,
+					self.record_runtime_api(relay_parent, recorded, tx),
+				)
+			},
+			RuntimeApiRequest::ValidationCode(para_id, assumption, tx) => {
+				let recorded = RecordedRuntimeApiRequest::ValidationCode(para_id, assumption);
+				RuntimeApiRequest::ValidationCode(
+					para_id,
+					assumption,
+					self.record_runtime_api(relay_parent, recorded, tx),
+				)
+			},
+			RuntimeApiRequest::CheckValidationOutputs(para_id, outputs, tx) => {
+				let recorded =
+					RecordedRuntimeApiRequest::CheckValidationOutputs(para_id, outputs.clone());
+				RuntimeApiRequest::CheckValidationOutputs(
+					para_id,
+					outputs,
+					self.record_runtime_api(relay_parent, recorded, tx),
+				)
+			},
+			request => request,
+		};
+
+		Some(AllMessages::RuntimeApi(RuntimeApiMessage::Request(relay_parent, request)))
+	}
+}
+
+/// Generates an overseer which records the messages of the candidate validation subsystem.
+pub(crate) struct RecordCandidateValidation {
+	writer: RecordWriter,
+}
+
+impl RecordCandidateValidation {
+	pub(crate) fn new(path: &std::path::Path) -> eyre::Result<Self> {
+		Ok(RecordCandidateValidation { writer: RecordWriter::create(path)? })
+	}
+}
+
+impl OverseerGen for RecordCandidateValidation {
+	fn generate<'a, Spawner, RuntimeClient>(
+		&self,
+		connector: OverseerConnector,
+		args: OverseerGenArgs<'a, Spawner, RuntimeClient>,
+	) -> Result<(Overseer<Spawner, Arc<RuntimeClient>>, OverseerHandle), Error>
+	where
+		RuntimeClient: 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block> + AuxStore,
+		RuntimeClient::Api: ParachainHost<Block> + BabeApi<Block> + AuthorityDiscoveryApi<Block>,
+		Spawner: 'static + SpawnNamed + Clone + Unpin,
+	{
+		let candidate_validation_config = args.candidate_validation_config.clone();
+		let recorder = Recorder {
+			writer: self.writer.clone(),
+			spawner: args.spawner.clone(),
+			next_id: Arc::new(AtomicU64::new(0)),
+		};
+
+		prepared_overseer_builder(args)?
+			.replace_candidate_validation(|cv| {
+				InterceptedSubsystem::new(
+					CandidateValidationSubsystem::with_config(
+						candidate_validation_config,
+						cv.metrics,
+						cv.pvf_metrics,
+					),
+					recorder,
+				)
+			})
+			.build_with_connector(connector)
+			.map_err(|e| e.into())
+	}
+}
+
+/// Answer the runtime API requests of the replayed subsystem from the log.
+fn answer_runtime_api(
+	responses: &HashMap<(Hash, RecordedRuntimeApiRequest), Option<Vec<u8>>>,
+	msg: AllMessages,
+) {
+	fn answer<T: Decode>(
+		responses: &HashMap<(Hash, RecordedRuntimeApiRequest), Option<Vec<u8>>>,
+		relay_parent: Hash,
+		request: RecordedRuntimeApiRequest,
+		tx: oneshot::Sender<Result<T, RuntimeApiError>>,
+	) {
+		let response = responses
+			.get(&(relay_parent, request.clone()))
+			.and_then(|response| response.as_ref())
+			.and_then(|encoded| T::decode(&mut &encoded[..]).ok());
+		if response.is_none() {
+			tracing::warn!(
+				target: MALUS,
+				?relay_parent,
+				?request,
+				"No recorded runtime API response"
+			);
+		}
+		let _ =
+			tx.send(response.ok_or(RuntimeApiError::NotSupported { runtime_api_name: "replay" }));
+	}
+
+	match msg {
+		AllMessages::RuntimeApi(RuntimeApiMessage::Request(relay_parent, request)) => match request
+		{
+			RuntimeApiRequest::ValidationCodeByHash(hash, tx) => answer(
+				responses,
+				relay_parent,
+				RecordedRuntimeApiRequest::ValidationCodeByHash(hash),
+				tx,
+			),
+			RuntimeApiRequest::PersistedValidationData(para_id, assumption, tx) => answer(
+				responses,
+				relay_parent,
+				RecordedRuntimeApiRequest::PersistedValidationData(para_id, assumption),
+				tx,
+			),
+			RuntimeApiRequest::ValidationCode(para_id, assumption, tx) => answer(
+				responses,
+				relay_parent,
+				RecordedRuntimeApiRequest::ValidationCode(para_id, assumption),
+				tx,
+			),
+			RuntimeApiRequest::CheckValidationOutputs(para_id, outputs, tx) => answer(
+				responses,
+				relay_parent,
+				RecordedRuntimeApiRequest::CheckValidationOutputs(para_id, outputs),
+				tx,
+			),
+			request => {
+				tracing::warn!(target: MALUS, ?request, "Unexpected runtime API request")
+			},
+		},
+		msg => tracing::debug!(target: MALUS, ?msg, "Ignoring message of the replayed subsystem"),
+	}
+}
+
+/// Send the recorded signals and requests to the subsystem, in the recorded order, and collect
+/// its answers.
+async fn send_records(
+	mut to_subsystem: impl Sink<FromOverseer<CandidateValidationMessage>> + Unpin,
+	records: Vec<Record>,
+) -> Vec<(u64, Verdict)> {
+	let mut answers = FuturesUnordered::new();
+	for record in records {
+		let msg = match record {
+			Record::Signal(signal) => FromOverseer::Signal(signal.into_signal()),
+			Record::Request { id, request } => {
+				let (msg, answer) = into_message(request);
+				answers.push(answer.map(move |verdict| (id, verdict)));
+				FromOverseer::Communication { msg }
+			},
+			Record::Verdict { .. } | Record::RuntimeApi { .. } => continue,
+		};
+
+		if to_subsystem.send(msg).await.is_err() {
+			tracing::warn!(target: MALUS, "The replayed subsystem exited early");
+			break
+		}
+	}
+
+	let verdicts = answers.collect().await;
+	let _ = to_subsystem.send(FromOverseer::Signal(OverseerSignal::Conclude)).await;
+	verdicts
+}
+
+/// Turn a recorded request into a message, along with the future resolving to its answer.
+fn into_message(
+	request: RecordedRequest,
+) -> (CandidateValidationMessage, future::BoxFuture<'static, Verdict>) {
+	fn validation_answer(
+		rx: oneshot::Receiver<Result<ValidationResult, ValidationFailed>>,
+	) -> future::BoxFuture<'static, Verdict> {
+		rx.map(|answer| match answer {
+			Ok(result) => Verdict::from(&result),
+			Err(_) => Verdict::Failed("No answer".into()),
+		})
+		.boxed()
+	}
+
+	match request {
+		RecordedRequest::ValidateFromChainState { descriptor, pov, timeout_millis } => {
+			let (tx, rx) = oneshot::channel();
+			let msg = CandidateValidationMessage::ValidateFromChainState(
+				descriptor,
+				Arc::new(pov),
+				Duration::from_millis(timeout_millis),
+				tx,
+			);
+			(msg, validation_answer(rx))
+		},
+		RecordedRequest::ValidateFromExhaustive {
+			persisted_validation_data,
+			validation_code,
+			descriptor,
+			pov,
+			timeout_millis,
+		} => {
+			let (tx, rx) = oneshot::channel();
+			let msg = CandidateValidationMessage::ValidateFromExhaustive(
+				persisted_validation_data,
+				validation_code,
+				descriptor,
+				Arc::new(pov),
+				Duration::from_millis(timeout_millis),
+				tx,
+			);
+			(msg, validation_answer(rx))
+		},
+		RecordedRequest::ValidateFromCodeHash {
+			persisted_validation_data,
+			validation_code_hash,
+			descriptor,
+			pov,
+			timeout_millis,
+		} => {
+			let (tx, rx) = oneshot::channel();
+			let msg = CandidateValidationMessage::ValidateFromCodeHash(
+				persisted_validation_data,
+				validation_code_hash,
+				descriptor,
+				Arc::new(pov),
+				Duration::from_millis(timeout_millis),
+				tx,
+			);
+			(msg, validation_answer(rx))
+		},
+		RecordedRequest::PreCheck { relay_parent, validation_code_hash } => {
+			let (tx, rx) = oneshot::channel();
+			let msg = CandidateValidationMessage::PreCheck(relay_parent, validation_code_hash, tx);
+			let answer = rx
+				.map(|answer| match answer {
+					Ok(outcome) => Verdict::from(&outcome),
+					Err(_) => Verdict::Failed("No answer".into()),
+				})
+				.boxed();
+			(msg, answer)
+		},
+	}
+}
+
+/// Replay the log against a fresh subsystem, failing if any answer differs from the recorded one.
+pub(crate) fn replay(cmd: ReplayCmd) -> eyre::Result<()> {
+	let records = read_records::<Record>(&cmd.log)?;
+
+	let mut recorded_verdicts = HashMap::new();
+	let mut responses = HashMap::new();
+	for record in &records {
+		match record {
+			Record::Verdict { id, verdict } => {
+				recorded_verdicts.insert(*id, verdict.clone());
+			},
+			Record::RuntimeApi { relay_parent, request, response } => {
+				responses.insert((*relay_parent, request.clone()), response.clone());
+			},
+			Record::Signal(_) | Record::Request { .. } => {},
+		}
+	}
+
+	let config = CandidateValidationConfig {
+		artifacts_cache_path: cmd.artifacts_cache_path,
+		// This binary handles the worker subcommands itself.
+		isolation_strategy: IsolationStrategy::default(),
+		metrics_para_allow_list: Default::default(),
+		invalid_candidate_export: None,
+		audit_log: None,
+	};
+	let subsystem =
+		CandidateValidationSubsystem::with_config(config, Default::default(), Default::default());
+
+	let (context, handle) = make_subsystem_context(sp_core::testing::TaskExecutor::new());
+	let TestSubsystemContextHandle { tx: to_subsystem, rx: mut from_subsystem } = handle;
+
+	let run = future::join(send_records(to_subsystem, records), subsystem.start(context).future);
+	let answer = async move {
+		while let Some(msg) = from_subsystem.next().await {
+			answer_runtime_api(&responses, msg);
+		}
+	};
+	futures::pin_mut!(run, answer);
+
+	let (verdicts, result) = match futures::executor::block_on(future::select(run, answer)) {
+		future::Either::Left((outcome, _)) => outcome,
+		future::Either::Right(_) => return Err(eyre::eyre!("The replayed subsystem vanished")),
+	};
+	result?;
+
+	let mut differing = 0;
+	for (id, verdict) in &verdicts {
+		match recorded_verdicts.get(id) {
+			Some(recorded) if recorded == verdict => {},
+			recorded => {
+				differing += 1;
+				tracing::warn!(target: MALUS, id, ?recorded, replayed = ?verdict, "Verdict differs");
+			},
+		}
+	}
+	tracing::info!(target: MALUS, replayed = verdicts.len(), differing, "Replay finished");
+
+	if differing > 0 {
+		return Err(eyre::eyre!("{} of {} verdicts differ", differing, verdicts.len()))
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use selendra_node_primitives::InvalidCandidate;
+
+	#[test]
+	fn verdicts_compare_outcomes() {
+		let valid = Ok(ValidationResult::Valid(
+			CandidateCommitments::default(),
+			PersistedValidationData::default(),
+		));
+		let invalid = Ok(ValidationResult::Invalid(InvalidCandidate::Timeout));
+
+		assert_eq!(
+			Verdict::from(&valid),
+			Verdict::Valid { commitments_hash: CandidateCommitments::default().hash() },
+		);
+		assert_eq!(Verdict::from(&invalid), Verdict::Invalid("Timeout".into()));
+		assert_ne!(Verdict::from(&valid), Verdict::from(&invalid));
+	}
+
+	#[test]
+	fn recorded_requests_turn_into_messages() {
+		let request = RecordedRequest::PreCheck {
+			relay_parent: Hash::repeat_byte(1),
+			validation_code_hash: Hash::repeat_byte(2).into(),
+		};
+		let encoded = Record::Request { id: 3, request: request.clone() }.encode();
+		assert_eq!(
+			Record::decode(&mut &encoded[..]).unwrap(),
+			Record::Request { id: 3, request: request.clone() },
+		);
+
+		let (msg, _) = into_message(request);
+		assert!(matches!(
+			msg,
+			CandidateValidationMessage::PreCheck(relay_parent, _, _)
+				if relay_parent == Hash::repeat_byte(1)
+		));
+	}
+}
@@ -28,6 +28,9 @@ pub enum Error {
 	#[error("failed to query full data from store")]
 	CanceledQueryFullData(#[source] oneshot::Canceled),
 
+	#[error("failed to query all chunks from store")]
+	CanceledQueryAllChunks(#[source] oneshot::Canceled),
+
 	#[error("failed to query session info")]
 	CanceledSessionInfo(#[source] oneshot::Canceled),
 
@@ -100,9 +100,32 @@ const TIMEOUT_START_NEW_REQUESTS: Duration = CHUNK_REQUEST_TIMEOUT;
 #[cfg(test)]
 const TIMEOUT_START_NEW_REQUESTS: Duration = Duration::from_millis(100);
 
+/// The default size limit of the available data of candidates recovered from the backing group
+/// first, when using [`RecoveryStrategy::BackersFirstIfSmaller`].
+pub const DEFAULT_FAST_PATH_SIZE_LIMIT: usize = 128 * 1024;
+
+/// The way the available data of candidates is recovered.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RecoveryStrategy {
+	/// Reconstruct the data from the chunks of all validators.
+	ChunksOnly,
+	/// Request the full data from the backing group first, if known.
+	BackersFirst,
+	/// Request the full data from the backing group first, if known and the data is estimated
+	/// to be at most the given number of bytes. Fetching small data in full saves the
+	/// latency of collecting chunks and the CPU time of erasure decoding them.
+	BackersFirstIfSmaller(usize),
+}
+
+impl Default for RecoveryStrategy {
+	fn default() -> Self {
+		RecoveryStrategy::BackersFirstIfSmaller(DEFAULT_FAST_PATH_SIZE_LIMIT)
+	}
+}
+
 /// The Availability Recovery Subsystem.
 pub struct AvailabilityRecoverySubsystem {
-	fast_path: bool,
+	strategy: RecoveryStrategy,
 	/// Receiver for available data requests.
 	req_receiver: IncomingRequestReceiver<request_v1::AvailableDataFetchingRequest>,
 	/// Metrics for this subsystem.
@@ -126,6 +149,8 @@ struct RequestChunksFromValidators {
 	/// request the chunk from them.
 	shuffling: VecDeque<ValidatorIndex>,
 	received_chunks: HashMap<ValidatorIndex, ErasureChunk>,
+	/// Our own chunks, if they were queried from the store already.
+	local_chunks: Option<Vec<ErasureChunk>>,
 	/// Pending chunk requests with soft timeout.
 	requesting_chunks: FuturesUndead<Result<Option<ErasureChunk>, (ValidatorIndex, RequestError)>>,
 }
@@ -168,6 +193,10 @@ struct RecoveryTask<S> {
 
 	/// The source to obtain the availability data from.
 	source: Source,
+
+	/// Our own chunks, if they were queried from the store already and the chunks are not
+	/// requested yet.
+	local_chunks: Option<Vec<ErasureChunk>>,
 }
 
 impl RequestFromBackers {
@@ -251,7 +280,7 @@ impl RequestFromBackers {
 }
 
 impl RequestChunksFromValidators {
-	fn new(n_validators: u32) -> Self {
+	fn new(n_validators: u32, local_chunks: Option<Vec<ErasureChunk>>) -> Self {
 		let mut shuffling: Vec<_> = (0..n_validators).map(ValidatorIndex).collect();
 		shuffling.shuffle(&mut rand::thread_rng());
 
@@ -260,6 +289,7 @@ impl RequestChunksFromValidators {
 			total_received_responses: 0,
 			shuffling: shuffling.into(),
 			received_chunks: HashMap::new(),
+			local_chunks,
 			requesting_chunks: FuturesUndead::new(),
 		}
 	}
@@ -461,34 +491,39 @@ impl RequestChunksFromValidators {
 		params: &RecoveryParams,
 		sender: &mut impl SubsystemSender,
 	) -> Result<AvailableData, RecoveryError> {
-		// First query the store for any chunks we've got.
-		{
-			let (tx, rx) = oneshot::channel();
-			sender
-				.send_message(
-					AvailabilityStoreMessage::QueryAllChunks(params.candidate_hash, tx).into(),
-				)
-				.await;
+		// First query the store for any chunks we've got, unless we did already.
+		let local_chunks = match self.local_chunks.take() {
+			Some(chunks) => Ok(chunks),
+			None => {
+				let (tx, rx) = oneshot::channel();
+				sender
+					.send_message(
+						AvailabilityStoreMessage::QueryAllChunks(params.candidate_hash, tx).into(),
+					)
+					.await;
 
-			match rx.await {
-				Ok(chunks) => {
-					// This should either be length 1 or 0. If we had the whole data,
-					// we wouldn't have reached this stage.
-					let chunk_indices: Vec<_> = chunks.iter().map(|c| c.index).collect();
-					self.shuffling.retain(|i| !chunk_indices.contains(i));
-
-					for chunk in chunks {
-						self.received_chunks.insert(chunk.index, chunk);
-					}
-				},
-				Err(oneshot::Canceled) => {
-					tracing::warn!(
-						target: LOG_TARGET,
-						candidate_hash = ?params.candidate_hash,
-						"Failed to reach the availability store"
-					);
-				},
-			}
+				rx.await
+			},
+		};
+
+		match local_chunks {
+			Ok(chunks) => {
+				// This should either be length 1 or 0. If we had the whole data,
+				// we wouldn't have reached this stage.
+				let chunk_indices: Vec<_> = chunks.iter().map(|c| c.index).collect();
+				self.shuffling.retain(|i| !chunk_indices.contains(i));
+
+				for chunk in chunks {
+					self.received_chunks.insert(chunk.index, chunk);
+				}
+			},
+			Err(oneshot::Canceled) => {
+				tracing::warn!(
+					target: LOG_TARGET,
+					candidate_hash = ?params.candidate_hash,
+					"Failed to reach the availability store"
+				);
+			},
 		}
 
 		loop {
@@ -642,6 +677,7 @@ impl<S: SubsystemSender> RecoveryTask<S> {
 						Err(RecoveryError::Unavailable) =>
 							self.source = Source::RequestChunks(RequestChunksFromValidators::new(
 								self.params.validators.len() as _,
+								self.local_chunks.take(),
 							)),
 					}
 				},
@@ -796,6 +832,7 @@ async fn launch_recovery_task<Context>(
 	session_info: SessionInfo,
 	receipt: CandidateReceipt,
 	backing_group: Option<GroupIndex>,
+	strategy: RecoveryStrategy,
 	response_sender: oneshot::Sender<Result<AvailableData, RecoveryError>>,
 	metrics: &Metrics,
 ) -> error::Result<()>
@@ -814,14 +851,43 @@ where
 		metrics: metrics.clone(),
 	};
 
-	let phase = backing_group
-		.and_then(|g| session_info.validator_groups.get(g.0 as usize))
-		.map(|group| Source::RequestFromBackers(RequestFromBackers::new(group.clone())))
-		.unwrap_or_else(|| {
-			Source::RequestChunks(RequestChunksFromValidators::new(params.validators.len() as _))
-		});
+	// Our own chunks, when queried to estimate the size of the data, are reused to recover it.
+	let mut local_chunks = None;
+
+	let backing_group = match strategy {
+		RecoveryStrategy::ChunksOnly => None,
+		RecoveryStrategy::BackersFirst => backing_group,
+		RecoveryStrategy::BackersFirstIfSmaller(limit) => match backing_group {
+			Some(group) => {
+				let chunks = query_all_chunks(ctx, candidate_hash).await?;
+				let size = estimate_available_data_size(&chunks, params.threshold);
+				local_chunks = Some(chunks);
+				tracing::trace!(
+					target: LOG_TARGET,
+					?candidate_hash,
+					?size,
+					limit,
+					"Estimated the size of the available data",
+				);
 
-	let recovery_task = RecoveryTask { sender: ctx.sender().clone(), params, source: phase };
+				// Without an estimate the data may be arbitrarily large, so it is safer to
+				// recover it from chunks.
+				size.filter(|size| *size <= limit).map(|_| group)
+			},
+			None => None,
+		},
+	};
+
+	let phase = match backing_group.and_then(|g| session_info.validator_groups.get(g.0 as usize)) {
+		Some(group) => Source::RequestFromBackers(RequestFromBackers::new(group.clone())),
+		None => Source::RequestChunks(RequestChunksFromValidators::new(
+			params.validators.len() as _,
+			local_chunks.take(),
+		)),
+	};
+
+	let recovery_task =
+		RecoveryTask { sender: ctx.sender().clone(), params, source: phase, local_chunks };
 
 	let (remote, remote_handle) = recovery_task.run().remote_handle();
 
@@ -849,6 +915,7 @@ async fn handle_recover<Context>(
 	receipt: CandidateReceipt,
 	session_index: SessionIndex,
 	backing_group: Option<GroupIndex>,
+	strategy: RecoveryStrategy,
 	response_sender: oneshot::Sender<Result<AvailableData, RecoveryError>>,
 	metrics: &Metrics,
 ) -> error::Result<()>
@@ -896,6 +963,7 @@ where
 				session_info,
 				receipt,
 				backing_group,
+				strategy,
 				response_sender,
 				metrics,
 			)
@@ -926,6 +994,29 @@ where
 	Ok(rx.await.map_err(error::Error::CanceledQueryFullData)?)
 }
 
+/// Queries the chunks we've got from av-store.
+async fn query_all_chunks<Context>(
+	ctx: &mut Context,
+	candidate_hash: CandidateHash,
+) -> error::Result<Vec<ErasureChunk>>
+where
+	Context: SubsystemContext<Message = AvailabilityRecoveryMessage>,
+	Context: overseer::SubsystemContext<Message = AvailabilityRecoveryMessage>,
+{
+	let (tx, rx) = oneshot::channel();
+	ctx.send_message(AvailabilityStoreMessage::QueryAllChunks(candidate_hash, tx))
+		.await;
+
+	Ok(rx.await.map_err(error::Error::CanceledQueryAllChunks)?)
+}
+
+/// Estimates the size of the available data of a candidate from the chunks we've got.
+///
+/// The data is split into `threshold` pieces, each of which is about as large as a chunk.
+fn estimate_available_data_size(chunks: &[ErasureChunk], threshold: usize) -> Option<usize> {
+	chunks.first().map(|chunk| chunk.chunk.len().saturating_mul(threshold))
+}
+
 impl AvailabilityRecoverySubsystem {
 	/// Create a new instance of `AvailabilityRecoverySubsystem` which starts with a fast path to
 	/// request data from backers.
@@ -933,7 +1024,34 @@ impl AvailabilityRecoverySubsystem {
 		req_receiver: IncomingRequestReceiver<request_v1::AvailableDataFetchingRequest>,
 		metrics: Metrics,
 	) -> Self {
-		Self { fast_path: true, req_receiver, metrics }
+		Self { strategy: RecoveryStrategy::BackersFirst, req_receiver, metrics }
+	}
+
+	/// Create a new instance of `AvailabilityRecoverySubsystem` which starts with a fast path to
+	/// request data from backers, if the data is estimated to be at most `size_limit` bytes.
+	///
+	/// The size is estimated from our own chunk. Larger data, or data of which we don't have a
+	/// chunk, is recovered from chunks only.
+	pub fn with_fast_path_if_smaller(
+		req_receiver: IncomingRequestReceiver<request_v1::AvailableDataFetchingRequest>,
+		metrics: Metrics,
+		size_limit: usize,
+	) -> Self {
+		Self {
+			strategy: RecoveryStrategy::BackersFirstIfSmaller(size_limit),
+			req_receiver,
+			metrics,
+		}
+	}
+
+	/// Create a new instance of `AvailabilityRecoverySubsystem` recovering data with the given
+	/// strategy.
+	pub fn with_strategy(
+		req_receiver: IncomingRequestReceiver<request_v1::AvailableDataFetchingRequest>,
+		metrics: Metrics,
+		strategy: RecoveryStrategy,
+	) -> Self {
+		Self { strategy, req_receiver, metrics }
 	}
 
 	/// Create a new instance of `AvailabilityRecoverySubsystem` which requests only chunks
@@ -941,7 +1059,7 @@ impl AvailabilityRecoverySubsystem {
 		req_receiver: IncomingRequestReceiver<request_v1::AvailableDataFetchingRequest>,
 		metrics: Metrics,
 	) -> Self {
-		Self { fast_path: false, req_receiver, metrics }
+		Self { strategy: RecoveryStrategy::ChunksOnly, req_receiver, metrics }
 	}
 
 	async fn run<Context>(self, mut ctx: Context) -> SubsystemResult<()>
@@ -950,7 +1068,7 @@ impl AvailabilityRecoverySubsystem {
 		Context: overseer::SubsystemContext<Message = AvailabilityRecoveryMessage>,
 	{
 		let mut state = State::default();
-		let Self { fast_path, mut req_receiver, metrics } = self;
+		let Self { strategy, mut req_receiver, metrics } = self;
 
 		loop {
 			let recv_req = req_receiver.recv(|| vec![COST_INVALID_REQUEST]).fuse();
@@ -977,7 +1095,8 @@ impl AvailabilityRecoverySubsystem {
 										&mut ctx,
 										receipt,
 										session_index,
-										maybe_backing_group.filter(|_| fast_path),
+										maybe_backing_group,
+										strategy,
 										response_sender,
 										&metrics,
 									).await {
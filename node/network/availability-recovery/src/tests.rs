@@ -110,6 +110,45 @@ fn test_harness_chunks_only<T: Future<Output = (VirtualOverseer, RequestResponse
 	.unwrap();
 }
 
+fn test_harness_fast_path_if_smaller<
+	T: Future<Output = (VirtualOverseer, RequestResponseConfig)>,
+>(
+	size_limit: usize,
+	test: impl FnOnce(VirtualOverseer, RequestResponseConfig) -> T,
+) {
+	let _ = env_logger::builder()
+		.is_test(true)
+		.filter(Some("selendra_availability_recovery"), log::LevelFilter::Trace)
+		.try_init();
+
+	let pool = sp_core::testing::TaskExecutor::new();
+
+	let (context, virtual_overseer) = make_subsystem_context(pool.clone());
+
+	let (collation_req_receiver, req_cfg) = IncomingRequest::get_config_receiver();
+	let subsystem = AvailabilityRecoverySubsystem::with_fast_path_if_smaller(
+		collation_req_receiver,
+		Metrics::new_dummy(),
+		size_limit,
+	);
+	let subsystem = subsystem.run(context);
+
+	let test_fut = test(virtual_overseer, req_cfg);
+
+	futures::pin_mut!(test_fut);
+	futures::pin_mut!(subsystem);
+
+	executor::block_on(future::join(
+		async move {
+			let (mut overseer, _req_cfg) = test_fut.await;
+			overseer_signal(&mut overseer, OverseerSignal::Conclude).await;
+		},
+		subsystem,
+	))
+	.1
+	.unwrap();
+}
+
 const TIMEOUT: Duration = Duration::from_millis(300);
 
 macro_rules! delay {
@@ -931,6 +970,149 @@ fn no_answers_in_fast_path_causes_chunk_requests() {
 	});
 }
 
+#[test]
+fn small_data_is_recovered_from_backing_group() {
+	let test_state = TestState::default();
+
+	test_harness_fast_path_if_smaller(
+		DEFAULT_FAST_PATH_SIZE_LIMIT,
+		|mut virtual_overseer, req_cfg| async move {
+			overseer_signal(
+				&mut virtual_overseer,
+				OverseerSignal::ActiveLeaves(ActiveLeavesUpdate::start_work(ActivatedLeaf {
+					hash: test_state.current.clone(),
+					number: 1,
+					status: LeafStatus::Fresh,
+					span: Arc::new(jaeger::Span::Disabled),
+				})),
+			)
+			.await;
+
+			let (tx, rx) = oneshot::channel();
+
+			overseer_send(
+				&mut virtual_overseer,
+				AvailabilityRecoveryMessage::RecoverAvailableData(
+					test_state.candidate.clone(),
+					test_state.session_index,
+					Some(GroupIndex(0)),
+					tx,
+				),
+			)
+			.await;
+
+			test_state.test_runtime_api(&mut virtual_overseer).await;
+
+			let candidate_hash = test_state.candidate.hash();
+
+			// Our own chunk is used to estimate the size of the data.
+			test_state.respond_to_query_all_request(&mut virtual_overseer, |i| i == 0).await;
+			test_state.respond_to_available_data_query(&mut virtual_overseer, false).await;
+
+			let who_has = |i| match i {
+				3 => Has::Yes,
+				_ => Has::No,
+			};
+
+			test_state
+				.test_full_data_requests(candidate_hash, &mut virtual_overseer, who_has)
+				.await;
+
+			// Recovered data should match the original one.
+			assert_eq!(rx.await.unwrap().unwrap(), test_state.available_data);
+			(virtual_overseer, req_cfg)
+		},
+	);
+}
+
+#[test]
+fn large_data_or_data_without_local_chunk_is_recovered_from_chunks() {
+	let test_state = TestState::default();
+
+	test_harness_fast_path_if_smaller(1, |mut virtual_overseer, req_cfg| async move {
+		overseer_signal(
+			&mut virtual_overseer,
+			OverseerSignal::ActiveLeaves(ActiveLeavesUpdate::start_work(ActivatedLeaf {
+				hash: test_state.current.clone(),
+				number: 1,
+				status: LeafStatus::Fresh,
+				span: Arc::new(jaeger::Span::Disabled),
+			})),
+		)
+		.await;
+
+		let (tx, rx) = oneshot::channel();
+
+		overseer_send(
+			&mut virtual_overseer,
+			AvailabilityRecoveryMessage::RecoverAvailableData(
+				test_state.candidate.clone(),
+				test_state.session_index,
+				Some(GroupIndex(0)),
+				tx,
+			),
+		)
+		.await;
+
+		test_state.test_runtime_api(&mut virtual_overseer).await;
+
+		let candidate_hash = test_state.candidate.hash();
+
+		// The data is estimated to be larger than the limit.
+		test_state.respond_to_query_all_request(&mut virtual_overseer, |i| i == 0).await;
+		test_state.respond_to_available_data_query(&mut virtual_overseer, false).await;
+
+		// Our own chunk, queried already, is not requested again.
+		test_state
+			.test_chunk_requests(
+				candidate_hash,
+				&mut virtual_overseer,
+				test_state.threshold() - 1,
+				|i| if i == 0 { panic!("requested from local validator") } else { Has::Yes },
+			)
+			.await;
+
+		// Recovered data should match the original one.
+		assert_eq!(rx.await.unwrap().unwrap(), test_state.available_data);
+
+		let (tx, rx) = oneshot::channel();
+
+		// Test another candidate, of which we don't have a chunk to estimate the size from.
+		let mut new_candidate = dummy_candidate_receipt(dummy_hash());
+
+		new_candidate.descriptor.relay_parent = test_state.candidate.descriptor.relay_parent;
+
+		overseer_send(
+			&mut virtual_overseer,
+			AvailabilityRecoveryMessage::RecoverAvailableData(
+				new_candidate.clone(),
+				test_state.session_index,
+				Some(GroupIndex(0)),
+				tx,
+			),
+		)
+		.await;
+
+		test_state.test_runtime_api(&mut virtual_overseer).await;
+
+		test_state.respond_to_query_all_request(&mut virtual_overseer, |_| false).await;
+		test_state.respond_to_available_data_query(&mut virtual_overseer, false).await;
+
+		test_state
+			.test_chunk_requests(
+				new_candidate.hash(),
+				&mut virtual_overseer,
+				test_state.impossibility_threshold(),
+				|_| Has::No,
+			)
+			.await;
+
+		// A request times out with `Unavailable` error.
+		assert_eq!(rx.await.unwrap().unwrap_err(), RecoveryError::Unavailable);
+		(virtual_overseer, req_cfg)
+	});
+}
+
 #[test]
 fn task_canceled_when_receivers_dropped() {
 	let test_state = TestState::default();
@@ -1283,7 +1465,7 @@ fn does_not_query_local_validator() {
 fn parallel_request_calculation_works_as_expected() {
 	let num_validators = 100;
 	let threshold = recovery_threshold(num_validators).unwrap();
-	let mut phase = RequestChunksFromValidators::new(100);
+	let mut phase = RequestChunksFromValidators::new(100, None);
 	assert_eq!(phase.get_desired_request_count(threshold), threshold);
 	phase.error_count = 1;
 	phase.total_received_responses = 1;
@@ -46,7 +46,7 @@ use selendra_primitives::v1::{
 };
 use selendra_subsystem::{
 	jaeger,
-	messages::{CollatorProtocolMessage, NetworkBridgeEvent, NetworkBridgeMessage},
+	messages::{CollatorProtocolMessage, NetworkBridgeEvent, NetworkBridgeMessage, NewCollatorKey},
 	overseer, FromOverseer, OverseerSignal, PerLeafSpan, SubsystemContext,
 };
 
@@ -652,6 +652,23 @@ where
 				"ReportCollator message is not expected on the collator side of the protocol",
 			);
 		},
+		RotateKey(NewCollatorKey(collator_pair)) => {
+			state.collator_pair = collator_pair;
+
+			// Validators only accept a single declaration per connection, so reconnect to them
+			// to declare the new key.
+			let peers = state.peer_views.keys().cloned().collect::<Vec<_>>();
+			tracing::info!(
+				target: LOG_TARGET,
+				collator_id = ?state.collator_pair.public(),
+				peers = peers.len(),
+				"Rotated the collator key, reconnecting to validators.",
+			);
+			for peer in peers {
+				ctx.send_message(NetworkBridgeMessage::DisconnectPeer(peer, PeerSet::Collation))
+					.await;
+			}
+		},
 		NetworkBridgeUpdateV1(event) => {
 			if let Err(e) = handle_network_msg(ctx, runtime, state, event).await {
 				tracing::warn!(
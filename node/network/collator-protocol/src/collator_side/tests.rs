@@ -684,6 +684,43 @@ fn collators_declare_to_connected_peers() {
 	})
 }
 
+#[test]
+fn collators_redeclare_after_key_rotation() {
+	let mut test_state = TestState::default();
+	let local_peer_id = test_state.local_peer_id.clone();
+	let collator_pair = test_state.collator_pair.clone();
+
+	test_harness(local_peer_id, collator_pair, |mut test_harness| async move {
+		let virtual_overseer = &mut test_harness.virtual_overseer;
+		let peer = test_state.validator_peer_id[0].clone();
+		let validator_id = test_state.current_group_validator_authority_ids()[0].clone();
+
+		setup_system(virtual_overseer, &test_state).await;
+
+		connect_peer(virtual_overseer, peer.clone(), Some(validator_id.clone())).await;
+		expect_declare_msg(virtual_overseer, &test_state, &peer).await;
+
+		// Rotating the key disconnects the validator, to declare the new key on reconnect.
+		test_state.collator_pair = CollatorPair::generate().0;
+		overseer_send(
+			virtual_overseer,
+			CollatorProtocolMessage::RotateKey(NewCollatorKey(test_state.collator_pair.clone())),
+		)
+		.await;
+		assert_matches!(
+			overseer_recv(virtual_overseer).await,
+			AllMessages::NetworkBridge(NetworkBridgeMessage::DisconnectPeer(p, PeerSet::Collation)) => {
+				assert_eq!(p, peer);
+			}
+		);
+
+		disconnect_peer(virtual_overseer, peer.clone()).await;
+		connect_peer(virtual_overseer, peer.clone(), Some(validator_id)).await;
+		expect_declare_msg(virtual_overseer, &test_state, &peer).await;
+		test_harness
+	})
+}
+
 #[test]
 fn collations_are_only_advertised_to_validators_with_correct_view() {
 	let test_state = TestState::default();
@@ -70,6 +70,8 @@ const COST_INVALID_SIGNATURE: Rep = Rep::Malicious("Invalid network message sign
 const COST_REPORT_BAD: Rep = Rep::Malicious("A collator was reported by another subsystem");
 const COST_WRONG_PARA: Rep = Rep::Malicious("A collator provided a collation for the wrong para");
 const COST_UNNEEDED_COLLATOR: Rep = Rep::CostMinor("An unneeded collator connected");
+const COST_ADVERTISEMENT_QUOTA: Rep =
+	Rep::Malicious("A collator exceeded its quota of invalid advertisements");
 const BENEFIT_NOTIFY_GOOD: Rep =
 	Rep::BenefitMinor("A collator was noted good by another subsystem");
 
@@ -86,6 +88,19 @@ const BENEFIT_NOTIFY_GOOD: Rep =
 /// There is debug logging output, so we can adjust this value based on production results.
 const MAX_UNSHARED_DOWNLOAD_TIME: Duration = Duration::from_millis(400);
 
+/// Maximum number of advertisements we act upon per para and relay parent.
+///
+/// Every accepted advertisement might result in a collation fetch, so this bounds the work the
+/// collators of a single para can cause us for any given relay parent.
+const MAX_ADVERTISEMENTS_PER_PARA: usize = 16;
+
+/// Maximum number of invalid advertisements (duplicate, out of view or undeclared) we tolerate
+/// from a single peer between two changes of our view.
+///
+/// Honest collators might race with our view changes once in a while, but never repeatedly.
+/// Peers exceeding this quota get disconnected immediately.
+const MAX_INVALID_ADVERTISEMENTS_PER_PEER: usize = 4;
+
 // How often to check all peers with activity.
 #[cfg(not(test))]
 const ACTIVITY_POLL: Duration = Duration::from_secs(1);
@@ -130,6 +145,13 @@ impl Metrics {
 			.as_ref()
 			.map(|metrics| metrics.collator_peer_count.set(collator_peers as u64));
 	}
+
+	/// Note the outcome of processing an advertisement.
+	fn on_advertisement(&self, outcome: &'static str) {
+		if let Some(metrics) = &self.0 {
+			metrics.advertisements.with_label_values(&[outcome]).inc();
+		}
+	}
 }
 
 #[derive(Clone)]
@@ -138,6 +160,7 @@ struct MetricsInner {
 	process_msg: prometheus::Histogram,
 	handle_collation_request_result: prometheus::Histogram,
 	collator_peer_count: prometheus::Gauge<prometheus::U64>,
+	advertisements: prometheus::CounterVec<prometheus::U64>,
 }
 
 impl metrics::Metrics for Metrics {
@@ -180,6 +203,16 @@ impl metrics::Metrics for Metrics {
 				)?,
 				registry,
 			)?,
+			advertisements: prometheus::register(
+				prometheus::CounterVec::new(
+					prometheus::Opts::new(
+						"selendra_parachain_collator_protocol_validator_advertisements_total",
+						"Number of collation advertisements received from collators.",
+					),
+					&["outcome"],
+				)?,
+				registry,
+			)?,
 		};
 
 		Ok(Metrics(Some(metrics)))
@@ -223,11 +256,13 @@ enum AdvertisementError {
 struct PeerData {
 	view: View,
 	state: PeerState,
+	/// Invalid advertisements received since our last view change.
+	invalid_advertisements: usize,
 }
 
 impl PeerData {
 	fn new(view: View) -> Self {
-		PeerData { view, state: PeerState::Connected(Instant::now()) }
+		PeerData { view, state: PeerState::Connected(Instant::now()), invalid_advertisements: 0 }
 	}
 
 	/// Update the view, clearing all advertisements that are no longer in the
@@ -242,12 +277,22 @@ impl PeerData {
 	}
 
 	/// Prune old advertisements relative to our view.
+	///
+	/// This also resets the quota of invalid advertisements of the peer.
 	fn prune_old_advertisements(&mut self, our_view: &View) {
+		self.invalid_advertisements = 0;
 		if let PeerState::Collating(ref mut peer_state) = self.state {
 			peer_state.advertisements.retain(|a| our_view.contains(a));
 		}
 	}
 
+	/// Note an invalid advertisement by the peer. Returns `true` if the peer exceeded its quota
+	/// of invalid advertisements.
+	fn note_invalid_advertisement(&mut self) -> bool {
+		self.invalid_advertisements += 1;
+		self.invalid_advertisements > MAX_INVALID_ADVERTISEMENTS_PER_PEER
+	}
+
 	/// Note an advertisement by the collator. Returns `true` if the advertisement was imported
 	/// successfully. Fails if the advertisement is duplicate, out of view, or the peer has not
 	/// declared itself a collator.
@@ -516,9 +561,25 @@ struct CollationsPerRelayParent {
 	waiting_collation: Option<CollatorId>,
 	/// Collation that were advertised to us, but we did not yet fetch.
 	unfetched_collations: Vec<(PendingCollation, CollatorId)>,
+	/// Number of advertisements we accepted per para.
+	advertisements_per_para: HashMap<ParaId, usize>,
 }
 
 impl CollationsPerRelayParent {
+	/// Note an advertisement for the given para.
+	///
+	/// Returns `false` if the para already exhausted its quota of advertisements for this relay
+	/// parent, in which case the advertisement should be ignored.
+	fn note_advertisement(&mut self, para_id: ParaId) -> bool {
+		let count = self.advertisements_per_para.entry(para_id).or_default();
+		if *count >= MAX_ADVERTISEMENTS_PER_PARA {
+			return false
+		}
+
+		*count += 1;
+		true
+	}
+
 	/// Returns the next collation to fetch from the `unfetched_collations`.
 	///
 	/// This will reset the status back to `Waiting` using [`CollationStatus::back_to_waiting`].
@@ -618,6 +679,38 @@ where
 		.await
 }
 
+/// A peer sent us an invalid advertisement.
+///
+/// The peer gets disconnected once it exceeds its quota of invalid advertisements.
+async fn handle_invalid_advertisement<Context>(
+	ctx: &mut Context,
+	state: &mut State,
+	peer_id: PeerId,
+) where
+	Context: overseer::SubsystemContext<Message = CollatorProtocolMessage>,
+	Context: SubsystemContext<Message = CollatorProtocolMessage>,
+{
+	let quota_exceeded = state
+		.peer_data
+		.get_mut(&peer_id)
+		.map_or(false, |d| d.note_invalid_advertisement());
+
+	if quota_exceeded {
+		tracing::debug!(
+			target: LOG_TARGET,
+			?peer_id,
+			"Peer exceeded its quota of invalid advertisements, disconnecting",
+		);
+
+		state.metrics.on_advertisement("peer_quota_exceeded");
+		modify_reputation(ctx, peer_id.clone(), COST_ADVERTISEMENT_QUOTA).await;
+		disconnect_peer(ctx, peer_id).await;
+	} else {
+		state.metrics.on_advertisement("invalid");
+		modify_reputation(ctx, peer_id, COST_UNEXPECTED_MESSAGE).await;
+	}
+}
+
 /// Another subsystem has requested to fetch collations on a particular leaf for some para.
 async fn fetch_collation<Context>(
 	ctx: &mut Context,
@@ -856,7 +949,7 @@ async fn process_incoming_peer_message<Context>(
 					"Advertise collation out of view",
 				);
 
-				modify_reputation(ctx, origin, COST_UNEXPECTED_MESSAGE).await;
+				handle_invalid_advertisement(ctx, state, origin).await;
 				return
 			}
 
@@ -883,6 +976,21 @@ async fn process_incoming_peer_message<Context>(
 					let collations =
 						state.collations_per_relay_parent.entry(relay_parent).or_default();
 
+					if !collations.note_advertisement(para_id) {
+						tracing::debug!(
+							target: LOG_TARGET,
+							peer_id = ?origin,
+							%para_id,
+							?relay_parent,
+							"Para exceeded its advertisement quota, ignoring advertisement",
+						);
+
+						state.metrics.on_advertisement("para_quota_exceeded");
+						return
+					}
+
+					state.metrics.on_advertisement("accepted");
+
 					match collations.status {
 						CollationStatus::Fetching | CollationStatus::WaitingOnValidation =>
 							collations.unfetched_collations.push((pending_collation, id)),
@@ -904,7 +1012,7 @@ async fn process_incoming_peer_message<Context>(
 						"Invalid advertisement",
 					);
 
-					modify_reputation(ctx, origin, COST_UNEXPECTED_MESSAGE).await;
+					handle_invalid_advertisement(ctx, state, origin).await;
 				},
 			}
 		},
@@ -1054,6 +1162,12 @@ async fn process_msg<Context>(
 		ReportCollator(id) => {
 			report_collator(ctx, &state.peer_data, id).await;
 		},
+		RotateKey(_) => {
+			tracing::warn!(
+				target: LOG_TARGET,
+				"RotateKey message is not expected on the validator side of the protocol",
+			);
+		},
 		NetworkBridgeUpdateV1(event) => {
 			if let Err(e) = handle_network_msg(ctx, state, keystore, event).await {
 				tracing::warn!(
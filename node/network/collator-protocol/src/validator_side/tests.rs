@@ -1063,3 +1063,166 @@ fn view_change_clears_old_collators() {
 		virtual_overseer
 	})
 }
+
+// A collator which keeps sending invalid advertisements gets disconnected.
+#[test]
+fn disconnect_on_invalid_advertisement_quota() {
+	let test_state = TestState::default();
+
+	test_harness(|test_harness| async move {
+		let TestHarness { mut virtual_overseer } = test_harness;
+
+		overseer_send(
+			&mut virtual_overseer,
+			CollatorProtocolMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::OurViewChange(
+				our_view![test_state.relay_parent],
+			)),
+		)
+		.await;
+
+		respond_to_core_info_queries(&mut virtual_overseer, &test_state).await;
+
+		let peer_b = PeerId::random();
+
+		connect_and_declare_collator(
+			&mut virtual_overseer,
+			peer_b.clone(),
+			test_state.collators[0].clone(),
+			test_state.chain_ids[0],
+		)
+		.await;
+
+		let out_of_view = Hash::repeat_byte(0xAA);
+
+		for _ in 0..MAX_INVALID_ADVERTISEMENTS_PER_PEER {
+			advertise_collation(&mut virtual_overseer, peer_b.clone(), out_of_view).await;
+
+			assert_matches!(
+				overseer_recv(&mut virtual_overseer).await,
+				AllMessages::NetworkBridge(NetworkBridgeMessage::ReportPeer(peer, rep)) => {
+					assert_eq!(peer, peer_b);
+					assert_eq!(rep, COST_UNEXPECTED_MESSAGE);
+				}
+			);
+		}
+
+		advertise_collation(&mut virtual_overseer, peer_b.clone(), out_of_view).await;
+
+		assert_matches!(
+			overseer_recv(&mut virtual_overseer).await,
+			AllMessages::NetworkBridge(NetworkBridgeMessage::ReportPeer(peer, rep)) => {
+				assert_eq!(peer, peer_b);
+				assert_eq!(rep, COST_ADVERTISEMENT_QUOTA);
+			}
+		);
+
+		assert_collator_disconnect(&mut virtual_overseer, peer_b.clone()).await;
+
+		virtual_overseer
+	})
+}
+
+// The advertisements of a para are accepted up to the quota, per relay parent, and ignored above.
+#[test]
+fn para_advertisement_quota() {
+	let test_state = TestState::default();
+	let (para_a, para_b) = (test_state.chain_ids[0], test_state.chain_ids[1]);
+
+	let mut collations = CollationsPerRelayParent::default();
+
+	for _ in 0..MAX_ADVERTISEMENTS_PER_PARA {
+		assert!(collations.note_advertisement(para_a));
+	}
+	assert!(!collations.note_advertisement(para_a));
+	assert!(!collations.note_advertisement(para_a));
+
+	// The quota of another para is unaffected.
+	for _ in 0..MAX_ADVERTISEMENTS_PER_PARA {
+		assert!(collations.note_advertisement(para_b));
+	}
+	assert!(!collations.note_advertisement(para_b));
+
+	// So is the quota for another relay parent.
+	let mut collations = CollationsPerRelayParent::default();
+	assert!(collations.note_advertisement(para_a));
+}
+
+// Only as many advertisements of a para as its quota are ever fetched for a relay parent.
+#[test]
+fn advertisements_above_para_quota_are_ignored() {
+	let test_state = TestState::default();
+
+	test_harness(|test_harness| async move {
+		let TestHarness { mut virtual_overseer } = test_harness;
+
+		overseer_send(
+			&mut virtual_overseer,
+			CollatorProtocolMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::OurViewChange(
+				our_view![test_state.relay_parent],
+			)),
+		)
+		.await;
+
+		respond_to_core_info_queries(&mut virtual_overseer, &test_state).await;
+
+		// One advertisement above the quota, each from another collator.
+		for _ in 0..=MAX_ADVERTISEMENTS_PER_PARA {
+			let peer = PeerId::random();
+			connect_and_declare_collator(
+				&mut virtual_overseer,
+				peer.clone(),
+				CollatorPair::generate().0,
+				test_state.chain_ids[0],
+			)
+			.await;
+			advertise_collation(&mut virtual_overseer, peer, test_state.relay_parent).await;
+		}
+
+		// Every accepted collation is fetched in turn, as the previous one turns out invalid.
+		for _ in 0..MAX_ADVERTISEMENTS_PER_PARA {
+			let response_channel = assert_fetch_collation_request(
+				&mut virtual_overseer,
+				test_state.relay_parent,
+				test_state.chain_ids[0],
+			)
+			.await;
+
+			let pov = PoV { block_data: BlockData(vec![]) };
+			let mut candidate =
+				dummy_candidate_receipt_bad_sig(dummy_hash(), Some(Default::default()));
+			candidate.descriptor.para_id = test_state.chain_ids[0];
+			candidate.descriptor.relay_parent = test_state.relay_parent;
+			response_channel
+				.send(Ok(CollationFetchingResponse::Collation(candidate, pov.clone()).encode()))
+				.expect("Sending response should succeed");
+
+			let receipt = assert_candidate_backing_second(
+				&mut virtual_overseer,
+				test_state.relay_parent,
+				test_state.chain_ids[0],
+				&pov,
+			)
+			.await;
+
+			overseer_send(
+				&mut virtual_overseer,
+				CollatorProtocolMessage::Invalid(test_state.relay_parent, receipt),
+			)
+			.await;
+
+			assert_matches!(
+				overseer_recv(&mut virtual_overseer).await,
+				AllMessages::NetworkBridge(NetworkBridgeMessage::ReportPeer(_, rep)) => {
+					assert_eq!(rep, COST_REPORT_BAD);
+				}
+			);
+		}
+
+		// The advertisement above the quota was never accepted, so there is nothing left to fetch.
+		assert!(overseer_recv_with_timeout(&mut virtual_overseer, Duration::from_millis(100))
+			.await
+			.is_none());
+
+		virtual_overseer
+	})
+}
@@ -23,6 +23,11 @@
 //! to be an order of sqrt of the validators. Our neighbors
 //! in this graph will be forwarded to the network bridge with
 //! the `NetworkBridgeMessage::NewGossipTopology` message.
+//!
+//! The members of our backing group are treated as priority connections:
+//! whenever one of them is disconnected, the connection request is issued
+//! again, so that statements about the candidates of our core keep
+//! propagating directly within the group.
 
 use std::{
 	collections::{HashMap, HashSet},
@@ -52,7 +57,10 @@ use selendra_node_subsystem::{
 	SubsystemError, SubsystemSender,
 };
 use selendra_node_subsystem_util as util;
-use selendra_primitives::v1::{AuthorityDiscoveryId, Hash, SessionIndex};
+use selendra_primitives::{
+	v1::{AuthorityDiscoveryId, Hash, SessionIndex, ValidatorIndex},
+	v2::SessionInfo,
+};
 
 #[cfg(test)]
 mod tests;
@@ -104,6 +112,11 @@ pub struct GossipSupport<AD> {
 	///
 	/// Needed for efficient handling of disconnect events.
 	connected_authorities_by_peer_id: HashMap<PeerId, HashSet<AuthorityDiscoveryId>>,
+	/// The other members of our backing group in the current session.
+	backing_group: HashSet<AuthorityDiscoveryId>,
+	/// The last time we issued a connection request because of a disconnected member of our
+	/// backing group.
+	last_backing_group_request: Option<Instant>,
 	/// Authority discovery service.
 	authority_discovery: AD,
 
@@ -129,6 +142,8 @@ where
 			resolved_authorities: HashMap::new(),
 			connected_authorities: HashMap::new(),
 			connected_authorities_by_peer_id: HashMap::new(),
+			backing_group: HashSet::new(),
+			last_backing_group_request: None,
 			authority_discovery,
 			metrics,
 		}
@@ -200,7 +215,8 @@ where
 			let current_index =
 				util::request_session_index_for_child(leaf, ctx.sender()).await.await??;
 			let since_failure = self.last_failure.map(|i| i.elapsed()).unwrap_or_default();
-			let force_request = since_failure >= BACKOFF_DURATION;
+			let force_request =
+				since_failure >= BACKOFF_DURATION || self.should_reconnect_backing_group();
 			let leaf_session = Some((current_index, leaf));
 			let maybe_new_session = match self.last_session_index {
 				Some(i) if current_index <= i => None,
@@ -240,6 +256,36 @@ where
 		Ok(())
 	}
 
+	/// Whether a member of our backing group is disconnected and we didn't try to reconnect to
+	/// it recently.
+	fn should_reconnect_backing_group(&mut self) -> bool {
+		let disconnected = self
+			.backing_group
+			.iter()
+			.filter(|a| !self.connected_authorities.contains_key(a))
+			.count();
+		if disconnected == 0 {
+			return false
+		}
+
+		let since_last_request =
+			self.last_backing_group_request.map(|i| i.elapsed()).unwrap_or(BACKOFF_DURATION);
+		if since_last_request < BACKOFF_DURATION {
+			return false
+		}
+
+		tracing::debug!(
+			target: LOG_TARGET,
+			?disconnected,
+			group_size = ?self.backing_group.len(),
+			"Reconnecting to our backing group",
+		);
+		self.last_backing_group_request = Some(Instant::now());
+		true
+	}
+
+	/// Update the metrics on whether we are an authority and a parachain validator, as well as
+	/// our backing group.
 	async fn update_authority_status_metrics(
 		&mut self,
 		leaf: Hash,
@@ -275,7 +321,7 @@ where
 
 			if let Some(validator_index) = maybe_index {
 				// The subset of authorities participating in parachain consensus.
-				let parachain_validators_this_session = session_info.validators;
+				let parachain_validators_this_session = &session_info.validators;
 
 				// First `maxValidators` entries are the parachain validators. We'll check
 				// if our index is in this set to avoid searching for the keys.
@@ -285,6 +331,10 @@ where
 					self.metrics.on_is_not_parachain_validator();
 				}
 			}
+
+			self.backing_group =
+				maybe_index.map(|index| backing_group(&session_info, index)).unwrap_or_default();
+			self.last_backing_group_request = None;
 		}
 		Ok(())
 	}
@@ -412,6 +462,21 @@ where
 	}
 }
 
+/// The discovery keys of the other members of the backing group the validator at `our_index`
+/// belongs to, if any.
+fn backing_group(session_info: &SessionInfo, our_index: usize) -> HashSet<AuthorityDiscoveryId> {
+	let our_index = ValidatorIndex(our_index as u32);
+	session_info
+		.validator_groups
+		.iter()
+		.find(|group| group.contains(&our_index))
+		.into_iter()
+		.flatten()
+		.filter(|index| **index != our_index)
+		.filter_map(|index| session_info.discovery_keys.get(index.0 as usize).cloned())
+		.collect()
+}
+
 async fn determine_relevant_authorities<Context>(
 	ctx: &mut Context,
 	relay_parent: Hash,
@@ -502,6 +502,135 @@ fn issues_a_connection_request_when_last_request_was_mostly_unresolved() {
 	assert!(state.last_failure.is_none());
 }
 
+#[test]
+fn reissues_connection_request_when_backing_group_member_is_disconnected() {
+	let hash = Hash::repeat_byte(0xAA);
+	let state = make_subsystem();
+
+	let state = test_harness(state, |mut virtual_overseer| async move {
+		let overseer = &mut virtual_overseer;
+		overseer_signal_active_leaves(overseer, hash).await;
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+				relay_parent,
+				RuntimeApiRequest::SessionIndexForChild(tx),
+			)) => {
+				assert_eq!(relay_parent, hash);
+				tx.send(Ok(1)).unwrap();
+			}
+		);
+
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+				relay_parent,
+				RuntimeApiRequest::Authorities(tx),
+			)) => {
+				assert_eq!(relay_parent, hash);
+				tx.send(Ok(AUTHORITIES.clone())).unwrap();
+			}
+		);
+
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::NetworkBridge(NetworkBridgeMessage::ConnectToResolvedValidators {
+				peer_set,
+				..
+			}) => {
+				assert_eq!(peer_set, PeerSet::Validation);
+			}
+		);
+
+		test_neighbors(overseer).await;
+
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+				relay_parent,
+				RuntimeApiRequest::SessionInfo(1, sender),
+			)) => {
+				assert_eq!(relay_parent, hash);
+				// Ferdie is the last authority and backs together with One and Two.
+				let session_info = SessionInfo {
+					active_validator_indices: Vec::new(),
+					random_seed: [0u8; 32],
+					dispute_period: 6,
+					validators: AUTHORITIES.iter().map(|_| Sr25519Keyring::Ferdie.public().into()).collect(),
+					discovery_keys: AUTHORITIES.clone(),
+					assignment_keys: Vec::new(),
+					validator_groups: vec![
+						vec![ValidatorIndex(0), ValidatorIndex(1)],
+						vec![ValidatorIndex(2), ValidatorIndex(3)],
+						vec![ValidatorIndex(4), ValidatorIndex(5), ValidatorIndex(6)],
+					],
+					n_cores: 3,
+					zeroth_delay_tranche_width: 0,
+					relay_vrf_modulo_samples: 0,
+					n_delay_tranches: 0,
+					no_show_slots: 0,
+					needed_approvals: 0,
+				};
+				sender.send(Ok(Some(session_info))).unwrap();
+			}
+		);
+
+		virtual_overseer
+	});
+
+	let expected: HashSet<AuthorityDiscoveryId> =
+		[Sr25519Keyring::One.public().into(), Sr25519Keyring::Two.public().into()]
+			.into_iter()
+			.collect();
+	assert_eq!(state.backing_group, expected);
+	assert!(state.last_failure.is_none());
+	assert!(state.last_backing_group_request.is_none());
+
+	// Neither One nor Two connected, so the request is issued again within the same session.
+	let hash = Hash::repeat_byte(0xBB);
+	let state = test_harness(state, |mut virtual_overseer| async move {
+		let overseer = &mut virtual_overseer;
+		overseer_signal_active_leaves(overseer, hash).await;
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+				relay_parent,
+				RuntimeApiRequest::SessionIndexForChild(tx),
+			)) => {
+				assert_eq!(relay_parent, hash);
+				tx.send(Ok(1)).unwrap();
+			}
+		);
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+				relay_parent,
+				RuntimeApiRequest::Authorities(tx),
+			)) => {
+				assert_eq!(relay_parent, hash);
+				tx.send(Ok(AUTHORITIES.clone())).unwrap();
+			}
+		);
+
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::NetworkBridge(NetworkBridgeMessage::ConnectToResolvedValidators {
+				validator_addrs,
+				peer_set,
+			}) => {
+				let expected: HashSet<Multiaddr> = get_other_authorities_addrs().await.into_iter().flatten().collect();
+				assert_eq!(validator_addrs.into_iter().flatten().collect::<HashSet<_>>(), expected);
+				assert_eq!(peer_set, PeerSet::Validation);
+			}
+		);
+
+		virtual_overseer
+	});
+
+	assert_eq!(state.last_session_index, Some(1));
+	assert!(state.last_backing_group_request.is_some());
+}
+
 #[test]
 fn test_matrix_neighbors() {
 	for (our_index, len, expected) in vec![
@@ -16,13 +16,14 @@
 
 //! Requests and responses as sent over the wire for the individual protocols.
 
-use parity_scale_codec::{Decode, Encode};
+use parity_scale_codec::{Decode, Encode, Error as CodecError, Input};
 
 use selendra_node_primitives::{
 	AvailableData, DisputeMessage, ErasureChunk, PoV, Proof, UncheckedDisputeMessage,
 };
 use selendra_primitives::v1::{
-	CandidateHash, CandidateReceipt, CommittedCandidateReceipt, Hash, Id as ParaId, ValidatorIndex,
+	CandidateHash, CandidateReceipt, CommitmentsLimits, CommittedCandidateReceipt, Hash,
+	Id as ParaId, ValidatorIndex,
 };
 
 use super::{IsRequest, Protocol};
@@ -179,13 +180,25 @@ pub struct StatementFetchingRequest {
 /// In this protocol the requester will only request data it was previously notified about,
 /// therefore not having the data is not really an option and would just result in a
 /// `RequestFailure`.
-#[derive(Debug, Clone, Encode, Decode)]
+#[derive(Debug, Clone, Encode)]
 pub enum StatementFetchingResponse {
 	/// Data missing to reconstruct the full signed statement.
 	#[codec(index = 0)]
 	Statement(CommittedCandidateReceipt),
 }
 
+// The receipt comes from an arbitrary peer and is handed on to backing, so its commitments are
+// bounded already while decoding.
+impl Decode for StatementFetchingResponse {
+	fn decode<I: Input>(input: &mut I) -> Result<Self, CodecError> {
+		match input.read_byte()? {
+			0 => CommittedCandidateReceipt::decode_bounded(input, &CommitmentsLimits::default())
+				.map(Self::Statement),
+			_ => Err("Invalid StatementFetchingResponse variant".into()),
+		}
+	}
+}
+
 impl IsRequest for StatementFetchingRequest {
 	type Response = StatementFetchingResponse;
 	const PROTOCOL: Protocol = Protocol::StatementFetching;
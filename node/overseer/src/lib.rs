@@ -68,7 +68,12 @@ use std::{
 	time::Duration,
 };
 
-use futures::{channel::oneshot, future::BoxFuture, select, Future, FutureExt, StreamExt};
+use futures::{
+	channel::oneshot,
+	future::{BoxFuture, Fuse},
+	select, Future, FutureExt, StreamExt,
+};
+use futures_timer::Delay;
 use lru::LruCache;
 
 use client::{BlockImportNotification, BlockchainEvents, FinalityNotification};
@@ -200,6 +205,14 @@ impl Handle {
 		self.send_and_log_error(Event::Stop).await;
 	}
 
+	/// Tell `Overseer` to shutdown once the work on the current leaves had the given time to
+	/// complete.
+	///
+	/// No new leaves are activated in the meantime.
+	pub async fn stop_gracefully(&mut self, grace_period: Duration) {
+		self.send_and_log_error(Event::StopGracefully(grace_period)).await;
+	}
+
 	/// Most basic operation, to stop a server.
 	async fn send_and_log_error(&mut self, event: Event) {
 		if self.0.send(event).await.is_err() {
@@ -254,6 +267,8 @@ pub enum Event {
 	ExternalRequest(ExternalRequest),
 	/// Stop the overseer on i.e. a UNIX signal.
 	Stop,
+	/// Stop activating new leaves, and stop the overseer after the given grace period.
+	StopGracefully(Duration),
 }
 
 /// Some request from outer world.
@@ -604,6 +619,10 @@ where
 			}
 		}
 
+		// Elapses at the end of the grace period of a graceful stop.
+		let mut grace_period_end: Fuse<Delay> = Fuse::terminated();
+		let mut stopping = false;
+
 		loop {
 			select! {
 				msg = self.events_rx.select_next_some() => {
@@ -616,8 +635,22 @@ where
 							self.stop().await;
 							return Ok(());
 						}
+						Event::StopGracefully(grace_period) => {
+							if !stopping {
+								tracing::info!(
+									target: LOG_TARGET,
+									?grace_period,
+									"Stopping once the work on the current leaves is done",
+								);
+								stopping = true;
+								grace_period_end = Delay::new(grace_period).fuse();
+							}
+						}
 						Event::BlockImported(block) => {
-							self.block_imported(block).await?;
+							// New leaves would only start work which can't be completed anymore.
+							if !stopping {
+								self.block_imported(block).await?;
+							}
 						}
 						Event::BlockFinalized(block) => {
 							self.block_finalized(block).await?;
@@ -637,6 +670,10 @@ where
 						}
 					}
 				},
+				_ = grace_period_end => {
+					self.stop().await;
+					return Ok(());
+				},
 				res = self.running_subsystems.select_next_some() => {
 					tracing::error!(
 						target: LOG_TARGET,
@@ -476,6 +476,63 @@ fn overseer_start_stop_works() {
 	});
 }
 
+// Tests that a graceful stop doesn't activate new leaves, and stops the overseer once the grace
+// period elapsed.
+#[test]
+fn overseer_stops_gracefully() {
+	let spawner = sp_core::testing::TaskExecutor::new();
+
+	executor::block_on(async move {
+		let first_block_hash = [1; 32].into();
+		let second_block_hash = [2; 32].into();
+
+		let first_block =
+			BlockInfo { hash: first_block_hash, parent_hash: [0; 32].into(), number: 1 };
+		let second_block =
+			BlockInfo { hash: second_block_hash, parent_hash: first_block_hash, number: 2 };
+
+		let (tx_5, mut rx_5) = metered::channel(64);
+
+		let (overseer, handle) = dummy_overseer_builder(spawner, MockSupportsParachains, None)
+			.unwrap()
+			.replace_candidate_validation(move |_| TestSubsystem5(tx_5))
+			.leaves(block_info_to_pair(vec![first_block]))
+			.build()
+			.unwrap();
+		let mut handle = Handle::new(handle);
+
+		let overseer_fut = overseer.run().fuse();
+		pin_mut!(overseer_fut);
+
+		handle.stop_gracefully(Duration::from_millis(50)).await;
+		handle.block_imported(second_block).await;
+
+		// runs until the overseer stopped and the subsystem concluded.
+		let mut ss5_results = Vec::new();
+		loop {
+			select! {
+				res = overseer_fut => assert!(res.is_ok()),
+				res = rx_5.next() => {
+					if let Some(res) = res {
+						ss5_results.push(res);
+					}
+				}
+				complete => break,
+			}
+		}
+
+		assert_eq!(
+			ss5_results,
+			vec![OverseerSignal::ActiveLeaves(ActiveLeavesUpdate::start_work(ActivatedLeaf {
+				hash: first_block_hash,
+				number: 1,
+				span: Arc::new(jaeger::Span::Disabled),
+				status: LeafStatus::Fresh,
+			}))],
+		);
+	});
+}
+
 // Tests that starting with a defined set of leaves and receiving
 // notifications on imported blocks triggers expected `StartWork` and `StopWork` heartbeats.
 #[test]
@@ -0,0 +1,35 @@
+// Copyright 2020-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use parity_scale_codec::Encode;
+use selendra_node_primitives::{BlockData, PoV};
+
+const POV_SIZE: usize = 5 * 1024 * 1024;
+
+fn pov_hashing(c: &mut Criterion) {
+	let pov = PoV { block_data: BlockData((0..POV_SIZE).map(|i| i as u8).collect()) };
+	assert_eq!(pov.hash_and_encoded_size(), (pov.hash(), pov.encoded_size()));
+
+	let mut group = c.benchmark_group("pov_hashing");
+	group.throughput(Throughput::Bytes(POV_SIZE as u64));
+	group.bench_function("hash_then_encoded_size", |b| b.iter(|| (pov.hash(), pov.encoded_size())));
+	group.bench_function("hash_and_encoded_size", |b| b.iter(|| pov.hash_and_encoded_size()));
+	group.finish();
+}
+
+criterion_group!(benches, pov_hashing);
+criterion_main!(benches);
@@ -26,7 +26,7 @@ use std::{convert::TryFrom, pin::Pin, time::Duration};
 
 use bounded_vec::BoundedVec;
 use futures::Future;
-use parity_scale_codec::{Decode, Encode, Error as CodecError, Input};
+use parity_scale_codec::{Decode, Encode, Error as CodecError, Input, Output};
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 
 pub use sp_consensus_babe::{
@@ -255,6 +255,29 @@ impl PoV {
 	pub fn hash(&self) -> Hash {
 		BlakeTwo256::hash_of(self)
 	}
+
+	/// Get the blake2-256 hash of the PoV together with the size of its encoding.
+	///
+	/// The encoding is fed to the hasher as it is produced, so unlike calling [`PoV::hash`] and
+	/// `encoded_size` this neither copies the block data into a new buffer nor walks it twice.
+	pub fn hash_and_encoded_size(&self) -> (Hash, usize) {
+		let mut output = HashingOutput { hasher: blake2_rfc::blake2b::Blake2b::new(32), size: 0 };
+		self.encode_to(&mut output);
+		(Hash::from_slice(output.hasher.finalize().as_bytes()), output.size)
+	}
+}
+
+/// An encoding output which hashes the bytes written to it and counts them.
+struct HashingOutput {
+	hasher: blake2_rfc::blake2b::Blake2b,
+	size: usize,
+}
+
+impl Output for HashingOutput {
+	fn write(&mut self, bytes: &[u8]) {
+		self.hasher.update(bytes);
+		self.size += bytes.len();
+	}
 }
 
 /// A type that represents a maybe compressed [`PoV`].
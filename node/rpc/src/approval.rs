@@ -0,0 +1,128 @@
+// Copyright 2019-2020 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! RPC for inspecting the state of approval voting.
+
+use futures::{channel::oneshot, FutureExt};
+use jsonrpc_core::{BoxFuture, Error as RpcError, ErrorCode, Result as RpcResult};
+use jsonrpc_derive::rpc;
+use sc_rpc::DenyUnsafe;
+use selendra_node_subsystem_types::messages::{self, ApprovalVotingMessage};
+use selendra_overseer::Handle;
+use selendra_primitives::v1::Hash;
+use serde::Serialize;
+
+/// The approval state of a candidate under a block.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CandidateApprovalStatus {
+	/// The hash of the candidate.
+	pub candidate_hash: Hash,
+	/// Whether the candidate is approved under the block.
+	pub approved: bool,
+	/// The indices of the validators which approved the candidate.
+	pub approvals: Vec<u32>,
+	/// The assignments to check the candidate, by delay tranche.
+	pub tranches: Vec<TrancheAssignments>,
+	/// The number of assigned validators which didn't approve the candidate in time.
+	pub no_shows: u32,
+}
+
+/// The validators assigned to check a candidate in a delay tranche.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrancheAssignments {
+	/// The delay tranche.
+	pub tranche: u32,
+	/// The indices of the assigned validators.
+	pub validators: Vec<u32>,
+}
+
+impl From<messages::CandidateApprovalStatus> for CandidateApprovalStatus {
+	fn from(status: messages::CandidateApprovalStatus) -> Self {
+		CandidateApprovalStatus {
+			candidate_hash: status.candidate_hash.0,
+			approved: status.approved,
+			approvals: status.approvals.into_iter().map(|v| v.0).collect(),
+			tranches: status
+				.tranches
+				.into_iter()
+				.map(|(tranche, validators)| TrancheAssignments {
+					tranche,
+					validators: validators.into_iter().map(|v| v.0).collect(),
+				})
+				.collect(),
+			no_shows: status.no_shows as u32,
+		}
+	}
+}
+
+/// Approval voting inspection RPC methods.
+#[rpc]
+pub trait ApprovalApi {
+	/// Get the approval state of all candidates included in the given block.
+	///
+	/// Returns `null` if the block is unknown to approval voting, e.g. because it is already
+	/// finalized. This is an unsafe method, as every call is answered by the approval voting
+	/// subsystem itself.
+	#[rpc(name = "selendra_approvalStatus")]
+	fn approval_status(
+		&self,
+		block_hash: Hash,
+	) -> BoxFuture<RpcResult<Option<Vec<CandidateApprovalStatus>>>>;
+}
+
+/// Implements the [`ApprovalApi`] by querying the approval voting subsystem.
+pub struct Approval {
+	overseer_handle: Handle,
+	deny_unsafe: DenyUnsafe,
+}
+
+impl Approval {
+	/// Create a new approval RPC handler sending its queries through the given overseer handle.
+	pub fn new(overseer_handle: Handle, deny_unsafe: DenyUnsafe) -> Self {
+		Approval { overseer_handle, deny_unsafe }
+	}
+}
+
+impl ApprovalApi for Approval {
+	fn approval_status(
+		&self,
+		block_hash: Hash,
+	) -> BoxFuture<RpcResult<Option<Vec<CandidateApprovalStatus>>>> {
+		if let Err(e) = self.deny_unsafe.check_if_safe() {
+			return async move { Err(e.into()) }.boxed()
+		}
+
+		let mut overseer_handle = self.overseer_handle.clone();
+
+		async move {
+			let (tx, rx) = oneshot::channel();
+			overseer_handle
+				.send_msg(ApprovalVotingMessage::GetApprovalStatus(block_hash, tx), "approval-rpc")
+				.await;
+
+			let statuses = rx.await.map_err(|_| RpcError {
+				code: ErrorCode::InternalError,
+				message: "Approval voting is unavailable".into(),
+				data: None,
+			})?;
+
+			Ok(statuses.map(|s| s.into_iter().map(Into::into).collect()))
+		}
+		.boxed()
+	}
+}
@@ -0,0 +1,398 @@
+// Copyright 2019-2020 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! RPC for submitting and following governance proposals, e.g. runtime upgrades.
+//!
+//! A proposal takes several extrinsics, noting the preimage of the call and proposing its hash
+//! to democracy or the council, and then moves through several pallets before it is enacted. The
+//! node cannot sign for the proposer, so the extrinsics are signed by the caller and submitted
+//! here in order, while the progress of the proposal is followed by watching the democracy
+//! storage on every new best block.
+
+use std::sync::Arc;
+
+use futures::{future, stream, FutureExt, SinkExt, StreamExt, TryFutureExt};
+use jsonrpc_core::{BoxFuture, Error as RpcError, ErrorCode, Result as RpcResult};
+use jsonrpc_derive::rpc;
+use jsonrpc_pubsub::{manager::SubscriptionManager, typed::Subscriber, SubscriptionId};
+use parity_scale_codec::{Decode, Encode};
+use sc_client_api::{BlockchainEvents, StorageProvider};
+use sc_rpc::SubscriptionTaskExecutor;
+use selendra_primitives::v0::{AccountId, Block, BlockNumber, Hash};
+use serde::Serialize;
+use sp_blockchain::HeaderBackend;
+use sp_core::{
+	hashing::{blake2_256, twox_128, twox_64},
+	storage::StorageKey,
+	Bytes,
+};
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+use txpool_api::{TransactionPool, TransactionSource};
+
+/// The pallet the proposals go through, as named by the runtimes.
+const DEMOCRACY: &[u8] = b"Democracy";
+
+/// The progress of a proposal, as seen at the best block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase", tag = "status")]
+pub enum ProposalStatus {
+	/// Nothing on chain refers to the proposal.
+	Unknown,
+	/// The preimage of the proposal is noted, but it isn't proposed yet.
+	PreimageNoted,
+	/// The proposal was put forward by the council and is up for the next external referendum.
+	External,
+	/// The proposal is a public proposal waiting for a referendum.
+	Proposed {
+		/// The index of the public proposal.
+		index: u32,
+	},
+	/// The proposal is being voted on.
+	Referendum {
+		/// The index of the referendum.
+		index: u32,
+	},
+	/// The referendum on the proposal passed, the proposal is enacted after the delay.
+	Approved {
+		/// The index of the referendum.
+		index: u32,
+	},
+	/// The referendum on the proposal failed.
+	Rejected {
+		/// The index of the referendum.
+		index: u32,
+	},
+}
+
+/// Governance RPC methods.
+#[rpc]
+pub trait GovernanceApi {
+	/// RPC metadata
+	type Metadata;
+
+	/// The hash the given SCALE-encoded call is proposed and noted as a preimage under.
+	#[rpc(name = "selendra_governance_proposalHash")]
+	fn proposal_hash(&self, call: Bytes) -> RpcResult<Hash>;
+
+	/// Submit the signed extrinsics of a proposal, e.g. noting the preimage and proposing it.
+	///
+	/// The extrinsics are submitted in the given order, stopping at the first one the transaction
+	/// pool rejects. Returns the hashes of the submitted extrinsics.
+	#[rpc(name = "selendra_governance_submitProposal")]
+	fn submit_proposal(&self, extrinsics: Vec<Bytes>) -> BoxFuture<RpcResult<Vec<Hash>>>;
+
+	/// Follow the status of the proposal with the given hash.
+	///
+	/// The current status is sent right away, every change of it at a new best block after.
+	#[pubsub(
+		subscription = "selendra_governance_proposalStatus",
+		subscribe,
+		name = "selendra_governance_subscribeProposalStatus"
+	)]
+	fn subscribe_proposal_status(
+		&self,
+		metadata: Self::Metadata,
+		subscriber: Subscriber<ProposalStatus>,
+		proposal_hash: Hash,
+	);
+
+	/// Stop following the status of a proposal.
+	#[pubsub(
+		subscription = "selendra_governance_proposalStatus",
+		unsubscribe,
+		name = "selendra_governance_unsubscribeProposalStatus"
+	)]
+	fn unsubscribe_proposal_status(
+		&self,
+		metadata: Option<Self::Metadata>,
+		id: SubscriptionId,
+	) -> RpcResult<bool>;
+}
+
+/// Implements the [`GovernanceApi`] on top of the client and the transaction pool.
+pub struct Governance<C, P, B> {
+	client: Arc<C>,
+	pool: Arc<P>,
+	manager: SubscriptionManager,
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, P, B> Governance<C, P, B> {
+	/// Create a new governance RPC handler.
+	pub fn new(client: Arc<C>, pool: Arc<P>, executor: SubscriptionTaskExecutor) -> Self {
+		Governance {
+			client,
+			pool,
+			manager: SubscriptionManager::new(Arc::new(executor)),
+			_marker: Default::default(),
+		}
+	}
+}
+
+impl<C, P, B> GovernanceApi for Governance<C, P, B>
+where
+	C: HeaderBackend<Block>
+		+ BlockchainEvents<Block>
+		+ StorageProvider<Block, B>
+		+ Send
+		+ Sync
+		+ 'static,
+	P: TransactionPool<Block = Block, Hash = Hash> + Send + Sync + 'static,
+	B: sc_client_api::Backend<Block> + Send + Sync + 'static,
+{
+	type Metadata = sc_rpc::Metadata;
+
+	fn proposal_hash(&self, call: Bytes) -> RpcResult<Hash> {
+		Ok(blake2_256(&call).into())
+	}
+
+	fn submit_proposal(&self, extrinsics: Vec<Bytes>) -> BoxFuture<RpcResult<Vec<Hash>>> {
+		let extrinsics = match extrinsics
+			.iter()
+			.map(|xt| <Block as BlockT>::Extrinsic::decode(&mut &xt[..]))
+			.collect::<Result<Vec<_>, _>>()
+		{
+			Ok(extrinsics) => extrinsics,
+			Err(e) => return future::err(invalid_params(format!("Bad extrinsic: {}", e))).boxed(),
+		};
+
+		let best = BlockId::hash(self.client.info().best_hash);
+		let pool = self.pool.clone();
+
+		async move {
+			let mut hashes = Vec::with_capacity(extrinsics.len());
+			for xt in extrinsics {
+				let hash = pool
+					.submit_one(&best, TransactionSource::External, xt)
+					.map_err(|e| RpcError {
+						code: ErrorCode::ServerError(1),
+						message: format!("Extrinsic {} was rejected: {}", hashes.len(), e),
+						data: None,
+					})
+					.await?;
+				hashes.push(hash);
+			}
+			Ok(hashes)
+		}
+		.boxed()
+	}
+
+	fn subscribe_proposal_status(
+		&self,
+		_metadata: Self::Metadata,
+		subscriber: Subscriber<ProposalStatus>,
+		proposal_hash: Hash,
+	) {
+		let client = self.client.clone();
+		let mut watch = ProposalWatch::new(proposal_hash);
+		let initial = watch.update(&*client, self.client.info().best_hash);
+
+		let updates = self
+			.client
+			.import_notification_stream()
+			.filter(|notification| future::ready(notification.is_new_best))
+			.filter_map(move |notification| {
+				future::ready(watch.update(&*client, notification.hash))
+			});
+
+		self.manager.add(subscriber, |sink| {
+			stream::iter(initial)
+				.chain(updates)
+				.map(|status| Ok::<_, ()>(Ok(status)))
+				.forward(sink.sink_map_err(|e| {
+					log::warn!("Error sending governance proposal status: {:?}", e)
+				}))
+				.map(drop)
+		});
+	}
+
+	fn unsubscribe_proposal_status(
+		&self,
+		_metadata: Option<Self::Metadata>,
+		id: SubscriptionId,
+	) -> RpcResult<bool> {
+		Ok(self.manager.cancel(id))
+	}
+}
+
+/// Tracks the status of a proposal across blocks.
+struct ProposalWatch {
+	proposal_hash: Hash,
+	last: Option<ProposalStatus>,
+	referendum: Option<u32>,
+}
+
+impl ProposalWatch {
+	fn new(proposal_hash: Hash) -> Self {
+		ProposalWatch { proposal_hash, last: None, referendum: None }
+	}
+
+	/// The status at the given block, if it changed since the last update.
+	fn update<B: sc_client_api::Backend<Block>>(
+		&mut self,
+		client: &impl StorageProvider<Block, B>,
+		at: Hash,
+	) -> Option<ProposalStatus> {
+		let status = match self.status(client, &BlockId::hash(at)) {
+			Ok(status) => status,
+			Err(e) => {
+				log::debug!(
+					"Failed to read the status of proposal {:?}: {}",
+					self.proposal_hash,
+					e
+				);
+				return None
+			},
+		};
+
+		if self.last == Some(status) {
+			return None
+		}
+		self.last = Some(status);
+		Some(status)
+	}
+
+	fn status<B: sc_client_api::Backend<Block>>(
+		&mut self,
+		client: &impl StorageProvider<Block, B>,
+		at: &BlockId<Block>,
+	) -> sp_blockchain::Result<ProposalStatus> {
+		let read = |key: StorageKey| client.storage(at, &key).map(|data| data.map(|d| d.0));
+
+		// Finished referendums don't name their proposal anymore, so remember where it went.
+		if let Some(index) = self.referendum {
+			match read(referendum_key(index))?.as_deref().map(decode_referendum) {
+				Some(Some(Referendum::Ongoing(hash))) if hash == self.proposal_hash =>
+					return Ok(ProposalStatus::Referendum { index }),
+				Some(Some(Referendum::Finished(true))) =>
+					return Ok(ProposalStatus::Approved { index }),
+				Some(Some(Referendum::Finished(false))) =>
+					return Ok(ProposalStatus::Rejected { index }),
+				_ => self.referendum = None,
+			}
+		}
+
+		let lowest_unbaked: u32 = read_value(&read, value_key(b"LowestUnbaked"))?.unwrap_or(0);
+		let referendum_count: u32 = read_value(&read, value_key(b"ReferendumCount"))?.unwrap_or(0);
+		for index in lowest_unbaked..referendum_count {
+			if let Some(Some(Referendum::Ongoing(hash))) =
+				read(referendum_key(index))?.as_deref().map(decode_referendum)
+			{
+				if hash == self.proposal_hash {
+					self.referendum = Some(index);
+					return Ok(ProposalStatus::Referendum { index })
+				}
+			}
+		}
+
+		let public_props: Vec<(u32, Hash, AccountId)> =
+			read_value(&read, value_key(b"PublicProps"))?.unwrap_or_default();
+		if let Some((index, _, _)) =
+			public_props.into_iter().find(|(_, hash, _)| *hash == self.proposal_hash)
+		{
+			return Ok(ProposalStatus::Proposed { index })
+		}
+
+		// `Option<(Hash, VoteThreshold)>`, of which only the hash matters.
+		let next_external: Option<Hash> =
+			read(value_key(b"NextExternal"))?.and_then(|data| Hash::decode(&mut &data[..]).ok());
+		if next_external == Some(self.proposal_hash) {
+			return Ok(ProposalStatus::External)
+		}
+
+		let mut preimage_key = value_key(b"Preimages").0;
+		preimage_key.extend(self.proposal_hash.as_bytes());
+		if read(StorageKey(preimage_key))?.is_some() {
+			return Ok(ProposalStatus::PreimageNoted)
+		}
+
+		Ok(ProposalStatus::Unknown)
+	}
+}
+
+/// The part of a democracy `ReferendumInfo` needed to follow a proposal.
+enum Referendum {
+	/// The referendum on the proposal with the given hash is ongoing.
+	Ongoing(Hash),
+	/// The referendum finished, the proposal was approved or not.
+	Finished(bool),
+}
+
+/// Decode the leading fields of an encoded `ReferendumInfo`.
+fn decode_referendum(mut data: &[u8]) -> Option<Referendum> {
+	match u8::decode(&mut data).ok()? {
+		0 => {
+			let (_end, hash) = <(BlockNumber, Hash)>::decode(&mut data).ok()?;
+			Some(Referendum::Ongoing(hash))
+		},
+		1 => bool::decode(&mut data).ok().map(Referendum::Finished),
+		_ => None,
+	}
+}
+
+fn read_value<T: Decode>(
+	read: &impl Fn(StorageKey) -> sp_blockchain::Result<Option<Vec<u8>>>,
+	key: StorageKey,
+) -> sp_blockchain::Result<Option<T>> {
+	Ok(read(key)?.and_then(|data| T::decode(&mut &data[..]).ok()))
+}
+
+/// The key of a democracy storage value, or the prefix of a democracy storage map.
+fn value_key(item: &[u8]) -> StorageKey {
+	StorageKey([twox_128(DEMOCRACY), twox_128(item)].concat())
+}
+
+/// The key of `ReferendumInfoOf`, whose keys are hashed with `Twox64Concat`.
+fn referendum_key(index: u32) -> StorageKey {
+	let encoded = index.encode();
+	let mut key = value_key(b"ReferendumInfoOf").0;
+	key.extend(twox_64(&encoded));
+	key.extend(encoded);
+	StorageKey(key)
+}
+
+fn invalid_params(message: String) -> RpcError {
+	RpcError { code: ErrorCode::InvalidParams, message, data: None }
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn decodes_referendum_info_prefix() {
+		let hash = Hash::repeat_byte(1);
+
+		// `ReferendumInfo::Ongoing` with an `end`, the proposal hash and more we don't decode.
+		let mut ongoing = vec![0u8];
+		ongoing.extend((10 as BlockNumber, hash, 0u8, 5 as BlockNumber).encode());
+		assert!(matches!(decode_referendum(&ongoing), Some(Referendum::Ongoing(h)) if h == hash));
+
+		// `ReferendumInfo::Finished { approved, end }`.
+		let finished = (1u8, true, 10 as BlockNumber).encode();
+		assert!(matches!(decode_referendum(&finished), Some(Referendum::Finished(true))));
+
+		assert!(decode_referendum(&[2u8]).is_none());
+	}
+
+	#[test]
+	fn referendum_keys_are_twox_64_concat() {
+		let key = referendum_key(3).0;
+		assert_eq!(key.len(), 32 + 8 + 4);
+		assert_eq!(&key[..16], &twox_128(b"Democracy"));
+		assert_eq!(&key[40..], &3u32.encode()[..]);
+	}
+}
@@ -20,7 +20,7 @@
 
 use std::sync::Arc;
 
-use sc_client_api::AuxStore;
+use sc_client_api::{AuxStore, BlockchainEvents, StorageProvider};
 use sc_consensus_babe::Epoch;
 use sc_finality_grandpa::FinalityProofProvider;
 pub use sc_rpc::{DenyUnsafe, SubscriptionTaskExecutor};
@@ -34,6 +34,12 @@ use sp_consensus_babe::BabeApi;
 use sp_keystore::SyncCryptoStorePtr;
 use txpool_api::TransactionPool;
 
+mod approval;
+mod governance;
+
+pub use approval::{Approval, ApprovalApi, CandidateApprovalStatus, TrancheAssignments};
+pub use governance::{Governance, GovernanceApi, ProposalStatus};
+
 /// A type representing all RPC extensions.
 pub type RpcExtension = jsonrpc_core::IoHandler<sc_rpc::Metadata>;
 
@@ -90,6 +96,10 @@ pub struct FullDeps<C, P, SC, B> {
 	pub grandpa: GrandpaDeps<B>,
 	/// BEEFY specific dependencies.
 	pub beefy: BeefyDeps,
+	/// A handle to the overseer, used to inspect the approval voting subsystem.
+	///
+	/// The approval RPC is only exposed if this is set.
+	pub overseer_handle: Option<selendra_overseer::Handle>,
 }
 
 /// Instantiate all RPC extensions.
@@ -101,6 +111,8 @@ where
 		+ HeaderBackend<Block>
 		+ AuxStore
 		+ HeaderMetadata<Block, Error = BlockChainError>
+		+ BlockchainEvents<Block>
+		+ StorageProvider<Block, B>
 		+ Send
 		+ Sync
 		+ 'static,
@@ -109,7 +121,7 @@ where
 	C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
 	C::Api: BabeApi<Block>,
 	C::Api: BlockBuilder<Block>,
-	P: TransactionPool + Sync + Send + 'static,
+	P: TransactionPool<Block = Block, Hash = Hash> + Sync + Send + 'static,
 	SC: SelectChain<Block> + 'static,
 	B: sc_client_api::Backend<Block> + Send + Sync + 'static,
 	B::State: sc_client_api::StateBackend<sp_runtime::traits::HashFor<Block>>,
@@ -121,8 +133,17 @@ where
 	use sc_finality_grandpa_rpc::{GrandpaApi, GrandpaRpcHandler};
 
 	let mut io = jsonrpc_core::IoHandler::default();
-	let FullDeps { client, pool, select_chain, chain_spec, deny_unsafe, babe, grandpa, beefy } =
-		deps;
+	let FullDeps {
+		client,
+		pool,
+		select_chain,
+		chain_spec,
+		deny_unsafe,
+		babe,
+		grandpa,
+		beefy,
+		overseer_handle,
+	} = deps;
 	let BabeDeps { keystore, babe_config, shared_epoch_changes } = babe;
 	let GrandpaDeps {
 		shared_voter_state,
@@ -132,6 +153,11 @@ where
 		finality_provider,
 	} = grandpa;
 
+	io.extend_with(GovernanceApi::to_delegate(Governance::new(
+		client.clone(),
+		pool.clone(),
+		subscription_executor.clone(),
+	)));
 	io.extend_with(SystemApi::to_delegate(FullSystem::new(client.clone(), pool, deny_unsafe)));
 	io.extend_with(TransactionPaymentApi::to_delegate(TransactionPayment::new(client.clone())));
 	io.extend_with(MmrApi::to_delegate(Mmr::new(client.clone())));
@@ -164,5 +190,9 @@ where
 	)?;
 	io.extend_with(beefy_gadget_rpc::BeefyApi::to_delegate(handler));
 
+	if let Some(overseer_handle) = overseer_handle {
+		io.extend_with(ApprovalApi::to_delegate(Approval::new(overseer_handle, deny_unsafe)));
+	}
+
 	Ok(io)
 }
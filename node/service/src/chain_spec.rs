@@ -218,6 +218,17 @@ fn selendra_session_keys(
 	}
 }
 
+#[cfg(feature = "selendra-native")]
+fn selendra_transaction_storage_config() -> selendra::TransactionStorageConfig {
+	use sp_core::Get;
+
+	selendra::TransactionStorageConfig {
+		byte_fee: selendra::TransactionStorageByteFee::get(),
+		entry_fee: selendra::TransactionStorageEntryFee::get(),
+		storage_period: selendra::TransactionStoragePeriod::get(),
+	}
+}
+
 #[cfg(feature = "selendra-native")]
 fn selendra_staging_testnet_config_genesis(wasm_binary: &[u8]) -> selendra::GenesisConfig {
 	use hex_literal::hex;
@@ -405,6 +416,7 @@ fn selendra_staging_testnet_config_genesis(wasm_binary: &[u8]) -> selendra::Gene
 		},
 		paras: Default::default(),
 		xcm_pallet: Default::default(),
+		transaction_storage: selendra_transaction_storage_config(),
 	}
 }
 
@@ -520,6 +532,7 @@ pub fn selendra_testnet_genesis(
 		},
 		paras: Default::default(),
 		xcm_pallet: Default::default(),
+		transaction_storage: selendra_transaction_storage_config(),
 	}
 }
 
@@ -600,6 +613,322 @@ pub fn selendra_local_testnet_config() -> Result<SelendraChainSpec, String> {
 	))
 }
 
+/// The session keys of an initial validator, in a [`ChainSpecBuilder`].
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct AuthorityKeys {
+	pub stash: AccountId,
+	pub controller: AccountId,
+	pub babe: BabeId,
+	pub grandpa: GrandpaId,
+	pub im_online: ImOnlineId,
+	pub para_validator: ValidatorId,
+	pub para_assignment: AssignmentId,
+	pub authority_discovery: AuthorityDiscoveryId,
+}
+
+/// An account endowed at genesis, in a [`ChainSpecBuilder`].
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct EndowedAccount {
+	pub account: AccountId,
+	/// The free balance of the account, in whole tokens.
+	pub balance: u64,
+}
+
+/// The description of a custom network, to build its chain spec from.
+///
+/// Lets operators spin up their own networks without recompiling the node. The description is
+/// read from a TOML file or from a JSON file with the `.network.json` suffix, for example:
+///
+/// ```toml
+/// name = "My Testnet"
+/// id = "my_testnet"
+/// chainType = "Live"
+/// validatorStake = 31416
+/// council = ["5FL2rGw6SRFNCLADcdmerz2wSwUDCghsLztprnkrXq1AARw5"]
+///
+/// [[authorities]]
+/// stash = "5Ggb7sQMr59VuPBswCBvdrUog5qKs6X5tfHfG7vvNNH7RHrX"
+/// controller = "5ENSsELpir3F6EzFtqmNa8aiLsvsXe5Kf2qh3XJVLSMi8BAd"
+/// babe = "5HH6CY44d9oy3q3WstHAHzazS64ShHuW4yR9kf7qYrWA3yFv"
+/// # ... the other session keys
+///
+/// [[endowedAccounts]]
+/// account = "5FL2rGw6SRFNCLADcdmerz2wSwUDCghsLztprnkrXq1AARw5"
+/// balance = 1000000
+/// ```
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ChainSpecBuilder {
+	/// The human readable name of the network.
+	pub name: String,
+	/// The identifier of the network, which also names its database directory.
+	pub id: String,
+	#[serde(default)]
+	pub chain_type: ChainType,
+	/// The stake bonded by each initial validator, in whole tokens.
+	pub validator_stake: u64,
+	/// The initial members of the council.
+	#[serde(default)]
+	pub council: Vec<AccountId>,
+	#[serde(default)]
+	pub boot_nodes: Vec<service::config::MultiaddrWithPeerId>,
+	// The tables come last, as TOML can't have plain values after them.
+	/// The initial validators. Their stashes are endowed with the validator stake on top of
+	/// the endowed accounts.
+	pub authorities: Vec<AuthorityKeys>,
+	#[serde(default)]
+	pub endowed_accounts: Vec<EndowedAccount>,
+}
+
+impl ChainSpecBuilder {
+	/// Whether the file at `path` is read as the description of a network, rather than as a
+	/// chain spec.
+	pub fn is_description(path: &str) -> bool {
+		path.ends_with(".toml") || path.ends_with(".network.json")
+	}
+
+	/// Read the description of a network from a file, in TOML if its extension is `toml` and in
+	/// JSON otherwise.
+	pub fn from_file(path: &std::path::Path) -> Result<Self, String> {
+		let content = std::fs::read_to_string(path)
+			.map_err(|e| format!("Error opening network description {}: {}", path.display(), e))?;
+
+		if path.extension().map_or(false, |ext| ext == "toml") {
+			toml::from_str(&content)
+				.map_err(|e| format!("Error parsing network description: {}", e))
+		} else {
+			serde_json::from_str(&content)
+				.map_err(|e| format!("Error parsing network description: {}", e))
+		}
+	}
+
+	/// Whether the network runs the cardamom runtime, going by its identifier.
+	pub fn is_cardamom(&self) -> bool {
+		self.id.starts_with("cardamom") || self.id.starts_with("cdm")
+	}
+
+	/// The initial validators, in the form taken by the testnet genesis builders.
+	#[cfg(any(feature = "selendra-native", feature = "cardamom-native"))]
+	fn initial_authorities(
+		&self,
+	) -> Vec<(
+		AccountId,
+		AccountId,
+		BabeId,
+		GrandpaId,
+		ImOnlineId,
+		ValidatorId,
+		AssignmentId,
+		AuthorityDiscoveryId,
+	)> {
+		self.authorities
+			.iter()
+			.map(|keys| {
+				(
+					keys.stash.clone(),
+					keys.controller.clone(),
+					keys.babe.clone(),
+					keys.grandpa.clone(),
+					keys.im_online.clone(),
+					keys.para_validator.clone(),
+					keys.para_assignment.clone(),
+					keys.authority_discovery.clone(),
+				)
+			})
+			.collect()
+	}
+
+	/// The genesis balances, given the value of a whole token and the stake of each validator.
+	#[cfg(any(feature = "selendra-native", feature = "cardamom-native"))]
+	fn balances(&self, unit: u128, stake: u128) -> Vec<(AccountId, u128)> {
+		let mut balances = std::collections::BTreeMap::<AccountId, u128>::new();
+		for endowed in &self.endowed_accounts {
+			*balances.entry(endowed.account.clone()).or_default() +=
+				u128::from(endowed.balance) * unit;
+		}
+		for keys in &self.authorities {
+			*balances.entry(keys.stash.clone()).or_default() += stake;
+		}
+		balances.into_iter().collect()
+	}
+
+	/// Build the chain spec of a network running the selendra runtime.
+	#[cfg(feature = "selendra-native")]
+	pub fn build_selendra(self) -> Result<SelendraChainSpec, String> {
+		let wasm_binary = selendra::WASM_BINARY.ok_or("Selendra development wasm not available")?;
+		if self.authorities.is_empty() {
+			return Err("A network needs at least one authority".into())
+		}
+
+		let (name, id, chain_type, boot_nodes) =
+			(self.name.clone(), self.id.clone(), self.chain_type.clone(), self.boot_nodes.clone());
+
+		Ok(SelendraChainSpec::from_genesis(
+			&name,
+			&id,
+			chain_type,
+			move || self.selendra_genesis(wasm_binary),
+			boot_nodes,
+			None,
+			Some(DEFAULT_PROTOCOL_ID),
+			None,
+			Some(
+				serde_json::from_str(
+					"{
+            \"tokenDecimals\": 18,
+            \"tokenSymbol\": \"SEL\"
+        	}",
+				)
+				.expect("Provided valid json map"),
+			),
+			Default::default(),
+		))
+	}
+
+	/// Build the chain spec of a network running the selendra runtime.
+	#[cfg(not(feature = "selendra-native"))]
+	pub fn build_selendra(self) -> Result<SelendraChainSpec, String> {
+		Err("Selendra networks are only supported with `selendra-native` feature enabled.".into())
+	}
+
+	#[cfg(feature = "selendra-native")]
+	fn selendra_genesis(&self, wasm_binary: &[u8]) -> selendra::GenesisConfig {
+		let stake = u128::from(self.validator_stake) * SEL;
+		let root_key = self.authorities[0].stash.clone();
+
+		let mut genesis = selendra_testnet_genesis(
+			wasm_binary,
+			self.initial_authorities(),
+			root_key,
+			Some(Vec::new()),
+		);
+		genesis.balances.balances = self.balances(SEL, stake);
+		genesis.staking.stakers = self
+			.authorities
+			.iter()
+			.map(|keys| {
+				(
+					keys.stash.clone(),
+					keys.controller.clone(),
+					stake,
+					selendra::StakerStatus::Validator,
+				)
+			})
+			.collect();
+		genesis.council.members = self.council.clone();
+		genesis
+	}
+
+	/// Build the chain spec of a network running the cardamom runtime.
+	#[cfg(feature = "cardamom-native")]
+	pub fn build_cardamom(self) -> Result<CardamomChainSpec, String> {
+		let wasm_binary = cardamom::WASM_BINARY.ok_or("Cardamom development wasm not available")?;
+		if self.authorities.is_empty() {
+			return Err("A network needs at least one authority".into())
+		}
+
+		let (name, id, chain_type, boot_nodes) =
+			(self.name.clone(), self.id.clone(), self.chain_type.clone(), self.boot_nodes.clone());
+
+		Ok(CardamomChainSpec::from_genesis(
+			&name,
+			&id,
+			chain_type,
+			move || self.cardamom_genesis(wasm_binary),
+			boot_nodes,
+			None,
+			Some(DEFAULT_PROTOCOL_ID),
+			None,
+			Some(
+				serde_json::from_str(
+					"{
+            \"tokenDecimals\": 18,
+            \"tokenSymbol\": \"CDM\"
+        	}",
+				)
+				.expect("Provided valid json map"),
+			),
+			Default::default(),
+		))
+	}
+
+	/// Build the chain spec of a network running the cardamom runtime.
+	#[cfg(not(feature = "cardamom-native"))]
+	pub fn build_cardamom(self) -> Result<CardamomChainSpec, String> {
+		Err("Cardamom networks are only supported with `cardamom-native` feature enabled.".into())
+	}
+
+	#[cfg(feature = "cardamom-native")]
+	fn cardamom_genesis(&self, wasm_binary: &[u8]) -> cardamom::GenesisConfig {
+		let stake = u128::from(self.validator_stake) * CDM;
+		let root_key = self.authorities[0].stash.clone();
+
+		let mut genesis = cardamom_testnet_genesis(
+			wasm_binary,
+			self.initial_authorities(),
+			root_key,
+			Some(Vec::new()),
+		);
+		genesis.balances.balances = self.balances(CDM, stake);
+		genesis.staking.stakers = self
+			.authorities
+			.iter()
+			.map(|keys| {
+				(
+					keys.stash.clone(),
+					keys.controller.clone(),
+					stake,
+					cardamom::StakerStatus::Validator,
+				)
+			})
+			.collect();
+		genesis.council.members = self.council.clone();
+		genesis
+	}
+}
+
+#[test]
+fn network_description_is_read_from_toml_and_json() {
+	let alice = get_authority_keys_from_seed_no_beefy("Alice");
+	let builder = ChainSpecBuilder {
+		name: "My Testnet".into(),
+		id: "my_testnet".into(),
+		chain_type: ChainType::Local,
+		validator_stake: 31416,
+		council: vec![alice.1.clone()],
+		boot_nodes: Vec::new(),
+		authorities: vec![AuthorityKeys {
+			stash: alice.0,
+			controller: alice.1.clone(),
+			babe: alice.2,
+			grandpa: alice.3,
+			im_online: alice.4,
+			para_validator: alice.5,
+			para_assignment: alice.6,
+			authority_discovery: alice.7,
+		}],
+		endowed_accounts: vec![EndowedAccount { account: alice.1, balance: 1_000_000 }],
+	};
+	let expected = serde_json::to_value(&builder).unwrap();
+
+	let from_toml: ChainSpecBuilder = toml::from_str(&toml::to_string(&builder).unwrap()).unwrap();
+	assert_eq!(serde_json::to_value(&from_toml).unwrap(), expected);
+
+	let from_json: ChainSpecBuilder =
+		serde_json::from_str(&serde_json::to_string(&builder).unwrap()).unwrap();
+	assert_eq!(serde_json::to_value(&from_json).unwrap(), expected);
+}
+
+#[test]
+fn network_descriptions_are_told_apart_from_chain_specs() {
+	assert!(ChainSpecBuilder::is_description("my_testnet.toml"));
+	assert!(ChainSpecBuilder::is_description("my_testnet.network.json"));
+	assert!(!ChainSpecBuilder::is_description("my_testnet.json"));
+	assert!(!ChainSpecBuilder::is_description("selendra-dev"));
+}
+
 // Cardamom-navtive chain spec
 
 #[cfg(feature = "cardamom-native")]
@@ -642,6 +971,17 @@ fn cardamom_session_keys(
 	}
 }
 
+#[cfg(feature = "cardamom-native")]
+fn cardamom_transaction_storage_config() -> cardamom::TransactionStorageConfig {
+	use sp_core::Get;
+
+	cardamom::TransactionStorageConfig {
+		byte_fee: cardamom::TransactionStorageByteFee::get(),
+		entry_fee: cardamom::TransactionStorageEntryFee::get(),
+		storage_period: cardamom::TransactionStoragePeriod::get(),
+	}
+}
+
 #[cfg(feature = "cardamom-native")]
 fn cardamom_staging_testnet_config_genesis(wasm_binary: &[u8]) -> cardamom::GenesisConfig {
 	use hex_literal::hex;
@@ -804,6 +1144,7 @@ fn cardamom_staging_testnet_config_genesis(wasm_binary: &[u8]) -> cardamom::Gene
 		},
 		paras: Default::default(),
 		xcm_pallet: Default::default(),
+		transaction_storage: cardamom_transaction_storage_config(),
 	}
 }
 
@@ -920,6 +1261,7 @@ pub fn cardamom_testnet_genesis(
 		},
 		paras: Default::default(),
 		xcm_pallet: Default::default(),
+		transaction_storage: cardamom_transaction_storage_config(),
 	}
 }
 
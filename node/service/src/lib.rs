@@ -22,12 +22,21 @@ pub mod chain_spec;
 mod grandpa_support;
 mod parachains_db;
 mod relay_chain_selection;
+mod rpc_servers;
+#[cfg(feature = "full-node")]
+mod shutdown;
 
 #[cfg(feature = "full-node")]
 pub mod overseer;
 
 #[cfg(feature = "full-node")]
-pub use self::overseer::{OverseerGen, OverseerGenArgs, RealOverseerGen};
+pub use self::overseer::{
+	AvailabilityRecoveryStrategy, OverseerGen, OverseerGenArgs, RealOverseerGen,
+	AVAILABILITY_FAST_PATH_SIZE_LIMIT,
+};
+
+#[cfg(feature = "full-node")]
+pub use selendra_node_subsystem_util::pending_work::PendingWork;
 
 #[cfg(test)]
 mod tests;
@@ -50,11 +59,24 @@ use {
 	tracing::info,
 };
 
+pub use rpc_servers::{
+	take_rpc_servers, HttpRpcConfig, RpcHandlerBuilder, RpcServers, RpcServersConfig, WsRpcConfig,
+};
+#[cfg(feature = "full-node")]
+pub use shutdown::run_until_exit;
 pub use sp_core::traits::SpawnNamed;
 #[cfg(feature = "full-node")]
 pub use {
 	relay_chain_selection::SelectRelayChain,
 	sc_client_api::AuxStore,
+	selendra_node_core_approval_voting::{
+		CheckpointSummary as ApprovalCheckpointSummary, FinalityLagWatchdogConfig,
+	},
+	selendra_node_core_av_store::IntegrityReport as AvailabilityIntegrityReport,
+	selendra_node_core_candidate_validation::{
+		AuditLogConfig as ValidationAuditLogConfig,
+		BundleExportConfig as ValidationBundleExportConfig, IsolationStrategy, SandboxConfig,
+	},
 	selendra_overseer::{Handle, Overseer, OverseerConnector, OverseerHandle},
 	selendra_primitives::v2::ParachainHost,
 	sp_authority_discovery::AuthorityDiscoveryApi,
@@ -215,16 +237,62 @@ pub enum Error {
 	#[error(transparent)]
 	Availability(#[from] AvailabilityError),
 
+	#[cfg(feature = "full-node")]
+	#[error(transparent)]
+	ApprovalCheckpoint(#[from] selendra_node_core_approval_voting::CheckpointError),
+
+	#[cfg(feature = "full-node")]
+	#[error("Failed to generate the light sync state: {0}")]
+	SyncState(String),
+
 	#[error("Authorities require the real overseer implementation")]
 	AuthoritiesRequireRealOverseer,
 
+	#[cfg(feature = "full-node")]
+	#[error(transparent)]
+	CandidateValidation(#[from] selendra_node_core_candidate_validation::Error),
+
 	#[cfg(feature = "full-node")]
 	#[error("Creating a custom database is required for validators")]
 	DatabasePathRequired,
 
+	#[cfg(feature = "full-node")]
+	#[error("The parachain subsystems require a RocksDB or ParityDB database, not a custom one")]
+	CustomDatabaseUnsupported,
+
 	#[cfg(feature = "full-node")]
 	#[error("Expected at least one of polkadot, cardamom, runtime feature")]
 	NoRuntime,
+
+	#[cfg(feature = "full-node")]
+	#[error(
+		"Validators and collators require the state of all finalized blocks, \
+		pass `--state-pruning archive-canonical` or `--state-pruning archive`"
+	)]
+	StatePruningRequired,
+
+	#[cfg(feature = "full-node")]
+	#[error(
+		"The database was created with a different state pruning mode ({0}). \
+		Pass the pruning mode it was created with, or remove the database with `purge-chain` \
+		and resync"
+	)]
+	PruningModeMismatch(String),
+
+	#[cfg(feature = "full-node")]
+	#[error(
+		"Block authors have to prove the data stored in the last {storage_period} blocks, but only \
+		{keep_blocks} blocks are kept. Pass `--blocks-pruning archive`, `--storage-chain` or at least \
+		`--blocks-pruning {storage_period}`"
+	)]
+	TransactionStoragePeriodNotKept { storage_period: u32, keep_blocks: u32 },
+
+	#[cfg(feature = "full-node")]
+	#[error(
+		"Availability data can still be disputed and must be kept for at least {min} blocks \
+		after finality, but `--availability-pruning {given}` was passed"
+	)]
+	AvailabilityPruningTooShort { given: u32, min: u32 },
 }
 
 /// Can be called for a `Configuration` to identify which network the configuration targets.
@@ -293,6 +361,22 @@ where
 	telemetry: Option<Telemetry>,
 }
 
+/// Whether the state database error `reason` is [`sc_state_db::Error::InvalidPruningMode`].
+///
+/// `sc-client-db` only passes on the `Debug` output of the state database error, so it is
+/// compared with the output of that variant.
+#[cfg(feature = "full-node")]
+fn is_pruning_mode_mismatch(reason: &str) -> bool {
+	// Not escaped by `Debug`, so it is found again in the output.
+	const MARKER: &str = "pruning-mode-marker";
+
+	let formatted =
+		format!("{:?}", sc_state_db::Error::<std::io::Error>::InvalidPruningMode(MARKER.into()));
+	let prefix = formatted.split(MARKER).next().unwrap_or_default();
+
+	!prefix.is_empty() && reason.starts_with(prefix)
+}
+
 #[cfg(feature = "full-node")]
 fn new_partial_basics<RuntimeApi, ExecutorDispatch>(
 	config: &mut Configuration,
@@ -337,7 +421,15 @@ where
 			&config,
 			telemetry.as_ref().map(|(_, telemetry)| telemetry.handle()),
 			executor,
-		)?;
+		)
+		.map_err(|e| match e {
+			// The state database refuses to open with a pruning mode other than the one it was
+			// created with.
+			SubstrateServiceError::Client(sp_blockchain::Error::StateDatabase(ref reason))
+				if is_pruning_mode_mismatch(reason) =>
+				Error::PruningModeMismatch(reason.clone()),
+			e => Error::Sub(e),
+		})?;
 	let client = Arc::new(client);
 
 	let telemetry = telemetry.map(|(worker, telemetry)| {
@@ -364,6 +456,7 @@ fn new_partial<RuntimeApi, ExecutorDispatch, ChainSelection>(
 		ExecutorDispatch,
 	>,
 	select_chain: ChainSelection,
+	overseer_handle: Option<Handle>,
 ) -> Result<
 	service::PartialComponents<
 		FullClient<RuntimeApi, ExecutorDispatch>,
@@ -505,6 +598,7 @@ where
 					beefy_best_block_stream: beefy_best_block_stream.clone(),
 					subscription_executor,
 				},
+				overseer_handle: overseer_handle.clone(),
 			};
 
 			selendra_rpc::create_full(deps).map_err(Into::into)
@@ -530,7 +624,11 @@ pub struct NewFull<C> {
 	pub overseer_handle: Option<Handle>,
 	pub network: Arc<sc_network::NetworkService<Block, <Block as BlockT>::Hash>>,
 	pub rpc_handlers: RpcHandlers,
+	/// Builds the handlers of the HTTP and WS RPC servers started by the node.
+	pub rpc_handler_builder: RpcHandlerBuilder,
 	pub backend: Arc<FullBackend>,
+	/// The work on the current leaves which a graceful shutdown waits for.
+	pub pending_work: PendingWork,
 }
 
 #[cfg(feature = "full-node")]
@@ -543,7 +641,9 @@ impl<C> NewFull<C> {
 			overseer_handle: self.overseer_handle,
 			network: self.network,
 			rpc_handlers: self.rpc_handlers,
+			rpc_handler_builder: self.rpc_handler_builder,
 			backend: self.backend,
+			pending_work: self.pending_work,
 		}
 	}
 }
@@ -627,6 +727,72 @@ where
 	Ok(leaves.into_iter().rev().take(MAX_ACTIVE_LEAVES).collect())
 }
 
+/// Checks that the blocks, and with them the data indexed by the transaction storage pallet, are
+/// kept for at least the storage period of the pallet.
+///
+/// Block authors have to include a proof that the data stored a storage period ago is still
+/// available, so pruning blocks any earlier leaves them unable to author blocks once the chain is
+/// old enough. Runtimes without the pallet are not affected.
+#[cfg(feature = "full-node")]
+fn check_transaction_storage_period<Client>(
+	client: &Client,
+	keep_blocks: &service::KeepBlocks,
+) -> Result<(), Error>
+where
+	Client: sc_client_api::StorageProvider<Block, FullBackend> + HeaderBackend<Block>,
+{
+	let keep_blocks = match keep_blocks {
+		service::KeepBlocks::All => return Ok(()),
+		service::KeepBlocks::Some(n) => *n,
+	};
+
+	let key = [
+		sp_core::hashing::twox_128(b"TransactionStorage"),
+		sp_core::hashing::twox_128(b"StoragePeriod"),
+	]
+	.concat();
+	let best_hash = client.info().best_hash;
+	let storage_period =
+		match client.storage(&BlockId::Hash(best_hash), &sp_storage::StorageKey(key))? {
+			// The period is a SCALE encoded `u32`.
+			Some(data) => match <[u8; 4]>::try_from(&data.0[..]) {
+				Ok(bytes) => u32::from_le_bytes(bytes),
+				Err(_) => return Ok(()),
+			},
+			None => return Ok(()),
+		};
+
+	if keep_blocks < storage_period {
+		return Err(Error::TransactionStoragePeriodNotKept { storage_period, keep_blocks })
+	}
+
+	Ok(())
+}
+
+/// The configuration of the parachain subsystems of a full node, passed to [`new_full`] and
+/// [`build_full`].
+#[cfg(feature = "full-node")]
+#[derive(Default)]
+pub struct NewFullParams {
+	/// How the workers preparing and executing PVFs are spawned and hardened.
+	pub isolation_strategy: IsolationStrategy,
+	/// Prune the availability data of candidates once they've been finalized for this many
+	/// blocks, in addition to the time based pruning.
+	pub availability_pruning: Option<selendra_primitives::v1::BlockNumber>,
+	/// Append the verdict of every candidate validation to this log.
+	pub validation_audit_log: Option<ValidationAuditLogConfig>,
+	/// Report the per-para candidate validation metrics only for these paras, and aggregate all
+	/// others under a single label.
+	pub validation_metrics_paras: std::collections::HashSet<ParaId>,
+	/// Export the inputs of every candidate found invalid, so that the validation can be replayed
+	/// offline.
+	pub invalid_candidate_export: Option<ValidationBundleExportConfig>,
+	/// When approval voting raises the alarm about finality lagging behind the chain head.
+	pub finality_lag_watchdog: FinalityLagWatchdogConfig,
+	/// How the data of candidates is recovered, e.g. for approval checking.
+	pub availability_recovery_strategy: AvailabilityRecoveryStrategy,
+}
+
 /// Create a new full node of arbitrary runtime and executor.
 ///
 /// This is an advanced feature and not recommended for general use. Generally, `build_full` is
@@ -635,16 +801,18 @@ where
 /// `overseer_enable_anyways` always enables the overseer, based on the provided `OverseerGenerator`,
 /// regardless of the role the node has. The relay chain selection (longest or disputes-aware) is
 /// still determined based on the role of the node. Likewise for authority discovery.
+///
+/// `params` configures the parachain subsystems, see [`NewFullParams`].
 #[cfg(feature = "full-node")]
 pub fn new_full<RuntimeApi, ExecutorDispatch, OverseerGenerator>(
 	mut config: Configuration,
 	is_collator: IsCollator,
 	grandpa_pause: Option<(u32, u32)>,
-	_enable_beefy: bool,
+	enable_beefy: bool,
 	jaeger_agent: Option<std::net::SocketAddr>,
 	telemetry_worker_handle: Option<TelemetryWorkerHandle>,
-	program_path: Option<std::path::PathBuf>,
 	overseer_enable_anyways: bool,
+	params: NewFullParams,
 	overseer_gen: OverseerGenerator,
 ) -> Result<NewFull<Arc<FullClient<RuntimeApi, ExecutorDispatch>>>, Error>
 where
@@ -659,7 +827,31 @@ where
 {
 	use selendra_node_network_protocol::request_response::IncomingRequest;
 
+	let NewFullParams {
+		isolation_strategy,
+		availability_pruning,
+		validation_audit_log,
+		validation_metrics_paras,
+		invalid_candidate_export,
+		finality_lag_watchdog,
+		availability_recovery_strategy,
+	} = params;
+
 	let role = config.role.clone();
+
+	if let Some(given) = availability_pruning {
+		let min = selendra_node_core_av_store::MIN_KEEP_FINALIZED_BLOCKS;
+		if given < min {
+			return Err(Error::AvailabilityPruningTooShort { given, min })
+		}
+	}
+
+	// Approval voting, disputes and collation query the runtime at finalized blocks which may lag
+	// far behind the best block. Checked before the database is created with the pruning mode.
+	if (role.is_authority() || is_collator.is_collator()) && !config.state_pruning.is_archive() {
+		return Err(Error::StatePruningRequired)
+	}
+
 	let force_authoring = config.force_authoring;
 	let backoff_authoring_blocks = {
 		let backoff = sc_consensus_slots::BackoffAuthoringOnFinalizedHeadLagging::default();
@@ -717,8 +909,14 @@ where
 		&mut config,
 		basics,
 		select_chain,
+		// Only nodes running the overseer can answer approval RPC queries.
+		requires_overseer_for_chain_sel.then(|| overseer_handle.clone()),
 	)?;
 
+	if role.is_authority() {
+		check_transaction_storage_period(&*client, &config.keep_blocks)?;
+	}
+
 	let shared_voter_state = rpc_setup;
 	let auth_disc_publish_non_global_ips = config.network.allow_non_globals_in_dht;
 
@@ -734,10 +932,16 @@ where
 		.extra_sets
 		.push(grandpa::grandpa_peers_set_config(grandpa_protocol_name.clone()));
 
-	let _beefy_protocol_name = beefy_gadget::protocol_standard_name(
+	let beefy_protocol_name = beefy_gadget::protocol_standard_name(
 		&client.block_hash(0).ok().flatten().expect("Genesis block exists; qed"),
 		&config.chain_spec,
 	);
+	if enable_beefy {
+		config
+			.network
+			.extra_sets
+			.push(beefy_gadget::beefy_peers_set_config(beefy_protocol_name.clone()));
+	}
 
 	{
 		use selendra_network_bridge::{peer_sets_info, IsAuthority};
@@ -801,40 +1005,21 @@ where
 		);
 	}
 
-	let parachains_db = match &config.database {
-		DatabaseSource::RocksDb { path, .. } => crate::parachains_db::open_creating_rocksdb(
-			path.clone(),
-			crate::parachains_db::CacheSizes::default(),
-		)?,
-		DatabaseSource::ParityDb { path, .. } => crate::parachains_db::open_creating_paritydb(
-			path.parent().ok_or(Error::DatabasePathRequired)?.into(),
-			crate::parachains_db::CacheSizes::default(),
-		)?,
-		DatabaseSource::Auto { paritydb_path, rocksdb_path, .. } =>
-			if paritydb_path.is_dir() && paritydb_path.exists() {
-				crate::parachains_db::open_creating_paritydb(
-					paritydb_path.parent().ok_or(Error::DatabasePathRequired)?.into(),
-					crate::parachains_db::CacheSizes::default(),
-				)?
-			} else {
-				crate::parachains_db::open_creating_rocksdb(
-					rocksdb_path.clone(),
-					crate::parachains_db::CacheSizes::default(),
-				)?
-			},
-		DatabaseSource::Custom { .. } => {
-			unimplemented!("No selendra subsystem db for custom source.");
-		},
-	};
+	let parachains_db = open_parachains_db(&config.database, true)?;
 
 	let availability_config = AvailabilityConfig {
 		col_data: crate::parachains_db::REAL_COLUMNS.col_availability_data,
 		col_meta: crate::parachains_db::REAL_COLUMNS.col_availability_meta,
+		keep_finalized_blocks: availability_pruning,
 	};
 
+	let pending_work = PendingWork::default();
+
 	let approval_voting_config = ApprovalVotingConfig {
 		col_data: crate::parachains_db::REAL_COLUMNS.col_approval_data,
 		slot_duration_millis: slot_duration.as_millis() as u64,
+		finality_lag_watchdog,
+		pending_work: pending_work.clone(),
 	};
 
 	let candidate_validation_config = CandidateValidationConfig {
@@ -843,10 +1028,10 @@ where
 			.path()
 			.ok_or(Error::DatabasePathRequired)?
 			.join("pvf-artifacts"),
-		program_path: match program_path {
-			None => std::env::current_exe()?,
-			Some(p) => p,
-		},
+		isolation_strategy,
+		metrics_para_allow_list: validation_metrics_paras,
+		invalid_candidate_export,
+		audit_log: validation_audit_log,
 	};
 
 	let chain_selection_config = ChainSelectionConfig {
@@ -858,20 +1043,93 @@ where
 		col_data: crate::parachains_db::REAL_COLUMNS.col_dispute_coordinator_data,
 	};
 
+	// The HTTP and WS servers are started by the node, with a handler built for each of them the
+	// same way `sc-service` builds the handlers of the servers it starts itself.
+	let rpc_extensions_builder = Arc::new(rpc_extensions_builder);
+	let rpc_handler_builder: RpcHandlerBuilder = {
+		use sc_rpc::{author, chain, offchain, state, system};
+
+		let system_info = system::SystemInfo {
+			chain_name: config.chain_spec.name().into(),
+			impl_name: config.impl_name.clone(),
+			impl_version: config.impl_version.clone(),
+			properties: config.chain_spec.properties(),
+			chain_type: config.chain_spec.chain_type(),
+		};
+		let rpc_max_payload = config.rpc_max_payload;
+		let spawn_handle = task_manager.spawn_handle();
+		let client = client.clone();
+		let transaction_pool = transaction_pool.clone();
+		let keystore = keystore_container.sync_keystore();
+		let offchain_storage = backend.offchain_storage();
+		let system_rpc_tx = system_rpc_tx.clone();
+		let rpc_extensions_builder = rpc_extensions_builder.clone();
+		let rpc_metrics = rpc_servers::register_rpc_metrics(config.prometheus_registry())?;
+
+		Arc::new(move |deny_unsafe: sc_rpc::DenyUnsafe| -> Result<_, Error> {
+			let task_executor = sc_rpc::SubscriptionTaskExecutor::new(spawn_handle.clone());
+			let subscriptions =
+				jsonrpc_pubsub::manager::SubscriptionManager::new(Arc::new(task_executor.clone()));
+
+			let chain = chain::new_full(client.clone(), subscriptions.clone());
+			let (state, child_state) = state::new_full(
+				client.clone(),
+				subscriptions.clone(),
+				deny_unsafe,
+				rpc_max_payload,
+			);
+			let author = author::Author::new(
+				client.clone(),
+				transaction_pool.clone(),
+				subscriptions,
+				keystore.clone(),
+				deny_unsafe,
+			);
+			let system =
+				system::System::new(system_info.clone(), system_rpc_tx.clone(), deny_unsafe);
+			let offchain = offchain_storage.clone().map(|storage| {
+				offchain::OffchainApi::to_delegate(offchain::Offchain::new(storage, deny_unsafe))
+			});
+
+			let middleware = sc_rpc_server::RpcMiddleware::new(
+				rpc_metrics.clone(),
+				Default::default(),
+				"selendra",
+			);
+
+			Ok(sc_rpc_server::rpc_handler(
+				(
+					state::StateApi::to_delegate(state),
+					state::ChildStateApi::to_delegate(child_state),
+					chain::ChainApi::to_delegate(chain),
+					offchain,
+					author::AuthorApi::to_delegate(author),
+					system::SystemApi::to_delegate(system),
+					rpc_extensions_builder(deny_unsafe, task_executor)?,
+				),
+				middleware,
+			))
+		})
+	};
+
 	let rpc_handlers = service::spawn_tasks(service::SpawnTasksParams {
 		config,
 		backend: backend.clone(),
 		client: client.clone(),
 		keystore: keystore_container.sync_keystore(),
 		network: network.clone(),
-		rpc_extensions_builder: Box::new(rpc_extensions_builder),
+		rpc_extensions_builder: Box::new(
+			move |deny_unsafe, subscription_executor: selendra_rpc::SubscriptionTaskExecutor| {
+				rpc_extensions_builder(deny_unsafe, subscription_executor)
+			},
+		),
 		transaction_pool: transaction_pool.clone(),
 		task_manager: &mut task_manager,
 		system_rpc_tx,
 		telemetry: telemetry.as_mut(),
 	})?;
 
-	let (block_import, link_half, babe_link, _beefy_links) = import_setup;
+	let (block_import, link_half, babe_link, beefy_links) = import_setup;
 
 	let overseer_client = client.clone();
 	let spawner = task_manager.spawn_handle();
@@ -953,6 +1211,8 @@ where
 					dispute_coordinator_config,
 					disputes_enabled,
 					pvf_checker_enabled,
+					availability_recovery_strategy,
+					pending_work: pending_work.clone(),
 				},
 			)
 			.map_err(|e| {
@@ -1034,6 +1294,13 @@ where
 						parent,
 					)?;
 
+					// Proves that the data stored by the transaction storage pallet is still kept.
+					let storage_proof =
+						sp_transaction_storage_proof::registration::new_data_provider(
+							&*client_clone,
+							&parent,
+						)?;
+
 					let timestamp = sp_timestamp::InherentDataProvider::from_system_time();
 
 					let slot =
@@ -1042,7 +1309,7 @@ where
 							slot_duration,
 						);
 
-					Ok((timestamp, slot, uncles, parachain))
+					Ok((timestamp, slot, uncles, parachain, storage_proof))
 				}
 			},
 			force_authoring,
@@ -1063,6 +1330,25 @@ where
 	let keystore_opt =
 		if role.is_authority() { Some(keystore_container.sync_keystore()) } else { None };
 
+	if enable_beefy {
+		let beefy_params = beefy_gadget::BeefyParams {
+			client: client.clone(),
+			backend: backend.clone(),
+			key_store: keystore_opt.clone(),
+			network: network.clone(),
+			signed_commitment_sender: beefy_links.0,
+			beefy_best_block_sender: beefy_links.1,
+			min_block_delta: 8,
+			prometheus_registry: prometheus_registry.clone(),
+			protocol_name: beefy_protocol_name,
+		};
+
+		// Bridges rely on BEEFY, but block production and finality don't, so a failing gadget
+		// doesn't bring the node down.
+		let gadget = beefy_gadget::start_beefy_gadget::<_, _, _, _>(beefy_params);
+		task_manager.spawn_handle().spawn_blocking("beefy-gadget", None, gadget);
+	}
+
 	let config = grandpa::Config {
 		// FIXME substrate#1578 make this available through chainspec
 		gossip_duration: Duration::from_millis(1000),
@@ -1123,7 +1409,137 @@ where
 
 	network_starter.start_network();
 
-	Ok(NewFull { task_manager, client, overseer_handle, network, rpc_handlers, backend })
+	Ok(NewFull {
+		task_manager,
+		client,
+		overseer_handle,
+		network,
+		rpc_handlers,
+		rpc_handler_builder,
+		backend,
+		pending_work,
+	})
+}
+
+/// Open the database used by the parachain subsystems of a node using the given database.
+///
+/// When `create` is not set, opening fails if the database doesn't exist yet.
+#[cfg(feature = "full-node")]
+fn open_parachains_db(
+	database: &DatabaseSource,
+	create: bool,
+) -> Result<Arc<dyn selendra_node_subsystem_util::database::Database>, Error> {
+	let open_rocksdb = |root: std::path::PathBuf| {
+		let cache_sizes = crate::parachains_db::CacheSizes::default();
+		if create {
+			crate::parachains_db::open_creating_rocksdb(root, cache_sizes)
+		} else {
+			crate::parachains_db::open_existing_rocksdb(root, cache_sizes)
+		}
+	};
+	let open_paritydb = |root: std::path::PathBuf| {
+		let cache_sizes = crate::parachains_db::CacheSizes::default();
+		if create {
+			crate::parachains_db::open_creating_paritydb(root, cache_sizes)
+		} else {
+			crate::parachains_db::open_existing_paritydb(root, cache_sizes)
+		}
+	};
+
+	let parachains_db = match database {
+		DatabaseSource::RocksDb { path, .. } => open_rocksdb(path.clone())?,
+		DatabaseSource::ParityDb { path, .. } =>
+			open_paritydb(path.parent().ok_or(Error::DatabasePathRequired)?.into())?,
+		DatabaseSource::Auto { paritydb_path, rocksdb_path, .. } =>
+			if paritydb_path.is_dir() && paritydb_path.exists() {
+				open_paritydb(paritydb_path.parent().ok_or(Error::DatabasePathRequired)?.into())?
+			} else {
+				open_rocksdb(rocksdb_path.clone())?
+			},
+		DatabaseSource::Custom { .. } => return Err(Error::CustomDatabaseUnsupported),
+	};
+
+	Ok(parachains_db)
+}
+
+/// Check the integrity of the availability store of a node with the given configuration.
+///
+/// When `prune` is set, the data of candidates found to be corrupted is deleted. The node must
+/// not be running.
+#[cfg(feature = "full-node")]
+pub fn check_availability_store(
+	config: &Configuration,
+	prune: bool,
+) -> Result<AvailabilityIntegrityReport, Error> {
+	let parachains_db = open_parachains_db(&config.database, false)?;
+	let availability_config = AvailabilityConfig {
+		col_data: crate::parachains_db::REAL_COLUMNS.col_availability_data,
+		col_meta: crate::parachains_db::REAL_COLUMNS.col_availability_meta,
+		keep_finalized_blocks: None,
+	};
+
+	Ok(selendra_node_core_av_store::check_integrity(&parachains_db, &availability_config, prune)?)
+}
+
+/// Export the approval voting data of a node with the given configuration to the file at `path`.
+///
+/// The node must not be running.
+#[cfg(feature = "full-node")]
+pub fn export_approval_checkpoint(
+	config: &Configuration,
+	path: &std::path::Path,
+) -> Result<ApprovalCheckpointSummary, Error> {
+	let parachains_db = open_parachains_db(&config.database, false)?;
+	let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+
+	Ok(selendra_node_core_approval_voting::export_checkpoint(
+		&parachains_db,
+		crate::parachains_db::REAL_COLUMNS.col_approval_data,
+		&mut file,
+	)?)
+}
+
+/// Import the approval voting data exported by [`export_approval_checkpoint`] from the file at
+/// `path` into the database of a node with the given configuration.
+///
+/// The node must not be running and must not have any approval voting data yet.
+#[cfg(feature = "full-node")]
+pub fn import_approval_checkpoint(
+	config: &Configuration,
+	path: &std::path::Path,
+) -> Result<ApprovalCheckpointSummary, Error> {
+	let parachains_db = open_parachains_db(&config.database, true)?;
+	let mut file = std::io::BufReader::new(std::fs::File::open(path)?);
+
+	Ok(selendra_node_core_approval_voting::import_checkpoint(
+		&parachains_db,
+		crate::parachains_db::REAL_COLUMNS.col_approval_data,
+		&mut file,
+	)?)
+}
+
+/// Copy the parachains database of a node with the given configuration from RocksDB to ParityDB.
+///
+/// The client database isn't copied, as its state is stored in a layout specific to the backend.
+/// The node has to sync it again when started with `--database paritydb`. `progress` is called
+/// with the column and the number of keys copied from it so far.
+///
+/// The node must not be running and there must not be a ParityDB parachains database yet.
+#[cfg(feature = "full-node")]
+pub fn migrate_parachains_db_to_paritydb(
+	config: &Configuration,
+	progress: impl FnMut(u32, usize),
+) -> Result<Vec<usize>, Error> {
+	let (rocksdb_path, paritydb_path) = match &config.database {
+		DatabaseSource::Auto { rocksdb_path, paritydb_path, .. } => (rocksdb_path, paritydb_path),
+		_ => return Err(Error::MigrationPathsRequired),
+	};
+
+	Ok(crate::parachains_db::migrate_rocksdb_to_paritydb(
+		rocksdb_path.clone(),
+		paritydb_path.parent().ok_or(Error::DatabasePathRequired)?.into(),
+		progress,
+	)?)
 }
 
 #[cfg(feature = "full-node")]
@@ -1147,6 +1563,7 @@ macro_rules! chain_ops {
 				&mut config,
 				basics,
 				chain_selection,
+				None,
 			)?;
 		Ok((Arc::new(Client::$variant(client)), backend, import_queue, task_manager))
 	}};
@@ -1183,6 +1600,73 @@ pub fn new_chain_ops(
 	Err(Error::NoRuntime)
 }
 
+/// Write the chain spec of a node with the given configuration to the file at `path`, with the
+/// light sync state of its last finalized block embedded.
+///
+/// Light clients started from the written chain spec sync from that block rather than from
+/// genesis. The node must not be running.
+#[cfg(feature = "full-node")]
+pub fn export_sync_state(
+	config: &mut Configuration,
+	path: &std::path::Path,
+	raw: bool,
+) -> Result<(), Error> {
+	config.keystore = service::config::KeystoreConfig::InMemory;
+
+	#[cfg(feature = "cardamom-native")]
+	if config.chain_spec.is_cardamom() {
+		return write_sync_spec::<cardamom_runtime::RuntimeApi, CardamomExecutorDispatch>(
+			config, path, raw,
+		)
+	}
+
+	#[cfg(feature = "selendra-native")]
+	{
+		return write_sync_spec::<selendra_runtime::RuntimeApi, SelendraExecutorDispatch>(
+			config, path, raw,
+		)
+	}
+	#[cfg(not(feature = "selendra-native"))]
+	Err(Error::NoRuntime)
+}
+
+#[cfg(feature = "full-node")]
+fn write_sync_spec<RuntimeApi, ExecutorDispatch>(
+	config: &mut Configuration,
+	path: &std::path::Path,
+	raw: bool,
+) -> Result<(), Error>
+where
+	RuntimeApi: ConstructRuntimeApi<Block, FullClient<RuntimeApi, ExecutorDispatch>>
+		+ Send
+		+ Sync
+		+ 'static,
+	RuntimeApi::RuntimeApi:
+		RuntimeApiCollection<StateBackend = sc_client_api::StateBackendFor<FullBackend, Block>>,
+	ExecutorDispatch: NativeExecutionDispatch + 'static,
+{
+	use sc_sync_state_rpc::{SyncStateRpcApi, SyncStateRpcHandler};
+
+	let basics = new_partial_basics::<RuntimeApi, ExecutorDispatch>(config, None, None)?;
+	let chain_selection = sc_consensus::LongestChain::new(basics.backend.clone());
+	let service::PartialComponents { client, other: (_, import_setup, ..), .. } =
+		new_partial(config, basics, chain_selection, None)?;
+	let (_, grandpa_link, babe_link, _) = import_setup;
+
+	let sync_state = SyncStateRpcHandler::new(
+		config.chain_spec.cloned_box(),
+		client,
+		grandpa_link.shared_authority_set().clone(),
+		babe_link.epoch_changes().clone(),
+	)
+	.map_err(|e| Error::SyncState(e.to_string()))?;
+
+	let spec = sync_state.system_gen_sync_spec(raw).map_err(|e| Error::SyncState(e.message))?;
+	let spec = serde_json::to_string_pretty(&spec).map_err(|e| Error::SyncState(e.to_string()))?;
+	std::fs::write(path, spec)?;
+	Ok(())
+}
+
 /// Build a full node.
 ///
 /// The actual "flavor", aka if it will use `Selendra`,` or `Cardamom` is determined based on
@@ -1191,6 +1675,8 @@ pub fn new_chain_ops(
 /// `overseer_enable_anyways` always enables the overseer, based on the provided `OverseerGenerator`,
 /// regardless of the role the node has. The relay chain selection (longest or disputes-aware) is
 /// still determined based on the role of the node. Likewise for authority discovery.
+///
+/// `params` configures the parachain subsystems, see [`NewFullParams`].
 #[cfg(feature = "full-node")]
 pub fn build_full(
 	config: Configuration,
@@ -1200,6 +1686,7 @@ pub fn build_full(
 	jaeger_agent: Option<std::net::SocketAddr>,
 	telemetry_worker_handle: Option<TelemetryWorkerHandle>,
 	overseer_enable_anyways: bool,
+	params: NewFullParams,
 	overseer_gen: impl OverseerGen,
 ) -> Result<NewFull<Client>, Error> {
 	#[cfg(feature = "cardamom-native")]
@@ -1211,8 +1698,8 @@ pub fn build_full(
 			enable_beefy,
 			jaeger_agent,
 			telemetry_worker_handle,
-			None,
 			overseer_enable_anyways,
+			params,
 			overseer_gen,
 		)
 		.map(|full| full.with_client(Client::Cardamom))
@@ -1227,8 +1714,8 @@ pub fn build_full(
 			enable_beefy,
 			jaeger_agent,
 			telemetry_worker_handle,
-			None,
 			overseer_enable_anyways,
+			params,
 			overseer_gen,
 		)
 		.map(|full| full.with_client(Client::Selendra))
@@ -19,11 +19,13 @@ use lru::LruCache;
 use selendra_availability_distribution::IncomingRequestReceivers;
 use selendra_node_core_approval_voting::Config as ApprovalVotingConfig;
 use selendra_node_core_av_store::Config as AvailabilityConfig;
+use selendra_node_core_bitfield_signing::BitfieldSigningConfig;
 use selendra_node_core_candidate_validation::Config as CandidateValidationConfig;
 use selendra_node_core_chain_selection::Config as ChainSelectionConfig;
 use selendra_node_core_dispute_coordinator::Config as DisputeCoordinatorConfig;
 use selendra_node_core_provisioner::ProvisionerConfig;
 use selendra_node_network_protocol::request_response::{v1 as request_v1, IncomingRequestReceiver};
+use selendra_node_subsystem_util::pending_work::PendingWork;
 #[cfg(any(feature = "malus", test))]
 pub use selendra_overseer::{
 	dummy::{dummy_overseer_builder, DummySubsystem},
@@ -46,7 +48,10 @@ use std::sync::Arc;
 pub use selendra_approval_distribution::ApprovalDistribution as ApprovalDistributionSubsystem;
 pub use selendra_availability_bitfield_distribution::BitfieldDistribution as BitfieldDistributionSubsystem;
 pub use selendra_availability_distribution::AvailabilityDistributionSubsystem;
-pub use selendra_availability_recovery::AvailabilityRecoverySubsystem;
+pub use selendra_availability_recovery::{
+	AvailabilityRecoverySubsystem, RecoveryStrategy as AvailabilityRecoveryStrategy,
+	DEFAULT_FAST_PATH_SIZE_LIMIT as AVAILABILITY_FAST_PATH_SIZE_LIMIT,
+};
 pub use selendra_collator_protocol::{CollatorProtocolSubsystem, ProtocolSide};
 pub use selendra_dispute_distribution::DisputeDistributionSubsystem;
 pub use selendra_gossip_support::GossipSupport as GossipSupportSubsystem;
@@ -112,6 +117,10 @@ where
 	pub disputes_enabled: bool,
 	/// Enable PVF pre-checking
 	pub pvf_checker_enabled: bool,
+	/// The way the availability recovery subsystem recovers the data of candidates.
+	pub availability_recovery_strategy: AvailabilityRecoveryStrategy,
+	/// The work on the current leaves which a graceful shutdown waits for.
+	pub pending_work: PendingWork,
 }
 
 /// Obtain a prepared `OverseerBuilder`, that is initialized
@@ -140,6 +149,8 @@ pub fn prepared_overseer_builder<'a, Spawner, RuntimeClient>(
 		dispute_coordinator_config,
 		disputes_enabled,
 		pvf_checker_enabled,
+		availability_recovery_strategy,
+		pending_work,
 	}: OverseerGenArgs<'a, Spawner, RuntimeClient>,
 ) -> Result<
 	InitializedOverseerBuilder<
@@ -187,9 +198,10 @@ where
 			IncomingRequestReceivers { pov_req_receiver, chunk_req_receiver },
 			Metrics::register(registry)?,
 		))
-		.availability_recovery(AvailabilityRecoverySubsystem::with_chunks_only(
+		.availability_recovery(AvailabilityRecoverySubsystem::with_strategy(
 			available_data_req_receiver,
 			Metrics::register(registry)?,
+			availability_recovery_strategy,
 		))
 		.availability_store(AvailabilityStoreSubsystem::new(
 			parachains_db.clone(),
@@ -199,7 +211,7 @@ where
 		.bitfield_distribution(BitfieldDistributionSubsystem::new(Metrics::register(registry)?))
 		.bitfield_signing(BitfieldSigningSubsystem::new(
 			spawner.clone(),
-			keystore.clone(),
+			BitfieldSigningConfig { keystore: keystore.clone(), pending_work },
 			Metrics::register(registry)?,
 		))
 		.candidate_backing(CandidateBackingSubsystem::new(
@@ -207,11 +219,12 @@ where
 			keystore.clone(),
 			Metrics::register(registry)?,
 		))
-		.candidate_validation(CandidateValidationSubsystem::with_config(
-			candidate_validation_config,
-			Metrics::register(registry)?, // candidate-validation metrics
-			Metrics::register(registry)?, // validation host metrics
-		))
+		.candidate_validation(
+			CandidateValidationSubsystem::builder(candidate_validation_config)
+				.metrics(Metrics::register(registry)?)
+				.pvf_metrics(Metrics::register(registry)?)
+				.build()?,
+		)
 		.pvf_checker(PvfCheckerSubsystem::new(
 			pvf_checker_enabled,
 			keystore.clone(),
@@ -15,7 +15,10 @@
 
 #[cfg(feature = "full-node")]
 use {
-	selendra_node_subsystem_util::database::Database, std::io, std::path::PathBuf, std::sync::Arc,
+	selendra_node_subsystem_util::database::Database,
+	std::io,
+	std::path::{Path, PathBuf},
+	std::sync::Arc,
 };
 
 #[cfg(feature = "full-node")]
@@ -146,3 +149,133 @@ pub fn open_creating_paritydb(
 	);
 	Ok(Arc::new(db))
 }
+
+/// Open the existing database on disk, failing if it doesn't exist.
+#[cfg(feature = "full-node")]
+pub fn open_existing_rocksdb(
+	root: PathBuf,
+	cache_sizes: CacheSizes,
+) -> io::Result<Arc<dyn Database>> {
+	ensure_exists(&root.join("parachains").join("db"))?;
+	open_creating_rocksdb(root, cache_sizes)
+}
+
+/// Open the existing parity db database, failing if it doesn't exist.
+#[cfg(feature = "full-node")]
+pub fn open_existing_paritydb(
+	root: PathBuf,
+	cache_sizes: CacheSizes,
+) -> io::Result<Arc<dyn Database>> {
+	ensure_exists(&root.join("parachains"))?;
+	open_creating_paritydb(root, cache_sizes)
+}
+
+#[cfg(feature = "full-node")]
+fn ensure_exists(path: &Path) -> io::Result<()> {
+	if path.is_dir() {
+		Ok(())
+	} else {
+		Err(io::Error::new(
+			io::ErrorKind::NotFound,
+			format!("No parachains database found at {:?}", path),
+		))
+	}
+}
+
+/// The number of keys copied in a single transaction by [`migrate_rocksdb_to_paritydb`].
+#[cfg(feature = "full-node")]
+const MIGRATION_BATCH_SIZE: usize = 10_000;
+
+/// Copy the RocksDB database under `rocksdb_root` into a new ParityDB database under
+/// `paritydb_root`, column by column.
+///
+/// `progress` is called with the column and the number of keys copied from it so far after every
+/// batch. Returns the number of keys copied from each column.
+#[cfg(feature = "full-node")]
+pub fn migrate_rocksdb_to_paritydb(
+	rocksdb_root: PathBuf,
+	paritydb_root: PathBuf,
+	mut progress: impl FnMut(u32, usize),
+) -> io::Result<Vec<usize>> {
+	use selendra_node_subsystem_util::database::DBTransaction;
+
+	let rocksdb_path = rocksdb_root.join("parachains").join("db");
+	if !rocksdb_path.is_dir() {
+		return Err(other_io_error(format!("No RocksDB database at {:?}", rocksdb_path)))
+	}
+	let paritydb_path = paritydb_root.join("parachains");
+	if paritydb_path.read_dir().map_or(false, |mut d| d.next().is_some()) {
+		return Err(other_io_error(format!("A database already exists at {:?}", paritydb_path)))
+	}
+
+	let source = open_creating_rocksdb(rocksdb_root, CacheSizes::default())?;
+	let target = open_creating_paritydb(paritydb_root, CacheSizes::default())?;
+
+	(0..columns::NUM_COLUMNS)
+		.map(|col| {
+			let mut copied = 0;
+			let mut transaction = DBTransaction::new();
+			for (key, value) in source.iter(col) {
+				transaction.put_vec(col, &key, value.into_vec());
+				copied += 1;
+				if copied % MIGRATION_BATCH_SIZE == 0 {
+					target.write(std::mem::take(&mut transaction))?;
+					progress(col, copied);
+				}
+			}
+			target.write(transaction)?;
+			progress(col, copied);
+
+			Ok(copied)
+		})
+		.collect()
+}
+
+#[cfg(all(test, feature = "full-node"))]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn migrate_rocksdb_to_paritydb_copies_all_columns() {
+		let rocksdb_root = tempfile::tempdir().unwrap();
+		let paritydb_root = tempfile::tempdir().unwrap();
+
+		let source =
+			open_creating_rocksdb(rocksdb_root.path().into(), CacheSizes::default()).unwrap();
+		let mut transaction = selendra_node_subsystem_util::database::DBTransaction::new();
+		for col in 0..columns::NUM_COLUMNS {
+			for i in 0..=col {
+				transaction.put(col, &[col as u8, i as u8], &[i as u8; 4]);
+			}
+		}
+		source.write(transaction).unwrap();
+		drop(source);
+
+		let mut reported = Vec::new();
+		let copied = migrate_rocksdb_to_paritydb(
+			rocksdb_root.path().into(),
+			paritydb_root.path().into(),
+			|col, n| reported.push((col, n)),
+		)
+		.unwrap();
+
+		assert_eq!(copied, vec![1, 2, 3, 4, 5]);
+		assert_eq!(reported, vec![(0, 1), (1, 2), (2, 3), (3, 4), (4, 5)]);
+
+		let target =
+			open_creating_paritydb(paritydb_root.path().into(), CacheSizes::default()).unwrap();
+		for col in 0..columns::NUM_COLUMNS {
+			for i in 0..=col {
+				assert_eq!(target.get(col, &[col as u8, i as u8]).unwrap(), Some(vec![i as u8; 4]));
+			}
+		}
+
+		// The target must be empty.
+		assert!(migrate_rocksdb_to_paritydb(
+			rocksdb_root.path().into(),
+			paritydb_root.path().into(),
+			|_, _| {},
+		)
+		.is_err());
+	}
+}
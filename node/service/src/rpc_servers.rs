@@ -0,0 +1,473 @@
+// Copyright 2020-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! HTTP and WS RPC servers with independent settings.
+//!
+//! `sc-service` starts both servers with the same CORS and payload settings. The node takes them
+//! out of the [`Configuration`] with [`take_rpc_servers`] and starts them itself, so every
+//! transport can be enabled and limited on its own.
+
+use crate::Error;
+use jsonrpc_core::{BoxFuture, ErrorCode, Params, RemoteProcedure, RpcMethod, Value};
+use jsonrpc_pubsub::PubSubMetadata;
+use parking_lot::Mutex;
+use prometheus_endpoint::{
+	prometheus::{
+		core::{Collector, Desc},
+		proto::MetricFamily,
+	},
+	Registry,
+};
+use sc_rpc_api::{DenyUnsafe, Metadata};
+use sc_rpc_server::{RpcHandler, RpcMetrics};
+use service::{config::RpcMethods, Configuration};
+use std::{
+	collections::HashMap,
+	net::SocketAddr,
+	sync::{
+		atomic::{AtomicUsize, Ordering},
+		Arc,
+	},
+};
+
+const LOG_TARGET: &str = "selendra::rpc";
+
+/// The error code returned when a WS connection exceeds its subscription limit.
+const TOO_MANY_SUBSCRIPTIONS_ERROR: i64 = -32010;
+
+/// The error code returned when the HTTP server is serving its maximum number of requests.
+const TOO_MANY_REQUESTS_ERROR: i64 = -32011;
+
+/// Builds the RPC handler of a server, denying unsafe methods or not.
+///
+/// Every RPC checks [`DenyUnsafe`] on its own, some depending on the arguments of the call, so a
+/// handler is built for each server, like `sc-service` does for the servers it starts itself.
+pub type RpcHandlerBuilder =
+	Arc<dyn Fn(DenyUnsafe) -> Result<RpcHandler<Metadata>, Error> + Send + Sync>;
+
+/// The settings of the HTTP RPC server.
+#[derive(Debug, Clone)]
+pub struct HttpRpcConfig {
+	/// The address to listen on.
+	pub address: SocketAddr,
+	/// The allowed origins, `None` to allow all.
+	pub cors: Option<Vec<String>>,
+	/// The maximum payload size in MiB.
+	pub max_payload: Option<usize>,
+	/// The maximum number of requests served at the same time.
+	///
+	/// A connection only has a single request in flight, so this bounds the connections being
+	/// served.
+	pub max_connections: Option<usize>,
+}
+
+/// The settings of the WS RPC server.
+#[derive(Debug, Clone)]
+pub struct WsRpcConfig {
+	/// The address to listen on.
+	pub address: SocketAddr,
+	/// The allowed origins, `None` to allow all.
+	pub cors: Option<Vec<String>>,
+	/// The maximum payload size in MiB.
+	pub max_payload: Option<usize>,
+	/// The maximum number of connections.
+	pub max_connections: Option<usize>,
+	/// The maximum size of the output buffer of a connection in MiB.
+	pub max_out_buffer_capacity: Option<usize>,
+	/// The maximum number of subscriptions open at the same time on a connection.
+	pub max_subscriptions_per_connection: Option<usize>,
+}
+
+/// The settings of the HTTP and WS RPC servers.
+#[derive(Debug, Clone)]
+pub struct RpcServersConfig {
+	/// The HTTP server, `None` if disabled.
+	pub http: Option<HttpRpcConfig>,
+	/// The WS server, `None` if disabled.
+	pub ws: Option<WsRpcConfig>,
+	/// Which methods are exposed.
+	pub methods: RpcMethods,
+	tokio_handle: tokio::runtime::Handle,
+}
+
+/// Take the settings of the HTTP and WS RPC servers out of `config`, so `sc-service` doesn't
+/// start them.
+///
+/// The servers are started with [`RpcServersConfig::start`] once the service is built. The IPC
+/// server is left to `sc-service`.
+pub fn take_rpc_servers(config: &mut Configuration) -> RpcServersConfig {
+	RpcServersConfig {
+		http: config.rpc_http.take().map(|address| HttpRpcConfig {
+			address,
+			cors: config.rpc_cors.clone(),
+			max_payload: config.rpc_max_payload,
+			max_connections: None,
+		}),
+		ws: config.rpc_ws.take().map(|address| WsRpcConfig {
+			address,
+			cors: config.rpc_cors.clone(),
+			max_payload: config.rpc_max_payload,
+			max_connections: config.rpc_ws_max_connections,
+			max_out_buffer_capacity: config.ws_max_out_buffer_capacity,
+			max_subscriptions_per_connection: None,
+		}),
+		methods: config.rpc_methods,
+		tokio_handle: config.tokio_handle.clone(),
+	}
+}
+
+impl RpcServersConfig {
+	/// Start the enabled servers, serving the RPC handlers built by `handlers`.
+	pub fn start(self, handlers: &RpcHandlerBuilder) -> Result<RpcServers, Error> {
+		let mut servers = RpcServers { http: None, ws: None };
+
+		if let Some(http) = self.http {
+			let mut io = self.handler(handlers, &http.address)?;
+			if let Some(limit) = http.max_connections {
+				limit_requests(&mut io, limit);
+			}
+			let server = sc_rpc_server::start_http(
+				&http.address,
+				http.cors.as_ref(),
+				io,
+				http.max_payload,
+				self.tokio_handle.clone(),
+			)?;
+			tracing::info!(target: LOG_TARGET, address = %http.address, "Started the HTTP RPC server.");
+			servers.http = Some(server);
+		}
+
+		if let Some(ws) = self.ws {
+			let mut io = self.handler(handlers, &ws.address)?;
+			if let Some(limit) = ws.max_subscriptions_per_connection {
+				limit_subscriptions(&mut io, limit);
+			}
+			let server = sc_rpc_server::start_ws(
+				&ws.address,
+				ws.max_connections,
+				ws.cors.as_ref(),
+				io,
+				ws.max_payload,
+				ws.max_out_buffer_capacity,
+				Default::default(),
+				self.tokio_handle.clone(),
+			)?;
+			tracing::info!(target: LOG_TARGET, address = %ws.address, "Started the WS RPC server.");
+			servers.ws = Some(server);
+		}
+
+		Ok(servers)
+	}
+
+	/// The RPC handler of a server listening on `address`.
+	fn handler(
+		&self,
+		handlers: &RpcHandlerBuilder,
+		address: &SocketAddr,
+	) -> Result<RpcHandler<Metadata>, Error> {
+		handlers(if deny_unsafe(address, self.methods) { DenyUnsafe::Yes } else { DenyUnsafe::No })
+	}
+}
+
+/// Register the call metrics of the node's servers in `registry`.
+///
+/// `sc-service` registers the call metrics of the servers it starts in `registry` too, so the
+/// node's are kept in a registry of their own, gathered with `registry` under the same names.
+pub fn register_rpc_metrics(registry: Option<&Registry>) -> Result<Option<RpcMetrics>, Error> {
+	let registry = match registry {
+		Some(registry) => registry,
+		None => return Ok(None),
+	};
+	let servers = Registry::new();
+	let metrics = RpcMetrics::new(Some(&servers))?;
+	registry.register(Box::new(GatheredRegistry(servers)))?;
+	Ok(metrics)
+}
+
+/// The metrics of a registry, collected with the metrics of the registry it is registered in.
+struct GatheredRegistry(Registry);
+
+impl Collector for GatheredRegistry {
+	fn desc(&self) -> Vec<&Desc> {
+		Vec::new()
+	}
+
+	fn collect(&self) -> Vec<MetricFamily> {
+		self.0.gather()
+	}
+}
+
+/// Limit the number of subscriptions open at the same time on every connection to `limit`.
+fn limit_subscriptions(io: &mut RpcHandler<Metadata>, limit: usize) {
+	let counts = SubscriptionCounts::default();
+	let limited = io
+		.iter()
+		.filter_map(|(name, procedure)| match procedure {
+			RemoteProcedure::Method(method) => {
+				let name = name.clone();
+				let procedure = if is_unsubscribe(&name) {
+					LimitedSubscriptions::unsubscribe(method.clone(), counts.clone())
+				} else if is_subscribe(&name) {
+					LimitedSubscriptions::subscribe(method.clone(), counts.clone(), limit)
+				} else {
+					return None
+				};
+				Some((name, RemoteProcedure::Method(Arc::new(procedure))))
+			},
+			_ => None,
+		})
+		.collect::<Vec<_>>();
+	io.extend_with(limited);
+}
+
+/// Limit the number of requests served at the same time to `limit`.
+fn limit_requests(io: &mut RpcHandler<Metadata>, limit: usize) {
+	let in_flight = Arc::new(AtomicUsize::new(0));
+	let limited = io
+		.iter()
+		.filter_map(|(name, procedure)| match procedure {
+			RemoteProcedure::Method(method) => {
+				let procedure =
+					LimitedRequests { inner: method.clone(), in_flight: in_flight.clone(), limit };
+				Some((name.clone(), RemoteProcedure::Method(Arc::new(procedure))))
+			},
+			_ => None,
+		})
+		.collect::<Vec<_>>();
+	io.extend_with(limited);
+}
+
+/// The running HTTP and WS RPC servers, closed when dropped.
+pub struct RpcServers {
+	http: Option<sc_rpc_server::HttpServer>,
+	ws: Option<sc_rpc_server::WsServer>,
+}
+
+impl Drop for RpcServers {
+	fn drop(&mut self) {
+		if let Some(server) = self.http.take() {
+			server.close_handle().close();
+			server.wait();
+		}
+		if let Some(server) = self.ws.take() {
+			server.close_handle().close();
+			let _ = server.wait();
+		}
+	}
+}
+
+/// Whether unsafe methods are denied on a server listening on `address`, following the rules of
+/// `sc-service`.
+fn deny_unsafe(address: &SocketAddr, methods: RpcMethods) -> bool {
+	match (address.ip().is_loopback(), methods) {
+		(_, RpcMethods::Unsafe) | (true, RpcMethods::Auto) => false,
+		_ => true,
+	}
+}
+
+/// Whether the method opens a subscription.
+///
+/// Subscriptions which end on their own, like `author_submitAndWatchExtrinsic`, aren't counted,
+/// since they are usually not unsubscribed from.
+fn is_subscribe(method: &str) -> bool {
+	method.contains("_subscribe")
+}
+
+/// Whether the method closes a subscription.
+fn is_unsubscribe(method: &str) -> bool {
+	method.contains("_unsubscribe")
+}
+
+/// The number of open subscriptions of every WS connection.
+#[derive(Clone, Default)]
+struct SubscriptionCounts(Arc<Mutex<HashMap<usize, usize>>>);
+
+impl SubscriptionCounts {
+	/// Count a new subscription of `session`, unless it has `limit` subscriptions already.
+	fn acquire(&self, session: &Arc<jsonrpc_pubsub::Session>, limit: usize) -> bool {
+		let key = Arc::as_ptr(session) as usize;
+		let mut counts = self.0.lock();
+
+		let count = match counts.get_mut(&key) {
+			Some(count) => count,
+			None => {
+				let this = self.clone();
+				session.on_drop(move || {
+					let _ = this.0.lock().remove(&key);
+				});
+				counts.entry(key).or_default()
+			},
+		};
+
+		if *count >= limit {
+			return false
+		}
+
+		*count += 1;
+		true
+	}
+
+	/// Stop counting a subscription of `session`.
+	fn release(&self, session: &Arc<jsonrpc_pubsub::Session>) {
+		let key = Arc::as_ptr(session) as usize;
+		if let Some(count) = self.0.lock().get_mut(&key) {
+			*count = count.saturating_sub(1);
+		}
+	}
+}
+
+/// A method refusing calls while `limit` calls of any limited method are in flight.
+struct LimitedRequests {
+	inner: Arc<dyn RpcMethod<Metadata>>,
+	in_flight: Arc<AtomicUsize>,
+	limit: usize,
+}
+
+impl RpcMethod<Metadata> for LimitedRequests {
+	fn call(&self, params: Params, meta: Metadata) -> BoxFuture<jsonrpc_core::Result<Value>> {
+		if self.in_flight.fetch_add(1, Ordering::SeqCst) >= self.limit {
+			self.in_flight.fetch_sub(1, Ordering::SeqCst);
+			return Box::pin(futures::future::ready(Err(jsonrpc_core::Error {
+				code: ErrorCode::ServerError(TOO_MANY_REQUESTS_ERROR),
+				message: format!("Too many requests in flight, at most {}", self.limit),
+				data: None,
+			})))
+		}
+
+		// Released when the call completes or is dropped, e.g. because the client went away.
+		let in_flight = InFlight(self.in_flight.clone());
+		let call = self.inner.call(params, meta);
+		Box::pin(async move {
+			let _in_flight = in_flight;
+			call.await
+		})
+	}
+}
+
+/// A request counted by [`LimitedRequests`].
+struct InFlight(Arc<AtomicUsize>);
+
+impl Drop for InFlight {
+	fn drop(&mut self) {
+		self.0.fetch_sub(1, Ordering::SeqCst);
+	}
+}
+
+/// A subscribe or unsubscribe method keeping track of the subscriptions of every connection.
+struct LimitedSubscriptions {
+	inner: Arc<dyn RpcMethod<Metadata>>,
+	counts: SubscriptionCounts,
+	/// The maximum number of subscriptions per connection, `None` for unsubscribe methods.
+	limit: Option<usize>,
+}
+
+impl LimitedSubscriptions {
+	fn subscribe(
+		inner: Arc<dyn RpcMethod<Metadata>>,
+		counts: SubscriptionCounts,
+		limit: usize,
+	) -> Self {
+		Self { inner, counts, limit: Some(limit) }
+	}
+
+	fn unsubscribe(inner: Arc<dyn RpcMethod<Metadata>>, counts: SubscriptionCounts) -> Self {
+		Self { inner, counts, limit: None }
+	}
+}
+
+impl RpcMethod<Metadata> for LimitedSubscriptions {
+	fn call(&self, params: Params, meta: Metadata) -> BoxFuture<jsonrpc_core::Result<Value>> {
+		let session = match meta.session() {
+			Some(session) => session,
+			None => return self.inner.call(params, meta),
+		};
+		let counts = self.counts.clone();
+
+		match self.limit {
+			Some(limit) => {
+				if !counts.acquire(&session, limit) {
+					return Box::pin(futures::future::ready(Err(jsonrpc_core::Error {
+						code: ErrorCode::ServerError(TOO_MANY_SUBSCRIPTIONS_ERROR),
+						message: format!(
+							"Too many subscriptions on the connection, at most {}",
+							limit
+						),
+						data: None,
+					})))
+				}
+
+				let call = self.inner.call(params, meta);
+				Box::pin(async move {
+					let result = call.await;
+					if result.is_err() {
+						counts.release(&session);
+					}
+					result
+				})
+			},
+			None => {
+				let call = self.inner.call(params, meta);
+				Box::pin(async move {
+					let result = call.await;
+					if let Ok(Value::Bool(true)) = result {
+						counts.release(&session);
+					}
+					result
+				})
+			},
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn unsafe_methods_are_denied_on_exposed_addresses() {
+		let local = SocketAddr::from(([127, 0, 0, 1], 9933));
+		let exposed = SocketAddr::from(([0, 0, 0, 0], 9933));
+
+		assert!(!deny_unsafe(&local, RpcMethods::Auto));
+		assert!(deny_unsafe(&local, RpcMethods::Safe));
+		assert!(deny_unsafe(&exposed, RpcMethods::Auto));
+		assert!(!deny_unsafe(&exposed, RpcMethods::Unsafe));
+	}
+
+	#[test]
+	fn requests_over_the_limit_are_refused() {
+		let inner: Arc<dyn RpcMethod<Metadata>> = Arc::new(|_: Params, _: Metadata| {
+			futures::future::pending::<jsonrpc_core::Result<Value>>()
+		});
+		let method = LimitedRequests { inner, in_flight: Default::default(), limit: 1 };
+
+		let first = method.call(Params::None, Default::default());
+		let refused = futures::executor::block_on(method.call(Params::None, Default::default()));
+		assert_eq!(refused.unwrap_err().code, ErrorCode::ServerError(TOO_MANY_REQUESTS_ERROR));
+
+		drop(first);
+		assert_eq!(method.in_flight.load(Ordering::SeqCst), 0);
+	}
+
+	#[test]
+	fn subscription_methods_are_recognized() {
+		assert!(is_subscribe("chain_subscribeNewHeads"));
+		assert!(!is_unsubscribe("chain_subscribeNewHeads"));
+		assert!(is_unsubscribe("state_unsubscribeStorage"));
+		assert!(!is_subscribe("author_submitAndWatchExtrinsic"));
+		assert!(!is_subscribe("chain_getHeader"));
+	}
+}
@@ -0,0 +1,154 @@
+// Copyright 2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Shutting the node down without abandoning the work on the current leaves.
+//!
+//! `sc-cli` tears the whole service down as soon as it receives SIGINT or SIGTERM, so approvals
+//! being checked and the bitfield of the current leaf are lost, and the validator shows up as a
+//! no-show after every restart. [`run_until_exit`] instead asks the overseer to stop activating
+//! new leaves and gives the subsystems a grace period to finish their work, while networking keeps
+//! running to broadcast the results. The overseer is stopped as soon as the subsystems report their
+//! work as done through [`PendingWork`], and only then is the rest of the service shut down.
+
+use futures::{future, pin_mut, select, FutureExt};
+use selendra_node_subsystem_util::pending_work::PendingWork;
+use selendra_overseer::Handle;
+use service::TaskManager;
+use std::time::{Duration, Instant};
+
+use crate::Error;
+
+/// The time the subsystems are given to conclude after the grace period, on top of the second
+/// the overseer waits for them itself.
+const CONCLUDE_MARGIN: Duration = Duration::from_secs(2);
+
+/// The time the results of the work on the current leaves are given to be distributed once the
+/// work completed.
+const DISTRIBUTION_TIME: Duration = Duration::from_secs(1);
+
+/// Resolves once the node is asked to exit by SIGINT or SIGTERM.
+async fn exit_signal() -> std::io::Result<()> {
+	#[cfg(unix)]
+	{
+		use tokio::signal::unix::{signal, SignalKind};
+
+		let mut interrupt = signal(SignalKind::interrupt())?;
+		let mut terminate = signal(SignalKind::terminate())?;
+		let interrupt = interrupt.recv().fuse();
+		let terminate = terminate.recv().fuse();
+		pin_mut!(interrupt, terminate);
+		let _ = future::select(interrupt, terminate).await;
+		Ok(())
+	}
+
+	#[cfg(not(unix))]
+	{
+		tokio::signal::ctrl_c().await
+	}
+}
+
+/// Run the service until the node is asked to exit or an essential task fails.
+///
+/// On exit, the overseer behind `overseer_handle` gets up to `grace_period` to complete the
+/// `pending_work` on the current leaves before the service is shut down. A second signal cuts the
+/// grace period short.
+pub async fn run_until_exit(
+	mut task_manager: TaskManager,
+	overseer_handle: Option<Handle>,
+	pending_work: PendingWork,
+	grace_period: Duration,
+) -> Result<(), Error> {
+	let exited = {
+		let exit = exit_signal().fuse();
+		let essential_tasks = task_manager.future().fuse();
+		pin_mut!(exit, essential_tasks);
+		match future::select(exit, essential_tasks).await {
+			future::Either::Left((res, _)) => res.map(|()| true).map_err(Error::from),
+			future::Either::Right((res, _)) => res.map(|()| false).map_err(Error::from),
+		}
+	};
+
+	let res = match (exited, overseer_handle) {
+		(Ok(true), Some(overseer_handle)) if !grace_period.is_zero() =>
+			stop_gracefully(&mut task_manager, overseer_handle, &pending_work, grace_period).await,
+		(res, _) => res.map(|_| ()),
+	};
+
+	task_manager.clean_shutdown().await;
+	res
+}
+
+/// Stop the overseer once the `pending_work` completed, or at the latest once `grace_period`
+/// elapsed.
+async fn stop_gracefully(
+	task_manager: &mut TaskManager,
+	mut overseer_handle: Handle,
+	pending_work: &PendingWork,
+	grace_period: Duration,
+) -> Result<(), Error> {
+	tracing::info!(
+		?grace_period,
+		pending = pending_work.pending(),
+		"Completing the work on the current leaves before exiting"
+	);
+	overseer_handle.stop_gracefully(grace_period).await;
+	// The overseer starts its own timer once it received the message, so it never stops before
+	// `grace_period_end`.
+	let grace_period_end = Instant::now() + grace_period;
+
+	let work_done = async {
+		pending_work.idle().await;
+		tokio::time::sleep(DISTRIBUTION_TIME).await;
+	}
+	.fuse();
+	// The overseer runs as an essential task, so the task manager resolves once it stopped.
+	let essential_tasks = task_manager.future().fuse();
+	let timeout = tokio::time::sleep(grace_period + CONCLUDE_MARGIN).fuse();
+	let exit = exit_signal().fuse();
+	pin_mut!(work_done, essential_tasks, timeout, exit);
+
+	select! {
+		() = work_done => {
+			tracing::info!("Completed the work on the current leaves");
+			overseer_handle.stop().await;
+		},
+		res = essential_tasks => {
+			// The end of an essential task is reported as its failure, even for the overseer
+			// stopping at the end of the grace period. Any earlier one is a genuine failure.
+			if Instant::now() < grace_period_end {
+				tracing::error!(
+					"Essential task failed while completing the work on the current leaves"
+				);
+				return res.map_err(Error::from)
+			}
+			return Ok(())
+		},
+		() = timeout => {
+			tracing::warn!("The overseer didn't stop at the end of the grace period");
+			return Ok(())
+		},
+		_ = exit => {
+			tracing::info!("Exiting right away");
+			return Ok(())
+		},
+	}
+
+	// The overseer gives the subsystems a second to conclude.
+	if tokio::time::timeout(CONCLUDE_MARGIN, essential_tasks).await.is_err() {
+		tracing::warn!("The overseer didn't stop after the work on the current leaves completed");
+	}
+	Ok(())
+}
@@ -33,7 +33,9 @@ use selendra_node_network_protocol::{
 	UnifiedReputationChange,
 };
 use selendra_node_primitives::{
-	approval::{BlockApprovalMeta, IndirectAssignmentCert, IndirectSignedApprovalVote},
+	approval::{
+		BlockApprovalMeta, DelayTranche, IndirectAssignmentCert, IndirectSignedApprovalVote,
+	},
 	AvailableData, BabeEpoch, BlockWeight, CandidateVotes, CollationGenerationConfig,
 	CollationSecondedSignal, DisputeMessage, ErasureChunk, PoV, SignedDisputeStatement,
 	SignedFullStatement, ValidationResult,
@@ -41,14 +43,14 @@ use selendra_node_primitives::{
 use selendra_primitives::{
 	v1::{
 		AuthorityDiscoveryId, BackedCandidate, BlockNumber, CandidateDescriptor, CandidateEvent,
-		CandidateHash, CandidateIndex, CandidateReceipt, CollatorId, CommittedCandidateReceipt,
-		CoreState, GroupIndex, GroupRotationInfo, Hash, Header as BlockHeader, Id as ParaId,
-		InboundDownwardMessage, InboundHrmpMessage, MultiDisputeStatementSet,
-		OccupiedCoreAssumption, PersistedValidationData, SessionIndex, SignedAvailabilityBitfield,
-		SignedAvailabilityBitfields, ValidationCode, ValidationCodeHash, ValidatorId,
-		ValidatorIndex, ValidatorSignature,
+		CandidateHash, CandidateIndex, CandidateReceipt, CollatorId, CollatorPair,
+		CommittedCandidateReceipt, CoreState, GroupIndex, GroupRotationInfo, Hash,
+		Header as BlockHeader, Id as ParaId, InboundDownwardMessage, InboundHrmpMessage,
+		MultiDisputeStatementSet, OccupiedCoreAssumption, PersistedValidationData, SessionIndex,
+		SignedAvailabilityBitfield, SignedAvailabilityBitfields, ValidationCode,
+		ValidationCodeHash, ValidatorId, ValidatorIndex, ValidatorSignature,
 	},
-	v2::{PvfCheckStatement, SessionInfo},
+	v2::{BackingState, PvfCheckStatement, SessionInfo},
 };
 use selendra_statement_table::v1::Misbehavior;
 use std::{
@@ -160,6 +162,21 @@ pub enum CandidateValidationMessage {
 		Duration,
 		oneshot::Sender<Result<ValidationResult, ValidationFailed>>,
 	),
+	/// Validate a candidate with provided parameters, where the validation code is only given by
+	/// its hash.
+	///
+	/// This behaves like `ValidateFromExhaustive`, but the validation code is resolved from the
+	/// runtime API at the relay-parent of the `CandidateDescriptor`. Recently used code is cached
+	/// by the subsystem, so callers don't need to fetch and send the code themselves.
+	ValidateFromCodeHash(
+		PersistedValidationData,
+		ValidationCodeHash,
+		CandidateDescriptor,
+		Arc<PoV>,
+		/// Execution timeout
+		Duration,
+		oneshot::Sender<Result<ValidationResult, ValidationFailed>>,
+	),
 	/// Try to compile the given validation code and send back
 	/// the outcome.
 	///
@@ -179,6 +196,7 @@ impl CandidateValidationMessage {
 		match self {
 			Self::ValidateFromChainState(_, _, _, _) => None,
 			Self::ValidateFromExhaustive(_, _, _, _, _, _) => None,
+			Self::ValidateFromCodeHash(_, _, _, _, _, _) => None,
 			Self::PreCheck(relay_parent, _, _) => Some(*relay_parent),
 		}
 	}
@@ -213,6 +231,22 @@ pub enum CollatorProtocolMessage {
 	///
 	/// The hash is the relay parent.
 	Seconded(Hash, SignedFullStatement),
+	/// Switch to a new collator key.
+	///
+	/// Sent by the Collation Generation subsystem right before it signs collations with the new
+	/// key. Validators only accept one declaration per connection, so the collator reconnects to
+	/// them to declare the new key.
+	RotateKey(NewCollatorKey),
+}
+
+/// A collator key to switch to, see [`CollatorProtocolMessage::RotateKey`].
+#[derive(Clone)]
+pub struct NewCollatorKey(pub CollatorPair);
+
+impl std::fmt::Debug for NewCollatorKey {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "NewCollatorKey {{ ... }}")
+	}
 }
 
 impl Default for CollatorProtocolMessage {
@@ -683,6 +717,14 @@ pub enum RuntimeApiRequest {
 		OccupiedCoreAssumption,
 		RuntimeApiSender<Option<ValidationCodeHash>>,
 	),
+	/// Get the number of pending messages in the downward message queue for a para.
+	DmqLength(ParaId, RuntimeApiSender<u32>),
+	/// Get the blocks at which the given recipient received HRMP messages, along with the paras
+	/// which sent them.
+	HrmpChannelDigests(ParaId, RuntimeApiSender<Vec<(BlockNumber, Vec<ParaId>)>>),
+	/// Get the state of a para relevant for backing its next candidates.
+	/// Returns `None` if the para is not registered.
+	StagingParaBackingState(ParaId, RuntimeApiSender<Option<BackingState>>),
 }
 
 /// A message to the Runtime API subsystem.
@@ -765,6 +807,9 @@ impl BoundToRelayParent for ProvisionerMessage {
 #[derive(Debug)]
 pub enum CollationGenerationMessage {
 	/// Initialize the collation generation subsystem
+	///
+	/// Initializing it again for the same para rotates the collator key: the new config is
+	/// used from the next session on.
 	Initialize(CollationGenerationConfig),
 }
 
@@ -863,6 +908,22 @@ pub struct HighestApprovedAncestorBlock {
 	pub descriptions: Vec<BlockDescription>,
 }
 
+/// The approval state of a candidate under a block, as returned by
+/// `ApprovalVotingMessage::GetApprovalStatus`.
+#[derive(Clone, Debug)]
+pub struct CandidateApprovalStatus {
+	/// The hash of the candidate.
+	pub candidate_hash: CandidateHash,
+	/// Whether the candidate is approved under the block.
+	pub approved: bool,
+	/// The validators which approved the candidate.
+	pub approvals: Vec<ValidatorIndex>,
+	/// The validators assigned to check the candidate under the block, by delay tranche.
+	pub tranches: Vec<(DelayTranche, Vec<ValidatorIndex>)>,
+	/// The number of assigned validators which didn't approve the candidate in time.
+	pub no_shows: usize,
+}
+
 /// Message to the Approval Voting subsystem.
 #[derive(Debug)]
 pub enum ApprovalVotingMessage {
@@ -886,6 +947,17 @@ pub enum ApprovalVotingMessage {
 	/// It can also return the same block hash, if that is acceptable to vote upon.
 	/// Return `None` if the input hash is unrecognized.
 	ApprovedAncestor(Hash, BlockNumber, oneshot::Sender<Option<HighestApprovedAncestorBlock>>),
+	/// Inspect the approval state of all candidates included in the given block.
+	///
+	/// Returns `None` if the block is unknown, e.g. because it is already finalized.
+	GetApprovalStatus(Hash, oneshot::Sender<Option<Vec<CandidateApprovalStatus>>>),
+	/// Trigger our assignments for the candidate right away in all blocks it is included in,
+	/// regardless of their tranches, and launch the approval work. Assignments too far in the
+	/// future for peers to accept are still triggered at their tick.
+	///
+	/// Used by the dispute coordinator when participating in a dispute about the candidate.
+	/// No-op if the candidate is unknown or we have no untriggered assignment for it.
+	ForceTriggerAssignment(CandidateHash),
 }
 
 /// Message to the Approval Distribution subsystem.
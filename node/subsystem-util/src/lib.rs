@@ -51,12 +51,12 @@ use pin_project::pin_project;
 
 use selendra_primitives::{
 	v1::{
-		AuthorityDiscoveryId, CandidateEvent, CommittedCandidateReceipt, CoreState, EncodeAs,
-		GroupIndex, GroupRotationInfo, Hash, Id as ParaId, OccupiedCoreAssumption,
+		AuthorityDiscoveryId, BlockNumber, CandidateEvent, CommittedCandidateReceipt, CoreState,
+		EncodeAs, GroupIndex, GroupRotationInfo, Hash, Id as ParaId, OccupiedCoreAssumption,
 		PersistedValidationData, SessionIndex, Signed, SigningContext, ValidationCode,
 		ValidationCodeHash, ValidatorId, ValidatorIndex, ValidatorSignature,
 	},
-	v2::SessionInfo,
+	v2::{BackingState, SessionInfo},
 };
 use sp_application_crypto::AppKey;
 use sp_core::{traits::SpawnNamed, ByteArray};
@@ -90,6 +90,9 @@ pub mod runtime;
 /// Database trait for subsystem.
 pub mod database;
 
+/// Tracking the work a graceful shutdown waits for.
+pub mod pending_work;
+
 mod determine_new_blocks;
 
 #[cfg(test)]
@@ -220,6 +223,9 @@ specialize_requests! {
 	fn request_session_info(index: SessionIndex) -> Option<SessionInfo>; SessionInfo;
 	fn request_validation_code_hash(para_id: ParaId, assumption: OccupiedCoreAssumption)
 		-> Option<ValidationCodeHash>; ValidationCodeHash;
+	fn request_dmq_length(recipient: ParaId) -> u32; DmqLength;
+	fn request_hrmp_channel_digests(recipient: ParaId) -> Vec<(BlockNumber, Vec<ParaId>)>; HrmpChannelDigests;
+	fn request_staging_para_backing_state(para_id: ParaId) -> Option<BackingState>; StagingParaBackingState;
 }
 
 /// From the given set of validators, find the first key we can sign with, if any.
@@ -0,0 +1,117 @@
+// Copyright 2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Tracking the work on the current leaves which a graceful shutdown waits for.
+//!
+//! Subsystems hold a [`PendingWorkGuard`] for every piece of work whose loss would be noticed by
+//! the other validators, such as an approval check or the bitfield of a leaf. A graceful shutdown
+//! then stops as soon as [`PendingWork::idle`] resolves, instead of waiting for its whole grace
+//! period.
+
+use futures::{future, task::AtomicWaker};
+use std::{
+	fmt,
+	sync::{
+		atomic::{AtomicUsize, Ordering},
+		Arc,
+	},
+	task::Poll,
+};
+
+#[derive(Default)]
+struct Inner {
+	pending: AtomicUsize,
+	waker: AtomicWaker,
+}
+
+/// The work on the current leaves which a graceful shutdown waits for.
+///
+/// Clones refer to the same work.
+#[derive(Clone, Default)]
+pub struct PendingWork(Arc<Inner>);
+
+impl PendingWork {
+	/// Note the start of some work, which lasts until the returned guard is dropped.
+	pub fn start(&self) -> PendingWorkGuard {
+		self.0.pending.fetch_add(1, Ordering::SeqCst);
+		PendingWorkGuard(self.0.clone())
+	}
+
+	/// The number of pieces of work which are still pending.
+	pub fn pending(&self) -> usize {
+		self.0.pending.load(Ordering::SeqCst)
+	}
+
+	/// Resolves once no work is pending anymore.
+	///
+	/// Only one task may wait for this at a time.
+	pub async fn idle(&self) {
+		future::poll_fn(|cx| {
+			self.0.waker.register(cx.waker());
+			if self.pending() == 0 {
+				Poll::Ready(())
+			} else {
+				Poll::Pending
+			}
+		})
+		.await
+	}
+}
+
+impl fmt::Debug for PendingWork {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_tuple("PendingWork").field(&self.pending()).finish()
+	}
+}
+
+/// A piece of pending work, which is done once dropped.
+#[must_use = "the work is done once the guard is dropped"]
+pub struct PendingWorkGuard(Arc<Inner>);
+
+impl Drop for PendingWorkGuard {
+	fn drop(&mut self) {
+		if self.0.pending.fetch_sub(1, Ordering::SeqCst) == 1 {
+			self.0.waker.wake();
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use futures::{executor, FutureExt};
+
+	#[test]
+	fn idle_once_all_guards_dropped() {
+		let pending_work = PendingWork::default();
+		assert!(pending_work.idle().now_or_never().is_some());
+
+		let first = pending_work.start();
+		let second = pending_work.clone().start();
+		assert_eq!(pending_work.pending(), 2);
+
+		let idle = pending_work.idle();
+		futures::pin_mut!(idle);
+		assert!(idle.as_mut().now_or_never().is_none());
+
+		drop(first);
+		assert!(idle.as_mut().now_or_never().is_none());
+
+		drop(second);
+		executor::block_on(idle);
+		assert_eq!(pending_work.pending(), 0);
+	}
+}
@@ -25,14 +25,29 @@ use selendra_primitives::{
 	v2::SessionInfo,
 };
 
-use futures::channel::oneshot;
+use futures::{channel::oneshot, stream::FuturesOrdered, FutureExt, StreamExt};
+use parity_scale_codec::{Decode, Encode};
 use selendra_node_subsystem::{
 	errors::RuntimeApiError,
 	messages::{RuntimeApiMessage, RuntimeApiRequest},
 	overseer, SubsystemContext,
 };
+use std::sync::Arc;
 use thiserror::Error;
 
+use crate::database::{DBTransaction, Database};
+
+const LOG_TARGET: &str = "parachain::rolling-session-window";
+
+/// The maximum number of `SessionInfo` runtime API requests in flight at once.
+const MAX_PARALLEL_SESSION_INFO_REQUESTS: usize = 8;
+
+/// Key of the earliest session persisted in the database.
+const EARLIEST_SESSION_KEY: &[u8] = b"RollingSessionWindow_earliest";
+
+/// Prefix of the keys of persisted session info, followed by the big-endian session index.
+const SESSION_INFO_PREFIX: &[u8] = b"RollingSessionWindow_info";
+
 /// Sessions unavailable in state to cache.
 #[derive(Debug, Clone)]
 pub enum SessionsUnavailableReason {
@@ -88,11 +103,90 @@ pub enum SessionWindowUpdate {
 	Unchanged,
 }
 
+/// The database a [`RollingSessionWindow`] persists its session info in.
+///
+/// Session info never changes once a session has started, so the persisted entries save the node
+/// from fetching the whole window from the runtime again after a restart.
+#[derive(Clone)]
+pub struct DatabaseParams {
+	/// The database to use.
+	pub db: Arc<dyn Database>,
+	/// The column to store the session info in.
+	pub db_column: u32,
+}
+
+impl DatabaseParams {
+	fn load_earliest_session(&self) -> Option<SessionIndex> {
+		self.load(EARLIEST_SESSION_KEY)
+	}
+
+	fn load_session_info(&self, index: SessionIndex) -> Option<SessionInfo> {
+		self.load(&session_info_key(index))
+	}
+
+	// Failures are only logged, the session info is then fetched from the runtime instead.
+	fn load<T: Decode>(&self, key: &[u8]) -> Option<T> {
+		match self.db.get(self.db_column, key) {
+			Ok(Some(raw)) => match T::decode(&mut &raw[..]) {
+				Ok(value) => Some(value),
+				Err(err) => {
+					tracing::warn!(
+						target: LOG_TARGET,
+						?err,
+						"Failed to decode persisted session data"
+					);
+					None
+				},
+			},
+			Ok(None) => None,
+			Err(err) => {
+				tracing::warn!(target: LOG_TARGET, ?err, "Failed to read persisted session data");
+				None
+			},
+		}
+	}
+
+	/// Persist the freshly fetched session info and prune all entries before `earliest_session`.
+	fn store(
+		&self,
+		earliest_session: SessionIndex,
+		window_size: SessionWindowSize,
+		fresh: &[(SessionIndex, SessionInfo)],
+	) {
+		let mut tx = DBTransaction::new();
+
+		// All persisted entries lie within one window starting at the previous earliest session.
+		if let Some(prev_earliest) = self.load_earliest_session() {
+			let outdated_end =
+				std::cmp::min(earliest_session, prev_earliest.saturating_add(window_size.get()));
+			for index in prev_earliest..outdated_end {
+				tx.delete(self.db_column, &session_info_key(index));
+			}
+		}
+
+		for (index, session_info) in fresh {
+			tx.put_vec(self.db_column, &session_info_key(*index), session_info.encode());
+		}
+		tx.put_vec(self.db_column, EARLIEST_SESSION_KEY, earliest_session.encode());
+
+		if let Err(err) = self.db.write(tx) {
+			tracing::warn!(target: LOG_TARGET, ?err, "Failed to persist session info");
+		}
+	}
+}
+
+fn session_info_key(index: SessionIndex) -> Vec<u8> {
+	let mut key = SESSION_INFO_PREFIX.to_vec();
+	key.extend_from_slice(&index.to_be_bytes());
+	key
+}
+
 /// A rolling window of sessions and cached session info.
 pub struct RollingSessionWindow {
 	earliest_session: SessionIndex,
 	session_info: Vec<SessionInfo>,
 	window_size: SessionWindowSize,
+	db_params: Option<DatabaseParams>,
 }
 
 impl RollingSessionWindow {
@@ -101,12 +195,35 @@ impl RollingSessionWindow {
 		ctx: &mut (impl SubsystemContext + overseer::SubsystemContext),
 		window_size: SessionWindowSize,
 		block_hash: Hash,
+	) -> Result<Self, SessionsUnavailable> {
+		Self::new_inner(ctx, window_size, block_hash, None).await
+	}
+
+	/// Initialize a new session info cache with the given window size, which persists the
+	/// session info in the given database.
+	///
+	/// Sessions already present in the database are not requested from the runtime again.
+	pub async fn new_with_db(
+		ctx: &mut (impl SubsystemContext + overseer::SubsystemContext),
+		window_size: SessionWindowSize,
+		block_hash: Hash,
+		db_params: DatabaseParams,
+	) -> Result<Self, SessionsUnavailable> {
+		Self::new_inner(ctx, window_size, block_hash, Some(db_params)).await
+	}
+
+	async fn new_inner(
+		ctx: &mut (impl SubsystemContext + overseer::SubsystemContext),
+		window_size: SessionWindowSize,
+		block_hash: Hash,
+		db_params: Option<DatabaseParams>,
 	) -> Result<Self, SessionsUnavailable> {
 		let session_index = get_session_index_for_child(ctx, block_hash).await?;
 
 		let window_start = session_index.saturating_sub(window_size.get() - 1);
 
-		match load_all_sessions(ctx, block_hash, window_start, session_index).await {
+		match load_sessions(ctx, block_hash, window_start, session_index, db_params.as_ref()).await
+		{
 			Err(kind) => Err(SessionsUnavailable {
 				kind,
 				info: Some(SessionsUnavailableInfo {
@@ -115,7 +232,13 @@ impl RollingSessionWindow {
 					block_hash,
 				}),
 			}),
-			Ok(s) => Ok(Self { earliest_session: window_start, session_info: s, window_size }),
+			Ok((s, fresh)) => {
+				if let Some(db_params) = db_params.as_ref() {
+					db_params.store(window_start, window_size, &fresh);
+				}
+
+				Ok(Self { earliest_session: window_start, session_info: s, window_size, db_params })
+			},
 		}
 	}
 
@@ -126,7 +249,7 @@ impl RollingSessionWindow {
 		earliest_session: SessionIndex,
 		session_info: Vec<SessionInfo>,
 	) -> Self {
-		RollingSessionWindow { earliest_session, session_info, window_size }
+		RollingSessionWindow { earliest_session, session_info, window_size, db_params: None }
 	}
 
 	/// Access the session info for the given session index, if stored within the window.
@@ -180,7 +303,9 @@ impl RollingSessionWindow {
 
 		let fresh_start = if latest < window_start { window_start } else { latest + 1 };
 
-		match load_all_sessions(ctx, block_hash, fresh_start, session_index).await {
+		match load_sessions(ctx, block_hash, fresh_start, session_index, self.db_params.as_ref())
+			.await
+		{
 			Err(kind) => Err(SessionsUnavailable {
 				kind,
 				info: Some(SessionsUnavailableInfo {
@@ -189,7 +314,7 @@ impl RollingSessionWindow {
 					block_hash,
 				}),
 			}),
-			Ok(s) => {
+			Ok((s, fresh)) => {
 				let update = SessionWindowUpdate::Advanced {
 					prev_window_start: old_window_start,
 					prev_window_end: old_window_end,
@@ -206,6 +331,10 @@ impl RollingSessionWindow {
 				let new_earliest = std::cmp::max(window_start, old_window_start);
 				self.earliest_session = new_earliest;
 
+				if let Some(db_params) = self.db_params.as_ref() {
+					db_params.store(new_earliest, self.window_size, &fresh);
+				}
+
 				Ok(update)
 			},
 		}
@@ -245,29 +374,79 @@ async fn get_session_index_for_child(
 	}
 }
 
-async fn load_all_sessions(
+// Loads the session info of `start..=end_inclusive`, taking what is available from the database
+// and fetching the rest from the runtime. The fetched session info is returned separately so that
+// it can be persisted.
+async fn load_sessions(
 	ctx: &mut (impl SubsystemContext + overseer::SubsystemContext),
 	block_hash: Hash,
 	start: SessionIndex,
 	end_inclusive: SessionIndex,
-) -> Result<Vec<SessionInfo>, SessionsUnavailableReason> {
+	db_params: Option<&DatabaseParams>,
+) -> Result<(Vec<SessionInfo>, Vec<(SessionIndex, SessionInfo)>), SessionsUnavailableReason> {
+	let cached: Vec<Option<SessionInfo>> = (start..=end_inclusive)
+		.map(|i| db_params.and_then(|db_params| db_params.load_session_info(i)))
+		.collect();
+
+	let missing = (start..=end_inclusive).zip(cached.iter()).filter_map(|(i, s)| {
+		if s.is_none() {
+			Some(i)
+		} else {
+			None
+		}
+	});
+	let fresh = load_all_sessions(ctx, block_hash, missing).await?;
+
+	let mut fresh_iter = fresh.iter().map(|(_, s)| s.clone());
+	let sessions = cached
+		.into_iter()
+		.map(|s| s.or_else(|| fresh_iter.next()))
+		.collect::<Option<Vec<_>>>()
+		.expect("one session info was fetched for each session missing in the cache; qed");
+
+	Ok((sessions, fresh))
+}
+
+// Requests the session info of all given sessions from the runtime, with a bounded number of
+// requests in flight. The results are in the order of `indices`.
+async fn load_all_sessions(
+	ctx: &mut (impl SubsystemContext + overseer::SubsystemContext),
+	block_hash: Hash,
+	mut indices: impl Iterator<Item = SessionIndex>,
+) -> Result<Vec<(SessionIndex, SessionInfo)>, SessionsUnavailableReason> {
+	let mut pending = FuturesOrdered::new();
 	let mut v = Vec::new();
-	for i in start..=end_inclusive {
-		let (tx, rx) = oneshot::channel();
-		ctx.send_message(RuntimeApiMessage::Request(
-			block_hash,
-			RuntimeApiRequest::SessionInfo(i, tx),
-		))
-		.await;
-
-		let session_info = match rx.await {
+
+	loop {
+		while pending.len() < MAX_PARALLEL_SESSION_INFO_REQUESTS {
+			let i = match indices.next() {
+				Some(i) => i,
+				None => break,
+			};
+
+			let (tx, rx) = oneshot::channel();
+			ctx.send_message(RuntimeApiMessage::Request(
+				block_hash,
+				RuntimeApiRequest::SessionInfo(i, tx),
+			))
+			.await;
+
+			pending.push(rx.map(move |res| (i, res)));
+		}
+
+		let (i, res) = match pending.next().await {
+			Some(r) => r,
+			None => break,
+		};
+
+		let session_info = match res {
 			Ok(Ok(Some(s))) => s,
 			Ok(Ok(None)) => return Err(SessionsUnavailableReason::Missing(i)),
 			Ok(Err(e)) => return Err(SessionsUnavailableReason::RuntimeApi(e)),
 			Err(canceled) => return Err(SessionsUnavailableReason::RuntimeApiUnavailable(canceled)),
 		};
 
-		v.push(session_info);
+		v.push((i, session_info));
 	}
 
 	Ok(v)
@@ -381,6 +560,7 @@ mod tests {
 			earliest_session: 1,
 			session_info: vec![dummy_session_info(1)],
 			window_size: TEST_WINDOW_SIZE,
+			db_params: None,
 		};
 
 		cache_session_info_test(1, 2, Some(window), 2);
@@ -406,6 +586,7 @@ mod tests {
 				dummy_session_info(52),
 			],
 			window_size: TEST_WINDOW_SIZE,
+			db_params: None,
 		};
 
 		cache_session_info_test(
@@ -423,6 +604,7 @@ mod tests {
 			earliest_session: start,
 			session_info: (start..=99).map(dummy_session_info).collect(),
 			window_size: TEST_WINDOW_SIZE,
+			db_params: None,
 		};
 
 		cache_session_info_test(
@@ -440,6 +622,7 @@ mod tests {
 			earliest_session: start,
 			session_info: (start..=97).map(dummy_session_info).collect(),
 			window_size: TEST_WINDOW_SIZE,
+			db_params: None,
 		};
 
 		cache_session_info_test(
@@ -457,6 +640,7 @@ mod tests {
 			earliest_session: start,
 			session_info: (0..=1).map(dummy_session_info).collect(),
 			window_size: TEST_WINDOW_SIZE,
+			db_params: None,
 		};
 
 		cache_session_info_test(
@@ -474,6 +658,7 @@ mod tests {
 			earliest_session: start,
 			session_info: (0..=1).map(dummy_session_info).collect(),
 			window_size: TEST_WINDOW_SIZE,
+			db_params: None,
 		};
 
 		cache_session_info_test(0, 3, Some(window), 2);
@@ -594,4 +779,79 @@ mod tests {
 
 		futures::executor::block_on(futures::future::join(test_fut, aux_fut));
 	}
+
+	#[test]
+	fn session_info_is_loaded_from_db() {
+		let session: SessionIndex = 10;
+		let start_session = session.saturating_sub(TEST_WINDOW_SIZE.get() - 1);
+
+		let db = kvdb_memorydb::create(1);
+		let db_params = DatabaseParams {
+			db: Arc::new(crate::database::kvdb_impl::DbAdapter::new(db, &[])),
+			db_column: 0,
+		};
+
+		let hash = Hash::repeat_byte(1);
+
+		// `session` is the session of the child, `expect_requests_from` the first session
+		// requested from the runtime.
+		let load_window = |session: SessionIndex, expect_requests_from: SessionIndex| {
+			let pool = TaskExecutor::new();
+			let (mut ctx, mut handle) = make_subsystem_context::<(), _>(pool.clone());
+			let db_params = db_params.clone();
+
+			let test_fut = Box::pin(async move {
+				let window =
+					RollingSessionWindow::new_with_db(&mut ctx, TEST_WINDOW_SIZE, hash, db_params)
+						.await
+						.unwrap();
+
+				let window_start = session.saturating_sub(TEST_WINDOW_SIZE.get() - 1);
+				assert_eq!(window.earliest_session, window_start);
+				assert_eq!(
+					window.session_info,
+					(window_start..=session).map(dummy_session_info).collect::<Vec<_>>(),
+				);
+			});
+
+			let aux_fut = Box::pin(async move {
+				assert_matches!(
+					handle.recv().await,
+					AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+						_,
+						RuntimeApiRequest::SessionIndexForChild(s_tx),
+					)) => {
+						let _ = s_tx.send(Ok(session));
+					}
+				);
+
+				for i in expect_requests_from..=session {
+					assert_matches!(
+						handle.recv().await,
+						AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+							_,
+							RuntimeApiRequest::SessionInfo(j, s_tx),
+						)) => {
+							assert_eq!(i, j);
+							let _ = s_tx.send(Ok(Some(dummy_session_info(i))));
+						}
+					);
+				}
+			});
+
+			futures::executor::block_on(futures::future::join(test_fut, aux_fut));
+		};
+
+		// Nothing persisted yet.
+		load_window(session, start_session);
+		// Everything persisted.
+		load_window(session, session + 1);
+		// Only the new sessions are fetched.
+		load_window(session + 2, session + 1);
+
+		assert!(db_params.load_session_info(start_session).is_none());
+		assert!(db_params.load_session_info(start_session + 1).is_none());
+		assert_eq!(db_params.load_session_info(session), Some(dummy_session_info(session)));
+		assert_eq!(db_params.load_earliest_session(), Some(start_session + 2));
+	}
 }
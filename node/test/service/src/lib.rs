@@ -30,7 +30,7 @@ use sc_network::{
 };
 use sc_service::{
 	config::{DatabaseSource, KeystoreConfig, MultiaddrWithPeerId, WasmExecutionMethod},
-	BasePath, Configuration, KeepBlocks, Role, RpcHandlers, TaskManager,
+	BasePath, Configuration, KeepBlocks, PruningMode, Role, RpcHandlers, TaskManager,
 };
 use selendra_node_primitives::{CollationGenerationConfig, CollatorFn};
 use selendra_node_subsystem::messages::{CollationGenerationMessage, CollatorProtocolMessage};
@@ -39,7 +39,8 @@ use selendra_primitives::v1::{Balance, CollatorPair, HeadData, Id as ParaId, Val
 use selendra_runtime_common::BlockHashCount;
 use selendra_runtime_parachains::paras::ParaGenesisArgs;
 use selendra_service::{
-	ClientHandle, Error, ExecuteWithClient, FullClient, IsCollator, NewFull, PrometheusConfig,
+	ClientHandle, Error, ExecuteWithClient, FullClient, IsCollator, IsolationStrategy, NewFull,
+	PrometheusConfig,
 };
 use selendra_test_runtime::{
 	ParasSudoWrapperCall, Runtime, SignedExtra, SignedPayload, SudoCall, UncheckedExtrinsic,
@@ -94,8 +95,17 @@ pub fn new_full(
 		true,
 		None,
 		None,
-		worker_program_path,
 		false,
+		selendra_service::NewFullParams {
+			isolation_strategy: match worker_program_path {
+				None => IsolationStrategy::default(),
+				Some(binary) => IsolationStrategy::ExternalProcessCustomHost {
+					binary,
+					sandbox: Default::default(),
+				},
+			},
+			..Default::default()
+		},
 		selendra_service::RealOverseerGen,
 	)
 }
@@ -171,7 +181,7 @@ pub fn node_config(
 		database: DatabaseSource::RocksDb { path: root.join("db"), cache_size: 128 },
 		state_cache_size: 16777216,
 		state_cache_child_ratio: None,
-		state_pruning: Default::default(),
+		state_pruning: PruningMode::ArchiveCanonical,
 		keep_blocks: KeepBlocks::All,
 		chain_spec: Box::new(spec),
 		wasm_method: WasmExecutionMethod::Compiled,
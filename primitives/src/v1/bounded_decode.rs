@@ -0,0 +1,285 @@
+// Copyright 2020-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Decoding of candidate commitments from untrusted sources.
+//!
+//! Plain SCALE decoding trusts the length prefixes of the encoded vectors, so an adversarial
+//! encoding can make the decoder allocate far more than any valid candidate needs before the
+//! commitments are ever checked. The decoders here reject lengths above the given limits while
+//! reading them.
+
+use parity_scale_codec::{Compact, Decode, Error, Input};
+use sp_std::prelude::*;
+
+use selendra_parachain::primitives::ValidationResult;
+
+use super::{
+	CandidateCommitments, CandidateDescriptor, CommittedCandidateReceipt, HeadData, Id,
+	OutboundHrmpMessage, UpwardMessage, ValidationCode, MAX_CODE_SIZE, MAX_HEAD_DATA_SIZE,
+	MAX_HRMP_MESSAGE_NUM_PER_CANDIDATE, MAX_HRMP_MESSAGE_SIZE,
+	MAX_UPWARD_MESSAGE_NUM_PER_CANDIDATE, MAX_UPWARD_MESSAGE_SIZE,
+};
+
+/// Upper bounds on the outputs of a candidate enforced while decoding them.
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct CommitmentsLimits {
+	/// The maximum number of upward messages.
+	pub max_upward_messages: u32,
+	/// The maximum size of a single upward message.
+	pub max_upward_message_size: u32,
+	/// The maximum number of horizontal messages.
+	pub max_horizontal_messages: u32,
+	/// The maximum size of the payload of a single horizontal message.
+	pub max_horizontal_message_size: u32,
+	/// The maximum size of the head data.
+	pub max_head_data_size: u32,
+	/// The maximum size of new validation code.
+	pub max_code_size: u32,
+}
+
+impl Default for CommitmentsLimits {
+	/// Limits no candidate accepted by the runtime can exceed, the hard limits on the host
+	/// configuration.
+	fn default() -> Self {
+		Self {
+			max_upward_messages: MAX_UPWARD_MESSAGE_NUM_PER_CANDIDATE,
+			max_upward_message_size: MAX_UPWARD_MESSAGE_SIZE,
+			max_horizontal_messages: MAX_HRMP_MESSAGE_NUM_PER_CANDIDATE,
+			max_horizontal_message_size: MAX_HRMP_MESSAGE_SIZE,
+			max_head_data_size: MAX_HEAD_DATA_SIZE,
+			max_code_size: MAX_CODE_SIZE,
+		}
+	}
+}
+
+impl<N: Decode> CandidateCommitments<N> {
+	/// Decode commitments received from an untrusted source, enforcing `limits`.
+	pub fn decode_bounded<I: Input>(
+		input: &mut I,
+		limits: &CommitmentsLimits,
+	) -> Result<Self, Error> {
+		Ok(Self {
+			upward_messages: decode_upward_messages(input, limits)?,
+			horizontal_messages: decode_horizontal_messages(input, limits)?,
+			new_validation_code: decode_validation_code(input, limits)?,
+			head_data: decode_head_data(input, limits)?,
+			processed_downward_messages: Decode::decode(input)?,
+			hrmp_watermark: Decode::decode(input)?,
+		})
+	}
+}
+
+impl<H: Decode> CommittedCandidateReceipt<H> {
+	/// Decode a receipt received from an untrusted source, enforcing `limits` on its commitments.
+	pub fn decode_bounded<I: Input>(
+		input: &mut I,
+		limits: &CommitmentsLimits,
+	) -> Result<Self, Error> {
+		Ok(Self {
+			descriptor: CandidateDescriptor::decode(input)?,
+			commitments: CandidateCommitments::decode_bounded(input, limits)?,
+		})
+	}
+}
+
+/// Decode the result returned by the validation function of a parachain, enforcing `limits`.
+pub fn decode_bounded_validation_result<I: Input>(
+	input: &mut I,
+	limits: &CommitmentsLimits,
+) -> Result<ValidationResult, Error> {
+	Ok(ValidationResult {
+		head_data: decode_head_data(input, limits)?,
+		new_validation_code: decode_validation_code(input, limits)?,
+		upward_messages: decode_upward_messages(input, limits)?,
+		horizontal_messages: decode_horizontal_messages(input, limits)?,
+		processed_downward_messages: Decode::decode(input)?,
+		hrmp_watermark: Decode::decode(input)?,
+	})
+}
+
+fn decode_upward_messages<I: Input>(
+	input: &mut I,
+	limits: &CommitmentsLimits,
+) -> Result<Vec<UpwardMessage>, Error> {
+	let len = decode_len(input, limits.max_upward_messages, "Too many upward messages")?;
+	(0..len)
+		.map(|_| decode_bytes(input, limits.max_upward_message_size, "Upward message too large"))
+		.collect()
+}
+
+fn decode_horizontal_messages<I: Input>(
+	input: &mut I,
+	limits: &CommitmentsLimits,
+) -> Result<Vec<OutboundHrmpMessage<Id>>, Error> {
+	let len = decode_len(input, limits.max_horizontal_messages, "Too many horizontal messages")?;
+	(0..len)
+		.map(|_| {
+			Ok(OutboundHrmpMessage {
+				recipient: Id::decode(input)?,
+				data: decode_bytes(
+					input,
+					limits.max_horizontal_message_size,
+					"Horizontal message too large",
+				)?,
+			})
+		})
+		.collect()
+}
+
+fn decode_validation_code<I: Input>(
+	input: &mut I,
+	limits: &CommitmentsLimits,
+) -> Result<Option<ValidationCode>, Error> {
+	match input.read_byte()? {
+		0 => Ok(None),
+		1 => decode_bytes(input, limits.max_code_size, "Validation code too large")
+			.map(|code| Some(ValidationCode(code))),
+		_ => Err("Invalid Option variant".into()),
+	}
+}
+
+fn decode_head_data<I: Input>(
+	input: &mut I,
+	limits: &CommitmentsLimits,
+) -> Result<HeadData, Error> {
+	decode_bytes(input, limits.max_head_data_size, "Head data too large").map(HeadData)
+}
+
+/// Decode a length prefix, failing if it exceeds `max`.
+fn decode_len<I: Input>(input: &mut I, max: u32, err: &'static str) -> Result<u32, Error> {
+	let len = <Compact<u32>>::decode(input)?.0;
+	if len > max {
+		return Err(err.into())
+	}
+	Ok(len)
+}
+
+/// Decode a byte vector of at most `max` bytes, allocating only once the input is known to hold
+/// them.
+fn decode_bytes<I: Input>(input: &mut I, max: u32, err: &'static str) -> Result<Vec<u8>, Error> {
+	let len = decode_len(input, max, err)? as usize;
+	if input.remaining_len()?.map_or(false, |remaining| remaining < len) {
+		return Err("Not enough data to fill buffer".into())
+	}
+	let mut bytes = Vec::new();
+	bytes.resize(len, 0);
+	input.read(&mut bytes)?;
+	Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::v1::Hash;
+	use parity_scale_codec::Encode;
+	use primitives::sr25519;
+
+	fn commitments() -> CandidateCommitments {
+		CandidateCommitments {
+			upward_messages: vec![vec![1; 10], vec![2; 20]],
+			horizontal_messages: vec![OutboundHrmpMessage {
+				recipient: 2000.into(),
+				data: vec![3; 30],
+			}],
+			new_validation_code: Some(vec![4; 40].into()),
+			head_data: vec![5; 50].into(),
+			processed_downward_messages: 6,
+			hrmp_watermark: 7,
+		}
+	}
+
+	#[test]
+	fn decodes_like_plain_decoding() {
+		let commitments = commitments();
+		let encoded = commitments.encode();
+
+		assert_eq!(
+			CandidateCommitments::decode_bounded(&mut &encoded[..], &CommitmentsLimits::default())
+				.unwrap(),
+			commitments,
+		);
+
+		let descriptor = CandidateDescriptor {
+			para_id: 2000.into(),
+			relay_parent: Hash::repeat_byte(1),
+			collator: sr25519::Public::from_raw([2; 32]).into(),
+			persisted_validation_data_hash: Hash::repeat_byte(3),
+			pov_hash: Hash::repeat_byte(4),
+			erasure_root: Hash::repeat_byte(5),
+			signature: sr25519::Signature::from_raw([6; 64]).into(),
+			para_head: Hash::repeat_byte(7),
+			validation_code_hash: Hash::repeat_byte(8).into(),
+		};
+		let receipt = CommittedCandidateReceipt { descriptor, commitments: commitments.clone() };
+		let encoded = receipt.encode();
+		assert_eq!(
+			CommittedCandidateReceipt::decode_bounded(
+				&mut &encoded[..],
+				&CommitmentsLimits::default()
+			)
+			.unwrap(),
+			receipt,
+		);
+
+		let result = ValidationResult {
+			head_data: commitments.head_data.clone(),
+			new_validation_code: commitments.new_validation_code.clone(),
+			upward_messages: commitments.upward_messages.clone(),
+			horizontal_messages: commitments.horizontal_messages.clone(),
+			processed_downward_messages: commitments.processed_downward_messages,
+			hrmp_watermark: commitments.hrmp_watermark,
+		};
+		let encoded = result.encode();
+		assert_eq!(
+			decode_bounded_validation_result(&mut &encoded[..], &CommitmentsLimits::default())
+				.unwrap(),
+			result,
+		);
+	}
+
+	#[test]
+	fn rejects_outputs_above_limits() {
+		let encoded = commitments().encode();
+		let limits = CommitmentsLimits::default();
+
+		for limits in [
+			CommitmentsLimits { max_upward_messages: 1, ..limits },
+			CommitmentsLimits { max_upward_message_size: 19, ..limits },
+			CommitmentsLimits { max_horizontal_messages: 0, ..limits },
+			CommitmentsLimits { max_horizontal_message_size: 29, ..limits },
+			CommitmentsLimits { max_code_size: 39, ..limits },
+			CommitmentsLimits { max_head_data_size: 49, ..limits },
+		] {
+			assert!(
+				CandidateCommitments::<u32>::decode_bounded(&mut &encoded[..], &limits).is_err()
+			);
+		}
+	}
+
+	#[test]
+	fn rejects_lengths_beyond_the_input() {
+		// A single upward message claiming to be 50 KiB long, without the data.
+		let mut encoded = Compact(1u32).encode();
+		encoded.extend(Compact(50 * 1024u32).encode());
+
+		assert!(CandidateCommitments::<u32>::decode_bounded(
+			&mut &encoded[..],
+			&CommitmentsLimits::default()
+		)
+		.is_err());
+	}
+}
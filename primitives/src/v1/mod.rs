@@ -63,6 +63,10 @@ pub use sp_staking::SessionIndex;
 mod signed;
 pub use signed::{EncodeAs, Signed, UncheckedSigned};
 
+/// Decoding of candidate commitments from untrusted sources.
+mod bounded_decode;
+pub use bounded_decode::{decode_bounded_validation_result, CommitmentsLimits};
+
 mod metrics;
 pub use metrics::{
 	metric_definitions, RuntimeMetricLabel, RuntimeMetricLabelValue, RuntimeMetricLabelValues,
@@ -261,6 +265,38 @@ pub const MAX_HEAD_DATA_SIZE: u32 = 1 * 1024 * 1024;
 // NOTE: This value is used in the runtime so be careful when changing it.
 pub const MAX_POV_SIZE: u32 = 5 * 1024 * 1024;
 
+/// Maximum size of an upward message we support right now.
+///
+/// Used for:
+/// * checking updates to this stored runtime configuration do not exceed this limit
+/// * bounding the upward messages of candidates decoded from untrusted input
+// NOTE: This value is used in the runtime so be careful when changing it.
+pub const MAX_UPWARD_MESSAGE_SIZE: u32 = 50 * 1024;
+
+/// Maximum number of upward messages a candidate can send we support right now.
+///
+/// Used for:
+/// * checking updates to this stored runtime configuration do not exceed this limit
+/// * bounding the upward messages of candidates decoded from untrusted input
+// NOTE: This value is used in the runtime so be careful when changing it.
+pub const MAX_UPWARD_MESSAGE_NUM_PER_CANDIDATE: u32 = 1024;
+
+/// Maximum size of an HRMP message we support right now.
+///
+/// Used for:
+/// * checking updates to this stored runtime configuration do not exceed this limit
+/// * bounding the horizontal messages of candidates decoded from untrusted input
+// NOTE: This value is used in the runtime so be careful when changing it.
+pub const MAX_HRMP_MESSAGE_SIZE: u32 = 1 * 1024 * 1024;
+
+/// Maximum number of HRMP messages a candidate can send we support right now.
+///
+/// Used for:
+/// * checking updates to this stored runtime configuration do not exceed this limit
+/// * bounding the horizontal messages of candidates decoded from untrusted input
+// NOTE: This value is used in the runtime so be careful when changing it.
+pub const MAX_HRMP_MESSAGE_NUM_PER_CANDIDATE: u32 = 1024;
+
 // The public key of a keypair used by a validator for determining assignments
 /// to approve included parachain candidates.
 mod assignment_app {
@@ -1062,6 +1098,7 @@ pub struct AbridgedHrmpChannel {
 
 /// A possible upgrade restriction that prevents a parachain from performing an upgrade.
 #[derive(Copy, Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(MallocSizeOf))]
 pub enum UpgradeRestriction {
 	/// There is an upgrade restriction and there are no details about its specifics nor how long
 	/// it could last.
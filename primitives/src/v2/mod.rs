@@ -130,9 +130,96 @@ impl PvfCheckStatement {
 	}
 }
 
+/// Constraints on the candidates of a para which can be backed on top of the current relay chain
+/// state.
+#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(MallocSizeOf))]
+pub struct Constraints<N = v1::BlockNumber> {
+	/// The minimum relay-parent number accepted under these constraints.
+	pub min_relay_parent_number: N,
+	/// The maximum size of the proof of validity of a candidate.
+	pub max_pov_size: u32,
+	/// The maximum size of the head data of a candidate.
+	pub max_head_data_size: u32,
+	/// The maximum size of new validation code.
+	pub max_code_size: u32,
+	/// The number of upward messages which can still be queued.
+	pub ump_remaining: u32,
+	/// The total size of upward messages which can still be queued.
+	pub ump_remaining_bytes: u32,
+	/// The maximum number of upward messages a single candidate can send.
+	pub max_ump_num_per_candidate: u32,
+	/// The maximum size of a single upward message.
+	pub max_upward_message_size: u32,
+	/// The blocks at which the messages in the downward message queue were sent, oldest first.
+	pub dmp_remaining_messages: Vec<N>,
+	/// The limitations on the inbound HRMP channels.
+	pub hrmp_inbound: InboundHrmpLimitations<N>,
+	/// The limitations on the outbound HRMP channels, sorted ascending by recipient.
+	pub hrmp_channels_out: Vec<(v1::Id, OutboundHrmpChannelLimitations)>,
+	/// The maximum number of HRMP messages a single candidate can send.
+	pub max_hrmp_num_per_candidate: u32,
+	/// The head data a candidate must build on.
+	pub required_parent: v1::HeadData,
+	/// The hash of the validation code a candidate must be valid under.
+	pub validation_code_hash: v1::ValidationCodeHash,
+	/// Whether the para is currently not allowed to signal a code upgrade.
+	pub upgrade_restriction: Option<v1::UpgradeRestriction>,
+	/// The block at which the pending code upgrade, if any, is applied, and the hash of the new
+	/// validation code.
+	pub future_validation_code: Option<(N, v1::ValidationCodeHash)>,
+}
+
+/// The limitations on the inbound HRMP channels of a para.
+#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(MallocSizeOf))]
+pub struct InboundHrmpLimitations<N = v1::BlockNumber> {
+	/// The blocks the HRMP watermark of the next candidate can be advanced to, sorted ascending.
+	/// Besides these, the watermark can always be advanced to the relay parent.
+	pub valid_watermarks: Vec<N>,
+}
+
+/// The limitations on an outbound HRMP channel.
+#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(MallocSizeOf))]
+pub struct OutboundHrmpChannelLimitations {
+	/// The number of bytes which can still be sent over the channel.
+	pub bytes_remaining: u32,
+	/// The number of messages which can still be sent over the channel.
+	pub messages_remaining: u32,
+	/// The maximum size of a single message sent over the channel.
+	pub max_message_size: u32,
+}
+
+/// A candidate of a para which is pending availability.
+#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(MallocSizeOf))]
+pub struct CandidatePendingAvailability<H = v1::Hash, N = v1::BlockNumber> {
+	/// The hash of the candidate.
+	pub candidate_hash: v1::CandidateHash,
+	/// The descriptor of the candidate.
+	pub descriptor: v1::CandidateDescriptor<H>,
+	/// The commitments of the candidate.
+	pub commitments: v1::CandidateCommitments,
+	/// The number of the relay-parent of the candidate.
+	pub relay_parent_number: N,
+	/// The maximum size of the proof of validity at the relay-parent of the candidate.
+	pub max_pov_size: u32,
+}
+
+/// The state of a para relevant for backing its next candidates.
+#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(MallocSizeOf))]
+pub struct BackingState<H = v1::Hash, N = v1::BlockNumber> {
+	/// The constraints on the next candidates of the para.
+	pub constraints: Constraints<N>,
+	/// The candidates of the para pending availability, oldest first.
+	pub pending_availability: Vec<CandidatePendingAvailability<H, N>>,
+}
+
 sp_api::decl_runtime_apis! {
 	/// The API for querying the state of parachains on-chain.
-	#[api_version(2)]
+	#[api_version(4)]
 	pub trait ParachainHost<H: Encode + Decode = v1::Hash, N: Encode + Decode = v1::BlockNumber> {
 		/// Get the current validators.
 		fn validators() -> Vec<v1::ValidatorId>;
@@ -225,5 +312,30 @@ sp_api::decl_runtime_apis! {
 		/// NOTE: This function is only available since parachain host version 2.
 		fn validation_code_hash(para_id: v1::Id, assumption: v1::OccupiedCoreAssumption)
 			-> Option<v1::ValidationCodeHash>;
+
+		/***** Added in v3 *****/
+
+		/// Get the number of pending messages in the downward message queue for a para.
+		///
+		/// NOTE: This function is only available since parachain host version 3.
+		fn dmq_length(recipient: v1::Id) -> u32;
+
+		/// Get the digest of the channels addressed to the given recipient, i.e. the blocks at
+		/// which it received HRMP messages along with the senders of those messages, sorted
+		/// ascending by block number.
+		///
+		/// NOTE: This function is only available since parachain host version 3.
+		fn hrmp_channel_digests(recipient: v1::Id) -> Vec<(N, Vec<v1::Id>)>;
+
+		/***** Added in v4 *****/
+
+		/// Returns the state of a para relevant for backing its next candidates, i.e. the
+		/// constraints on them and the candidates of the para pending availability.
+		///
+		/// Returns `None` if the para is not registered.
+		///
+		/// NOTE: This function is only available since parachain host version 4. It is a prelude
+		/// to asynchronous backing and may still change.
+		fn staging_para_backing_state(para_id: v1::Id) -> Option<BackingState<H, N>>;
 	}
 }
@@ -33,16 +33,18 @@ use primitives::{
 	v2::SessionInfo,
 };
 use runtime_common::{
-	impls::DealWithFees, paras_registrar, paras_sudo_wrapper, prod_or_fast, slots, BlockHashCount,
-	BlockLength, BlockWeights, CurrencyToVote, OffchainSolutionLengthLimit,
-	OffchainSolutionWeightLimit, RocksDbWeight, SlowAdjustingFeeUpdate,
+	asset_rate, collator_rewards, impls::DealWithFees, offence_chill, paras_registrar,
+	paras_sudo_wrapper, payout_splits, prod_or_fast, slots, BlockHashCount, BlockLength,
+	BlockWeights, CurrencyToVote, OffchainSolutionLengthLimit, OffchainSolutionWeightLimit,
+	RocksDbWeight, SlowAdjustingFeeUpdate,
 };
 use sp_std::{cmp::Ordering, collections::btree_map::BTreeMap, prelude::*};
 
 use runtime_parachains::{
-	configuration as parachains_configuration, disputes as parachains_disputes,
-	dmp as parachains_dmp, hrmp as parachains_hrmp, inclusion as parachains_inclusion,
-	initializer as parachains_initializer, origin as parachains_origin, paras as parachains_paras,
+	assigner_on_demand as parachains_assigner_on_demand, configuration as parachains_configuration,
+	disputes as parachains_disputes, dmp as parachains_dmp, hrmp as parachains_hrmp,
+	inclusion as parachains_inclusion, initializer as parachains_initializer,
+	origin as parachains_origin, paras as parachains_paras,
 	paras_inherent as parachains_paras_inherent, reward_points as parachains_reward_points,
 	runtime_api_impl::v1 as parachains_runtime_api_impl, scheduler as parachains_scheduler,
 	session_info as parachains_session_info, shared as parachains_shared, ump as parachains_ump,
@@ -150,6 +152,16 @@ type MoreThanHalfCouncil = EnsureOneOf<
 	pallet_collective::EnsureProportionMoreThan<AccountId, CouncilCollective, 1, 2>,
 >;
 
+/// Root, two thirds of the council or two thirds of the technical committee, so that a dangerous
+/// referendum can be stopped even when the council cannot gather in time.
+type EmergencyCancelOrigin = EnsureOneOf<
+	EnsureRoot<AccountId>,
+	EnsureOneOf<
+		pallet_collective::EnsureProportionAtLeast<AccountId, CouncilCollective, 2, 3>,
+		pallet_collective::EnsureProportionAtLeast<AccountId, TechnicalCollective, 2, 3>,
+	>,
+>;
+
 parameter_types! {
 	pub const Version: RuntimeVersion = VERSION;
 	pub const SS58Prefix: u16 = 204;
@@ -279,8 +291,11 @@ impl pallet_babe::Config for Runtime {
 
 	type KeyOwnerProofSystem = Historical;
 
-	type HandleEquivocation =
-		pallet_babe::EquivocationHandler<Self::KeyOwnerIdentification, Offences, ReportLongevity>;
+	type HandleEquivocation = pallet_babe::EquivocationHandler<
+		Self::KeyOwnerIdentification,
+		ChillOffenders,
+		ReportLongevity,
+	>;
 
 	type WeightInfo = ();
 
@@ -575,11 +590,10 @@ impl pallet_democracy::Config for Runtime {
 		pallet_collective::EnsureProportionAtLeast<AccountId, TechnicalCollective, 1, 1>;
 	type InstantAllowed = InstantAllowed;
 	type FastTrackVotingPeriod = FastTrackVotingPeriod;
-	// To cancel a proposal which has been passed, 2/3 of the council must agree to it.
-	type CancellationOrigin = EnsureOneOf<
-		EnsureRoot<AccountId>,
-		pallet_collective::EnsureProportionAtLeast<AccountId, CouncilCollective, 2, 3>,
-	>;
+	// To cancel a proposal which has been passed, 2/3 of the council or of the technical
+	// committee must agree to it. Cancelled proposals are recorded in `Cancellations` and can't be
+	// cancelled this way again.
+	type CancellationOrigin = EmergencyCancelOrigin;
 	type BlacklistOrigin = EnsureRoot<AccountId>;
 	// To cancel a proposal before it has been passed, the technical committee must be unanimous or
 	// Root must agree.
@@ -732,6 +746,50 @@ impl pallet_treasury::Config for Runtime {
 	type SpendFunds = Bounties;
 }
 
+impl asset_rate::Config for Runtime {
+	type Event = Event;
+	type RateOrigin = ApproveOrigin;
+	type WeightInfo = weights::runtime_common_asset_rate::WeightInfo<Runtime>;
+}
+
+parameter_types! {
+	/// BABE and GRANDPA equivocations.
+	pub ChillingOffences: Vec<sp_staking::offence::Kind> =
+		vec![*b"babe:equivocatio", *b"grandpa:equivoca"];
+	// Chilled validators sit out as long as it takes to unbond.
+	pub OffenceCooldownPeriod: BlockNumber =
+		BondingDuration::get() * SessionsPerEra::get() * EpochDuration::get() as BlockNumber;
+	pub const OffenceCooldownCheckInterval: BlockNumber = 10 * MINUTES;
+}
+
+/// Reports offences, chilling the offenders of the `ChillingOffences`.
+type ChillOffenders = offence_chill::ChillOffenders<Runtime, Offences>;
+
+parameter_types! {
+	pub const PayoutSplitsPalletId: PalletId = PalletId(*b"py/split");
+	pub const MaxPayoutSplitPayees: u32 = 16;
+}
+
+impl payout_splits::Config for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type Staking = Staking;
+	type PalletId = PayoutSplitsPalletId;
+	type MaxPayees = MaxPayoutSplitPayees;
+	type WeightInfo = weights::runtime_common_payout_splits::WeightInfo<Runtime>;
+}
+
+impl offence_chill::Config for Runtime {
+	type Event = Event;
+	type Validators = Staking;
+	type ChillingOffences = ChillingOffences;
+	type CooldownPeriod = OffenceCooldownPeriod;
+	type CheckInterval = OffenceCooldownCheckInterval;
+	type DisputeOffenders = offence_chill::ParachainValidatorStash<Runtime>;
+	type ForceOrigin = SlashCancelOrigin;
+	type WeightInfo = weights::runtime_common_offence_chill::WeightInfo<Runtime>;
+}
+
 impl pallet_bounties::Config for Runtime {
 	type BountyDepositBase = BountyDepositBase;
 	type BountyDepositPayoutDelay = BountyDepositPayoutDelay;
@@ -801,7 +859,7 @@ impl pallet_grandpa::Config for Runtime {
 
 	type HandleEquivocation = pallet_grandpa::EquivocationHandler<
 		Self::KeyOwnerIdentification,
-		Offences,
+		ChillOffenders,
 		ReportLongevity,
 	>;
 
@@ -937,6 +995,54 @@ impl pallet_recovery::Config for Runtime {
 	type RecoveryDeposit = RecoveryDeposit;
 }
 
+parameter_types! {
+	// Stored data is priced like the length fee of regular transactions, plus a fee per entry.
+	pub TransactionStorageByteFee: Balance = TransactionByteFee::get();
+	pub const TransactionStorageEntryFee: Balance = 10 * CENTS;
+	// Stored data has to be proven to be kept for a week.
+	pub TransactionStoragePeriod: BlockNumber =
+		prod_or_fast!(7 * DAYS, 10 * MINUTES, "SEL_TRANSACTION_STORAGE_PERIOD");
+	pub const MaxBlockTransactions: u32 =
+		pallet_transaction_storage::DEFAULT_MAX_BLOCK_TRANSACTIONS;
+	pub const MaxTransactionSize: u32 = pallet_transaction_storage::DEFAULT_MAX_TRANSACTION_SIZE;
+}
+
+impl pallet_transaction_storage::Config for Runtime {
+	type Event = Event;
+	type Call = Call;
+	type Currency = Balances;
+	type FeeDestination = DealWithFees<Runtime>;
+	type WeightInfo = weights::pallet_transaction_storage::WeightInfo<Runtime>;
+	type MaxBlockTransactions = MaxBlockTransactions;
+	type MaxTransactionSize = MaxTransactionSize;
+}
+
+/// Configures the fees and the storage period of the transaction storage on chains which
+/// didn't do so at genesis, as storing data fails with `NotConfigured` otherwise.
+pub struct InitializeTransactionStorage;
+impl frame_support::traits::OnRuntimeUpgrade for InitializeTransactionStorage {
+	fn on_runtime_upgrade() -> Weight {
+		use frame_support::{
+			storage::{storage_prefix, unhashed},
+			traits::{Get, PalletInfoAccess},
+		};
+
+		let key = |item: &[u8]| storage_prefix(TransactionStorage::name().as_bytes(), item);
+		let db_weight = <Runtime as frame_system::Config>::DbWeight::get();
+
+		let storage_period_key = key(b"StoragePeriod");
+		if unhashed::exists(&storage_period_key) {
+			return db_weight.reads(1)
+		}
+
+		unhashed::put(&key(b"ByteFee"), &TransactionStorageByteFee::get());
+		unhashed::put(&key(b"EntryFee"), &TransactionStorageEntryFee::get());
+		unhashed::put(&storage_period_key, &TransactionStoragePeriod::get());
+
+		db_weight.reads_writes(1, 3)
+	}
+}
+
 parameter_types! {
 	pub const MinVestedTransfer: Balance = 1000 * CENTS;
 }
@@ -1099,6 +1205,19 @@ impl parachains_inclusion::Config for Runtime {
 	type Event = Event;
 	type DisputesHandler = ParasDisputes;
 	type RewardValidators = parachains_reward_points::RewardValidatorsWithEraPoints<Runtime>;
+	type RewardCollators = CollatorRewards;
+}
+
+parameter_types! {
+	pub const CollatorRewardsPalletId: PalletId = PalletId(*b"py/colrw");
+}
+
+impl collator_rewards::Config for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type PalletId = CollatorRewardsPalletId;
+	type RewardOrigin = ApproveOrigin;
+	type WeightInfo = weights::runtime_common_collator_rewards::WeightInfo<Runtime>;
 }
 
 parameter_types! {
@@ -1125,12 +1244,34 @@ impl parachains_ump::Config for Runtime {
 	type WeightInfo = weights::runtime_parachains_ump::WeightInfo<Runtime>;
 }
 
-impl parachains_dmp::Config for Runtime {}
+parameter_types! {
+	pub const DmpQueueHighWatermark: parachains_dmp::QueueWatermark =
+		parachains_dmp::DEFAULT_QUEUE_HIGH_WATERMARK;
+	pub const DmpQueueLowWatermark: parachains_dmp::QueueWatermark =
+		parachains_dmp::DEFAULT_QUEUE_LOW_WATERMARK;
+	pub const DmpQueueCapacity: parachains_dmp::QueueWatermark =
+		parachains_dmp::DEFAULT_QUEUE_CAPACITY;
+}
+
+impl parachains_dmp::Config for Runtime {
+	type Event = Event;
+	type QueueHighWatermark = DmpQueueHighWatermark;
+	type QueueLowWatermark = DmpQueueLowWatermark;
+	type QueueCapacity = DmpQueueCapacity;
+}
+
+parameter_types! {
+	pub const HrmpChannelCloseGracePeriod: BlockNumber = 1 * HOURS;
+	/// Paras from the public range which are treated as system paras for HRMP.
+	pub HrmpSystemParas: Vec<ParaId> = Vec::new();
+}
 
 impl parachains_hrmp::Config for Runtime {
 	type Event = Event;
 	type Origin = Origin;
 	type Currency = Balances;
+	type ChannelCloseGracePeriod = HrmpChannelCloseGracePeriod;
+	type SystemParas = parachains_hrmp::IsSystemPara<HrmpSystemParas>;
 	type WeightInfo = weights::runtime_parachains_hrmp::WeightInfo<Self>;
 }
 
@@ -1140,6 +1281,21 @@ impl parachains_paras_inherent::Config for Runtime {
 
 impl parachains_scheduler::Config for Runtime {}
 
+parameter_types! {
+	pub const OnDemandBaseFee: Balance = 10 * CENTS;
+	pub const OnDemandTargetQueueUtilization: Perbill = Perbill::from_percent(25);
+	pub const OnDemandFeeVariability: Perbill = Perbill::from_percent(3);
+}
+
+impl parachains_assigner_on_demand::Config for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type BaseFee = OnDemandBaseFee;
+	type TargetQueueUtilization = OnDemandTargetQueueUtilization;
+	type FeeVariability = OnDemandFeeVariability;
+	type WeightInfo = weights::runtime_parachains_assigner_on_demand::WeightInfo<Runtime>;
+}
+
 impl parachains_initializer::Config for Runtime {
 	type Randomness = pallet_babe::RandomnessFromOneEpochAgo<Runtime>;
 	type ForceOrigin = EnsureRoot<AccountId>;
@@ -1149,12 +1305,18 @@ impl parachains_initializer::Config for Runtime {
 impl parachains_disputes::Config for Runtime {
 	type Event = Event;
 	type RewardValidators = ();
-	type PunishValidators = ();
+	// Losers of a dispute are slashed by staking and put on a cooldown.
+	type PunishValidators = (
+		parachains_disputes::slashing::SlashValidatorsForDisputes<Runtime, Offences>,
+		OffenceChill,
+	);
 	type WeightInfo = weights::runtime_parachains_disputes::WeightInfo<Runtime>;
 }
 
 parameter_types! {
-	pub const ParaDeposit: Balance = 5 * UNITS;
+	pub const ParaDeposit: Balance = 1 * UNITS;
+	// Held on top of `ParaDeposit` while the para is a parachain.
+	pub const ParaUpgradeDeposit: Balance = 4 * UNITS;
 }
 
 impl paras_registrar::Config for Runtime {
@@ -1167,6 +1329,7 @@ impl paras_registrar::Config for Runtime {
 	type Currency = Balances;
 	type OnSwap = Slots;
 	type ParaDeposit = ParaDeposit;
+	type UpgradeDeposit = ParaUpgradeDeposit;
 	type DataDepositPerByte = DataDepositPerByte;
 	type WeightInfo = weights::runtime_common_paras_registrar::WeightInfo<Runtime>;
 }
@@ -1266,6 +1429,21 @@ construct_runtime! {
 		// Provides a semi-sorted list of nominators for staking.
 		BagsList: pallet_bags_list::{Pallet, Call, Storage, Event<T>} = 39,
 
+		// Conversion rates of foreign assets to CDM.
+		AssetRate: asset_rate::{Pallet, Call, Storage, Event<T>} = 40,
+
+		// Storage of transaction data, proven to be kept for the storage period.
+		TransactionStorage: pallet_transaction_storage::{Pallet, Call, Storage, Inherent, Config<T>, Event<T>} = 41,
+
+		// Chilling validators committing serious offences.
+		OffenceChill: offence_chill::{Pallet, Call, Storage, Event<T>} = 42,
+
+		// Paying collators of included candidates from per-para pots.
+		CollatorRewards: collator_rewards::{Pallet, Call, Storage, Event<T>} = 43,
+
+		// Splitting the staking rewards of validators between several payees.
+		PayoutSplits: payout_splits::{Pallet, Call, Storage, Event<T>} = 44,
+
 		// Parachains pallets. Start indices at 50 to leave room.
 		ParachainsOrigin: parachains_origin::{Pallet, Origin} = 50,
 		Configuration: parachains_configuration::{Pallet, Call, Storage, Config<T>} = 51,
@@ -1275,11 +1453,12 @@ construct_runtime! {
 		ParaScheduler: parachains_scheduler::{Pallet, Storage} = 55,
 		Paras: parachains_paras::{Pallet, Call, Storage, Event, Config} = 56,
 		Initializer: parachains_initializer::{Pallet, Call, Storage} = 57,
-		Dmp: parachains_dmp::{Pallet, Call, Storage} = 58,
+		Dmp: parachains_dmp::{Pallet, Call, Storage, Event} = 58,
 		Ump: parachains_ump::{Pallet, Call, Storage, Event} = 59,
 		Hrmp: parachains_hrmp::{Pallet, Call, Storage, Event<T>, Config} = 60,
 		ParaSessionInfo: parachains_session_info::{Pallet, Storage} = 61,
 		ParasDisputes: parachains_disputes::{Pallet, Call, Storage, Event<T>} = 62,
+		OnDemandAssignment: parachains_assigner_on_demand::{Pallet, Call, Storage, Event<T>} = 64,
 		ParasSudoWrapper: paras_sudo_wrapper::{Pallet, Call} = 63,
 
 		// Parachain Onboarding Pallets. Start indices at 70 to leave room.
@@ -1324,7 +1503,7 @@ pub type Executive = frame_executive::Executive<
 	frame_system::ChainContext<Runtime>,
 	Runtime,
 	AllPalletsWithSystem,
-	(),
+	InitializeTransactionStorage,
 >;
 /// The payload being signed in the transactions.
 pub type SignedPayload = generic::SignedPayload<Call, SignedExtra>;
@@ -1341,6 +1520,11 @@ mod benches {
 		// the that path resolves correctly in the generated file.
 		[runtime_common::slots, Slots]
 		[runtime_common::paras_registrar, Registrar]
+		[runtime_common::asset_rate, AssetRate]
+		[runtime_common::offence_chill, OffenceChill]
+		[runtime_common::collator_rewards, CollatorRewards]
+		[runtime_common::payout_splits, PayoutSplits]
+		[runtime_parachains::assigner_on_demand, OnDemandAssignment]
 		[runtime_parachains::configuration, Configuration]
 		[runtime_parachains::hrmp, Hrmp]
 		[runtime_parachains::disputes, ParasDisputes]
@@ -1372,6 +1556,7 @@ mod benches {
 		[frame_system, SystemBench::<Runtime>]
 		[pallet_timestamp, Timestamp]
 		[pallet_tips, Tips]
+		[pallet_transaction_storage, TransactionStorage]
 		[pallet_treasury, Treasury]
 		[pallet_utility, Utility]
 		[pallet_vesting, Vesting]
@@ -1534,6 +1719,31 @@ sp_api::impl_runtime_apis! {
 		{
 			parachains_runtime_api_impl::validation_code_hash::<Runtime>(para_id, assumption)
 		}
+
+		fn dmq_length(recipient: ParaId) -> u32 {
+			parachains_runtime_api_impl::dmq_length::<Runtime>(recipient)
+		}
+
+		fn hrmp_channel_digests(recipient: ParaId) -> Vec<(BlockNumber, Vec<ParaId>)> {
+			parachains_runtime_api_impl::hrmp_channel_digests::<Runtime>(recipient)
+		}
+
+		fn staging_para_backing_state(
+			para_id: ParaId,
+		) -> Option<primitives::v2::BackingState<Hash, BlockNumber>> {
+			parachains_runtime_api_impl::staging_para_backing_state::<Runtime>(para_id)
+		}
+	}
+
+	impl parachains_configuration::HostConfigurationApi<Block, BlockNumber> for Runtime {
+		fn active_config() -> parachains_configuration::HostConfiguration<BlockNumber> {
+			Configuration::config()
+		}
+
+		fn pending_configs(
+		) -> Vec<(SessionIndex, parachains_configuration::HostConfiguration<BlockNumber>)> {
+			Configuration::pending_configs()
+		}
 	}
 
 	impl beefy_primitives::BeefyApi<Block> for Runtime {
@@ -1681,6 +1891,30 @@ sp_api::impl_runtime_apis! {
 		}
 	}
 
+	impl runtime_common::asset_rate::AssetRateApi<Block> for Runtime {
+		fn conversion_rate(asset: xcm::VersionedMultiLocation) -> Option<sp_runtime::FixedU128> {
+			AssetRate::versioned_conversion_rate(asset)
+		}
+	}
+
+	impl runtime_common::sovereign_accounts::SovereignAccountApi<Block, AccountId, Balance>
+		for Runtime
+	{
+		fn para_sovereign_account(
+			para: ParaId,
+		) -> runtime_common::sovereign_accounts::SovereignAccount<AccountId, Balance> {
+			use runtime_common::sovereign_accounts::{para_account, SovereignAccount};
+			SovereignAccount::of::<Balances>(para_account(para))
+		}
+
+		fn sibling_sovereign_account(
+			para: ParaId,
+		) -> runtime_common::sovereign_accounts::SovereignAccount<AccountId, Balance> {
+			use runtime_common::sovereign_accounts::{sibling_account, SovereignAccount};
+			SovereignAccount::of::<Balances>(sibling_account(para))
+		}
+	}
+
 	impl pallet_transaction_payment_rpc_runtime_api::TransactionPaymentApi<
 		Block,
 		Balance,
@@ -36,11 +36,17 @@ pub mod pallet_session;
 pub mod pallet_staking;
 pub mod pallet_timestamp;
 pub mod pallet_tips;
+pub mod pallet_transaction_storage;
 pub mod pallet_treasury;
 pub mod pallet_utility;
 pub mod pallet_vesting;
+pub mod runtime_common_asset_rate;
+pub mod runtime_common_collator_rewards;
+pub mod runtime_common_offence_chill;
 pub mod runtime_common_paras_registrar;
+pub mod runtime_common_payout_splits;
 pub mod runtime_common_slots;
+pub mod runtime_parachains_assigner_on_demand;
 pub mod runtime_parachains_configuration;
 pub mod runtime_parachains_disputes;
 pub mod runtime_parachains_hrmp;
@@ -0,0 +1,37 @@
+//! Weights for `runtime_common::asset_rate`
+//!
+//! NOT YET GENERATED ON REFERENCE HARDWARE: the base weights are derived from the weights measured
+//! for this runtime of extrinsics with the same storage accesses (`pallet_bounties::propose_curator`),
+//! plus the decoding and conversion of the versioned asset location.
+//! They have to be replaced by the output of the `benchmark` subcommand of the node before the
+//! next runtime release.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::Weight};
+use sp_std::marker::PhantomData;
+
+/// Weight functions for `runtime_common::asset_rate`.
+pub struct WeightInfo<T>(PhantomData<T>);
+impl<T: frame_system::Config> runtime_common::asset_rate::WeightInfo for WeightInfo<T> {
+	// Storage: AssetRate ConversionRateToNative (r:1 w:1)
+	fn create() -> Weight {
+		(11_870_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: AssetRate ConversionRateToNative (r:1 w:1)
+	fn update() -> Weight {
+		(12_431_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: AssetRate ConversionRateToNative (r:1 w:1)
+	fn remove() -> Weight {
+		(12_902_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+}
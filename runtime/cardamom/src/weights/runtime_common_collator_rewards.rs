@@ -0,0 +1,37 @@
+//! Weights for `runtime_common::collator_rewards`
+//!
+//! NOT YET GENERATED ON REFERENCE HARDWARE: the base weights are derived from the weights measured
+//! for this runtime of extrinsics with the same storage accesses (`pallet_balances::transfer` for
+//! the transfers), plus the derivation of the pot account and the event.
+//! They have to be replaced by the output of the `benchmark` subcommand of the node before the
+//! next runtime release.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::Weight};
+use sp_std::marker::PhantomData;
+
+/// Weight functions for `runtime_common::collator_rewards`.
+pub struct WeightInfo<T>(PhantomData<T>);
+impl<T: frame_system::Config> runtime_common::collator_rewards::WeightInfo for WeightInfo<T> {
+	// Storage: CollatorRewards Rewards (r:0 w:1)
+	fn set_reward() -> Weight {
+		(10_241_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: System Account (r:1 w:1)
+	fn top_up() -> Weight {
+		(71_826_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: CollatorRewards Rewards (r:1 w:0)
+	// Storage: System Account (r:2 w:2)
+	fn reward_collator() -> Weight {
+		(73_109_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+}
@@ -0,0 +1,44 @@
+//! Weights for `runtime_common::offence_chill`
+//!
+//! NOT YET GENERATED ON REFERENCE HARDWARE: the base weights are derived from the weights measured
+//! for this runtime of extrinsics with the same storage accesses, `enforce_cooldowns` assuming
+//! every checked validator is chilled again through `pallet_staking::chill`.
+//! They have to be replaced by the output of the `benchmark` subcommand of the node before the
+//! next runtime release.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::Weight};
+use sp_std::marker::PhantomData;
+
+/// Weight functions for `runtime_common::offence_chill`.
+pub struct WeightInfo<T>(PhantomData<T>);
+impl<T: frame_system::Config> runtime_common::offence_chill::WeightInfo for WeightInfo<T> {
+	// Storage: OffenceChill Cooldowns (r:1 w:1)
+	fn lift_cooldown() -> Weight {
+		(13_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: OffenceChill ScheduledChecks (r:1 w:2)
+	// Storage: OffenceChill Cooldowns (r:1 w:1)
+	// Storage: Staking Validators (r:1 w:1)
+	// Storage: Staking Bonded (r:1 w:0)
+	// Storage: Staking Ledger (r:1 w:0)
+	// Storage: Staking Nominators (r:1 w:1)
+	// Storage: Staking CounterForValidators (r:1 w:1)
+	// Storage: BagsList ListNodes (r:2 w:2)
+	// Storage: BagsList ListBags (r:1 w:1)
+	// Storage: BagsList CounterForListNodes (r:1 w:1)
+	fn enforce_cooldowns(n: u32, ) -> Weight {
+		(3_000_000 as Weight)
+			// Standard Error: 0
+			.saturating_add((97_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().reads((10 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes((9 as Weight).saturating_mul(n as Weight)))
+	}
+}
@@ -0,0 +1,472 @@
+// Copyright 2020-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Pallet to maintain the conversion rates of foreign assets to SEL.
+//!
+//! Governance sets, for every foreign asset identified by its location, how many SEL one unit of
+//! the asset is worth. The [`AssetRateTrader`] lets XCM messages pay for execution in any rated
+//! asset, and the rates are published through the [`AssetRateApi`].
+//!
+//! The pallet also implements [`ConvertToNative`] for valuing treasury spends of foreign assets.
+//! The treasury of this runtime only spends SEL, so wiring it up is left until it supports spending
+//! other assets.
+
+use frame_support::{pallet_prelude::*, traits::EnsureOrigin, weights::WeightToFeePolynomial};
+use frame_system::pallet_prelude::*;
+pub use pallet::*;
+use sp_runtime::{traits::Zero, FixedPointNumber, FixedPointOperand, FixedU128};
+use sp_std::{boxed::Box, convert::TryFrom, marker::PhantomData};
+use xcm::{
+	latest::{
+		AssetId::Concrete, Error as XcmError, MultiAsset, MultiLocation, Weight as XcmWeight,
+	},
+	VersionedMultiLocation,
+};
+use xcm_executor::{traits::WeightTrader, Assets};
+
+use crate::traits::ConvertToNative;
+
+pub trait WeightInfo {
+	fn create() -> Weight;
+	fn update() -> Weight;
+	fn remove() -> Weight;
+}
+
+pub struct TestWeightInfo;
+impl WeightInfo for TestWeightInfo {
+	fn create() -> Weight {
+		0
+	}
+	fn update() -> Weight {
+		0
+	}
+	fn remove() -> Weight {
+		0
+	}
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::without_storage_info]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The origin allowed to create, update and remove conversion rates.
+		type RateOrigin: EnsureOrigin<Self::Origin>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A conversion rate was created. [asset, rate]
+		AssetRateCreated(MultiLocation, FixedU128),
+		/// A conversion rate was updated. [asset, old_rate, new_rate]
+		AssetRateUpdated(MultiLocation, FixedU128, FixedU128),
+		/// A conversion rate was removed. [asset]
+		AssetRateRemoved(MultiLocation),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The given asset location cannot be converted into the latest XCM version.
+		BadVersion,
+		/// There is no conversion rate for the given asset.
+		UnknownAssetKind,
+		/// There already is a conversion rate for the given asset.
+		AlreadyExists,
+		/// A conversion rate of zero is not allowed.
+		ZeroRate,
+	}
+
+	/// How many SEL one unit of the asset at the given location is worth.
+	#[pallet::storage]
+	#[pallet::getter(fn conversion_rate)]
+	pub type ConversionRateToNative<T: Config> =
+		StorageMap<_, Blake2_128Concat, MultiLocation, FixedU128>;
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Set the conversion rate of an asset which has no rate yet.
+		///
+		/// Must be called by `RateOrigin`.
+		#[pallet::weight(T::WeightInfo::create())]
+		pub fn create(
+			origin: OriginFor<T>,
+			asset: Box<VersionedMultiLocation>,
+			rate: FixedU128,
+		) -> DispatchResult {
+			T::RateOrigin::ensure_origin(origin)?;
+			ensure!(!rate.is_zero(), Error::<T>::ZeroRate);
+
+			let asset = Self::latest_location(*asset)?;
+			ensure!(!ConversionRateToNative::<T>::contains_key(&asset), Error::<T>::AlreadyExists);
+			ConversionRateToNative::<T>::insert(&asset, rate);
+
+			Self::deposit_event(Event::<T>::AssetRateCreated(asset, rate));
+			Ok(())
+		}
+
+		/// Change the conversion rate of an asset.
+		///
+		/// Must be called by `RateOrigin`.
+		#[pallet::weight(T::WeightInfo::update())]
+		pub fn update(
+			origin: OriginFor<T>,
+			asset: Box<VersionedMultiLocation>,
+			rate: FixedU128,
+		) -> DispatchResult {
+			T::RateOrigin::ensure_origin(origin)?;
+			ensure!(!rate.is_zero(), Error::<T>::ZeroRate);
+
+			let asset = Self::latest_location(*asset)?;
+			let old = ConversionRateToNative::<T>::try_mutate(&asset, |maybe_rate| {
+				let old = maybe_rate.ok_or(Error::<T>::UnknownAssetKind)?;
+				*maybe_rate = Some(rate);
+				Ok::<_, Error<T>>(old)
+			})?;
+
+			Self::deposit_event(Event::<T>::AssetRateUpdated(asset, old, rate));
+			Ok(())
+		}
+
+		/// Remove the conversion rate of an asset.
+		///
+		/// Must be called by `RateOrigin`.
+		#[pallet::weight(T::WeightInfo::remove())]
+		pub fn remove(origin: OriginFor<T>, asset: Box<VersionedMultiLocation>) -> DispatchResult {
+			T::RateOrigin::ensure_origin(origin)?;
+
+			let asset = Self::latest_location(*asset)?;
+			ensure!(
+				ConversionRateToNative::<T>::contains_key(&asset),
+				Error::<T>::UnknownAssetKind
+			);
+			ConversionRateToNative::<T>::remove(&asset);
+
+			Self::deposit_event(Event::<T>::AssetRateRemoved(asset));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	fn latest_location(asset: VersionedMultiLocation) -> Result<MultiLocation, Error<T>> {
+		MultiLocation::try_from(asset).map_err(|()| Error::<T>::BadVersion)
+	}
+
+	/// The conversion rate of the asset at the given versioned location. Used by the runtime API.
+	pub fn versioned_conversion_rate(asset: VersionedMultiLocation) -> Option<FixedU128> {
+		MultiLocation::try_from(asset).ok().and_then(Self::conversion_rate)
+	}
+}
+
+impl<T: Config, Balance: FixedPointOperand> ConvertToNative<Balance> for Pallet<T> {
+	fn to_native(asset: &MultiLocation, amount: Balance) -> Option<Balance> {
+		Self::conversion_rate(asset).map(|rate| rate.saturating_mul_int(amount))
+	}
+
+	fn from_native(asset: &MultiLocation, amount: Balance) -> Option<Balance> {
+		Self::conversion_rate(asset)?
+			.reciprocal()
+			.map(|rate| rate.saturating_mul_int(amount))
+	}
+}
+
+/// Buys execution weight with any fungible asset which has a conversion rate in the pallet.
+///
+/// The fee is computed in SEL using `WeightToFee` and then converted into the paying asset.
+/// Only the first rated asset found in the payment is used; the fees are burnt along with the
+/// holding register.
+pub struct AssetRateTrader<T, WeightToFee> {
+	weight: XcmWeight,
+	paid: Option<(MultiLocation, u128)>,
+	_marker: PhantomData<(T, WeightToFee)>,
+}
+
+impl<T, WeightToFee> WeightTrader for AssetRateTrader<T, WeightToFee>
+where
+	T: Config,
+	WeightToFee: WeightToFeePolynomial<Balance = u128>,
+{
+	fn new() -> Self {
+		Self { weight: 0, paid: None, _marker: PhantomData }
+	}
+
+	fn buy_weight(&mut self, weight: XcmWeight, payment: Assets) -> Result<Assets, XcmError> {
+		log::trace!(target: "xcm::weight", "AssetRateTrader::buy_weight weight: {:?}, payment: {:?}", weight, payment);
+		let native_fee = WeightToFee::calc(&weight);
+
+		let (location, amount) = payment
+			.fungible
+			.keys()
+			.filter_map(|id| match id {
+				Concrete(location) => Some(location),
+				_ => None,
+			})
+			// Once paid, keep charging in the same asset so refunds stay consistent.
+			.filter(|location| self.paid.as_ref().map_or(true, |(paid, _)| paid == *location))
+			.find_map(|location| {
+				Pallet::<T>::from_native(location, native_fee)
+					.map(|amount| (location.clone(), amount))
+			})
+			.ok_or(XcmError::TooExpensive)?;
+
+		let required: MultiAsset = (Concrete(location.clone()), amount).into();
+		let unused = payment.checked_sub(required).map_err(|_| XcmError::TooExpensive)?;
+
+		self.weight = self.weight.saturating_add(weight);
+		let paid = self.paid.get_or_insert((location, 0));
+		paid.1 = paid.1.saturating_add(amount);
+
+		Ok(unused)
+	}
+
+	fn refund_weight(&mut self, weight: XcmWeight) -> Option<MultiAsset> {
+		log::trace!(target: "xcm::weight", "AssetRateTrader::refund_weight weight: {:?}", weight);
+		let (location, paid) = self.paid.as_mut()?;
+
+		let weight = weight.min(self.weight);
+		let native_fee = WeightToFee::calc(&weight);
+		let amount = Pallet::<T>::from_native(location, native_fee)?.min(*paid);
+
+		self.weight -= weight;
+		*paid = paid.saturating_sub(amount);
+
+		if amount > 0 {
+			Some((Concrete(location.clone()), amount).into())
+		} else {
+			None
+		}
+	}
+}
+
+sp_api::decl_runtime_apis! {
+	/// The API to query the conversion rates of foreign assets.
+	pub trait AssetRateApi {
+		/// How many SEL one unit of the asset at the given location is worth, if it has a rate.
+		fn conversion_rate(asset: VersionedMultiLocation) -> Option<FixedU128>;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::asset_rate;
+	use frame_support::{assert_noop, assert_ok, parameter_types, weights::IdentityFee};
+	use frame_system::EnsureRoot;
+	use sp_core::H256;
+	use sp_runtime::{
+		testing::Header,
+		traits::{BlakeTwo256, IdentityLookup},
+		DispatchError::BadOrigin,
+	};
+	use xcm::latest::{Junction::Parachain, Junctions::X1};
+
+	type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+	type Block = frame_system::mocking::MockBlock<Test>;
+
+	frame_support::construct_runtime!(
+		pub enum Test where
+			Block = Block,
+			NodeBlock = Block,
+			UncheckedExtrinsic = UncheckedExtrinsic,
+		{
+			System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+			AssetRate: asset_rate::{Pallet, Call, Storage, Event<T>},
+		}
+	);
+
+	parameter_types! {
+		pub const BlockHashCount: u64 = 250;
+	}
+
+	impl frame_system::Config for Test {
+		type BaseCallFilter = frame_support::traits::Everything;
+		type BlockWeights = ();
+		type BlockLength = ();
+		type DbWeight = ();
+		type Origin = Origin;
+		type Call = Call;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<u64>;
+		type Header = Header;
+		type Event = Event;
+		type BlockHashCount = BlockHashCount;
+		type Version = ();
+		type PalletInfo = PalletInfo;
+		type AccountData = ();
+		type OnNewAccount = ();
+		type OnKilledAccount = ();
+		type SystemWeightInfo = ();
+		type SS58Prefix = ();
+		type OnSetCode = ();
+		type MaxConsumers = frame_support::traits::ConstU32<16>;
+	}
+
+	impl Config for Test {
+		type Event = Event;
+		type RateOrigin = EnsureRoot<u64>;
+		type WeightInfo = TestWeightInfo;
+	}
+
+	type Trader = AssetRateTrader<Test, IdentityFee<u128>>;
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		frame_system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
+	}
+
+	fn asset() -> MultiLocation {
+		MultiLocation::new(0, X1(Parachain(2000)))
+	}
+
+	fn versioned_asset() -> Box<VersionedMultiLocation> {
+		Box::new(asset().into())
+	}
+
+	fn fungible(amount: u128) -> MultiAsset {
+		(Concrete(asset()), amount).into()
+	}
+
+	#[test]
+	fn create_update_and_remove_rates() {
+		new_test_ext().execute_with(|| {
+			let rate = FixedU128::saturating_from_integer(2);
+			assert_noop!(AssetRate::create(Origin::signed(1), versioned_asset(), rate), BadOrigin);
+			assert_noop!(
+				AssetRate::update(Origin::root(), versioned_asset(), rate),
+				Error::<Test>::UnknownAssetKind,
+			);
+
+			assert_ok!(AssetRate::create(Origin::root(), versioned_asset(), rate));
+			assert_eq!(AssetRate::conversion_rate(asset()), Some(rate));
+			assert_noop!(
+				AssetRate::create(Origin::root(), versioned_asset(), rate),
+				Error::<Test>::AlreadyExists,
+			);
+
+			let new_rate = FixedU128::saturating_from_rational(1, 2);
+			assert_ok!(AssetRate::update(Origin::root(), versioned_asset(), new_rate));
+			assert_eq!(AssetRate::versioned_conversion_rate(*versioned_asset()), Some(new_rate));
+
+			assert_ok!(AssetRate::remove(Origin::root(), versioned_asset()));
+			assert_eq!(AssetRate::conversion_rate(asset()), None);
+			assert_noop!(
+				AssetRate::remove(Origin::root(), versioned_asset()),
+				Error::<Test>::UnknownAssetKind,
+			);
+		});
+	}
+
+	#[test]
+	fn zero_rate_is_rejected() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(
+				AssetRate::create(Origin::root(), versioned_asset(), FixedU128::zero()),
+				Error::<Test>::ZeroRate,
+			);
+		});
+	}
+
+	#[test]
+	fn converts_to_and_from_native() {
+		new_test_ext().execute_with(|| {
+			assert_eq!(<AssetRate as ConvertToNative<u128>>::to_native(&asset(), 10), None);
+
+			let rate = FixedU128::saturating_from_integer(4);
+			assert_ok!(AssetRate::create(Origin::root(), versioned_asset(), rate));
+
+			assert_eq!(<AssetRate as ConvertToNative<u128>>::to_native(&asset(), 10), Some(40));
+			assert_eq!(<AssetRate as ConvertToNative<u128>>::from_native(&asset(), 40), Some(10));
+		});
+	}
+
+	#[test]
+	fn trader_charges_rated_assets() {
+		new_test_ext().execute_with(|| {
+			let rate = FixedU128::saturating_from_integer(10);
+			let payment = Assets::from(fungible(100));
+
+			// Without a rate, the asset cannot pay for execution.
+			let mut trader = Trader::new();
+			assert_eq!(trader.buy_weight(500, payment.clone()), Err(XcmError::TooExpensive));
+
+			assert_ok!(AssetRate::create(Origin::root(), versioned_asset(), rate));
+
+			let mut trader = Trader::new();
+			let unused = trader.buy_weight(500, payment).unwrap();
+			assert_eq!(unused, Assets::from(fungible(50)));
+
+			assert_eq!(trader.refund_weight(200), Some(fungible(20)));
+			// Cannot refund more than what was bought.
+			assert_eq!(trader.refund_weight(1_000), Some(fungible(30)));
+			assert_eq!(trader.refund_weight(1_000), None);
+		});
+	}
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking {
+	use super::{Pallet as AssetRate, *};
+	use frame_benchmarking::benchmarks;
+	use xcm::latest::{Junction::Parachain, Junctions::X1};
+
+	fn asset() -> Box<VersionedMultiLocation> {
+		Box::new(MultiLocation::new(0, X1(Parachain(2000))).into())
+	}
+
+	benchmarks! {
+		create {
+			let origin = T::RateOrigin::successful_origin();
+		}: _<T::Origin>(origin, asset(), FixedU128::saturating_from_integer(2))
+		verify {
+			assert!(AssetRate::<T>::versioned_conversion_rate(*asset()).is_some());
+		}
+
+		update {
+			let origin = T::RateOrigin::successful_origin();
+			AssetRate::<T>::create(origin.clone(), asset(), FixedU128::saturating_from_integer(2))?;
+		}: _<T::Origin>(origin, asset(), FixedU128::saturating_from_integer(3))
+		verify {
+			assert_eq!(
+				AssetRate::<T>::versioned_conversion_rate(*asset()),
+				Some(FixedU128::saturating_from_integer(3)),
+			);
+		}
+
+		remove {
+			let origin = T::RateOrigin::successful_origin();
+			AssetRate::<T>::create(origin.clone(), asset(), FixedU128::saturating_from_integer(2))?;
+		}: _<T::Origin>(origin, asset())
+		verify {
+			assert!(AssetRate::<T>::versioned_conversion_rate(*asset()).is_none());
+		}
+	}
+}
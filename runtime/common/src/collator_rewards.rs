@@ -0,0 +1,438 @@
+// Copyright 2020-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Pallet paying collators for getting their candidates included.
+//!
+//! Every para has a pot, an account derived from the pallet id, which anyone can top up. Whenever
+//! a candidate of the para is included, the collator that produced it is paid the reward set for
+//! the para from its pot. The collator is identified by the collator id of the candidate receipt,
+//! whose public key doubles as its account.
+
+use frame_support::{
+	pallet_prelude::*,
+	traits::{Currency, EnsureOrigin, ExistenceRequirement},
+	PalletId,
+};
+use frame_system::pallet_prelude::*;
+pub use pallet::*;
+use primitives::v1::{CollatorId, Id as ParaId};
+use runtime_parachains::inclusion::RewardCollators;
+use sp_runtime::traits::{AccountIdConversion, Zero};
+
+type BalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+pub trait WeightInfo {
+	fn set_reward() -> Weight;
+	fn top_up() -> Weight;
+	fn reward_collator() -> Weight;
+}
+
+pub struct TestWeightInfo;
+impl WeightInfo for TestWeightInfo {
+	fn set_reward() -> Weight {
+		0
+	}
+	fn top_up() -> Weight {
+		0
+	}
+	fn reward_collator() -> Weight {
+		0
+	}
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::without_storage_info]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The currency the rewards are paid in.
+		type Currency: Currency<Self::AccountId>;
+
+		/// The id the pot accounts of the paras are derived from.
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+
+		/// The origin allowed to set the reward of a para.
+		type RewardOrigin: EnsureOrigin<Self::Origin>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// The reward per included candidate of a para was set. [para, reward]
+		RewardSet(ParaId, BalanceOf<T>),
+		/// The pot of a para was topped up. [para, who, amount]
+		PotToppedUp(ParaId, T::AccountId, BalanceOf<T>),
+		/// A collator was paid for an included candidate. [para, collator, reward]
+		CollatorRewarded(ParaId, T::AccountId, BalanceOf<T>),
+		/// The pot of a para could not pay the reward of a collator. [para, collator]
+		RewardNotPaid(ParaId, T::AccountId),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// Topping up with nothing.
+		ZeroAmount,
+	}
+
+	/// The reward paid to the collator of every included candidate of the para.
+	#[pallet::storage]
+	#[pallet::getter(fn reward)]
+	pub type Rewards<T: Config> = StorageMap<_, Twox64Concat, ParaId, BalanceOf<T>, ValueQuery>;
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Set the reward paid to the collator of every included candidate of `para`.
+		///
+		/// A reward of zero stops paying the collators of the para.
+		///
+		/// Must be called by `RewardOrigin`.
+		#[pallet::weight(T::WeightInfo::set_reward())]
+		pub fn set_reward(
+			origin: OriginFor<T>,
+			para: ParaId,
+			reward: BalanceOf<T>,
+		) -> DispatchResult {
+			T::RewardOrigin::ensure_origin(origin)?;
+
+			if reward.is_zero() {
+				Rewards::<T>::remove(para);
+			} else {
+				Rewards::<T>::insert(para, reward);
+			}
+
+			Self::deposit_event(Event::<T>::RewardSet(para, reward));
+			Ok(())
+		}
+
+		/// Move `amount` from the caller into the pot paying the collators of `para`.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		#[pallet::weight(T::WeightInfo::top_up())]
+		pub fn top_up(
+			origin: OriginFor<T>,
+			para: ParaId,
+			#[pallet::compact] amount: BalanceOf<T>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!amount.is_zero(), Error::<T>::ZeroAmount);
+
+			T::Currency::transfer(
+				&who,
+				&Self::pot_account(para),
+				amount,
+				ExistenceRequirement::KeepAlive,
+			)?;
+
+			Self::deposit_event(Event::<T>::PotToppedUp(para, who, amount));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// The account holding the funds paying the collators of `para`.
+	pub fn pot_account(para: ParaId) -> T::AccountId {
+		T::PalletId::get().into_sub_account(para)
+	}
+
+	/// The account of a collator, which shares the public key of its collator id.
+	fn collator_account(collator: &CollatorId) -> Option<T::AccountId> {
+		let public: &[u8] = collator.as_ref();
+		T::AccountId::decode(&mut &public[..]).ok()
+	}
+}
+
+impl<T: Config> RewardCollators for Pallet<T> {
+	fn reward_collator(para: ParaId, collator: &CollatorId) -> Weight {
+		let reward = Self::reward(para);
+		if reward.is_zero() {
+			return T::DbWeight::get().reads(1)
+		}
+
+		let collator = match Self::collator_account(collator) {
+			Some(account) => account,
+			None => return T::DbWeight::get().reads(1),
+		};
+
+		match T::Currency::transfer(
+			&Self::pot_account(para),
+			&collator,
+			reward,
+			ExistenceRequirement::AllowDeath,
+		) {
+			Ok(()) => Self::deposit_event(Event::<T>::CollatorRewarded(para, collator, reward)),
+			Err(_) => Self::deposit_event(Event::<T>::RewardNotPaid(para, collator)),
+		}
+
+		T::WeightInfo::reward_collator()
+	}
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking {
+	use super::{Pallet as CollatorRewards, *};
+	use frame_benchmarking::{account, benchmarks};
+	use frame_system::RawOrigin;
+	use sp_runtime::traits::Bounded;
+
+	fn para() -> ParaId {
+		ParaId::from(2000)
+	}
+
+	benchmarks! {
+		set_reward {
+			let origin = T::RewardOrigin::successful_origin();
+			let reward = T::Currency::minimum_balance();
+		}: _<T::Origin>(origin, para(), reward)
+		verify {
+			assert_eq!(CollatorRewards::<T>::reward(para()), reward);
+		}
+
+		top_up {
+			let caller: T::AccountId = account("caller", 0, 0);
+			T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+			let amount = T::Currency::minimum_balance();
+		}: _(RawOrigin::Signed(caller), para(), amount)
+		verify {
+			assert_eq!(T::Currency::free_balance(&CollatorRewards::<T>::pot_account(para())), amount);
+		}
+
+		reward_collator {
+			let collator = CollatorId::from(sp_core::sr25519::Public::from_raw([1; 32]));
+			let reward = T::Currency::minimum_balance();
+			Rewards::<T>::insert(para(), reward);
+			T::Currency::make_free_balance_be(
+				&CollatorRewards::<T>::pot_account(para()),
+				BalanceOf::<T>::max_value(),
+			);
+		}: {
+			CollatorRewards::<T>::reward_collator(para(), &collator);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::collator_rewards;
+	use frame_support::{assert_noop, assert_ok, parameter_types};
+	use frame_system::EnsureRoot;
+	use sp_core::{sr25519, H256};
+	use sp_runtime::{
+		testing::Header,
+		traits::{BlakeTwo256, IdentityLookup},
+		DispatchError::BadOrigin,
+	};
+
+	type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+	type Block = frame_system::mocking::MockBlock<Test>;
+
+	frame_support::construct_runtime!(
+		pub enum Test where
+			Block = Block,
+			NodeBlock = Block,
+			UncheckedExtrinsic = UncheckedExtrinsic,
+		{
+			System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+			Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+			CollatorRewards: collator_rewards::{Pallet, Call, Storage, Event<T>},
+		}
+	);
+
+	parameter_types! {
+		pub const BlockHashCount: u64 = 250;
+	}
+
+	impl frame_system::Config for Test {
+		type BaseCallFilter = frame_support::traits::Everything;
+		type BlockWeights = ();
+		type BlockLength = ();
+		type DbWeight = ();
+		type Origin = Origin;
+		type Call = Call;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<u64>;
+		type Header = Header;
+		type Event = Event;
+		type BlockHashCount = BlockHashCount;
+		type Version = ();
+		type PalletInfo = PalletInfo;
+		type AccountData = pallet_balances::AccountData<u64>;
+		type OnNewAccount = ();
+		type OnKilledAccount = ();
+		type SystemWeightInfo = ();
+		type SS58Prefix = ();
+		type OnSetCode = ();
+		type MaxConsumers = frame_support::traits::ConstU32<16>;
+	}
+
+	parameter_types! {
+		pub const ExistentialDeposit: u64 = 1;
+	}
+
+	impl pallet_balances::Config for Test {
+		type Balance = u64;
+		type DustRemoval = ();
+		type Event = Event;
+		type ExistentialDeposit = ExistentialDeposit;
+		type AccountStore = System;
+		type MaxLocks = ();
+		type MaxReserves = ();
+		type ReserveIdentifier = [u8; 8];
+		type WeightInfo = ();
+	}
+
+	parameter_types! {
+		pub const CollatorRewardsPalletId: PalletId = PalletId(*b"py/colrw");
+	}
+
+	impl Config for Test {
+		type Event = Event;
+		type Currency = Balances;
+		type PalletId = CollatorRewardsPalletId;
+		type RewardOrigin = EnsureRoot<u64>;
+		type WeightInfo = TestWeightInfo;
+	}
+
+	const PARA: ParaId = ParaId::new(2000);
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+		pallet_balances::GenesisConfig::<Test> { balances: vec![(1, 100), (2, 100)] }
+			.assimilate_storage(&mut t)
+			.unwrap();
+		let mut ext = sp_io::TestExternalities::new(t);
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+
+	fn collator() -> CollatorId {
+		CollatorId::from(sr25519::Public::from_raw([7; 32]))
+	}
+
+	fn collator_account() -> u64 {
+		u64::from_le_bytes([7; 8])
+	}
+
+	fn pot() -> u64 {
+		CollatorRewards::pot_account(PARA)
+	}
+
+	#[test]
+	fn set_reward_works() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(CollatorRewards::set_reward(Origin::signed(1), PARA, 10), BadOrigin);
+
+			assert_ok!(CollatorRewards::set_reward(Origin::root(), PARA, 10));
+			assert_eq!(CollatorRewards::reward(PARA), 10);
+			System::assert_last_event(Event::CollatorRewards(
+				crate::collator_rewards::Event::RewardSet(PARA, 10),
+			));
+
+			assert_ok!(CollatorRewards::set_reward(Origin::root(), PARA, 0));
+			assert!(!Rewards::<Test>::contains_key(PARA));
+		});
+	}
+
+	#[test]
+	fn top_up_works() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(
+				CollatorRewards::top_up(Origin::signed(1), PARA, 0),
+				Error::<Test>::ZeroAmount
+			);
+			assert_noop!(
+				CollatorRewards::top_up(Origin::signed(1), PARA, 100),
+				pallet_balances::Error::<Test>::KeepAlive
+			);
+
+			assert_ok!(CollatorRewards::top_up(Origin::signed(1), PARA, 50));
+			assert_ok!(CollatorRewards::top_up(Origin::signed(2), PARA, 20));
+			assert_eq!(Balances::free_balance(pot()), 70);
+			assert_eq!(Balances::free_balance(1), 50);
+			System::assert_last_event(Event::CollatorRewards(
+				crate::collator_rewards::Event::PotToppedUp(PARA, 2, 20),
+			));
+
+			// Every para has its own pot.
+			assert_eq!(Balances::free_balance(CollatorRewards::pot_account(ParaId::from(2001))), 0);
+		});
+	}
+
+	#[test]
+	fn included_candidates_pay_their_collator() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(CollatorRewards::top_up(Origin::signed(1), PARA, 50));
+			assert_ok!(CollatorRewards::set_reward(Origin::root(), PARA, 20));
+
+			CollatorRewards::reward_collator(PARA, &collator());
+			CollatorRewards::reward_collator(PARA, &collator());
+
+			assert_eq!(Balances::free_balance(collator_account()), 40);
+			assert_eq!(Balances::free_balance(pot()), 10);
+			System::assert_last_event(Event::CollatorRewards(
+				crate::collator_rewards::Event::CollatorRewarded(PARA, collator_account(), 20),
+			));
+		});
+	}
+
+	#[test]
+	fn exhausted_pot_pays_nothing() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(CollatorRewards::top_up(Origin::signed(1), PARA, 10));
+			assert_ok!(CollatorRewards::set_reward(Origin::root(), PARA, 20));
+
+			CollatorRewards::reward_collator(PARA, &collator());
+
+			assert_eq!(Balances::free_balance(collator_account()), 0);
+			assert_eq!(Balances::free_balance(pot()), 10);
+			System::assert_last_event(Event::CollatorRewards(
+				crate::collator_rewards::Event::RewardNotPaid(PARA, collator_account()),
+			));
+		});
+	}
+
+	#[test]
+	fn paras_without_reward_pay_nothing() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(CollatorRewards::top_up(Origin::signed(1), PARA, 50));
+			let events = System::events().len();
+
+			CollatorRewards::reward_collator(PARA, &collator());
+
+			assert_eq!(Balances::free_balance(pot()), 50);
+			assert_eq!(System::events().len(), events);
+		});
+	}
+}
@@ -18,14 +18,21 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+pub mod asset_rate;
 pub mod assigned_slots;
+pub mod auctions;
+pub mod collator_rewards;
+pub mod crowdloan;
 pub mod elections;
 pub mod impls;
+pub mod offence_chill;
 pub mod paras_registrar;
 pub mod paras_sudo_wrapper;
+pub mod payout_splits;
 pub mod purchase;
 pub mod slot_range;
 pub mod slots;
+pub mod sovereign_accounts;
 pub mod traits;
 pub mod xcm_sender;
 
@@ -0,0 +1,677 @@
+// Copyright 2020-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Pallet to chill validators committing serious offences.
+//!
+//! Validators reported for one of the [`Config::ChillingOffences`], or found on the losing side
+//! of a concluded dispute, are chilled and put on a cooldown of [`Config::CooldownPeriod`] blocks.
+//! Every [`Config::CheckInterval`] blocks, validators declaring their intention to validate during
+//! their cooldown are chilled again. The checks are indexed by block number, so only the
+//! validators due at a block are looked at. Governance can lift a cooldown early.
+//!
+//! Offences are seen by wrapping the offence reporter of the runtime with [`ChillOffenders`],
+//! disputes by using the pallet as the `PunishValidators` of the disputes pallet.
+
+use frame_support::{pallet_prelude::*, traits::EnsureOrigin};
+use frame_system::{pallet_prelude::*, RawOrigin};
+pub use pallet::*;
+use primitives::v1::{SessionIndex, ValidatorIndex};
+use runtime_parachains::{disputes::PunishValidators, session_info};
+use sp_runtime::traits::{Convert, One, Saturating};
+use sp_staking::offence::{Kind, Offence, OffenceError, ReportOffence};
+use sp_std::{marker::PhantomData, prelude::*};
+
+pub trait WeightInfo {
+	fn lift_cooldown() -> Weight;
+	fn enforce_cooldowns(n: u32) -> Weight;
+}
+
+pub struct TestWeightInfo;
+impl WeightInfo for TestWeightInfo {
+	fn lift_cooldown() -> Weight {
+		0
+	}
+	fn enforce_cooldowns(_n: u32) -> Weight {
+		0
+	}
+}
+
+/// Access to the validators of the staking system.
+pub trait ValidatorChilling<AccountId> {
+	/// Whether the stash intends to validate.
+	fn is_validator(stash: &AccountId) -> bool;
+
+	/// Chill the stash, so it isn't considered in the next elections.
+	fn chill(stash: &AccountId);
+}
+
+impl<T: pallet_staking::Config> ValidatorChilling<T::AccountId> for pallet_staking::Pallet<T> {
+	fn is_validator(stash: &T::AccountId) -> bool {
+		pallet_staking::Validators::<T>::contains_key(stash)
+	}
+
+	fn chill(stash: &T::AccountId) {
+		if let Some(controller) = pallet_staking::Bonded::<T>::get(stash) {
+			let _ = pallet_staking::Pallet::<T>::chill(RawOrigin::Signed(controller).into());
+		}
+	}
+}
+
+/// Why a validator was chilled.
+#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub enum ChillReason {
+	/// The validator was reported for an offence of the given kind.
+	Offence(Kind),
+	/// The validator backed or approved an invalid candidate in the given session.
+	DisputeForInvalid(SessionIndex),
+	/// The validator disputed a valid candidate in the given session.
+	DisputeAgainstValid(SessionIndex),
+}
+
+/// The cooldown of a chilled validator.
+#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct Cooldown<BlockNumber> {
+	/// The block from which the validator can validate again.
+	pub until: BlockNumber,
+	/// The block at which the validator is checked next, see [`ScheduledChecks`].
+	pub next_check: BlockNumber,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::without_storage_info]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The staking system the validators are chilled in.
+		type Validators: ValidatorChilling<Self::AccountId>;
+
+		/// The offence kinds for which the offenders are chilled.
+		type ChillingOffences: Get<Vec<Kind>>;
+
+		/// The number of blocks a chilled validator can't validate.
+		///
+		/// A validator chilled at block `n` can validate again from block `n + CooldownPeriod`.
+		#[pallet::constant]
+		type CooldownPeriod: Get<Self::BlockNumber>;
+
+		/// The number of blocks between two checks of a validator on a cooldown.
+		///
+		/// A validator declaring its intention to validate during its cooldown is chilled again
+		/// at its next check.
+		#[pallet::constant]
+		type CheckInterval: Get<Self::BlockNumber>;
+
+		/// Finds the stash of the validator with the given index in a session, for disputes.
+		type DisputeOffenders: Convert<(SessionIndex, ValidatorIndex), Option<Self::AccountId>>;
+
+		/// The origin allowed to lift a cooldown early.
+		type ForceOrigin: EnsureOrigin<Self::Origin>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A validator was chilled for an offence. [stash, reason, cooldown_until]
+		Chilled(T::AccountId, ChillReason, T::BlockNumber),
+		/// A validator declared its intention to validate during its cooldown and was chilled
+		/// again. [stash, cooldown_until]
+		ChilledDuringCooldown(T::AccountId, T::BlockNumber),
+		/// The cooldown of a validator was lifted by governance. [stash]
+		CooldownLifted(T::AccountId),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The given stash has no cooldown.
+		NotCoolingDown,
+	}
+
+	/// The cooldowns of chilled validators.
+	#[pallet::storage]
+	#[pallet::getter(fn cooldown)]
+	pub type Cooldowns<T: Config> =
+		StorageMap<_, Twox64Concat, T::AccountId, Cooldown<T::BlockNumber>>;
+
+	/// The validators on a cooldown to check at a block.
+	///
+	/// Entries of validators whose cooldown was lifted, or whose [`Cooldown::next_check`] is
+	/// another block, are stale and skipped.
+	#[pallet::storage]
+	pub type ScheduledChecks<T: Config> =
+		StorageMap<_, Twox64Concat, T::BlockNumber, Vec<T::AccountId>, ValueQuery>;
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			Self::enforce_cooldowns(now)
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Lift the cooldown of a chilled validator, so it can validate again right away.
+		///
+		/// Must be called by `ForceOrigin`.
+		#[pallet::weight(T::WeightInfo::lift_cooldown())]
+		pub fn lift_cooldown(origin: OriginFor<T>, stash: T::AccountId) -> DispatchResult {
+			T::ForceOrigin::ensure_origin(origin)?;
+			ensure!(Cooldowns::<T>::contains_key(&stash), Error::<T>::NotCoolingDown);
+			Cooldowns::<T>::remove(&stash);
+
+			Self::deposit_event(Event::<T>::CooldownLifted(stash));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Chill the stash and put it on a cooldown, extending the cooldown it might already have.
+	pub fn chill(stash: T::AccountId, reason: ChillReason) {
+		let now = frame_system::Pallet::<T>::block_number();
+		let until = now.saturating_add(T::CooldownPeriod::get());
+		let until = match Cooldowns::<T>::get(&stash) {
+			// The scheduled check keeps rescheduling itself until the extended end.
+			Some(mut cooldown) => {
+				cooldown.until = cooldown.until.max(until);
+				Cooldowns::<T>::insert(&stash, &cooldown);
+				cooldown.until
+			},
+			None => {
+				Self::schedule_check(&stash, now, until);
+				until
+			},
+		};
+		T::Validators::chill(&stash);
+
+		log::info!(
+			target: "runtime::offence-chill",
+			"Chilled validator {:?} until block {:?} for {:?}",
+			stash,
+			until,
+			reason,
+		);
+		Self::deposit_event(Event::<T>::Chilled(stash, reason, until));
+	}
+
+	/// Check the validators scheduled for the block: chill the ones which declared their intention
+	/// to validate during their cooldown and forget the cooldowns which are over.
+	fn enforce_cooldowns(now: T::BlockNumber) -> Weight {
+		let stashes = ScheduledChecks::<T>::take(now);
+
+		for stash in &stashes {
+			let cooldown = match Cooldowns::<T>::get(stash) {
+				Some(cooldown) if cooldown.next_check == now => cooldown,
+				_ => continue,
+			};
+
+			if cooldown.until <= now {
+				Cooldowns::<T>::remove(stash);
+				continue
+			}
+
+			if T::Validators::is_validator(stash) {
+				T::Validators::chill(stash);
+				Self::deposit_event(Event::<T>::ChilledDuringCooldown(
+					stash.clone(),
+					cooldown.until,
+				));
+			}
+			Self::schedule_check(stash, now, cooldown.until);
+		}
+
+		T::WeightInfo::enforce_cooldowns(stashes.len() as u32)
+	}
+
+	/// Schedule the next check of a cooldown ending at `until`, at the latest when it is over.
+	fn schedule_check(stash: &T::AccountId, now: T::BlockNumber, until: T::BlockNumber) {
+		let interval = T::CheckInterval::get().max(One::one());
+		let next_check = now.saturating_add(interval).min(until);
+		Cooldowns::<T>::insert(stash, Cooldown { until, next_check });
+		ScheduledChecks::<T>::append(next_check, stash);
+	}
+
+	fn chill_dispute_offenders(
+		session: SessionIndex,
+		validators: impl IntoIterator<Item = ValidatorIndex>,
+		reason: ChillReason,
+	) {
+		for validator in validators {
+			match T::DisputeOffenders::convert((session, validator)) {
+				Some(stash) => Self::chill(stash, reason.clone()),
+				None => log::warn!(
+					target: "runtime::offence-chill",
+					"Unable to find the stash of validator {:?} in session {}",
+					validator,
+					session,
+				),
+			}
+		}
+	}
+}
+
+impl<T: Config> PunishValidators for Pallet<T> {
+	fn punish_for_invalid(
+		session: SessionIndex,
+		validators: impl IntoIterator<Item = ValidatorIndex>,
+	) {
+		Self::chill_dispute_offenders(session, validators, ChillReason::DisputeForInvalid(session))
+	}
+
+	fn punish_against_valid(
+		session: SessionIndex,
+		validators: impl IntoIterator<Item = ValidatorIndex>,
+	) {
+		Self::chill_dispute_offenders(
+			session,
+			validators,
+			ChillReason::DisputeAgainstValid(session),
+		)
+	}
+
+	fn punish_inconclusive(_: SessionIndex, _: impl IntoIterator<Item = ValidatorIndex>) {}
+}
+
+/// Reports offences to `R` and chills the offenders of the [`Config::ChillingOffences`].
+///
+/// Only offences accepted by `R` are acted on, so duplicate reports don't extend cooldowns.
+pub struct ChillOffenders<T, R>(PhantomData<(T, R)>);
+
+impl<T, R, Reporter, Identification, O> ReportOffence<Reporter, (T::AccountId, Identification), O>
+	for ChillOffenders<T, R>
+where
+	T: Config,
+	R: ReportOffence<Reporter, (T::AccountId, Identification), O>,
+	O: Offence<(T::AccountId, Identification)>,
+{
+	fn report_offence(reporters: Vec<Reporter>, offence: O) -> Result<(), OffenceError> {
+		let offenders = if T::ChillingOffences::get().contains(&O::ID) {
+			offence.offenders()
+		} else {
+			Vec::new()
+		};
+
+		R::report_offence(reporters, offence)?;
+
+		for (stash, _) in offenders {
+			Pallet::<T>::chill(stash, ChillReason::Offence(O::ID));
+		}
+
+		Ok(())
+	}
+
+	fn is_known_offence(
+		offenders: &[(T::AccountId, Identification)],
+		time_slot: &O::TimeSlot,
+	) -> bool {
+		R::is_known_offence(offenders, time_slot)
+	}
+}
+
+/// Finds the stash of a parachain validator in the validator set of the session.
+///
+/// Uses the account keys recorded by the session info pallet for the session, so validators which
+/// changed their keys or left the validator set since are still found.
+pub struct ParachainValidatorStash<T>(PhantomData<T>);
+
+impl<T: session_info::Config> Convert<(SessionIndex, ValidatorIndex), Option<T::AccountId>>
+	for ParachainValidatorStash<T>
+{
+	fn convert((session, index): (SessionIndex, ValidatorIndex)) -> Option<T::AccountId> {
+		session_info::Pallet::<T>::account_keys(session)?.get(index.0 as usize).cloned()
+	}
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking {
+	use super::{Pallet as OffenceChill, *};
+	use frame_benchmarking::{account, benchmarks};
+	use sp_runtime::traits::Bounded;
+
+	benchmarks! {
+		lift_cooldown {
+			let stash: T::AccountId = account("stash", 0, 0);
+			let until = T::BlockNumber::max_value();
+			Cooldowns::<T>::insert(&stash, Cooldown { until, next_check: until });
+			let origin = T::ForceOrigin::successful_origin();
+		}: _<T::Origin>(origin, stash.clone())
+		verify {
+			assert!(OffenceChill::<T>::cooldown(&stash).is_none());
+		}
+
+		enforce_cooldowns {
+			let n in 0 .. 100;
+			let now = frame_system::Pallet::<T>::block_number();
+			for i in 0 .. n {
+				let stash: T::AccountId = account("stash", i, 0);
+				let until = T::BlockNumber::max_value();
+				Cooldowns::<T>::insert(&stash, Cooldown { until, next_check: now });
+				ScheduledChecks::<T>::append(now, stash);
+			}
+		}: {
+			OffenceChill::<T>::enforce_cooldowns(now);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::offence_chill;
+	use frame_support::{assert_noop, assert_ok, parameter_types};
+	use frame_system::EnsureRoot;
+	use sp_core::H256;
+	use sp_runtime::{
+		testing::Header,
+		traits::{BlakeTwo256, IdentityLookup},
+		DispatchError::BadOrigin,
+		Perbill,
+	};
+	use std::{cell::RefCell, collections::BTreeSet};
+
+	type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+	type Block = frame_system::mocking::MockBlock<Test>;
+
+	frame_support::construct_runtime!(
+		pub enum Test where
+			Block = Block,
+			NodeBlock = Block,
+			UncheckedExtrinsic = UncheckedExtrinsic,
+		{
+			System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+			OffenceChill: offence_chill::{Pallet, Call, Storage, Event<T>},
+		}
+	);
+
+	parameter_types! {
+		pub const BlockHashCount: u64 = 250;
+	}
+
+	impl frame_system::Config for Test {
+		type BaseCallFilter = frame_support::traits::Everything;
+		type BlockWeights = ();
+		type BlockLength = ();
+		type DbWeight = ();
+		type Origin = Origin;
+		type Call = Call;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<u64>;
+		type Header = Header;
+		type Event = Event;
+		type BlockHashCount = BlockHashCount;
+		type Version = ();
+		type PalletInfo = PalletInfo;
+		type AccountData = ();
+		type OnNewAccount = ();
+		type OnKilledAccount = ();
+		type SystemWeightInfo = ();
+		type SS58Prefix = ();
+		type OnSetCode = ();
+		type MaxConsumers = frame_support::traits::ConstU32<16>;
+	}
+
+	const EQUIVOCATION: Kind = *b"test:equivocatio";
+	const OFFLINE: Kind = *b"test:offline____";
+
+	thread_local! {
+		static VALIDATORS: RefCell<BTreeSet<u64>> = RefCell::new((1..=5).collect());
+	}
+
+	pub struct MockValidators;
+	impl ValidatorChilling<u64> for MockValidators {
+		fn is_validator(stash: &u64) -> bool {
+			VALIDATORS.with(|v| v.borrow().contains(stash))
+		}
+
+		fn chill(stash: &u64) {
+			VALIDATORS.with(|v| v.borrow_mut().remove(stash));
+		}
+	}
+
+	fn validate(stash: u64) {
+		VALIDATORS.with(|v| v.borrow_mut().insert(stash));
+	}
+
+	fn run_to_block(n: u64) {
+		while System::block_number() < n {
+			let b = System::block_number() + 1;
+			System::set_block_number(b);
+			OffenceChill::on_initialize(b);
+		}
+	}
+
+	/// The stash of the validator with index `i` in a session is `i + 1`.
+	pub struct MockDisputeOffenders;
+	impl Convert<(SessionIndex, ValidatorIndex), Option<u64>> for MockDisputeOffenders {
+		fn convert((_, index): (SessionIndex, ValidatorIndex)) -> Option<u64> {
+			Some(index.0 as u64 + 1)
+		}
+	}
+
+	parameter_types! {
+		pub ChillingOffences: Vec<Kind> = vec![EQUIVOCATION];
+		pub const CooldownPeriod: u64 = 30;
+		pub const CheckInterval: u64 = 10;
+	}
+
+	impl Config for Test {
+		type Event = Event;
+		type Validators = MockValidators;
+		type ChillingOffences = ChillingOffences;
+		type CooldownPeriod = CooldownPeriod;
+		type CheckInterval = CheckInterval;
+		type DisputeOffenders = MockDisputeOffenders;
+		type ForceOrigin = EnsureRoot<u64>;
+		type WeightInfo = TestWeightInfo;
+	}
+
+	macro_rules! test_offence {
+		($name:ident, $kind:expr) => {
+			struct $name(Vec<(u64, ())>);
+
+			impl Offence<(u64, ())> for $name {
+				const ID: Kind = $kind;
+				type TimeSlot = u64;
+
+				fn offenders(&self) -> Vec<(u64, ())> {
+					self.0.clone()
+				}
+				fn session_index(&self) -> SessionIndex {
+					0
+				}
+				fn validator_set_count(&self) -> u32 {
+					5
+				}
+				fn time_slot(&self) -> u64 {
+					0
+				}
+				fn slash_fraction(_: u32, _: u32) -> Perbill {
+					Perbill::zero()
+				}
+			}
+		};
+	}
+
+	test_offence!(Equivocation, EQUIVOCATION);
+	test_offence!(Offline, OFFLINE);
+
+	/// Accepts every offence, except the ones of stash 5 which are always known already.
+	struct MockReporter;
+	impl<O: Offence<(u64, ())>> ReportOffence<u64, (u64, ()), O> for MockReporter {
+		fn report_offence(_: Vec<u64>, offence: O) -> Result<(), OffenceError> {
+			if offence.offenders().iter().any(|(stash, _)| *stash == 5) {
+				return Err(OffenceError::DuplicateReport)
+			}
+			Ok(())
+		}
+
+		fn is_known_offence(_: &[(u64, ())], _: &O::TimeSlot) -> bool {
+			false
+		}
+	}
+
+	type Reporter = ChillOffenders<Test, MockReporter>;
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let mut ext: sp_io::TestExternalities =
+			frame_system::GenesisConfig::default().build_storage::<Test>().unwrap().into();
+		ext.execute_with(|| {
+			System::set_block_number(1);
+			VALIDATORS.with(|v| *v.borrow_mut() = (1..=5).collect());
+		});
+		ext
+	}
+
+	#[test]
+	fn offenders_of_chilling_offences_are_chilled() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Reporter::report_offence(vec![], Offline(vec![(1, ())])));
+			assert!(MockValidators::is_validator(&1));
+			assert_eq!(OffenceChill::cooldown(1), None);
+
+			assert_ok!(Reporter::report_offence(vec![], Equivocation(vec![(2, ())])));
+			assert!(!MockValidators::is_validator(&2));
+			assert_eq!(OffenceChill::cooldown(2).map(|c| c.until), Some(31));
+			System::assert_last_event(Event::OffenceChill(crate::offence_chill::Event::Chilled(
+				2,
+				ChillReason::Offence(EQUIVOCATION),
+				31,
+			)));
+
+			// Offences not accepted by the reporter are ignored.
+			assert_noop!(
+				Reporter::report_offence(vec![], Equivocation(vec![(5, ())])),
+				OffenceError::DuplicateReport,
+			);
+			assert!(MockValidators::is_validator(&5));
+		});
+	}
+
+	#[test]
+	fn dispute_offenders_are_chilled() {
+		new_test_ext().execute_with(|| {
+			OffenceChill::punish_inconclusive(1, vec![ValidatorIndex(0)]);
+			OffenceChill::punish_against_valid(1, vec![ValidatorIndex(1)]);
+			OffenceChill::punish_for_invalid(1, vec![ValidatorIndex(2)]);
+
+			assert!(MockValidators::is_validator(&1));
+			assert_eq!(OffenceChill::cooldown(1), None);
+			assert!(!MockValidators::is_validator(&2));
+			assert_eq!(OffenceChill::cooldown(2).map(|c| c.until), Some(31));
+			assert!(!MockValidators::is_validator(&3));
+			assert_eq!(OffenceChill::cooldown(3).map(|c| c.until), Some(31));
+		});
+	}
+
+	#[test]
+	fn validators_are_chilled_until_the_cooldown_is_over() {
+		new_test_ext().execute_with(|| {
+			OffenceChill::chill(1, ChillReason::Offence(EQUIVOCATION));
+			assert_eq!(OffenceChill::cooldown(1), Some(Cooldown { until: 31, next_check: 11 }));
+
+			// Validating again is only noticed at the next check.
+			run_to_block(5);
+			validate(1);
+			run_to_block(10);
+			assert!(MockValidators::is_validator(&1));
+			run_to_block(11);
+			assert!(!MockValidators::is_validator(&1));
+			System::assert_last_event(Event::OffenceChill(
+				crate::offence_chill::Event::ChilledDuringCooldown(1, 31),
+			));
+			assert_eq!(OffenceChill::cooldown(1), Some(Cooldown { until: 31, next_check: 21 }));
+
+			run_to_block(30);
+			assert!(OffenceChill::cooldown(1).is_some());
+			run_to_block(31);
+			assert_eq!(OffenceChill::cooldown(1), None);
+			assert!(ScheduledChecks::<Test>::iter().next().is_none());
+
+			validate(1);
+			run_to_block(50);
+			assert!(MockValidators::is_validator(&1));
+		});
+	}
+
+	#[test]
+	fn cooldowns_are_extended_with_a_single_check() {
+		new_test_ext().execute_with(|| {
+			OffenceChill::chill(1, ChillReason::Offence(EQUIVOCATION));
+			run_to_block(6);
+			OffenceChill::chill(1, ChillReason::DisputeAgainstValid(1));
+			assert_eq!(OffenceChill::cooldown(1), Some(Cooldown { until: 36, next_check: 11 }));
+			assert_eq!(ScheduledChecks::<Test>::iter().collect::<Vec<_>>(), vec![(11, vec![1])]);
+
+			run_to_block(31);
+			assert_eq!(OffenceChill::cooldown(1), Some(Cooldown { until: 36, next_check: 36 }));
+			run_to_block(36);
+			assert_eq!(OffenceChill::cooldown(1), None);
+		});
+	}
+
+	#[test]
+	fn governance_can_lift_cooldowns() {
+		new_test_ext().execute_with(|| {
+			OffenceChill::chill(1, ChillReason::Offence(EQUIVOCATION));
+
+			assert_noop!(OffenceChill::lift_cooldown(Origin::signed(1), 1), BadOrigin);
+			assert_noop!(
+				OffenceChill::lift_cooldown(Origin::root(), 2),
+				Error::<Test>::NotCoolingDown,
+			);
+
+			assert_ok!(OffenceChill::lift_cooldown(Origin::root(), 1));
+			assert_eq!(OffenceChill::cooldown(1), None);
+			System::assert_last_event(Event::OffenceChill(
+				crate::offence_chill::Event::CooldownLifted(1),
+			));
+
+			validate(1);
+			run_to_block(11);
+			assert!(MockValidators::is_validator(&1));
+		});
+	}
+
+	#[test]
+	fn stale_checks_are_skipped() {
+		new_test_ext().execute_with(|| {
+			OffenceChill::chill(1, ChillReason::Offence(EQUIVOCATION));
+			run_to_block(3);
+			assert_ok!(OffenceChill::lift_cooldown(Origin::root(), 1));
+			OffenceChill::chill(1, ChillReason::Offence(EQUIVOCATION));
+
+			// The check of the lifted cooldown is skipped, without scheduling another one.
+			run_to_block(11);
+			assert_eq!(OffenceChill::cooldown(1), Some(Cooldown { until: 33, next_check: 13 }));
+			assert_eq!(ScheduledChecks::<Test>::iter().collect::<Vec<_>>(), vec![(13, vec![1])]);
+		});
+	}
+}
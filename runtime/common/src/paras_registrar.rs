@@ -37,7 +37,7 @@ pub use pallet::*;
 use parity_scale_codec::{Decode, Encode};
 use scale_info::TypeInfo;
 use sp_runtime::{
-	traits::{CheckedSub, Saturating},
+	traits::{CheckedSub, Saturating, Zero},
 	RuntimeDebug,
 };
 
@@ -119,6 +119,11 @@ pub mod pallet {
 		#[pallet::constant]
 		type ParaDeposit: Get<BalanceOf<Self>>;
 
+		/// The deposit held from the manager, on top of `ParaDeposit`, while the para is a
+		/// parachain. It is reserved when the para is upgraded and returned when it is downgraded.
+		#[pallet::constant]
+		type UpgradeDeposit: Get<BalanceOf<Self>>;
+
 		/// The deposit to be paid per byte stored on chain.
 		#[pallet::constant]
 		type DataDepositPerByte: Get<BalanceOf<Self>>;
@@ -180,6 +185,12 @@ pub mod pallet {
 	pub type Paras<T: Config> =
 		StorageMap<_, Twox64Concat, ParaId, ParaInfo<T::AccountId, BalanceOf<T>>>;
 
+	/// The `UpgradeDeposit` held for each para upgraded to a parachain, and the account it is held
+	/// from.
+	#[pallet::storage]
+	pub type UpgradeDeposits<T: Config> =
+		StorageMap<_, Twox64Concat, ParaId, (T::AccountId, BalanceOf<T>)>;
+
 	/// The next free `ParaId`.
 	#[pallet::storage]
 	pub type NextFreeParaId<T> = StorageValue<_, ParaId, ValueQuery>;
@@ -272,7 +283,8 @@ pub mod pallet {
 		/// The `ParaId`s remain mapped to the same head data and code so external code can rely on
 		/// `ParaId` to be a long-term identifier of a notional "parachain". However, their
 		/// scheduling info (i.e. whether they're a parathread or parachain), auction information
-		/// and the auction deposit are switched.
+		/// and the auction deposit are switched. When a parathread swaps with a parachain, its
+		/// manager takes over holding the `UpgradeDeposit`.
 		#[pallet::weight(<T as Config>::WeightInfo::swap())]
 		pub fn swap(origin: OriginFor<T>, id: ParaId, other: ParaId) -> DispatchResult {
 			Self::ensure_origin_para_or_owner(origin, id)?;
@@ -295,11 +307,11 @@ pub mod pallet {
 				if id_lifecycle == ParaLifecycle::Parachain &&
 					other_lifecycle == ParaLifecycle::Parathread
 				{
-					Self::do_thread_and_chain_swap(id, other);
+					Self::do_thread_and_chain_swap(id, other)?;
 				} else if id_lifecycle == ParaLifecycle::Parathread &&
 					other_lifecycle == ParaLifecycle::Parachain
 				{
-					Self::do_thread_and_chain_swap(other, id);
+					Self::do_thread_and_chain_swap(other, id)?;
 				} else if id_lifecycle == ParaLifecycle::Parachain &&
 					other_lifecycle == ParaLifecycle::Parachain
 				{
@@ -406,14 +418,19 @@ impl<T: Config> Registrar for Pallet<T> {
 	}
 
 	// Upgrade a registered parathread into a parachain.
+	//
+	// Fails if the manager of the para cannot pay the `UpgradeDeposit`.
 	fn make_parachain(id: ParaId) -> DispatchResult {
 		// Para backend should think this is a parathread...
 		ensure!(
 			paras::Pallet::<T>::lifecycle(id) == Some(ParaLifecycle::Parathread),
 			Error::<T>::NotParathread
 		);
-		runtime_parachains::schedule_parathread_upgrade::<T>(id)
-			.map_err(|_| Error::<T>::CannotUpgrade)?;
+		Self::reserve_upgrade_deposit(id)?;
+		if runtime_parachains::schedule_parathread_upgrade::<T>(id).is_err() {
+			Self::unreserve_upgrade_deposit(id);
+			return Err(Error::<T>::CannotUpgrade.into())
+		}
 		// Once a para has upgraded to a parachain, it can no longer be managed by the owner.
 		// Intentionally, the flag stays with the para even after downgrade.
 		Self::apply_lock(id);
@@ -429,6 +446,7 @@ impl<T: Config> Registrar for Pallet<T> {
 		);
 		runtime_parachains::schedule_parachain_downgrade::<T>(id)
 			.map_err(|_| Error::<T>::CannotDowngrade)?;
+		Self::unreserve_upgrade_deposit(id);
 		Ok(())
 	}
 
@@ -551,6 +569,7 @@ impl<T: Config> Pallet<T> {
 		if let Some(info) = Paras::<T>::take(&id) {
 			<T as Config>::Currency::unreserve(&info.manager, info.deposit);
 		}
+		Self::unreserve_upgrade_deposit(id);
 
 		PendingSwap::<T>::remove(id);
 		Self::deposit_event(Event::<T>::Deregistered(id));
@@ -582,12 +601,39 @@ impl<T: Config> Pallet<T> {
 	}
 
 	/// Swap a parachain and parathread, which involves scheduling an appropriate lifecycle update.
-	fn do_thread_and_chain_swap(to_downgrade: ParaId, to_upgrade: ParaId) {
+	///
+	/// Fails if the manager of `to_upgrade` cannot pay the `UpgradeDeposit`.
+	fn do_thread_and_chain_swap(to_downgrade: ParaId, to_upgrade: ParaId) -> DispatchResult {
+		Self::reserve_upgrade_deposit(to_upgrade)?;
+		Self::unreserve_upgrade_deposit(to_downgrade);
 		let res1 = runtime_parachains::schedule_parachain_downgrade::<T>(to_downgrade);
 		debug_assert!(res1.is_ok());
 		let res2 = runtime_parachains::schedule_parathread_upgrade::<T>(to_upgrade);
 		debug_assert!(res2.is_ok());
 		T::OnSwap::on_swap(to_upgrade, to_downgrade);
+		Ok(())
+	}
+
+	/// Reserve the `UpgradeDeposit` from the manager of a para about to become a parachain.
+	///
+	/// Paras without a manager, such as those registered at genesis, don't hold the deposit.
+	fn reserve_upgrade_deposit(id: ParaId) -> DispatchResult {
+		let deposit = T::UpgradeDeposit::get();
+		if deposit.is_zero() || UpgradeDeposits::<T>::contains_key(id) {
+			return Ok(())
+		}
+		if let Some(info) = Paras::<T>::get(id) {
+			<T as Config>::Currency::reserve(&info.manager, deposit)?;
+			UpgradeDeposits::<T>::insert(id, (info.manager, deposit));
+		}
+		Ok(())
+	}
+
+	/// Return the `UpgradeDeposit` held for a para that stops being a parachain.
+	fn unreserve_upgrade_deposit(id: ParaId) {
+		if let Some((who, deposit)) = UpgradeDeposits::<T>::take(id) {
+			<T as Config>::Currency::unreserve(&who, deposit);
+		}
 	}
 }
 
@@ -713,6 +759,7 @@ mod tests {
 
 	parameter_types! {
 		pub const ParaDeposit: Balance = 10;
+		pub const UpgradeDeposit: Balance = 100;
 		pub const DataDepositPerByte: Balance = 1;
 		pub const MaxRetries: u32 = 3;
 	}
@@ -724,6 +771,7 @@ mod tests {
 		type Currency = Balances;
 		type OnSwap = MockSwap;
 		type ParaDeposit = ParaDeposit;
+		type UpgradeDeposit = UpgradeDeposit;
 		type DataDepositPerByte = DataDepositPerByte;
 		type WeightInfo = TestWeightInfo;
 	}
@@ -1074,6 +1122,93 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn upgrade_deposit_is_held_while_parachain() {
+		new_test_ext().execute_with(|| {
+			run_to_block(1);
+			let para_id = LOWEST_PUBLIC_ID;
+			assert_ok!(Registrar::reserve(Origin::signed(1)));
+			assert_ok!(Registrar::register(
+				Origin::signed(1),
+				para_id,
+				test_genesis_head(32),
+				test_validation_code(32),
+			));
+			run_to_session(2);
+
+			let para_deposit = <Test as Config>::ParaDeposit::get() +
+				64 * <Test as Config>::DataDepositPerByte::get();
+			let upgrade_deposit = <Test as Config>::UpgradeDeposit::get();
+			assert_eq!(Balances::reserved_balance(&1), para_deposit);
+
+			// Upgrading reserves the upgrade deposit...
+			assert_ok!(Registrar::make_parachain(para_id));
+			assert_eq!(Balances::reserved_balance(&1), para_deposit + upgrade_deposit);
+			assert_eq!(UpgradeDeposits::<Test>::get(para_id), Some((1, upgrade_deposit)));
+			run_to_session(4);
+			assert!(Parachains::is_parachain(para_id));
+
+			// ... and downgrading, e.g. once the lease ended, returns it.
+			assert_ok!(Registrar::make_parathread(para_id));
+			assert_eq!(Balances::reserved_balance(&1), para_deposit);
+			assert_eq!(UpgradeDeposits::<Test>::get(para_id), None);
+			run_to_session(6);
+			assert!(Parachains::is_parathread(para_id));
+
+			// A manager who cannot pay the upgrade deposit cannot upgrade.
+			let free = Balances::free_balance(&1);
+			assert_ok!(Balances::reserve(&1, free - upgrade_deposit + 1));
+			assert_noop!(
+				Registrar::make_parachain(para_id),
+				BalancesError::<Test, _>::InsufficientBalance
+			);
+
+			// The path back is open once the manager can pay again.
+			Balances::unreserve(&1, free - upgrade_deposit + 1);
+			assert_ok!(Registrar::make_parachain(para_id));
+			run_to_session(8);
+			assert!(Parachains::is_parachain(para_id));
+		});
+	}
+
+	#[test]
+	fn swap_moves_upgrade_deposit() {
+		new_test_ext().execute_with(|| {
+			let para_1 = LOWEST_PUBLIC_ID;
+			let para_2 = LOWEST_PUBLIC_ID + 1;
+			assert_ok!(Registrar::reserve(Origin::signed(1)));
+			assert_ok!(Registrar::register(
+				Origin::signed(1),
+				para_1,
+				test_genesis_head(32),
+				test_validation_code(32),
+			));
+			assert_ok!(Registrar::reserve(Origin::signed(2)));
+			assert_ok!(Registrar::register(
+				Origin::signed(2),
+				para_2,
+				test_genesis_head(32),
+				test_validation_code(32),
+			));
+			run_to_session(2);
+
+			let para_deposit = <Test as Config>::ParaDeposit::get() +
+				64 * <Test as Config>::DataDepositPerByte::get();
+			let upgrade_deposit = <Test as Config>::UpgradeDeposit::get();
+
+			assert_ok!(Registrar::make_parachain(para_1));
+			run_to_session(4);
+
+			assert_ok!(Registrar::swap(para_origin(para_1), para_1, para_2));
+			assert_ok!(Registrar::swap(para_origin(para_2), para_2, para_1));
+
+			assert_eq!(Balances::reserved_balance(&1), para_deposit);
+			assert_eq!(Balances::reserved_balance(&2), para_deposit + upgrade_deposit);
+			assert_eq!(UpgradeDeposits::<Test>::get(para_1), None);
+			assert_eq!(UpgradeDeposits::<Test>::get(para_2), Some((2, upgrade_deposit)));
+		});
+	}
+
 	#[test]
 	fn para_lock_works() {
 		new_test_ext().execute_with(|| {
@@ -62,6 +62,8 @@ pub mod pallet {
 		CannotUpgrade,
 		/// Cannot downgrade parachain.
 		CannotDowngrade,
+		/// A DMP message couldn't be sent because the downward message queue of the para is full.
+		ExceedsQueueCapacity,
 	}
 
 	#[pallet::hooks]
@@ -142,6 +144,8 @@ pub mod pallet {
 			{
 				dmp::QueueDownwardMessageError::ExceedsMaxMessageSize =>
 					Error::<T>::ExceedsMaxMessageSize.into(),
+				dmp::QueueDownwardMessageError::ExceedsQueueCapacity =>
+					Error::<T>::ExceedsQueueCapacity.into(),
 			})
 		}
 
@@ -0,0 +1,715 @@
+// Copyright 2020-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Pallet splitting the staking rewards of validators between several payees.
+//!
+//! A validator registers up to [`Config::MaxPayees`] payees with their shares of its rewards.
+//! The staking rewards of the validator are then paid into an account derived from its stash,
+//! and split from there whenever its rewards are paid out through [`Pallet::payout_stakers`].
+//! Rewards paid out directly through staking are split by the next payout, or by anyone calling
+//! [`Pallet::distribute`]. Whatever the shares leave over goes to the stash. Removing the split
+//! pays the rewards where they were paid before it was set.
+
+use frame_support::{
+	dispatch::DispatchResultWithPostInfo,
+	pallet_prelude::*,
+	traits::{Currency, ExistenceRequirement},
+	PalletId,
+};
+use frame_system::{pallet_prelude::*, RawOrigin};
+pub use pallet::*;
+use sp_runtime::{
+	traits::{AccountIdConversion, Zero},
+	Perbill,
+};
+use sp_staking::EraIndex;
+use sp_std::prelude::*;
+
+type BalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+type PayeeOf<T> =
+	<<T as Config>::Staking as PayoutStaking<<T as frame_system::Config>::AccountId>>::Payee;
+
+pub trait WeightInfo {
+	fn set_payout_split(n: u32) -> Weight;
+	fn remove_payout_split(n: u32) -> Weight;
+	fn distribute(n: u32) -> Weight;
+}
+
+pub struct TestWeightInfo;
+impl WeightInfo for TestWeightInfo {
+	fn set_payout_split(_n: u32) -> Weight {
+		0
+	}
+	fn remove_payout_split(_n: u32) -> Weight {
+		0
+	}
+	fn distribute(_n: u32) -> Weight {
+		0
+	}
+}
+
+/// Access to the reward payouts of the staking system.
+pub trait PayoutStaking<AccountId> {
+	/// Where the rewards of a stash are paid to.
+	type Payee: Parameter;
+
+	/// The stash bonded by the controller, if any.
+	fn stash_of(controller: &AccountId) -> Option<AccountId>;
+
+	/// Where the rewards of `stash` are paid to.
+	fn payee(stash: &AccountId) -> Self::Payee;
+
+	/// The payee paying the rewards to `account`.
+	fn account_payee(account: AccountId) -> Self::Payee;
+
+	/// Pay the rewards of the stash bonded by `controller` to `payee`.
+	fn set_payee(controller: AccountId, payee: Self::Payee) -> DispatchResult;
+
+	/// Pay out the rewards of the validator `stash` and its nominators for `era`.
+	fn payout_stakers(
+		who: AccountId,
+		stash: AccountId,
+		era: EraIndex,
+	) -> DispatchResultWithPostInfo;
+
+	/// The weight of paying out the rewards of a validator.
+	fn payout_stakers_weight() -> Weight;
+
+	/// Bond `stash` with `controller`.
+	#[cfg(feature = "runtime-benchmarks")]
+	fn bond(stash: AccountId, controller: AccountId) -> DispatchResult;
+}
+
+impl<T: pallet_staking::Config> PayoutStaking<T::AccountId> for pallet_staking::Pallet<T> {
+	type Payee = pallet_staking::RewardDestination<T::AccountId>;
+
+	fn stash_of(controller: &T::AccountId) -> Option<T::AccountId> {
+		pallet_staking::Ledger::<T>::get(controller).map(|ledger| ledger.stash)
+	}
+
+	fn payee(stash: &T::AccountId) -> Self::Payee {
+		pallet_staking::Payee::<T>::get(stash)
+	}
+
+	fn account_payee(account: T::AccountId) -> Self::Payee {
+		pallet_staking::RewardDestination::Account(account)
+	}
+
+	fn set_payee(controller: T::AccountId, payee: Self::Payee) -> DispatchResult {
+		pallet_staking::Pallet::<T>::set_payee(RawOrigin::Signed(controller).into(), payee)
+	}
+
+	fn payout_stakers(
+		who: T::AccountId,
+		stash: T::AccountId,
+		era: EraIndex,
+	) -> DispatchResultWithPostInfo {
+		pallet_staking::Pallet::<T>::payout_stakers(RawOrigin::Signed(who).into(), stash, era)
+	}
+
+	fn payout_stakers_weight() -> Weight {
+		use pallet_staking::WeightInfo as _;
+		<T as pallet_staking::Config>::WeightInfo::payout_stakers_alive_staked(
+			<T as pallet_staking::Config>::MaxNominatorRewardedPerValidator::get(),
+		)
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn bond(stash: T::AccountId, controller: T::AccountId) -> DispatchResult {
+		use sp_runtime::traits::StaticLookup;
+
+		let value = <T as pallet_staking::Config>::Currency::minimum_balance() * 10u32.into();
+		<T as pallet_staking::Config>::Currency::make_free_balance_be(&stash, value * 10u32.into());
+		pallet_staking::Pallet::<T>::bond(
+			RawOrigin::Signed(stash).into(),
+			T::Lookup::unlookup(controller),
+			value,
+			pallet_staking::RewardDestination::Staked,
+		)
+	}
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::without_storage_info]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The currency the staking rewards are paid in.
+		type Currency: Currency<Self::AccountId>;
+
+		/// The staking system paying the rewards.
+		type Staking: PayoutStaking<Self::AccountId>;
+
+		/// The id the accounts collecting the rewards to split are derived from.
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+
+		/// The maximum number of payees a validator can split its rewards between.
+		#[pallet::constant]
+		type MaxPayees: Get<u32>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A validator split its rewards between the payees. [stash, payees]
+		PayoutSplitSet(T::AccountId, Vec<(T::AccountId, Perbill)>),
+		/// A validator stopped splitting its rewards. [stash]
+		PayoutSplitRemoved(T::AccountId),
+		/// The collected rewards of a validator were split. [stash, amount]
+		RewardsSplit(T::AccountId, BalanceOf<T>),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The caller doesn't control a bonded stash.
+		NotController,
+		/// A split needs at least one payee.
+		NoPayees,
+		/// More payees than `MaxPayees`.
+		TooManyPayees,
+		/// A payee is given more than once.
+		DuplicatePayee,
+		/// The shares add up to more than the whole reward.
+		SharesExceedWhole,
+		/// The stash doesn't split its rewards.
+		NoPayoutSplit,
+	}
+
+	/// The payees a validator stash splits its rewards between, with their shares.
+	#[pallet::storage]
+	#[pallet::getter(fn payout_split)]
+	pub type PayoutSplits<T: Config> =
+		StorageMap<_, Twox64Concat, T::AccountId, Vec<(T::AccountId, Perbill)>>;
+
+	/// Where the rewards of a validator stash splitting them were paid to before the split was
+	/// set.
+	#[pallet::storage]
+	#[pallet::getter(fn previous_payee)]
+	pub type PreviousPayees<T: Config> = StorageMap<_, Twox64Concat, T::AccountId, PayeeOf<T>>;
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Split the rewards of the stash controlled by the caller between `payees`.
+		///
+		/// Every payee gets its share of each payout, the stash keeps what is left. Replaces a
+		/// previous split and redirects the staking rewards of the stash to be split.
+		///
+		/// The dispatch origin for this call must be _Signed_ by the controller.
+		#[pallet::weight(T::WeightInfo::set_payout_split(payees.len() as u32))]
+		pub fn set_payout_split(
+			origin: OriginFor<T>,
+			payees: Vec<(T::AccountId, Perbill)>,
+		) -> DispatchResult {
+			let controller = ensure_signed(origin)?;
+			let stash = T::Staking::stash_of(&controller).ok_or(Error::<T>::NotController)?;
+
+			ensure!(!payees.is_empty(), Error::<T>::NoPayees);
+			ensure!(payees.len() as u32 <= T::MaxPayees::get(), Error::<T>::TooManyPayees);
+			let mut accounts = payees.iter().map(|(account, _)| account).collect::<Vec<_>>();
+			accounts.sort();
+			accounts.dedup();
+			ensure!(accounts.len() == payees.len(), Error::<T>::DuplicatePayee);
+			let total = payees
+				.iter()
+				.try_fold(0u32, |total, (_, share)| total.checked_add(share.deconstruct()));
+			ensure!(
+				total.map_or(false, |total| total <= Perbill::one().deconstruct()),
+				Error::<T>::SharesExceedWhole
+			);
+
+			// A replaced split already pays to the split account, keep what was there before it.
+			let previous_payee =
+				(!PayoutSplits::<T>::contains_key(&stash)).then(|| T::Staking::payee(&stash));
+			T::Staking::set_payee(
+				controller,
+				T::Staking::account_payee(Self::split_account(&stash)),
+			)?;
+			PayoutSplits::<T>::insert(&stash, &payees);
+			if let Some(previous_payee) = previous_payee {
+				PreviousPayees::<T>::insert(&stash, previous_payee);
+			}
+
+			Self::deposit_event(Event::<T>::PayoutSplitSet(stash, payees));
+			Ok(())
+		}
+
+		/// Stop splitting the rewards of the stash controlled by the caller.
+		///
+		/// Rewards collected so far are split one last time, later rewards are paid where they
+		/// were paid before the split was set.
+		///
+		/// The dispatch origin for this call must be _Signed_ by the controller.
+		#[pallet::weight(T::WeightInfo::remove_payout_split(T::MaxPayees::get()))]
+		pub fn remove_payout_split(origin: OriginFor<T>) -> DispatchResult {
+			let controller = ensure_signed(origin)?;
+			let stash = T::Staking::stash_of(&controller).ok_or(Error::<T>::NotController)?;
+			ensure!(PayoutSplits::<T>::contains_key(&stash), Error::<T>::NoPayoutSplit);
+
+			Self::split_rewards(&stash);
+			let previous_payee = Self::previous_payee(&stash)
+				.unwrap_or_else(|| T::Staking::account_payee(stash.clone()));
+			T::Staking::set_payee(controller, previous_payee)?;
+			PayoutSplits::<T>::remove(&stash);
+			PreviousPayees::<T>::remove(&stash);
+
+			Self::deposit_event(Event::<T>::PayoutSplitRemoved(stash));
+			Ok(())
+		}
+
+		/// Pay out the rewards of the validator `stash` for `era` and split its own.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		#[pallet::weight(
+			T::Staking::payout_stakers_weight()
+				.saturating_add(T::WeightInfo::distribute(T::MaxPayees::get()))
+		)]
+		pub fn payout_stakers(
+			origin: OriginFor<T>,
+			stash: T::AccountId,
+			era: EraIndex,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+
+			let post_info = T::Staking::payout_stakers(who, stash.clone(), era)?;
+			Self::split_rewards(&stash);
+
+			Ok(post_info
+				.actual_weight
+				.map(|weight| weight.saturating_add(T::WeightInfo::distribute(T::MaxPayees::get())))
+				.into())
+		}
+
+		/// Split the rewards collected for the validator `stash`.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		#[pallet::weight(T::WeightInfo::distribute(T::MaxPayees::get()))]
+		pub fn distribute(origin: OriginFor<T>, stash: T::AccountId) -> DispatchResult {
+			ensure_signed(origin)?;
+			ensure!(PayoutSplits::<T>::contains_key(&stash), Error::<T>::NoPayoutSplit);
+
+			Self::split_rewards(&stash);
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// The account collecting the rewards of `stash` until they are split.
+	pub fn split_account(stash: &T::AccountId) -> T::AccountId {
+		T::PalletId::get().into_sub_account(stash)
+	}
+
+	/// Split everything collected for `stash` between its payees and the stash.
+	fn split_rewards(stash: &T::AccountId) {
+		let payees = match Self::payout_split(stash) {
+			Some(payees) => payees,
+			None => return,
+		};
+
+		let account = Self::split_account(stash);
+		let amount = T::Currency::free_balance(&account);
+		if amount.is_zero() {
+			return
+		}
+
+		for (payee, share) in payees {
+			let part = share.mul_floor(amount);
+			let _ = T::Currency::transfer(&account, &payee, part, ExistenceRequirement::AllowDeath);
+		}
+		// Parts which can't be paid, e.g. because they are below the existential deposit of a new
+		// account, are left to the stash along with the rest.
+		let left = T::Currency::free_balance(&account);
+		let _ = T::Currency::transfer(&account, stash, left, ExistenceRequirement::AllowDeath);
+
+		Self::deposit_event(Event::<T>::RewardsSplit(stash.clone(), amount));
+	}
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking {
+	use super::*;
+	use frame_benchmarking::{account, benchmarks, whitelisted_caller};
+
+	fn payees<T: Config>(n: u32) -> Vec<(T::AccountId, Perbill)> {
+		(0..n).map(|i| (account("payee", i, 0), Perbill::from_rational(1, n))).collect()
+	}
+
+	fn fund_split_account<T: Config>(stash: &T::AccountId, n: u32) {
+		let account = Pallet::<T>::split_account(stash);
+		T::Currency::make_free_balance_be(
+			&account,
+			T::Currency::minimum_balance() * 1_000u32.into() * n.into(),
+		);
+	}
+
+	fn split<T: Config>(n: u32) -> T::AccountId {
+		let stash: T::AccountId = whitelisted_caller();
+		PayoutSplits::<T>::insert(&stash, payees::<T>(n));
+		fund_split_account::<T>(&stash, n);
+		stash
+	}
+
+	/// A bonded stash and its controller.
+	fn bonded<T: Config>() -> (T::AccountId, T::AccountId) {
+		let stash: T::AccountId = account("stash", 0, 0);
+		let controller: T::AccountId = whitelisted_caller();
+		T::Staking::bond(stash.clone(), controller.clone()).unwrap();
+		(stash, controller)
+	}
+
+	benchmarks! {
+		set_payout_split {
+			let n in 1 .. T::MaxPayees::get();
+			let (stash, controller) = bonded::<T>();
+			let payees = payees::<T>(n);
+		}: _(RawOrigin::Signed(controller), payees.clone())
+		verify {
+			assert_eq!(Pallet::<T>::payout_split(&stash), Some(payees));
+		}
+
+		remove_payout_split {
+			let n in 1 .. T::MaxPayees::get();
+			let (stash, controller) = bonded::<T>();
+			Pallet::<T>::set_payout_split(
+				RawOrigin::Signed(controller.clone()).into(),
+				payees::<T>(n),
+			)?;
+			fund_split_account::<T>(&stash, n);
+		}: _(RawOrigin::Signed(controller))
+		verify {
+			assert!(Pallet::<T>::payout_split(&stash).is_none());
+			assert!(T::Currency::free_balance(&Pallet::<T>::split_account(&stash)).is_zero());
+		}
+
+		distribute {
+			let n in 1 .. T::MaxPayees::get();
+			let stash = split::<T>(n);
+			let caller: T::AccountId = account("caller", 0, 0);
+		}: _(RawOrigin::Signed(caller), stash.clone())
+		verify {
+			assert!(T::Currency::free_balance(&Pallet::<T>::split_account(&stash)).is_zero());
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::payout_splits;
+	use frame_support::{assert_noop, assert_ok, parameter_types};
+	use pallet_staking::RewardDestination;
+	use sp_core::H256;
+	use sp_runtime::{
+		testing::Header,
+		traits::{BlakeTwo256, IdentityLookup},
+		DispatchError,
+	};
+	use std::{cell::RefCell, collections::BTreeMap};
+
+	type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+	type Block = frame_system::mocking::MockBlock<Test>;
+
+	frame_support::construct_runtime!(
+		pub enum Test where
+			Block = Block,
+			NodeBlock = Block,
+			UncheckedExtrinsic = UncheckedExtrinsic,
+		{
+			System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+			Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+			PayoutSplits: payout_splits::{Pallet, Call, Storage, Event<T>},
+		}
+	);
+
+	parameter_types! {
+		pub const BlockHashCount: u64 = 250;
+	}
+
+	impl frame_system::Config for Test {
+		type BaseCallFilter = frame_support::traits::Everything;
+		type BlockWeights = ();
+		type BlockLength = ();
+		type DbWeight = ();
+		type Origin = Origin;
+		type Call = Call;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<u64>;
+		type Header = Header;
+		type Event = Event;
+		type BlockHashCount = BlockHashCount;
+		type Version = ();
+		type PalletInfo = PalletInfo;
+		type AccountData = pallet_balances::AccountData<u64>;
+		type OnNewAccount = ();
+		type OnKilledAccount = ();
+		type SystemWeightInfo = ();
+		type SS58Prefix = ();
+		type OnSetCode = ();
+		type MaxConsumers = frame_support::traits::ConstU32<16>;
+	}
+
+	parameter_types! {
+		pub const ExistentialDeposit: u64 = 1;
+	}
+
+	impl pallet_balances::Config for Test {
+		type Balance = u64;
+		type DustRemoval = ();
+		type Event = Event;
+		type ExistentialDeposit = ExistentialDeposit;
+		type AccountStore = System;
+		type MaxLocks = ();
+		type MaxReserves = ();
+		type ReserveIdentifier = [u8; 8];
+		type WeightInfo = ();
+	}
+
+	/// The stash bonded by controller `10`.
+	const STASH: u64 = 11;
+	const CONTROLLER: u64 = 10;
+	/// What every payout pays the validator.
+	const REWARD: u64 = 1_000;
+
+	thread_local! {
+		static PAYEES: RefCell<BTreeMap<u64, RewardDestination<u64>>> =
+			RefCell::new(BTreeMap::new());
+	}
+
+	pub struct TestStaking;
+	impl PayoutStaking<u64> for TestStaking {
+		type Payee = RewardDestination<u64>;
+
+		fn stash_of(controller: &u64) -> Option<u64> {
+			(*controller == CONTROLLER).then(|| STASH)
+		}
+
+		fn payee(stash: &u64) -> RewardDestination<u64> {
+			PAYEES.with(|p| p.borrow().get(stash).cloned()).unwrap_or_default()
+		}
+
+		fn account_payee(account: u64) -> RewardDestination<u64> {
+			RewardDestination::Account(account)
+		}
+
+		fn set_payee(controller: u64, payee: RewardDestination<u64>) -> DispatchResult {
+			let stash = Self::stash_of(&controller).ok_or(DispatchError::Other("not bonded"))?;
+			PAYEES.with(|p| p.borrow_mut().insert(stash, payee));
+			Ok(())
+		}
+
+		fn payout_stakers(_who: u64, stash: u64, _era: EraIndex) -> DispatchResultWithPostInfo {
+			let payee = match Self::payee(&stash) {
+				RewardDestination::Account(account) => account,
+				RewardDestination::Controller => CONTROLLER,
+				_ => stash,
+			};
+			let _ = Balances::deposit_creating(&payee, REWARD);
+			Ok(().into())
+		}
+
+		fn payout_stakers_weight() -> Weight {
+			0
+		}
+
+		#[cfg(feature = "runtime-benchmarks")]
+		fn bond(_stash: u64, _controller: u64) -> DispatchResult {
+			Ok(())
+		}
+	}
+
+	parameter_types! {
+		pub const PayoutSplitsPalletId: PalletId = PalletId(*b"py/split");
+		pub const MaxPayees: u32 = 3;
+	}
+
+	impl Config for Test {
+		type Event = Event;
+		type Currency = Balances;
+		type Staking = TestStaking;
+		type PalletId = PayoutSplitsPalletId;
+		type MaxPayees = MaxPayees;
+		type WeightInfo = TestWeightInfo;
+	}
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		PAYEES.with(|p| p.borrow_mut().clear());
+		let t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+		let mut ext = sp_io::TestExternalities::new(t);
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+
+	fn split_account() -> u64 {
+		PayoutSplits::split_account(&STASH)
+	}
+
+	#[test]
+	fn set_payout_split_checks_payees() {
+		new_test_ext().execute_with(|| {
+			let half = Perbill::from_percent(50);
+			assert_noop!(
+				PayoutSplits::set_payout_split(Origin::signed(STASH), vec![(1, half)]),
+				Error::<Test>::NotController
+			);
+			assert_noop!(
+				PayoutSplits::set_payout_split(Origin::signed(CONTROLLER), vec![]),
+				Error::<Test>::NoPayees
+			);
+			assert_noop!(
+				PayoutSplits::set_payout_split(
+					Origin::signed(CONTROLLER),
+					vec![(1, half), (2, half), (3, half), (4, half)]
+				),
+				Error::<Test>::TooManyPayees
+			);
+			assert_noop!(
+				PayoutSplits::set_payout_split(
+					Origin::signed(CONTROLLER),
+					vec![(1, half), (1, half)]
+				),
+				Error::<Test>::DuplicatePayee
+			);
+			assert_noop!(
+				PayoutSplits::set_payout_split(
+					Origin::signed(CONTROLLER),
+					vec![(1, half), (2, Perbill::from_percent(51))]
+				),
+				Error::<Test>::SharesExceedWhole
+			);
+
+			assert_ok!(PayoutSplits::set_payout_split(
+				Origin::signed(CONTROLLER),
+				vec![(1, half), (2, half)]
+			));
+			assert_eq!(PayoutSplits::payout_split(STASH), Some(vec![(1, half), (2, half)]));
+			assert_eq!(TestStaking::payee(&STASH), RewardDestination::Account(split_account()));
+			System::assert_last_event(Event::PayoutSplits(
+				crate::payout_splits::Event::PayoutSplitSet(STASH, vec![(1, half), (2, half)]),
+			));
+		});
+	}
+
+	#[test]
+	fn payouts_are_split() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(PayoutSplits::set_payout_split(
+				Origin::signed(CONTROLLER),
+				vec![(1, Perbill::from_percent(50)), (2, Perbill::from_percent(30))]
+			));
+
+			assert_ok!(PayoutSplits::payout_stakers(Origin::signed(3), STASH, 1));
+
+			assert_eq!(Balances::free_balance(1), 500);
+			assert_eq!(Balances::free_balance(2), 300);
+			assert_eq!(Balances::free_balance(STASH), 200);
+			assert_eq!(Balances::free_balance(split_account()), 0);
+			System::assert_last_event(Event::PayoutSplits(
+				crate::payout_splits::Event::RewardsSplit(STASH, REWARD),
+			));
+		});
+	}
+
+	#[test]
+	fn direct_payouts_are_split_by_distribute() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(
+				PayoutSplits::distribute(Origin::signed(3), STASH),
+				Error::<Test>::NoPayoutSplit
+			);
+			assert_ok!(PayoutSplits::set_payout_split(
+				Origin::signed(CONTROLLER),
+				vec![(1, Perbill::from_percent(100))]
+			));
+
+			// Paid out through staking itself, the rewards wait to be split.
+			assert_ok!(TestStaking::payout_stakers(3, STASH, 1));
+			assert_eq!(Balances::free_balance(split_account()), REWARD);
+
+			assert_ok!(PayoutSplits::distribute(Origin::signed(3), STASH));
+			assert_eq!(Balances::free_balance(1), REWARD);
+			assert_eq!(Balances::free_balance(split_account()), 0);
+		});
+	}
+
+	#[test]
+	fn remove_payout_split_pays_out_and_restores_payee() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(
+				PayoutSplits::remove_payout_split(Origin::signed(CONTROLLER)),
+				Error::<Test>::NoPayoutSplit
+			);
+			assert_ok!(PayoutSplits::set_payout_split(
+				Origin::signed(CONTROLLER),
+				vec![(1, Perbill::from_percent(10))]
+			));
+			assert_ok!(TestStaking::payout_stakers(3, STASH, 1));
+
+			assert_ok!(PayoutSplits::remove_payout_split(Origin::signed(CONTROLLER)));
+			assert_eq!(Balances::free_balance(1), 100);
+			assert_eq!(Balances::free_balance(STASH), 900);
+			assert!(PayoutSplits::payout_split(STASH).is_none());
+			assert!(PayoutSplits::previous_payee(STASH).is_none());
+			assert_eq!(TestStaking::payee(&STASH), RewardDestination::Staked);
+
+			// Later payouts go to the stash in full.
+			assert_ok!(PayoutSplits::payout_stakers(Origin::signed(3), STASH, 2));
+			assert_eq!(Balances::free_balance(STASH), 900 + REWARD);
+		});
+	}
+
+	#[test]
+	fn remove_payout_split_restores_previous_payee() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(TestStaking::set_payee(CONTROLLER, RewardDestination::Controller));
+			assert_ok!(PayoutSplits::set_payout_split(
+				Origin::signed(CONTROLLER),
+				vec![(1, Perbill::from_percent(10))]
+			));
+			assert_eq!(PayoutSplits::previous_payee(STASH), Some(RewardDestination::Controller));
+
+			// Replacing the split keeps the payee from before the first one.
+			assert_ok!(PayoutSplits::set_payout_split(
+				Origin::signed(CONTROLLER),
+				vec![(2, Perbill::from_percent(20))]
+			));
+			assert_eq!(PayoutSplits::previous_payee(STASH), Some(RewardDestination::Controller));
+
+			assert_ok!(PayoutSplits::remove_payout_split(Origin::signed(CONTROLLER)));
+			assert_eq!(TestStaking::payee(&STASH), RewardDestination::Controller);
+
+			assert_ok!(PayoutSplits::payout_stakers(Origin::signed(3), STASH, 1));
+			assert_eq!(Balances::free_balance(CONTROLLER), REWARD);
+		});
+	}
+}
@@ -133,6 +133,10 @@ pub mod pallet {
 			BalanceOf<T>,
 			BalanceOf<T>,
 		),
+		/// A para with a lease for the new lease period could not be upgraded to a parachain and
+		/// remains a parathread. It can be onboarded later with `trigger_onboard`.
+		/// `[parachain_id]`
+		OnboardingFailed(ParaId),
 	}
 
 	#[pallet::error]
@@ -283,8 +287,12 @@ impl<T: Config> Pallet<T> {
 		for para in parachains.iter() {
 			if old_parachains.binary_search(para).is_err() {
 				// incoming.
-				let res = T::Registrar::make_parachain(*para);
-				debug_assert!(res.is_ok());
+				//
+				// A para whose manager cannot pay the upgrade deposit stays a parathread. We
+				// report it so it can be onboarded later with `trigger_onboard`.
+				if T::Registrar::make_parachain(*para).is_err() {
+					Self::deposit_event(Event::<T>::OnboardingFailed(*para));
+				}
 			}
 		}
 
@@ -944,6 +952,32 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn failed_onboarding_is_reported() {
+		new_test_ext().execute_with(|| {
+			run_to_block(1);
+
+			// Para 1 is not registered, so the registrar refuses to make it a parachain.
+			assert_ok!(Slots::lease_out(1.into(), &1, 1, 1, 1));
+
+			run_to_block(10);
+			System::assert_last_event(Event::Slots(slots::Event::OnboardingFailed(1.into())));
+			assert!(TestRegistrar::<Test>::operations().is_empty());
+			// The lease and its deposit are kept.
+			assert_eq!(Slots::deposit_held(1.into(), &1), 1);
+
+			// Once the problem is fixed, the para can still be onboarded for its lease.
+			assert_ok!(TestRegistrar::<Test>::register(
+				1,
+				ParaId::from(1),
+				dummy_head_data(),
+				dummy_validation_code()
+			));
+			assert_ok!(Slots::trigger_onboard(Origin::signed(1), 1.into()));
+			assert_eq!(TestRegistrar::<Test>::operations(), vec![(1.into(), 10, true)]);
+		});
+	}
+
 	#[test]
 	fn lease_period_offset_works() {
 		new_test_ext().execute_with(|| {
@@ -0,0 +1,100 @@
+// Copyright 2020-2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! The sovereign accounts of paras and their balances.
+//!
+//! Paras pay fees and deposits from their sovereign account. [`SovereignAccountApi`] reports
+//! them, so para teams can monitor their funding without deriving the accounts themselves.
+
+use frame_support::traits::ReservableCurrency;
+use parity_scale_codec::{Codec, Decode, Encode};
+use primitives::v1::Id as ParaId;
+use scale_info::TypeInfo;
+use selendra_parachain::primitives::Sibling;
+use sp_runtime::{traits::AccountIdConversion, RuntimeDebug};
+
+/// A sovereign account and its balances.
+#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct SovereignAccount<AccountId, Balance> {
+	/// The account.
+	pub account: AccountId,
+	/// The free balance of the account.
+	pub free: Balance,
+	/// The reserved balance of the account, e.g. for deposits.
+	pub reserved: Balance,
+}
+
+impl<AccountId, Balance> SovereignAccount<AccountId, Balance> {
+	/// Look up the balances of the given account in `Currency`.
+	pub fn of<Currency>(account: AccountId) -> Self
+	where
+		Currency: ReservableCurrency<AccountId, Balance = Balance>,
+	{
+		Self {
+			free: Currency::free_balance(&account),
+			reserved: Currency::reserved_balance(&account),
+			account,
+		}
+	}
+}
+
+/// The sovereign account of a para on the relay chain.
+pub fn para_account<AccountId: Encode + Decode>(para: ParaId) -> AccountId {
+	para.into_account()
+}
+
+/// The account representing a para on its sibling paras.
+pub fn sibling_account<AccountId: Encode + Decode>(para: ParaId) -> AccountId {
+	Sibling::from(para).into_account()
+}
+
+sp_api::decl_runtime_apis! {
+	/// The API to query the sovereign accounts of paras.
+	pub trait SovereignAccountApi<AccountId, Balance> where
+		AccountId: Codec,
+		Balance: Codec,
+	{
+		/// The sovereign account of the para on the relay chain.
+		fn para_sovereign_account(para: ParaId) -> SovereignAccount<AccountId, Balance>;
+
+		/// The account representing the para on its sibling paras, with its balances on the
+		/// relay chain.
+		fn sibling_sovereign_account(para: ParaId) -> SovereignAccount<AccountId, Balance>;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_runtime::AccountId32;
+
+	#[test]
+	fn accounts_are_derived_from_para_id() {
+		let para = ParaId::from(2000);
+
+		let para_account: AccountId32 = para_account(para);
+		let sibling_account: AccountId32 = sibling_account(para);
+
+		let mut expected = [0u8; 32];
+		expected[4..8].copy_from_slice(&2000u32.encode());
+
+		expected[..4].copy_from_slice(b"para");
+		assert_eq!(para_account, AccountId32::new(expected));
+
+		expected[..4].copy_from_slice(b"sibl");
+		assert_eq!(sibling_account, AccountId32::new(expected));
+	}
+}
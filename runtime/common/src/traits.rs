@@ -22,6 +22,7 @@ use frame_support::{
 };
 use primitives::v1::{HeadData, Id as ParaId, ValidationCode};
 use sp_std::vec::*;
+use xcm::latest::MultiLocation;
 
 /// Parachain registration API.
 pub trait Registrar {
@@ -258,3 +259,14 @@ pub trait OnSwap {
 	/// such as leases, deposits held and thread/chain nature are swapped.
 	fn on_swap(one: ParaId, other: ParaId);
 }
+
+/// Conversion between amounts of a foreign asset and the native currency.
+pub trait ConvertToNative<Balance> {
+	/// The amount of native currency `amount` of the asset at `asset` is worth, if the asset has
+	/// a conversion rate.
+	fn to_native(asset: &MultiLocation, amount: Balance) -> Option<Balance>;
+
+	/// The amount of the asset at `asset` which is worth `amount` of the native currency, if the
+	/// asset has a conversion rate.
+	fn from_native(asset: &MultiLocation, amount: Balance) -> Option<Balance>;
+}
@@ -0,0 +1,267 @@
+// Copyright 2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Selling single blocks of the parathread cores on demand.
+//!
+//! A para without a lease can buy a slot on one of the parathread cores by placing an order. The
+//! order is paid at the spot price, which is the base fee scaled by the spot traffic, and turned
+//! into a parathread claim that the scheduler assigns to the next free parathread core, in
+//! between the leased parachains which keep their own cores.
+//!
+//! The spot traffic follows the demand: at the start of every block it grows while the claim queue
+//! is fuller than the target utilization and shrinks, down to 1, while it is emptier.
+
+use crate::{
+	configuration, paras,
+	scheduler::{self, ClaimQueueError},
+};
+use frame_support::{
+	pallet_prelude::*,
+	traits::{Currency, ExistenceRequirement, WithdrawReasons},
+	transactional,
+};
+use frame_system::pallet_prelude::*;
+use primitives::v1::{Balance, CollatorId, Id as ParaId, ParathreadClaim};
+use sp_runtime::{
+	traits::{One, Saturating},
+	FixedPointNumber, FixedU128, PerThing, Perbill,
+};
+
+pub use pallet::*;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+#[cfg(test)]
+mod tests;
+
+pub trait WeightInfo {
+	fn place_order_allow_death() -> Weight;
+	fn place_order_keep_alive() -> Weight;
+}
+
+/// A weight info that is only suitable for testing.
+pub struct TestWeightInfo;
+
+impl WeightInfo for TestWeightInfo {
+	fn place_order_allow_death() -> Weight {
+		0
+	}
+	fn place_order_keep_alive() -> Weight {
+		0
+	}
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::without_storage_info]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config:
+		frame_system::Config + configuration::Config + paras::Config + scheduler::Config
+	{
+		/// The outer event type.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The currency the orders are paid in. The payments are burned.
+		type Currency: Currency<Self::AccountId, Balance = Balance>;
+
+		/// The price of a block at a spot traffic of 1.
+		#[pallet::constant]
+		type BaseFee: Get<Balance>;
+
+		/// The share of the claim queue that is expected to be in use. The spot traffic goes up
+		/// while the queue is fuller and down while it is emptier.
+		#[pallet::constant]
+		type TargetQueueUtilization: Get<Perbill>;
+
+		/// How fast the spot traffic follows the utilization of the claim queue.
+		#[pallet::constant]
+		type FeeVariability: Get<Perbill>;
+
+		/// Something that provides the weight of this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// An order for a block of a para was placed. `[para, spot_price]`
+		OnDemandOrderPlaced(ParaId, Balance),
+		/// The spot traffic changed. `[traffic]`
+		SpotTrafficSet(FixedU128),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The spot price is higher than the maximum amount the caller is willing to pay.
+		SpotPriceHigherThanMaxAmount,
+		/// The para is not a live parathread.
+		NotParathread,
+		/// The claim queue is full.
+		QueueFull,
+		/// The para already has an order queued or a block assigned to a core.
+		CompetingClaim,
+	}
+
+	impl<T> From<ClaimQueueError> for Error<T> {
+		fn from(e: ClaimQueueError) -> Self {
+			match e {
+				ClaimQueueError::NotParathread => Error::<T>::NotParathread,
+				ClaimQueueError::QueueFull => Error::<T>::QueueFull,
+				ClaimQueueError::CompetingClaim => Error::<T>::CompetingClaim,
+			}
+		}
+	}
+
+	#[pallet::type_value]
+	pub(super) fn SpotTrafficOnEmpty() -> FixedU128 {
+		FixedU128::one()
+	}
+
+	/// The factor the base fee is multiplied with to get the spot price. Never below 1.
+	#[pallet::storage]
+	#[pallet::getter(fn spot_traffic)]
+	pub(super) type SpotTraffic<T: Config> =
+		StorageValue<_, FixedU128, ValueQuery, SpotTrafficOnEmpty>;
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(_now: T::BlockNumber) -> Weight {
+			// `ActiveConfig`
+			let mut weight = T::DbWeight::get().reads(1);
+
+			let config = <configuration::Pallet<T>>::config();
+			let queue_max_size = <scheduler::Pallet<T>>::parathread_queue_max_size(&config);
+			if queue_max_size != 0 {
+				// `ParathreadQueue` and `SpotTraffic`
+				weight = weight.saturating_add(T::DbWeight::get().reads(2));
+
+				let queue_size = <scheduler::Pallet<T>>::parathread_queue_len();
+				let traffic = Self::spot_traffic();
+				let new_traffic = Self::calculate_spot_traffic(
+					traffic,
+					Perbill::from_rational(queue_size, queue_max_size),
+					T::TargetQueueUtilization::get(),
+					T::FeeVariability::get(),
+				);
+				if new_traffic != traffic {
+					SpotTraffic::<T>::put(new_traffic);
+					Self::deposit_event(Event::SpotTrafficSet(new_traffic));
+					weight = weight.saturating_add(T::DbWeight::get().writes(1));
+				}
+			}
+
+			weight
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Buy a block of `para_id` on a parathread core, collated by `collator`.
+		///
+		/// The caller pays the spot price, if it doesn't exceed `max_amount`, and may be reaped by
+		/// doing so.
+		#[pallet::weight(<T as Config>::WeightInfo::place_order_allow_death())]
+		pub fn place_order_allow_death(
+			origin: OriginFor<T>,
+			max_amount: Balance,
+			para_id: ParaId,
+			collator: CollatorId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::do_place_order(
+				who,
+				max_amount,
+				para_id,
+				collator,
+				ExistenceRequirement::AllowDeath,
+			)
+		}
+
+		/// Same as [`Pallet::place_order_allow_death`], but fails rather than reaping the caller.
+		#[pallet::weight(<T as Config>::WeightInfo::place_order_keep_alive())]
+		pub fn place_order_keep_alive(
+			origin: OriginFor<T>,
+			max_amount: Balance,
+			para_id: ParaId,
+			collator: CollatorId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::do_place_order(
+				who,
+				max_amount,
+				para_id,
+				collator,
+				ExistenceRequirement::KeepAlive,
+			)
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// The price of the next block bought on demand.
+	pub fn spot_price() -> Balance {
+		Self::spot_traffic().saturating_mul_int(T::BaseFee::get())
+	}
+
+	#[transactional]
+	fn do_place_order(
+		who: T::AccountId,
+		max_amount: Balance,
+		para_id: ParaId,
+		collator: CollatorId,
+		existence_requirement: ExistenceRequirement,
+	) -> DispatchResult {
+		let spot_price = Self::spot_price();
+		ensure!(spot_price <= max_amount, Error::<T>::SpotPriceHigherThanMaxAmount);
+
+		<scheduler::Pallet<T>>::queue_parathread_claim(ParathreadClaim(para_id, collator))
+			.map_err(Error::<T>::from)?;
+
+		// Dropping the imbalance burns the payment.
+		let _ =
+			T::Currency::withdraw(&who, spot_price, WithdrawReasons::FEE, existence_requirement)?;
+
+		Self::deposit_event(Event::OnDemandOrderPlaced(para_id, spot_price));
+		Ok(())
+	}
+
+	/// The spot traffic following `traffic` at the given utilization of the claim queue.
+	fn calculate_spot_traffic(
+		traffic: FixedU128,
+		utilization: Perbill,
+		target_utilization: Perbill,
+		variability: Perbill,
+	) -> FixedU128 {
+		let to_fixed =
+			|p: Perbill| FixedU128::saturating_from_rational(p.deconstruct(), Perbill::ACCURACY);
+
+		if utilization > target_utilization {
+			let step = variability * utilization.saturating_sub(target_utilization);
+			traffic.saturating_mul(FixedU128::one().saturating_add(to_fixed(step)))
+		} else {
+			let step = variability * target_utilization.saturating_sub(utilization);
+			traffic
+				.saturating_mul(FixedU128::one().saturating_sub(to_fixed(step)))
+				.max(FixedU128::one())
+		}
+	}
+}
@@ -0,0 +1,96 @@
+// Copyright 2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::{Pallet as OnDemandAssignment, *};
+use crate::{configuration::HostConfiguration, paras::ParaGenesisArgs};
+use frame_benchmarking::whitelisted_caller;
+use frame_system::RawOrigin;
+use sp_core::sr25519;
+
+fn assert_last_event<T: Config>(generic_event: <T as Config>::Event) {
+	frame_system::Pallet::<T>::assert_last_event(generic_event.into());
+}
+
+fn collator() -> CollatorId {
+	CollatorId::from(sr25519::Public::from_raw([42; 32]))
+}
+
+fn make_parathread<T: Config>(para_id: ParaId) {
+	let genesis = ParaGenesisArgs {
+		genesis_head: vec![0; 32].into(),
+		validation_code: vec![1, 2, 3].into(),
+		parachain: false,
+	};
+	paras::Pallet::<T>::initialize_para_now(&mut paras::ParachainsCache::new(), para_id, &genesis);
+}
+
+/// Register the parathread the order is placed for, and fill the claim queue up to the last
+/// free slot with the claims of other parathreads.
+fn setup_claim_queue<T: Config>() -> ParaId {
+	let config = configuration::Pallet::<T>::config();
+	configuration::Pallet::<T>::force_set_active_config(HostConfiguration {
+		parathread_cores: config.parathread_cores.max(1),
+		scheduling_lookahead: config.scheduling_lookahead.max(1),
+		..config
+	});
+
+	let config = configuration::Pallet::<T>::config();
+	let queue_max_size = scheduler::Pallet::<T>::parathread_queue_max_size(&config);
+	for i in 1..queue_max_size {
+		let para_id = ParaId::from(1000 + i);
+		make_parathread::<T>(para_id);
+		scheduler::Pallet::<T>::queue_parathread_claim(ParathreadClaim(para_id, collator()))
+			.expect("the queue has room for all claims but one; qed");
+	}
+
+	let para_id = ParaId::from(1000);
+	make_parathread::<T>(para_id);
+	para_id
+}
+
+frame_benchmarking::benchmarks! {
+	// Reaps the caller, by paying the spot price with all of its balance.
+	place_order_allow_death {
+		let caller: T::AccountId = whitelisted_caller();
+		let para_id = setup_claim_queue::<T>();
+		let spot_price = OnDemandAssignment::<T>::spot_price();
+		T::Currency::make_free_balance_be(&caller, spot_price.max(T::Currency::minimum_balance()));
+		frame_system::Pallet::<T>::set_block_number(1u32.into());
+	}: _(RawOrigin::Signed(caller), spot_price, para_id, collator())
+	verify {
+		assert_last_event::<T>(Event::<T>::OnDemandOrderPlaced(para_id, spot_price).into());
+	}
+
+	place_order_keep_alive {
+		let caller: T::AccountId = whitelisted_caller();
+		let para_id = setup_claim_queue::<T>();
+		let spot_price = OnDemandAssignment::<T>::spot_price();
+		T::Currency::make_free_balance_be(
+			&caller,
+			spot_price.saturating_add(T::Currency::minimum_balance().saturating_mul(2)),
+		);
+		frame_system::Pallet::<T>::set_block_number(1u32.into());
+	}: _(RawOrigin::Signed(caller), spot_price, para_id, collator())
+	verify {
+		assert_last_event::<T>(Event::<T>::OnDemandOrderPlaced(para_id, spot_price).into());
+	}
+}
+
+frame_benchmarking::impl_benchmark_test_suite!(
+	OnDemandAssignment,
+	crate::mock::new_test_ext(Default::default()),
+	crate::mock::Test
+);
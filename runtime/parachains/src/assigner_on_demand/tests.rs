@@ -0,0 +1,199 @@
+// Copyright 2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::*;
+use crate::{
+	configuration::HostConfiguration,
+	mock::{
+		assert_last_event, new_test_ext, Balances, Event as MockEvent, ExistentialDeposit,
+		MockGenesisConfig, OnDemandAssignment, Origin, Scheduler, System, Test,
+	},
+	paras::ParaGenesisArgs,
+};
+use frame_support::{assert_noop, assert_ok, traits::OnInitialize};
+use keyring::Sr25519Keyring;
+
+const ALICE: u64 = 1;
+
+fn collator() -> CollatorId {
+	CollatorId::from(Sr25519Keyring::Alice.public())
+}
+
+fn blank_para(parachain: bool) -> ParaGenesisArgs {
+	ParaGenesisArgs {
+		genesis_head: Vec::new().into(),
+		validation_code: vec![1, 2, 3].into(),
+		parachain,
+	}
+}
+
+/// Three parathreads, 10 to 12, and a parachain, 20, with room for two claims in the queue.
+fn genesis_config() -> MockGenesisConfig {
+	MockGenesisConfig {
+		configuration: crate::configuration::GenesisConfig {
+			config: HostConfiguration {
+				parathread_cores: 1,
+				scheduling_lookahead: 2,
+				..Default::default()
+			},
+			..Default::default()
+		},
+		paras: crate::paras::GenesisConfig {
+			paras: vec![
+				(10.into(), blank_para(false)),
+				(11.into(), blank_para(false)),
+				(12.into(), blank_para(false)),
+				(20.into(), blank_para(true)),
+			],
+		},
+		..Default::default()
+	}
+}
+
+fn fixed(n: u128, d: u128) -> FixedU128 {
+	FixedU128::saturating_from_rational(n, d)
+}
+
+#[test]
+fn spot_traffic_follows_utilization() {
+	let target = Perbill::from_percent(50);
+	let variability = Perbill::from_percent(10);
+	let calculate = |traffic, utilization| {
+		OnDemandAssignment::calculate_spot_traffic(traffic, utilization, target, variability)
+	};
+
+	// Rises while the queue is fuller than the target.
+	assert_eq!(calculate(FixedU128::one(), Perbill::from_percent(100)), fixed(105, 100));
+	assert_eq!(calculate(fixed(2, 1), Perbill::from_percent(75)), fixed(205, 100));
+
+	// Stays at the target.
+	assert_eq!(calculate(fixed(2, 1), Perbill::from_percent(50)), fixed(2, 1));
+
+	// Falls while the queue is emptier than the target, but never below 1.
+	assert_eq!(calculate(fixed(2, 1), Perbill::zero()), fixed(190, 100));
+	assert_eq!(calculate(fixed(101, 100), Perbill::zero()), FixedU128::one());
+	assert_eq!(calculate(FixedU128::one(), Perbill::zero()), FixedU128::one());
+}
+
+#[test]
+fn spot_traffic_is_updated_on_initialize() {
+	new_test_ext(genesis_config()).execute_with(|| {
+		System::set_block_number(1);
+		Balances::make_free_balance_be(&ALICE, 1_000);
+
+		// An empty queue leaves the spot traffic at 1.
+		OnDemandAssignment::on_initialize(1);
+		assert_eq!(OnDemandAssignment::spot_traffic(), FixedU128::one());
+		assert_eq!(OnDemandAssignment::spot_price(), 10);
+
+		for para in [10, 11] {
+			assert_ok!(OnDemandAssignment::place_order_allow_death(
+				Origin::signed(ALICE),
+				1_000,
+				para.into(),
+				collator(),
+			));
+		}
+
+		// A full queue raises it.
+		OnDemandAssignment::on_initialize(2);
+		assert_eq!(OnDemandAssignment::spot_traffic(), fixed(105, 100));
+		assert_eq!(OnDemandAssignment::spot_price(), 10);
+		assert_last_event(MockEvent::OnDemandAssignment(Event::SpotTrafficSet(fixed(105, 100))));
+
+		OnDemandAssignment::on_initialize(3);
+		assert_eq!(OnDemandAssignment::spot_traffic(), fixed(11025, 10000));
+		assert_eq!(OnDemandAssignment::spot_price(), 11);
+	});
+}
+
+#[test]
+fn place_order_charges_and_queues_claim() {
+	new_test_ext(genesis_config()).execute_with(|| {
+		System::set_block_number(1);
+		Balances::make_free_balance_be(&ALICE, 100);
+
+		assert_ok!(OnDemandAssignment::place_order_allow_death(
+			Origin::signed(ALICE),
+			10,
+			10.into(),
+			collator(),
+		));
+
+		assert_eq!(Balances::free_balance(&ALICE), 90);
+		assert_eq!(Scheduler::parathread_queue_len(), 1);
+		assert_last_event(MockEvent::OnDemandAssignment(Event::OnDemandOrderPlaced(10.into(), 10)));
+	});
+}
+
+#[test]
+fn failed_orders_are_not_charged() {
+	new_test_ext(genesis_config()).execute_with(|| {
+		System::set_block_number(1);
+		Balances::make_free_balance_be(&ALICE, 100);
+
+		let place_order = |para: u32, max_amount| {
+			OnDemandAssignment::place_order_allow_death(
+				Origin::signed(ALICE),
+				max_amount,
+				para.into(),
+				collator(),
+			)
+		};
+
+		assert_noop!(place_order(10, 9), Error::<Test>::SpotPriceHigherThanMaxAmount);
+		assert_noop!(place_order(20, 10), Error::<Test>::NotParathread);
+		assert_noop!(place_order(30, 10), Error::<Test>::NotParathread);
+
+		assert_ok!(place_order(10, 10));
+		assert_noop!(place_order(10, 10), Error::<Test>::CompetingClaim);
+
+		assert_ok!(place_order(11, 10));
+		assert_noop!(place_order(12, 10), Error::<Test>::QueueFull);
+
+		assert_eq!(Balances::free_balance(&ALICE), 80);
+		assert_eq!(Scheduler::parathread_queue_len(), 2);
+	});
+}
+
+#[test]
+fn unaffordable_orders_are_not_queued() {
+	new_test_ext(genesis_config()).execute_with(|| {
+		System::set_block_number(1);
+		ExistentialDeposit::set(1);
+		Balances::make_free_balance_be(&ALICE, 10);
+
+		assert_noop!(
+			OnDemandAssignment::place_order_keep_alive(
+				Origin::signed(ALICE),
+				10,
+				10.into(),
+				collator(),
+			),
+			pallet_balances::Error::<Test>::KeepAlive,
+		);
+		assert_eq!(Scheduler::parathread_queue_len(), 0);
+
+		assert_ok!(OnDemandAssignment::place_order_allow_death(
+			Origin::signed(ALICE),
+			10,
+			10.into(),
+			collator(),
+		));
+		assert_eq!(Balances::free_balance(&ALICE), 0);
+		assert_eq!(Scheduler::parathread_queue_len(), 1);
+	});
+}
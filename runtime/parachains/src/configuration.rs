@@ -21,8 +21,11 @@
 use crate::shared;
 use frame_support::{pallet_prelude::*, weights::constants::WEIGHT_PER_MILLIS};
 use frame_system::pallet_prelude::*;
-use parity_scale_codec::{Decode, Encode};
-use primitives::v1::{Balance, SessionIndex, MAX_CODE_SIZE, MAX_HEAD_DATA_SIZE, MAX_POV_SIZE};
+use parity_scale_codec::{Codec, Decode, Encode};
+use primitives::v1::{
+	Balance, SessionIndex, MAX_CODE_SIZE, MAX_HEAD_DATA_SIZE, MAX_HRMP_MESSAGE_NUM_PER_CANDIDATE,
+	MAX_HRMP_MESSAGE_SIZE, MAX_POV_SIZE, MAX_UPWARD_MESSAGE_NUM_PER_CANDIDATE,
+};
 use sp_runtime::traits::Zero;
 use sp_std::prelude::*;
 
@@ -322,6 +325,14 @@ pub enum InconsistentError<BlockNumber> {
 	ValidationUpgradeDelayIsTooLow { validation_upgrade_delay: BlockNumber },
 	/// Maximum UMP message size (`MAX_UPWARD_MESSAGE_SIZE_BOUND`) exceeded.
 	MaxUpwardMessageSizeExceeded { max_message_size: u32 },
+	/// `max_upward_message_num_per_candidate` exceeds the hard limit of
+	/// `MAX_UPWARD_MESSAGE_NUM_PER_CANDIDATE`.
+	MaxUpwardMessageNumPerCandidateExceedHardLimit { max_upward_message_num_per_candidate: u32 },
+	/// `hrmp_channel_max_message_size` exceeds the hard limit of `MAX_HRMP_MESSAGE_SIZE`.
+	HrmpChannelMaxMessageSizeExceedHardLimit { hrmp_channel_max_message_size: u32 },
+	/// `hrmp_max_message_num_per_candidate` exceeds the hard limit of
+	/// `MAX_HRMP_MESSAGE_NUM_PER_CANDIDATE`.
+	HrmpMaxMessageNumPerCandidateExceedHardLimit { hrmp_max_message_num_per_candidate: u32 },
 	/// Maximum number of HRMP outbound channels exceeded.
 	MaxHrmpOutboundChannelsExceeded,
 	/// Maximum number of HRMP inbound channels exceeded.
@@ -394,6 +405,24 @@ where
 			})
 		}
 
+		if self.max_upward_message_num_per_candidate > MAX_UPWARD_MESSAGE_NUM_PER_CANDIDATE {
+			return Err(MaxUpwardMessageNumPerCandidateExceedHardLimit {
+				max_upward_message_num_per_candidate: self.max_upward_message_num_per_candidate,
+			})
+		}
+
+		if self.hrmp_channel_max_message_size > MAX_HRMP_MESSAGE_SIZE {
+			return Err(HrmpChannelMaxMessageSizeExceedHardLimit {
+				hrmp_channel_max_message_size: self.hrmp_channel_max_message_size,
+			})
+		}
+
+		if self.hrmp_max_message_num_per_candidate > MAX_HRMP_MESSAGE_NUM_PER_CANDIDATE {
+			return Err(HrmpMaxMessageNumPerCandidateExceedHardLimit {
+				hrmp_max_message_num_per_candidate: self.hrmp_max_message_num_per_candidate,
+			})
+		}
+
 		if self.hrmp_max_parachain_outbound_channels > crate::hrmp::HRMP_MAX_OUTBOUND_CHANNELS_BOUND
 		{
 			return Err(MaxHrmpOutboundChannelsExceeded)
@@ -492,6 +521,7 @@ pub mod pallet {
 	/// The list is sorted ascending by session index. Also, this list can only contain at most
 	/// 2 items: for the next session and for the `scheduled_session`.
 	#[pallet::storage]
+	#[pallet::getter(fn pending_configs)]
 	pub(crate) type PendingConfigs<T: Config> =
 		StorageValue<_, Vec<(SessionIndex, HostConfiguration<T::BlockNumber>)>, ValueQuery>;
 
@@ -1310,3 +1340,15 @@ impl<T: Config> Pallet<T> {
 		Ok(())
 	}
 }
+
+sp_api::decl_runtime_apis! {
+	/// The API to query the host configuration, which only changes at session boundaries.
+	pub trait HostConfigurationApi<BlockNumber> where BlockNumber: Codec {
+		/// The configuration in effect for the current session.
+		fn active_config() -> HostConfiguration<BlockNumber>;
+
+		/// The configurations scheduled to take effect, with the session they do so at, sorted
+		/// ascending by session.
+		fn pending_configs() -> Vec<(SessionIndex, HostConfiguration<BlockNumber>)>;
+	}
+}
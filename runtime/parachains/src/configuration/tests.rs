@@ -227,6 +227,30 @@ fn invariants() {
 			Error::<Test>::InvalidNewValue
 		);
 
+		assert_err!(
+			Configuration::set_max_upward_message_num_per_candidate(
+				Origin::root(),
+				MAX_UPWARD_MESSAGE_NUM_PER_CANDIDATE + 1
+			),
+			Error::<Test>::InvalidNewValue
+		);
+
+		assert_err!(
+			Configuration::set_hrmp_channel_max_message_size(
+				Origin::root(),
+				MAX_HRMP_MESSAGE_SIZE + 1
+			),
+			Error::<Test>::InvalidNewValue
+		);
+
+		assert_err!(
+			Configuration::set_hrmp_max_message_num_per_candidate(
+				Origin::root(),
+				MAX_HRMP_MESSAGE_NUM_PER_CANDIDATE + 1
+			),
+			Error::<Test>::InvalidNewValue
+		);
+
 		assert_err!(
 			Configuration::set_chain_availability_period(Origin::root(), 0),
 			Error::<Test>::InvalidNewValue
@@ -45,6 +45,8 @@ mod tests;
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
 
+pub mod slashing;
+
 /// Whether the dispute is local or remote.
 #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
 pub enum DisputeLocation {
@@ -104,6 +106,35 @@ impl PunishValidators for () {
 	fn punish_inconclusive(_: SessionIndex, _: impl IntoIterator<Item = ValidatorIndex>) {}
 }
 
+impl<A: PunishValidators, B: PunishValidators> PunishValidators for (A, B) {
+	fn punish_for_invalid(
+		session: SessionIndex,
+		validators: impl IntoIterator<Item = ValidatorIndex>,
+	) {
+		let validators = validators.into_iter().collect::<Vec<_>>();
+		A::punish_for_invalid(session, validators.iter().cloned());
+		B::punish_for_invalid(session, validators);
+	}
+
+	fn punish_against_valid(
+		session: SessionIndex,
+		validators: impl IntoIterator<Item = ValidatorIndex>,
+	) {
+		let validators = validators.into_iter().collect::<Vec<_>>();
+		A::punish_against_valid(session, validators.iter().cloned());
+		B::punish_against_valid(session, validators);
+	}
+
+	fn punish_inconclusive(
+		session: SessionIndex,
+		validators: impl IntoIterator<Item = ValidatorIndex>,
+	) {
+		let validators = validators.into_iter().collect::<Vec<_>>();
+		A::punish_inconclusive(session, validators.iter().cloned());
+		B::punish_inconclusive(session, validators);
+	}
+}
+
 /// Binary discriminator to determine if the expensive signature
 /// checks are necessary.
 #[derive(Clone, Copy)]
@@ -0,0 +1,280 @@
+// Copyright 2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Slashing of the validators on the losing side of a concluded dispute.
+//!
+//! [`SlashValidatorsForDisputes`] is a [`PunishValidators`] which reports the losing side of a
+//! dispute as a [`DisputeOffence`]. Reported to `pallet_offences`, the offence ends up in
+//! `pallet_staking`, which slashes the offenders and their nominators.
+//!
+//! The validators of the session of the dispute are identified through the account keys the
+//! session info pallet recorded for the session, and are slashed for their exposure in the era of
+//! the session, as kept in the history of `pallet_staking`. Rotating keys or leaving the validator
+//! set after the session doesn't escape the slash. Only sessions of eras which fell out of the
+//! staking history go unpunished.
+
+use super::PunishValidators;
+use crate::session_info;
+use frame_support::RuntimeDebug;
+use pallet_staking::{BalanceOf, Exposure};
+use primitives::v1::{SessionIndex, ValidatorIndex};
+use sp_runtime::Perbill;
+use sp_staking::{
+	offence::{Kind, Offence, ReportOffence},
+	EraIndex,
+};
+use sp_std::{marker::PhantomData, prelude::*};
+
+/// The side of a dispute a validator lost on.
+pub trait DisputeOffenceKind {
+	/// The identifier of the offence.
+	const ID: Kind;
+
+	/// The share of the exposure of each offender which is slashed.
+	fn slash_fraction() -> Perbill;
+}
+
+/// Backing or approving a candidate which turned out to be invalid.
+#[derive(Clone, PartialEq, Eq, RuntimeDebug)]
+pub enum ForInvalid {}
+
+impl DisputeOffenceKind for ForInvalid {
+	const ID: Kind = *b"disputes:invalid";
+
+	fn slash_fraction() -> Perbill {
+		Perbill::from_percent(100)
+	}
+}
+
+/// Disputing a candidate which turned out to be valid.
+///
+/// Not slashed, as honest validators can end up on this side by mistake, but still reported, so
+/// the staking system chills the offenders.
+#[derive(Clone, PartialEq, Eq, RuntimeDebug)]
+pub enum AgainstValid {}
+
+impl DisputeOffenceKind for AgainstValid {
+	const ID: Kind = *b"disputes:against";
+
+	fn slash_fraction() -> Perbill {
+		Perbill::zero()
+	}
+}
+
+/// The offence of the validators on the losing side of the disputes of a session.
+///
+/// The time slot of the offence is the session, so the offenders of several disputes in the same
+/// session are only slashed once.
+#[derive(Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct DisputeOffence<Offender, K> {
+	/// The session the disputed candidates were included in.
+	pub session_index: SessionIndex,
+	/// The number of validators in the session.
+	pub validator_set_count: u32,
+	/// The validators on the losing side.
+	pub offenders: Vec<Offender>,
+	_kind: PhantomData<K>,
+}
+
+impl<Offender, K> DisputeOffence<Offender, K> {
+	/// Create the offence of `offenders` out of the `validator_set_count` validators of a session.
+	pub fn new(
+		session_index: SessionIndex,
+		validator_set_count: u32,
+		offenders: Vec<Offender>,
+	) -> Self {
+		DisputeOffence { session_index, validator_set_count, offenders, _kind: PhantomData }
+	}
+}
+
+/// The offence of backing or approving an invalid candidate.
+pub type ForInvalidOffence<Offender> = DisputeOffence<Offender, ForInvalid>;
+
+/// The offence of disputing a valid candidate.
+pub type AgainstValidOffence<Offender> = DisputeOffence<Offender, AgainstValid>;
+
+impl<Offender: Clone, K: DisputeOffenceKind> Offence<Offender> for DisputeOffence<Offender, K> {
+	const ID: Kind = K::ID;
+	type TimeSlot = SessionIndex;
+
+	fn offenders(&self) -> Vec<Offender> {
+		self.offenders.clone()
+	}
+
+	fn session_index(&self) -> SessionIndex {
+		self.session_index
+	}
+
+	fn validator_set_count(&self) -> u32 {
+		self.validator_set_count
+	}
+
+	fn time_slot(&self) -> SessionIndex {
+		self.session_index
+	}
+
+	fn slash_fraction(_offenders_count: u32, _validator_set_count: u32) -> Perbill {
+		K::slash_fraction()
+	}
+}
+
+/// The identification of a validator by the staking system.
+type IdentificationTuple<T> = pallet_session::historical::IdentificationTuple<T>;
+
+/// The staking system the validators of past sessions are identified in.
+///
+/// The stashes are the validator ids, identified by their exposure, as with
+/// `pallet_staking::ExposureOf` for the current era.
+pub trait HistoricalStaking:
+	session_info::Config
+	+ pallet_staking::Config
+	+ pallet_session::Config<ValidatorId = <Self as frame_system::Config>::AccountId>
+	+ pallet_session::historical::Config<
+		FullIdentification = Exposure<<Self as frame_system::Config>::AccountId, BalanceOf<Self>>,
+	>
+{
+}
+
+impl<T> HistoricalStaking for T where
+	T: session_info::Config
+		+ pallet_staking::Config
+		+ pallet_session::Config<ValidatorId = <T as frame_system::Config>::AccountId>
+		+ pallet_session::historical::Config<
+			FullIdentification = Exposure<<T as frame_system::Config>::AccountId, BalanceOf<T>>,
+		>
+{
+}
+
+/// The era the session belonged to, if it is still in the history of the staking system.
+fn era_of_session<T: pallet_staking::Config>(session: SessionIndex) -> Option<EraIndex> {
+	let active_era = pallet_staking::Pallet::<T>::active_era()?.index;
+	let oldest_era = active_era.saturating_sub(pallet_staking::Pallet::<T>::history_depth());
+
+	(oldest_era..=active_era).rev().find(|era| {
+		pallet_staking::Pallet::<T>::eras_start_session_index(era)
+			.map_or(false, |start| start <= session)
+	})
+}
+
+/// Reports the validators on the losing side of a dispute to `R` to be slashed.
+///
+/// Inconclusive disputes aren't reported.
+pub struct SlashValidatorsForDisputes<T, R>(PhantomData<(T, R)>);
+
+impl<T: HistoricalStaking, R> SlashValidatorsForDisputes<T, R> {
+	/// Identify the validators of a session by their exposure in the era of the session, skipping
+	/// the ones which can't be found.
+	///
+	/// Returns `None` if the session is unknown, or its era no longer in the staking history.
+	fn identify(
+		session: SessionIndex,
+		validators: impl IntoIterator<Item = ValidatorIndex>,
+	) -> Option<(u32, Vec<IdentificationTuple<T>>)> {
+		let info = session_info::Pallet::<T>::session_info(session)?;
+		let account_keys = session_info::Pallet::<T>::account_keys(session)?;
+		let era = match era_of_session::<T>(session) {
+			Some(era) => era,
+			None => {
+				log::warn!(
+					target: "runtime::disputes",
+					"Session {} is no longer in the staking history",
+					session,
+				);
+				return None
+			},
+		};
+
+		let offenders = validators
+			.into_iter()
+			.filter_map(|index| {
+				let identification = account_keys.get(index.0 as usize).and_then(|stash| {
+					if !pallet_staking::ErasStakers::<T>::contains_key(era, stash) {
+						return None
+					}
+					let exposure = pallet_staking::Pallet::<T>::eras_stakers(era, stash);
+					Some((stash.clone(), exposure))
+				});
+				if identification.is_none() {
+					log::warn!(
+						target: "runtime::disputes",
+						"Unable to identify validator {:?} in session {}",
+						index,
+						session,
+					);
+				}
+				identification
+			})
+			.collect();
+
+		Some((info.validators.len() as u32, offenders))
+	}
+
+	fn report<K: DisputeOffenceKind>(
+		session: SessionIndex,
+		validators: impl IntoIterator<Item = ValidatorIndex>,
+	) where
+		R: ReportOffence<
+			T::AccountId,
+			IdentificationTuple<T>,
+			DisputeOffence<IdentificationTuple<T>, K>,
+		>,
+	{
+		let (validator_set_count, offenders) = match Self::identify(session, validators) {
+			Some((count, offenders)) if !offenders.is_empty() => (count, offenders),
+			_ => return,
+		};
+
+		let offence = DisputeOffence::<_, K>::new(session, validator_set_count, offenders);
+		if let Err(e) = R::report_offence(Vec::new(), offence) {
+			log::warn!(
+				target: "runtime::disputes",
+				"Unable to report the dispute offence in session {}: {:?}",
+				session,
+				e,
+			);
+		}
+	}
+}
+
+impl<T, R> PunishValidators for SlashValidatorsForDisputes<T, R>
+where
+	T: HistoricalStaking,
+	R: ReportOffence<
+			T::AccountId,
+			IdentificationTuple<T>,
+			ForInvalidOffence<IdentificationTuple<T>>,
+		> + ReportOffence<
+			T::AccountId,
+			IdentificationTuple<T>,
+			AgainstValidOffence<IdentificationTuple<T>>,
+		>,
+{
+	fn punish_for_invalid(
+		session: SessionIndex,
+		validators: impl IntoIterator<Item = ValidatorIndex>,
+	) {
+		Self::report::<ForInvalid>(session, validators)
+	}
+
+	fn punish_against_valid(
+		session: SessionIndex,
+		validators: impl IntoIterator<Item = ValidatorIndex>,
+	) {
+		Self::report::<AgainstValid>(session, validators)
+	}
+
+	fn punish_inconclusive(_: SessionIndex, _: impl IntoIterator<Item = ValidatorIndex>) {}
+}
@@ -2451,3 +2451,211 @@ fn import_ignores_single_sided() {
 		assert!(statements.is_empty());
 	})
 }
+
+#[test]
+fn test_punish_validators_pair_punishes_both() {
+	new_test_ext(Default::default()).execute_with(|| {
+		type Pair = (Test, Test);
+
+		Pair::punish_for_invalid(1, vec![ValidatorIndex(0)]);
+		Pair::punish_against_valid(2, vec![ValidatorIndex(1), ValidatorIndex(2)]);
+		Pair::punish_inconclusive(3, vec![]);
+
+		assert_eq!(
+			PUNISH_VALIDATORS_FOR.with(|r| r.borrow().clone()),
+			vec![(1, vec![ValidatorIndex(0)]), (1, vec![ValidatorIndex(0)])],
+		);
+		assert_eq!(
+			PUNISH_VALIDATORS_AGAINST.with(|r| r.borrow().clone()),
+			vec![
+				(2, vec![ValidatorIndex(1), ValidatorIndex(2)]),
+				(2, vec![ValidatorIndex(1), ValidatorIndex(2)]),
+			],
+		);
+		assert_eq!(
+			PUNISH_VALIDATORS_INCONCLUSIVE.with(|r| r.borrow().clone()),
+			vec![(3, vec![]), (3, vec![])],
+		);
+	})
+}
+
+#[test]
+fn test_dispute_offences() {
+	use slashing::{AgainstValidOffence, ForInvalidOffence};
+	use sp_runtime::Perbill;
+	use sp_staking::offence::Offence;
+
+	let for_invalid = ForInvalidOffence::new(3, 10, vec![1u64, 2]);
+	assert_eq!(for_invalid.offenders(), vec![1, 2]);
+	assert_eq!(for_invalid.session_index(), 3);
+	assert_eq!(for_invalid.time_slot(), 3);
+	assert_eq!(for_invalid.validator_set_count(), 10);
+	assert_eq!(ForInvalidOffence::<u64>::slash_fraction(2, 10), Perbill::one());
+
+	assert_eq!(AgainstValidOffence::<u64>::slash_fraction(2, 10), Perbill::zero());
+	assert_ne!(ForInvalidOffence::<u64>::ID, AgainstValidOffence::<u64>::ID);
+}
+
+mod slashing_offenders {
+	use super::*;
+	use crate::session_info;
+	use pallet_staking::{ActiveEraInfo, Exposure, IndividualExposure};
+	use primitives::v2::SessionInfo;
+	use slashing::DisputeOffenceKind as _;
+	use sp_core::sr25519;
+	use sp_staking::{
+		offence::{Kind, Offence, OffenceError, ReportOffence},
+		EraIndex,
+	};
+	use std::cell::RefCell;
+
+	type Offender = pallet_session::historical::IdentificationTuple<Test>;
+
+	thread_local! {
+		static REPORTED_OFFENCES: RefCell<Vec<(Kind, SessionIndex, u32, Vec<Offender>)>> =
+			RefCell::new(Vec::new());
+	}
+
+	/// Records the reported offences.
+	struct RecordingReporter;
+
+	impl<O: Offence<Offender>> ReportOffence<AccountId, Offender, O> for RecordingReporter {
+		fn report_offence(_reporters: Vec<AccountId>, offence: O) -> Result<(), OffenceError> {
+			REPORTED_OFFENCES.with(|r| {
+				r.borrow_mut().push((
+					O::ID,
+					offence.session_index(),
+					offence.validator_set_count(),
+					offence.offenders(),
+				))
+			});
+			Ok(())
+		}
+
+		fn is_known_offence(_offenders: &[Offender], _time_slot: &O::TimeSlot) -> bool {
+			false
+		}
+	}
+
+	type Slash = slashing::SlashValidatorsForDisputes<Test, RecordingReporter>;
+
+	fn reported_offences() -> Vec<(Kind, SessionIndex, u32, Vec<Offender>)> {
+		REPORTED_OFFENCES.with(|r| r.borrow_mut().drain(..).collect())
+	}
+
+	/// Note the validators of `session`, as their stashes and the seeds of their keys.
+	fn note_session(session: SessionIndex, validators: &[(AccountId, u8)]) {
+		let info = SessionInfo {
+			active_validator_indices: (0..validators.len() as u32).map(ValidatorIndex).collect(),
+			random_seed: [0; 32],
+			dispute_period: 6,
+			validators: validators
+				.iter()
+				.map(|(_, seed)| ValidatorId::from(sr25519::Public::from_raw([*seed; 32])))
+				.collect(),
+			discovery_keys: Vec::new(),
+			assignment_keys: Vec::new(),
+			validator_groups: Vec::new(),
+			n_cores: 0,
+			zeroth_delay_tranche_width: 0,
+			relay_vrf_modulo_samples: 0,
+			n_delay_tranches: 0,
+			no_show_slots: 0,
+			needed_approvals: 0,
+		};
+		session_info::Sessions::<Test>::insert(session, info);
+		session_info::AccountKeys::<Test>::insert(
+			session,
+			validators.iter().map(|(stash, _)| *stash).collect::<Vec<_>>(),
+		);
+	}
+
+	/// Start `era` at `start_session`.
+	fn start_era(era: EraIndex, start_session: SessionIndex) {
+		pallet_staking::ErasStartSessionIndex::<Test>::insert(era, start_session);
+		pallet_staking::ActiveEra::<Test>::put(ActiveEraInfo { index: era, start: None });
+	}
+
+	fn exposure(own: u128) -> Exposure<AccountId, u128> {
+		Exposure { total: own + 10, own, others: vec![IndividualExposure { who: 100, value: 10 }] }
+	}
+
+	fn expose(era: EraIndex, stash: AccountId, own: u128) {
+		pallet_staking::ErasStakers::<Test>::insert(era, stash, exposure(own));
+	}
+
+	#[test]
+	fn offenders_are_reported_with_the_exposure_of_the_era_of_the_session() {
+		new_test_ext(Default::default()).execute_with(|| {
+			start_era(4, 12);
+			for (stash, own) in [(11, 100), (21, 200), (31, 300)] {
+				expose(4, stash, own);
+			}
+			start_era(5, 15);
+			for (stash, own) in [(11, 1_100), (21, 1_200), (31, 1_300)] {
+				expose(5, stash, own);
+			}
+			note_session(13, &[(11, 1), (21, 2), (31, 3)]);
+
+			Slash::punish_for_invalid(13, vec![ValidatorIndex(0), ValidatorIndex(2)]);
+
+			assert_eq!(
+				reported_offences(),
+				vec![(
+					slashing::ForInvalid::ID,
+					13,
+					3,
+					vec![(11, exposure(100)), (31, exposure(300))],
+				)],
+			);
+		});
+	}
+
+	#[test]
+	fn offenders_are_resolved_after_rotating_keys() {
+		new_test_ext(Default::default()).execute_with(|| {
+			start_era(4, 12);
+			expose(4, 11, 100);
+			expose(4, 21, 200);
+			note_session(13, &[(11, 1), (21, 2)]);
+
+			// 11 rotated its keys and ended up with another index.
+			start_era(5, 15);
+			expose(5, 11, 1_100);
+			expose(5, 21, 1_200);
+			note_session(16, &[(21, 2), (11, 9)]);
+
+			Slash::punish_against_valid(13, vec![ValidatorIndex(0)]);
+			Slash::punish_against_valid(16, vec![ValidatorIndex(1)]);
+
+			assert_eq!(
+				reported_offences(),
+				vec![
+					(slashing::AgainstValid::ID, 13, 2, vec![(11, exposure(100))]),
+					(slashing::AgainstValid::ID, 16, 2, vec![(11, exposure(1_100))]),
+				],
+			);
+		});
+	}
+
+	#[test]
+	fn sessions_outside_the_staking_history_are_skipped() {
+		new_test_ext(Default::default()).execute_with(|| {
+			let history_depth = pallet_staking::Pallet::<Test>::history_depth();
+			let active_era = history_depth + 10;
+			// Only the eras in the history are kept.
+			for era in (active_era - history_depth)..=active_era {
+				start_era(era, era * 3);
+				expose(era, 11, 100);
+			}
+			note_session(3, &[(11, 1)]);
+
+			Slash::punish_for_invalid(3, vec![ValidatorIndex(0)]);
+			Slash::punish_against_valid(3, vec![ValidatorIndex(0)]);
+			// Unknown sessions are skipped as well.
+			Slash::punish_for_invalid(active_era * 3, vec![ValidatorIndex(0)]);
+
+			assert!(reported_offences().is_empty());
+		});
+	}
+}
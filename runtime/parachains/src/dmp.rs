@@ -20,30 +20,100 @@ use crate::{
 };
 use frame_support::pallet_prelude::*;
 use primitives::v1::{DownwardMessage, Hash, Id as ParaId, InboundDownwardMessage};
-use sp_runtime::traits::{BlakeTwo256, Hash as HashT, SaturatedConversion};
+use scale_info::TypeInfo;
+use sp_runtime::{
+	traits::{BlakeTwo256, Hash as HashT, One, SaturatedConversion, Saturating},
+	FixedPointNumber, FixedU128,
+};
 use sp_std::{fmt, prelude::*};
 use xcm::latest::SendError;
 
 pub use pallet::*;
 
+pub mod migration;
+
 #[cfg(test)]
 mod tests;
 
+/// The maximum number of messages stored in a single page of a downward message queue.
+pub const QUEUE_PAGE_CAPACITY: u32 = 32;
+
+/// Locates the pending messages of a downward message queue within its pages.
+///
+/// The messages are stored in consecutive pages starting at `first_page`, all of which but the
+/// last are full. Processed messages are only removed along with the page holding them, so the
+/// first `first_offset` messages of the first page are stale.
+#[derive(Encode, Decode, Default, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct QueuePageIndex {
+	/// The index of the page holding the oldest pending message.
+	pub first_page: u32,
+	/// The number of already processed messages at the start of the first page.
+	pub first_offset: u32,
+	/// The number of pending messages.
+	pub count: u32,
+	/// The total size of the pending messages, in bytes.
+	pub bytes: u32,
+}
+
+impl QueuePageIndex {
+	/// The index of the page the next message is appended to.
+	fn back_page(&self) -> u32 {
+		self.first_page
+			.wrapping_add((self.first_offset + self.count) / QUEUE_PAGE_CAPACITY)
+	}
+}
+
 /// An error sending a downward message.
 #[cfg_attr(test, derive(Debug))]
 pub enum QueueDownwardMessageError {
 	/// The message being sent exceeds the configured max message size.
 	ExceedsMaxMessageSize,
+	/// The recipient's queue can't take the message without exceeding [`Config::QueueCapacity`].
+	ExceedsQueueCapacity,
 }
 
 impl From<QueueDownwardMessageError> for SendError {
 	fn from(err: QueueDownwardMessageError) -> Self {
 		match err {
 			QueueDownwardMessageError::ExceedsMaxMessageSize => SendError::ExceedsMaxMessageSize,
+			QueueDownwardMessageError::ExceedsQueueCapacity =>
+				SendError::Transport("Downward message queue is full"),
 		}
 	}
 }
 
+/// A threshold on the contents of a downward message queue.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub struct QueueWatermark {
+	/// The number of messages in the queue.
+	pub count: u32,
+	/// The total size of the messages in the queue, in bytes.
+	pub bytes: u32,
+}
+
+/// A sensible default for [`Config::QueueHighWatermark`].
+pub const DEFAULT_QUEUE_HIGH_WATERMARK: QueueWatermark =
+	QueueWatermark { count: 100, bytes: 512 * 1024 };
+
+/// A sensible default for [`Config::QueueLowWatermark`].
+pub const DEFAULT_QUEUE_LOW_WATERMARK: QueueWatermark =
+	QueueWatermark { count: 10, bytes: 64 * 1024 };
+
+/// A sensible default for [`Config::QueueCapacity`].
+pub const DEFAULT_QUEUE_CAPACITY: QueueWatermark =
+	QueueWatermark { count: 1000, bytes: 4 * 1024 * 1024 };
+
+/// The factor the delivery fee factor of a para is multiplied with for every message queued while
+/// its queue is under pressure, and divided by for every pruning after the pressure was relieved.
+fn exponential_fee_base() -> FixedU128 {
+	FixedU128::saturating_from_rational(105u32, 100u32)
+}
+
+/// The addition to [`exponential_fee_base`] per kilobyte of a message queued under pressure.
+fn message_size_fee_base() -> FixedU128 {
+	FixedU128::saturating_from_rational(1u32, 1000u32)
+}
+
 /// An error returned by [`check_processed_downward_messages`] that indicates an acceptance check
 /// didn't pass.
 pub enum ProcessedDownwardMessagesAcceptanceErr {
@@ -71,25 +141,61 @@ impl fmt::Debug for ProcessedDownwardMessagesAcceptanceErr {
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
+	use frame_system::pallet_prelude::BlockNumberFor;
 
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(migration::STORAGE_VERSION)]
 	#[pallet::without_storage_info]
 	pub struct Pallet<T>(_);
 
 	#[pallet::config]
-	pub trait Config: frame_system::Config + configuration::Config {}
+	pub trait Config: frame_system::Config + configuration::Config {
+		/// The aggregate event.
+		type Event: From<Event> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// A downward message queue holding at least this many messages or bytes is considered to
+		/// be under pressure.
+		type QueueHighWatermark: Get<QueueWatermark>;
+
+		/// A downward message queue under pressure is relieved once it holds at most this many
+		/// messages and bytes.
+		type QueueLowWatermark: Get<QueueWatermark>;
+
+		/// The maximum number of messages and bytes a downward message queue may hold. Messages
+		/// which would exceed either are rejected.
+		type QueueCapacity: Get<QueueWatermark>;
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event {
+		/// The downward message queue of a para reached the high watermark.
+		/// \[ para, count, bytes \]
+		QueuePressureHigh(ParaId, u32, u32),
+		/// The downward message queue of a para under pressure was drained to the low watermark.
+		/// \[ para, count, bytes \]
+		QueuePressureRelieved(ParaId, u32, u32),
+	}
 
-	/// The downward messages addressed for a certain para.
+	/// The pages of the downward messages addressed for a certain para, keyed by the para and
+	/// the index of the page.
 	#[pallet::storage]
-	pub(crate) type DownwardMessageQueues<T: Config> = StorageMap<
+	pub(crate) type DownwardMessageQueuePages<T: Config> = StorageDoubleMap<
 		_,
 		Twox64Concat,
 		ParaId,
+		Twox64Concat,
+		u32,
 		Vec<InboundDownwardMessage<T::BlockNumber>>,
 		ValueQuery,
 	>;
 
+	/// The location of the pending downward messages of a para within its pages.
+	#[pallet::storage]
+	pub(crate) type DownwardMessageQueueIndex<T: Config> =
+		StorageMap<_, Twox64Concat, ParaId, QueuePageIndex, OptionQuery>;
+
 	/// A mapping that stores the downward message queue MQC head for each para.
 	///
 	/// Each link in this chain has a form:
@@ -101,6 +207,32 @@ pub mod pallet {
 	pub(crate) type DownwardMessageQueueHeads<T: Config> =
 		StorageMap<_, Twox64Concat, ParaId, Hash, ValueQuery>;
 
+	/// The paras whose downward message queue reached the high watermark and wasn't drained to
+	/// the low watermark since.
+	#[pallet::storage]
+	pub(crate) type QueuesUnderPressure<T: Config> =
+		StorageMap<_, Twox64Concat, ParaId, (), OptionQuery>;
+
+	#[pallet::type_value]
+	pub(crate) fn InitialFactor() -> FixedU128 {
+		FixedU128::one()
+	}
+
+	/// The factor to multiply the base delivery fee of a downward message to a para by.
+	///
+	/// It grows exponentially while the queue of the para is under pressure and decays back to one
+	/// after the pressure is relieved, so that senders can price floods of messages out.
+	#[pallet::storage]
+	pub(crate) type DeliveryFeeFactor<T: Config> =
+		StorageMap<_, Twox64Concat, ParaId, FixedU128, ValueQuery, InitialFactor>;
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_runtime_upgrade() -> Weight {
+			migration::migrate_to_latest::<T>()
+		}
+	}
+
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {}
 }
@@ -133,13 +265,17 @@ impl<T: Config> Pallet<T> {
 
 	/// Remove all relevant storage items for an outgoing parachain.
 	fn clean_dmp_after_outgoing(outgoing_para: &ParaId) {
-		<Self as Store>::DownwardMessageQueues::remove(outgoing_para);
+		<Self as Store>::DownwardMessageQueuePages::remove_prefix(outgoing_para, None);
+		<Self as Store>::DownwardMessageQueueIndex::remove(outgoing_para);
 		<Self as Store>::DownwardMessageQueueHeads::remove(outgoing_para);
+		<Self as Store>::QueuesUnderPressure::remove(outgoing_para);
+		<Self as Store>::DeliveryFeeFactor::remove(outgoing_para);
 	}
 
 	/// Enqueue a downward message to a specific recipient para.
 	///
-	/// When encoded, the message should not exceed the `config.max_downward_message_size`.
+	/// When encoded, the message should not exceed the `config.max_downward_message_size`, and the
+	/// queue of the recipient should have room for it within [`Config::QueueCapacity`].
 	/// Otherwise, the message won't be sent and `Err` will be returned.
 	///
 	/// It is possible to send a downward message to a non-existent para. That, however, would lead
@@ -155,6 +291,14 @@ impl<T: Config> Pallet<T> {
 			return Err(QueueDownwardMessageError::ExceedsMaxMessageSize)
 		}
 
+		let mut index = <Self as Store>::DownwardMessageQueueIndex::get(para).unwrap_or_default();
+		let capacity = T::QueueCapacity::get();
+		if index.count >= capacity.count ||
+			index.bytes.saturating_add(serialized_len) > capacity.bytes
+		{
+			return Err(QueueDownwardMessageError::ExceedsQueueCapacity)
+		}
+
 		let inbound =
 			InboundDownwardMessage { msg, sent_at: <frame_system::Pallet<T>>::block_number() };
 
@@ -165,9 +309,20 @@ impl<T: Config> Pallet<T> {
 			*head = new_head;
 		});
 
-		<Self as Store>::DownwardMessageQueues::mutate(para, |v| {
-			v.push(inbound);
-		});
+		<Self as Store>::DownwardMessageQueuePages::append(para, index.back_page(), inbound);
+		index.count += 1;
+		index.bytes = index.bytes.saturating_add(serialized_len);
+		Self::update_queue_pressure(para, &index);
+		<Self as Store>::DownwardMessageQueueIndex::insert(para, index);
+
+		if <Self as Store>::QueuesUnderPressure::contains_key(para) {
+			let kilobytes = FixedU128::saturating_from_integer(serialized_len / 1024);
+			let increase =
+				exponential_fee_base() + message_size_fee_base().saturating_mul(kilobytes);
+			<Self as Store>::DeliveryFeeFactor::mutate(para, |factor| {
+				*factor = factor.saturating_mul(increase);
+			});
+		}
 
 		Ok(())
 	}
@@ -193,18 +348,98 @@ impl<T: Config> Pallet<T> {
 	}
 
 	/// Prunes the specified number of messages from the downward message queue of the given para.
+	///
+	/// Only the pages holding the processed messages are read, and a page is only removed once
+	/// all of its messages are processed.
 	pub(crate) fn prune_dmq(para: ParaId, processed_downward_messages: u32) -> Weight {
-		<Self as Store>::DownwardMessageQueues::mutate(para, |q| {
-			let processed_downward_messages = processed_downward_messages as usize;
-			if processed_downward_messages > q.len() {
-				// reaching this branch is unexpected due to the constraint established by
-				// `check_processed_downward_messages`. But better be safe than sorry.
-				q.clear();
-			} else {
-				*q = q.split_off(processed_downward_messages);
+		let mut pages_touched = 0;
+		<Self as Store>::DownwardMessageQueueIndex::mutate_exists(para, |maybe_index| {
+			let index = match maybe_index {
+				Some(index) => index,
+				None => return,
+			};
+
+			// `processed_downward_messages` exceeding the queue length is unexpected due to the
+			// constraint established by `check_processed_downward_messages`. But better be safe
+			// than sorry.
+			let mut to_prune = processed_downward_messages.min(index.count);
+			while to_prune > 0 {
+				pages_touched += 1;
+				let page = <Self as Store>::DownwardMessageQueuePages::get(para, index.first_page);
+				let pruned = page
+					.iter()
+					.skip(index.first_offset as usize)
+					.take(to_prune as usize)
+					.fold((0u32, 0u32), |(count, bytes), m| {
+						(count + 1, bytes.saturating_add(m.msg.len().saturated_into::<u32>()))
+					});
+				if pruned.0 == 0 {
+					// The page doesn't hold the messages the index claims it does.
+					index.count = 0;
+					break
+				}
+
+				to_prune -= pruned.0;
+				index.count -= pruned.0;
+				index.bytes = index.bytes.saturating_sub(pruned.1);
+				index.first_offset += pruned.0;
+
+				if index.first_offset >= QUEUE_PAGE_CAPACITY || index.count == 0 {
+					<Self as Store>::DownwardMessageQueuePages::remove(para, index.first_page);
+					index.first_page = index.first_page.wrapping_add(1);
+					index.first_offset = 0;
+				}
 			}
+
+			Self::update_queue_pressure(para, index);
+			if index.count == 0 {
+				*maybe_index = None;
+			}
+		});
+		if !<Self as Store>::QueuesUnderPressure::contains_key(para) {
+			Self::decrease_delivery_fee_factor(para);
+		}
+		T::DbWeight::get().reads_writes(3 + pages_touched, 2 + pages_touched)
+	}
+
+	/// Decay the delivery fee factor of a para one step towards one.
+	fn decrease_delivery_fee_factor(para: ParaId) {
+		<Self as Store>::DeliveryFeeFactor::mutate_exists(para, |maybe_factor| {
+			let factor = match maybe_factor {
+				Some(factor) => *factor,
+				None => return,
+			};
+			let decreased = factor / exponential_fee_base();
+			*maybe_factor = if decreased > FixedU128::one() { Some(decreased) } else { None };
 		});
-		T::DbWeight::get().reads_writes(1, 1)
+	}
+
+	/// Returns the factor the base delivery fee of a downward message to the given para should be
+	/// multiplied by.
+	///
+	/// Returns one if the queue of the para isn't and recently wasn't under pressure.
+	pub fn delivery_fee_factor(para: ParaId) -> FixedU128 {
+		<Self as Store>::DeliveryFeeFactor::get(para)
+	}
+
+	/// Note the contents of the downward message queue of a para after it changed, emitting an
+	/// event if it reached the high or drained to the low watermark.
+	fn update_queue_pressure(para: ParaId, index: &QueuePageIndex) {
+		let QueuePageIndex { count, bytes, .. } = *index;
+
+		if <Self as Store>::QueuesUnderPressure::contains_key(para) {
+			let low = T::QueueLowWatermark::get();
+			if count <= low.count && bytes <= low.bytes {
+				<Self as Store>::QueuesUnderPressure::remove(para);
+				Self::deposit_event(Event::QueuePressureRelieved(para, count, bytes));
+			}
+		} else {
+			let high = T::QueueHighWatermark::get();
+			if count >= high.count || bytes >= high.bytes {
+				<Self as Store>::QueuesUnderPressure::insert(para, ());
+				Self::deposit_event(Event::QueuePressureHigh(para, count, bytes));
+			}
+		}
 	}
 
 	/// Returns the Head of Message Queue Chain for the given para or `None` if there is none
@@ -217,16 +452,31 @@ impl<T: Config> Pallet<T> {
 	/// Returns the number of pending downward messages addressed to the given para.
 	///
 	/// Returns 0 if the para doesn't have an associated downward message queue.
-	pub(crate) fn dmq_length(para: ParaId) -> u32 {
-		<Self as Store>::DownwardMessageQueues::decode_len(&para)
-			.unwrap_or(0)
-			.saturated_into::<u32>()
+	pub fn dmq_length(para: ParaId) -> u32 {
+		<Self as Store>::DownwardMessageQueueIndex::get(&para).map_or(0, |index| index.count)
 	}
 
 	/// Returns the downward message queue contents for the given para.
 	///
 	/// The most recent messages are the latest in the vector.
 	pub(crate) fn dmq_contents(recipient: ParaId) -> Vec<InboundDownwardMessage<T::BlockNumber>> {
-		<Self as Store>::DownwardMessageQueues::get(&recipient)
+		let index = match <Self as Store>::DownwardMessageQueueIndex::get(&recipient) {
+			Some(index) => index,
+			None => return Vec::new(),
+		};
+
+		let mut contents = Vec::with_capacity(index.count as usize);
+		let mut page = index.first_page;
+		let mut skip = index.first_offset as usize;
+		while contents.len() < index.count as usize {
+			let messages = <Self as Store>::DownwardMessageQueuePages::get(&recipient, page);
+			if messages.is_empty() {
+				break
+			}
+			contents.extend(messages.into_iter().skip(skip));
+			page = page.wrapping_add(1);
+			skip = 0;
+		}
+		contents
 	}
 }
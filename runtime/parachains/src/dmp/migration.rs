@@ -0,0 +1,81 @@
+// Copyright 2021 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A module that is responsible for migration of storage.
+
+use crate::dmp::{Config, Pallet, QueuePageIndex, Store, QUEUE_PAGE_CAPACITY};
+use frame_support::{
+	pallet_prelude::*,
+	storage::migration::storage_key_iter,
+	traits::{PalletInfoAccess, StorageVersion},
+	weights::Weight,
+};
+use primitives::v1::{Id as ParaId, InboundDownwardMessage};
+use sp_runtime::traits::SaturatedConversion;
+use sp_std::prelude::*;
+
+/// The current storage version.
+pub const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
+/// Migrates the pallet storage to the most recent version, checking and setting the `StorageVersion`.
+pub fn migrate_to_latest<T: Config>() -> Weight {
+	let mut weight = 0;
+	if StorageVersion::get::<Pallet<T>>() < 1 {
+		weight += migrate_to_v1::<T>();
+		StorageVersion::new(1).put::<Pallet<T>>();
+	}
+	weight
+}
+
+/// Moves the downward message queues, previously stored as a single vector per para under
+/// `DownwardMessageQueues`, into pages.
+pub fn migrate_to_v1<T: Config>() -> Weight {
+	let queues = storage_key_iter::<
+		ParaId,
+		Vec<InboundDownwardMessage<T::BlockNumber>>,
+		Twox64Concat,
+	>(<Pallet<T>>::name().as_bytes(), b"DownwardMessageQueues")
+	.drain()
+	.collect::<Vec<_>>();
+
+	let mut vs = 0;
+	for (para, queue) in queues {
+		vs += 1;
+		if queue.is_empty() {
+			continue
+		}
+
+		for (page, messages) in queue.chunks(QUEUE_PAGE_CAPACITY as usize).enumerate() {
+			vs += 1;
+			<Pallet<T> as Store>::DownwardMessageQueuePages::insert(para, page as u32, messages);
+		}
+
+		let bytes = queue
+			.iter()
+			.fold(0u32, |bytes, m| bytes.saturating_add(m.msg.len().saturated_into::<u32>()));
+		<Pallet<T> as Store>::DownwardMessageQueueIndex::insert(
+			para,
+			QueuePageIndex {
+				first_page: 0,
+				first_offset: 0,
+				count: queue.len().saturated_into::<u32>(),
+				bytes,
+			},
+		);
+	}
+
+	T::DbWeight::get().reads_writes(vs, vs)
+}
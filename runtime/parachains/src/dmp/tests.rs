@@ -73,9 +73,11 @@ fn clean_dmp_works() {
 		let outgoing_paras = vec![a, b];
 		Dmp::initializer_on_new_session(&notification, &outgoing_paras);
 
-		assert!(<Dmp as Store>::DownwardMessageQueues::get(&a).is_empty());
-		assert!(<Dmp as Store>::DownwardMessageQueues::get(&b).is_empty());
-		assert!(!<Dmp as Store>::DownwardMessageQueues::get(&c).is_empty());
+		assert!(Dmp::dmq_contents(a).is_empty());
+		assert!(Dmp::dmq_contents(b).is_empty());
+		assert!(!Dmp::dmq_contents(c).is_empty());
+		assert_eq!(<Dmp as Store>::DownwardMessageQueuePages::iter_prefix(a).count(), 0);
+		assert!(<Dmp as Store>::DownwardMessageQueueIndex::get(a).is_none());
 	});
 }
 
@@ -160,6 +162,136 @@ fn dmq_pruning() {
 	});
 }
 
+#[test]
+fn dmq_pages_are_pruned_lazily() {
+	let a = ParaId::from(1312);
+	let pages = || <Dmp as Store>::DownwardMessageQueuePages::iter_key_prefix(a).count();
+
+	new_test_ext(default_genesis_config()).execute_with(|| {
+		let messages = (0..2 * QUEUE_PAGE_CAPACITY + 5).map(|i| i.encode()).collect::<Vec<_>>();
+		for msg in &messages {
+			queue_downward_message(a, msg.clone()).unwrap();
+		}
+		assert_eq!(Dmp::dmq_length(a), 2 * QUEUE_PAGE_CAPACITY + 5);
+		assert_eq!(pages(), 3);
+
+		// the first page is kept until all of its messages are processed.
+		Dmp::prune_dmq(a, QUEUE_PAGE_CAPACITY - 1);
+		assert_eq!(pages(), 3);
+		let contents = Dmp::dmq_contents(a);
+		assert_eq!(contents.len() as u32, QUEUE_PAGE_CAPACITY + 6);
+		assert_eq!(contents[0].msg, messages[QUEUE_PAGE_CAPACITY as usize - 1]);
+
+		Dmp::prune_dmq(a, 2);
+		assert_eq!(pages(), 2);
+		assert_eq!(Dmp::dmq_contents(a)[0].msg, messages[QUEUE_PAGE_CAPACITY as usize + 1]);
+
+		// new messages are appended to the last page.
+		queue_downward_message(a, vec![1, 2, 3]).unwrap();
+		assert_eq!(pages(), 2);
+		assert_eq!(Dmp::dmq_contents(a).last().unwrap().msg, vec![1, 2, 3]);
+
+		Dmp::prune_dmq(a, Dmp::dmq_length(a));
+		assert_eq!(Dmp::dmq_length(a), 0);
+		assert_eq!(pages(), 0);
+		assert!(<Dmp as Store>::DownwardMessageQueueIndex::get(a).is_none());
+	});
+}
+
+#[test]
+fn migrates_queues_into_pages() {
+	use frame_support::{
+		storage::migration::put_storage_value, traits::PalletInfoAccess, StorageHasher,
+	};
+
+	let a = ParaId::from(1312);
+	let b = ParaId::from(228);
+
+	new_test_ext(default_genesis_config()).execute_with(|| {
+		let queue = (0..QUEUE_PAGE_CAPACITY + 1)
+			.map(|i| InboundDownwardMessage { sent_at: i, msg: vec![i as u8; 2] })
+			.collect::<Vec<_>>();
+		let pallet = <Dmp as PalletInfoAccess>::name().as_bytes();
+		put_storage_value(
+			pallet,
+			b"DownwardMessageQueues",
+			&a.using_encoded(Twox64Concat::hash),
+			&queue,
+		);
+		put_storage_value(
+			pallet,
+			b"DownwardMessageQueues",
+			&b.using_encoded(Twox64Concat::hash),
+			Vec::<InboundDownwardMessage<BlockNumber>>::new(),
+		);
+
+		migration::migrate_to_v1::<crate::mock::Test>();
+
+		assert_eq!(Dmp::dmq_contents(a), queue);
+		assert_eq!(
+			<Dmp as Store>::DownwardMessageQueueIndex::get(a),
+			Some(QueuePageIndex {
+				first_page: 0,
+				first_offset: 0,
+				count: QUEUE_PAGE_CAPACITY + 1,
+				bytes: 2 * (QUEUE_PAGE_CAPACITY + 1),
+			}),
+		);
+		assert_eq!(<Dmp as Store>::DownwardMessageQueuePages::iter_prefix(a).count(), 2);
+		assert_eq!(Dmp::dmq_length(b), 0);
+		assert!(frame_support::storage::migration::storage_key_iter::<
+			ParaId,
+			Vec<InboundDownwardMessage<BlockNumber>>,
+			Twox64Concat,
+		>(pallet, b"DownwardMessageQueues")
+		.next()
+		.is_none());
+	});
+}
+
+#[test]
+fn dmq_pressure_events_are_emitted_at_watermarks() {
+	let a = ParaId::from(1312);
+	let b = ParaId::from(228);
+
+	let pressure_events = || {
+		System::events()
+			.into_iter()
+			.filter_map(|r| match r.event {
+				crate::mock::Event::Dmp(e) => Some(e),
+				_ => None,
+			})
+			.collect::<Vec<_>>()
+	};
+
+	new_test_ext(default_genesis_config()).execute_with(|| {
+		// events are not deposited in the genesis block.
+		System::set_block_number(1);
+
+		// the high watermark is reached by the count of messages...
+		for _ in 0..3 {
+			queue_downward_message(a, vec![1, 2, 3]).unwrap();
+		}
+		assert!(pressure_events().is_empty());
+		queue_downward_message(a, vec![1, 2, 3]).unwrap();
+		assert_eq!(pressure_events(), vec![Event::QueuePressureHigh(a, 4, 12)]);
+
+		// ...or by their size.
+		queue_downward_message(b, vec![0; 1000]).unwrap();
+		queue_downward_message(b, vec![0; 30]).unwrap();
+		assert_eq!(pressure_events().last(), Some(&Event::QueuePressureHigh(b, 2, 1030)));
+
+		// a queue under pressure doesn't emit again, until it's drained to the low watermark.
+		queue_downward_message(a, vec![1, 2, 3]).unwrap();
+		Dmp::prune_dmq(a, 3);
+		assert_eq!(pressure_events().len(), 2);
+
+		Dmp::prune_dmq(a, 1);
+		assert_eq!(pressure_events().last(), Some(&Event::QueuePressureRelieved(a, 1, 3)));
+		assert_eq!(pressure_events().len(), 3);
+	});
+}
+
 #[test]
 fn queue_downward_message_critical() {
 	let a = ParaId::from(1312);
@@ -181,6 +313,75 @@ fn queue_downward_message_critical() {
 	});
 }
 
+#[test]
+fn queue_capacity_is_enforced() {
+	let a = ParaId::from(1312);
+
+	new_test_ext(default_genesis_config()).execute_with(|| {
+		crate::mock::DmpQueueCapacity::set(QueueWatermark { count: 3, bytes: 10 });
+
+		// the capacity is exceeded by the size of the messages...
+		queue_downward_message(a, vec![0; 4]).unwrap();
+		queue_downward_message(a, vec![0; 4]).unwrap();
+		let head = Dmp::dmq_mqc_head(a);
+		assert!(matches!(
+			queue_downward_message(a, vec![0; 4]),
+			Err(QueueDownwardMessageError::ExceedsQueueCapacity)
+		));
+		assert_eq!(Dmp::dmq_length(a), 2);
+		assert_eq!(Dmp::dmq_mqc_head(a), head);
+
+		// ...or by their count.
+		queue_downward_message(a, vec![0; 2]).unwrap();
+		assert!(matches!(
+			queue_downward_message(a, vec![0]),
+			Err(QueueDownwardMessageError::ExceedsQueueCapacity)
+		));
+
+		// processing messages makes room again.
+		Dmp::prune_dmq(a, 1);
+		queue_downward_message(a, vec![0]).unwrap();
+		assert_eq!(Dmp::dmq_length(a), 3);
+	});
+}
+
+#[test]
+fn delivery_fee_factor_grows_under_pressure() {
+	let a = ParaId::from(1312);
+
+	new_test_ext(default_genesis_config()).execute_with(|| {
+		// the factor stays put until the high watermark is reached.
+		for _ in 0..3 {
+			queue_downward_message(a, vec![1, 2, 3]).unwrap();
+		}
+		assert_eq!(Dmp::delivery_fee_factor(a), FixedU128::one());
+
+		queue_downward_message(a, vec![1, 2, 3]).unwrap();
+		assert_eq!(Dmp::delivery_fee_factor(a), FixedU128::saturating_from_rational(105, 100));
+
+		// bigger messages increase it further.
+		queue_downward_message(a, vec![0; 1024]).unwrap();
+		assert_eq!(
+			Dmp::delivery_fee_factor(a),
+			FixedU128::saturating_from_rational(110355, 100000),
+		);
+
+		// pruning decays it, but only once the pressure is relieved.
+		Dmp::prune_dmq(a, 1);
+		assert_eq!(
+			Dmp::delivery_fee_factor(a),
+			FixedU128::saturating_from_rational(110355, 100000),
+		);
+		Dmp::prune_dmq(a, 4);
+		assert_eq!(Dmp::delivery_fee_factor(a), FixedU128::saturating_from_rational(1051, 1000));
+
+		Dmp::prune_dmq(a, 0);
+		Dmp::prune_dmq(a, 0);
+		assert_eq!(Dmp::delivery_fee_factor(a), FixedU128::one());
+		assert!(!<Dmp as Store>::DeliveryFeeFactor::contains_key(a));
+	});
+}
+
 #[test]
 fn verify_dmq_mqc_head_is_externally_accessible() {
 	use hex_literal::hex;
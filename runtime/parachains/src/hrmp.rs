@@ -18,15 +18,20 @@ use crate::{
 	configuration::{self, HostConfiguration},
 	dmp, ensure_parachain, initializer, paras,
 };
-use frame_support::{pallet_prelude::*, traits::ReservableCurrency};
+use frame_support::{
+	pallet_prelude::*,
+	traits::{Contains, ReservableCurrency},
+};
 use frame_system::pallet_prelude::*;
 use parity_scale_codec::{Decode, Encode};
 use primitives::v1::{
 	Balance, Hash, HrmpChannelId, Id as ParaId, InboundHrmpMessage, OutboundHrmpMessage,
-	SessionIndex,
+	SessionIndex, LOWEST_PUBLIC_ID,
 };
 use scale_info::TypeInfo;
-use sp_runtime::traits::{AccountIdConversion, BlakeTwo256, Hash as HashT, UniqueSaturatedInto};
+use sp_runtime::traits::{
+	AccountIdConversion, BlakeTwo256, Hash as HashT, Saturating, UniqueSaturatedInto, Zero,
+};
 use sp_std::{
 	collections::{btree_map::BTreeMap, btree_set::BTreeSet},
 	fmt, mem,
@@ -35,6 +40,16 @@ use sp_std::{
 
 pub use pallet::*;
 
+/// The system paras, i.e. those with an id below [`LOWEST_PUBLIC_ID`], as well as the paras listed
+/// by `Extra`.
+pub struct IsSystemPara<Extra>(PhantomData<Extra>);
+
+impl<Extra: Get<Vec<ParaId>>> Contains<ParaId> for IsSystemPara<Extra> {
+	fn contains(para: &ParaId) -> bool {
+		*para < LOWEST_PUBLIC_ID || Extra::get().contains(para)
+	}
+}
+
 /// Maximum bound that can be set for inbound channels.
 ///
 /// If inaccurate, the weighing of this pallet might become inaccurate. It is expected form the
@@ -158,6 +173,7 @@ pub enum OutboundHrmpAcceptanceErr {
 	MoreMessagesThanPermitted { sent: u32, permitted: u32 },
 	NotSorted { idx: u32 },
 	NoSuchChannel { idx: u32, channel_id: HrmpChannelId },
+	ChannelClosing { idx: u32, channel_id: HrmpChannelId },
 	MaxMessageSizeExceeded { idx: u32, msg_size: u32, max_size: u32 },
 	TotalSizeExceeded { idx: u32, total_size: u32, limit: u32 },
 	CapacityExceeded { idx: u32, count: u32, limit: u32 },
@@ -206,6 +222,11 @@ impl fmt::Debug for OutboundHrmpAcceptanceErr {
 				"the HRMP message at index {} is sent to a non existent channel {:?}->{:?}",
 				idx, channel_id.sender, channel_id.recipient,
 			),
+			ChannelClosing { idx, channel_id } => write!(
+				fmt,
+				"the HRMP message at index {} is sent to the closing channel {:?}->{:?}",
+				idx, channel_id.sender, channel_id.recipient,
+			),
 			MaxMessageSizeExceeded { idx, msg_size, max_size } => write!(
 				fmt,
 				"the HRMP message at index {} exceeds the negotiated channel maximum message size ({} > {})",
@@ -252,6 +273,18 @@ pub mod pallet {
 		/// implementation should be the same as `Balance` as used in the `Configuration`.
 		type Currency: ReservableCurrency<Self::AccountId>;
 
+		/// The number of blocks a channel stays open for draining after its closure took effect.
+		///
+		/// During this period the sender cannot send any new messages, but the messages that are
+		/// already in the channel can still be received by the recipient. The channel is removed
+		/// as soon as it is drained or the period ends, whichever comes first.
+		#[pallet::constant]
+		type ChannelCloseGracePeriod: Get<Self::BlockNumber>;
+
+		/// The paras which neither pay deposits for their channels nor are subject to the limits
+		/// on the number of channels they can open or accept, such as system parachains.
+		type SystemParas: Contains<ParaId>;
+
 		/// Something that provides the weight of this pallet.
 		type WeightInfo: WeightInfo;
 	}
@@ -358,6 +391,21 @@ pub mod pallet {
 	pub type HrmpCloseChannelRequestsList<T: Config> =
 		StorageValue<_, Vec<HrmpChannelId>, ValueQuery>;
 
+	/// The channels whose closure took effect but which still have messages to be drained,
+	/// mapped to the block number at which they are removed regardless.
+	///
+	/// The set is accompanied by a list for iteration.
+	///
+	/// Invariants:
+	/// - There are no channels that exists in list but not in the set and vice versa.
+	/// - Each channel in the set is present in `HrmpChannels`.
+	#[pallet::storage]
+	pub type HrmpClosingChannels<T: Config> =
+		StorageMap<_, Twox64Concat, HrmpChannelId, T::BlockNumber>;
+
+	#[pallet::storage]
+	pub type HrmpClosingChannelsList<T: Config> = StorageValue<_, Vec<HrmpChannelId>, ValueQuery>;
+
 	/// The HRMP watermark associated with each para.
 	/// Invariant:
 	/// - each para `P` used here as a key should satisfy `Paras::is_valid_para(P)` within a session.
@@ -608,8 +656,8 @@ fn preopen_hrmp_channel<T: Config>(
 /// Routines and getters related to HRMP.
 impl<T: Config> Pallet<T> {
 	/// Block initialization logic, called by initializer.
-	pub(crate) fn initializer_initialize(_now: T::BlockNumber) -> Weight {
-		0
+	pub(crate) fn initializer_initialize(now: T::BlockNumber) -> Weight {
+		Self::process_hrmp_closing_channels(now)
 	}
 
 	/// Block finalization logic, called by initializer.
@@ -694,14 +742,14 @@ impl<T: Config> Pallet<T> {
 
 			// If the request was confirmed, then it means it was confirmed in the finished session.
 			// Therefore, the config's hrmp_recipient_deposit represents the actual value of the
-			// deposit.
+			// deposit, unless the recipient is exempt from it.
 			//
 			// We still want to refund the deposit only if the para is not being offboarded.
 			if req_data.confirmed {
 				if !outgoing.contains(&req_id.recipient) {
 					T::Currency::unreserve(
 						&req_id.recipient.into_account(),
-						config.hrmp_recipient_deposit.unique_saturated_into(),
+						Self::recipient_deposit(config, req_id.recipient).unique_saturated_into(),
 					);
 				}
 				Self::decrease_accepted_channel_request_count(req_id.recipient);
@@ -764,7 +812,10 @@ impl<T: Config> Pallet<T> {
 						&channel_id,
 						HrmpChannel {
 							sender_deposit: request.sender_deposit,
-							recipient_deposit: config.hrmp_recipient_deposit,
+							recipient_deposit: Self::recipient_deposit(
+								config,
+								channel_id.recipient,
+							),
 							max_capacity: request.max_capacity,
 							max_total_size: request.max_total_size,
 							max_message_size: request.max_message_size,
@@ -798,12 +849,43 @@ impl<T: Config> Pallet<T> {
 	}
 
 	/// Iterate over all close channel requests unconditionally closing the channels.
+	///
+	/// Channels that still have messages in them are not removed right away, but are left for the
+	/// recipient to drain them during the grace period.
 	fn process_hrmp_close_channel_requests() {
 		let close_reqs = <Self as Store>::HrmpCloseChannelRequestsList::take();
+		let grace_period = T::ChannelCloseGracePeriod::get();
+		let deadline = <frame_system::Pallet<T>>::block_number().saturating_add(grace_period);
 		for condemned_ch_id in close_reqs {
 			<Self as Store>::HrmpCloseChannelRequests::remove(&condemned_ch_id);
-			Self::close_hrmp_channel(&condemned_ch_id);
+
+			let drained = !<Self as Store>::HrmpChannelContents::contains_key(&condemned_ch_id);
+			if drained || grace_period.is_zero() {
+				Self::close_hrmp_channel(&condemned_ch_id);
+			} else {
+				<Self as Store>::HrmpClosingChannels::insert(&condemned_ch_id, deadline);
+				<Self as Store>::HrmpClosingChannelsList::append(condemned_ch_id);
+			}
+		}
+	}
+
+	/// Remove the closing channels whose grace period ended by the given block number.
+	fn process_hrmp_closing_channels(now: T::BlockNumber) -> Weight {
+		let closing = <Self as Store>::HrmpClosingChannelsList::get();
+		let mut weight = T::DbWeight::get().reads(1);
+
+		for channel_id in closing {
+			weight += T::DbWeight::get().reads(1);
+			match <Self as Store>::HrmpClosingChannels::get(&channel_id) {
+				Some(deadline) if deadline <= now => {
+					Self::close_hrmp_channel(&channel_id);
+					weight += T::DbWeight::get().reads_writes(3, 7);
+				},
+				_ => {},
+			}
 		}
+
+		weight
 	}
 
 	/// Close and remove the designated HRMP channel.
@@ -828,6 +910,10 @@ impl<T: Config> Pallet<T> {
 
 		<Self as Store>::HrmpChannelContents::remove(channel_id);
 
+		if <Self as Store>::HrmpClosingChannels::take(channel_id).is_some() {
+			<Self as Store>::HrmpClosingChannelsList::mutate(|v| v.retain(|c| c != channel_id));
+		}
+
 		<Self as Store>::HrmpEgressChannelsIndex::mutate(&channel_id.sender, |v| {
 			if let Ok(i) = v.binary_search(&channel_id.recipient) {
 				v.remove(i);
@@ -921,6 +1007,11 @@ impl<T: Config> Pallet<T> {
 				None => return Err(OutboundHrmpAcceptanceErr::NoSuchChannel { channel_id, idx }),
 			};
 
+			// a closing channel is only kept around for the recipient to drain it.
+			if <Self as Store>::HrmpClosingChannels::contains_key(&channel_id) {
+				return Err(OutboundHrmpAcceptanceErr::ChannelClosing { channel_id, idx })
+			}
+
 			let msg_size = out_msg.data.len() as u32;
 			if msg_size > channel.max_message_size {
 				return Err(OutboundHrmpAcceptanceErr::MaxMessageSizeExceeded {
@@ -990,7 +1081,8 @@ impl<T: Config> Pallet<T> {
 					leftover.push(msg);
 				}
 			}
-			if !leftover.is_empty() {
+			let drained = leftover.is_empty();
+			if !drained {
 				<Self as Store>::HrmpChannelContents::insert(&channel_id, leftover);
 			} else {
 				<Self as Store>::HrmpChannelContents::remove(&channel_id);
@@ -1005,6 +1097,13 @@ impl<T: Config> Pallet<T> {
 			});
 
 			weight += T::DbWeight::get().reads_writes(2, 2);
+
+			// a closing channel has served its purpose once the recipient received everything
+			// from it.
+			if drained && <Self as Store>::HrmpClosingChannels::contains_key(&channel_id) {
+				Self::close_hrmp_channel(&channel_id);
+				weight += T::DbWeight::get().reads_writes(3, 7);
+			}
 		}
 
 		<Self as Store>::HrmpWatermarks::insert(&recipient, new_hrmp_watermark);
@@ -1132,14 +1231,12 @@ impl<T: Config> Pallet<T> {
 			config.hrmp_max_parachain_outbound_channels
 		};
 		ensure!(
-			egress_cnt + open_req_cnt < channel_num_limit,
+			T::SystemParas::contains(&origin) || egress_cnt + open_req_cnt < channel_num_limit,
 			Error::<T>::OpenHrmpChannelLimitExceeded,
 		);
 
-		T::Currency::reserve(
-			&origin.into_account(),
-			config.hrmp_sender_deposit.unique_saturated_into(),
-		)?;
+		let sender_deposit = Self::sender_deposit(&config, origin);
+		T::Currency::reserve(&origin.into_account(), sender_deposit.unique_saturated_into())?;
 
 		// mutating storage directly now -- shall not bail henceforth.
 
@@ -1149,7 +1246,7 @@ impl<T: Config> Pallet<T> {
 			HrmpOpenChannelRequest {
 				confirmed: false,
 				_age: 0,
-				sender_deposit: config.hrmp_sender_deposit,
+				sender_deposit,
 				max_capacity: proposed_max_capacity,
 				max_message_size: proposed_max_message_size,
 				max_total_size: config.hrmp_channel_max_total_size,
@@ -1205,13 +1302,13 @@ impl<T: Config> Pallet<T> {
 			<Self as Store>::HrmpIngressChannelsIndex::decode_len(&origin).unwrap_or(0) as u32;
 		let accepted_cnt = <Self as Store>::HrmpAcceptedChannelRequestCount::get(&origin);
 		ensure!(
-			ingress_cnt + accepted_cnt < channel_num_limit,
+			T::SystemParas::contains(&origin) || ingress_cnt + accepted_cnt < channel_num_limit,
 			Error::<T>::AcceptHrmpChannelLimitExceeded,
 		);
 
 		T::Currency::reserve(
 			&origin.into_account(),
-			config.hrmp_recipient_deposit.unique_saturated_into(),
+			Self::recipient_deposit(&config, origin).unique_saturated_into(),
 		)?;
 
 		// persist the updated open channel request and then increment the number of accepted
@@ -1241,6 +1338,24 @@ impl<T: Config> Pallet<T> {
 		Ok(())
 	}
 
+	/// The deposit `sender` has to reserve for opening a channel.
+	fn sender_deposit(config: &HostConfiguration<T::BlockNumber>, sender: ParaId) -> Balance {
+		if T::SystemParas::contains(&sender) {
+			Zero::zero()
+		} else {
+			config.hrmp_sender_deposit
+		}
+	}
+
+	/// The deposit `recipient` has to reserve for accepting a channel.
+	fn recipient_deposit(config: &HostConfiguration<T::BlockNumber>, recipient: ParaId) -> Balance {
+		if T::SystemParas::contains(&recipient) {
+			Zero::zero()
+		} else {
+			config.hrmp_recipient_deposit
+		}
+	}
+
 	fn cancel_open_request(origin: ParaId, channel_id: HrmpChannelId) -> DispatchResult {
 		// check if the origin is allowed to close the channel.
 		ensure!(channel_id.is_participant(origin), Error::<T>::CancelHrmpOpenChannelUnauthorized);
@@ -1281,9 +1396,11 @@ impl<T: Config> Pallet<T> {
 			Error::<T>::CloseHrmpChannelDoesntExist,
 		);
 
-		// check that there is no outstanding close request for this channel
+		// check that there is no outstanding close request for this channel and that it isn't
+		// already being drained.
 		ensure!(
-			<Self as Store>::HrmpCloseChannelRequests::get(&channel_id).is_none(),
+			<Self as Store>::HrmpCloseChannelRequests::get(&channel_id).is_none() &&
+				!<Self as Store>::HrmpClosingChannels::contains_key(&channel_id),
 			Error::<T>::CloseHrmpChannelAlreadyUnderway,
 		);
 
@@ -1356,6 +1473,50 @@ impl<T: Config> Pallet<T> {
 
 		inbound_hrmp_channels_contents
 	}
+
+	/// Returns the blocks at which the given recipient received messages, along with the paras
+	/// which sent them. The blocks are sorted ascending.
+	pub fn hrmp_channel_digests(recipient: ParaId) -> Vec<(T::BlockNumber, Vec<ParaId>)> {
+		<Self as Store>::HrmpChannelDigests::get(&recipient)
+	}
+
+	/// Returns the blocks the HRMP watermark of the given recipient can be advanced to, apart from
+	/// the relay parent, i.e. the blocks at which it received messages. Sorted ascending.
+	pub(crate) fn valid_watermarks(recipient: ParaId) -> Vec<T::BlockNumber> {
+		<Self as Store>::HrmpChannelDigests::get(&recipient)
+			.into_iter()
+			.map(|(block_no, _)| block_no)
+			.collect()
+	}
+
+	/// Returns the number of messages and bytes that can still be sent over each outbound channel
+	/// of the given sender and the maximum size of a message, paired with the recipient. Sorted
+	/// ascending by recipient. Closing channels are left out, as they accept no new messages.
+	pub(crate) fn outbound_remaining_capacity(sender: ParaId) -> Vec<(ParaId, (u32, u32, u32))> {
+		let recipients = <Self as Store>::HrmpEgressChannelsIndex::get(&sender);
+		let mut remaining = Vec::with_capacity(recipients.len());
+
+		for recipient in recipients {
+			let channel_id = HrmpChannelId { sender, recipient };
+			if <Self as Store>::HrmpClosingChannels::contains_key(&channel_id) {
+				continue
+			}
+			let channel = match <Self as Store>::HrmpChannels::get(&channel_id) {
+				Some(channel) => channel,
+				None => continue,
+			};
+			remaining.push((
+				recipient,
+				(
+					channel.max_capacity.saturating_sub(channel.msg_count),
+					channel.max_total_size.saturating_sub(channel.total_size),
+					channel.max_message_size,
+				),
+			));
+		}
+
+		remaining
+	}
 }
 
 impl<T: Config> Pallet<T> {
@@ -1457,6 +1618,18 @@ impl<T: Config> Pallet<T> {
 				.collect::<BTreeSet<_>>(),
 		);
 
+		assert_eq!(
+			<Self as Store>::HrmpClosingChannels::iter()
+				.map(|(k, _)| k)
+				.collect::<BTreeSet<_>>(),
+			<Self as Store>::HrmpClosingChannelsList::get()
+				.into_iter()
+				.collect::<BTreeSet<_>>(),
+		);
+		for (closing_channel, _) in <Self as Store>::HrmpClosingChannels::iter() {
+			assert!(<Self as Store>::HrmpChannels::contains_key(&closing_channel));
+		}
+
 		// A HRMP watermark can be None for an onboarded parachain. However, an offboarded parachain
 		// cannot have an HRMP watermark: it should've been cleanup.
 		assert_contains_only_onboarded(
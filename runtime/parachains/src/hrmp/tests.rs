@@ -16,8 +16,8 @@
 
 use super::*;
 use crate::mock::{
-	new_test_ext, Configuration, Event as MockEvent, Hrmp, MockGenesisConfig, Paras, ParasShared,
-	System, Test,
+	new_test_ext, Configuration, Event as MockEvent, Hrmp, HrmpChannelCloseGracePeriod,
+	HrmpSystemParas, MockGenesisConfig, Paras, ParasShared, System, Test,
 };
 use frame_support::{assert_noop, assert_ok, traits::Currency as _};
 use primitives::v1::BlockNumber;
@@ -227,6 +227,90 @@ fn close_channel_works() {
 	});
 }
 
+#[test]
+fn closing_channel_is_drained_during_grace_period() {
+	let para_a = 32.into();
+	let para_b = 64.into();
+
+	HrmpChannelCloseGracePeriod::set(5);
+	let mut genesis = GenesisConfigBuilder::default();
+	genesis.hrmp_channel_max_message_size = 20;
+	genesis.hrmp_channel_max_total_size = 20;
+	new_test_ext(genesis.build()).execute_with(|| {
+		register_parachain(para_a);
+		register_parachain(para_b);
+
+		run_to_block(5, Some(vec![4, 5]));
+		Hrmp::init_open_channel(para_a, para_b, 2, 20).unwrap();
+		Hrmp::accept_open_channel(para_b, para_a).unwrap();
+
+		// A sends a message to B and then closes the channel before B received it.
+		run_to_block(6, Some(vec![6]));
+		let channel_id = HrmpChannelId { sender: para_a, recipient: para_b };
+		let msgs = vec![OutboundHrmpMessage { recipient: para_b, data: b"in flight".to_vec() }];
+		let _ = Hrmp::queue_outbound_hrmp(para_a, msgs.clone());
+		Hrmp::close_channel(para_a, channel_id.clone()).unwrap();
+
+		// After the session change the channel is kept for B to drain it, but A cannot send any
+		// new messages to it.
+		run_to_block(8, Some(vec![8]));
+		assert!(channel_exists(para_a, para_b));
+		assert_eq!(<Hrmp as Store>::HrmpClosingChannels::get(&channel_id), Some(12));
+		assert_eq!(Hrmp::inbound_hrmp_channels_contents(para_b)[&para_a].len(), 1);
+		let config = Configuration::config();
+		assert!(matches!(
+			Hrmp::check_outbound_hrmp(&config, para_a, &msgs),
+			Err(OutboundHrmpAcceptanceErr::ChannelClosing { idx: 0, .. }),
+		));
+		assert_noop!(
+			Hrmp::close_channel(para_b, channel_id.clone()),
+			Error::<Test>::CloseHrmpChannelAlreadyUnderway,
+		);
+		Hrmp::assert_storage_consistency_exhaustive();
+
+		// Once B received the message, the channel is removed.
+		assert!(Hrmp::check_hrmp_watermark(para_b, 8, 6).is_ok());
+		let _ = Hrmp::prune_hrmp(para_b, 6);
+		assert!(!channel_exists(para_a, para_b));
+		assert!(<Hrmp as Store>::HrmpClosingChannelsList::get().is_empty());
+		Hrmp::assert_storage_consistency_exhaustive();
+	});
+}
+
+#[test]
+fn closing_channel_is_removed_after_grace_period() {
+	let para_a = 32.into();
+	let para_b = 64.into();
+
+	HrmpChannelCloseGracePeriod::set(5);
+	let mut genesis = GenesisConfigBuilder::default();
+	genesis.hrmp_channel_max_message_size = 20;
+	genesis.hrmp_channel_max_total_size = 20;
+	new_test_ext(genesis.build()).execute_with(|| {
+		register_parachain(para_a);
+		register_parachain(para_b);
+
+		run_to_block(5, Some(vec![4, 5]));
+		Hrmp::init_open_channel(para_a, para_b, 2, 20).unwrap();
+		Hrmp::accept_open_channel(para_b, para_a).unwrap();
+
+		run_to_block(6, Some(vec![6]));
+		let channel_id = HrmpChannelId { sender: para_a, recipient: para_b };
+		let _ = Hrmp::queue_outbound_hrmp(
+			para_a,
+			vec![OutboundHrmpMessage { recipient: para_b, data: b"never read".to_vec() }],
+		);
+		Hrmp::close_channel(para_b, channel_id).unwrap();
+
+		// B doesn't receive the message in time.
+		run_to_block(11, Some(vec![8]));
+		assert!(channel_exists(para_a, para_b));
+		run_to_block(12, None);
+		assert!(!channel_exists(para_a, para_b));
+		Hrmp::assert_storage_consistency_exhaustive();
+	});
+}
+
 #[test]
 fn send_recv_messages() {
 	let para_a = 32.into();
@@ -263,6 +347,41 @@ fn send_recv_messages() {
 	});
 }
 
+#[test]
+fn remaining_capacity_and_valid_watermarks() {
+	let para_a = 32.into();
+	let para_b = 64.into();
+
+	let mut genesis = GenesisConfigBuilder::default();
+	genesis.hrmp_channel_max_message_size = 20;
+	genesis.hrmp_channel_max_total_size = 20;
+	new_test_ext(genesis.build()).execute_with(|| {
+		register_parachain(para_a);
+		register_parachain(para_b);
+
+		run_to_block(5, Some(vec![4, 5]));
+		Hrmp::init_open_channel(para_a, para_b, 2, 20).unwrap();
+		Hrmp::accept_open_channel(para_b, para_a).unwrap();
+
+		run_to_block(6, Some(vec![6]));
+		assert_eq!(Hrmp::outbound_remaining_capacity(para_a), vec![(para_b, (2, 20, 20))]);
+		assert!(Hrmp::outbound_remaining_capacity(para_b).is_empty());
+		assert!(Hrmp::valid_watermarks(para_b).is_empty());
+
+		let _ = Hrmp::queue_outbound_hrmp(
+			para_a,
+			vec![OutboundHrmpMessage { recipient: para_b, data: b"ten bytes!".to_vec() }],
+		);
+		assert_eq!(Hrmp::outbound_remaining_capacity(para_a), vec![(para_b, (1, 10, 20))]);
+		assert_eq!(Hrmp::valid_watermarks(para_b), vec![6]);
+
+		run_to_block(7, None);
+		let _ = Hrmp::prune_hrmp(para_b, 6);
+		assert_eq!(Hrmp::outbound_remaining_capacity(para_a), vec![(para_b, (2, 20, 20))]);
+		assert!(Hrmp::valid_watermarks(para_b).is_empty());
+	});
+}
+
 #[test]
 fn hrmp_mqc_head_fixture() {
 	let para_a = 2000.into();
@@ -536,6 +655,70 @@ fn refund_deposit_on_offboarding() {
 	});
 }
 
+#[test]
+fn system_paras_are_exempt_from_deposits_and_channel_limits() {
+	let para_a = 32.into();
+	let para_b = 64.into();
+	let para_c = 128.into();
+
+	let mut genesis = GenesisConfigBuilder::default();
+	genesis.hrmp_sender_deposit = 20;
+	genesis.hrmp_recipient_deposit = 15;
+	genesis.hrmp_max_parachain_outbound_channels = 1;
+	genesis.hrmp_max_parachain_inbound_channels = 1;
+	new_test_ext(genesis.build()).execute_with(|| {
+		HrmpSystemParas::set(vec![para_a]);
+		register_parachain_with_balance(para_a, 0);
+		register_parachain_with_balance(para_b, 100);
+		register_parachain_with_balance(para_c, 100);
+		run_to_block(5, Some(vec![4, 5]));
+
+		// The system para opens more channels than the limit allows, without a deposit.
+		Hrmp::init_open_channel(para_a, para_b, 2, 8).unwrap();
+		Hrmp::init_open_channel(para_a, para_c, 2, 8).unwrap();
+		Hrmp::accept_open_channel(para_b, para_a).unwrap();
+		Hrmp::accept_open_channel(para_c, para_a).unwrap();
+
+		// Only the community paras pay for accepting, and are still subject to the limits.
+		Hrmp::init_open_channel(para_b, para_c, 2, 8).unwrap();
+		assert_noop!(
+			Hrmp::accept_open_channel(para_c, para_b),
+			Error::<Test>::AcceptHrmpChannelLimitExceeded,
+		);
+		assert_eq!(<Test as Config>::Currency::free_balance(&para_b.into_account()), 65);
+		assert_eq!(<Test as Config>::Currency::free_balance(&para_c.into_account()), 85);
+
+		// Accepting from the system para is also exempt from the inbound limit.
+		Hrmp::init_open_channel(para_c, para_a, 2, 8).unwrap();
+		Hrmp::accept_open_channel(para_a, para_c).unwrap();
+		assert_eq!(<Test as Config>::Currency::free_balance(&para_a.into_account()), 0);
+		assert_eq!(<Test as Config>::Currency::free_balance(&para_c.into_account()), 65);
+
+		run_to_block(8, Some(vec![8]));
+		assert!(channel_exists(para_a, para_b));
+		assert!(channel_exists(para_a, para_c));
+		assert!(channel_exists(para_c, para_a));
+		assert_eq!(
+			<Hrmp as Store>::HrmpChannels::get(&HrmpChannelId {
+				sender: para_a,
+				recipient: para_b
+			})
+			.unwrap()
+			.sender_deposit,
+			0,
+		);
+
+		// Closing the channels refunds the deposits the community paras paid.
+		Hrmp::close_channel(para_b, HrmpChannelId { sender: para_a, recipient: para_b }).unwrap();
+		Hrmp::close_channel(para_a, HrmpChannelId { sender: para_c, recipient: para_a }).unwrap();
+		run_to_block(10, Some(vec![10]));
+		assert_eq!(<Test as Config>::Currency::free_balance(&para_a.into_account()), 0);
+		assert_eq!(<Test as Config>::Currency::free_balance(&para_b.into_account()), 80);
+		assert_eq!(<Test as Config>::Currency::free_balance(&para_c.into_account()), 85);
+		Hrmp::assert_storage_consistency_exhaustive();
+	});
+}
+
 #[test]
 fn no_dangling_open_requests() {
 	let para_a = 32.into();
@@ -29,9 +29,9 @@ use frame_support::pallet_prelude::*;
 use parity_scale_codec::{Decode, Encode};
 use primitives::v1::{
 	AvailabilityBitfield, BackedCandidate, CandidateCommitments, CandidateDescriptor,
-	CandidateHash, CandidateReceipt, CommittedCandidateReceipt, CoreIndex, GroupIndex, Hash,
-	HeadData, Id as ParaId, SigningContext, UncheckedSignedAvailabilityBitfields, ValidatorId,
-	ValidatorIndex, ValidityAttestation,
+	CandidateHash, CandidateReceipt, CollatorId, CommittedCandidateReceipt, CoreIndex, GroupIndex,
+	Hash, HeadData, Id as ParaId, SigningContext, UncheckedSignedAvailabilityBitfields,
+	ValidatorId, ValidatorIndex, ValidityAttestation,
 };
 use scale_info::TypeInfo;
 use sp_runtime::{traits::One, DispatchError};
@@ -115,6 +115,11 @@ impl<H, N> CandidatePendingAvailability<H, N> {
 		&self.descriptor
 	}
 
+	/// Get the block number of the relay-parent of the receipt.
+	pub(crate) fn relay_parent_number(&self) -> &N {
+		&self.relay_parent_number
+	}
+
 	#[cfg(any(feature = "runtime-benchmarks", test))]
 	pub(crate) fn new(
 		core: CoreIndex,
@@ -149,6 +154,20 @@ pub trait RewardValidators {
 	fn reward_bitfields(validators: impl IntoIterator<Item = ValidatorIndex>);
 }
 
+/// A hook for rewarding the collators of included candidates.
+pub trait RewardCollators {
+	/// Reward the collator of a candidate of `para` which was just included.
+	///
+	/// Returns the weight consumed by the reward.
+	fn reward_collator(para: ParaId, collator: &CollatorId) -> Weight;
+}
+
+impl RewardCollators for () {
+	fn reward_collator(_: ParaId, _: &CollatorId) -> Weight {
+		0
+	}
+}
+
 /// Helper return type for `process_candidates`.
 #[derive(Encode, Decode, PartialEq, TypeInfo)]
 #[cfg_attr(test, derive(Debug))]
@@ -194,6 +213,7 @@ pub mod pallet {
 		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
 		type DisputesHandler: disputes::DisputesHandler<Self::BlockNumber>;
 		type RewardValidators: RewardValidators;
+		type RewardCollators: RewardCollators;
 	}
 
 	#[pallet::event]
@@ -744,6 +764,10 @@ impl<T: Config> Pallet<T> {
 
 		// initial weight is config read.
 		let mut weight = T::DbWeight::get().reads_writes(1, 0);
+		weight += T::RewardCollators::reward_collator(
+			receipt.descriptor.para_id,
+			&receipt.descriptor.collator,
+		);
 		if let Some(new_code) = commitments.new_validation_code {
 			weight += <paras::Pallet<T>>::schedule_code_upgrade(
 				receipt.descriptor.para_id,
@@ -887,6 +887,11 @@ fn supermajority_bitfields_trigger_availability() {
 			assert_eq!(rewards.get(&ValidatorIndex(3)).unwrap(), &1);
 			assert_eq!(rewards.get(&ValidatorIndex(4)).unwrap(), &1);
 		}
+
+		assert_eq!(
+			crate::mock::collator_rewards(),
+			vec![(chain_a, candidate_a.descriptor.collator.clone())],
+		);
 	});
 }
 
@@ -30,7 +30,7 @@ use frame_support::{
 };
 use frame_system::limits::BlockWeights;
 use parity_scale_codec::{Decode, Encode};
-use primitives::v1::{BlockNumber, ConsensusLog, SessionIndex, ValidatorId};
+use primitives::v1::{BlockNumber, ConsensusLog, Id as ParaId, SessionIndex, ValidatorId};
 use scale_info::TypeInfo;
 use sp_std::prelude::*;
 
@@ -79,6 +79,101 @@ struct BufferedSessionChange {
 	session_index: SessionIndex,
 }
 
+/// The parachains modules driven by the initializer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum HookModule {
+	Configuration,
+	Shared,
+	Paras,
+	Scheduler,
+	Inclusion,
+	SessionInfo,
+	Disputes,
+	Dmp,
+	Ump,
+	Hrmp,
+}
+
+/// The order in which the modules are initialized and notified about new sessions. They are
+/// finalized in the reverse order.
+///
+/// Later modules may rely on the state set up by earlier ones, e.g. the paras module needs the
+/// configuration of the session and the message queues need the outgoing paras from the paras
+/// module. These constraints are checked in the tests.
+pub(crate) const HOOK_ORDER: &[HookModule] = &[
+	HookModule::Configuration,
+	HookModule::Shared,
+	HookModule::Paras,
+	HookModule::Scheduler,
+	HookModule::Inclusion,
+	HookModule::SessionInfo,
+	HookModule::Disputes,
+	HookModule::Dmp,
+	HookModule::Ump,
+	HookModule::Hrmp,
+];
+
+impl HookModule {
+	fn initialize<T: Config>(self, now: T::BlockNumber) -> Weight {
+		match self {
+			HookModule::Configuration => configuration::Pallet::<T>::initializer_initialize(now),
+			HookModule::Shared => shared::Pallet::<T>::initializer_initialize(now),
+			HookModule::Paras => paras::Pallet::<T>::initializer_initialize(now),
+			HookModule::Scheduler => scheduler::Pallet::<T>::initializer_initialize(now),
+			HookModule::Inclusion => inclusion::Pallet::<T>::initializer_initialize(now),
+			HookModule::SessionInfo => session_info::Pallet::<T>::initializer_initialize(now),
+			HookModule::Disputes => T::DisputesHandler::initializer_initialize(now),
+			HookModule::Dmp => dmp::Pallet::<T>::initializer_initialize(now),
+			HookModule::Ump => ump::Pallet::<T>::initializer_initialize(now),
+			HookModule::Hrmp => hrmp::Pallet::<T>::initializer_initialize(now),
+		}
+	}
+
+	fn finalize<T: Config>(self, now: T::BlockNumber) {
+		match self {
+			HookModule::Configuration => configuration::Pallet::<T>::initializer_finalize(),
+			HookModule::Shared => shared::Pallet::<T>::initializer_finalize(),
+			HookModule::Paras => paras::Pallet::<T>::initializer_finalize(now),
+			HookModule::Scheduler => scheduler::Pallet::<T>::initializer_finalize(),
+			HookModule::Inclusion => inclusion::Pallet::<T>::initializer_finalize(),
+			HookModule::SessionInfo => session_info::Pallet::<T>::initializer_finalize(),
+			HookModule::Disputes => T::DisputesHandler::initializer_finalize(),
+			HookModule::Dmp => dmp::Pallet::<T>::initializer_finalize(),
+			HookModule::Ump => ump::Pallet::<T>::initializer_finalize(),
+			HookModule::Hrmp => hrmp::Pallet::<T>::initializer_finalize(),
+		}
+	}
+
+	/// Notify the module about the new session.
+	///
+	/// The configuration and shared modules are not notified here, as they produce the
+	/// notification. The paras module fills in `outgoing_paras` for the modules after it.
+	fn on_new_session<T: Config>(
+		self,
+		notification: &SessionChangeNotification<T::BlockNumber>,
+		outgoing_paras: &mut Vec<ParaId>,
+	) {
+		match self {
+			HookModule::Configuration | HookModule::Shared => {},
+			HookModule::Paras =>
+				*outgoing_paras = paras::Pallet::<T>::initializer_on_new_session(notification),
+			HookModule::Scheduler =>
+				scheduler::Pallet::<T>::initializer_on_new_session(notification),
+			HookModule::Inclusion =>
+				inclusion::Pallet::<T>::initializer_on_new_session(notification),
+			HookModule::SessionInfo =>
+				session_info::Pallet::<T>::initializer_on_new_session(notification),
+			HookModule::Disputes => T::DisputesHandler::initializer_on_new_session(notification),
+			HookModule::Dmp =>
+				dmp::Pallet::<T>::initializer_on_new_session(notification, outgoing_paras),
+			HookModule::Ump =>
+				ump::Pallet::<T>::initializer_on_new_session(notification, outgoing_paras),
+			HookModule::Hrmp =>
+				hrmp::Pallet::<T>::initializer_on_new_session(notification, outgoing_paras),
+		}
+	}
+}
+
 pub trait WeightInfo {
 	fn force_approve(d: u32) -> Weight;
 }
@@ -146,26 +241,11 @@ pub mod pallet {
 	#[pallet::hooks]
 	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
 		fn on_initialize(now: T::BlockNumber) -> Weight {
-			// The other modules are initialized in this order:
-			// - Configuration
-			// - Paras
-			// - Scheduler
-			// - Inclusion
-			// - `SessionInfo`
-			// - Disputes
-			// - DMP
-			// - UMP
-			// - HRMP
-			let total_weight = configuration::Pallet::<T>::initializer_initialize(now) +
-				shared::Pallet::<T>::initializer_initialize(now) +
-				paras::Pallet::<T>::initializer_initialize(now) +
-				scheduler::Pallet::<T>::initializer_initialize(now) +
-				inclusion::Pallet::<T>::initializer_initialize(now) +
-				session_info::Pallet::<T>::initializer_initialize(now) +
-				T::DisputesHandler::initializer_initialize(now) +
-				dmp::Pallet::<T>::initializer_initialize(now) +
-				ump::Pallet::<T>::initializer_initialize(now) +
-				hrmp::Pallet::<T>::initializer_initialize(now);
+			// The other modules are initialized in the order of `HOOK_ORDER`.
+			let total_weight = HOOK_ORDER
+				.iter()
+				.map(|module| module.initialize::<T>(now))
+				.fold(0, |total: Weight, weight| total.saturating_add(weight));
 
 			HasInitialized::<T>::set(Some(()));
 
@@ -174,16 +254,9 @@ pub mod pallet {
 
 		fn on_finalize(now: T::BlockNumber) {
 			// reverse initialization order.
-			hrmp::Pallet::<T>::initializer_finalize();
-			ump::Pallet::<T>::initializer_finalize();
-			dmp::Pallet::<T>::initializer_finalize();
-			T::DisputesHandler::initializer_finalize();
-			session_info::Pallet::<T>::initializer_finalize();
-			inclusion::Pallet::<T>::initializer_finalize();
-			scheduler::Pallet::<T>::initializer_finalize();
-			paras::Pallet::<T>::initializer_finalize(now);
-			shared::Pallet::<T>::initializer_finalize();
-			configuration::Pallet::<T>::initializer_finalize();
+			for module in HOOK_ORDER.iter().rev() {
+				module.finalize::<T>(now);
+			}
 
 			// Apply buffered session changes as the last thing. This way the runtime APIs and the
 			// next block will observe the next session.
@@ -254,14 +327,10 @@ impl<T: Config> Pallet<T> {
 			session_index,
 		};
 
-		let outgoing_paras = paras::Pallet::<T>::initializer_on_new_session(&notification);
-		scheduler::Pallet::<T>::initializer_on_new_session(&notification);
-		inclusion::Pallet::<T>::initializer_on_new_session(&notification);
-		session_info::Pallet::<T>::initializer_on_new_session(&notification);
-		T::DisputesHandler::initializer_on_new_session(&notification);
-		dmp::Pallet::<T>::initializer_on_new_session(&notification, &outgoing_paras);
-		ump::Pallet::<T>::initializer_on_new_session(&notification, &outgoing_paras);
-		hrmp::Pallet::<T>::initializer_on_new_session(&notification, &outgoing_paras);
+		let mut outgoing_paras = Vec::new();
+		for module in HOOK_ORDER {
+			module.on_new_session::<T>(&notification, &mut outgoing_paras);
+		}
 	}
 
 	/// Should be called when a new session occurs. Buffers the session notification to be applied
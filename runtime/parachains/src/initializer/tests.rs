@@ -129,3 +129,62 @@ fn scheduled_cleanup_performed() {
 		assert!(!Dmp::dmq_contents(c).is_empty());
 	});
 }
+
+fn hook_position(module: HookModule) -> usize {
+	HOOK_ORDER
+		.iter()
+		.position(|m| *m == module)
+		.unwrap_or_else(|| panic!("{:?} is not driven by the initializer", module))
+}
+
+#[test]
+fn hook_order_contains_every_module_once() {
+	let all = [
+		HookModule::Configuration,
+		HookModule::Shared,
+		HookModule::Paras,
+		HookModule::Scheduler,
+		HookModule::Inclusion,
+		HookModule::SessionInfo,
+		HookModule::Disputes,
+		HookModule::Dmp,
+		HookModule::Ump,
+		HookModule::Hrmp,
+	];
+
+	assert_eq!(HOOK_ORDER.len(), all.len());
+	for module in all {
+		assert_eq!(HOOK_ORDER.iter().filter(|m| **m == module).count(), 1, "{:?}", module);
+	}
+}
+
+#[test]
+fn hook_order_satisfies_constraints() {
+	// (earlier, later): `later` relies on state set up by `earlier`.
+	let constraints = [
+		// Everything reads the active configuration.
+		(HookModule::Configuration, HookModule::Shared),
+		(HookModule::Configuration, HookModule::Paras),
+		// The active validators are needed for PVF pre-checking and scheduling.
+		(HookModule::Shared, HookModule::Paras),
+		(HookModule::Shared, HookModule::Scheduler),
+		// Scheduling depends on the set of parachains.
+		(HookModule::Paras, HookModule::Scheduler),
+		(HookModule::Scheduler, HookModule::Inclusion),
+		// Disputes need the session info of the new session.
+		(HookModule::SessionInfo, HookModule::Disputes),
+		// The message queues of outgoing paras are cleaned up after the paras module.
+		(HookModule::Paras, HookModule::Dmp),
+		(HookModule::Paras, HookModule::Ump),
+		(HookModule::Paras, HookModule::Hrmp),
+	];
+
+	for (earlier, later) in constraints {
+		assert!(
+			hook_position(earlier) < hook_position(later),
+			"{:?} must be driven before {:?}",
+			earlier,
+			later,
+		);
+	}
+}
@@ -23,6 +23,7 @@
 #![cfg_attr(feature = "runtime-benchmarks", recursion_limit = "256")]
 #![cfg_attr(not(feature = "std"), no_std)]
 
+pub mod assigner_on_demand;
 pub mod configuration;
 pub mod disputes;
 pub mod dmp;
@@ -17,8 +17,8 @@
 //! Mocks for all the traits.
 
 use crate::{
-	configuration, disputes, dmp, hrmp, inclusion, initializer, origin, paras, paras_inherent,
-	scheduler, session_info, shared,
+	assigner_on_demand, configuration, disputes, dmp, hrmp, inclusion, initializer, origin, paras,
+	paras_inherent, scheduler, session_info, shared,
 	ump::{self, MessageId, UmpSink},
 	ParaId,
 };
@@ -31,15 +31,16 @@ use frame_support::{
 use frame_support_test::TestRandomness;
 use parity_scale_codec::Decode;
 use primitives::v1::{
-	AuthorityDiscoveryId, Balance, BlockNumber, Header, Moment, SessionIndex, UpwardMessage,
-	ValidatorIndex,
+	AuthorityDiscoveryId, Balance, BlockNumber, CollatorId, Header, Moment, SessionIndex,
+	UpwardMessage, ValidatorIndex,
 };
 use sp_core::H256;
 use sp_io::TestExternalities;
 use sp_runtime::{
-	traits::{BlakeTwo256, IdentityLookup},
+	testing::{UintAuthorityId, UINT_DUMMY_KEY},
+	traits::{BlakeTwo256, IdentityLookup, OpaqueKeys},
 	transaction_validity::TransactionPriority,
-	KeyTypeId, Permill,
+	KeyTypeId, Perbill, Permill,
 };
 use std::{cell::RefCell, collections::HashMap};
 
@@ -67,7 +68,11 @@ frame_support::construct_runtime!(
 		ParachainsOrigin: origin,
 		SessionInfo: session_info,
 		Disputes: disputes,
+		OnDemandAssignment: assigner_on_demand,
 		Babe: pallet_babe,
+		Session: pallet_session,
+		Historical: pallet_session::historical,
+		Staking: pallet_staking,
 	}
 );
 
@@ -176,6 +181,90 @@ impl pallet_timestamp::Config for Test {
 	type WeightInfo = ();
 }
 
+/// Sessions only change through the initializer in the tests.
+pub struct TestShouldEndSession;
+
+impl pallet_session::ShouldEndSession<BlockNumber> for TestShouldEndSession {
+	fn should_end_session(_now: BlockNumber) -> bool {
+		false
+	}
+}
+
+pub struct TestSessionHandler;
+
+impl pallet_session::SessionHandler<AccountId> for TestSessionHandler {
+	const KEY_TYPE_IDS: &'static [KeyTypeId] = &[UINT_DUMMY_KEY];
+
+	fn on_genesis_session<Ks: OpaqueKeys>(_validators: &[(AccountId, Ks)]) {}
+
+	fn on_new_session<Ks: OpaqueKeys>(
+		_changed: bool,
+		_validators: &[(AccountId, Ks)],
+		_queued_validators: &[(AccountId, Ks)],
+	) {
+	}
+
+	fn on_disabled(_validator_index: u32) {}
+}
+
+impl pallet_session::Config for Test {
+	type Event = Event;
+	type ValidatorId = AccountId;
+	type ValidatorIdOf = pallet_staking::StashOf<Self>;
+	type ShouldEndSession = TestShouldEndSession;
+	type NextSessionRotation = TestNextSessionRotation;
+	type SessionManager = pallet_session::historical::NoteHistoricalRoot<Self, Staking>;
+	type SessionHandler = TestSessionHandler;
+	type Keys = UintAuthorityId;
+	type WeightInfo = ();
+}
+
+impl pallet_session::historical::Config for Test {
+	type FullIdentification = pallet_staking::Exposure<AccountId, Balance>;
+	type FullIdentificationOf = pallet_staking::ExposureOf<Self>;
+}
+
+impl frame_election_provider_support::onchain::Config for Test {
+	type Accuracy = Perbill;
+	type DataProvider = Staking;
+}
+
+parameter_types! {
+	pub const SessionsPerEra: SessionIndex = 3;
+	pub const BondingDuration: sp_staking::EraIndex = 3;
+	pub const SlashDeferDuration: sp_staking::EraIndex = 0;
+	pub const MaxNominatorRewardedPerValidator: u32 = 64;
+	pub const OffendingValidatorsThreshold: Perbill = Perbill::from_percent(17);
+}
+
+impl pallet_staking::Config for Test {
+	type MaxNominations = frame_support::traits::ConstU32<16>;
+	type Currency = Balances;
+	type UnixTime = pallet_timestamp::Pallet<Test>;
+	type CurrencyToVote = frame_support::traits::U128CurrencyToVote;
+	type RewardRemainder = ();
+	type Event = Event;
+	type Slash = ();
+	type Reward = ();
+	type SessionsPerEra = SessionsPerEra;
+	type BondingDuration = BondingDuration;
+	type SlashDeferDuration = SlashDeferDuration;
+	type SlashCancelOrigin = frame_system::EnsureRoot<AccountId>;
+	type SessionInterface = Self;
+	type EraPayout = ();
+	type MaxNominatorRewardedPerValidator = MaxNominatorRewardedPerValidator;
+	type OffendingValidatorsThreshold = OffendingValidatorsThreshold;
+	type NextNewSession = Session;
+	type ElectionProvider =
+		frame_election_provider_support::onchain::OnChainSequentialPhragmen<Self>;
+	type GenesisElectionProvider =
+		frame_election_provider_support::onchain::OnChainSequentialPhragmen<Self>;
+	type SortedListProvider = pallet_staking::UseNominatorsMap<Self>;
+	type MaxUnlockingChunks = frame_support::traits::ConstU32<32>;
+	type BenchmarkingConfig = pallet_staking::TestBenchmarkingConfig;
+	type WeightInfo = ();
+}
+
 impl crate::initializer::Config for Test {
 	type Randomness = TestRandomness<Self>;
 	type ForceOrigin = frame_system::EnsureRoot<u64>;
@@ -219,7 +308,21 @@ impl crate::paras::Config for Test {
 	type NextSessionRotation = TestNextSessionRotation;
 }
 
-impl crate::dmp::Config for Test {}
+parameter_types! {
+	pub const DmpQueueHighWatermark: crate::dmp::QueueWatermark =
+		crate::dmp::QueueWatermark { count: 4, bytes: 1024 };
+	pub const DmpQueueLowWatermark: crate::dmp::QueueWatermark =
+		crate::dmp::QueueWatermark { count: 1, bytes: 256 };
+	pub static DmpQueueCapacity: crate::dmp::QueueWatermark =
+		crate::dmp::QueueWatermark { count: 100, bytes: 64 * 1024 };
+}
+
+impl crate::dmp::Config for Test {
+	type Event = Event;
+	type QueueHighWatermark = DmpQueueHighWatermark;
+	type QueueLowWatermark = DmpQueueLowWatermark;
+	type QueueCapacity = DmpQueueCapacity;
+}
 
 parameter_types! {
 	pub const FirstMessageFactorPercent: u64 = 100;
@@ -233,13 +336,45 @@ impl crate::ump::Config for Test {
 	type WeightInfo = crate::ump::TestWeightInfo;
 }
 
+parameter_types! {
+	pub static HrmpChannelCloseGracePeriod: BlockNumber = 0;
+	pub static HrmpSystemParas: Vec<ParaId> = Vec::new();
+}
+
+/// Treats the paras in [`HrmpSystemParas`] as system paras, since the test ids are all below the
+/// public range.
+pub struct MockSystemParas;
+
+impl frame_support::traits::Contains<ParaId> for MockSystemParas {
+	fn contains(para: &ParaId) -> bool {
+		HrmpSystemParas::get().contains(para)
+	}
+}
+
 impl crate::hrmp::Config for Test {
 	type Event = Event;
 	type Origin = Origin;
 	type Currency = pallet_balances::Pallet<Test>;
+	type ChannelCloseGracePeriod = HrmpChannelCloseGracePeriod;
+	type SystemParas = MockSystemParas;
 	type WeightInfo = crate::hrmp::TestWeightInfo;
 }
 
+parameter_types! {
+	pub const OnDemandBaseFee: Balance = 10;
+	pub const OnDemandTargetQueueUtilization: Perbill = Perbill::from_percent(50);
+	pub const OnDemandFeeVariability: Perbill = Perbill::from_percent(10);
+}
+
+impl crate::assigner_on_demand::Config for Test {
+	type Event = Event;
+	type Currency = pallet_balances::Pallet<Test>;
+	type BaseFee = OnDemandBaseFee;
+	type TargetQueueUtilization = OnDemandTargetQueueUtilization;
+	type FeeVariability = OnDemandFeeVariability;
+	type WeightInfo = crate::assigner_on_demand::TestWeightInfo;
+}
+
 impl crate::disputes::Config for Test {
 	type Event = Event;
 	type RewardValidators = Self;
@@ -295,6 +430,7 @@ impl crate::inclusion::Config for Test {
 	type Event = Event;
 	type DisputesHandler = Disputes;
 	type RewardValidators = TestRewardValidators;
+	type RewardCollators = TestRewardCollators;
 }
 
 impl crate::paras_inherent::Config for Test {
@@ -327,6 +463,8 @@ thread_local! {
 
 	pub static AVAILABILITY_REWARDS: RefCell<HashMap<ValidatorIndex, usize>>
 		= RefCell::new(HashMap::new());
+
+	pub static COLLATOR_REWARDS: RefCell<Vec<(ParaId, CollatorId)>> = RefCell::new(Vec::new());
 }
 
 pub fn backing_rewards() -> HashMap<ValidatorIndex, usize> {
@@ -337,6 +475,10 @@ pub fn availability_rewards() -> HashMap<ValidatorIndex, usize> {
 	AVAILABILITY_REWARDS.with(|r| r.borrow().clone())
 }
 
+pub fn collator_rewards() -> Vec<(ParaId, CollatorId)> {
+	COLLATOR_REWARDS.with(|r| r.borrow().clone())
+}
+
 std::thread_local! {
 	static PROCESSED: RefCell<Vec<(ParaId, UpwardMessage)>> = RefCell::new(vec![]);
 }
@@ -394,6 +536,15 @@ impl inclusion::RewardValidators for TestRewardValidators {
 	}
 }
 
+pub struct TestRewardCollators;
+
+impl inclusion::RewardCollators for TestRewardCollators {
+	fn reward_collator(para: ParaId, collator: &CollatorId) -> Weight {
+		COLLATOR_REWARDS.with(|r| r.borrow_mut().push((para, collator.clone())));
+		0
+	}
+}
+
 /// Create a new set of test externalities.
 pub fn new_test_ext(state: MockGenesisConfig) -> TestExternalities {
 	use sp_keystore::{testing::KeyStore, KeystoreExt, SyncCryptoStorePtr};
@@ -403,6 +554,7 @@ pub fn new_test_ext(state: MockGenesisConfig) -> TestExternalities {
 
 	BACKING_REWARDS.with(|r| r.borrow_mut().clear());
 	AVAILABILITY_REWARDS.with(|r| r.borrow_mut().clear());
+	COLLATOR_REWARDS.with(|r| r.borrow_mut().clear());
 
 	let mut t = state.system.build_storage::<Test>().unwrap();
 	state.configuration.assimilate_storage(&mut t).unwrap();
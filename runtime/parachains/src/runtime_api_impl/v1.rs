@@ -19,7 +19,7 @@
 
 use crate::{
 	configuration, dmp, hrmp, inclusion, initializer, paras, paras_inherent, scheduler,
-	session_info, shared,
+	session_info, shared, ump,
 };
 use primitives::{
 	v1::{
@@ -29,7 +29,10 @@ use primitives::{
 		ScheduledCore, ScrapedOnChainVotes, SessionIndex, ValidationCode, ValidationCodeHash,
 		ValidatorId, ValidatorIndex, ValidatorSignature,
 	},
-	v2::{PvfCheckStatement, SessionInfo},
+	v2::{
+		BackingState, CandidatePendingAvailability, Constraints, InboundHrmpLimitations,
+		OutboundHrmpChannelLimitations, PvfCheckStatement, SessionInfo,
+	},
 };
 use sp_runtime::traits::One;
 use sp_std::{collections::btree_map::BTreeMap, prelude::*};
@@ -364,6 +367,90 @@ pub fn inbound_hrmp_channels_contents<T: hrmp::Config>(
 	<hrmp::Pallet<T>>::inbound_hrmp_channels_contents(recipient)
 }
 
+/// Implementation for the `dmq_length` function of the runtime API.
+pub fn dmq_length<T: dmp::Config>(recipient: ParaId) -> u32 {
+	<dmp::Pallet<T>>::dmq_length(recipient)
+}
+
+/// Implementation for the `hrmp_channel_digests` function of the runtime API.
+pub fn hrmp_channel_digests<T: hrmp::Config>(
+	recipient: ParaId,
+) -> Vec<(T::BlockNumber, Vec<ParaId>)> {
+	<hrmp::Pallet<T>>::hrmp_channel_digests(recipient)
+}
+
+/// Implementation for the `staging_para_backing_state` function of the runtime API.
+pub fn staging_para_backing_state<T: initializer::Config>(
+	para_id: ParaId,
+) -> Option<BackingState<T::Hash, T::BlockNumber>> {
+	let config = <configuration::Pallet<T>>::config();
+	// Candidates can only be backed on top of the current block for now, so that's the only
+	// relay parent the constraints accept.
+	let (min_relay_parent_number, _) = current_relay_parent::<T>();
+
+	let required_parent = <paras::Pallet<T>>::para_head(&para_id)?;
+	let validation_code_hash = <paras::Pallet<T>>::current_code_hash(&para_id)?;
+	let upgrade_restriction = <paras::Pallet<T>>::upgrade_restriction_signal(&para_id);
+	let future_validation_code = <paras::Pallet<T>>::future_code_upgrade_at(&para_id)
+		.zip(<paras::Pallet<T>>::future_code_hash(&para_id));
+
+	let (ump_msg_count, ump_total_bytes) = <ump::Pallet<T>>::relay_dispatch_queue_size(para_id);
+	let ump_remaining = config.max_upward_queue_count.saturating_sub(ump_msg_count);
+	let ump_remaining_bytes = config.max_upward_queue_size.saturating_sub(ump_total_bytes);
+
+	let dmp_remaining_messages = <dmp::Pallet<T>>::dmq_contents(para_id)
+		.into_iter()
+		.map(|msg| msg.sent_at)
+		.collect();
+
+	let hrmp_inbound =
+		InboundHrmpLimitations { valid_watermarks: <hrmp::Pallet<T>>::valid_watermarks(para_id) };
+	let hrmp_channels_out = <hrmp::Pallet<T>>::outbound_remaining_capacity(para_id)
+		.into_iter()
+		.map(|(recipient, (messages_remaining, bytes_remaining, max_message_size))| {
+			let limitations = OutboundHrmpChannelLimitations {
+				messages_remaining,
+				bytes_remaining,
+				max_message_size,
+			};
+			(recipient, limitations)
+		})
+		.collect();
+
+	let constraints = Constraints {
+		min_relay_parent_number,
+		max_pov_size: config.max_pov_size,
+		max_head_data_size: config.max_head_data_size,
+		max_code_size: config.max_code_size,
+		ump_remaining,
+		ump_remaining_bytes,
+		max_ump_num_per_candidate: config.max_upward_message_num_per_candidate,
+		max_upward_message_size: config.max_upward_message_size,
+		dmp_remaining_messages,
+		hrmp_inbound,
+		hrmp_channels_out,
+		max_hrmp_num_per_candidate: config.hrmp_max_message_num_per_candidate,
+		required_parent,
+		validation_code_hash,
+		upgrade_restriction,
+		future_validation_code,
+	};
+
+	let pending_availability = <inclusion::Pallet<T>>::pending_availability(para_id)
+		.zip(<inclusion::Pallet<T>>::candidate_pending_availability(para_id))
+		.map(|(pending, receipt)| CandidatePendingAvailability {
+			candidate_hash: pending.candidate_hash(),
+			descriptor: receipt.descriptor,
+			commitments: receipt.commitments,
+			relay_parent_number: *pending.relay_parent_number(),
+			max_pov_size: config.max_pov_size,
+		})
+		.into_iter()
+		.collect();
+
+	Some(BackingState { constraints, pending_availability })
+}
+
 /// Implementation for the `validation_code_by_hash` function of the runtime API.
 pub fn validation_code_by_hash<T: paras::Config>(
 	hash: ValidationCodeHash,
@@ -98,6 +98,17 @@ impl Default for ParathreadClaimQueue {
 	}
 }
 
+/// An error queueing a parathread claim.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum ClaimQueueError {
+	/// The claim isn't for a live parathread.
+	NotParathread,
+	/// The queue holds the maximum number of claims.
+	QueueFull,
+	/// Another claim of the parathread is queued or assigned to a core.
+	CompetingClaim,
+}
+
 /// Reasons a core might be freed
 #[derive(Clone, Copy)]
 pub enum FreedReason {
@@ -348,16 +359,22 @@ impl<T: Config> Pallet<T> {
 	/// Fails if the claim does not correspond to any live parathread.
 	#[allow(unused)]
 	pub fn add_parathread_claim(claim: ParathreadClaim) {
+		let _ = Self::queue_parathread_claim(claim);
+	}
+
+	/// Add a parathread claim to the queue, like [`Self::add_parathread_claim`], but report why
+	/// the claim couldn't be queued.
+	pub fn queue_parathread_claim(claim: ParathreadClaim) -> Result<(), ClaimQueueError> {
 		if !<paras::Pallet<T>>::is_parathread(claim.0) {
-			return
+			return Err(ClaimQueueError::NotParathread)
 		}
 
 		let config = <configuration::Pallet<T>>::config();
-		let queue_max_size = config.parathread_cores * config.scheduling_lookahead;
+		let queue_max_size = Self::parathread_queue_max_size(&config);
 
-		ParathreadQueue::<T>::mutate(|queue| {
+		ParathreadQueue::<T>::try_mutate(|queue| {
 			if queue.queue.len() >= queue_max_size as usize {
-				return
+				return Err(ClaimQueueError::QueueFull)
 			}
 
 			let para_id = claim.0;
@@ -372,14 +389,27 @@ impl<T: Config> Pallet<T> {
 				});
 
 			if competes_with_another {
-				return
+				return Err(ClaimQueueError::CompetingClaim)
 			}
 
 			let entry = ParathreadEntry { claim, retries: 0 };
 			queue.enqueue_entry(entry, config.parathread_cores);
+			Ok(())
 		})
 	}
 
+	/// The number of parathread claims waiting in the queue.
+	pub(crate) fn parathread_queue_len() -> u32 {
+		ParathreadQueue::<T>::get().queue.len() as u32
+	}
+
+	/// The maximum number of parathread claims waiting in the queue.
+	pub(crate) fn parathread_queue_max_size(
+		config: &configuration::HostConfiguration<T::BlockNumber>,
+	) -> u32 {
+		config.parathread_cores * config.scheduling_lookahead
+	}
+
 	/// Free unassigned cores. Provide a list of cores that should be considered newly-freed along with the reason
 	/// for them being freed. The list is assumed to be sorted in ascending order by core index.
 	pub(crate) fn free_cores(just_freed_cores: impl IntoIterator<Item = (CoreIndex, FreedReason)>) {
@@ -66,6 +66,12 @@ pub mod pallet {
 	pub(super) type AssignmentKeysUnsafe<T: Config> =
 		StorageValue<_, Vec<AssignmentId>, ValueQuery>;
 
+	/// Account keys of the validators for the current session.
+	/// Note that this API is private due to it being prone to 'off-by-one' at session boundaries.
+	/// When in doubt, use `AccountKeys` API instead.
+	#[pallet::storage]
+	pub(super) type AccountKeysUnsafe<T: Config> = StorageValue<_, Vec<T::AccountId>, ValueQuery>;
+
 	/// The earliest session for which previous session info is stored.
 	#[pallet::storage]
 	#[pallet::getter(fn earliest_stored_session)]
@@ -78,6 +84,16 @@ pub mod pallet {
 	#[pallet::getter(fn session_info)]
 	pub(crate) type Sessions<T: Config> = StorageMap<_, Identity, SessionIndex, SessionInfo>;
 
+	/// The account keys of the validators actively participating in parachain consensus, in the
+	/// same order as the validators of the `SessionInfo`. Kept for the same sessions as `Sessions`.
+	///
+	/// Used to find the validators of a past session, whose keys might have changed since.
+	// Not stored in `SessionInfo`, which is read by the client and doesn't know the `AccountId`.
+	#[pallet::storage]
+	#[pallet::getter(fn account_keys)]
+	pub(crate) type AccountKeys<T: Config> =
+		StorageMap<_, Identity, SessionIndex, Vec<T::AccountId>>;
+
 	#[pallet::hooks]
 	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
 		fn on_runtime_upgrade() -> Weight {
@@ -111,6 +127,7 @@ impl<T: Config> Pallet<T> {
 		let validators = notification.validators.clone();
 		let discovery_keys = <T as AuthorityDiscoveryConfig>::authorities();
 		let assignment_keys = AssignmentKeysUnsafe::<T>::get();
+		let account_keys = AccountKeysUnsafe::<T>::get();
 		let active_set = <shared::Pallet<T>>::active_validator_indices();
 
 		let validator_groups = <scheduler::Pallet<T>>::validator_groups();
@@ -132,6 +149,7 @@ impl<T: Config> Pallet<T> {
 		if old_earliest_stored_session != 0 || Sessions::<T>::get(0).is_some() {
 			for idx in old_earliest_stored_session..new_earliest_stored_session {
 				Sessions::<T>::remove(&idx);
+				AccountKeys::<T>::remove(&idx);
 			}
 			// update `EarliestStoredSession` based on `config.dispute_period`
 			EarliestStoredSession::<T>::set(new_earliest_stored_session);
@@ -156,6 +174,10 @@ impl<T: Config> Pallet<T> {
 			dispute_period,
 		};
 		Sessions::<T>::insert(&new_session_index, &new_session_info);
+		AccountKeys::<T>::insert(
+			&new_session_index,
+			take_active_subset(&active_set, &account_keys),
+		);
 	}
 
 	/// Called by the initializer to initialize the session info pallet.
@@ -184,7 +206,9 @@ impl<T: pallet_session::Config + Config> OneSessionHandler<T::AccountId> for Pal
 	where
 		I: Iterator<Item = (&'a T::AccountId, Self::Key)>,
 	{
-		let assignment_keys: Vec<_> = validators.map(|(_, v)| v).collect();
+		let (account_keys, assignment_keys): (Vec<_>, Vec<_>) =
+			validators.map(|(a, v)| (a.clone(), v)).unzip();
+		AccountKeysUnsafe::<T>::set(account_keys);
 		AssignmentKeysUnsafe::<T>::set(assignment_keys);
 	}
 
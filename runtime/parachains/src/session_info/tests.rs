@@ -106,6 +106,8 @@ fn session_pruning_is_based_on_dispute_period() {
 		assert!(Sessions::<Test>::get(7).is_none());
 		assert!(Sessions::<Test>::get(8).is_some());
 		assert!(Sessions::<Test>::get(9).is_some());
+		assert!(AccountKeys::<Test>::get(7).is_none());
+		assert!(AccountKeys::<Test>::get(8).is_some());
 
 		// changing `dispute_period` works
 		let dispute_period = 5;
@@ -180,6 +182,7 @@ fn session_info_active_subsets() {
 		unscrambled.iter().map(|v| v.public().into()).collect();
 	let unscrambled_assignment: Vec<AssignmentId> =
 		unscrambled.iter().map(|v| v.public().into()).collect();
+	let unscrambled_accounts: Vec<u64> = (0..unscrambled.len() as u64).collect();
 
 	let validators = take_active_subset(&active_set, &unscrambled_validators);
 
@@ -189,6 +192,7 @@ fn session_info_active_subsets() {
 		assert_eq!(ParasShared::active_validator_indices(), active_set);
 
 		AssignmentKeysUnsafe::<Test>::set(unscrambled_assignment.clone());
+		AccountKeysUnsafe::<Test>::set(unscrambled_accounts.clone());
 		crate::mock::set_discovery_authorities(unscrambled_discovery.clone());
 		assert_eq!(<Test>::authorities(), unscrambled_discovery);
 
@@ -210,5 +214,9 @@ fn session_info_active_subsets() {
 			session.assignment_keys,
 			take_active_subset(&active_set, &unscrambled_assignment),
 		);
+		assert_eq!(
+			AccountKeys::<Test>::get(&1).unwrap(),
+			take_active_subset(&active_set, &unscrambled_accounts),
+		);
 	})
 }
@@ -32,7 +32,7 @@ pub use pallet::*;
 ///
 /// This is used for benchmarking sanely bounding relevant storate items. It is expected from the `configurations`
 /// pallet to check these values before setting.
-pub const MAX_UPWARD_MESSAGE_SIZE_BOUND: u32 = 50 * 1024;
+pub const MAX_UPWARD_MESSAGE_SIZE_BOUND: u32 = primitives::v1::MAX_UPWARD_MESSAGE_SIZE;
 
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
@@ -209,6 +209,7 @@ impl WeightInfo for TestWeightInfo {
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
+	use frame_system::pallet_prelude::BlockNumberFor;
 
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
@@ -302,6 +303,7 @@ pub mod pallet {
 	// NOTE that this field is used by parachains via merkle storage proofs, therefore changing
 	// the format will require migration of parachains.
 	#[pallet::storage]
+	#[pallet::getter(fn relay_dispatch_queue_size)]
 	pub type RelayDispatchQueueSize<T: Config> =
 		StorageMap<_, Twox64Concat, ParaId, (u32, u32), ValueQuery>;
 
@@ -333,6 +335,21 @@ pub mod pallet {
 	#[pallet::storage]
 	pub type OverweightCount<T: Config> = StorageValue<_, OverweightIndex, ValueQuery>;
 
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		/// Dispatch pending upward messages with the weight left in the block, up to
+		/// `config.ump_service_total_weight`.
+		fn on_idle(_now: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+			// reading the config and the dispatch cursor, and writing the cursor back.
+			let overhead = T::DbWeight::get().reads_writes(3, 2);
+			if remaining_weight <= overhead {
+				return 0
+			}
+			overhead
+				.saturating_add(Self::process_pending_upward_messages(remaining_weight - overhead))
+		}
+	}
+
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 		/// Service a single overweight upward message.
@@ -504,15 +521,18 @@ impl<T: Config> Pallet<T> {
 	}
 
 	/// Devote some time into dispatching pending upward messages.
-	pub(crate) fn process_pending_upward_messages() -> Weight {
+	///
+	/// At most `weight_limit` or `config.ump_service_total_weight`, whichever is lower, is used.
+	pub(crate) fn process_pending_upward_messages(weight_limit: Weight) -> Weight {
 		let mut weight_used = 0;
 
 		let config = <configuration::Pallet<T>>::config();
+		let service_total_weight = config.ump_service_total_weight.min(weight_limit);
 		let mut cursor = NeedsDispatchCursor::new::<T>();
 		let mut queue_cache = QueueCache::new();
 
 		while let Some(dispatchee) = cursor.peek() {
-			if weight_used >= config.ump_service_total_weight {
+			if weight_used >= service_total_weight {
 				// Then check whether we've reached or overshoot the
 				// preferred weight for the dispatching stage.
 				//
@@ -522,9 +542,10 @@ impl<T: Config> Pallet<T> {
 			let max_weight = if weight_used == 0 {
 				// we increase the amount of weight that we're allowed to use on the first message to try to prevent
 				// the possibility of blockage of the queue.
-				config.ump_service_total_weight * T::FirstMessageFactorPercent::get() / 100
+				(config.ump_service_total_weight * T::FirstMessageFactorPercent::get() / 100)
+					.min(weight_limit)
 			} else {
-				config.ump_service_total_weight - weight_used
+				service_total_weight - weight_used
 			};
 
 			// attempt to process the next message from the queue of the dispatchee; if not beyond
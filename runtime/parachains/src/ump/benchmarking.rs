@@ -122,7 +122,7 @@ frame_benchmarking::benchmarks! {
 		// emitted during the genesis block they will be implicitly wiped.
 		frame_system::Pallet::<T>::set_block_number(1u32.into());
 		queue_upward_msg::<T>(&host_conf, para, msg.clone());
-		Ump::<T>::process_pending_upward_messages();
+		Ump::<T>::process_pending_upward_messages(Weight::MAX);
 		assert_last_event_type::<T>(
 			Event::OverweightEnqueued(para, upward_message_id(&msg), 0, 0).into()
 			);
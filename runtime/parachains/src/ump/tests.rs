@@ -122,7 +122,7 @@ fn dispatch_empty() {
 		assert_storage_consistency_exhaustive();
 
 		// make sure that the case with empty queues is handled properly
-		Ump::process_pending_upward_messages();
+		Ump::process_pending_upward_messages(Weight::MAX);
 
 		assert_storage_consistency_exhaustive();
 	});
@@ -135,7 +135,7 @@ fn dispatch_single_message() {
 
 	new_test_ext(GenesisConfigBuilder::default().build()).execute_with(|| {
 		queue_upward_msg(a, msg.clone());
-		Ump::process_pending_upward_messages();
+		Ump::process_pending_upward_messages(Weight::MAX);
 		assert_eq!(take_processed(), vec![(a, msg)]);
 
 		assert_storage_consistency_exhaustive();
@@ -166,7 +166,7 @@ fn dispatch_resume_after_exceeding_dispatch_stage_weight() {
 		assert_storage_consistency_exhaustive();
 
 		// we expect only two first messages to fit in the first iteration.
-		Ump::process_pending_upward_messages();
+		Ump::process_pending_upward_messages(Weight::MAX);
 		assert_eq!(take_processed(), vec![(a, a_msg_1), (c, c_msg_1)]);
 		assert_storage_consistency_exhaustive();
 
@@ -174,17 +174,17 @@ fn dispatch_resume_after_exceeding_dispatch_stage_weight() {
 		assert_storage_consistency_exhaustive();
 
 		// second iteration should process the second message.
-		Ump::process_pending_upward_messages();
+		Ump::process_pending_upward_messages(Weight::MAX);
 		assert_eq!(take_processed(), vec![(q, q_msg)]);
 		assert_storage_consistency_exhaustive();
 
 		// 3rd iteration.
-		Ump::process_pending_upward_messages();
+		Ump::process_pending_upward_messages(Weight::MAX);
 		assert_eq!(take_processed(), vec![(a, a_msg_2), (c, c_msg_2)]);
 		assert_storage_consistency_exhaustive();
 
 		// finally, make sure that the queue is empty.
-		Ump::process_pending_upward_messages();
+		Ump::process_pending_upward_messages(Weight::MAX);
 		assert_eq!(take_processed(), vec![]);
 		assert_storage_consistency_exhaustive();
 	});
@@ -212,22 +212,55 @@ fn dispatch_keeps_message_after_weight_exhausted() {
 		assert_storage_consistency_exhaustive();
 
 		// we expect only one message to fit in the first iteration.
-		Ump::process_pending_upward_messages();
+		Ump::process_pending_upward_messages(Weight::MAX);
 		assert_eq!(take_processed(), vec![(a, a_msg_1)]);
 		assert_storage_consistency_exhaustive();
 
 		// second iteration should process the remaining message.
-		Ump::process_pending_upward_messages();
+		Ump::process_pending_upward_messages(Weight::MAX);
 		assert_eq!(take_processed(), vec![(a, a_msg_2)]);
 		assert_storage_consistency_exhaustive();
 
 		// finally, make sure that the queue is empty.
-		Ump::process_pending_upward_messages();
+		Ump::process_pending_upward_messages(Weight::MAX);
 		assert_eq!(take_processed(), vec![]);
 		assert_storage_consistency_exhaustive();
 	});
 }
 
+#[test]
+fn dispatch_on_idle_is_limited_by_remaining_weight() {
+	use frame_support::traits::Hooks;
+
+	let a = ParaId::from(128);
+	let c = ParaId::from(228);
+
+	let a_msg = (300u32, "a_msg").encode();
+	let c_msg = (300u32, "c_msg").encode();
+
+	new_test_ext(
+		GenesisConfigBuilder { ump_max_individual_weight: 300, ..Default::default() }.build(),
+	)
+	.execute_with(|| {
+		queue_upward_msg(a, a_msg.clone());
+		queue_upward_msg(c, c_msg.clone());
+
+		// nothing is dispatched without any weight left in the block.
+		assert_eq!(Ump::on_idle(1, 0), 0);
+		assert_eq!(take_processed(), vec![]);
+
+		// only the first message fits into the remaining weight, even though the
+		// configured budget would allow both.
+		assert_eq!(Ump::on_idle(1, 400), 300);
+		assert_eq!(take_processed(), vec![(a, a_msg)]);
+		assert_storage_consistency_exhaustive();
+
+		assert_eq!(Ump::on_idle(1, Weight::MAX), 300);
+		assert_eq!(take_processed(), vec![(c, c_msg)]);
+		assert_storage_consistency_exhaustive();
+	});
+}
+
 #[test]
 fn dispatch_correctly_handle_remove_of_latest() {
 	let a = ParaId::from(1991);
@@ -250,7 +283,7 @@ fn dispatch_correctly_handle_remove_of_latest() {
 		queue_upward_msg(a, a_msg_1.clone());
 		queue_upward_msg(a, a_msg_2.clone());
 		queue_upward_msg(b, b_msg_1.clone());
-		Ump::process_pending_upward_messages();
+		Ump::process_pending_upward_messages(Weight::MAX);
 		assert_eq!(take_processed(), vec![(a, a_msg_1), (b, b_msg_1), (a, a_msg_2)]);
 	});
 }
@@ -318,13 +351,13 @@ fn overweight_queue_works() {
 		// This one is overweight. However, the weight is plenty and we can afford to execute
 		// this message, thus expect it.
 		queue_upward_msg(para_a, a_msg_1.clone());
-		Ump::process_pending_upward_messages();
+		Ump::process_pending_upward_messages(Weight::MAX);
 		assert_eq!(take_processed(), vec![(para_a, a_msg_1)]);
 
 		// This is overweight and this message cannot fit into the total weight budget.
 		queue_upward_msg(para_a, a_msg_2.clone());
 		queue_upward_msg(para_a, a_msg_3.clone());
-		Ump::process_pending_upward_messages();
+		Ump::process_pending_upward_messages(Weight::MAX);
 		assert_last_event(
 			Event::OverweightEnqueued(para_a, upward_message_id(&a_msg_3[..]), 0, 500).into(),
 		);
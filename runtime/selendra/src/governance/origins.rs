@@ -0,0 +1,58 @@
+// Copyright 2022 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Custom origins for governance interventions.
+
+pub use pallet_custom_origins::*;
+
+#[frame_support::pallet]
+pub mod pallet_custom_origins {
+	use frame_support::pallet_prelude::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {}
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	/// The origins a referendum can be dispatched with, one per track.
+	#[derive(PartialEq, Eq, Clone, MaxEncodedLen, Encode, Decode, TypeInfo, RuntimeDebug)]
+	#[pallet::origin]
+	pub enum Origin {
+		/// Origin able to extend and revoke parachain leases.
+		LeaseAdmin,
+	}
+
+	/// Ensures the origin is [`Origin::LeaseAdmin`].
+	///
+	/// Only the lease management calls accept this origin, which restricts what a referendum on
+	/// the lease admin track can dispatch.
+	pub struct LeaseAdmin;
+	impl<O: Into<Result<Origin, O>> + From<Origin>> EnsureOrigin<O> for LeaseAdmin {
+		type Success = ();
+
+		fn try_origin(o: O) -> Result<Self::Success, O> {
+			o.into().map(|o| match o {
+				Origin::LeaseAdmin => (),
+			})
+		}
+
+		#[cfg(feature = "runtime-benchmarks")]
+		fn successful_origin() -> O {
+			O::from(Origin::LeaseAdmin)
+		}
+	}
+}
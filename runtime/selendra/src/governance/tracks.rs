@@ -0,0 +1,59 @@
+// Copyright 2022 SmallWorld Selendra (Kh).
+// This file is part of Selendra.
+
+// Selendra is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Selendra is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Selendra.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Track configurations for governance.
+
+use super::*;
+
+const TRACKS_DATA: [(u16, pallet_referenda::TrackInfo<Balance, BlockNumber>); 1] = [(
+	0,
+	pallet_referenda::TrackInfo {
+		name: "lease_admin",
+		max_deciding: 10,
+		decision_deposit: 5_000 * UNITS,
+		prepare_period: 4 * HOURS,
+		decision_period: 14 * DAYS,
+		confirm_period: 3 * HOURS,
+		// Much shorter than the enactment period of general purpose referenda, as lease changes
+		// are usually time critical with respect to the lease periods.
+		min_enactment_period: 1 * DAYS,
+		min_approval: pallet_referenda::Curve::LinearDecreasing {
+			begin: Perbill::from_percent(100),
+			delta: Perbill::from_percent(50),
+		},
+		min_turnout: pallet_referenda::Curve::LinearDecreasing {
+			begin: Perbill::from_percent(50),
+			delta: Perbill::from_percent(40),
+		},
+	},
+)];
+
+pub struct TracksInfo;
+impl pallet_referenda::TracksInfo<Balance, BlockNumber> for TracksInfo {
+	type Id = u16;
+	type Origin = <Origin as frame_support::traits::OriginTrait>::PalletsOrigin;
+
+	fn tracks() -> &'static [(Self::Id, pallet_referenda::TrackInfo<Balance, BlockNumber>)] {
+		&TRACKS_DATA[..]
+	}
+
+	fn track_for(id: &Self::Origin) -> Result<Self::Id, ()> {
+		match id {
+			OriginCaller::Origins(pallet_custom_origins::Origin::LeaseAdmin) => Ok(0),
+			_ => Err(()),
+		}
+	}
+}
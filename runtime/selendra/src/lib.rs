@@ -22,15 +22,17 @@
 
 use pallet_transaction_payment::CurrencyAdapter;
 use runtime_common::{
-	impls::DealWithFees, paras_registrar, prod_or_fast, slots, BlockHashCount, BlockLength,
-	BlockWeights, CurrencyToVote, OffchainSolutionLengthLimit, OffchainSolutionWeightLimit,
-	RocksDbWeight, SlowAdjustingFeeUpdate,
+	asset_rate, auctions, collator_rewards, crowdloan, impls::DealWithFees, offence_chill,
+	paras_registrar, payout_splits, prod_or_fast, slots, BlockHashCount, BlockLength, BlockWeights,
+	CurrencyToVote, OffchainSolutionLengthLimit, OffchainSolutionWeightLimit, RocksDbWeight,
+	SlowAdjustingFeeUpdate,
 };
 
 use runtime_parachains::{
-	configuration as parachains_configuration, disputes as parachains_disputes,
-	dmp as parachains_dmp, hrmp as parachains_hrmp, inclusion as parachains_inclusion,
-	initializer as parachains_initializer, origin as parachains_origin, paras as parachains_paras,
+	assigner_on_demand as parachains_assigner_on_demand, configuration as parachains_configuration,
+	disputes as parachains_disputes, dmp as parachains_dmp, hrmp as parachains_hrmp,
+	inclusion as parachains_inclusion, initializer as parachains_initializer,
+	origin as parachains_origin, paras as parachains_paras,
 	paras_inherent as parachains_paras_inherent, reward_points as parachains_reward_points,
 	runtime_api_impl::v1 as parachains_runtime_api_impl, scheduler as parachains_scheduler,
 	session_info as parachains_session_info, shared as parachains_shared, ump as parachains_ump,
@@ -98,6 +100,10 @@ mod weights;
 
 mod bag_thresholds;
 
+// Governance tracks for referenda with conviction voting.
+pub mod governance;
+use governance::{pallet_custom_origins, LeaseAdmin};
+
 pub mod xcm_config;
 
 // Make the WASM binary available.
@@ -139,6 +145,8 @@ impl Contains<Call> for BaseFilter {
 		match call {
 			// These modules are all allowed to be called by transactions:
 			Call::Democracy(_) |
+			Call::ConvictionVoting(_) |
+			Call::Referenda(_) |
 			Call::Council(_) |
 			Call::TechnicalCommittee(_) |
 			Call::TechnicalMembership(_) |
@@ -174,10 +182,18 @@ impl Contains<Call> for BaseFilter {
 			Call::Dmp(_) |
 			Call::Ump(_) |
 			Call::Hrmp(_) |
+			Call::OnDemandAssignment(_) |
 			Call::Slots(_) |
+			Call::Auctions(_) |
+			Call::Crowdloan(_) |
 			Call::Registrar(_) |
 			Call::Recovery(_) |
 			Call::BagsList(_) |
+			Call::AssetRate(_) |
+			Call::OffenceChill(_) |
+			Call::CollatorRewards(_) |
+			Call::PayoutSplits(_) |
+			Call::TransactionStorage(_) |
 			Call::XcmPallet(_) => true,
 			// All pallets are allowed, but exhaustive match is defensive
 			// in the case of adding new pallets.
@@ -190,6 +206,16 @@ type MoreThanHalfCouncil = EnsureOneOf<
 	pallet_collective::EnsureProportionMoreThan<AccountId, CouncilCollective, 1, 2>,
 >;
 
+/// Root, two thirds of the council or two thirds of the technical committee, so that a dangerous
+/// referendum can be stopped even when the council cannot gather in time.
+type EmergencyCancelOrigin = EnsureOneOf<
+	EnsureRoot<AccountId>,
+	EnsureOneOf<
+		pallet_collective::EnsureProportionAtLeast<AccountId, CouncilCollective, 2, 3>,
+		pallet_collective::EnsureProportionAtLeast<AccountId, TechnicalCollective, 2, 3>,
+	>,
+>;
+
 parameter_types! {
 	pub const Version: RuntimeVersion = VERSION;
 	pub const SS58Prefix: u16 = 204;
@@ -319,8 +345,11 @@ impl pallet_babe::Config for Runtime {
 		pallet_babe::AuthorityId,
 	)>>::IdentificationTuple;
 
-	type HandleEquivocation =
-		pallet_babe::EquivocationHandler<Self::KeyOwnerIdentification, Offences, ReportLongevity>;
+	type HandleEquivocation = pallet_babe::EquivocationHandler<
+		Self::KeyOwnerIdentification,
+		ChillOffenders,
+		ReportLongevity,
+	>;
 
 	type WeightInfo = ();
 
@@ -645,11 +674,10 @@ impl pallet_democracy::Config for Runtime {
 	>;
 	type InstantAllowed = InstantAllowed;
 	type FastTrackVotingPeriod = FastTrackVotingPeriod;
-	// To cancel a proposal which has been passed, 2/3 of the council must agree to it.
-	type CancellationOrigin = EnsureOneOf<
-		pallet_collective::EnsureProportionAtLeast<AccountId, CouncilCollective, 2, 3>,
-		EnsureRoot<AccountId>,
-	>;
+	// To cancel a proposal which has been passed, 2/3 of the council or of the technical
+	// committee must agree to it. Cancelled proposals are recorded in `Cancellations` and can't be
+	// cancelled this way again.
+	type CancellationOrigin = EmergencyCancelOrigin;
 	// To cancel a proposal before it has been passed, the technical committee must be unanimous or
 	// Root must agree.
 	type CancelProposalOrigin = EnsureOneOf<
@@ -802,6 +830,50 @@ impl pallet_treasury::Config for Runtime {
 	type WeightInfo = weights::pallet_treasury::WeightInfo<Runtime>;
 }
 
+impl asset_rate::Config for Runtime {
+	type Event = Event;
+	type RateOrigin = ApproveOrigin;
+	type WeightInfo = weights::runtime_common_asset_rate::WeightInfo<Runtime>;
+}
+
+parameter_types! {
+	/// BABE and GRANDPA equivocations.
+	pub ChillingOffences: Vec<sp_staking::offence::Kind> =
+		vec![*b"babe:equivocatio", *b"grandpa:equivoca"];
+	// Chilled validators sit out as long as it takes to unbond.
+	pub OffenceCooldownPeriod: BlockNumber =
+		BondingDuration::get() * SessionsPerEra::get() * EpochDuration::get() as BlockNumber;
+	pub const OffenceCooldownCheckInterval: BlockNumber = 10 * MINUTES;
+}
+
+/// Reports offences, chilling the offenders of the `ChillingOffences`.
+type ChillOffenders = offence_chill::ChillOffenders<Runtime, Offences>;
+
+parameter_types! {
+	pub const PayoutSplitsPalletId: PalletId = PalletId(*b"py/split");
+	pub const MaxPayoutSplitPayees: u32 = 16;
+}
+
+impl payout_splits::Config for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type Staking = Staking;
+	type PalletId = PayoutSplitsPalletId;
+	type MaxPayees = MaxPayoutSplitPayees;
+	type WeightInfo = weights::runtime_common_payout_splits::WeightInfo<Runtime>;
+}
+
+impl offence_chill::Config for Runtime {
+	type Event = Event;
+	type Validators = Staking;
+	type ChillingOffences = ChillingOffences;
+	type CooldownPeriod = OffenceCooldownPeriod;
+	type CheckInterval = OffenceCooldownCheckInterval;
+	type DisputeOffenders = offence_chill::ParachainValidatorStash<Runtime>;
+	type ForceOrigin = SlashCancelOrigin;
+	type WeightInfo = weights::runtime_common_offence_chill::WeightInfo<Runtime>;
+}
+
 impl pallet_bounties::Config for Runtime {
 	type Event = Event;
 	type BountyDepositBase = BountyDepositBase;
@@ -871,7 +943,7 @@ impl pallet_grandpa::Config for Runtime {
 
 	type HandleEquivocation = pallet_grandpa::EquivocationHandler<
 		Self::KeyOwnerIdentification,
-		Offences,
+		ChillOffenders,
 		ReportLongevity,
 	>;
 
@@ -1000,6 +1072,54 @@ impl pallet_recovery::Config for Runtime {
 	type RecoveryDeposit = RecoveryDeposit;
 }
 
+parameter_types! {
+	// Stored data is priced like the length fee of regular transactions, plus a fee per entry.
+	pub TransactionStorageByteFee: Balance = TransactionByteFee::get();
+	pub const TransactionStorageEntryFee: Balance = 10 * CENTS;
+	// Stored data has to be proven to be kept for a week.
+	pub TransactionStoragePeriod: BlockNumber =
+		prod_or_fast!(7 * DAYS, 10 * MINUTES, "SEL_TRANSACTION_STORAGE_PERIOD");
+	pub const MaxBlockTransactions: u32 =
+		pallet_transaction_storage::DEFAULT_MAX_BLOCK_TRANSACTIONS;
+	pub const MaxTransactionSize: u32 = pallet_transaction_storage::DEFAULT_MAX_TRANSACTION_SIZE;
+}
+
+impl pallet_transaction_storage::Config for Runtime {
+	type Event = Event;
+	type Call = Call;
+	type Currency = Balances;
+	type FeeDestination = DealWithFees<Runtime>;
+	type WeightInfo = weights::pallet_transaction_storage::WeightInfo<Runtime>;
+	type MaxBlockTransactions = MaxBlockTransactions;
+	type MaxTransactionSize = MaxTransactionSize;
+}
+
+/// Configures the fees and the storage period of the transaction storage on chains which
+/// didn't do so at genesis, as storing data fails with `NotConfigured` otherwise.
+pub struct InitializeTransactionStorage;
+impl frame_support::traits::OnRuntimeUpgrade for InitializeTransactionStorage {
+	fn on_runtime_upgrade() -> Weight {
+		use frame_support::{
+			storage::{storage_prefix, unhashed},
+			traits::{Get, PalletInfoAccess},
+		};
+
+		let key = |item: &[u8]| storage_prefix(TransactionStorage::name().as_bytes(), item);
+		let db_weight = <Runtime as frame_system::Config>::DbWeight::get();
+
+		let storage_period_key = key(b"StoragePeriod");
+		if unhashed::exists(&storage_period_key) {
+			return db_weight.reads(1)
+		}
+
+		unhashed::put(&key(b"ByteFee"), &TransactionStorageByteFee::get());
+		unhashed::put(&key(b"EntryFee"), &TransactionStorageEntryFee::get());
+		unhashed::put(&storage_period_key, &TransactionStoragePeriod::get());
+
+		db_weight.reads_writes(1, 3)
+	}
+}
+
 parameter_types! {
 	// One storage item; key size 32, value size 8; .
 	pub const ProxyDepositBase: Balance = deposit(1, 8);
@@ -1092,6 +1212,8 @@ impl InstanceFilter<Call> for ProxyType {
 				Call::Grandpa(..) |
 				Call::ImOnline(..) |
 				Call::Democracy(..) |
+				Call::ConvictionVoting(..) |
+				Call::Referenda(..) |
 				Call::Council(..) |
 				Call::TechnicalCommittee(..) |
 				Call::PhragmenElection(..) |
@@ -1116,13 +1238,17 @@ impl InstanceFilter<Call> for ProxyType {
 				Call::Registrar(paras_registrar::Call::deregister {..}) |
 				// Specifically omitting Registrar `swap`
 				Call::Registrar(paras_registrar::Call::reserve {..}) |
-				Call::Slots(..) | // Specifically omitting the entire XCM Pallet
+				Call::Slots(..) |
+				Call::Auctions(..) |
+				Call::Crowdloan(..) | // Specifically omitting the entire XCM Pallet
 				Call::BagsList(..)
 			),
 			ProxyType::Governance => matches!(
 				c,
 				Call::Democracy(..) |
-					Call::Council(..) | Call::TechnicalCommittee(..) |
+					Call::ConvictionVoting(..) |
+					Call::Referenda(..) | Call::Council(..) |
+					Call::TechnicalCommittee(..) |
 					Call::PhragmenElection(..) |
 					Call::Treasury(..) | Call::Bounties(..) |
 					Call::Tips(..) | Call::Utility(..)
@@ -1136,7 +1262,7 @@ impl InstanceFilter<Call> for ProxyType {
 			),
 			ProxyType::CancelProxy => {
 				matches!(c, Call::Proxy(pallet_proxy::Call::reject_announcement { .. }))
-			}
+			},
 		}
 	}
 	fn is_superset(&self, o: &Self) -> bool {
@@ -1179,6 +1305,19 @@ impl parachains_inclusion::Config for Runtime {
 	type Event = Event;
 	type DisputesHandler = ParasDisputes;
 	type RewardValidators = parachains_reward_points::RewardValidatorsWithEraPoints<Runtime>;
+	type RewardCollators = CollatorRewards;
+}
+
+parameter_types! {
+	pub const CollatorRewardsPalletId: PalletId = PalletId(*b"py/colrw");
+}
+
+impl collator_rewards::Config for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type PalletId = CollatorRewardsPalletId;
+	type RewardOrigin = ApproveOrigin;
+	type WeightInfo = weights::runtime_common_collator_rewards::WeightInfo<Runtime>;
 }
 
 parameter_types! {
@@ -1205,12 +1344,34 @@ impl parachains_ump::Config for Runtime {
 	type WeightInfo = parachains_ump::TestWeightInfo;
 }
 
-impl parachains_dmp::Config for Runtime {}
+parameter_types! {
+	pub const DmpQueueHighWatermark: parachains_dmp::QueueWatermark =
+		parachains_dmp::DEFAULT_QUEUE_HIGH_WATERMARK;
+	pub const DmpQueueLowWatermark: parachains_dmp::QueueWatermark =
+		parachains_dmp::DEFAULT_QUEUE_LOW_WATERMARK;
+	pub const DmpQueueCapacity: parachains_dmp::QueueWatermark =
+		parachains_dmp::DEFAULT_QUEUE_CAPACITY;
+}
+
+impl parachains_dmp::Config for Runtime {
+	type Event = Event;
+	type QueueHighWatermark = DmpQueueHighWatermark;
+	type QueueLowWatermark = DmpQueueLowWatermark;
+	type QueueCapacity = DmpQueueCapacity;
+}
+
+parameter_types! {
+	pub const HrmpChannelCloseGracePeriod: BlockNumber = 1 * HOURS;
+	/// Paras from the public range which are treated as system paras for HRMP.
+	pub HrmpSystemParas: Vec<ParaId> = Vec::new();
+}
 
 impl parachains_hrmp::Config for Runtime {
 	type Event = Event;
 	type Origin = Origin;
 	type Currency = Balances;
+	type ChannelCloseGracePeriod = HrmpChannelCloseGracePeriod;
+	type SystemParas = parachains_hrmp::IsSystemPara<HrmpSystemParas>;
 	type WeightInfo = weights::runtime_parachains_hrmp::WeightInfo<Self>;
 }
 
@@ -1220,6 +1381,21 @@ impl parachains_paras_inherent::Config for Runtime {
 
 impl parachains_scheduler::Config for Runtime {}
 
+parameter_types! {
+	pub const OnDemandBaseFee: Balance = 10 * CENTS;
+	pub const OnDemandTargetQueueUtilization: Perbill = Perbill::from_percent(25);
+	pub const OnDemandFeeVariability: Perbill = Perbill::from_percent(3);
+}
+
+impl parachains_assigner_on_demand::Config for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type BaseFee = OnDemandBaseFee;
+	type TargetQueueUtilization = OnDemandTargetQueueUtilization;
+	type FeeVariability = OnDemandFeeVariability;
+	type WeightInfo = weights::runtime_parachains_assigner_on_demand::WeightInfo<Runtime>;
+}
+
 impl parachains_initializer::Config for Runtime {
 	type Randomness = pallet_babe::RandomnessFromOneEpochAgo<Runtime>;
 	type ForceOrigin = EnsureRoot<AccountId>;
@@ -1229,14 +1405,20 @@ impl parachains_initializer::Config for Runtime {
 impl parachains_disputes::Config for Runtime {
 	type Event = Event;
 	type RewardValidators = ();
-	type PunishValidators = ();
+	// Losers of a dispute are slashed by staking and put on a cooldown.
+	type PunishValidators = (
+		parachains_disputes::slashing::SlashValidatorsForDisputes<Runtime, Offences>,
+		OffenceChill,
+	);
 	type WeightInfo = weights::runtime_parachains_disputes::WeightInfo<Runtime>;
 }
 
 parameter_types! {
 	// Mostly arbitrary deposit price, but should provide an adequate incentive not to spam reserve
 	// `ParaId`s.
-	pub const ParaDeposit: Balance = 100 * UNITS;
+	pub const ParaDeposit: Balance = 10 * UNITS;
+	// Held on top of `ParaDeposit` while the para is a parachain.
+	pub const ParaUpgradeDeposit: Balance = 90 * UNITS;
 	pub const ParaDataByteDeposit: Balance = deposit(0, 1);
 }
 
@@ -1248,8 +1430,9 @@ impl paras_registrar::Config for Runtime {
 		pallet_collective::EnsureProportionAtLeast<AccountId, TechnicalCollective, 2, 3>,
 	>;
 	type Currency = Balances;
-	type OnSwap = Slots;
+	type OnSwap = (Crowdloan, Slots);
 	type ParaDeposit = ParaDeposit;
+	type UpgradeDeposit = ParaUpgradeDeposit;
 	type DataDepositPerByte = ParaDataByteDeposit;
 	type WeightInfo = weights::runtime_common_paras_registrar::WeightInfo<Runtime>;
 }
@@ -1265,10 +1448,50 @@ impl slots::Config for Runtime {
 	type Registrar = Registrar;
 	type LeasePeriod = LeasePeriod;
 	type LeaseOffset = ();
-	type ForceOrigin = MoreThanHalfCouncil;
+	// Leases are extended and revoked by the council or a referendum on the lease admin track.
+	type ForceOrigin = EnsureOneOf<MoreThanHalfCouncil, LeaseAdmin>;
 	type WeightInfo = weights::runtime_common_slots::WeightInfo<Runtime>;
 }
 
+parameter_types! {
+	// 5 days of retroactive ending, sampled every 2 minutes.
+	pub const EndingPeriod: BlockNumber = 5 * DAYS;
+	pub const SampleLength: BlockNumber = 2 * MINUTES;
+}
+
+impl auctions::Config for Runtime {
+	type Event = Event;
+	type Leaser = Slots;
+	type Registrar = Registrar;
+	type EndingPeriod = EndingPeriod;
+	type SampleLength = SampleLength;
+	type Randomness = pallet_babe::RandomnessFromOneEpochAgo<Runtime>;
+	// Auctions are started by the same origins that may force leases.
+	type InitiateOrigin = EnsureOneOf<MoreThanHalfCouncil, LeaseAdmin>;
+	type WeightInfo = weights::runtime_common_auctions::WeightInfo<Runtime>;
+}
+
+parameter_types! {
+	pub const CrowdloanId: PalletId = PalletId(*b"py/cfund");
+	pub const SubmissionDeposit: Balance = 100 * UNITS;
+	pub const MinContribution: Balance = 5 * UNITS;
+	pub const RemoveKeysLimit: u32 = 1000;
+	// Allow 32 bytes for an additional memo to a crowdloan.
+	pub const MaxMemoLength: u8 = 32;
+}
+
+impl crowdloan::Config for Runtime {
+	type Event = Event;
+	type PalletId = CrowdloanId;
+	type SubmissionDeposit = SubmissionDeposit;
+	type MinContribution = MinContribution;
+	type RemoveKeysLimit = RemoveKeysLimit;
+	type Registrar = Registrar;
+	type Auctioneer = Auctions;
+	type MaxMemoLength = MaxMemoLength;
+	type WeightInfo = weights::runtime_common_crowdloan::WeightInfo<Runtime>;
+}
+
 construct_runtime! {
 	pub enum Runtime where
 		Block = Block,
@@ -1303,6 +1526,9 @@ construct_runtime! {
 		PhragmenElection: pallet_elections_phragmen::{Pallet, Call, Storage, Event<T>, Config<T>} = 16,
 		TechnicalMembership: pallet_membership::<Instance1>::{Pallet, Call, Storage, Event<T>, Config<T>} = 17,
 		Treasury: pallet_treasury::{Pallet, Call, Storage, Config, Event<T>} = 18,
+		ConvictionVoting: pallet_conviction_voting::{Pallet, Call, Storage, Event<T>} = 20,
+		Referenda: pallet_referenda::{Pallet, Call, Storage, Event<T>} = 21,
+		Origins: pallet_custom_origins::{Origin} = 22,
 
 		// Utility module.
 		Utility: pallet_utility::{Pallet, Call, Event} = 24,
@@ -1340,6 +1566,21 @@ construct_runtime! {
 		// Provides a semi-sorted list of nominators for staking.
 		BagsList: pallet_bags_list::{Pallet, Call, Storage, Event<T>} = 39,
 
+		// Conversion rates of foreign assets to SEL.
+		AssetRate: asset_rate::{Pallet, Call, Storage, Event<T>} = 40,
+
+		// Data availability for rollups.
+		TransactionStorage: pallet_transaction_storage::{Pallet, Call, Storage, Inherent, Config<T>, Event<T>} = 41,
+
+		// Chilling validators committing serious offences.
+		OffenceChill: offence_chill::{Pallet, Call, Storage, Event<T>} = 42,
+
+		// Paying collators of included candidates from per-para pots.
+		CollatorRewards: collator_rewards::{Pallet, Call, Storage, Event<T>} = 43,
+
+		// Splitting the staking rewards of validators between several payees.
+		PayoutSplits: payout_splits::{Pallet, Call, Storage, Event<T>} = 44,
+
 		// Parachains pallets. Start indices at 50 to leave room.
 		ParachainsOrigin: parachains_origin::{Pallet, Origin} = 50,
 		Configuration: parachains_configuration::{Pallet, Call, Storage, Config<T>} = 51,
@@ -1349,15 +1590,18 @@ construct_runtime! {
 		ParaScheduler: parachains_scheduler::{Pallet, Storage} = 55,
 		Paras: parachains_paras::{Pallet, Call, Storage, Event, Config} = 56,
 		Initializer: parachains_initializer::{Pallet, Call, Storage} = 57,
-		Dmp: parachains_dmp::{Pallet, Call, Storage} = 58,
+		Dmp: parachains_dmp::{Pallet, Call, Storage, Event} = 58,
 		Ump: parachains_ump::{Pallet, Call, Storage, Event} = 59,
 		Hrmp: parachains_hrmp::{Pallet, Call, Storage, Event<T>, Config} = 60,
 		ParaSessionInfo: parachains_session_info::{Pallet, Storage} = 61,
 		ParasDisputes: parachains_disputes::{Pallet, Call, Storage, Event<T>} = 62,
+		OnDemandAssignment: parachains_assigner_on_demand::{Pallet, Call, Storage, Event<T>} = 63,
 
 		// Parachain Onboarding Pallets. Start indices at 70 to leave room.
 		Registrar: paras_registrar::{Pallet, Call, Storage, Event<T>} = 70,
 		Slots: slots::{Pallet, Call, Storage, Event<T>} = 71,
+		Auctions: auctions::{Pallet, Call, Storage, Event<T>} = 72,
+		Crowdloan: crowdloan::{Pallet, Call, Storage, Event<T>} = 73,
 
 		// Pallet for sending XCM.
 		XcmPallet: pallet_xcm::{Pallet, Call, Storage, Event<T>, Origin, Config} = 99,
@@ -1394,7 +1638,7 @@ pub type Executive = frame_executive::Executive<
 	frame_system::ChainContext<Runtime>,
 	Runtime,
 	AllPalletsWithSystem,
-	(),
+	InitializeTransactionStorage,
 >;
 /// The payload being signed in transactions.
 pub type SignedPayload = generic::SignedPayload<Call, SignedExtra>;
@@ -1410,7 +1654,14 @@ mod benches {
 		// NOTE: Make sure to prefix these with `runtime_common::` so
 		// the that path resolves correctly in the generated file.
 		[runtime_common::slots, Slots]
+		[runtime_common::auctions, Auctions]
+		[runtime_common::crowdloan, Crowdloan]
 		[runtime_common::paras_registrar, Registrar]
+		[runtime_common::asset_rate, AssetRate]
+		[runtime_common::offence_chill, OffenceChill]
+		[runtime_common::collator_rewards, CollatorRewards]
+		[runtime_common::payout_splits, PayoutSplits]
+		[runtime_parachains::assigner_on_demand, OnDemandAssignment]
 		[runtime_parachains::configuration, Configuration]
 		[runtime_parachains::disputes, ParasDisputes]
 		[runtime_parachains::initializer, Initializer]
@@ -1441,6 +1692,7 @@ mod benches {
 		[frame_system, SystemBench::<Runtime>]
 		[pallet_timestamp, Timestamp]
 		[pallet_tips, Tips]
+		[pallet_transaction_storage, TransactionStorage]
 		[pallet_treasury, Treasury]
 		[pallet_utility, Utility]
 		[pallet_vesting, Vesting]
@@ -1603,6 +1855,31 @@ sp_api::impl_runtime_apis! {
 		{
 			parachains_runtime_api_impl::validation_code_hash::<Runtime>(para_id, assumption)
 		}
+
+		fn dmq_length(recipient: ParaId) -> u32 {
+			parachains_runtime_api_impl::dmq_length::<Runtime>(recipient)
+		}
+
+		fn hrmp_channel_digests(recipient: ParaId) -> Vec<(BlockNumber, Vec<ParaId>)> {
+			parachains_runtime_api_impl::hrmp_channel_digests::<Runtime>(recipient)
+		}
+
+		fn staging_para_backing_state(
+			para_id: ParaId,
+		) -> Option<primitives::v2::BackingState<Hash, BlockNumber>> {
+			parachains_runtime_api_impl::staging_para_backing_state::<Runtime>(para_id)
+		}
+	}
+
+	impl parachains_configuration::HostConfigurationApi<Block, BlockNumber> for Runtime {
+		fn active_config() -> parachains_configuration::HostConfiguration<BlockNumber> {
+			Configuration::config()
+		}
+
+		fn pending_configs(
+		) -> Vec<(SessionIndex, parachains_configuration::HostConfiguration<BlockNumber>)> {
+			Configuration::pending_configs()
+		}
 	}
 
 	impl beefy_primitives::BeefyApi<Block> for Runtime {
@@ -1750,6 +2027,30 @@ sp_api::impl_runtime_apis! {
 		}
 	}
 
+	impl runtime_common::asset_rate::AssetRateApi<Block> for Runtime {
+		fn conversion_rate(asset: xcm::VersionedMultiLocation) -> Option<sp_runtime::FixedU128> {
+			AssetRate::versioned_conversion_rate(asset)
+		}
+	}
+
+	impl runtime_common::sovereign_accounts::SovereignAccountApi<Block, AccountId, Balance>
+		for Runtime
+	{
+		fn para_sovereign_account(
+			para: ParaId,
+		) -> runtime_common::sovereign_accounts::SovereignAccount<AccountId, Balance> {
+			use runtime_common::sovereign_accounts::{para_account, SovereignAccount};
+			SovereignAccount::of::<Balances>(para_account(para))
+		}
+
+		fn sibling_sovereign_account(
+			para: ParaId,
+		) -> runtime_common::sovereign_accounts::SovereignAccount<AccountId, Balance> {
+			use runtime_common::sovereign_accounts::{sibling_account, SovereignAccount};
+			SovereignAccount::of::<Balances>(sibling_account(para))
+		}
+	}
+
 	impl pallet_transaction_payment_rpc_runtime_api::TransactionPaymentApi<
 		Block,
 		Balance,
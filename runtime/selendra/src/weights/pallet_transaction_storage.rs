@@ -0,0 +1,52 @@
+//! Weights for `pallet_transaction_storage`
+//!
+//! NOT YET GENERATED ON REFERENCE HARDWARE: the base weights are the ones measured upstream for
+//! `pallet_transaction_storage`, the storage accesses are the ones of this runtime, where the
+//! block and transaction size limits are constants instead of storage items.
+//! They have to be replaced by the output of the `benchmark` subcommand of the node before the
+//! next runtime release.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::Weight};
+use sp_std::marker::PhantomData;
+
+/// Weight functions for `pallet_transaction_storage`.
+pub struct WeightInfo<T>(PhantomData<T>);
+impl<T: frame_system::Config> pallet_transaction_storage::WeightInfo for WeightInfo<T> {
+	// Storage: TransactionStorage ByteFee (r:1 w:0)
+	// Storage: TransactionStorage EntryFee (r:1 w:0)
+	// Storage: unknown [0x3a65787472696e7369635f696e646578] (r:1 w:0)
+	// Storage: TransactionStorage BlockTransactions (r:1 w:1)
+	// The base weight measured upstream is 0. Until this is benchmarked here, `store` is charged at
+	// least the base weight of `renew`, which does the same work besides finding the stored entry.
+	fn store(l: u32, ) -> Weight {
+		(50_978_000 as Weight)
+			// Standard Error: 0
+			.saturating_add((5_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: TransactionStorage Transactions (r:1 w:0)
+	// Storage: TransactionStorage ByteFee (r:1 w:0)
+	// Storage: TransactionStorage EntryFee (r:1 w:0)
+	// Storage: unknown [0x3a65787472696e7369635f696e646578] (r:1 w:0)
+	// Storage: TransactionStorage BlockTransactions (r:1 w:1)
+	fn renew() -> Weight {
+		(50_978_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: TransactionStorage ProofChecked (r:1 w:1)
+	// Storage: TransactionStorage StoragePeriod (r:1 w:0)
+	// Storage: TransactionStorage ChunkCount (r:1 w:0)
+	// Storage: System ParentHash (r:1 w:0)
+	// Storage: TransactionStorage Transactions (r:1 w:0)
+	fn check_proof_max() -> Weight {
+		(106_990_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+}
@@ -0,0 +1,64 @@
+//! Weights for `runtime_common::auctions`
+//!
+//! NOT YET GENERATED ON REFERENCE HARDWARE: the base weights are estimated from the storage
+//! accesses listed for each extrinsic, `on_initialize` assuming every one of the `EndingPeriod`
+//! / `SampleLength` samples holds a full set of `SLOT_RANGE_COUNT` winners.
+//! They have to be replaced by the output of the `benchmark` subcommand of the node before the
+//! next runtime release.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::Weight};
+use sp_std::marker::PhantomData;
+
+/// Weight functions for `runtime_common::auctions`.
+pub struct WeightInfo<T>(PhantomData<T>);
+impl<T: frame_system::Config> runtime_common::auctions::WeightInfo for WeightInfo<T> {
+	// Storage: Auctions AuctionInfo (r:1 w:1)
+	// Storage: Auctions AuctionCounter (r:1 w:1)
+	fn new_auction() -> Weight {
+		(17_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	// Storage: Paras ParaLifecycles (r:1 w:0)
+	// Storage: Auctions AuctionCounter (r:1 w:0)
+	// Storage: Auctions AuctionInfo (r:1 w:0)
+	// Storage: Slots Leases (r:1 w:0)
+	// Storage: Auctions Winning (r:1 w:1)
+	// Storage: Auctions ReservedAmounts (r:2 w:2)
+	// Storage: System Account (r:1 w:1)
+	fn bid() -> Weight {
+		(72_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(8 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	// Storage: Auctions AuctionInfo (r:1 w:1)
+	// Storage: Babe NextRandomness (r:1 w:0)
+	// Storage: Babe EpochStart (r:1 w:0)
+	// Storage: Auctions AuctionCounter (r:1 w:0)
+	// Storage: Auctions Winning (r:3601 w:3600)
+	// Storage: Auctions ReservedAmounts (r:37 w:36)
+	// Storage: System Account (r:36 w:36)
+	// Storage: Slots Leases (r:36 w:36)
+	// Storage: Paras ParaLifecycles (r:1 w:1)
+	// Storage: ParasShared CurrentSessionIndex (r:1 w:0)
+	// Storage: Paras ActionsQueue (r:1 w:1)
+	// Storage: Registrar Paras (r:1 w:1)
+	fn on_initialize() -> Weight {
+		(15_600_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3717 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3712 as Weight))
+	}
+	// Storage: Auctions ReservedAmounts (r:37 w:36)
+	// Storage: System Account (r:36 w:36)
+	// Storage: Auctions Winning (r:0 w:3600)
+	// Storage: Auctions AuctionInfo (r:0 w:1)
+	fn cancel_auction() -> Weight {
+		(3_500_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(73 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3673 as Weight))
+	}
+}
@@ -0,0 +1,103 @@
+//! Weights for `runtime_common::crowdloan`
+//!
+//! NOT YET GENERATED ON REFERENCE HARDWARE: the base weights are estimated from the storage
+//! accesses listed for each extrinsic, `contribute` assuming an ed25519 verifier signature.
+//! They have to be replaced by the output of the `benchmark` subcommand of the node before the
+//! next runtime release.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::Weight};
+use sp_std::marker::PhantomData;
+
+/// Weight functions for `runtime_common::crowdloan`.
+pub struct WeightInfo<T>(PhantomData<T>);
+impl<T: frame_system::Config> runtime_common::crowdloan::WeightInfo for WeightInfo<T> {
+	// Storage: Crowdloan Funds (r:1 w:1)
+	// Storage: Registrar Paras (r:1 w:1)
+	// Storage: Paras ParaLifecycles (r:1 w:0)
+	// Storage: Crowdloan NextTrieIndex (r:1 w:1)
+	fn create() -> Weight {
+		(48_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	// Storage: Crowdloan Funds (r:1 w:1)
+	// Storage: Slots Leases (r:1 w:0)
+	// Storage: Auctions AuctionInfo (r:1 w:0)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Crowdloan EndingsCount (r:1 w:0)
+	// Storage: Crowdloan NewRaise (r:1 w:1)
+	// Storage: Crowdloan child trie (contribution) (r:1 w:1)
+	fn contribute() -> Weight {
+		(116_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(7 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	// Storage: Crowdloan Funds (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	// Storage: Crowdloan child trie (contribution) (r:1 w:1)
+	fn withdraw() -> Weight {
+		(54_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	// Storage: Crowdloan Funds (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Crowdloan child trie (contribution) (r:1 w:1)
+	fn refund(k: u32, ) -> Weight {
+		(0 as Weight)
+			.saturating_add((46_000_000 as Weight).saturating_mul(k as Weight))
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(k as Weight)))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(k as Weight)))
+	}
+	// Storage: Crowdloan Funds (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn dissolve() -> Weight {
+		(35_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	// Storage: Crowdloan Funds (r:1 w:1)
+	fn edit() -> Weight {
+		(24_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Crowdloan Funds (r:1 w:0)
+	// Storage: Crowdloan child trie (contribution) (r:1 w:1)
+	fn add_memo() -> Weight {
+		(37_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Crowdloan Funds (r:1 w:0)
+	// Storage: Crowdloan NewRaise (r:1 w:1)
+	fn poke() -> Weight {
+		(26_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: Auctions AuctionInfo (r:1 w:0)
+	// Storage: Crowdloan EndingsCount (r:1 w:1)
+	// Storage: Crowdloan NewRaise (r:1 w:1)
+	// Storage: Crowdloan Funds (r:1 w:0)
+	// Storage: Auctions AuctionCounter (r:1 w:0)
+	// Storage: Paras ParaLifecycles (r:1 w:0)
+	// Storage: Slots Leases (r:1 w:0)
+	// Storage: Auctions Winning (r:1 w:1)
+	// Storage: Auctions ReservedAmounts (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn on_initialize(n: u32, ) -> Weight {
+		(0 as Weight)
+			.saturating_add((108_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().reads((7 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes((4 as Weight).saturating_mul(n as Weight)))
+	}
+}
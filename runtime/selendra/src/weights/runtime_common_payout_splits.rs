@@ -0,0 +1,55 @@
+//! Weights for `runtime_common::payout_splits`
+//!
+//! NOT YET GENERATED ON REFERENCE HARDWARE: the base weights are derived from the weights measured
+//! for this runtime of extrinsics with the same storage accesses (`pallet_staking::set_payee` for
+//! the payee changes, `pallet_balances::transfer` for the transfers to the payees).
+//! They have to be replaced by the output of the `benchmark` subcommand of the node before the
+//! next runtime release.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::Weight};
+use sp_std::marker::PhantomData;
+
+/// Weight functions for `runtime_common::payout_splits`.
+pub struct WeightInfo<T>(PhantomData<T>);
+impl<T: frame_system::Config> runtime_common::payout_splits::WeightInfo for WeightInfo<T> {
+	// Storage: Staking Ledger (r:1 w:0)
+	// Storage: PayoutSplits PayoutSplits (r:1 w:1)
+	// Storage: Staking Payee (r:1 w:1)
+	// Storage: PayoutSplits PreviousPayees (r:0 w:1)
+	fn set_payout_split(n: u32, ) -> Weight {
+		(38_000_000 as Weight)
+			// Standard Error: 0
+			.saturating_add((412_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	// Storage: Staking Ledger (r:1 w:0)
+	// Storage: PayoutSplits PayoutSplits (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	// Storage: PayoutSplits PreviousPayees (r:1 w:1)
+	// Storage: Staking Payee (r:0 w:1)
+	fn remove_payout_split(n: u32, ) -> Weight {
+		(66_000_000 as Weight)
+			// Standard Error: 0
+			.saturating_add((29_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(T::DbWeight::get().writes(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(n as Weight)))
+	}
+	// Storage: PayoutSplits PayoutSplits (r:1 w:0)
+	// Storage: System Account (r:2 w:2)
+	fn distribute(n: u32, ) -> Weight {
+		(29_000_000 as Weight)
+			// Standard Error: 0
+			.saturating_add((29_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(n as Weight)))
+	}
+}
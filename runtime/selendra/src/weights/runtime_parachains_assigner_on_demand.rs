@@ -0,0 +1,42 @@
+//! Weights for `runtime_parachains::assigner_on_demand`
+//!
+//! NOT YET GENERATED ON REFERENCE HARDWARE: the base weights are derived from the weights measured
+//! for this runtime of extrinsics with the same storage accesses (`pallet_balances::transfer` and
+//! `pallet_balances::transfer_keep_alive` for the payment), plus the spot price and the insertion
+//! into a full claim queue.
+//! They have to be replaced by the output of the `benchmark` subcommand of the node before the
+//! next runtime release.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::Weight};
+use sp_std::marker::PhantomData;
+
+/// Weight functions for `runtime_parachains::assigner_on_demand`.
+pub struct WeightInfo<T>(PhantomData<T>);
+impl<T: frame_system::Config> runtime_parachains::assigner_on_demand::WeightInfo for WeightInfo<T> {
+	// Storage: OnDemandAssignment SpotTraffic (r:1 w:0)
+	// Storage: Paras ParaLifecycles (r:1 w:0)
+	// Storage: Configuration ActiveConfig (r:1 w:0)
+	// Storage: ParaScheduler ParathreadQueue (r:1 w:1)
+	// Storage: ParaScheduler ParathreadClaimIndex (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn place_order_allow_death() -> Weight {
+		(77_512_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	// Storage: OnDemandAssignment SpotTraffic (r:1 w:0)
+	// Storage: Paras ParaLifecycles (r:1 w:0)
+	// Storage: Configuration ActiveConfig (r:1 w:0)
+	// Storage: ParaScheduler ParathreadQueue (r:1 w:1)
+	// Storage: ParaScheduler ParathreadClaimIndex (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn place_order_keep_alive() -> Weight {
+		(61_043_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+}
@@ -25,7 +25,7 @@ use frame_support::{
 	traits::{Everything, Nothing},
 	weights::Weight,
 };
-use runtime_common::{xcm_sender, ToAuthor};
+use runtime_common::{asset_rate::AssetRateTrader, xcm_sender, ToAuthor};
 use xcm::latest::prelude::*;
 use xcm_builder::{
 	AccountId32Aliases, AllowKnownQueryResponses, AllowSubscriptionsFrom,
@@ -136,8 +136,12 @@ impl xcm_executor::Config for XcmConfig {
 	type LocationInverter = LocationInverter<Ancestry>;
 	type Barrier = Barrier;
 	type Weigher = FixedWeightBounds<BaseXcmWeight, Call, MaxInstructions>;
-	// The weight trader piggybacks on the existing transaction-fee conversion logic.
-	type Trader = UsingComponents<WeightToFee, SelLocation, AccountId, Balances, ToAuthor<Runtime>>;
+	// The weight trader piggybacks on the existing transaction-fee conversion logic. Foreign assets
+	// with a conversion rate set by governance can pay as well.
+	type Trader = (
+		UsingComponents<WeightToFee, SelLocation, AccountId, Balances, ToAuthor<Runtime>>,
+		AssetRateTrader<Runtime, WeightToFee>,
+	);
 	type ResponseHandler = XcmPallet;
 	type AssetTrap = XcmPallet;
 	type AssetClaims = XcmPallet;
@@ -25,9 +25,10 @@ use parity_scale_codec::Encode;
 use sp_std::{collections::btree_map::BTreeMap, prelude::*};
 
 use selendra_runtime_parachains::{
-	configuration as parachains_configuration, disputes as parachains_disputes,
-	dmp as parachains_dmp, hrmp as parachains_hrmp, inclusion as parachains_inclusion,
-	initializer as parachains_initializer, origin as parachains_origin, paras as parachains_paras,
+	assigner_on_demand as parachains_assigner_on_demand, configuration as parachains_configuration,
+	disputes as parachains_disputes, dmp as parachains_dmp, hrmp as parachains_hrmp,
+	inclusion as parachains_inclusion, initializer as parachains_initializer,
+	origin as parachains_origin, paras as parachains_paras,
 	paras_inherent as parachains_paras_inherent, runtime_api_impl::v1 as runtime_impl,
 	scheduler as parachains_scheduler, session_info as parachains_session_info,
 	shared as parachains_shared, ump as parachains_ump,
@@ -462,6 +463,7 @@ impl parachains_inclusion::Config for Runtime {
 	type Event = Event;
 	type DisputesHandler = ParasDisputes;
 	type RewardValidators = RewardValidatorsWithEraPoints<Runtime>;
+	type RewardCollators = ();
 }
 
 impl parachains_disputes::Config for Runtime {
@@ -494,7 +496,21 @@ impl parachains_paras::Config for Runtime {
 	type NextSessionRotation = Babe;
 }
 
-impl parachains_dmp::Config for Runtime {}
+parameter_types! {
+	pub const DmpQueueHighWatermark: parachains_dmp::QueueWatermark =
+		parachains_dmp::DEFAULT_QUEUE_HIGH_WATERMARK;
+	pub const DmpQueueLowWatermark: parachains_dmp::QueueWatermark =
+		parachains_dmp::DEFAULT_QUEUE_LOW_WATERMARK;
+	pub const DmpQueueCapacity: parachains_dmp::QueueWatermark =
+		parachains_dmp::DEFAULT_QUEUE_CAPACITY;
+}
+
+impl parachains_dmp::Config for Runtime {
+	type Event = Event;
+	type QueueHighWatermark = DmpQueueHighWatermark;
+	type QueueLowWatermark = DmpQueueLowWatermark;
+	type QueueCapacity = DmpQueueCapacity;
+}
 
 parameter_types! {
 	pub const FirstMessageFactorPercent: u64 = 100;
@@ -535,15 +551,36 @@ impl pallet_xcm::Config for Runtime {
 	type AdvertisedXcmVersion = pallet_xcm::CurrentXcmVersion;
 }
 
+parameter_types! {
+	pub const HrmpChannelCloseGracePeriod: BlockNumber = 10;
+}
+
 impl parachains_hrmp::Config for Runtime {
 	type Event = Event;
 	type Origin = Origin;
 	type Currency = Balances;
+	type ChannelCloseGracePeriod = HrmpChannelCloseGracePeriod;
+	type SystemParas = frame_support::traits::Nothing;
 	type WeightInfo = parachains_hrmp::TestWeightInfo;
 }
 
 impl parachains_scheduler::Config for Runtime {}
 
+parameter_types! {
+	pub const OnDemandBaseFee: Balance = 10 * CENTS;
+	pub const OnDemandTargetQueueUtilization: Perbill = Perbill::from_percent(25);
+	pub const OnDemandFeeVariability: Perbill = Perbill::from_percent(3);
+}
+
+impl parachains_assigner_on_demand::Config for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type BaseFee = OnDemandBaseFee;
+	type TargetQueueUtilization = OnDemandTargetQueueUtilization;
+	type FeeVariability = OnDemandFeeVariability;
+	type WeightInfo = parachains_assigner_on_demand::TestWeightInfo;
+}
+
 impl paras_sudo_wrapper::Config for Runtime {}
 
 impl parachains_origin::Config for Runtime {}
@@ -671,12 +708,13 @@ construct_runtime! {
 		Paras: parachains_paras::{Pallet, Call, Storage, Event},
 		ParasShared: parachains_shared::{Pallet, Call, Storage},
 		Scheduler: parachains_scheduler::{Pallet, Storage},
+		OnDemandAssignment: parachains_assigner_on_demand::{Pallet, Call, Storage, Event<T>},
 		ParasSudoWrapper: paras_sudo_wrapper::{Pallet, Call},
 		ParasOrigin: parachains_origin::{Pallet, Origin},
 		ParaSessionInfo: parachains_session_info::{Pallet, Storage},
 		Hrmp: parachains_hrmp::{Pallet, Call, Storage, Event<T>},
 		Ump: parachains_ump::{Pallet, Call, Storage, Event},
-		Dmp: parachains_dmp::{Pallet, Call, Storage},
+		Dmp: parachains_dmp::{Pallet, Call, Storage, Event},
 		Xcm: pallet_xcm::{Pallet, Call, Event<T>, Origin},
 		ParasDisputes: parachains_disputes::{Pallet, Storage, Event<T>},
 
@@ -882,6 +920,31 @@ sp_api::impl_runtime_apis! {
 		{
 			runtime_impl::validation_code_hash::<Runtime>(para_id, assumption)
 		}
+
+		fn dmq_length(recipient: ParaId) -> u32 {
+			runtime_impl::dmq_length::<Runtime>(recipient)
+		}
+
+		fn hrmp_channel_digests(recipient: ParaId) -> Vec<(BlockNumber, Vec<ParaId>)> {
+			runtime_impl::hrmp_channel_digests::<Runtime>(recipient)
+		}
+
+		fn staging_para_backing_state(
+			para_id: ParaId,
+		) -> Option<primitives::v2::BackingState<Hash, BlockNumber>> {
+			runtime_impl::staging_para_backing_state::<Runtime>(para_id)
+		}
+	}
+
+	impl parachains_configuration::HostConfigurationApi<Block, BlockNumber> for Runtime {
+		fn active_config() -> parachains_configuration::HostConfiguration<BlockNumber> {
+			Configuration::config()
+		}
+
+		fn pending_configs(
+		) -> Vec<(SessionIndex, parachains_configuration::HostConfiguration<BlockNumber>)> {
+			Configuration::pending_configs()
+		}
 	}
 
 	impl beefy_primitives::BeefyApi<Block> for Runtime {